@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use rust_market_data_processor::OrderBook;
+use rust_market_data_processor::pool::Pool;
+use rust_market_data_processor::{OrderBook, PriceLevel, Timestamp};
 
 fn orderbook_updates(c: &mut Criterion) {
     c.bench_function("orderbook_update_bid", |b| {
@@ -7,7 +8,8 @@ fn orderbook_updates(c: &mut Criterion) {
         let mut price = 50000.0;
         
         b.iter(|| {
-            ob.update_bid(black_box(price), black_box(1.0));
+            ob.update_bid(black_box(price), black_box(1.0), Timestamp::EPOCH)
+                .unwrap();
             price += 0.01;
         });
     });
@@ -17,7 +19,8 @@ fn orderbook_updates(c: &mut Criterion) {
         let mut price = 50000.0;
         
         b.iter(|| {
-            ob.update_ask(black_box(price), black_box(1.0));
+            ob.update_ask(black_box(price), black_box(1.0), Timestamp::EPOCH)
+                .unwrap();
             price += 0.01;
         });
     });
@@ -27,8 +30,10 @@ fn orderbook_updates(c: &mut Criterion) {
         
         // Pre-populate orderbook
         for i in 0..100 {
-            ob.update_bid(50000.0 - i as f64, 1.0);
-            ob.update_ask(50001.0 + i as f64, 1.0);
+            ob.update_bid(50000.0 - i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_ask(50001.0 + i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
         }
         
         b.iter(|| {
@@ -42,8 +47,10 @@ fn orderbook_updates(c: &mut Criterion) {
         
         // Pre-populate orderbook
         for i in 0..100 {
-            ob.update_bid(50000.0 - i as f64, 1.0);
-            ob.update_ask(50001.0 + i as f64, 1.0);
+            ob.update_bid(50000.0 - i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_ask(50001.0 + i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
         }
         
         b.iter(|| {
@@ -58,8 +65,10 @@ fn orderbook_updates(c: &mut Criterion) {
         
         // Pre-populate orderbook
         for i in 0..100 {
-            ob.update_bid(50000.0 - i as f64, (i + 1) as f64);
-            ob.update_ask(50001.0 + i as f64, (i + 1) as f64);
+            ob.update_bid(50000.0 - i as f64, (i + 1) as f64, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_ask(50001.0 + i as f64, (i + 1) as f64, Timestamp::EPOCH)
+                .unwrap();
         }
         
         b.iter(|| {
@@ -70,5 +79,36 @@ fn orderbook_updates(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, orderbook_updates);
+fn top_levels(c: &mut Criterion) {
+    let mut ob = OrderBook::new("BTCUSD".to_string());
+    for i in 0..100 {
+        ob.update_bid(50000.0 - i as f64, 1.0, Timestamp::EPOCH)
+            .unwrap();
+        ob.update_ask(50001.0 + i as f64, 1.0, Timestamp::EPOCH)
+            .unwrap();
+    }
+
+    c.bench_function("orderbook_top_levels_alloc", |b| {
+        b.iter(|| {
+            black_box(ob.top_bids(black_box(20)));
+            black_box(ob.top_asks(black_box(20)));
+        });
+    });
+
+    c.bench_function("orderbook_top_levels_pooled", |b| {
+        let mut pool: Pool<Vec<PriceLevel>> = Pool::prefilled(4, || Vec::with_capacity(20));
+
+        b.iter(|| {
+            let mut bids = pool.acquire();
+            let mut asks = pool.acquire();
+            ob.top_bids_into(black_box(20), &mut bids);
+            ob.top_asks_into(black_box(20), &mut asks);
+            black_box((&bids, &asks));
+            pool.release(bids);
+            pool.release(asks);
+        });
+    });
+}
+
+criterion_group!(benches, orderbook_updates, top_levels);
 criterion_main!(benches);
@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_market_data_processor::aggregation::FootprintBuilder;
+use rust_market_data_processor::testing::SeededRng;
+use rust_market_data_processor::{
+    BollingerBands, OrderBook, Timestamp, Trade, EMA, MACD, RSI, SMA,
+};
+
+const EVENTS: usize = 10_000;
+
+/// Synthetic market event: mostly book updates with interleaved trades
+#[derive(Clone, Copy)]
+enum Event {
+    Bid(f64, f64, Timestamp),
+    Ask(f64, f64, Timestamp),
+    Trade(Trade),
+}
+
+/// Deterministic stream around a slowly drifting mid, roughly 9 book updates per trade
+fn event_stream(count: usize) -> Vec<Event> {
+    let mut rng = SeededRng::new(42);
+    let mut mid = 50_000.0;
+    let mut timestamp = Timestamp::EPOCH;
+    (0..count)
+        .map(|_| {
+            mid += rng.range(-1.0, 1.0);
+            timestamp = timestamp + Duration::from_millis(rng.range(0.0, 5.0) as u64);
+            let offset = rng.range(0.0, 20.0).floor() * 0.5 + 0.5;
+            let quantity = if rng.next_f64() < 0.2 {
+                0.0
+            } else {
+                rng.range(0.0, 2.0)
+            };
+
+            match rng.next_u64() % 10 {
+                0 => Event::Trade(Trade::new(
+                    mid,
+                    rng.range(0.01, 1.01),
+                    rng.side(),
+                    timestamp,
+                )),
+                n if n % 2 == 0 => Event::Bid(mid - offset, quantity, timestamp),
+                _ => Event::Ask(mid + offset, quantity, timestamp),
+            }
+        })
+        .collect()
+}
+
+/// All stages of the processing pipeline
+struct Pipeline {
+    book: OrderBook,
+    bars: FootprintBuilder,
+    sma: SMA,
+    ema: EMA,
+    rsi: RSI,
+    bollinger: BollingerBands,
+    macd: MACD,
+    buffer: Vec<u8>,
+}
+
+impl Pipeline {
+    fn new() -> Self {
+        Self {
+            book: OrderBook::new("BTCUSD".to_string()),
+            bars: FootprintBuilder::new(0.5, 1_000),
+            sma: SMA::new(20),
+            ema: EMA::new(20),
+            rsi: RSI::new(14),
+            bollinger: BollingerBands::new(20, 2.0),
+            macd: MACD::new(12, 26, 9),
+            buffer: Vec::with_capacity(256),
+        }
+    }
+
+    fn book(&mut self, event: &Event) {
+        let _ = match *event {
+            Event::Bid(price, quantity, timestamp) => {
+                self.book.update_bid(price, quantity, timestamp)
+            }
+            Event::Ask(price, quantity, timestamp) => {
+                self.book.update_ask(price, quantity, timestamp)
+            }
+            Event::Trade(_) => Ok(()),
+        };
+        black_box(self.book.quote());
+    }
+
+    fn bars(&mut self, event: &Event) -> Option<f64> {
+        match event {
+            Event::Trade(trade) => self.bars.add_trade(trade).map(|bar| bar.close),
+            _ => None,
+        }
+    }
+
+    fn indicators(&mut self, close: f64) {
+        black_box(self.sma.update(close));
+        black_box(self.ema.update(close));
+        black_box(self.rsi.update(close));
+        black_box(self.bollinger.update(close));
+        black_box(self.macd.update(close));
+    }
+
+    fn serialize(&mut self, event: &Event) {
+        self.buffer.clear();
+        if let Event::Trade(trade) = event {
+            serde_json::to_writer(&mut self.buffer, trade).unwrap();
+        } else if let Some(quote) = self.book.quote() {
+            serde_json::to_writer(&mut self.buffer, &quote).unwrap();
+        }
+        black_box(&self.buffer);
+    }
+
+    fn process(&mut self, event: &Event) {
+        self.book(event);
+        if let Some(close) = self.bars(event) {
+            self.indicators(close);
+        }
+        self.serialize(event);
+    }
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+/// Per-stage latency distribution, printed once outside the criterion loops
+fn report_stage_latencies(events: &[Event]) {
+    let mut pipeline = Pipeline::new();
+    let mut samples: [Vec<Duration>; 4] = Default::default();
+
+    for event in events {
+        let start = Instant::now();
+        pipeline.book(event);
+        samples[0].push(start.elapsed());
+
+        let start = Instant::now();
+        let close = pipeline.bars(event);
+        samples[1].push(start.elapsed());
+
+        if let Some(close) = close {
+            let start = Instant::now();
+            pipeline.indicators(close);
+            samples[2].push(start.elapsed());
+        }
+
+        let start = Instant::now();
+        pipeline.serialize(event);
+        samples[3].push(start.elapsed());
+    }
+
+    println!("stage latency percentiles over {} events:", events.len());
+    for (name, stage) in ["book", "bars", "indicators", "serialize"]
+        .iter()
+        .zip(samples.iter_mut())
+    {
+        if stage.is_empty() {
+            continue;
+        }
+        stage.sort();
+        println!(
+            "  {:<10} p50 {:>8?}  p99 {:>8?}  p99.9 {:>8?}",
+            name,
+            percentile(stage, 0.5),
+            percentile(stage, 0.99),
+            percentile(stage, 0.999)
+        );
+    }
+}
+
+fn pipeline_benchmark(c: &mut Criterion) {
+    let events = event_stream(EVENTS);
+    report_stage_latencies(&events);
+
+    let mut group = c.benchmark_group("pipeline");
+    group.throughput(Throughput::Elements(events.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("end_to_end", EVENTS),
+        &events,
+        |b, events| {
+            b.iter(|| {
+                let mut pipeline = Pipeline::new();
+                for event in events {
+                    pipeline.process(event);
+                }
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("book_only", EVENTS),
+        &events,
+        |b, events| {
+            b.iter(|| {
+                let mut pipeline = Pipeline::new();
+                for event in events {
+                    pipeline.book(event);
+                }
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("serialize_only", EVENTS),
+        &events,
+        |b, events| {
+            let mut pipeline = Pipeline::new();
+            for event in events {
+                pipeline.book(event);
+            }
+            b.iter(|| {
+                for event in events {
+                    pipeline.serialize(event);
+                }
+            });
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, pipeline_benchmark);
+criterion_main!(benches);
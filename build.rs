@@ -0,0 +1,28 @@
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        // Vendored protoc, so building the `grpc` feature needs no system install
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        println!("cargo:rerun-if-changed=proto/market_data.proto");
+        tonic_build::compile_protos("proto/market_data.proto").expect("compile market_data.proto");
+    }
+
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+        let out_dir = std::env::var("OUT_DIR").expect("set by cargo");
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+            .expect("read cbindgen.toml");
+        // Only OUT_DIR is writable here; the committed copy in include/ is checked
+        // against this one by the ffi tests
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{crate_dir}/src/ffi.rs"))
+            .generate()
+            .expect("generate C header")
+            .write_to_file(format!("{out_dir}/market_data.h"));
+    }
+}
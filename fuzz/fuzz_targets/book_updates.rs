@@ -0,0 +1,129 @@
+#![no_main]
+
+//! Applies arbitrary level updates, snapshots and deltas to a book and checks its invariants
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::{
+    BookDelta, BookSide, LevelUpdate, MarketDataError, OrderBook, PriceLevel, Timestamp,
+};
+
+/// Reads fixed-size fields off the front of the input until it runs out
+struct Cursor<'a>(&'a [u8]);
+
+impl Cursor<'_> {
+    fn u8(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.0.split_first()?;
+        self.0 = rest;
+        Some(byte)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let bytes = self.0.get(..8)?.try_into().unwrap();
+        self.0 = &self.0[8..];
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        self.u64().map(f64::from_bits)
+    }
+
+    /// Side flag, price bits, quantity bits
+    fn level(&mut self) -> Option<LevelUpdate> {
+        let bid = self.u8()? & 1 == 0;
+        let (price, quantity) = (self.f64()?, self.f64()?);
+        Some(if bid { LevelUpdate::bid(price, quantity) } else { LevelUpdate::ask(price, quantity) })
+    }
+
+    /// Count byte (at most 15) followed by that many levels
+    fn levels(&mut self) -> Option<Vec<LevelUpdate>> {
+        let count = self.u8()? & 0x0f;
+        (0..count).map(|_| self.level()).collect()
+    }
+}
+
+fn valid(update: &LevelUpdate) -> bool {
+    update.price.is_finite() && update.price > 0.0 && update.quantity.is_finite() && update.quantity >= 0.0
+}
+
+fn level(update: &LevelUpdate) -> PriceLevel {
+    PriceLevel { price: update.price, quantity: update.quantity }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut book = OrderBook::new("FUZZ".to_string());
+    let mut input = Cursor(data);
+
+    // Each op: opcode byte, then a single level, a snapshot or a delta
+    while let Some(op) = input.u8() {
+        let before = (book.bids.clone(), book.asks.clone(), book.sequence);
+
+        let result = match op % 3 {
+            0 => {
+                let Some(update) = input.level() else { break };
+                let result = if update.side == BookSide::Bid {
+                    book.update_bid(update.price, update.quantity, Timestamp::EPOCH)
+                } else {
+                    book.update_ask(update.price, update.quantity, Timestamp::EPOCH)
+                };
+                assert_eq!(result.is_ok(), valid(&update));
+                result.map(|_| ())
+            }
+            1 => {
+                let (Some(sequence), Some(levels)) = (input.u64(), input.levels()) else { break };
+                let (bids, asks): (Vec<_>, Vec<_>) = levels.iter().partition(|u| u.side == BookSide::Bid);
+                let bids: Vec<_> = bids.into_iter().map(level).collect();
+                let asks: Vec<_> = asks.into_iter().map(level).collect();
+
+                let result = book.apply_snapshot(&bids, &asks, sequence, Timestamp::EPOCH);
+                assert_eq!(result.is_ok(), levels.iter().all(valid));
+                if result.is_ok() {
+                    assert_eq!(book.sequence, Some(sequence));
+                }
+                result
+            }
+            _ => {
+                // Offset from the current sequence: 0 is stale, 1 the next delta, more a gap
+                let (Some(offset), Some(updates)) = (input.u8(), input.levels()) else { break };
+                let sequence = book.sequence.unwrap_or(0).wrapping_add(u64::from(offset % 4));
+                let all_valid = updates.iter().all(valid);
+                let delta = BookDelta { sequence, updates, timestamp: Timestamp::EPOCH };
+
+                let result = book.apply_delta(&delta);
+                match before.2 {
+                    None => assert!(result.is_err()),
+                    Some(current) if sequence <= current => {
+                        assert_eq!(result.as_ref().ok(), Some(&Default::default()));
+                        assert_eq!((&book.bids, &book.asks, book.sequence), (&before.0, &before.1, before.2));
+                    }
+                    Some(current) if sequence != current + 1 => {
+                        assert!(matches!(result, Err(MarketDataError::SequenceGap { .. })));
+                    }
+                    Some(_) => {
+                        assert_eq!(result.is_ok(), all_valid);
+                        if result.is_ok() {
+                            assert_eq!(book.sequence, Some(sequence));
+                        }
+                    }
+                }
+                result.map(|_| ())
+            }
+        };
+
+        // A rejected op leaves the book exactly as it was
+        if result.is_err() {
+            assert_eq!((&book.bids, &book.asks, book.sequence), (&before.0, &before.1, before.2));
+        }
+    }
+
+    for side in [&book.bids, &book.asks] {
+        assert!(side.keys().all(|k| k.0.is_finite() && k.0 > 0.0));
+        assert!(side.values().all(|q| q.is_finite() && *q > 0.0));
+        assert!(side.keys().zip(side.keys().skip(1)).all(|(a, b)| a.0 < b.0));
+    }
+
+    let _ = book.quote();
+    let _ = book.spread_percentage();
+    let _ = book.volume_imbalance();
+    let _ = book.top_bids(10);
+    let _ = book.top_asks(10);
+});
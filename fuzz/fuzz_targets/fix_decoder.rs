@@ -0,0 +1,21 @@
+#![no_main]
+
+//! Decodes arbitrary bytes as a stream of FIX market data messages
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::protocols::FixDecoder;
+use rust_market_data_processor::{OrderBookManager, Timestamp};
+
+fuzz_target!(|data: &[u8]| {
+    let mut books = OrderBookManager::new();
+    for decoder in [FixDecoder::new("fuzz"), FixDecoder::new("fuzz").with_delimiter(b'|')] {
+        let mut buf = data;
+        while let Ok(Some((events, len))) = decoder.decode(buf, Timestamp::EPOCH) {
+            assert!(len > 0 && len <= buf.len());
+            for event in &events {
+                let _ = books.apply_event(event);
+            }
+            buf = &buf[len..];
+        }
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+//! Decodes arbitrary bytes as length-prefixed ITCH 5.0 messages and replays them
+//! into the L3 books
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::protocols::{ItchBooks, ItchMessage, ItchReader};
+use rust_market_data_processor::Timestamp;
+
+fuzz_target!(|data: &[u8]| {
+    let mut books = ItchBooks::new(Timestamp::EPOCH);
+    for message in ItchReader::new(data) {
+        match message {
+            Ok(message) => {
+                let _ = books.apply(&message);
+            }
+            Err(_) => break,
+        }
+    }
+
+    // Unframed messages must fail cleanly too
+    let _ = ItchMessage::decode(data);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+//! Feeds arbitrary bytes to the JSON entry points; they must fail cleanly, never panic
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::{OrderBook, Quote, Trade};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(book) = OrderBook::from_json(data) {
+        // Validated books must round-trip and keep positive levels
+        assert!(book.bids.keys().chain(book.asks.keys()).all(|k| k.0 > 0.0));
+        let encoded = serde_json::to_vec(&book).unwrap();
+        assert!(OrderBook::from_json(&encoded).is_ok());
+        let _ = book.mid_price();
+    }
+
+    let _ = serde_json::from_slice::<Trade>(data);
+    let _ = serde_json::from_slice::<Quote>(data);
+});
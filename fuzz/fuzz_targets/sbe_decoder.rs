@@ -0,0 +1,33 @@
+#![no_main]
+
+//! Decodes arbitrary bytes as CME MDP 3.0 packets and applies their updates to books
+
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::protocols::Mdp3Decoder;
+use rust_market_data_processor::OrderBook;
+
+fn decoder() -> &'static Mdp3Decoder {
+    static DECODER: OnceLock<Mdp3Decoder> = OnceLock::new();
+    DECODER.get_or_init(|| {
+        Mdp3Decoder::from_xml(include_str!("../../src/protocols/testdata/mdp3_subset.xml"))
+            .expect("valid schema")
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let decoder = decoder();
+    let _ = decoder.schema().decode(data);
+
+    let Ok(packet) = decoder.decode_packet(data) else {
+        return;
+    };
+    let Ok(updates) = decoder.updates(&packet) else {
+        return;
+    };
+    let mut book = OrderBook::new("FUZZ".to_string());
+    for update in &updates {
+        let _ = update.apply_to(&mut book);
+    }
+});
@@ -0,0 +1,38 @@
+#![no_main]
+
+//! Streams arbitrary trades and quotes through the tick filter
+
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::{Quote, Side, TickFilter, Timestamp, Trade};
+
+fn f64_at(bytes: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut filter = TickFilter::default();
+
+    for chunk in data.chunks_exact(42) {
+        let timestamp = Timestamp::from_nanos(i64::from_le_bytes(chunk[1..9].try_into().unwrap()));
+
+        if chunk[0] & 1 == 0 {
+            let side = if chunk[0] & 2 == 0 { Side::Buy } else { Side::Sell };
+            let trade = Trade::new(f64_at(chunk, 9), f64_at(chunk, 17), side, timestamp);
+            let _ = filter.check_trade(&trade);
+        } else {
+            let quote = Quote {
+                bid_price: f64_at(chunk, 9),
+                bid_size: f64_at(chunk, 17),
+                ask_price: f64_at(chunk, 25),
+                ask_size: f64::from(u16::from_le_bytes([chunk[33], chunk[34]])),
+                timestamp,
+            };
+            let _ = filter.check_quote(&quote);
+        }
+    }
+
+    let stats = filter.stats();
+    let rejected: u64 = stats.rejected.values().sum();
+    assert!(stats.accepted_trades + stats.accepted_quotes + rejected <= data.len() as u64);
+    let _ = filter.drain_quarantine();
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+//! Decodes arbitrary bytes as binary tick records
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+use rust_market_data_processor::protocols::{RawTick, TickReader};
+
+fuzz_target!(|data: &[u8]| {
+    for tick in TickReader::new(data) {
+        match tick {
+            Ok(view) => {
+                let _ = view.to_tick();
+            }
+            Err(_) => break,
+        }
+    }
+
+    let mut buf = Bytes::copy_from_slice(data);
+    while let Ok(Some(raw)) = RawTick::split_from(&mut buf) {
+        let _ = raw.view().timestamp();
+    }
+});
@@ -0,0 +1,265 @@
+#ifndef MARKET_DATA_H
+#define MARKET_DATA_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Order book handle from md_orderbook_new
+ */
+typedef struct MdOrderBook MdOrderBook;
+
+/**
+ * Result of a fallible call
+ */
+typedef enum MdStatus {
+  MdStatus_Ok = 0,
+  MdStatus_NullPointer = 1,
+  MdStatus_InvalidPrice = 2,
+  MdStatus_InvalidQuantity = 3,
+  MdStatus_InvalidParameter = 4,
+  MdStatus_SequenceGap = 5,
+  MdStatus_CrossedBook = 6,
+  MdStatus_LockedBook = 7,
+  MdStatus_Other = 99,
+} MdStatus;
+
+/**
+ * Values of [`MdLevelUpdate::side`]
+ */
+typedef enum MdSide {
+  MdSide_Bid = 0,
+  MdSide_Ask = 1,
+} MdSide;
+
+/**
+ * Streaming indicator over a series of doubles
+ */
+typedef struct MdIndicator MdIndicator;
+
+/**
+ * Absolute level change; zero quantity removes the level
+ */
+typedef struct MdLevelUpdate {
+  /**
+   * An [`MdSide`] value; kept as a plain int so any value C writes is defined
+   */
+  int side;
+  double price;
+  double quantity;
+} MdLevelUpdate;
+
+/**
+ * Price level as passed to and returned by the book functions
+ */
+typedef struct MdLevel {
+  double price;
+  double quantity;
+} MdLevel;
+
+/**
+ * Best bid and offer; sizes are zero and prices NaN for an empty side
+ */
+typedef struct MdBbo {
+  double bid_price;
+  double bid_size;
+  double ask_price;
+  double ask_size;
+} MdBbo;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Copy the last error message of this thread into `buffer` as a NUL-terminated
+ * string, truncated to `capacity - 1` bytes
+ *
+ * Returns the full message length in bytes, excluding the terminator.
+ *
+ * # Safety
+ *
+ * `buffer` must be null or valid for writes of `capacity` bytes.
+ */
+size_t md_last_error(char *buffer, size_t capacity);
+
+/**
+ * New empty book, or null if `symbol` is null, not UTF-8 or not a valid symbol
+ *
+ * # Safety
+ *
+ * `symbol` must be null or a valid NUL-terminated string.
+ */
+MdOrderBook *md_orderbook_new(const char *symbol);
+
+/**
+ * Release a book; null is ignored
+ *
+ * # Safety
+ *
+ * `book` must be null or a handle from [`md_orderbook_new`] not freed before.
+ */
+void md_orderbook_free(MdOrderBook *book);
+
+/**
+ * Set one bid level at `timestamp_ns` nanoseconds since the epoch; zero quantity
+ * removes it
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle from [`md_orderbook_new`].
+ */
+enum MdStatus md_orderbook_update_bid(MdOrderBook *book,
+                                      double price,
+                                      double quantity,
+                                      int64_t timestamp_ns);
+
+/**
+ * Set one ask level at `timestamp_ns` nanoseconds since the epoch; zero quantity
+ * removes it
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle from [`md_orderbook_new`].
+ */
+enum MdStatus md_orderbook_update_ask(MdOrderBook *book,
+                                      double price,
+                                      double quantity,
+                                      int64_t timestamp_ns);
+
+/**
+ * Apply `len` level changes atomically, stamped `timestamp_ns` nanoseconds since
+ * the epoch
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle; `updates` must point to `len` elements
+ * (or may be null when `len` is zero).
+ */
+enum MdStatus md_orderbook_apply_updates(MdOrderBook *book,
+                                         const struct MdLevelUpdate *updates,
+                                         size_t len,
+                                         int64_t timestamp_ns);
+
+/**
+ * Replace the whole book with a snapshot taken at `timestamp_ns` nanoseconds since
+ * the epoch; levels are best first
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle; `bids` and `asks` must point to
+ * `bid_count` and `ask_count` elements (or may be null when their count is zero).
+ */
+enum MdStatus md_orderbook_apply_snapshot(MdOrderBook *book,
+                                          const struct MdLevel *bids,
+                                          size_t bid_count,
+                                          const struct MdLevel *asks,
+                                          size_t ask_count,
+                                          uint64_t sequence,
+                                          int64_t timestamp_ns);
+
+/**
+ * Write the best bid and offer to `out`
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle; `out` must be null or valid for writes.
+ */
+enum MdStatus md_orderbook_bbo(const MdOrderBook *book, struct MdBbo *out);
+
+/**
+ * Mid price, NaN when a side is empty or `book` is null
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle from [`md_orderbook_new`].
+ */
+double md_orderbook_mid_price(const MdOrderBook *book);
+
+/**
+ * Copy up to `capacity` best bids, highest first, returning how many were written
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle; `out` must be valid for `capacity` writes.
+ */
+size_t md_orderbook_top_bids(const MdOrderBook *book, struct MdLevel *out, size_t capacity);
+
+/**
+ * Copy up to `capacity` best asks, lowest first, returning how many were written
+ *
+ * # Safety
+ *
+ * `book` must be null or a live handle; `out` must be valid for `capacity` writes.
+ */
+size_t md_orderbook_top_asks(const MdOrderBook *book, struct MdLevel *out, size_t capacity);
+
+/**
+ * Simple moving average, or null if `period` is zero
+ */
+struct MdIndicator *md_sma_new(size_t period);
+
+/**
+ * Exponential moving average, or null if `period` is zero
+ */
+struct MdIndicator *md_ema_new(size_t period);
+
+/**
+ * Weighted moving average, or null if `period` is zero
+ */
+struct MdIndicator *md_wma_new(size_t period);
+
+/**
+ * RSI with Wilder smoothing if `wilder`, else simple; null if `period` is zero
+ */
+struct MdIndicator *md_rsi_new(size_t period, bool wilder);
+
+/**
+ * Feed one value, returning the indicator value or NaN while warming up
+ *
+ * # Safety
+ *
+ * `indicator` must be null or a live handle from an `md_*_new` indicator constructor.
+ */
+double md_indicator_update(struct MdIndicator *indicator, double value);
+
+/**
+ * Feed `len` values, writing one output per input to `out` (NaN while warming up)
+ *
+ * # Safety
+ *
+ * `indicator` must be a live handle; `values` and `out` must point to `len` elements.
+ */
+enum MdStatus md_indicator_update_batch(struct MdIndicator *indicator,
+                                        const double *values,
+                                        size_t len,
+                                        double *out);
+
+/**
+ * Clear the indicator as if freshly created
+ *
+ * # Safety
+ *
+ * `indicator` must be null or a live handle.
+ */
+void md_indicator_reset(struct MdIndicator *indicator);
+
+/**
+ * Release an indicator; null is ignored
+ *
+ * # Safety
+ *
+ * `indicator` must be null or a handle not freed before.
+ */
+void md_indicator_free(struct MdIndicator *indicator);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MARKET_DATA_H */
@@ -0,0 +1,228 @@
+use crate::candles::Candle;
+use crate::error::{ensure, Result};
+use crate::time::Timestamp;
+use crate::trades::Trade;
+
+/// How a [`CandleBuilder`] treats intervals without any trade or tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GapFill {
+    /// Emit nothing for empty intervals
+    #[default]
+    Skip,
+    /// Emit flat zero-volume candles at the previous close, as charting feeds do
+    CarryForward,
+}
+
+/// Time-bucketed OHLCV bars built from trades or mid-price ticks
+///
+/// Bars are `interval` milliseconds long and aligned to multiples of the interval
+/// since the Unix epoch, so `60_000` yields wall-clock minutes. Input for a bar that
+/// has already been emitted is counted as late and dropped.
+#[derive(Debug, Clone)]
+pub struct CandleBuilder {
+    interval: i64,
+    gap_fill: GapFill,
+    current: Option<Candle>,
+    last_close: Option<(i64, f64)>,
+    late: u64,
+}
+
+impl CandleBuilder {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(interval: i64) -> Self {
+        Self::try_new(interval).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(interval: i64) -> Result<Self> {
+        ensure(interval > 0, "interval must be positive")?;
+        Ok(Self {
+            interval,
+            gap_fill: GapFill::Skip,
+            current: None,
+            last_close: None,
+            late: 0,
+        })
+    }
+
+    pub fn with_gap_fill(mut self, gap_fill: GapFill) -> Self {
+        self.gap_fill = gap_fill;
+        self
+    }
+
+    pub fn interval(&self) -> i64 {
+        self.interval
+    }
+
+    /// Trades or ticks dropped because their bar was already emitted
+    pub fn late(&self) -> u64 {
+        self.late
+    }
+
+    pub fn add_trade(&mut self, trade: &Trade) -> Vec<Candle> {
+        self.add(trade.price, trade.quantity, trade.timestamp)
+    }
+
+    /// Add a volume-less price observation, e.g. a mid price
+    pub fn add_tick(&mut self, price: f64, timestamp: Timestamp) -> Vec<Candle> {
+        self.add(price, 0.0, timestamp)
+    }
+
+    /// Add a price observation, returning every bar it completes
+    pub fn add(&mut self, price: f64, volume: f64, timestamp: Timestamp) -> Vec<Candle> {
+        let start = self.bar_start(timestamp);
+        let floor = match (self.current, self.last_close) {
+            (Some(bar), _) => Some(bar.timestamp.as_millis()),
+            (None, Some((emitted, _))) => Some(emitted + self.interval),
+            (None, None) => None,
+        };
+        if floor.is_some_and(|floor| start < floor) {
+            self.late += 1;
+            return Vec::new();
+        }
+
+        let completed = self.close_before(start);
+        match &mut self.current {
+            Some(bar) => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                bar.volume += volume;
+            }
+            None => {
+                let open = Timestamp::from_millis(start);
+                self.current = Some(Candle::new(price, price, price, price, volume, open));
+            }
+        }
+        completed
+    }
+
+    /// Close every bar that ended at or before `now`, for timer-driven emission when
+    /// no trade arrives to roll the bar over
+    pub fn advance_to(&mut self, now: Timestamp) -> Vec<Candle> {
+        self.close_before(self.bar_start(now))
+    }
+
+    /// Bar in progress, if any input arrived since the last completed bar
+    pub fn current(&self) -> Option<Candle> {
+        self.current
+    }
+
+    /// Close the bar in progress and return it, e.g. at the end of a replay
+    pub fn flush(&mut self) -> Option<Candle> {
+        let bar = self.current.take()?;
+        self.last_close = Some((bar.timestamp.as_millis(), bar.close));
+        Some(bar)
+    }
+
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.last_close = None;
+        self.late = 0;
+    }
+
+    fn bar_start(&self, timestamp: Timestamp) -> i64 {
+        timestamp.as_millis().div_euclid(self.interval) * self.interval
+    }
+
+    /// Emit the bar in progress and any gap bars before the bar starting at `start`
+    fn close_before(&mut self, start: i64) -> Vec<Candle> {
+        let mut completed = Vec::new();
+        if self
+            .current
+            .is_some_and(|bar| bar.timestamp.as_millis() < start)
+        {
+            completed.extend(self.flush());
+        }
+
+        if self.gap_fill == GapFill::CarryForward && self.current.is_none() {
+            if let Some((emitted, close)) = self.last_close {
+                let mut next = emitted + self.interval;
+                while next < start {
+                    let open = Timestamp::from_millis(next);
+                    completed.push(Candle::new(close, close, close, close, 0.0, open));
+                    self.last_close = Some((next, close));
+                    next += self.interval;
+                }
+            }
+        }
+        completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trades::Side;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    const MINUTE: i64 = 60_000;
+
+    fn trade(price: f64, quantity: f64, timestamp: i64) -> Trade {
+        Trade::new(price, quantity, Side::Buy, at(timestamp))
+    }
+
+    #[test]
+    fn test_trades_roll_into_aligned_bars() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        assert!(builder.add_trade(&trade(100.0, 1.0, 5_000)).is_empty());
+        assert!(builder.add_trade(&trade(103.0, 2.0, 20_000)).is_empty());
+        assert!(builder.add_trade(&trade(99.0, 1.0, 59_999)).is_empty());
+
+        let bars = builder.add_trade(&trade(101.0, 4.0, 61_000));
+        assert_eq!(
+            bars,
+            vec![Candle::new(100.0, 103.0, 99.0, 99.0, 4.0, at(0))]
+        );
+        assert_eq!(
+            builder.current(),
+            Some(Candle::new(101.0, 101.0, 101.0, 101.0, 4.0, at(MINUTE)))
+        );
+        assert_eq!(builder.flush().unwrap().timestamp, at(MINUTE));
+        assert_eq!(builder.flush(), None);
+    }
+
+    #[test]
+    fn test_gap_handling() {
+        let mut skip = CandleBuilder::new(MINUTE);
+        let mut fill = CandleBuilder::new(MINUTE).with_gap_fill(GapFill::CarryForward);
+        for builder in [&mut skip, &mut fill] {
+            builder.add_tick(100.0, at(0));
+            builder.add_tick(101.0, at(30_000));
+        }
+
+        assert_eq!(skip.add_tick(105.0, at(3 * MINUTE + 1)).len(), 1);
+
+        let bars = fill.add_tick(105.0, at(3 * MINUTE + 1));
+        let starts: Vec<i64> = bars.iter().map(|c| c.timestamp.as_millis()).collect();
+        assert_eq!(starts, vec![0, MINUTE, 2 * MINUTE]);
+        assert_eq!(
+            bars[1],
+            Candle::new(101.0, 101.0, 101.0, 101.0, 0.0, at(MINUTE))
+        );
+    }
+
+    #[test]
+    fn test_advance_closes_idle_bars_and_drops_late_input() {
+        let mut builder = CandleBuilder::new(1_000).with_gap_fill(GapFill::CarryForward);
+        builder.add(10.0, 1.0, at(500));
+
+        assert!(builder.advance_to(at(999)).is_empty());
+        let bars = builder.advance_to(at(3_200));
+        assert_eq!(bars.len(), 3);
+        assert_eq!(builder.current(), None);
+
+        // The bar at 2_000 was already emitted as a gap bar
+        assert!(builder.add(11.0, 1.0, at(2_500)).is_empty());
+        assert_eq!(builder.late(), 1);
+        builder.add(12.0, 1.0, at(3_500));
+        assert_eq!(builder.current().unwrap().open, 12.0);
+    }
+
+    #[test]
+    fn test_invalid_interval() {
+        assert!(CandleBuilder::try_new(0).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Volume traded at one price inside a footprint bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FootprintLevel {
+    pub price: f64,
+    /// Volume of sell aggressors hitting the bid
+    pub bid_volume: f64,
+    /// Volume of buy aggressors lifting the ask
+    pub ask_volume: f64,
+}
+
+impl FootprintLevel {
+    pub fn delta(&self) -> f64 {
+        self.ask_volume - self.bid_volume
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.ask_volume + self.bid_volume
+    }
+}
+
+/// Diagonal imbalance between adjacent price levels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Imbalance {
+    pub price: f64,
+    /// `Buy` when ask volume dominates the bid volume one tick below, `Sell` for the reverse
+    pub side: Side,
+    pub ratio: f64,
+}
+
+/// One bar of bid/ask volume per traded price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FootprintBar {
+    /// Start of the bar interval
+    pub timestamp: Timestamp,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Levels ordered by ascending price
+    pub levels: Vec<FootprintLevel>,
+    tick_size: f64,
+}
+
+impl FootprintBar {
+    pub fn volume(&self) -> f64 {
+        self.levels.iter().map(FootprintLevel::volume).sum()
+    }
+
+    /// Buy minus sell aggressor volume over the bar
+    pub fn delta(&self) -> f64 {
+        self.levels.iter().map(FootprintLevel::delta).sum()
+    }
+
+    pub fn level(&self, price: f64) -> Option<&FootprintLevel> {
+        let key = tick_index(price, self.tick_size);
+        self.levels
+            .iter()
+            .find(|level| tick_index(level.price, self.tick_size) == key)
+    }
+
+    /// Diagonal imbalances where one side traded at least `ratio` times the other
+    ///
+    /// Ask volume at a price is compared with bid volume one tick below (and bid volume
+    /// with ask volume one tick above), as buyers lifting an offer compete with sellers
+    /// hitting the bid beneath it.
+    pub fn imbalances(&self, ratio: f64) -> Vec<Imbalance> {
+        let mut result = Vec::new();
+
+        for level in &self.levels {
+            let key = tick_index(level.price, self.tick_size);
+
+            let below = self.volume_at(key - 1, |l| l.bid_volume);
+            if level.ask_volume > 0.0 && level.ask_volume >= ratio * below {
+                result.push(Imbalance {
+                    price: level.price,
+                    side: Side::Buy,
+                    ratio: if below > 0.0 {
+                        level.ask_volume / below
+                    } else {
+                        f64::INFINITY
+                    },
+                });
+            }
+
+            let above = self.volume_at(key + 1, |l| l.ask_volume);
+            if level.bid_volume > 0.0 && level.bid_volume >= ratio * above {
+                result.push(Imbalance {
+                    price: level.price,
+                    side: Side::Sell,
+                    ratio: if above > 0.0 {
+                        level.bid_volume / above
+                    } else {
+                        f64::INFINITY
+                    },
+                });
+            }
+        }
+
+        result
+    }
+
+    fn volume_at(&self, key: i64, pick: impl Fn(&FootprintLevel) -> f64) -> f64 {
+        self.levels
+            .iter()
+            .find(|level| tick_index(level.price, self.tick_size) == key)
+            .map_or(0.0, pick)
+    }
+
+    /// Both sides traded at the high: the auction did not find excess and the high is
+    /// likely to be revisited
+    pub fn unfinished_high(&self) -> bool {
+        self.levels
+            .last()
+            .is_some_and(|l| l.bid_volume > 0.0 && l.ask_volume > 0.0)
+    }
+
+    /// Both sides traded at the low
+    pub fn unfinished_low(&self) -> bool {
+        self.levels
+            .first()
+            .is_some_and(|l| l.bid_volume > 0.0 && l.ask_volume > 0.0)
+    }
+}
+
+fn tick_index(price: f64, tick_size: f64) -> i64 {
+    (price / tick_size).round() as i64
+}
+
+/// Time-bucketed footprint (bid/ask volume per price per bar) built from trades
+pub struct FootprintBuilder {
+    tick_size: f64,
+    interval: i64,
+    bar_start: Option<i64>,
+    ohlc: (f64, f64, f64, f64),
+    levels: BTreeMap<i64, (f64, f64)>,
+}
+
+impl FootprintBuilder {
+    /// Prices are rounded to `tick_size`; bars are `interval` milliseconds long and
+    /// aligned to multiples of the interval
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(tick_size: f64, interval: i64) -> Self {
+        Self::try_new(tick_size, interval).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(tick_size: f64, interval: i64) -> Result<Self> {
+        ensure(tick_size.is_finite() && tick_size > 0.0, "tick size must be positive")?;
+        ensure(interval > 0, "interval must be positive")?;
+
+        Ok(Self {
+            tick_size,
+            interval,
+            bar_start: None,
+            ohlc: (0.0, 0.0, 0.0, 0.0),
+            levels: BTreeMap::new(),
+        })
+    }
+
+    /// Add a trade, returning the previous bar if this trade starts a new one
+    pub fn add_trade(&mut self, trade: &Trade) -> Option<FootprintBar> {
+        let start = trade.timestamp.as_millis().div_euclid(self.interval) * self.interval;
+
+        let completed = match self.bar_start {
+            Some(current) if start > current => self.flush(),
+            Some(current) if start < current => return None,
+            _ => None,
+        };
+
+        if self.bar_start.is_none() {
+            self.bar_start = Some(start);
+            self.ohlc = (trade.price, trade.price, trade.price, trade.price);
+        }
+
+        let (_, high, low, close) = &mut self.ohlc;
+        *high = high.max(trade.price);
+        *low = low.min(trade.price);
+        *close = trade.price;
+
+        let entry = self
+            .levels
+            .entry(tick_index(trade.price, self.tick_size))
+            .or_insert((0.0, 0.0));
+        match trade.side {
+            Side::Buy => entry.1 += trade.quantity,
+            Side::Sell => entry.0 += trade.quantity,
+        }
+
+        completed
+    }
+
+    /// Bar in progress, if any trade has been seen since the last completed bar
+    pub fn current(&self) -> Option<FootprintBar> {
+        let timestamp = Timestamp::from_millis(self.bar_start?);
+        let (open, high, low, close) = self.ohlc;
+
+        Some(FootprintBar {
+            timestamp,
+            open,
+            high,
+            low,
+            close,
+            levels: self
+                .levels
+                .iter()
+                .map(|(&key, &(bid_volume, ask_volume))| FootprintLevel {
+                    price: key as f64 * self.tick_size,
+                    bid_volume,
+                    ask_volume,
+                })
+                .collect(),
+            tick_size: self.tick_size,
+        })
+    }
+
+    /// Close the bar in progress and return it
+    pub fn flush(&mut self) -> Option<FootprintBar> {
+        let bar = self.current();
+        self.bar_start = None;
+        self.levels.clear();
+        bar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(price: f64, quantity: f64, side: Side, timestamp: i64) -> Trade {
+        Trade::new(price, quantity, side, Timestamp::from_millis(timestamp))
+    }
+
+    #[test]
+    fn test_bar_levels_and_delta() {
+        let mut fp = FootprintBuilder::new(0.5, 60_000);
+        assert!(fp.add_trade(&trade(100.0, 2.0, Side::Buy, 1_000)).is_none());
+        fp.add_trade(&trade(100.0, 1.0, Side::Sell, 2_000));
+        fp.add_trade(&trade(100.5, 3.0, Side::Buy, 3_000));
+        fp.add_trade(&trade(99.5, 4.0, Side::Sell, 4_000));
+
+        let bar = fp.add_trade(&trade(101.0, 1.0, Side::Buy, 60_000)).unwrap();
+        assert_eq!(bar.timestamp, Timestamp::EPOCH);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (100.0, 100.5, 99.5, 99.5)
+        );
+        assert_eq!(bar.levels.len(), 3);
+        assert_eq!(bar.volume(), 10.0);
+        assert_eq!(bar.delta(), 0.0);
+
+        let level = bar.level(100.0).unwrap();
+        assert_eq!((level.bid_volume, level.ask_volume), (1.0, 2.0));
+
+        // The trade that closed the bar opens the next one
+        assert_eq!(
+            fp.current().unwrap().timestamp,
+            Timestamp::from_millis(60_000)
+        );
+    }
+
+    #[test]
+    fn test_diagonal_imbalances() {
+        let mut fp = FootprintBuilder::new(1.0, 60_000);
+        fp.add_trade(&trade(100.0, 1.0, Side::Sell, 0));
+        fp.add_trade(&trade(101.0, 5.0, Side::Buy, 1));
+        fp.add_trade(&trade(101.0, 1.0, Side::Sell, 2));
+        fp.add_trade(&trade(102.0, 1.0, Side::Buy, 3));
+
+        let bar = fp.flush().unwrap();
+        let imbalances = bar.imbalances(3.0);
+
+        // 5 lifted at 101 vs 1 hit at 100
+        assert!(imbalances
+            .iter()
+            .any(|i| i.price == 101.0 && i.side == Side::Buy && i.ratio == 5.0));
+        // 1 hit at 101 vs 1 lifted at 102: balanced
+        assert!(!imbalances
+            .iter()
+            .any(|i| i.price == 101.0 && i.side == Side::Sell));
+    }
+
+    #[test]
+    fn test_unfinished_auction() {
+        let mut fp = FootprintBuilder::new(1.0, 60_000);
+        fp.add_trade(&trade(100.0, 1.0, Side::Sell, 0));
+        fp.add_trade(&trade(102.0, 1.0, Side::Buy, 1));
+        fp.add_trade(&trade(102.0, 2.0, Side::Sell, 2));
+
+        let bar = fp.flush().unwrap();
+        assert!(bar.unfinished_high());
+        assert!(!bar.unfinished_low());
+        assert!(fp.current().is_none());
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(FootprintBuilder::try_new(0.0, 1_000).is_err());
+        assert!(FootprintBuilder::try_new(0.01, 0).is_err());
+        assert!(FootprintBuilder::try_new(0.01, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_late_trade_is_ignored() {
+        let mut fp = FootprintBuilder::new(1.0, 1_000);
+        fp.add_trade(&trade(100.0, 1.0, Side::Buy, 5_500));
+        assert!(fp.add_trade(&trade(99.0, 1.0, Side::Buy, 4_900)).is_none());
+        assert_eq!(fp.current().unwrap().volume(), 1.0);
+    }
+}
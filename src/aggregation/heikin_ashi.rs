@@ -0,0 +1,100 @@
+use crate::candles::Candle;
+
+/// Converts OHLCV candles into Heikin-Ashi candles
+///
+/// The Heikin-Ashi close is the bar's OHLC average and its open the midpoint of the
+/// previous Heikin-Ashi body (the first bar uses its own open and close); high and low
+/// extend to cover both. Volume and timestamp pass through unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct HeikinAshi {
+    prev: Option<Candle>,
+}
+
+impl HeikinAshi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Candle {
+        let close = (candle.open + candle.high + candle.low + candle.close) / 4.0;
+        let open = match self.prev {
+            Some(prev) => (prev.open + prev.close) / 2.0,
+            None => (candle.open + candle.close) / 2.0,
+        };
+        let bar = Candle::new(
+            open,
+            candle.high.max(open).max(close),
+            candle.low.min(open).min(close),
+            close,
+            candle.volume,
+            candle.timestamp,
+        );
+        self.prev = Some(bar);
+        bar
+    }
+
+    /// Transform a whole series, continuing from any candles already seen
+    pub fn transform(&mut self, candles: &[Candle]) -> Vec<Candle> {
+        candles.iter().map(|c| self.update(c)).collect()
+    }
+
+    /// Last Heikin-Ashi candle produced
+    pub fn current(&self) -> Option<Candle> {
+        self.prev
+    }
+
+    pub fn reset(&mut self) {
+        self.prev = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    #[test]
+    fn test_heikin_ashi_bars() {
+        let mut ha = HeikinAshi::new();
+        let bars = ha.transform(&[
+            Candle::new(10.0, 14.0, 9.0, 13.0, 5.0, Timestamp::EPOCH),
+            Candle::new(13.0, 15.0, 12.0, 12.0, 7.0, Timestamp::from_millis(60)),
+        ]);
+
+        assert_eq!(
+            bars[0],
+            Candle::new(11.5, 14.0, 9.0, 11.5, 5.0, Timestamp::EPOCH)
+        );
+        // Open 11.5 from the previous body, close (13 + 15 + 12 + 12) / 4 = 13
+        assert_eq!(
+            bars[1],
+            Candle::new(11.5, 15.0, 11.5, 13.0, 7.0, Timestamp::from_millis(60))
+        );
+        assert_eq!(ha.current(), Some(bars[1]));
+    }
+
+    #[test]
+    fn test_trend_smoothing_and_reset() {
+        let mut ha = HeikinAshi::new();
+        // A steady uptrend with one red candle stays bullish in Heikin-Ashi
+        let closes = [100.0, 102.0, 104.0, 103.5, 106.0];
+        let mut open = 99.0;
+        for close in closes {
+            let bar = ha.update(&Candle::new(
+                open,
+                close + 0.5,
+                open - 0.5,
+                close,
+                1.0,
+                Timestamp::EPOCH,
+            ));
+            if open != 99.0 {
+                assert!(bar.is_bullish());
+            }
+            open = close;
+        }
+
+        ha.reset();
+        assert_eq!(ha.current(), None);
+    }
+}
@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::trades::Trade;
+
+/// TPO letters: `A`..`Z` followed by `a`..`z`
+const TPO_LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// One price row of a market profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TpoRow {
+    /// Lower bound of the price bin
+    pub price: f64,
+    /// Letters of every period that traded through this row
+    pub letters: String,
+}
+
+impl TpoRow {
+    pub fn tpo_count(&self) -> usize {
+        self.letters.chars().count()
+    }
+}
+
+/// Completed market profile for one session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketProfile {
+    /// Rows from the highest price to the lowest, as the profile is usually printed
+    pub rows: Vec<TpoRow>,
+    /// Low and high of the first `initial_balance_periods` periods
+    pub initial_balance: Option<(f64, f64)>,
+    /// Row with the most TPOs (ties go to the row closest to the middle of the range)
+    pub poc: Option<f64>,
+    /// High row was visited by more than one period (no excess, likely to be revisited)
+    pub poor_high: bool,
+    /// Low row was visited by more than one period
+    pub poor_low: bool,
+    /// Rows inside the range touched by a single period
+    pub single_prints: Vec<f64>,
+}
+
+/// Builds a TPO (time price opportunity) profile from trades within a session
+pub struct MarketProfileBuilder {
+    bin_size: f64,
+    period: i64,
+    initial_balance_periods: usize,
+    session_start: Option<i64>,
+    /// Low/high bin index traded in each period, indexed by period number
+    ranges: Vec<Option<(i64, i64)>>,
+}
+
+impl MarketProfileBuilder {
+    /// `bin_size` is the price granularity of a row and `period` the length of one
+    /// TPO period in milliseconds (traditionally 30 minutes)
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(bin_size: f64, period: i64) -> Self {
+        Self::try_new(bin_size, period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(bin_size: f64, period: i64) -> Result<Self> {
+        ensure(bin_size.is_finite() && bin_size > 0.0, "bin size must be positive")?;
+        ensure(period > 0, "period must be positive")?;
+
+        Ok(Self {
+            bin_size,
+            period,
+            initial_balance_periods: 2,
+            session_start: None,
+            ranges: Vec::new(),
+        })
+    }
+
+    /// Number of opening periods forming the initial balance (default 2: A and B)
+    pub fn with_initial_balance_periods(mut self, periods: usize) -> Self {
+        self.initial_balance_periods = periods;
+        self
+    }
+
+    /// Anchor period boundaries to the session open instead of the first trade
+    pub fn with_session_start(mut self, timestamp: i64) -> Self {
+        self.session_start = Some(timestamp);
+        self
+    }
+
+    pub fn add_trade(&mut self, trade: &Trade) {
+        self.add_price(trade.price, trade.timestamp.as_millis());
+    }
+
+    pub fn add_price(&mut self, price: f64, timestamp: i64) {
+        let start = *self.session_start.get_or_insert(timestamp);
+        if timestamp < start {
+            return;
+        }
+
+        let index = ((timestamp - start) / self.period) as usize;
+        if index >= TPO_LETTERS.len() {
+            return;
+        }
+        if self.ranges.len() <= index {
+            self.ranges.resize(index + 1, None);
+        }
+
+        let bin = (price / self.bin_size).floor() as i64;
+        let range = self.ranges[index].get_or_insert((bin, bin));
+        range.0 = range.0.min(bin);
+        range.1 = range.1.max(bin);
+    }
+
+    /// Number of periods seen so far
+    pub fn periods(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn profile(&self) -> MarketProfile {
+        let traded: Vec<(usize, (i64, i64))> = self
+            .ranges
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.map(|r| (i, r)))
+            .collect();
+
+        let (low, high) = match (
+            traded.iter().map(|(_, r)| r.0).min(),
+            traded.iter().map(|(_, r)| r.1).max(),
+        ) {
+            (Some(low), Some(high)) => (low, high),
+            _ => {
+                return MarketProfile {
+                    rows: Vec::new(),
+                    initial_balance: None,
+                    poc: None,
+                    poor_high: false,
+                    poor_low: false,
+                    single_prints: Vec::new(),
+                }
+            }
+        };
+
+        let rows: Vec<TpoRow> = (low..=high)
+            .rev()
+            .map(|bin| TpoRow {
+                price: bin as f64 * self.bin_size,
+                letters: traded
+                    .iter()
+                    .filter(|(_, (lo, hi))| (*lo..=*hi).contains(&bin))
+                    .map(|(i, _)| TPO_LETTERS[*i] as char)
+                    .collect(),
+            })
+            .collect();
+
+        let ib: Vec<(i64, i64)> = traded
+            .iter()
+            .filter(|(i, _)| *i < self.initial_balance_periods)
+            .map(|(_, r)| *r)
+            .collect();
+        let initial_balance = match (ib.iter().map(|r| r.0).min(), ib.iter().map(|r| r.1).max()) {
+            (Some(lo), Some(hi)) => Some((lo as f64 * self.bin_size, (hi + 1) as f64 * self.bin_size)),
+            _ => None,
+        };
+
+        let center = (rows.len() as f64 - 1.0) / 2.0;
+        let poc = rows
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| {
+                a.tpo_count()
+                    .cmp(&b.tpo_count())
+                    .then_with(|| (*j as f64 - center).abs().total_cmp(&(*i as f64 - center).abs()))
+            })
+            .map(|(_, row)| row.price);
+
+        let single_prints = if rows.len() > 2 {
+            rows[1..rows.len() - 1]
+                .iter()
+                .filter(|row| row.tpo_count() == 1)
+                .map(|row| row.price)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        MarketProfile {
+            poor_high: rows.first().is_some_and(|r| r.tpo_count() > 1),
+            poor_low: rows.last().is_some_and(|r| r.tpo_count() > 1),
+            rows,
+            initial_balance,
+            poc,
+            single_prints,
+        }
+    }
+
+    /// Start a new session; the next trade (or `session_start`) anchors the periods
+    pub fn reset(&mut self, session_start: Option<i64>) {
+        self.ranges.clear();
+        self.session_start = session_start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: i64 = 30 * 60 * 1000;
+
+    fn builder() -> MarketProfileBuilder {
+        MarketProfileBuilder::new(1.0, PERIOD).with_session_start(0)
+    }
+
+    #[test]
+    fn test_letters_assigned_per_period() {
+        let mut mp = builder();
+        mp.add_price(100.0, 0);
+        mp.add_price(102.0, 1_000);
+        mp.add_price(101.0, PERIOD);
+        mp.add_price(103.0, PERIOD + 1);
+
+        let profile = mp.profile();
+        let rows: Vec<(f64, &str)> = profile.rows.iter().map(|r| (r.price, r.letters.as_str())).collect();
+        assert_eq!(rows, vec![(103.0, "B"), (102.0, "AB"), (101.0, "AB"), (100.0, "A")]);
+        assert_eq!(mp.periods(), 2);
+    }
+
+    #[test]
+    fn test_initial_balance_and_poc() {
+        let mut mp = builder();
+        mp.add_price(100.0, 0);
+        mp.add_price(101.0, 1);
+        mp.add_price(101.0, PERIOD);
+        mp.add_price(102.0, PERIOD);
+        mp.add_price(101.0, 2 * PERIOD);
+        mp.add_price(105.0, 2 * PERIOD);
+
+        let profile = mp.profile();
+        assert_eq!(profile.initial_balance, Some((100.0, 103.0)));
+        assert_eq!(profile.poc, Some(101.0));
+    }
+
+    #[test]
+    fn test_poor_extremes_and_single_prints() {
+        let mut mp = builder();
+        // A and B both end at the same high; C spikes down alone leaving single prints
+        mp.add_price(104.0, 0);
+        mp.add_price(103.0, 0);
+        mp.add_price(104.0, PERIOD);
+        mp.add_price(103.0, PERIOD);
+        mp.add_price(103.0, 2 * PERIOD);
+        mp.add_price(100.0, 2 * PERIOD);
+
+        let profile = mp.profile();
+        assert!(profile.poor_high);
+        assert!(!profile.poor_low);
+        assert_eq!(profile.single_prints, vec![102.0, 101.0]);
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(MarketProfileBuilder::try_new(-1.0, PERIOD).is_err());
+        assert!(MarketProfileBuilder::try_new(1.0, 0).is_err());
+        assert!(MarketProfileBuilder::try_new(1.0, PERIOD).is_ok());
+    }
+
+    #[test]
+    fn test_empty_and_reset() {
+        let mut mp = builder();
+        assert!(mp.profile().rows.is_empty());
+
+        mp.add_price(100.0, 10);
+        mp.reset(None);
+        assert_eq!(mp.periods(), 0);
+
+        // New session anchored at the first trade
+        mp.add_price(100.0, 5 * PERIOD);
+        assert_eq!(mp.profile().rows[0].letters, "A");
+    }
+}
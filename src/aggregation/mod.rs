@@ -0,0 +1,15 @@
+pub mod candle_builder;
+pub mod footprint;
+pub mod heikin_ashi;
+pub mod market_profile;
+pub mod renko;
+pub mod seasonality;
+pub mod volume_profile;
+
+pub use candle_builder::{CandleBuilder, GapFill};
+pub use footprint::{FootprintBar, FootprintBuilder, FootprintLevel, Imbalance};
+pub use heikin_ashi::HeikinAshi;
+pub use market_profile::{MarketProfile, MarketProfileBuilder, TpoRow};
+pub use renko::{BrickSize, RenkoBuilder};
+pub use seasonality::{SeasonalBucket, SeasonalityProfiler};
+pub use volume_profile::{ValueArea, VolumeBin, VolumeProfile};
@@ -0,0 +1,221 @@
+use crate::candles::Candle;
+use crate::error::{ensure, Result};
+use crate::indicators::ATR;
+
+/// How tall each Renko brick is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrickSize {
+    /// Constant price distance
+    Fixed(f64),
+    /// `multiplier` times the [`ATR`] of the source candles over `period`, tracked as
+    /// the ATR moves; no bricks form until it has warmed up
+    Atr { period: usize, multiplier: f64 },
+}
+
+/// Converts OHLCV candles into close-based Renko bricks
+///
+/// A brick forms each time the close moves a full brick beyond the last brick;
+/// reversing direction takes two bricks' worth of movement, since the new brick starts
+/// from the far end of the last one. Bricks are returned as [`Candle`]s with the
+/// timestamp of the candle that completed them. The volume traded since the previous
+/// brick goes to the first brick a candle forms.
+#[derive(Debug, Clone)]
+pub struct RenkoBuilder {
+    size: BrickSize,
+    atr: Option<ATR>,
+    /// Price the first brick is measured from
+    anchor: Option<f64>,
+    /// (open, close) of the last brick
+    last: Option<(f64, f64)>,
+    pending_volume: f64,
+}
+
+impl RenkoBuilder {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(size: BrickSize) -> Self {
+        Self::try_new(size).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(size: BrickSize) -> Result<Self> {
+        let atr = match size {
+            BrickSize::Fixed(size) => {
+                ensure(
+                    size.is_finite() && size > 0.0,
+                    "brick size must be positive",
+                )?;
+                None
+            }
+            BrickSize::Atr { period, multiplier } => {
+                ensure(
+                    multiplier.is_finite() && multiplier > 0.0,
+                    "ATR multiplier must be positive",
+                )?;
+                Some(ATR::try_new(period)?)
+            }
+        };
+        Ok(Self {
+            size,
+            atr,
+            anchor: None,
+            last: None,
+            pending_volume: 0.0,
+        })
+    }
+
+    pub fn brick_size(&self) -> BrickSize {
+        self.size
+    }
+
+    /// Add a candle, returning the bricks its close completes
+    pub fn update(&mut self, candle: &Candle) -> Vec<Candle> {
+        let size = match (self.size, &mut self.atr) {
+            (BrickSize::Atr { multiplier, .. }, Some(atr)) => {
+                atr.update(candle).map(|atr| atr * multiplier)
+            }
+            (BrickSize::Fixed(size), _) => Some(size),
+            (BrickSize::Atr { .. }, None) => None,
+        };
+        self.pending_volume += candle.volume;
+
+        let price = candle.close;
+        let anchor = *self.anchor.get_or_insert(price);
+        let Some(size) = size.filter(|&s| s > 0.0) else {
+            return Vec::new();
+        };
+
+        let mut bricks = Vec::new();
+        loop {
+            let (bottom, top) = match self.last {
+                Some((open, close)) => (open.min(close), open.max(close)),
+                None => (anchor, anchor),
+            };
+            let (open, close) = if price >= top + size {
+                (top, top + size)
+            } else if price <= bottom - size {
+                (bottom, bottom - size)
+            } else {
+                break;
+            };
+            let volume = std::mem::take(&mut self.pending_volume);
+            bricks.push(Candle::new(
+                open,
+                open.max(close),
+                open.min(close),
+                close,
+                volume,
+                candle.timestamp,
+            ));
+            self.last = Some((open, close));
+        }
+        bricks
+    }
+
+    /// Transform a whole series, continuing from any candles already seen
+    pub fn transform(&mut self, candles: &[Candle]) -> Vec<Candle> {
+        candles.iter().flat_map(|c| self.update(c)).collect()
+    }
+
+    /// (open, close) of the last completed brick
+    pub fn last_brick(&self) -> Option<(f64, f64)> {
+        self.last
+    }
+
+    pub fn reset(&mut self) {
+        if let Some(atr) = &mut self.atr {
+            atr.reset();
+        }
+        self.anchor = None;
+        self.last = None;
+        self.pending_volume = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn close(price: f64, timestamp: i64) -> Candle {
+        Candle::new(
+            price,
+            price,
+            price,
+            price,
+            1.0,
+            Timestamp::from_millis(timestamp),
+        )
+    }
+
+    fn bodies(bricks: &[Candle]) -> Vec<(f64, f64)> {
+        bricks.iter().map(|b| (b.open, b.close)).collect()
+    }
+
+    #[test]
+    fn test_fixed_bricks_need_two_to_reverse() {
+        let mut renko = RenkoBuilder::new(BrickSize::Fixed(1.0));
+        assert!(renko.update(&close(100.0, 0)).is_empty());
+        assert!(renko.update(&close(100.9, 1)).is_empty());
+
+        let up = renko.update(&close(102.5, 2));
+        assert_eq!(bodies(&up), [(100.0, 101.0), (101.0, 102.0)]);
+        // The first brick carries all three candles' volume, timestamped at completion
+        assert_eq!((up[0].volume, up[1].volume), (3.0, 0.0));
+        assert_eq!(up[0].timestamp, Timestamp::from_millis(2));
+
+        // One brick down from the top is not enough to reverse
+        assert!(renko.update(&close(100.5, 3)).is_empty());
+        let down = renko.update(&close(99.8, 4));
+        assert_eq!(bodies(&down), [(101.0, 100.0)]);
+        assert_eq!((down[0].high, down[0].low), (101.0, 100.0));
+        assert_eq!(renko.last_brick(), Some((101.0, 100.0)));
+    }
+
+    #[test]
+    fn test_atr_bricks_wait_for_warm_up() {
+        let mut renko = RenkoBuilder::new(BrickSize::Atr {
+            period: 3,
+            multiplier: 1.0,
+        });
+        // Every candle spans 2 around its close, so the ATR is 2 from the third candle
+        let candles: Vec<Candle> = [100.0, 101.0, 102.0, 103.0, 106.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                Candle::new(
+                    c,
+                    c + 1.0,
+                    c - 1.0,
+                    c,
+                    1.0,
+                    Timestamp::from_millis(i as i64),
+                )
+            })
+            .collect();
+
+        assert!(renko.update(&candles[0]).is_empty());
+        assert!(renko.update(&candles[1]).is_empty());
+        assert_eq!(bodies(&renko.update(&candles[2])), [(100.0, 102.0)]);
+        assert!(renko.update(&candles[3]).is_empty());
+        // The gap to 106 has a true range of 4: ATR (2 * 2 + 4) / 3
+        let size = 8.0 / 3.0;
+        assert_eq!(bodies(&renko.update(&candles[4])), [(102.0, 102.0 + size)]);
+
+        renko.reset();
+        assert_eq!(renko.last_brick(), None);
+    }
+
+    #[test]
+    fn test_invalid_brick_sizes() {
+        assert!(RenkoBuilder::try_new(BrickSize::Fixed(0.0)).is_err());
+        assert!(RenkoBuilder::try_new(BrickSize::Atr {
+            period: 0,
+            multiplier: 1.0
+        })
+        .is_err());
+        assert!(RenkoBuilder::try_new(BrickSize::Atr {
+            period: 14,
+            multiplier: f64::NAN
+        })
+        .is_err());
+    }
+}
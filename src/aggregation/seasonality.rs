@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+use crate::orderbook::Quote;
+use crate::trades::Trade;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Average behaviour of one time-of-day bucket across sessions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeasonalBucket {
+    /// Bucket start as milliseconds since midnight (in the profiler's time zone)
+    pub time_of_day: i64,
+    pub avg_volume: f64,
+    /// Root mean of the summed squared log returns per session (realized volatility)
+    pub volatility: f64,
+    /// Time-unweighted average quoted spread, `None` if no quote was ever seen
+    pub avg_spread: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct BucketTotals {
+    volume: KahanSum,
+    squared_returns: KahanSum,
+    spread_sum: KahanSum,
+    spread_count: u64,
+}
+
+impl BucketTotals {
+    fn add(&mut self, other: &BucketTotals) {
+        self.volume += other.volume.value();
+        self.squared_returns += other.squared_returns.value();
+        self.spread_sum += other.spread_sum.value();
+        self.spread_count += other.spread_count;
+    }
+}
+
+/// Intraday volume, volatility and spread profile by time-of-day bucket
+///
+/// Data is accumulated per session (calendar day, shifted by `utc_offset`) and folded
+/// into the cross-session averages whenever a new day starts or
+/// [`end_session`](Self::end_session) is called.
+pub struct SeasonalityProfiler {
+    bucket_ms: i64,
+    utc_offset_ms: i64,
+    session: Option<i64>,
+    last_price: Option<f64>,
+    current: Vec<BucketTotals>,
+    history: Vec<BucketTotals>,
+    sessions: usize,
+}
+
+impl SeasonalityProfiler {
+    /// `bucket_ms` must divide a day evenly, e.g. 5 minutes or 30 minutes
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(bucket_ms: i64) -> Self {
+        Self::try_new(bucket_ms).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(bucket_ms: i64) -> Result<Self> {
+        ensure(bucket_ms > 0 && DAY_MS % bucket_ms == 0, "bucket must divide a day evenly")?;
+
+        let buckets = (DAY_MS / bucket_ms) as usize;
+        Ok(Self {
+            bucket_ms,
+            utc_offset_ms: 0,
+            session: None,
+            last_price: None,
+            current: vec![BucketTotals::default(); buckets],
+            history: vec![BucketTotals::default(); buckets],
+            sessions: 0,
+        })
+    }
+
+    /// Offset added to timestamps before bucketing, to align days with a local exchange calendar
+    pub fn with_utc_offset(mut self, offset_ms: i64) -> Self {
+        self.utc_offset_ms = offset_ms;
+        self
+    }
+
+    fn locate(&mut self, timestamp: i64) -> usize {
+        let local = timestamp + self.utc_offset_ms;
+        let day = local.div_euclid(DAY_MS);
+
+        if self.session.is_some_and(|session| session != day) {
+            self.end_session();
+        }
+        self.session = Some(day);
+
+        (local.rem_euclid(DAY_MS) / self.bucket_ms) as usize
+    }
+
+    pub fn on_trade(&mut self, trade: &Trade) {
+        let bucket = self.locate(trade.timestamp.as_millis());
+        let totals = &mut self.current[bucket];
+        totals.volume += trade.quantity;
+
+        if let Some(prev) = self.last_price {
+            if prev > 0.0 && trade.price > 0.0 {
+                totals.squared_returns += (trade.price / prev).ln().powi(2);
+            }
+        }
+        self.last_price = Some(trade.price);
+    }
+
+    pub fn on_quote(&mut self, quote: &Quote) {
+        let bucket = self.locate(quote.timestamp.as_millis());
+        let totals = &mut self.current[bucket];
+        totals.spread_sum += quote.spread();
+        totals.spread_count += 1;
+    }
+
+    /// Fold the current session into the averages
+    pub fn end_session(&mut self) {
+        if self.session.take().is_none() {
+            return;
+        }
+
+        for (history, current) in self.history.iter_mut().zip(self.current.iter_mut()) {
+            history.add(current);
+            *current = BucketTotals::default();
+        }
+        self.last_price = None;
+        self.sessions += 1;
+    }
+
+    /// Completed sessions included in the profile
+    pub fn sessions(&self) -> usize {
+        self.sessions
+    }
+
+    pub fn profile(&self) -> Vec<SeasonalBucket> {
+        let sessions = self.sessions.max(1) as f64;
+        self.history
+            .iter()
+            .enumerate()
+            .map(|(i, totals)| SeasonalBucket {
+                time_of_day: i as i64 * self.bucket_ms,
+                avg_volume: totals.volume.value() / sessions,
+                volatility: (totals.squared_returns.value() / sessions).sqrt(),
+                avg_spread: (totals.spread_count > 0)
+                    .then(|| totals.spread_sum.value() / totals.spread_count as f64),
+            })
+            .collect()
+    }
+
+    /// Bucket containing `timestamp`
+    pub fn bucket(&self, timestamp: i64) -> SeasonalBucket {
+        let local = (timestamp + self.utc_offset_ms).rem_euclid(DAY_MS);
+        self.profile()[(local / self.bucket_ms) as usize]
+    }
+
+    /// Share of an average day's volume traded at or after `timestamp`'s time of day
+    pub fn remaining_volume_fraction(&self, timestamp: i64) -> Option<f64> {
+        let total: f64 = self.history.iter().map(|t| t.volume.value()).sum();
+        if self.sessions == 0 || total <= 0.0 {
+            return None;
+        }
+
+        Some(self.remaining_average_volume(timestamp) * self.sessions as f64 / total)
+    }
+
+    /// Expected volume still to trade today after `timestamp`, prorating the current bucket
+    pub fn expected_volume_remaining(&self, timestamp: i64) -> Option<f64> {
+        (self.sessions > 0).then(|| self.remaining_average_volume(timestamp))
+    }
+
+    fn remaining_average_volume(&self, timestamp: i64) -> f64 {
+        let local = (timestamp + self.utc_offset_ms).rem_euclid(DAY_MS);
+        let bucket = (local / self.bucket_ms) as usize;
+        let elapsed = (local % self.bucket_ms) as f64 / self.bucket_ms as f64;
+        let sessions = self.sessions.max(1) as f64;
+
+        let current = self.history[bucket].volume.value() * (1.0 - elapsed);
+        let later: f64 = self.history[bucket + 1..]
+            .iter()
+            .map(|t| t.volume.value())
+            .sum();
+        (current + later) / sessions
+    }
+
+    pub fn reset(&mut self) {
+        self.current.fill(BucketTotals::default());
+        self.history.fill(BucketTotals::default());
+        self.session = None;
+        self.last_price = None;
+        self.sessions = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+    use crate::trades::Side;
+
+    const HOUR: i64 = 60 * 60 * 1000;
+
+    fn trade(price: f64, quantity: f64, timestamp: i64) -> Trade {
+        Trade::new(
+            price,
+            quantity,
+            Side::Buy,
+            Timestamp::from_millis(timestamp),
+        )
+    }
+
+    fn quote(spread: f64, timestamp: i64) -> Quote {
+        Quote {
+            bid_price: 100.0,
+            bid_size: 1.0,
+            ask_price: 100.0 + spread,
+            ask_size: 1.0,
+            timestamp: Timestamp::from_millis(timestamp),
+        }
+    }
+
+    #[test]
+    fn test_averages_across_sessions() {
+        let mut profiler = SeasonalityProfiler::new(HOUR);
+
+        for day in 0..2 {
+            let base = day * DAY_MS;
+            profiler.on_trade(&trade(100.0, 10.0 * (day + 1) as f64, base + 9 * HOUR));
+            profiler.on_quote(&quote(0.02 * (day + 1) as f64, base + 9 * HOUR));
+            profiler.on_trade(&trade(100.0, 5.0, base + 15 * HOUR));
+        }
+        profiler.end_session();
+
+        assert_eq!(profiler.sessions(), 2);
+        let nine = profiler.bucket(9 * HOUR + 1);
+        assert_eq!(nine.time_of_day, 9 * HOUR);
+        assert_eq!(nine.avg_volume, 15.0);
+        assert!((nine.avg_spread.unwrap() - 0.03).abs() < 1e-12);
+        assert_eq!(profiler.bucket(15 * HOUR).avg_volume, 5.0);
+        assert_eq!(profiler.bucket(3 * HOUR).avg_spread, None);
+    }
+
+    #[test]
+    fn test_volatility_per_bucket() {
+        let mut profiler = SeasonalityProfiler::new(HOUR);
+        profiler.on_trade(&trade(100.0, 1.0, 10 * HOUR));
+        profiler.on_trade(&trade(110.0, 1.0, 10 * HOUR + 1));
+        profiler.end_session();
+
+        let vol = profiler.bucket(10 * HOUR).volatility;
+        assert!((vol - (1.1_f64).ln()).abs() < 1e-12);
+        assert_eq!(profiler.bucket(11 * HOUR).volatility, 0.0);
+    }
+
+    #[test]
+    fn test_expected_volume_remaining() {
+        let mut profiler = SeasonalityProfiler::new(HOUR);
+        assert_eq!(profiler.expected_volume_remaining(0), None);
+
+        profiler.on_trade(&trade(100.0, 40.0, 10 * HOUR));
+        profiler.on_trade(&trade(100.0, 60.0, 12 * HOUR));
+        profiler.end_session();
+
+        // Half way through the 10:00 bucket: 20 + 60 left
+        let remaining = profiler.expected_volume_remaining(DAY_MS + 10 * HOUR + HOUR / 2);
+        assert_eq!(remaining, Some(80.0));
+        assert_eq!(profiler.remaining_volume_fraction(13 * HOUR), Some(0.0));
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(SeasonalityProfiler::try_new(7 * 60 * 1000).is_err());
+        assert!(SeasonalityProfiler::try_new(0).is_err());
+        assert!(SeasonalityProfiler::try_new(HOUR).is_ok());
+    }
+
+    #[test]
+    fn test_utc_offset_and_reset() {
+        // UTC-5: 14:00 UTC is 09:00 local
+        let mut profiler = SeasonalityProfiler::new(HOUR).with_utc_offset(-5 * HOUR);
+        profiler.on_trade(&trade(100.0, 1.0, 14 * HOUR));
+        profiler.end_session();
+        assert_eq!(profiler.profile()[9].avg_volume, 1.0);
+
+        profiler.reset();
+        assert_eq!(profiler.sessions(), 0);
+        assert!(profiler.profile().iter().all(|b| b.avg_volume == 0.0));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::candles::Candle;
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+use crate::trades::{Side, Trade, TradeTape};
+
+/// Volume traded inside one price bin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VolumeBin {
+    /// Inclusive lower bound of the bin
+    pub low: f64,
+    /// Exclusive upper bound of the bin
+    pub high: f64,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+}
+
+impl VolumeBin {
+    pub fn total(&self) -> f64 {
+        self.buy_volume + self.sell_volume
+    }
+
+    /// Buy minus sell volume
+    pub fn delta(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+
+    pub fn mid(&self) -> f64 {
+        (self.low + self.high) / 2.0
+    }
+}
+
+/// Price range containing a given share of the profile's volume
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValueArea {
+    pub high: f64,
+    pub low: f64,
+    pub volume: f64,
+}
+
+/// Traded volume binned by price, over a whole session or a rolling time window
+pub struct VolumeProfile {
+    bin_size: f64,
+    window: Option<i64>,
+    bins: BTreeMap<i64, (KahanSum, KahanSum)>,
+    trades: VecDeque<Trade>,
+    total: KahanSum,
+}
+
+impl VolumeProfile {
+    /// Profile accumulating until [`reset`](Self::reset) is called (e.g. at session end)
+    ///
+    /// Panics on invalid parameters; see [`try_session`](Self::try_session)
+    pub fn session(bin_size: f64) -> Self {
+        Self::try_session(bin_size).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Profile over the trades of the last `window` milliseconds
+    ///
+    /// Panics on invalid parameters; see [`try_rolling`](Self::try_rolling)
+    pub fn rolling(bin_size: f64, window: i64) -> Self {
+        Self::try_rolling(bin_size, window).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_session(bin_size: f64) -> Result<Self> {
+        Self::build(bin_size, None)
+    }
+
+    pub fn try_rolling(bin_size: f64, window: i64) -> Result<Self> {
+        ensure(window > 0, "window must be positive")?;
+        Self::build(bin_size, Some(window))
+    }
+
+    fn build(bin_size: f64, window: Option<i64>) -> Result<Self> {
+        ensure(bin_size.is_finite() && bin_size > 0.0, "bin size must be positive")?;
+
+        Ok(Self {
+            bin_size,
+            window,
+            bins: BTreeMap::new(),
+            trades: VecDeque::new(),
+            total: KahanSum::new(),
+        })
+    }
+
+    /// Session profile of the trades a tape currently retains for `symbol`
+    pub fn from_tape(tape: &TradeTape, symbol: &str, bin_size: f64) -> Result<Self> {
+        let mut profile = Self::try_session(bin_size)?;
+        for trade in tape.trades(symbol) {
+            profile.add_trade(trade);
+        }
+        Ok(profile)
+    }
+
+    pub fn add_trade(&mut self, trade: &Trade) {
+        if let Some(window) = self.window {
+            let cutoff = trade.timestamp.as_millis() - window;
+            while let Some(old) = self.trades.front() {
+                if old.timestamp.as_millis() > cutoff {
+                    break;
+                }
+                let old = *old;
+                self.trades.pop_front();
+                self.apply(&old, -1.0);
+            }
+            self.trades.push_back(*trade);
+        }
+
+        self.apply(trade, 1.0);
+    }
+
+    /// Spread a candle's volume evenly over the bins between its low and high
+    ///
+    /// Candles carry no aggressor side, so the volume counts as buy volume on bars
+    /// closing at or above their open and as sell volume otherwise. In a rolling
+    /// profile each bin's share expires with the candle's timestamp.
+    pub fn add_candle(&mut self, candle: &Candle) {
+        let side = if candle.close >= candle.open {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        let (low, high) = (self.bin_index(candle.low), self.bin_index(candle.high));
+        let share = candle.volume / (high - low + 1) as f64;
+        for key in low..=high {
+            let mid = (key as f64 + 0.5) * self.bin_size;
+            self.add_trade(&Trade::new(mid, share, side, candle.timestamp));
+        }
+    }
+
+    fn apply(&mut self, trade: &Trade, sign: f64) {
+        let key = self.bin_index(trade.price);
+        let entry = self.bins.entry(key).or_default();
+        match trade.side {
+            Side::Buy => entry.0 += sign * trade.quantity,
+            Side::Sell => entry.1 += sign * trade.quantity,
+        }
+        self.total += sign * trade.quantity;
+
+        if entry.0.value() + entry.1.value() <= f64::EPSILON {
+            self.bins.remove(&key);
+        }
+    }
+
+    fn bin_index(&self, price: f64) -> i64 {
+        (price / self.bin_size).floor() as i64
+    }
+
+    fn bin(&self, key: i64, (buy, sell): (KahanSum, KahanSum)) -> VolumeBin {
+        VolumeBin {
+            low: key as f64 * self.bin_size,
+            high: (key + 1) as f64 * self.bin_size,
+            buy_volume: buy.value(),
+            sell_volume: sell.value(),
+        }
+    }
+
+    fn bin_volume(&self, key: i64) -> f64 {
+        let (buy, sell) = self.bins[&key];
+        buy.value() + sell.value()
+    }
+
+    /// Bins with volume, ordered by price
+    pub fn bins(&self) -> Vec<VolumeBin> {
+        self.bins.iter().map(|(&k, &v)| self.bin(k, v)).collect()
+    }
+
+    pub fn total_volume(&self) -> f64 {
+        self.total.value()
+    }
+
+    /// Point of control: mid price of the bin with the most volume
+    pub fn poc(&self) -> Option<f64> {
+        self.poc_index().map(|k| self.bin(k, self.bins[&k]).mid())
+    }
+
+    fn poc_index(&self) -> Option<i64> {
+        self.bins
+            .iter()
+            .map(|(&k, _)| (k, self.bin_volume(k)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(k, _)| k)
+    }
+
+    /// Smallest contiguous range around the POC holding `fraction` of the volume (typically 0.7)
+    ///
+    /// Expands one bin at a time towards whichever neighbour has more volume.
+    pub fn value_area(&self, fraction: f64) -> Option<ValueArea> {
+        let poc = self.poc_index()?;
+        let keys: Vec<i64> = self.bins.keys().copied().collect();
+        let volume = |i: usize| self.bin_volume(keys[i]);
+
+        let target = self.total.value() * fraction;
+        let start = keys.binary_search(&poc).ok()?;
+        let (mut lo, mut hi) = (start, start);
+        let mut accumulated = volume(start);
+
+        while accumulated < target && (lo > 0 || hi + 1 < keys.len()) {
+            let below = if lo > 0 { volume(lo - 1) } else { -1.0 };
+            let above = if hi + 1 < keys.len() { volume(hi + 1) } else { -1.0 };
+
+            if above >= below {
+                hi += 1;
+                accumulated += above;
+            } else {
+                lo -= 1;
+                accumulated += below;
+            }
+        }
+
+        Some(ValueArea {
+            high: (keys[hi] + 1) as f64 * self.bin_size,
+            low: keys[lo] as f64 * self.bin_size,
+            volume: accumulated,
+        })
+    }
+
+    /// Histogram as CSV (`low,high,buy_volume,sell_volume,total`), one row per bin
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("low,high,buy_volume,sell_volume,total\n");
+        for bin in self.bins() {
+            out.push_str(&format!(
+                "{},{},{},{},{}\n",
+                bin.low,
+                bin.high,
+                bin.buy_volume,
+                bin.sell_volume,
+                bin.total()
+            ));
+        }
+        out
+    }
+
+    /// Histogram bins as JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.bins())
+    }
+
+    pub fn reset(&mut self) {
+        self.bins.clear();
+        self.trades.clear();
+        self.total.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn trade(price: f64, quantity: f64, side: Side, timestamp: i64) -> Trade {
+        Trade::new(price, quantity, side, Timestamp::from_millis(timestamp))
+    }
+
+    #[test]
+    fn test_binning_and_split() {
+        let mut profile = VolumeProfile::session(1.0);
+        profile.add_trade(&trade(100.2, 2.0, Side::Buy, 1));
+        profile.add_trade(&trade(100.8, 1.0, Side::Sell, 2));
+        profile.add_trade(&trade(101.5, 4.0, Side::Sell, 3));
+
+        let bins = profile.bins();
+        assert_eq!(bins.len(), 2);
+        assert_eq!(bins[0].low, 100.0);
+        assert_eq!(bins[0].buy_volume, 2.0);
+        assert_eq!(bins[0].sell_volume, 1.0);
+        assert_eq!(bins[0].delta(), 1.0);
+        assert_eq!(profile.total_volume(), 7.0);
+        assert_eq!(profile.poc(), Some(101.5));
+    }
+
+    #[test]
+    fn test_value_area() {
+        let mut profile = VolumeProfile::session(1.0);
+        let volumes = [(98.0, 1.0), (99.0, 3.0), (100.0, 10.0), (101.0, 4.0), (102.0, 2.0)];
+        for (i, &(price, qty)) in volumes.iter().enumerate() {
+            profile.add_trade(&trade(price, qty, Side::Buy, i as i64));
+        }
+
+        // 70% of 20 = 14: POC (10) + above (4)
+        let va = profile.value_area(0.7).unwrap();
+        assert_eq!(va.low, 100.0);
+        assert_eq!(va.high, 102.0);
+        assert_eq!(va.volume, 14.0);
+
+        let va = profile.value_area(0.8).unwrap();
+        assert_eq!(va.low, 99.0);
+        assert_eq!(va.volume, 17.0);
+    }
+
+    #[test]
+    fn test_rolling_window_evicts_old_trades() {
+        let mut profile = VolumeProfile::rolling(1.0, 1_000);
+        profile.add_trade(&trade(100.0, 5.0, Side::Buy, 0));
+        profile.add_trade(&trade(105.0, 1.0, Side::Sell, 500));
+        assert_eq!(profile.poc(), Some(100.5));
+
+        profile.add_trade(&trade(105.0, 1.0, Side::Sell, 1_200));
+        assert_eq!(profile.total_volume(), 2.0);
+        assert_eq!(profile.bins().len(), 1);
+        assert_eq!(profile.poc(), Some(105.5));
+    }
+
+    #[test]
+    fn test_fallible_constructors() {
+        assert!(VolumeProfile::try_session(0.0).is_err());
+        assert!(VolumeProfile::try_rolling(1.0, 0).is_err());
+        assert!(VolumeProfile::try_rolling(1.0, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_candles_spread_volume_across_their_range() {
+        let mut profile = VolumeProfile::session(1.0);
+        // Spans bins 100, 101 and 102
+        profile.add_candle(&Candle::new(
+            100.5,
+            102.5,
+            100.2,
+            102.0,
+            9.0,
+            Timestamp::EPOCH,
+        ));
+        // Down bar inside one bin
+        profile.add_candle(&Candle::new(
+            101.8,
+            101.9,
+            101.1,
+            101.2,
+            2.0,
+            Timestamp::from_millis(1),
+        ));
+
+        let bins = profile.bins();
+        assert_eq!(bins.len(), 3);
+        assert_eq!((bins[1].buy_volume, bins[1].sell_volume), (3.0, 2.0));
+        assert_eq!(profile.total_volume(), 11.0);
+        assert_eq!(profile.poc(), Some(101.5));
+        assert_eq!(profile.to_csv().lines().nth(2), Some("101,102,3,2,5"));
+        assert!(profile.to_json().unwrap().starts_with("[{\"low\":100.0"));
+    }
+
+    #[test]
+    fn test_profile_from_tape() {
+        let mut tape = TradeTape::new(crate::trades::Retention::Count(2));
+        tape.record("BTC", trade(100.0, 5.0, Side::Buy, 0));
+        tape.record("BTC", trade(101.0, 1.0, Side::Sell, 1));
+        tape.record("BTC", trade(101.2, 1.0, Side::Buy, 2));
+
+        // Only the two retained trades count
+        let profile = VolumeProfile::from_tape(&tape, "BTC", 1.0).unwrap();
+        assert_eq!(profile.total_volume(), 2.0);
+        assert_eq!(profile.poc(), Some(101.5));
+        assert!(VolumeProfile::from_tape(&tape, "ETH", 1.0)
+            .unwrap()
+            .bins()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_empty_profile() {
+        let mut profile = VolumeProfile::session(0.5);
+        assert_eq!(profile.poc(), None);
+        assert_eq!(profile.value_area(0.7), None);
+
+        profile.add_trade(&trade(10.0, 1.0, Side::Buy, 0));
+        profile.reset();
+        assert!(profile.bins().is_empty());
+    }
+}
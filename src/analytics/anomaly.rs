@@ -0,0 +1,332 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::time::Timestamp;
+
+/// MAD is scaled by this factor so robust z-scores match standard z-scores for normal data
+const MAD_SCALE: f64 = 1.4826;
+
+/// What kind of abnormal behaviour was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnomalyKind {
+    PriceJump,
+    VolumeSpike,
+    SpreadBlowout,
+}
+
+/// How far outside the normal range the observation was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// Flagged observation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub severity: Severity,
+    /// Observed value (log return, volume or spread)
+    pub value: f64,
+    /// Distance from the baseline in robust standard deviations
+    pub score: f64,
+    pub timestamp: Timestamp,
+}
+
+/// Robust z-score against the median/MAD of a rolling window
+pub struct MadZScore {
+    window: usize,
+    values: VecDeque<f64>,
+}
+
+impl MadZScore {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            values: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Score `value` against the current window, `None` while warming up or if the window is flat
+    pub fn score(&self, value: f64) -> Option<f64> {
+        if self.values.len() < self.window {
+            return None;
+        }
+
+        let mut sorted: Vec<f64> = self.values.iter().copied().collect();
+        let center = median(&mut sorted);
+        let mut deviations: Vec<f64> = sorted.iter().map(|v| (v - center).abs()).collect();
+        let mad = median(&mut deviations) * MAD_SCALE;
+
+        if mad == 0.0 {
+            return None;
+        }
+
+        Some((value - center) / mad)
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        if self.values.len() > self.window {
+            self.values.pop_front();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+    }
+}
+
+/// EWMA control chart: score is the distance from the EWMA mean in EWMA standard deviations
+pub struct EwmaControl {
+    alpha: f64,
+    warmup: usize,
+    count: usize,
+    mean: f64,
+    variance: f64,
+}
+
+impl EwmaControl {
+    /// `span` sets the smoothing as for an EMA of that period
+    pub fn new(span: usize) -> Self {
+        Self {
+            alpha: 2.0 / (span as f64 + 1.0),
+            warmup: span,
+            count: 0,
+            mean: 0.0,
+            variance: 0.0,
+        }
+    }
+
+    pub fn score(&self, value: f64) -> Option<f64> {
+        if self.count < self.warmup || self.variance <= 0.0 {
+            return None;
+        }
+
+        Some((value - self.mean) / self.variance.sqrt())
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.count == 0 {
+            self.mean = value;
+        } else {
+            let diff = value - self.mean;
+            self.mean += self.alpha * diff;
+            self.variance = (1.0 - self.alpha) * (self.variance + self.alpha * diff * diff);
+        }
+        self.count += 1;
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.mean = 0.0;
+        self.variance = 0.0;
+    }
+}
+
+/// Thresholds and window lengths for [`AnomalyDetector`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// Rolling window for the price-return and volume MAD baselines
+    pub window: usize,
+    /// EWMA span for the spread control chart
+    pub spread_span: usize,
+    pub warning_score: f64,
+    pub critical_score: f64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            window: 100,
+            spread_span: 100,
+            warning_score: 4.0,
+            critical_score: 8.0,
+        }
+    }
+}
+
+/// Streaming detector for price jumps, volume spikes and spread blowouts
+///
+/// Every observation is scored against the baseline *before* it is added, so a
+/// single outlier cannot mask itself.
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    returns: MadZScore,
+    volumes: MadZScore,
+    spreads: EwmaControl,
+    last_price: Option<f64>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            returns: MadZScore::new(config.window),
+            volumes: MadZScore::new(config.window),
+            spreads: EwmaControl::new(config.spread_span),
+            last_price: None,
+        }
+    }
+
+    /// Check a trade for price jumps (either direction) and volume spikes (upwards only)
+    pub fn on_trade(&mut self, price: f64, volume: f64, timestamp: Timestamp) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+
+        if let Some(prev) = self.last_price {
+            if prev > 0.0 && price > 0.0 {
+                let ret = (price / prev).ln();
+                if let Some(score) = self.returns.score(ret) {
+                    anomalies.extend(self.flag(
+                        AnomalyKind::PriceJump,
+                        ret,
+                        score.abs(),
+                        timestamp,
+                    ));
+                }
+                self.returns.push(ret);
+            }
+        }
+        self.last_price = Some(price);
+
+        if let Some(score) = self.volumes.score(volume) {
+            anomalies.extend(self.flag(AnomalyKind::VolumeSpike, volume, score, timestamp));
+        }
+        self.volumes.push(volume);
+
+        anomalies
+    }
+
+    /// Check a quote for spread blowouts
+    pub fn on_quote(&mut self, bid: f64, ask: f64, timestamp: Timestamp) -> Option<Anomaly> {
+        let spread = ask - bid;
+        let anomaly = self
+            .spreads
+            .score(spread)
+            .and_then(|score| self.flag(AnomalyKind::SpreadBlowout, spread, score, timestamp));
+        self.spreads.push(spread);
+
+        anomaly
+    }
+
+    fn flag(
+        &self,
+        kind: AnomalyKind,
+        value: f64,
+        score: f64,
+        timestamp: Timestamp,
+    ) -> Option<Anomaly> {
+        let severity = if score >= self.config.critical_score {
+            Severity::Critical
+        } else if score >= self.config.warning_score {
+            Severity::Warning
+        } else {
+            return None;
+        };
+
+        Some(Anomaly {
+            kind,
+            severity,
+            value,
+            score,
+            timestamp,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.returns.reset();
+        self.volumes.reset();
+        self.spreads.reset();
+        self.last_price = None;
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn config() -> AnomalyConfig {
+        AnomalyConfig {
+            window: 20,
+            spread_span: 20,
+            ..AnomalyConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_mad_zscore() {
+        let mut mad = MadZScore::new(5);
+        for v in [1.0, 2.0, 3.0, 4.0] {
+            mad.push(v);
+        }
+        assert_eq!(mad.score(3.0), None);
+
+        mad.push(5.0);
+        // median 3, MAD 1 -> scaled 1.4826
+        assert!((mad.score(6.0).unwrap() - 3.0 / MAD_SCALE).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_price_jump_detected() {
+        let mut detector = AnomalyDetector::new(config());
+
+        for i in 0..30 {
+            let price = 100.0 + if i % 2 == 0 { 0.01 } else { -0.01 } * (1 + i % 3) as f64;
+            assert!(detector
+                .on_trade(price, 1.0 + (i % 4) as f64, at(i))
+                .is_empty());
+        }
+
+        let anomalies = detector.on_trade(105.0, 2.0, at(30));
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].kind, AnomalyKind::PriceJump);
+        assert_eq!(anomalies[0].severity, Severity::Critical);
+        assert_eq!(anomalies[0].timestamp, at(30));
+    }
+
+    #[test]
+    fn test_volume_spike_detected() {
+        let mut detector = AnomalyDetector::new(config());
+
+        for i in 0..30 {
+            detector.on_trade(100.0 + (i % 3) as f64 * 0.01, 1.0 + (i % 4) as f64, at(i));
+        }
+
+        let anomalies = detector.on_trade(100.01, 9.0, at(30));
+        assert!(anomalies
+            .iter()
+            .any(|a| a.kind == AnomalyKind::VolumeSpike && a.severity == Severity::Warning));
+
+        // Unusually small volume is not a spike
+        let anomalies = detector.on_trade(100.01, 0.0, at(31));
+        assert!(anomalies.iter().all(|a| a.kind != AnomalyKind::VolumeSpike));
+    }
+
+    #[test]
+    fn test_spread_blowout_detected() {
+        let mut detector = AnomalyDetector::new(config());
+
+        for i in 0..40 {
+            let spread = 1.0 + (i % 2) as f64 * 0.1;
+            assert_eq!(detector.on_quote(100.0, 100.0 + spread, at(i)), None);
+        }
+
+        let anomaly = detector.on_quote(100.0, 110.0, at(40)).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::SpreadBlowout);
+        assert_eq!(anomaly.severity, Severity::Critical);
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use crate::orderbook::OrderBook;
+use crate::time::Timestamp;
+
+/// Currency pair traded on one book, e.g. `ETH/BTC` has base `ETH` and quote `BTC`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Market {
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+}
+
+impl Market {
+    pub fn new(symbol: &str, base: &str, quote: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+        }
+    }
+
+    fn other(&self, currency: &str) -> Option<&str> {
+        if currency == self.base {
+            Some(&self.quote)
+        } else if currency == self.quote {
+            Some(&self.base)
+        } else {
+            None
+        }
+    }
+}
+
+/// Profitable round trip found across the three books
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageOpportunity {
+    /// Currency the round trip starts and ends in
+    pub start_currency: String,
+    /// Symbols traded, in execution order
+    pub path: Vec<String>,
+    /// Currency amount after the round trip per unit invested, fees included
+    pub gross_multiplier: f64,
+    /// `gross_multiplier - 1`
+    pub net_return: f64,
+    /// Largest start amount the top-of-book sizes can absorb on every leg
+    pub max_start_amount: f64,
+    /// Profit in start currency when trading `max_start_amount`
+    pub expected_profit: f64,
+    pub timestamp: Timestamp,
+}
+
+/// Detects triangular arbitrage across three books sharing three currencies
+pub struct TriangularArbitrage {
+    markets: [Market; 3],
+    fee_rate: f64,
+    min_return: f64,
+    start_currency: String,
+}
+
+impl TriangularArbitrage {
+    /// Markets must form a triangle over exactly three currencies. The round trip
+    /// starts in the quote currency of the first market.
+    pub fn new(markets: [Market; 3], fee_rate: f64) -> Self {
+        let start_currency = markets[0].quote.clone();
+        Self {
+            markets,
+            fee_rate,
+            min_return: 0.0,
+            start_currency,
+        }
+    }
+
+    /// Only report round trips returning more than this after fees
+    pub fn with_min_return(mut self, min_return: f64) -> Self {
+        self.min_return = min_return;
+        self
+    }
+
+    /// Evaluate both cycle directions; `books` are given in the same order as the markets
+    pub fn check(&self, books: [&OrderBook; 3], timestamp: Timestamp) -> Vec<ArbitrageOpportunity> {
+        self.cycles()
+            .into_iter()
+            .filter_map(|cycle| self.evaluate(&cycle, &books, timestamp))
+            .filter(|opportunity| opportunity.net_return > self.min_return)
+            .collect()
+    }
+
+    /// Market indices in execution order for each direction around the triangle
+    fn cycles(&self) -> Vec<[usize; 3]> {
+        let mut cycles = Vec::new();
+        for first in 0..3 {
+            let Some(second_currency) = self.markets[first].other(&self.start_currency) else {
+                continue;
+            };
+
+            for second in (0..3).filter(|&i| i != first) {
+                let third = 3 - first - second;
+                let Some(third_currency) = self.markets[second].other(second_currency) else {
+                    continue;
+                };
+                if self.markets[third].other(third_currency) == Some(self.start_currency.as_str()) {
+                    cycles.push([first, second, third]);
+                }
+            }
+        }
+        cycles
+    }
+
+    fn evaluate(
+        &self,
+        cycle: &[usize; 3],
+        books: &[&OrderBook; 3],
+        timestamp: Timestamp,
+    ) -> Option<ArbitrageOpportunity> {
+        let mut currency = self.start_currency.as_str();
+        let mut multiplier = 1.0;
+        let mut max_start = f64::INFINITY;
+
+        for &i in cycle {
+            let market = &self.markets[i];
+            let (rate, max_input) = self.convert(market, books[i], currency)?;
+
+            // max_input is denominated in the currency held before this leg
+            max_start = max_start.min(max_input / multiplier);
+            multiplier *= rate;
+            currency = market.other(currency)?;
+        }
+
+        Some(ArbitrageOpportunity {
+            start_currency: self.start_currency.clone(),
+            path: cycle
+                .iter()
+                .map(|&i| self.markets[i].symbol.clone())
+                .collect(),
+            gross_multiplier: multiplier,
+            net_return: multiplier - 1.0,
+            max_start_amount: max_start,
+            expected_profit: max_start * (multiplier - 1.0),
+            timestamp,
+        })
+    }
+
+    /// Conversion rate (after fees) and the most input the best level can absorb
+    fn convert(&self, market: &Market, book: &OrderBook, from: &str) -> Option<(f64, f64)> {
+        let keep = 1.0 - self.fee_rate;
+        if from == market.quote {
+            // Buy base with quote at the ask
+            let (price, size) = book.best_ask()?;
+            Some((keep / price, size * price))
+        } else if from == market.base {
+            // Sell base for quote at the bid
+            let (price, size) = book.best_bid()?;
+            Some((price * keep, size))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::top_of_book;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn detector(fee: f64) -> TriangularArbitrage {
+        TriangularArbitrage::new(
+            [
+                Market::new("BTCUSD", "BTC", "USD"),
+                Market::new("ETHBTC", "ETH", "BTC"),
+                Market::new("ETHUSD", "ETH", "USD"),
+            ],
+            fee,
+        )
+    }
+
+    #[test]
+    fn test_detects_mispriced_cross() {
+        // ETH is cheap in BTC terms: USD -> BTC -> ETH -> USD is profitable
+        let btc = top_of_book("BTCUSD", 49_990.0, 50_000.0, 1.0);
+        let eth_btc = top_of_book("ETHBTC", 0.0599, 0.0600, 10.0);
+        let eth = top_of_book("ETHUSD", 3_100.0, 3_101.0, 10.0);
+
+        let opportunities = detector(0.0).check([&btc, &eth_btc, &eth], at(42));
+        assert_eq!(opportunities.len(), 1);
+
+        let opp = &opportunities[0];
+        assert_eq!(opp.path, vec!["BTCUSD", "ETHBTC", "ETHUSD"]);
+        assert_eq!(opp.start_currency, "USD");
+        assert!((opp.gross_multiplier - 3_100.0 / (50_000.0 * 0.06)).abs() < 1e-9);
+        assert_eq!(opp.timestamp, at(42));
+
+        // Binding constraint: 10 ETH on the ETHBTC ask = 0.6 BTC = 30,000 USD
+        assert!((opp.max_start_amount - 30_000.0).abs() < 1e-6);
+        assert!(opp.expected_profit > 0.0);
+    }
+
+    #[test]
+    fn test_fees_remove_opportunity() {
+        let btc = top_of_book("BTCUSD", 49_990.0, 50_000.0, 1.0);
+        let eth_btc = top_of_book("ETHBTC", 0.0599, 0.0600, 10.0);
+        let eth = top_of_book("ETHUSD", 3_100.0, 3_101.0, 10.0);
+
+        // ~3.3% edge vs 3 * 1.5% fees
+        assert!(detector(0.015)
+            .check([&btc, &eth_btc, &eth], at(0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fair_prices_have_no_opportunity() {
+        let btc = top_of_book("BTCUSD", 49_995.0, 50_005.0, 1.0);
+        let eth_btc = top_of_book("ETHBTC", 0.05999, 0.06001, 10.0);
+        let eth = top_of_book("ETHUSD", 2_999.5, 3_000.5, 10.0);
+
+        assert!(detector(0.0)
+            .check([&btc, &eth_btc, &eth], at(0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reverse_direction_and_empty_book() {
+        // ETH is expensive in BTC terms: USD -> ETH -> BTC -> USD
+        let btc = top_of_book("BTCUSD", 50_000.0, 50_010.0, 1.0);
+        let eth_btc = top_of_book("ETHBTC", 0.0650, 0.0651, 10.0);
+        let eth = top_of_book("ETHUSD", 2_999.0, 3_000.0, 10.0);
+
+        let opportunities = detector(0.001).check([&btc, &eth_btc, &eth], at(0));
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].path, vec!["ETHUSD", "ETHBTC", "BTCUSD"]);
+
+        let empty = OrderBook::new("ETHBTC".to_string());
+        assert!(detector(0.0).check([&btc, &empty, &eth], at(0)).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+
+/// Sample autocorrelation for lags `0..=max_lag`
+///
+/// Uses the standard biased estimator (normalized by the full-sample variance),
+/// so values are directly comparable across lags. Returns an empty vector when the
+/// series is too short or has zero variance.
+pub fn acf(series: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = series.len();
+    if n < 2 || max_lag >= n {
+        return Vec::new();
+    }
+
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let denom: f64 = series.iter().map(|v| (v - mean).powi(2)).sum();
+    if denom == 0.0 {
+        return Vec::new();
+    }
+
+    (0..=max_lag)
+        .map(|lag| {
+            let num: f64 = series[lag..]
+                .iter()
+                .zip(series)
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum();
+            num / denom
+        })
+        .collect()
+}
+
+/// Sample partial autocorrelation for lags `0..=max_lag` (Durbin-Levinson recursion)
+pub fn pacf(series: &[f64], max_lag: usize) -> Vec<f64> {
+    pacf_from_acf(&acf(series, max_lag))
+}
+
+fn pacf_from_acf(rho: &[f64]) -> Vec<f64> {
+    if rho.is_empty() {
+        return Vec::new();
+    }
+
+    let max_lag = rho.len() - 1;
+    let mut result = Vec::with_capacity(rho.len());
+    result.push(1.0);
+
+    let mut phi_prev: Vec<f64> = Vec::new();
+    for k in 1..=max_lag {
+        let num = rho[k] - (1..k).map(|j| phi_prev[j - 1] * rho[k - j]).sum::<f64>();
+        let den = 1.0 - (1..k).map(|j| phi_prev[j - 1] * rho[j]).sum::<f64>();
+        let phi_kk = if den.abs() < f64::EPSILON {
+            0.0
+        } else {
+            num / den
+        };
+
+        let mut phi = Vec::with_capacity(k);
+        for j in 1..k {
+            phi.push(phi_prev[j - 1] - phi_kk * phi_prev[k - j - 1]);
+        }
+        phi.push(phi_kk);
+
+        result.push(phi_kk);
+        phi_prev = phi;
+    }
+
+    result
+}
+
+/// Autocorrelation over a rolling window of returns
+pub struct RollingAutocorrelation {
+    window: usize,
+    max_lag: usize,
+    values: VecDeque<f64>,
+}
+
+impl RollingAutocorrelation {
+    pub fn new(window: usize, max_lag: usize) -> Self {
+        Self {
+            window,
+            max_lag,
+            values: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Push a return and get the ACF for lags `0..=max_lag` once the window is full
+    pub fn update(&mut self, value: f64) -> Option<Vec<f64>> {
+        self.values.push_back(value);
+
+        if self.values.len() > self.window {
+            self.values.pop_front();
+        }
+
+        self.acf()
+    }
+
+    pub fn acf(&self) -> Option<Vec<f64>> {
+        if self.values.len() < self.window {
+            return None;
+        }
+
+        let series: Vec<f64> = self.values.iter().copied().collect();
+        let result = acf(&series, self.max_lag);
+        (!result.is_empty()).then_some(result)
+    }
+
+    pub fn pacf(&self) -> Option<Vec<f64>> {
+        self.acf().map(|rho| pacf_from_acf(&rho))
+    }
+
+    /// Autocorrelation at a single lag
+    pub fn lag(&self, lag: usize) -> Option<f64> {
+        self.acf()?.get(lag).copied()
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acf_alternating_series() {
+        // Perfect bid-ask bounce style reversal
+        let series: Vec<f64> = (0..100)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let rho = acf(&series, 2);
+
+        assert_eq!(rho[0], 1.0);
+        assert!(rho[1] < -0.95);
+        assert!(rho[2] > 0.95);
+    }
+
+    #[test]
+    fn test_acf_degenerate_inputs() {
+        assert!(acf(&[1.0, 1.0, 1.0], 1).is_empty());
+        assert!(acf(&[1.0, 2.0], 5).is_empty());
+    }
+
+    #[test]
+    fn test_pacf_of_ar1_cuts_off() {
+        // Deterministic AR(1)-like series driven by a fixed pseudo-random sequence
+        let mut x = 0.0;
+        let mut seed: u64 = 42;
+        let series: Vec<f64> = (0..5000)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let noise = (seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+                x = 0.6 * x + noise;
+                x
+            })
+            .collect();
+
+        let phi = pacf(&series, 3);
+        assert!((phi[1] - 0.6).abs() < 0.05);
+        assert!(phi[2].abs() < 0.05);
+        assert!(phi[3].abs() < 0.05);
+    }
+
+    #[test]
+    fn test_rolling_acf() {
+        let mut rolling = RollingAutocorrelation::new(10, 1);
+
+        for i in 0..9 {
+            assert!(rolling.update(i as f64).is_none());
+        }
+
+        let rho = rolling.update(9.0).unwrap();
+        assert_eq!(rho.len(), 2);
+        assert!(rho[1] > 0.0); // trending window
+        assert_eq!(rolling.lag(1), Some(rho[1]));
+        assert!(rolling.pacf().is_some());
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::indicators::Indicator;
+use crate::numeric::KahanSum;
+
+/// Rolling beta of an asset's returns against a benchmark
+///
+/// Keeps running sums so each update is O(1) regardless of window length. As an
+/// [`Indicator`] it consumes `(asset, benchmark)` return pairs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingBeta {
+    window: usize,
+    pairs: VecDeque<(f64, f64)>,
+    sum_asset: KahanSum,
+    sum_bench: KahanSum,
+    sum_cross: KahanSum,
+    sum_bench_sq: KahanSum,
+}
+
+impl RollingBeta {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(window: usize) -> Self {
+        Self::try_new(window).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(window: usize) -> Result<Self> {
+        ensure(window >= 2, "window must be at least 2")?;
+
+        Ok(Self {
+            window,
+            pairs: VecDeque::with_capacity(window),
+            sum_asset: KahanSum::new(),
+            sum_bench: KahanSum::new(),
+            sum_cross: KahanSum::new(),
+            sum_bench_sq: KahanSum::new(),
+        })
+    }
+
+    /// Push one pair of synchronized returns and get beta once the window is full
+    pub fn update(&mut self, asset_return: f64, benchmark_return: f64) -> Option<f64> {
+        self.pairs.push_back((asset_return, benchmark_return));
+        self.add(asset_return, benchmark_return, 1.0);
+
+        if self.pairs.len() > self.window {
+            if let Some((a, b)) = self.pairs.pop_front() {
+                self.add(a, b, -1.0);
+            }
+        }
+
+        self.beta()
+    }
+
+    fn add(&mut self, asset: f64, bench: f64, sign: f64) {
+        self.sum_asset += sign * asset;
+        self.sum_bench += sign * bench;
+        self.sum_cross += sign * asset * bench;
+        self.sum_bench_sq += sign * bench * bench;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.window >= 2 && self.pairs.len() == self.window
+    }
+
+    /// Sample covariance between asset and benchmark returns
+    pub fn covariance(&self) -> Option<f64> {
+        if !self.is_ready() {
+            return None;
+        }
+
+        let n = self.window as f64;
+        Some(
+            (self.sum_cross.value() - self.sum_asset.value() * self.sum_bench.value() / n)
+                / (n - 1.0),
+        )
+    }
+
+    /// Sample variance of benchmark returns
+    pub fn benchmark_variance(&self) -> Option<f64> {
+        if !self.is_ready() {
+            return None;
+        }
+
+        let n = self.window as f64;
+        let sum_bench = self.sum_bench.value();
+        Some(((self.sum_bench_sq.value() - sum_bench * sum_bench / n) / (n - 1.0)).max(0.0))
+    }
+
+    /// Beta = cov(asset, benchmark) / var(benchmark), `None` if the benchmark is flat
+    pub fn beta(&self) -> Option<f64> {
+        let variance = self.benchmark_variance()?;
+        if variance <= f64::EPSILON * f64::EPSILON {
+            return None;
+        }
+
+        Some(self.covariance()? / variance)
+    }
+
+    /// Intercept of the regression of asset returns on benchmark returns
+    pub fn alpha(&self) -> Option<f64> {
+        let beta = self.beta()?;
+        let n = self.window as f64;
+        Some(self.sum_asset.value() / n - beta * self.sum_bench.value() / n)
+    }
+
+    /// Benchmark notional to short per unit of asset notional to neutralize market exposure
+    pub fn hedge_ratio(&self) -> Option<f64> {
+        self.beta()
+    }
+
+    pub fn reset(&mut self) {
+        self.pairs.clear();
+        self.sum_asset.reset();
+        self.sum_bench.reset();
+        self.sum_cross.reset();
+        self.sum_bench_sq.reset();
+    }
+}
+
+impl Indicator<(f64, f64), f64> for RollingBeta {
+    fn update(&mut self, (asset, benchmark): (f64, f64)) -> Option<f64> {
+        RollingBeta::update(self, asset, benchmark)
+    }
+
+    fn reset(&mut self) {
+        RollingBeta::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.beta()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beta_of_scaled_series() {
+        let mut beta = RollingBeta::new(4);
+        let bench = [0.01, -0.02, 0.015, 0.005, -0.01];
+
+        let mut value = None;
+        for &b in &bench {
+            value = beta.update(1.5 * b + 0.001, b);
+        }
+
+        assert!((value.unwrap() - 1.5).abs() < 1e-9);
+        assert!((beta.alpha().unwrap() - 0.001).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_warm_up_and_flat_benchmark() {
+        let mut beta = RollingBeta::new(3);
+        assert_eq!(beta.update(0.01, 0.0), None);
+        assert_eq!(beta.update(0.02, 0.0), None);
+        assert_eq!(beta.update(0.03, 0.0), None);
+
+        assert!(RollingBeta::try_new(1).is_err());
+    }
+
+    #[test]
+    fn test_window_rolls_off_old_samples() {
+        let mut beta = RollingBeta::new(3);
+
+        // Regime with beta 2, followed by a full window of beta -1
+        for &b in &[0.01, 0.02, -0.01] {
+            beta.update(2.0 * b, b);
+        }
+        for &b in &[0.03, -0.02, 0.01] {
+            beta.update(-b, b);
+        }
+
+        assert!((beta.hedge_ratio().unwrap() + 1.0).abs() < 1e-9);
+
+        beta.reset();
+        assert_eq!(beta.beta(), None);
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::indicators::Indicator;
+
+/// Correlation coefficient used by the rolling trackers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorrelationMethod {
+    /// Linear (product-moment) correlation
+    Pearson,
+    /// Rank correlation, robust to outliers and monotonic non-linearity
+    Spearman,
+}
+
+/// Rolling correlation between two synchronized return series
+///
+/// As an [`Indicator`] it consumes `(x, y)` pairs; wrap it in
+/// [`PairedReturns`](crate::indicators::PairedReturns) to feed two price series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingCorrelation {
+    window: usize,
+    method: CorrelationMethod,
+    xs: VecDeque<f64>,
+    ys: VecDeque<f64>,
+}
+
+impl RollingCorrelation {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(window: usize, method: CorrelationMethod) -> Self {
+        Self::try_new(window, method).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(window: usize, method: CorrelationMethod) -> Result<Self> {
+        ensure(window >= 2, "window must be at least 2")?;
+
+        Ok(Self {
+            window,
+            method,
+            xs: VecDeque::with_capacity(window),
+            ys: VecDeque::with_capacity(window),
+        })
+    }
+
+    /// Create a Pearson correlation tracker
+    pub fn pearson(window: usize) -> Self {
+        Self::new(window, CorrelationMethod::Pearson)
+    }
+
+    /// Create a Spearman rank correlation tracker
+    pub fn spearman(window: usize) -> Self {
+        Self::new(window, CorrelationMethod::Spearman)
+    }
+
+    /// Push one pair of synchronized returns and get the correlation once the window is full
+    pub fn update(&mut self, x: f64, y: f64) -> Option<f64> {
+        self.xs.push_back(x);
+        self.ys.push_back(y);
+
+        if self.xs.len() > self.window {
+            self.xs.pop_front();
+            self.ys.pop_front();
+        }
+
+        self.value()
+    }
+
+    /// Correlation over the current window, `None` while warming up or if either series is flat
+    pub fn value(&self) -> Option<f64> {
+        if self.window < 2 || self.xs.len() < self.window {
+            return None;
+        }
+
+        let xs: Vec<f64> = self.xs.iter().copied().collect();
+        let ys: Vec<f64> = self.ys.iter().copied().collect();
+
+        correlation(&xs, &ys, self.method)
+    }
+
+    pub fn method(&self) -> CorrelationMethod {
+        self.method
+    }
+
+    pub fn reset(&mut self) {
+        self.xs.clear();
+        self.ys.clear();
+    }
+}
+
+impl Indicator<(f64, f64), f64> for RollingCorrelation {
+    fn update(&mut self, (x, y): (f64, f64)) -> Option<f64> {
+        RollingCorrelation::update(self, x, y)
+    }
+
+    fn reset(&mut self) {
+        RollingCorrelation::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.value()
+    }
+}
+
+/// Rolling correlation matrix across a fixed set of symbols
+pub struct CorrelationMatrix {
+    symbols: Vec<String>,
+    window: usize,
+    method: CorrelationMethod,
+    returns: Vec<VecDeque<f64>>,
+}
+
+impl CorrelationMatrix {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(symbols: Vec<String>, window: usize, method: CorrelationMethod) -> Self {
+        Self::try_new(symbols, window, method).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(symbols: Vec<String>, window: usize, method: CorrelationMethod) -> Result<Self> {
+        ensure(window >= 2, "window must be at least 2")?;
+
+        let returns = symbols
+            .iter()
+            .map(|_| VecDeque::with_capacity(window))
+            .collect();
+
+        Ok(Self {
+            symbols,
+            window,
+            method,
+            returns,
+        })
+    }
+
+    /// Push one synchronized return per symbol, in the order given at construction
+    pub fn update(&mut self, returns: &[f64]) -> Result<()> {
+        if returns.len() != self.symbols.len() {
+            return Err(MarketDataError::DimensionMismatch {
+                expected: self.symbols.len(),
+                actual: returns.len(),
+            });
+        }
+
+        for (series, &value) in self.returns.iter_mut().zip(returns) {
+            series.push_back(value);
+            if series.len() > self.window {
+                series.pop_front();
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether every series holds a full window
+    pub fn is_ready(&self) -> bool {
+        self.window >= 2 && self.returns.iter().all(|r| r.len() == self.window)
+    }
+
+    /// Full symmetric matrix; pairs involving a flat series are reported as NaN
+    pub fn matrix(&self) -> Option<Vec<Vec<f64>>> {
+        if !self.is_ready() {
+            return None;
+        }
+
+        let series: Vec<Vec<f64>> = self
+            .returns
+            .iter()
+            .map(|r| r.iter().copied().collect())
+            .collect();
+
+        let n = series.len();
+        let mut matrix = vec![vec![1.0; n]; n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let value = correlation(&series[i], &series[j], self.method).unwrap_or(f64::NAN);
+                matrix[i][j] = value;
+                matrix[j][i] = value;
+            }
+        }
+
+        Some(matrix)
+    }
+
+    /// Correlation between two named symbols
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        if !self.is_ready() {
+            return None;
+        }
+
+        let i = self.symbols.iter().position(|s| s == a)?;
+        let j = self.symbols.iter().position(|s| s == b)?;
+        let xs: Vec<f64> = self.returns[i].iter().copied().collect();
+        let ys: Vec<f64> = self.returns[j].iter().copied().collect();
+
+        correlation(&xs, &ys, self.method)
+    }
+
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    pub fn reset(&mut self) {
+        for series in self.returns.iter_mut() {
+            series.clear();
+        }
+    }
+}
+
+/// Correlation of two equally sized samples
+pub fn correlation(xs: &[f64], ys: &[f64], method: CorrelationMethod) -> Option<f64> {
+    match method {
+        CorrelationMethod::Pearson => pearson(xs, ys),
+        CorrelationMethod::Spearman => pearson(&ranks(xs), &ranks(ys)),
+    }
+}
+
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len();
+    if n < 2 || n != ys.len() {
+        return None;
+    }
+
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+
+    Some((cov / (var_x.sqrt() * var_y.sqrt())).clamp(-1.0, 1.0))
+}
+
+/// Fractional ranks (1-based), ties receive the average of their positions
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &idx in &order[i..=j] {
+            ranks[idx] = rank;
+        }
+        i = j + 1;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pearson_perfect_correlation() {
+        let mut corr = RollingCorrelation::pearson(5);
+
+        for i in 1..=4 {
+            assert_eq!(corr.update(i as f64, 2.0 * i as f64), None);
+        }
+
+        let value = corr.update(5.0, 10.0).unwrap();
+        assert!((value - 1.0).abs() < 1e-12);
+
+        // Window slides: anti-correlated samples push the value negative
+        for i in 0..5 {
+            corr.update(i as f64, -(i as f64));
+        }
+        assert!((corr.value().unwrap() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_flat_series_has_no_correlation() {
+        let mut corr = RollingCorrelation::pearson(3);
+        corr.update(1.0, 0.5);
+        corr.update(2.0, 0.5);
+        assert_eq!(corr.update(3.0, 0.5), None);
+    }
+
+    #[test]
+    fn test_spearman_monotonic() {
+        let mut corr = RollingCorrelation::spearman(5);
+        let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        let mut value = None;
+        for &x in &xs {
+            // Non-linear but strictly increasing relationship
+            value = corr.update(x, f64::exp(x));
+        }
+
+        assert!((value.unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ranks_with_ties() {
+        assert_eq!(ranks(&[10.0, 20.0, 10.0, 30.0]), vec![1.5, 3.0, 1.5, 4.0]);
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let symbols = vec![
+            "BTCUSD".to_string(),
+            "ETHUSD".to_string(),
+            "SOLUSD".to_string(),
+        ];
+        let mut matrix = CorrelationMatrix::new(symbols, 4, CorrelationMethod::Pearson);
+
+        for i in 0..4 {
+            let r = i as f64 * 0.01;
+            matrix.update(&[r, r * 3.0, -r]).unwrap();
+        }
+
+        let m = matrix.matrix().unwrap();
+        assert_eq!(m.len(), 3);
+        assert!((m[0][1] - 1.0).abs() < 1e-12);
+        assert!((m[0][2] + 1.0).abs() < 1e-12);
+        assert_eq!(m[1][0], m[0][1]);
+        assert!((matrix.correlation("ETHUSD", "SOLUSD").unwrap() + 1.0).abs() < 1e-12);
+        assert_eq!(matrix.correlation("BTCUSD", "XRPUSD"), None);
+
+        assert!(matches!(
+            matrix.update(&[0.01, 0.02]),
+            Err(MarketDataError::DimensionMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+
+        assert!(RollingCorrelation::try_new(1, CorrelationMethod::Spearman).is_err());
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        assert!(CorrelationMatrix::try_new(symbols, 1, CorrelationMethod::Pearson).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::time::Timestamp;
+
+/// Drawdown state after an update
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownState {
+    pub peak: f64,
+    /// Fractional decline from the peak, `0.0` at a new high
+    pub drawdown: f64,
+    pub max_drawdown: f64,
+    /// Milliseconds since the last peak
+    pub duration: i64,
+    /// Longest time spent below a previous peak, in milliseconds
+    pub max_duration: i64,
+    pub timestamp: Timestamp,
+}
+
+/// Running drawdown statistics of an equity curve or price series
+pub struct DrawdownTracker {
+    peak: Option<(f64, Timestamp)>,
+    max_drawdown: f64,
+    max_duration: i64,
+    /// Peak, trough and recovery time of the deepest drawdown
+    worst: Option<(Timestamp, Timestamp, Option<Timestamp>)>,
+    underwater: VecDeque<(Timestamp, f64)>,
+    underwater_capacity: usize,
+    last: Option<DrawdownState>,
+}
+
+impl DrawdownTracker {
+    pub fn new() -> Self {
+        Self {
+            peak: None,
+            max_drawdown: 0.0,
+            max_duration: 0,
+            worst: None,
+            underwater: VecDeque::new(),
+            underwater_capacity: usize::MAX,
+            last: None,
+        }
+    }
+
+    /// Keep at most `capacity` points of the underwater curve (oldest are dropped)
+    pub fn with_underwater_capacity(mut self, capacity: usize) -> Self {
+        self.underwater_capacity = capacity;
+        self
+    }
+
+    /// Push the next value; non-positive or non-finite values are ignored
+    pub fn update(&mut self, value: f64, timestamp: Timestamp) -> Option<DrawdownState> {
+        if !(value > 0.0 && value.is_finite()) {
+            return None;
+        }
+
+        let (peak, peak_time) = match self.peak {
+            Some((peak, time)) if peak >= value => (peak, time),
+            _ => {
+                if let Some((_, _, recovery @ None)) = &mut self.worst {
+                    *recovery = Some(timestamp);
+                }
+                self.peak = Some((value, timestamp));
+                (value, timestamp)
+            }
+        };
+
+        let drawdown = 1.0 - value / peak;
+        let duration = if drawdown > 0.0 {
+            timestamp.as_millis() - peak_time.as_millis()
+        } else {
+            0
+        };
+
+        if drawdown > self.max_drawdown {
+            self.max_drawdown = drawdown;
+            self.worst = Some((peak_time, timestamp, None));
+        }
+        self.max_duration = self.max_duration.max(duration);
+
+        if self.underwater_capacity > 0 {
+            if self.underwater.len() >= self.underwater_capacity {
+                self.underwater.pop_front();
+            }
+            self.underwater.push_back((timestamp, -drawdown));
+        }
+
+        let state = DrawdownState {
+            peak,
+            drawdown,
+            max_drawdown: self.max_drawdown,
+            duration,
+            max_duration: self.max_duration,
+            timestamp,
+        };
+        self.last = Some(state);
+
+        Some(state)
+    }
+
+    pub fn last(&self) -> Option<DrawdownState> {
+        self.last
+    }
+
+    pub fn max_drawdown(&self) -> f64 {
+        self.max_drawdown
+    }
+
+    /// Peak time, trough time and recovery time (if recovered) of the deepest drawdown
+    pub fn worst_period(&self) -> Option<(Timestamp, Timestamp, Option<Timestamp>)> {
+        self.worst
+    }
+
+    /// `(timestamp, -drawdown)` points, at or below zero
+    pub fn underwater_curve(&self) -> Vec<(Timestamp, f64)> {
+        self.underwater.iter().copied().collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.peak = None;
+        self.max_drawdown = 0.0;
+        self.max_duration = 0;
+        self.worst = None;
+        self.underwater.clear();
+        self.last = None;
+    }
+}
+
+impl Default for DrawdownTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_drawdown_and_recovery() {
+        let mut tracker = DrawdownTracker::new();
+        tracker.update(100.0, at(0));
+        tracker.update(120.0, at(1));
+
+        let state = tracker.update(90.0, at(2)).unwrap();
+        assert!((state.drawdown - 0.25).abs() < 1e-12);
+        assert_eq!(state.peak, 120.0);
+        assert_eq!(state.duration, 1);
+
+        tracker.update(108.0, at(5));
+        let state = tracker.update(130.0, at(8)).unwrap();
+        assert_eq!(state.drawdown, 0.0);
+        assert!((state.max_drawdown - 0.25).abs() < 1e-12);
+        assert_eq!(state.max_duration, 4);
+        assert_eq!(tracker.worst_period(), Some((at(1), at(2), Some(at(8)))));
+    }
+
+    #[test]
+    fn test_underwater_curve() {
+        let mut tracker = DrawdownTracker::new().with_underwater_capacity(2);
+        for (i, value) in [100.0, 50.0, 75.0].iter().enumerate() {
+            tracker.update(*value, at(i as i64));
+        }
+
+        assert_eq!(
+            tracker.underwater_curve(),
+            vec![(at(1), -0.5), (at(2), -0.25)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_and_reset() {
+        let mut tracker = DrawdownTracker::default();
+        assert!(tracker.update(f64::NAN, at(0)).is_none());
+        assert!(tracker.update(0.0, at(0)).is_none());
+
+        tracker.update(10.0, at(1));
+        tracker.update(5.0, at(2));
+        tracker.reset();
+        assert_eq!(tracker.max_drawdown(), 0.0);
+        assert!(tracker.last().is_none());
+        assert!(tracker.underwater_curve().is_empty());
+    }
+}
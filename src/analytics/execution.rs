@@ -0,0 +1,421 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+use crate::orderbook::Quote;
+use crate::trades::{Side, Trade};
+
+/// Parameters for [`ExecutionAnalyzer`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionConfig {
+    /// Delays after the trade at which realized spread and price impact are measured,
+    /// in milliseconds
+    pub horizons: Vec<i64>,
+    /// Width of the statistics time buckets, in milliseconds
+    pub bucket: i64,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            horizons: vec![1_000, 5_000, 60_000],
+            bucket: 60 * 60 * 1000,
+        }
+    }
+}
+
+/// Realized spread and price impact measured one horizon after the trades
+///
+/// Both are in basis points of the mid at trade time and add up to the effective
+/// spread of the same trades: the impact is the part of the spread the market moved
+/// away from the liquidity provider, the realized spread what the provider kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HorizonStats {
+    pub horizon: i64,
+    /// Trades whose horizon has elapsed
+    pub trades: u64,
+    pub avg_realized_spread_bps: f64,
+    pub avg_price_impact_bps: f64,
+}
+
+/// Execution-quality statistics for one symbol and time bucket
+///
+/// Spreads are expressed in basis points of the prevailing mid price.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionStats {
+    pub trades: u64,
+    pub volume: f64,
+    pub avg_effective_spread_bps: f64,
+    /// One entry per configured horizon, in configuration order
+    pub horizons: Vec<HorizonStats>,
+    /// Share of trades executed strictly inside the quote
+    pub price_improvement_rate: f64,
+    /// Average improvement versus the quote on the trade's side, in price units
+    pub avg_price_improvement: f64,
+}
+
+impl ExecutionStats {
+    /// Statistics of the given horizon, if it is configured
+    pub fn at_horizon(&self, horizon: i64) -> Option<&HorizonStats> {
+        self.horizons.iter().find(|h| h.horizon == horizon)
+    }
+}
+
+/// Realized spread and impact sums of one horizon
+#[derive(Debug, Clone, Copy, Default)]
+struct HorizonAccumulator {
+    trades: u64,
+    realized_bps: KahanSum,
+    impact_bps: KahanSum,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    trades: u64,
+    volume: KahanSum,
+    effective_bps: KahanSum,
+    improved: u64,
+    improvement: KahanSum,
+    horizons: Vec<HorizonAccumulator>,
+}
+
+impl Accumulator {
+    fn merge(&mut self, other: &Accumulator) {
+        self.trades += other.trades;
+        self.volume += other.volume.value();
+        self.effective_bps += other.effective_bps.value();
+        self.improved += other.improved;
+        self.improvement += other.improvement.value();
+        if self.horizons.len() < other.horizons.len() {
+            self.horizons
+                .resize(other.horizons.len(), HorizonAccumulator::default());
+        }
+        for (total, h) in self.horizons.iter_mut().zip(&other.horizons) {
+            total.trades += h.trades;
+            total.realized_bps += h.realized_bps.value();
+            total.impact_bps += h.impact_bps.value();
+        }
+    }
+
+    fn stats(&self, horizons: &[i64]) -> ExecutionStats {
+        let per_trade = |sum: f64, n: u64| if n == 0 { 0.0 } else { sum / n as f64 };
+
+        ExecutionStats {
+            trades: self.trades,
+            volume: self.volume.value(),
+            avg_effective_spread_bps: per_trade(self.effective_bps.value(), self.trades),
+            horizons: horizons
+                .iter()
+                .enumerate()
+                .map(|(i, &horizon)| {
+                    let h = self.horizons.get(i).copied().unwrap_or_default();
+                    HorizonStats {
+                        horizon,
+                        trades: h.trades,
+                        avg_realized_spread_bps: per_trade(h.realized_bps.value(), h.trades),
+                        avg_price_impact_bps: per_trade(h.impact_bps.value(), h.trades),
+                    }
+                })
+                .collect(),
+            price_improvement_rate: per_trade(self.improved as f64, self.trades),
+            avg_price_improvement: per_trade(self.improvement.value(), self.trades),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SymbolState {
+    last_quote: Option<Quote>,
+    /// Per horizon, trades waiting for it to elapse: (trade, mid at trade time)
+    pending: Vec<VecDeque<(Trade, f64)>>,
+    buckets: BTreeMap<i64, Accumulator>,
+}
+
+/// Joins trades with the prevailing quote to measure effective spread, realized
+/// spread, price impact and price improvement per symbol and time bucket
+///
+/// Quotes and trades must be fed in timestamp order per symbol, e.g. from
+/// [`OrderBook::quote`](crate::orderbook::OrderBook::quote) after each book update
+/// and from the trade feed; [`replay`](Self::replay) merges recorded histories.
+pub struct ExecutionAnalyzer {
+    config: ExecutionConfig,
+    symbols: HashMap<String, SymbolState>,
+}
+
+impl ExecutionAnalyzer {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: ExecutionConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: ExecutionConfig) -> Result<Self> {
+        ensure(config.bucket > 0, "bucket width must be positive")?;
+        ensure(
+            config.horizons.iter().all(|&h| h >= 0),
+            "horizons must not be negative",
+        )?;
+
+        Ok(Self {
+            config,
+            symbols: HashMap::new(),
+        })
+    }
+
+    pub fn on_quote(&mut self, symbol: &str, quote: &Quote) {
+        let bucket = self.config.bucket;
+        let state = self.symbols.entry(symbol.to_string()).or_default();
+
+        let now = quote.timestamp.as_millis();
+        for (index, &horizon) in self.config.horizons.iter().enumerate() {
+            let Some(pending) = state.pending.get_mut(index) else {
+                break;
+            };
+            while let Some(&(trade, mid)) = pending.front() {
+                let due = trade.timestamp.as_millis() + horizon;
+                if due > now {
+                    break;
+                }
+                pending.pop_front();
+
+                // Mid prevailing at t + horizon
+                let later = if now == due {
+                    Some(quote.mid())
+                } else {
+                    state.last_quote.map(|q| q.mid())
+                };
+                if let Some(later_mid) = later {
+                    let acc = state
+                        .buckets
+                        .entry(bucket_start(trade.timestamp.as_millis(), bucket))
+                        .or_default();
+                    if acc.horizons.len() <= index {
+                        acc.horizons
+                            .resize(index + 1, HorizonAccumulator::default());
+                    }
+                    let h = &mut acc.horizons[index];
+                    let sign = trade.side.sign();
+                    h.trades += 1;
+                    h.realized_bps += 2.0 * sign * (trade.price - later_mid) / mid * 1e4;
+                    h.impact_bps += 2.0 * sign * (later_mid - mid) / mid * 1e4;
+                }
+            }
+        }
+
+        state.last_quote = Some(*quote);
+    }
+
+    /// Record a trade against the latest quote; trades before any quote are ignored
+    pub fn on_trade(&mut self, symbol: &str, trade: &Trade) {
+        let bucket = self.config.bucket;
+        let horizons = self.config.horizons.len();
+        let state = self.symbols.entry(symbol.to_string()).or_default();
+        let quote = match state.last_quote {
+            Some(quote) => quote,
+            None => return,
+        };
+
+        let mid = quote.mid();
+        if mid <= 0.0 {
+            return;
+        }
+
+        let improvement = match trade.side {
+            Side::Buy => quote.ask_price - trade.price,
+            Side::Sell => trade.price - quote.bid_price,
+        };
+
+        let acc = state
+            .buckets
+            .entry(bucket_start(trade.timestamp.as_millis(), bucket))
+            .or_default();
+        acc.trades += 1;
+        acc.volume += trade.quantity;
+        acc.effective_bps += 2.0 * trade.side.sign() * (trade.price - mid) / mid * 1e4;
+        acc.improvement += improvement;
+        if improvement > 0.0 {
+            acc.improved += 1;
+        }
+
+        state.pending.resize_with(horizons, VecDeque::new);
+        for pending in &mut state.pending {
+            pending.push_back((*trade, mid));
+        }
+    }
+
+    /// Feed recorded quote and trade histories of a symbol, each in timestamp order
+    ///
+    /// A quote stamped at the same time as a trade is taken as the book's reaction to
+    /// it, so the trade is measured against the quote before.
+    pub fn replay(&mut self, symbol: &str, quotes: &[Quote], trades: &[Trade]) {
+        let mut quotes = quotes.iter().peekable();
+        for trade in trades {
+            while let Some(quote) = quotes.next_if(|q| q.timestamp < trade.timestamp) {
+                self.on_quote(symbol, quote);
+            }
+            self.on_trade(symbol, trade);
+        }
+        for quote in quotes {
+            self.on_quote(symbol, quote);
+        }
+    }
+
+    /// Per-bucket statistics for a symbol, ordered by bucket start time
+    pub fn stats(&self, symbol: &str) -> Vec<(i64, ExecutionStats)> {
+        self.symbols
+            .get(symbol)
+            .map(|state| {
+                state
+                    .buckets
+                    .iter()
+                    .map(|(&t, acc)| (t, acc.stats(&self.config.horizons)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Statistics for a symbol aggregated over all buckets
+    pub fn summary(&self, symbol: &str) -> Option<ExecutionStats> {
+        let state = self.symbols.get(symbol)?;
+        let total = state
+            .buckets
+            .values()
+            .fold(Accumulator::default(), |mut total, acc| {
+                total.merge(acc);
+                total
+            });
+
+        Some(total.stats(&self.config.horizons))
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.symbols.keys().map(|s| s.as_str())
+    }
+
+    pub fn reset(&mut self) {
+        self.symbols.clear();
+    }
+}
+
+fn bucket_start(timestamp: i64, bucket: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(bucket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn quote(bid: f64, ask: f64, timestamp: i64) -> Quote {
+        Quote {
+            bid_price: bid,
+            bid_size: 1.0,
+            ask_price: ask,
+            ask_size: 1.0,
+            timestamp: at(timestamp),
+        }
+    }
+
+    fn analyzer() -> ExecutionAnalyzer {
+        ExecutionAnalyzer::new(ExecutionConfig {
+            horizons: vec![1_000, 3_000],
+            bucket: 10_000,
+        })
+    }
+
+    #[test]
+    fn test_effective_spread() {
+        let mut ea = analyzer();
+        ea.on_quote("BTCUSD", &quote(99.0, 101.0, 0));
+        ea.on_trade("BTCUSD", &Trade::new(101.0, 2.0, Side::Buy, at(10)));
+        ea.on_trade("BTCUSD", &Trade::new(99.0, 1.0, Side::Sell, at(20)));
+
+        let stats = ea.summary("BTCUSD").unwrap();
+        assert_eq!(stats.trades, 2);
+        assert_eq!(stats.volume, 3.0);
+        // Both trades paid the full quoted spread of 2 on a mid of 100
+        assert!((stats.avg_effective_spread_bps - 200.0).abs() < 1e-9);
+        assert_eq!(stats.price_improvement_rate, 0.0);
+    }
+
+    #[test]
+    fn test_realized_spread_uses_mid_after_horizon() {
+        let mut ea = analyzer();
+        ea.on_quote("BTCUSD", &quote(99.0, 101.0, 0));
+        ea.on_trade("BTCUSD", &Trade::new(101.0, 1.0, Side::Buy, at(100)));
+
+        // Market moves up after the buy; the quote prevailing at t+1000 is the one at 500
+        ea.on_quote("BTCUSD", &quote(100.0, 102.0, 500));
+        let stats = ea.summary("BTCUSD").unwrap();
+        assert_eq!(stats.at_horizon(1_000).unwrap().trades, 0);
+        ea.on_quote("BTCUSD", &quote(105.0, 107.0, 2_000));
+
+        let stats = ea.summary("BTCUSD").unwrap();
+        let short = stats.at_horizon(1_000).unwrap();
+        assert_eq!(short.trades, 1);
+        // 2 * (101 - 101) / 100: the whole effective spread was impact
+        assert!(short.avg_realized_spread_bps.abs() < 1e-9);
+        assert!((short.avg_price_impact_bps - 200.0).abs() < 1e-9);
+        assert_eq!(stats.at_horizon(3_000).unwrap().trades, 0);
+    }
+
+    #[test]
+    fn test_realized_spread_and_impact_add_up_per_horizon() {
+        let mut ea = analyzer();
+        let quotes = [
+            quote(99.0, 101.0, 0),
+            // The sell at 100 pushes the book down, then it recovers
+            quote(98.5, 100.5, 100),
+            quote(99.5, 101.5, 2_000),
+            quote(99.0, 101.0, 5_000),
+        ];
+        let trades = [Trade::new(99.0, 1.0, Side::Sell, at(100))];
+        ea.replay("BTCUSD", &quotes, &trades);
+
+        let stats = ea.summary("BTCUSD").unwrap();
+        // Measured against the quote before the trade: mid 100
+        assert!((stats.avg_effective_spread_bps - 200.0).abs() < 1e-9);
+        for (horizon, impact) in [(1_000, 100.0), (3_000, -100.0)] {
+            let h = stats.at_horizon(horizon).unwrap();
+            assert_eq!(h.trades, 1);
+            assert!((h.avg_price_impact_bps - impact).abs() < 1e-9);
+            assert!((h.avg_realized_spread_bps + h.avg_price_impact_bps - 200.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_price_improvement() {
+        let mut ea = analyzer();
+        ea.on_quote("ETHUSD", &quote(99.0, 101.0, 0));
+        ea.on_trade("ETHUSD", &Trade::new(100.5, 1.0, Side::Buy, at(1)));
+        ea.on_trade("ETHUSD", &Trade::new(99.0, 1.0, Side::Sell, at(2)));
+
+        let stats = ea.summary("ETHUSD").unwrap();
+        assert_eq!(stats.price_improvement_rate, 0.5);
+        assert!((stats.avg_price_improvement - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_buckets_and_symbols() {
+        let mut ea = analyzer();
+        ea.on_trade("BTCUSD", &Trade::new(100.0, 1.0, Side::Buy, at(0)));
+        assert!(ea.stats("BTCUSD").is_empty());
+
+        ea.on_quote("BTCUSD", &quote(99.0, 101.0, 0));
+        ea.on_trade("BTCUSD", &Trade::new(101.0, 1.0, Side::Buy, at(5_000)));
+        ea.on_trade("BTCUSD", &Trade::new(101.0, 1.0, Side::Buy, at(15_000)));
+
+        let buckets: Vec<i64> = ea.stats("BTCUSD").iter().map(|(t, _)| *t).collect();
+        assert_eq!(buckets, vec![0, 10_000]);
+        assert!(ea.stats("ETHUSD").is_empty());
+        assert_eq!(ea.symbols().count(), 1);
+
+        let config = |bucket, horizons| ExecutionConfig { horizons, bucket };
+        assert!(ExecutionAnalyzer::try_new(config(0, vec![1_000])).is_err());
+        assert!(ExecutionAnalyzer::try_new(config(1_000, vec![-1])).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::orderbook::OrderBook;
+use crate::time::Timestamp;
+
+/// Sampling and bucketing parameters for [`LiquidityHeatmap`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapConfig {
+    /// Minimum time between two snapshots, in milliseconds
+    pub interval: i64,
+    /// Price bucket width; resting size inside a bucket is summed
+    pub price_bucket: f64,
+    /// Number of book levels captured per side
+    pub depth: usize,
+    /// Maximum number of columns kept; the oldest are dropped first
+    pub max_columns: usize,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            interval: 1_000,
+            price_bucket: 1.0,
+            depth: 50,
+            max_columns: 3_600,
+        }
+    }
+}
+
+/// Resting liquidity at one point in time, keyed by bucket lower bound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapColumn {
+    pub timestamp: Timestamp,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// Dense price x time grid suitable for plotting libraries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapGrid {
+    pub timestamps: Vec<Timestamp>,
+    /// Bucket lower bounds, ascending
+    pub prices: Vec<f64>,
+    /// `bids[t][p]` is the bid size at `timestamps[t]` and `prices[p]`
+    pub bids: Vec<Vec<f64>>,
+    pub asks: Vec<Vec<f64>>,
+}
+
+/// Time series of depth snapshots forming a resting-liquidity heatmap
+pub struct LiquidityHeatmap {
+    config: HeatmapConfig,
+    columns: VecDeque<HeatmapColumn>,
+    last_sample: Option<Timestamp>,
+}
+
+impl LiquidityHeatmap {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: HeatmapConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: HeatmapConfig) -> Result<Self> {
+        ensure(config.interval >= 0, "interval must not be negative")?;
+        ensure(config.price_bucket > 0.0, "price bucket must be positive")?;
+        ensure(config.depth > 0, "depth must be positive")?;
+        ensure(
+            config.max_columns > 0,
+            "heatmap must keep at least one column",
+        )?;
+
+        Ok(Self {
+            config,
+            columns: VecDeque::new(),
+            last_sample: None,
+        })
+    }
+
+    /// Snapshot the book if at least one interval has passed since the last column
+    ///
+    /// Returns whether a column was captured.
+    pub fn sample(&mut self, book: &OrderBook, timestamp: Timestamp) -> bool {
+        if let Some(last) = self.last_sample {
+            if timestamp.as_millis() - last.as_millis() < self.config.interval {
+                return false;
+            }
+        }
+
+        let bids = self.bucket(
+            book.top_bids(self.config.depth)
+                .iter()
+                .map(|l| (l.price, l.quantity)),
+        );
+        let asks = self.bucket(
+            book.top_asks(self.config.depth)
+                .iter()
+                .map(|l| (l.price, l.quantity)),
+        );
+
+        if self.columns.len() == self.config.max_columns {
+            self.columns.pop_front();
+        }
+        self.columns.push_back(HeatmapColumn {
+            timestamp,
+            bids,
+            asks,
+        });
+        self.last_sample = Some(timestamp);
+
+        true
+    }
+
+    fn bucket(&self, levels: impl Iterator<Item = (f64, f64)>) -> Vec<(f64, f64)> {
+        let mut buckets: BTreeMap<i64, f64> = BTreeMap::new();
+        for (price, quantity) in levels {
+            let key = (price / self.config.price_bucket).floor() as i64;
+            *buckets.entry(key).or_insert(0.0) += quantity;
+        }
+
+        buckets
+            .into_iter()
+            .map(|(k, q)| (k as f64 * self.config.price_bucket, q))
+            .collect()
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &HeatmapColumn> {
+        self.columns.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Dense grid over every price bucket seen in the captured columns
+    pub fn grid(&self) -> HeatmapGrid {
+        let keys: BTreeSet<i64> = self
+            .columns
+            .iter()
+            .flat_map(|c| c.bids.iter().chain(c.asks.iter()))
+            .map(|(price, _)| (price / self.config.price_bucket).round() as i64)
+            .collect();
+        let index: BTreeMap<i64, usize> = keys.iter().enumerate().map(|(i, &k)| (k, i)).collect();
+
+        let fill = |levels: &[(f64, f64)]| {
+            let mut row = vec![0.0; index.len()];
+            for (price, quantity) in levels {
+                let key = (price / self.config.price_bucket).round() as i64;
+                row[index[&key]] = *quantity;
+            }
+            row
+        };
+
+        HeatmapGrid {
+            timestamps: self.columns.iter().map(|c| c.timestamp).collect(),
+            prices: keys
+                .iter()
+                .map(|&k| k as f64 * self.config.price_bucket)
+                .collect(),
+            bids: self.columns.iter().map(|c| fill(&c.bids)).collect(),
+            asks: self.columns.iter().map(|c| fill(&c.asks)).collect(),
+        }
+    }
+
+    /// Long-format CSV (`timestamp,side,price,quantity`), one row per non-empty cell
+    ///
+    /// Timestamps are written as epoch milliseconds.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("timestamp,side,price,quantity\n");
+        for column in &self.columns {
+            for (side, levels) in [("bid", &column.bids), ("ask", &column.asks)] {
+                for (price, quantity) in levels {
+                    out.push_str(&format!(
+                        "{},{},{},{}\n",
+                        column.timestamp.as_millis(),
+                        side,
+                        price,
+                        quantity
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Dense grid as JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.grid())
+    }
+
+    pub fn reset(&mut self) {
+        self.columns.clear();
+        self.last_sample = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn book() -> OrderBook {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(99.5, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_bid(99.2, 2.0, Timestamp::EPOCH).unwrap();
+        ob.update_bid(98.0, 4.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(100.5, 3.0, Timestamp::EPOCH).unwrap();
+        ob
+    }
+
+    #[test]
+    fn test_sampling_respects_interval() {
+        let mut heatmap = LiquidityHeatmap::new(HeatmapConfig::default());
+        let ob = book();
+
+        assert!(heatmap.sample(&ob, at(0)));
+        assert!(!heatmap.sample(&ob, at(500)));
+        assert!(heatmap.sample(&ob, at(1_000)));
+        assert_eq!(heatmap.len(), 2);
+    }
+
+    #[test]
+    fn test_levels_are_bucketed() {
+        let mut heatmap = LiquidityHeatmap::new(HeatmapConfig::default());
+        heatmap.sample(&book(), at(0));
+
+        let column = heatmap.columns().next().unwrap();
+        assert_eq!(column.bids, vec![(98.0, 4.0), (99.0, 3.0)]);
+        assert_eq!(column.asks, vec![(100.0, 3.0)]);
+    }
+
+    #[test]
+    fn test_grid_and_exports() {
+        let mut heatmap = LiquidityHeatmap::new(HeatmapConfig::default());
+        let mut ob = book();
+        heatmap.sample(&ob, at(0));
+        ob.update_ask(101.0, 5.0, Timestamp::EPOCH).unwrap();
+        heatmap.sample(&ob, at(1_000));
+
+        let grid = heatmap.grid();
+        assert_eq!(grid.timestamps, vec![at(0), at(1_000)]);
+        assert_eq!(grid.prices, vec![98.0, 99.0, 100.0, 101.0]);
+        assert_eq!(grid.asks[0], vec![0.0, 0.0, 3.0, 0.0]);
+        assert_eq!(grid.asks[1], vec![0.0, 0.0, 3.0, 5.0]);
+
+        let csv = heatmap.to_csv();
+        assert!(csv.starts_with("timestamp,side,price,quantity\n"));
+        assert!(csv.contains("1000,ask,101,5\n"));
+
+        let parsed: HeatmapGrid = serde_json::from_str(&heatmap.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, grid);
+    }
+
+    #[test]
+    fn test_column_capacity() {
+        let config = HeatmapConfig {
+            max_columns: 2,
+            ..HeatmapConfig::default()
+        };
+        let mut heatmap = LiquidityHeatmap::new(config);
+        let ob = book();
+        for t in 0..5 {
+            heatmap.sample(&ob, at(t * 1_000));
+        }
+
+        assert_eq!(heatmap.len(), 2);
+        assert_eq!(heatmap.columns().next().unwrap().timestamp, at(3_000));
+
+        let config = HeatmapConfig {
+            price_bucket: 0.0,
+            ..HeatmapConfig::default()
+        };
+        assert!(LiquidityHeatmap::try_new(config).is_err());
+        for config in [
+            HeatmapConfig {
+                depth: 0,
+                ..HeatmapConfig::default()
+            },
+            HeatmapConfig {
+                max_columns: 0,
+                ..HeatmapConfig::default()
+            },
+        ] {
+            assert!(LiquidityHeatmap::try_new(config).is_err());
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::numeric::KahanSum;
+use crate::orderbook::Quote;
+use crate::trades::Trade;
+
+/// Functional form of the impact curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImpactModelKind {
+    /// impact = coefficient * q
+    Linear,
+    /// impact = coefficient * sign(q) * sqrt(|q|)
+    SquareRoot,
+}
+
+impl ImpactModelKind {
+    fn regressor(&self, signed_size: f64) -> f64 {
+        match self {
+            ImpactModelKind::Linear => signed_size,
+            ImpactModelKind::SquareRoot => signed_size.signum() * signed_size.abs().sqrt(),
+        }
+    }
+}
+
+/// One trade with the mid move that followed it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpactObservation {
+    /// Trade size, positive for buys and negative for sells
+    pub signed_size: f64,
+    pub mid_before: f64,
+    pub mid_after: f64,
+}
+
+impl ImpactObservation {
+    /// Mid move in basis points of the pre-trade mid
+    pub fn mid_move_bps(&self) -> f64 {
+        (self.mid_after - self.mid_before) / self.mid_before * 1e4
+    }
+}
+
+/// Fitted impact curve, in basis points
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpactFit {
+    pub kind: ImpactModelKind,
+    pub coefficient: f64,
+    /// Uncentered R² of the regression through the origin
+    pub r_squared: f64,
+    pub samples: u64,
+}
+
+impl ImpactFit {
+    /// Expected mid move in basis points for a signed trade size
+    pub fn impact_bps(&self, signed_size: f64) -> f64 {
+        self.coefficient * self.kind.regressor(signed_size)
+    }
+}
+
+/// Running sums for a least-squares fit through the origin
+#[derive(Debug, Clone, Copy, Default)]
+struct Regression {
+    sxx: KahanSum,
+    sxy: KahanSum,
+    syy: KahanSum,
+    n: u64,
+}
+
+impl Regression {
+    fn add(&mut self, x: f64, y: f64) {
+        self.sxx += x * x;
+        self.sxy += x * y;
+        self.syy += y * y;
+        self.n += 1;
+    }
+
+    fn fit(&self, kind: ImpactModelKind) -> Option<ImpactFit> {
+        let (sxx, sxy, syy) = (self.sxx.value(), self.sxy.value(), self.syy.value());
+        if self.n < 2 || sxx == 0.0 {
+            return None;
+        }
+
+        let r_squared = if syy == 0.0 { 0.0 } else { sxy * sxy / (sxx * syy) };
+
+        Some(ImpactFit {
+            kind,
+            coefficient: sxy / sxx,
+            r_squared,
+            samples: self.n,
+        })
+    }
+}
+
+/// Fit an impact curve over a batch of observations
+pub fn fit_impact(observations: &[ImpactObservation], kind: ImpactModelKind) -> Option<ImpactFit> {
+    let mut regression = Regression::default();
+    for obs in observations.iter().filter(|o| o.mid_before > 0.0) {
+        regression.add(kind.regressor(obs.signed_size), obs.mid_move_bps());
+    }
+    regression.fit(kind)
+}
+
+#[derive(Default)]
+struct SymbolImpact {
+    last_quote: Option<Quote>,
+    pending: VecDeque<(Trade, f64)>,
+    linear: Regression,
+    square_root: Regression,
+}
+
+impl SymbolImpact {
+    fn add(&mut self, obs: &ImpactObservation) {
+        let y = obs.mid_move_bps();
+        self.linear
+            .add(ImpactModelKind::Linear.regressor(obs.signed_size), y);
+        self.square_root
+            .add(ImpactModelKind::SquareRoot.regressor(obs.signed_size), y);
+    }
+}
+
+/// Streaming estimator of per-symbol impact curves from trades and quotes
+///
+/// Each trade is paired with the mid just before it and the mid `horizon`
+/// milliseconds later. Inputs must be in timestamp order per symbol.
+pub struct ImpactAnalyzer {
+    horizon: i64,
+    symbols: HashMap<String, SymbolImpact>,
+}
+
+impl ImpactAnalyzer {
+    pub fn new(horizon: i64) -> Self {
+        Self {
+            horizon,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn on_quote(&mut self, symbol: &str, quote: &Quote) {
+        let horizon = self.horizon;
+        let state = self.symbols.entry(symbol.to_string()).or_default();
+
+        let now = quote.timestamp.as_millis();
+        while let Some(&(trade, mid_before)) = state.pending.front() {
+            let due = trade.timestamp.as_millis() + horizon;
+            if due > now {
+                break;
+            }
+            state.pending.pop_front();
+
+            let mid_after = if now == due {
+                Some(quote.mid())
+            } else {
+                state.last_quote.map(|q| q.mid())
+            };
+            if let Some(mid_after) = mid_after {
+                state.add(&ImpactObservation {
+                    signed_size: trade.signed_quantity(),
+                    mid_before,
+                    mid_after,
+                });
+            }
+        }
+
+        state.last_quote = Some(*quote);
+    }
+
+    pub fn on_trade(&mut self, symbol: &str, trade: &Trade) {
+        let state = self.symbols.entry(symbol.to_string()).or_default();
+        if let Some(quote) = state.last_quote {
+            if quote.mid() > 0.0 {
+                state.pending.push_back((*trade, quote.mid()));
+            }
+        }
+    }
+
+    /// Add an already aligned observation
+    pub fn add_observation(&mut self, symbol: &str, observation: &ImpactObservation) {
+        if observation.mid_before > 0.0 {
+            self.symbols
+                .entry(symbol.to_string())
+                .or_default()
+                .add(observation);
+        }
+    }
+
+    pub fn fit(&self, symbol: &str, kind: ImpactModelKind) -> Option<ImpactFit> {
+        let state = self.symbols.get(symbol)?;
+        match kind {
+            ImpactModelKind::Linear => state.linear.fit(kind),
+            ImpactModelKind::SquareRoot => state.square_root.fit(kind),
+        }
+    }
+
+    /// Whichever model explains more of the observed mid moves
+    pub fn best_fit(&self, symbol: &str) -> Option<ImpactFit> {
+        match (
+            self.fit(symbol, ImpactModelKind::Linear),
+            self.fit(symbol, ImpactModelKind::SquareRoot),
+        ) {
+            (Some(a), Some(b)) => Some(if b.r_squared >= a.r_squared { b } else { a }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.symbols.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+    use crate::trades::Side;
+
+    fn obs(size: f64, move_bps: f64) -> ImpactObservation {
+        ImpactObservation {
+            signed_size: size,
+            mid_before: 100.0,
+            mid_after: 100.0 * (1.0 + move_bps / 1e4),
+        }
+    }
+
+    #[test]
+    fn test_linear_fit() {
+        let data: Vec<_> = [1.0, -2.0, 3.0, -4.0]
+            .iter()
+            .map(|&q| obs(q, 0.5 * q))
+            .collect();
+        let fit = fit_impact(&data, ImpactModelKind::Linear).unwrap();
+
+        assert!((fit.coefficient - 0.5).abs() < 1e-9);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+        assert!((fit.impact_bps(-10.0) + 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_square_root_preferred_for_concave_impact() {
+        let mut analyzer = ImpactAnalyzer::new(1_000);
+        for &q in &[1.0_f64, 4.0, 9.0, 16.0, 25.0, -4.0, -16.0] {
+            let move_bps = 2.0 * q.signum() * f64::sqrt(q.abs());
+            analyzer.add_observation("BTCUSD", &obs(q, move_bps));
+        }
+
+        let best = analyzer.best_fit("BTCUSD").unwrap();
+        assert_eq!(best.kind, ImpactModelKind::SquareRoot);
+        assert!((best.coefficient - 2.0).abs() < 1e-9);
+        assert_eq!(best.samples, 7);
+    }
+
+    #[test]
+    fn test_streaming_alignment() {
+        let mut analyzer = ImpactAnalyzer::new(1_000);
+        let quote = |mid: f64, timestamp: i64| Quote {
+            bid_price: mid - 0.5,
+            bid_size: 1.0,
+            ask_price: mid + 0.5,
+            ask_size: 1.0,
+            timestamp: Timestamp::from_millis(timestamp),
+        };
+
+        let mut mid = 100.0;
+        for i in 0..10 {
+            let t = i * 2_000;
+            analyzer.on_quote("BTCUSD", &quote(mid, t));
+            let side = if i % 2 == 0 { Side::Buy } else { Side::Sell };
+            analyzer.on_trade(
+                "BTCUSD",
+                &Trade::new(mid, 2.0, side, Timestamp::from_millis(t + 1)),
+            );
+            // Each trade pushes the mid 1bp per unit in its direction
+            mid *= 1.0 + side.sign() * 2.0 / 1e4;
+            analyzer.on_quote("BTCUSD", &quote(mid, t + 500));
+        }
+        analyzer.on_quote("BTCUSD", &quote(mid, 20_000));
+
+        let fit = analyzer.fit("BTCUSD", ImpactModelKind::Linear).unwrap();
+        assert_eq!(fit.samples, 10);
+        assert!((fit.coefficient - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_insufficient_data() {
+        assert!(fit_impact(&[obs(1.0, 1.0)], ImpactModelKind::Linear).is_none());
+        assert!(ImpactAnalyzer::new(1).best_fit("BTCUSD").is_none());
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::message_rate::MessageKind;
+use crate::error::{ensure, Result};
+use crate::time::Timestamp;
+use crate::trades::Side;
+
+/// Exponentially decayed event rate, the streaming analogue of a Poisson rate estimate
+///
+/// Each event adds `1 / tau` to the intensity, which then decays with time constant
+/// `tau`, so a constant arrival rate `r` converges to an intensity of `r`.
+#[derive(Debug, Clone)]
+pub struct PoissonIntensity {
+    tau_ms: f64,
+    intensity: f64,
+    last: Option<i64>,
+}
+
+impl PoissonIntensity {
+    /// `tau_ms` is the decay time constant in milliseconds
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(tau_ms: f64) -> Self {
+        Self::try_new(tau_ms).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(tau_ms: f64) -> Result<Self> {
+        ensure(tau_ms > 0.0, "decay constant must be positive")?;
+
+        Ok(Self {
+            tau_ms,
+            intensity: 0.0,
+            last: None,
+        })
+    }
+
+    /// Record an event and return the updated intensity in events per second
+    pub fn on_event(&mut self, timestamp: i64) -> f64 {
+        self.decay_to(timestamp);
+        self.intensity += 1000.0 / self.tau_ms;
+        self.intensity
+    }
+
+    /// Intensity in events per second at `timestamp`, without recording an event
+    pub fn intensity_at(&self, timestamp: i64) -> f64 {
+        match self.last {
+            Some(last) if timestamp > last => {
+                self.intensity * (-((timestamp - last) as f64) / self.tau_ms).exp()
+            }
+            _ => self.intensity,
+        }
+    }
+
+    fn decay_to(&mut self, timestamp: i64) {
+        self.intensity = self.intensity_at(timestamp);
+        self.last = Some(self.last.map_or(timestamp, |last| last.max(timestamp)));
+    }
+
+    pub fn reset(&mut self) {
+        self.intensity = 0.0;
+        self.last = None;
+    }
+}
+
+/// Self-exciting (Hawkes) intensity with an exponential kernel
+///
+/// `λ(t) = μ + Σ α·exp(-β (t - tᵢ))`, updated recursively in O(1) per event.
+/// Rates are in events per second and `beta` in 1/second.
+#[derive(Debug, Clone)]
+pub struct HawkesIntensity {
+    mu: f64,
+    alpha: f64,
+    beta: f64,
+    excitation: f64,
+    last: Option<i64>,
+}
+
+impl HawkesIntensity {
+    /// The process is stationary only when `alpha < beta` (branching ratio below one)
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(mu: f64, alpha: f64, beta: f64) -> Self {
+        Self::try_new(mu, alpha, beta).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(mu: f64, alpha: f64, beta: f64) -> Result<Self> {
+        ensure(mu >= 0.0 && alpha >= 0.0, "rates must be non-negative")?;
+        ensure(beta > 0.0, "decay must be positive")?;
+
+        Ok(Self {
+            mu,
+            alpha,
+            beta,
+            excitation: 0.0,
+            last: None,
+        })
+    }
+
+    /// Record an event and return the intensity just after it
+    pub fn on_event(&mut self, timestamp: i64) -> f64 {
+        self.excitation = self.excitation_at(timestamp) + self.alpha;
+        self.last = Some(self.last.map_or(timestamp, |last| last.max(timestamp)));
+        self.mu + self.excitation
+    }
+
+    pub fn intensity_at(&self, timestamp: i64) -> f64 {
+        self.mu + self.excitation_at(timestamp)
+    }
+
+    fn excitation_at(&self, timestamp: i64) -> f64 {
+        match self.last {
+            Some(last) if timestamp > last => {
+                let elapsed = (timestamp - last) as f64 / 1000.0;
+                self.excitation * (-self.beta * elapsed).exp()
+            }
+            _ => self.excitation,
+        }
+    }
+
+    /// Expected number of events over the next `horizon_ms`, ignoring events
+    /// triggered inside the horizon itself
+    pub fn expected_events(&self, timestamp: i64, horizon_ms: i64) -> f64 {
+        let horizon = horizon_ms as f64 / 1000.0;
+        self.mu * horizon
+            + self.excitation_at(timestamp) / self.beta * (1.0 - (-self.beta * horizon).exp())
+    }
+
+    /// Share of events triggered by earlier events, `alpha / beta`
+    pub fn branching_ratio(&self) -> f64 {
+        self.alpha / self.beta
+    }
+
+    pub fn reset(&mut self) {
+        self.excitation = 0.0;
+        self.last = None;
+    }
+}
+
+/// Parameters for [`ArrivalIntensity`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IntensityConfig {
+    /// Decay constant of the fast (current) rate, in milliseconds
+    pub fast_tau_ms: f64,
+    /// Decay constant of the slow (baseline) rate, in milliseconds
+    pub slow_tau_ms: f64,
+    /// Fast rate must exceed this multiple of the baseline to flag a burst
+    pub burst_ratio: f64,
+    /// Baseline below which bursts are not reported, in events per second
+    pub min_baseline: f64,
+}
+
+impl Default for IntensityConfig {
+    fn default() -> Self {
+        Self {
+            fast_tau_ms: 1_000.0,
+            slow_tau_ms: 60_000.0,
+            burst_ratio: 3.0,
+            min_baseline: 0.1,
+        }
+    }
+}
+
+/// Streaming intensity of one event stream after an update
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IntensityEstimate {
+    pub kind: MessageKind,
+    pub side: Side,
+    /// Current rate in events per second
+    pub intensity: f64,
+    /// Long-run rate in events per second
+    pub baseline: f64,
+    pub burst: bool,
+    pub timestamp: Timestamp,
+}
+
+impl IntensityEstimate {
+    /// Events expected over the next `horizon_ms` if the current rate persists
+    pub fn expected_events(&self, horizon_ms: i64) -> f64 {
+        self.intensity * horizon_ms as f64 / 1000.0
+    }
+}
+
+struct Stream {
+    first: i64,
+    fast: PoissonIntensity,
+    slow: PoissonIntensity,
+}
+
+/// Arrival rates of trades and book events per side with burst detection
+///
+/// Compares a fast-decaying rate against a slow baseline; a burst is flagged while
+/// the fast rate is a multiple of the baseline. Bursts are only reported once a
+/// stream has been observed for at least `slow_tau_ms`.
+pub struct ArrivalIntensity {
+    config: IntensityConfig,
+    streams: HashMap<(MessageKind, Side), Stream>,
+}
+
+impl ArrivalIntensity {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: IntensityConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: IntensityConfig) -> Result<Self> {
+        PoissonIntensity::try_new(config.fast_tau_ms)?;
+        ensure(
+            config.fast_tau_ms < config.slow_tau_ms,
+            "fast decay must be shorter than slow decay",
+        )?;
+        ensure(config.burst_ratio > 0.0, "burst ratio must be positive")?;
+        ensure(
+            config.min_baseline >= 0.0,
+            "minimum baseline must not be negative",
+        )?;
+
+        Ok(Self {
+            config,
+            streams: HashMap::new(),
+        })
+    }
+
+    pub fn on_trade(&mut self, side: Side, timestamp: Timestamp) -> IntensityEstimate {
+        self.on_event(MessageKind::Trade, side, timestamp)
+    }
+
+    /// Book event on the bid (`Side::Buy`) or ask (`Side::Sell`)
+    pub fn on_book_event(&mut self, side: Side, timestamp: Timestamp) -> IntensityEstimate {
+        self.on_event(MessageKind::Quote, side, timestamp)
+    }
+
+    pub fn on_event(
+        &mut self,
+        kind: MessageKind,
+        side: Side,
+        timestamp: Timestamp,
+    ) -> IntensityEstimate {
+        let config = self.config;
+        let millis = timestamp.as_millis();
+        let stream = self.streams.entry((kind, side)).or_insert_with(|| Stream {
+            first: millis,
+            fast: PoissonIntensity::new(config.fast_tau_ms),
+            slow: PoissonIntensity::new(config.slow_tau_ms),
+        });
+
+        stream.fast.on_event(millis);
+        stream.slow.on_event(millis);
+        self.estimate_stream(kind, side, timestamp)
+            .expect("stream was just inserted")
+    }
+
+    /// Current estimate for one stream, decayed to `timestamp`
+    pub fn estimate(
+        &self,
+        kind: MessageKind,
+        side: Side,
+        timestamp: Timestamp,
+    ) -> Option<IntensityEstimate> {
+        self.estimate_stream(kind, side, timestamp)
+    }
+
+    fn estimate_stream(
+        &self,
+        kind: MessageKind,
+        side: Side,
+        timestamp: Timestamp,
+    ) -> Option<IntensityEstimate> {
+        let stream = self.streams.get(&(kind, side))?;
+        let millis = timestamp.as_millis();
+        let intensity = stream.fast.intensity_at(millis);
+        let baseline = stream.slow.intensity_at(millis);
+        // The baseline underestimates the rate until it has seen one decay constant of history
+        let warmed_up = (millis - stream.first) as f64 >= self.config.slow_tau_ms;
+
+        Some(IntensityEstimate {
+            kind,
+            side,
+            intensity,
+            baseline,
+            burst: warmed_up
+                && baseline >= self.config.min_baseline
+                && intensity > self.config.burst_ratio * baseline,
+            timestamp,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_poisson_converges_to_rate() {
+        let mut poisson = PoissonIntensity::new(5_000.0);
+
+        // 10 events per second for a minute
+        let mut rate = 0.0;
+        for i in 0..600 {
+            rate = poisson.on_event(i * 100);
+        }
+
+        assert!((rate - 10.0).abs() < 0.6);
+        assert!(poisson.intensity_at(600 * 100 + 5_000) < rate * 0.4);
+    }
+
+    #[test]
+    fn test_hawkes_excitation_decays() {
+        let mut hawkes = HawkesIntensity::new(1.0, 0.5, 2.0);
+        assert_eq!(hawkes.intensity_at(0), 1.0);
+
+        assert_eq!(hawkes.on_event(0), 1.5);
+        assert_eq!(hawkes.on_event(0), 2.0);
+        // After one second the excitation of 1.0 has decayed by exp(-2)
+        assert!((hawkes.intensity_at(1_000) - (1.0 + (-2.0_f64).exp())).abs() < 1e-12);
+        assert_eq!(hawkes.branching_ratio(), 0.25);
+
+        // Long horizon: baseline plus the whole remaining excitation / beta
+        let expected = hawkes.expected_events(0, 100_000);
+        assert!((expected - (100.0 + 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_burst_detection_per_side() {
+        let mut arrivals = ArrivalIntensity::new(IntensityConfig::default());
+
+        // Steady 1 trade per second on the buy side
+        for i in 0..120 {
+            assert!(!arrivals.on_trade(Side::Buy, at(i * 1_000)).burst);
+        }
+
+        // Then 50 trades within half a second
+        let mut estimate = None;
+        for i in 0..50 {
+            estimate = Some(arrivals.on_trade(Side::Buy, at(120_000 + i * 10)));
+        }
+        let estimate = estimate.unwrap();
+        assert!(estimate.burst);
+        assert!(estimate.expected_events(1_000) > 10.0);
+
+        // Other streams are untouched
+        assert!(arrivals
+            .estimate(MessageKind::Trade, Side::Sell, at(120_500))
+            .is_none());
+        assert!(arrivals.on_book_event(Side::Sell, at(120_500)).intensity > 0.0);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut arrivals = ArrivalIntensity::new(IntensityConfig::default());
+        arrivals.on_trade(Side::Sell, at(0));
+        arrivals.reset();
+        assert!(arrivals
+            .estimate(MessageKind::Trade, Side::Sell, at(0))
+            .is_none());
+
+        assert!(PoissonIntensity::try_new(0.0).is_err());
+        assert!(HawkesIntensity::try_new(1.0, -0.5, 2.0).is_err());
+        assert!(HawkesIntensity::try_new(1.0, 0.5, 0.0).is_err());
+        let config = IntensityConfig {
+            fast_tau_ms: 60_000.0,
+            ..IntensityConfig::default()
+        };
+        assert!(ArrivalIntensity::try_new(config).is_err());
+        // A zero fast decay would only fail on the first event
+        let config = IntensityConfig {
+            fast_tau_ms: 0.0,
+            ..IntensityConfig::default()
+        };
+        assert!(ArrivalIntensity::try_new(config).is_err());
+    }
+}
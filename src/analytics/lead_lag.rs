@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use super::correlation::{correlation, CorrelationMethod};
+use crate::error::{ensure, Result};
+
+/// Parameters for [`LeadLagAnalyzer`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LeadLagConfig {
+    /// Grid spacing used to align venues, in milliseconds
+    pub sample_interval: i64,
+    /// Largest lag examined in each direction, in grid steps
+    pub max_lag: usize,
+    /// History kept per venue, in milliseconds
+    pub history: i64,
+}
+
+impl Default for LeadLagConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval: 10,
+            max_lag: 50,
+            history: 60_000,
+        }
+    }
+}
+
+/// Outcome of a lead-lag analysis between two venues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeadLagResult {
+    pub leader: String,
+    pub follower: String,
+    /// How far the leader's moves precede the follower's, in milliseconds
+    pub lag_ms: i64,
+    /// Return correlation at the best lag
+    pub correlation: f64,
+    /// Correlation of `a(t)` with `b(t + lag)` for every lag, in milliseconds;
+    /// positive lags mean the first venue moves first
+    pub curve: Vec<(i64, f64)>,
+}
+
+/// Lagged cross-correlation of mid-price returns for one instrument across venues
+pub struct LeadLagAnalyzer {
+    config: LeadLagConfig,
+    venues: BTreeMap<String, VecDeque<(i64, f64)>>,
+}
+
+impl LeadLagAnalyzer {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: LeadLagConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: LeadLagConfig) -> Result<Self> {
+        ensure(
+            config.sample_interval > 0,
+            "sample interval must be positive",
+        )?;
+        ensure(
+            config.history >= config.sample_interval,
+            "history must cover at least one sample interval",
+        )?;
+
+        Ok(Self {
+            config,
+            venues: BTreeMap::new(),
+        })
+    }
+
+    /// Record a venue's mid price; updates must be in time order per venue
+    pub fn on_mid(&mut self, venue: &str, timestamp: i64, mid: f64) {
+        if mid <= 0.0 {
+            return;
+        }
+
+        let ticks = self.venues.entry(venue.to_string()).or_default();
+        ticks.push_back((timestamp, mid));
+
+        let cutoff = timestamp - self.config.history;
+        // Keep one tick before the cutoff so previous-tick sampling stays defined
+        while ticks.len() > 1 && ticks[1].0 <= cutoff {
+            ticks.pop_front();
+        }
+    }
+
+    pub fn venues(&self) -> impl Iterator<Item = &str> {
+        self.venues.keys().map(|v| v.as_str())
+    }
+
+    /// Analyse a venue pair over their overlapping history
+    pub fn analyze(&self, venue_a: &str, venue_b: &str) -> Option<LeadLagResult> {
+        let a = self.venues.get(venue_a)?;
+        let b = self.venues.get(venue_b)?;
+
+        let start = a.front()?.0.max(b.front()?.0);
+        let end = a.back()?.0.min(b.back()?.0);
+        if end <= start {
+            return None;
+        }
+
+        let ra = self.grid_returns(a, start, end);
+        let rb = self.grid_returns(b, start, end);
+        let max_lag = self.config.max_lag.min(ra.len().saturating_sub(3));
+
+        let mut curve = Vec::new();
+        for lag in -(max_lag as i64)..=(max_lag as i64) {
+            let shift = lag.unsigned_abs() as usize;
+            let (xs, ys) = if lag >= 0 {
+                (&ra[..ra.len() - shift], &rb[shift..])
+            } else {
+                (&ra[shift..], &rb[..rb.len() - shift])
+            };
+
+            if let Some(c) = correlation(xs, ys, CorrelationMethod::Pearson) {
+                curve.push((lag * self.config.sample_interval, c));
+            }
+        }
+
+        let &(lag_ms, best) = curve.iter().max_by(|x, y| x.1.total_cmp(&y.1))?;
+        let (leader, follower) = if lag_ms >= 0 {
+            (venue_a, venue_b)
+        } else {
+            (venue_b, venue_a)
+        };
+
+        Some(LeadLagResult {
+            leader: leader.to_string(),
+            follower: follower.to_string(),
+            lag_ms: lag_ms.abs(),
+            correlation: best,
+            curve,
+        })
+    }
+
+    /// Log returns of previous-tick samples on the grid `start, start + dt, ..., end`
+    fn grid_returns(&self, ticks: &VecDeque<(i64, f64)>, start: i64, end: i64) -> Vec<f64> {
+        let mut samples = Vec::new();
+        let mut idx = 0;
+        let mut t = start;
+        while t <= end {
+            while idx + 1 < ticks.len() && ticks[idx + 1].0 <= t {
+                idx += 1;
+            }
+            samples.push(ticks[idx].1);
+            t += self.config.sample_interval;
+        }
+
+        samples.windows(2).map(|w| (w[1] / w[0]).ln()).collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.venues.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LeadLagConfig {
+        LeadLagConfig {
+            sample_interval: 10,
+            max_lag: 10,
+            history: 100_000,
+        }
+    }
+
+    /// Venue "fast" sees the efficient price immediately, "slow" 30ms later
+    fn feed(analyzer: &mut LeadLagAnalyzer) {
+        let mut seed: u64 = 17;
+        let mut price = 100.0;
+        let mut path = Vec::new();
+        for i in 0..2_000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let u = (seed >> 11) as f64 / (1u64 << 53) as f64;
+            price *= 1.0 + (u - 0.5) * 0.001;
+            path.push((i * 10, price));
+        }
+
+        for &(t, p) in &path {
+            analyzer.on_mid("fast", t, p);
+        }
+        for &(t, p) in &path {
+            analyzer.on_mid("slow", t + 30, p);
+        }
+    }
+
+    #[test]
+    fn test_detects_leader_and_lag() {
+        let mut analyzer = LeadLagAnalyzer::new(config());
+        feed(&mut analyzer);
+
+        let result = analyzer.analyze("fast", "slow").unwrap();
+        assert_eq!(result.leader, "fast");
+        assert_eq!(result.follower, "slow");
+        assert_eq!(result.lag_ms, 30);
+        assert!(result.correlation > 0.99);
+        assert_eq!(result.curve.len(), 21);
+    }
+
+    #[test]
+    fn test_order_of_arguments_does_not_matter() {
+        let mut analyzer = LeadLagAnalyzer::new(config());
+        feed(&mut analyzer);
+
+        let result = analyzer.analyze("slow", "fast").unwrap();
+        assert_eq!(result.leader, "fast");
+        assert_eq!(result.lag_ms, 30);
+    }
+
+    #[test]
+    fn test_missing_or_disjoint_venues() {
+        let mut analyzer = LeadLagAnalyzer::new(config());
+        analyzer.on_mid("a", 0, 100.0);
+        analyzer.on_mid("a", 10, 101.0);
+        analyzer.on_mid("b", 1_000, 100.0);
+
+        assert!(analyzer.analyze("a", "c").is_none());
+        assert!(analyzer.analyze("a", "b").is_none());
+        assert_eq!(analyzer.venues().count(), 2);
+
+        for (sample_interval, history) in [(0, 1_000), (10, 0)] {
+            let invalid = LeadLagConfig {
+                sample_interval,
+                history,
+                ..config()
+            };
+            assert!(LeadLagAnalyzer::try_new(invalid).is_err());
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::orderbook::{LiquidityConfig, LiquiditySnapshot, OrderBook};
+
+/// Parameters for [`LiquidityTracker`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityTrackerConfig {
+    pub liquidity: LiquidityConfig,
+    /// Minimum time between samples, in milliseconds
+    pub sample_interval: i64,
+    /// Samples kept for the aggregates
+    pub window: usize,
+}
+
+impl Default for LiquidityTrackerConfig {
+    fn default() -> Self {
+        Self {
+            liquidity: LiquidityConfig::default(),
+            sample_interval: 1_000,
+            window: 300,
+        }
+    }
+}
+
+/// Aggregates over the retained liquidity samples
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LiquiditySummary {
+    pub samples: usize,
+    pub avg_spread_bps: f64,
+    pub max_spread_bps: f64,
+    pub avg_bid_depth: f64,
+    pub avg_ask_depth: f64,
+    pub avg_score: f64,
+    pub min_score: f64,
+    /// Share of samples where either side could not fill the configured quantity
+    pub thin_rate: f64,
+}
+
+/// Samples [`OrderBook::liquidity`] at a fixed interval and aggregates the samples of
+/// a rolling window, for liquidity dashboards and alerts
+///
+/// Sampling is driven by the timestamps passed to [`on_book`](Self::on_book), so
+/// replays produce the same aggregates as live feeds.
+#[derive(Debug, Clone)]
+pub struct LiquidityTracker {
+    config: LiquidityTrackerConfig,
+    last_sample: Option<i64>,
+    samples: VecDeque<(i64, LiquiditySnapshot)>,
+}
+
+impl LiquidityTracker {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: LiquidityTrackerConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: LiquidityTrackerConfig) -> Result<Self> {
+        ensure(
+            config.liquidity.band_bps.is_finite() && config.liquidity.band_bps > 0.0,
+            "band must be positive",
+        )?;
+        ensure(
+            config.liquidity.fill_quantity.is_finite() && config.liquidity.fill_quantity > 0.0,
+            "fill quantity must be positive",
+        )?;
+        ensure(
+            config.sample_interval >= 0,
+            "sample interval must not be negative",
+        )?;
+        ensure(config.window > 0, "window must be positive")?;
+
+        Ok(Self {
+            config,
+            last_sample: None,
+            samples: VecDeque::with_capacity(config.window),
+        })
+    }
+
+    /// Sample the book if the interval has elapsed since the last sample
+    ///
+    /// Returns the new sample; books without both sides are not sampled.
+    pub fn on_book(&mut self, book: &OrderBook, now: i64) -> Option<LiquiditySnapshot> {
+        if self
+            .last_sample
+            .is_some_and(|last| now - last < self.config.sample_interval)
+        {
+            return None;
+        }
+        let snapshot = book.liquidity(&self.config.liquidity)?;
+        self.last_sample = Some(now);
+        self.samples.push_back((now, snapshot));
+        if self.samples.len() > self.config.window {
+            self.samples.pop_front();
+        }
+        Some(snapshot)
+    }
+
+    /// Retained samples with their timestamps, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = &(i64, LiquiditySnapshot)> {
+        self.samples.iter()
+    }
+
+    pub fn summary(&self) -> Option<LiquiditySummary> {
+        let n = self.samples.len();
+        if n == 0 {
+            return None;
+        }
+        let mean = |f: fn(&LiquiditySnapshot) -> f64| {
+            self.samples.iter().map(|(_, s)| f(s)).sum::<f64>() / n as f64
+        };
+        let thin = self
+            .samples
+            .iter()
+            .filter(|(_, s)| s.buy_cost_bps.is_none() || s.sell_cost_bps.is_none())
+            .count();
+
+        Some(LiquiditySummary {
+            samples: n,
+            avg_spread_bps: mean(|s| s.spread_bps),
+            max_spread_bps: self
+                .samples
+                .iter()
+                .map(|(_, s)| s.spread_bps)
+                .fold(f64::MIN, f64::max),
+            avg_bid_depth: mean(|s| s.bid_depth),
+            avg_ask_depth: mean(|s| s.ask_depth),
+            avg_score: mean(|s| s.score),
+            min_score: self
+                .samples
+                .iter()
+                .map(|(_, s)| s.score)
+                .fold(f64::MAX, f64::min),
+            thin_rate: thin as f64 / n as f64,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.last_sample = None;
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::top_of_book;
+    use crate::time::Timestamp;
+
+    fn tracker(window: usize) -> LiquidityTracker {
+        LiquidityTracker::new(LiquidityTrackerConfig {
+            liquidity: LiquidityConfig {
+                band_bps: 50.0,
+                fill_quantity: 1.0,
+            },
+            sample_interval: 1_000,
+            window,
+        })
+    }
+
+    #[test]
+    fn test_samples_at_interval() {
+        let mut tracker = tracker(10);
+        let tight = top_of_book("BTCUSD", 99.95, 100.05, 2.0);
+        assert!(tracker.on_book(&tight, 0).is_some());
+        assert!(tracker.on_book(&tight, 999).is_none());
+        assert!(tracker.on_book(&tight, 1_000).is_some());
+        // One-sided books are skipped
+        let mut one_sided = OrderBook::new("BTCUSD".to_string());
+        one_sided.update_bid(99.0, 1.0, Timestamp::EPOCH).unwrap();
+        assert!(tracker.on_book(&one_sided, 5_000).is_none());
+        assert_eq!(tracker.samples().count(), 2);
+    }
+
+    #[test]
+    fn test_summary_over_window() {
+        let mut tracker = tracker(2);
+        tracker.on_book(&top_of_book("BTCUSD", 99.0, 101.0, 5.0), 0);
+        tracker.on_book(&top_of_book("BTCUSD", 99.95, 100.05, 2.0), 1_000);
+        tracker.on_book(&top_of_book("BTCUSD", 99.9, 100.1, 0.5), 2_000);
+
+        let summary = tracker.summary().unwrap();
+        // The first sample has left the window
+        assert_eq!(summary.samples, 2);
+        assert!((summary.avg_spread_bps - 15.0).abs() < 1e-9);
+        assert!((summary.max_spread_bps - 20.0).abs() < 1e-9);
+        assert_eq!(summary.avg_bid_depth, 1.25);
+        assert_eq!(summary.min_score, 0.0);
+        assert_eq!(summary.thin_rate, 0.5);
+
+        tracker.reset();
+        assert_eq!(tracker.summary(), None);
+        assert!(LiquidityTracker::try_new(LiquidityTrackerConfig {
+            window: 0,
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
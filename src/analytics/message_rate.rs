@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+
+/// Category of a market data message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MessageKind {
+    /// Book or quote update (adds, modifies and cancels)
+    Quote,
+    Trade,
+}
+
+/// Parameters for [`MessageRateMonitor`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MessageRateConfig {
+    /// Rolling window over which statistics are computed, in milliseconds
+    pub window: i64,
+    /// Slice length used to measure bursts, in milliseconds
+    pub burst_interval: i64,
+    /// Messages within one slice required to count it as a burst
+    pub burst_threshold: usize,
+}
+
+impl Default for MessageRateConfig {
+    fn default() -> Self {
+        Self {
+            window: 60_000,
+            burst_interval: 10,
+            burst_threshold: 100,
+        }
+    }
+}
+
+/// Message statistics for one symbol over the rolling window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MessageStats {
+    pub quotes: u64,
+    pub trades: u64,
+    /// Quote updates per trade, `None` when no trade happened in the window
+    pub quote_to_trade_ratio: Option<f64>,
+    pub messages_per_second: f64,
+    /// Most messages seen in a single burst slice
+    pub max_burst: usize,
+    /// Number of slices reaching the burst threshold
+    pub bursts: usize,
+}
+
+#[derive(Default)]
+struct SymbolCounters {
+    events: VecDeque<(i64, MessageKind)>,
+    quotes: u64,
+    trades: u64,
+}
+
+impl SymbolCounters {
+    fn evict(&mut self, cutoff: i64) {
+        while let Some(&(timestamp, kind)) = self.events.front() {
+            if timestamp > cutoff {
+                break;
+            }
+            self.events.pop_front();
+            match kind {
+                MessageKind::Quote => self.quotes -= 1,
+                MessageKind::Trade => self.trades -= 1,
+            }
+        }
+    }
+}
+
+/// Per-symbol quote-to-trade ratios, message rates and burst statistics, useful
+/// for venue monitoring and spotting quote stuffing
+pub struct MessageRateMonitor {
+    config: MessageRateConfig,
+    symbols: HashMap<String, SymbolCounters>,
+}
+
+impl MessageRateMonitor {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: MessageRateConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: MessageRateConfig) -> Result<Self> {
+        ensure(config.window > 0, "window must be positive")?;
+        ensure(config.burst_interval > 0, "burst interval must be positive")?;
+        ensure(
+            config.burst_threshold > 0,
+            "burst threshold must be positive",
+        )?;
+
+        Ok(Self {
+            config,
+            symbols: HashMap::new(),
+        })
+    }
+
+    pub fn record(&mut self, symbol: &str, kind: MessageKind, timestamp: i64) {
+        let counters = match self.symbols.get_mut(symbol) {
+            Some(counters) => counters,
+            None => self.symbols.entry(symbol.to_string()).or_default(),
+        };
+
+        counters.evict(timestamp - self.config.window);
+        counters.events.push_back((timestamp, kind));
+        match kind {
+            MessageKind::Quote => counters.quotes += 1,
+            MessageKind::Trade => counters.trades += 1,
+        }
+    }
+
+    pub fn record_quote(&mut self, symbol: &str, timestamp: i64) {
+        self.record(symbol, MessageKind::Quote, timestamp);
+    }
+
+    pub fn record_trade(&mut self, symbol: &str, timestamp: i64) {
+        self.record(symbol, MessageKind::Trade, timestamp);
+    }
+
+    /// Statistics for the window ending at `now`
+    pub fn stats(&mut self, symbol: &str, now: i64) -> Option<MessageStats> {
+        let config = self.config;
+        let counters = self.symbols.get_mut(symbol)?;
+        counters.evict(now - config.window);
+
+        let mut slices: BTreeMap<i64, usize> = BTreeMap::new();
+        for &(timestamp, _) in &counters.events {
+            *slices
+                .entry(timestamp.div_euclid(config.burst_interval))
+                .or_insert(0) += 1;
+        }
+
+        let quote_to_trade_ratio = if counters.trades > 0 {
+            Some(counters.quotes as f64 / counters.trades as f64)
+        } else {
+            None
+        };
+
+        Some(MessageStats {
+            quotes: counters.quotes,
+            trades: counters.trades,
+            quote_to_trade_ratio,
+            messages_per_second: counters.events.len() as f64 * 1000.0 / config.window as f64,
+            max_burst: slices.values().copied().max().unwrap_or(0),
+            bursts: slices
+                .values()
+                .filter(|&&n| n >= config.burst_threshold)
+                .count(),
+        })
+    }
+
+    /// Statistics for every tracked symbol
+    pub fn all_stats(&mut self, now: i64) -> Vec<(String, MessageStats)> {
+        let symbols: Vec<String> = self.symbols.keys().cloned().collect();
+        let mut result: Vec<(String, MessageStats)> = symbols
+            .into_iter()
+            .filter_map(|symbol| self.stats(&symbol, now).map(|s| (symbol, s)))
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    pub fn reset(&mut self) {
+        self.symbols.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> MessageRateMonitor {
+        MessageRateMonitor::new(MessageRateConfig {
+            window: 1_000,
+            burst_interval: 10,
+            burst_threshold: 5,
+        })
+    }
+
+    #[test]
+    fn test_quote_to_trade_ratio() {
+        let mut m = monitor();
+        for t in 0..20 {
+            m.record_quote("BTCUSD", t * 10);
+        }
+        m.record_trade("BTCUSD", 100);
+        m.record_trade("BTCUSD", 200);
+
+        let stats = m.stats("BTCUSD", 300).unwrap();
+        assert_eq!(stats.quotes, 20);
+        assert_eq!(stats.trades, 2);
+        assert_eq!(stats.quote_to_trade_ratio, Some(10.0));
+        assert!((stats.messages_per_second - 22.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_window_eviction() {
+        let mut m = monitor();
+        m.record_quote("BTCUSD", 0);
+        m.record_trade("BTCUSD", 0);
+        m.record_quote("BTCUSD", 1_500);
+
+        let stats = m.stats("BTCUSD", 1_500).unwrap();
+        assert_eq!(stats.quotes, 1);
+        assert_eq!(stats.trades, 0);
+        assert_eq!(stats.quote_to_trade_ratio, None);
+
+        assert_eq!(m.stats("BTCUSD", 5_000).unwrap().quotes, 0);
+        assert!(m.stats("ETHUSD", 0).is_none());
+    }
+
+    #[test]
+    fn test_burst_detection() {
+        let mut m = monitor();
+        // Quiet traffic followed by 8 messages within 3ms
+        for t in 0..5 {
+            m.record_quote("BTCUSD", t * 100);
+        }
+        for i in 0..8 {
+            m.record_quote("BTCUSD", 600 + i % 3);
+        }
+
+        let stats = m.stats("BTCUSD", 700).unwrap();
+        assert_eq!(stats.max_burst, 8);
+        assert_eq!(stats.bursts, 1);
+    }
+
+    #[test]
+    fn test_all_stats_sorted() {
+        let mut m = monitor();
+        m.record_trade("ETHUSD", 0);
+        m.record_trade("BTCUSD", 0);
+
+        let all = m.all_stats(10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0, "BTCUSD");
+
+        let invalid = [(0, 10, 5), (1_000, 0, 5), (1_000, 10, 0)];
+        for (window, burst_interval, burst_threshold) in invalid {
+            let config = MessageRateConfig {
+                window,
+                burst_interval,
+                burst_threshold,
+            };
+            assert!(MessageRateMonitor::try_new(config).is_err());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+pub mod anomaly;
+pub mod arbitrage;
+pub mod autocorrelation;
+pub mod beta;
+pub mod correlation;
+pub mod drawdown;
+pub mod execution;
+pub mod heatmap;
+pub mod impact;
+pub mod intensity;
+pub mod lead_lag;
+pub mod liquidity;
+pub mod message_rate;
+pub mod noise;
+pub mod ofi;
+pub mod pairs;
+pub mod regime;
+pub mod replenishment;
+pub mod spread;
+pub mod twap;
+
+pub use anomaly::{
+    Anomaly, AnomalyConfig, AnomalyDetector, AnomalyKind, EwmaControl, MadZScore, Severity,
+};
+pub use arbitrage::{ArbitrageOpportunity, Market, TriangularArbitrage};
+pub use autocorrelation::{acf, pacf, RollingAutocorrelation};
+pub use beta::RollingBeta;
+pub use correlation::{CorrelationMatrix, CorrelationMethod, RollingCorrelation};
+pub use drawdown::{DrawdownState, DrawdownTracker};
+pub use execution::{ExecutionAnalyzer, ExecutionConfig, ExecutionStats, HorizonStats};
+pub use heatmap::{HeatmapColumn, HeatmapConfig, HeatmapGrid, LiquidityHeatmap};
+pub use impact::{fit_impact, ImpactAnalyzer, ImpactFit, ImpactModelKind, ImpactObservation};
+pub use intensity::{
+    ArrivalIntensity, HawkesIntensity, IntensityConfig, IntensityEstimate, PoissonIntensity,
+};
+pub use lead_lag::{LeadLagAnalyzer, LeadLagConfig, LeadLagResult};
+pub use liquidity::{LiquiditySummary, LiquidityTracker, LiquidityTrackerConfig};
+pub use message_rate::{MessageKind, MessageRateConfig, MessageRateMonitor, MessageStats};
+pub use noise::{
+    noise_variance, roll_spread, signature_plot, signature_plot_by_time, RollSpreadEstimator,
+    SignaturePoint,
+};
+pub use ofi::{ofi_contribution, OrderFlowImbalance};
+pub use pairs::{HedgeMethod, PairSignal, PairsConfig, PairsEngine, PairsUpdate};
+pub use regime::{RegimeCallback, RegimeChange, RegimeDetector, VolatilityRegime};
+pub use replenishment::{ReplenishmentConfig, ReplenishmentStats, ReplenishmentTracker};
+pub use spread::{SpreadSnapshot, SpreadTracker};
+pub use twap::{TwapTracker, TwapValue};
@@ -0,0 +1,232 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Roll (1984) implied effective spread from successive price changes
+///
+/// `spread = 2 * sqrt(-cov(Δp_t, Δp_{t-1}))`. Returns `None` when the serial
+/// covariance is non-negative, where the model has no solution.
+pub fn roll_spread(prices: &[f64]) -> Option<f64> {
+    let changes: Vec<f64> = prices.windows(2).map(|w| w[1] - w[0]).collect();
+    roll_from_changes(&changes)
+}
+
+fn roll_from_changes(changes: &[f64]) -> Option<f64> {
+    if changes.len() < 3 {
+        return None;
+    }
+
+    let lagged = &changes[..changes.len() - 1];
+    let current = &changes[1..];
+    let n = current.len() as f64;
+    let mean_l = lagged.iter().sum::<f64>() / n;
+    let mean_c = current.iter().sum::<f64>() / n;
+    let cov = lagged
+        .iter()
+        .zip(current)
+        .map(|(l, c)| (l - mean_l) * (c - mean_c))
+        .sum::<f64>()
+        / (n - 1.0);
+
+    if cov >= 0.0 {
+        None
+    } else {
+        Some(2.0 * (-cov).sqrt())
+    }
+}
+
+/// Roll spread over a rolling window of trade prices
+pub struct RollSpreadEstimator {
+    window: usize,
+    changes: VecDeque<f64>,
+    last_price: Option<f64>,
+}
+
+impl RollSpreadEstimator {
+    /// `window` is the number of price changes used
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            changes: VecDeque::with_capacity(window),
+            last_price: None,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        if let Some(prev) = self.last_price.replace(price) {
+            self.changes.push_back(price - prev);
+            if self.changes.len() > self.window {
+                self.changes.pop_front();
+            }
+        }
+
+        if self.changes.len() < self.window {
+            return None;
+        }
+
+        let changes: Vec<f64> = self.changes.iter().copied().collect();
+        roll_from_changes(&changes)
+    }
+
+    pub fn reset(&mut self) {
+        self.changes.clear();
+        self.last_price = None;
+    }
+}
+
+/// Realized variance measured at one sampling frequency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignaturePoint {
+    /// Sampling step: ticks for [`signature_plot`], milliseconds for [`signature_plot_by_time`]
+    pub step: i64,
+    pub realized_variance: f64,
+    pub returns: usize,
+}
+
+/// Realized variance of log prices sampled every `k` ticks, for `k` in `steps`
+///
+/// With microstructure noise, realized variance blows up at the finest steps and
+/// flattens out at coarser ones; plotting it against `k` shows where noise stops
+/// dominating.
+pub fn signature_plot(prices: &[f64], steps: &[usize]) -> Vec<SignaturePoint> {
+    steps
+        .iter()
+        .filter(|&&k| k > 0)
+        .map(|&k| {
+            let sampled: Vec<f64> = prices.iter().step_by(k).copied().collect();
+            let (rv, n) = realized_variance(&sampled);
+            SignaturePoint {
+                step: k as i64,
+                realized_variance: rv,
+                returns: n,
+            }
+        })
+        .collect()
+}
+
+/// Realized variance on calendar-time grids using previous-tick sampling
+///
+/// `ticks` are `(timestamp, price)` pairs in time order; `intervals` are grid spacings
+/// in milliseconds.
+pub fn signature_plot_by_time(ticks: &[(i64, f64)], intervals: &[i64]) -> Vec<SignaturePoint> {
+    let (start, end) = match (ticks.first(), ticks.last()) {
+        (Some(first), Some(last)) => (first.0, last.0),
+        _ => return Vec::new(),
+    };
+
+    intervals
+        .iter()
+        .filter(|&&dt| dt > 0)
+        .map(|&dt| {
+            let mut sampled = Vec::new();
+            let mut idx = 0;
+            let mut t = start;
+            while t <= end {
+                while idx + 1 < ticks.len() && ticks[idx + 1].0 <= t {
+                    idx += 1;
+                }
+                sampled.push(ticks[idx].1);
+                t += dt;
+            }
+
+            let (rv, n) = realized_variance(&sampled);
+            SignaturePoint {
+                step: dt,
+                realized_variance: rv,
+                returns: n,
+            }
+        })
+        .collect()
+}
+
+/// Variance of the microstructure noise, estimated as `RV / (2n)` at tick frequency
+pub fn noise_variance(prices: &[f64]) -> Option<f64> {
+    let (rv, n) = realized_variance(prices);
+    if n == 0 {
+        None
+    } else {
+        Some(rv / (2.0 * n as f64))
+    }
+}
+
+fn realized_variance(prices: &[f64]) -> (f64, usize) {
+    let returns: Vec<f64> = prices
+        .windows(2)
+        .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+        .map(|w| (w[1] / w[0]).ln())
+        .collect();
+
+    (returns.iter().map(|r| r * r).sum(), returns.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Efficient price random walk observed through a bouncing bid/ask
+    fn bounce_prices(n: usize, half_spread: f64) -> Vec<f64> {
+        let mut seed: u64 = 3;
+        let mut efficient = 100.0;
+        (0..n)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = (seed >> 11) as f64 / (1u64 << 53) as f64;
+                efficient += (u - 0.5) * 0.001;
+                let side = if (seed >> 7) & 1 == 0 { 1.0 } else { -1.0 };
+                efficient + side * half_spread
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_roll_spread_recovers_bounce() {
+        let prices = bounce_prices(20_000, 0.05);
+        let spread = roll_spread(&prices).unwrap();
+        assert!((spread - 0.1).abs() < 0.01, "spread {}", spread);
+    }
+
+    #[test]
+    fn test_roll_spread_undefined_for_trend() {
+        let prices: Vec<f64> = (0..50).map(|i| 100.0 + (i * i) as f64 * 0.01).collect();
+        assert_eq!(roll_spread(&prices), None);
+    }
+
+    #[test]
+    fn test_rolling_estimator() {
+        let mut roll = RollSpreadEstimator::new(500);
+        let mut last = None;
+        for p in bounce_prices(1_000, 0.05) {
+            last = roll.update(p);
+        }
+        assert!(last.is_some());
+
+        roll.reset();
+        assert_eq!(roll.update(100.0), None);
+    }
+
+    #[test]
+    fn test_signature_plot_decreases_with_step() {
+        let prices = bounce_prices(10_000, 0.05);
+        let plot = signature_plot(&prices, &[1, 10, 100]);
+
+        assert_eq!(plot.len(), 3);
+        assert!(plot[0].realized_variance > plot[1].realized_variance);
+        assert!(plot[1].realized_variance > plot[2].realized_variance);
+        assert!(noise_variance(&prices).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_signature_plot_by_time() {
+        let ticks: Vec<(i64, f64)> = bounce_prices(1_000, 0.05)
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (i as i64 * 100, p))
+            .collect();
+
+        let plot = signature_plot_by_time(&ticks, &[100, 1_000]);
+        assert_eq!(plot[0].returns, 999);
+        assert_eq!(plot[1].returns, 99);
+        assert!(signature_plot_by_time(&[], &[100]).is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+use crate::orderbook::{BboUpdate, BookChange};
+
+/// Order flow imbalance of one best bid/offer change, after Cont, Kukanov and Stoikov
+///
+/// Positive when bid-side demand grew or ask-side supply shrank: a higher bid adds the
+/// new bid size, an unchanged bid adds the size change and a lower bid removes the
+/// previous size; the ask side contributes the mirror image with opposite sign.
+pub fn ofi_contribution(prev: (f64, f64, f64, f64), next: (f64, f64, f64, f64)) -> f64 {
+    let (prev_bid, prev_bid_size, prev_ask, prev_ask_size) = prev;
+    let (bid, bid_size, ask, ask_size) = next;
+
+    let mut e = 0.0;
+    if bid >= prev_bid {
+        e += bid_size;
+    }
+    if bid <= prev_bid {
+        e -= prev_bid_size;
+    }
+    if ask <= prev_ask {
+        e -= ask_size;
+    }
+    if ask >= prev_ask {
+        e += prev_ask_size;
+    }
+    e
+}
+
+/// Rolling order flow imbalance over the BBO changes of the last `window` milliseconds
+///
+/// Feed it the [`BboUpdate`]s reported by the order book. Updates with an empty side
+/// break the chain: the next two-sided update only sets the reference.
+#[derive(Debug, Clone)]
+pub struct OrderFlowImbalance {
+    window: i64,
+    /// (bid, bid size, ask, ask size) of the previous update
+    prev: Option<(f64, f64, f64, f64)>,
+    /// (millisecond timestamp, contribution) of each change in the window
+    events: VecDeque<(i64, f64)>,
+    total: KahanSum,
+}
+
+impl OrderFlowImbalance {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(window: i64) -> Self {
+        Self::try_new(window).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(window: i64) -> Result<Self> {
+        ensure(window > 0, "window must be positive")?;
+        Ok(Self {
+            window,
+            prev: None,
+            events: VecDeque::new(),
+            total: KahanSum::new(),
+        })
+    }
+
+    /// Add a BBO change, returning its own contribution
+    pub fn update(&mut self, bbo: &BboUpdate) -> Option<f64> {
+        let (Some((bid, bid_size)), Some((ask, ask_size))) = (bbo.best_bid, bbo.best_ask) else {
+            self.prev = None;
+            return None;
+        };
+        let next = (bid, bid_size, ask, ask_size);
+        let e = ofi_contribution(self.prev.replace(next)?, next);
+
+        let now = bbo.timestamp.as_millis();
+        self.events.push_back((now, e));
+        self.total += e;
+        while let Some(&(timestamp, old)) = self.events.front() {
+            if timestamp > now - self.window {
+                break;
+            }
+            self.events.pop_front();
+            self.total -= old;
+        }
+        Some(e)
+    }
+
+    /// Add the BBO change of a book update, if it moved the top of book
+    pub fn on_change(&mut self, change: &BookChange) -> Option<f64> {
+        self.update(change.bbo.as_ref()?)
+    }
+
+    /// Sum of the contributions in the window
+    pub fn value(&self) -> f64 {
+        self.total.value()
+    }
+
+    /// BBO changes in the window
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.prev = None;
+        self.events.clear();
+        self.total.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn bbo(bid: (f64, f64), ask: (f64, f64), millis: i64) -> BboUpdate {
+        BboUpdate {
+            best_bid: Some(bid),
+            best_ask: Some(ask),
+            timestamp: Timestamp::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_contribution_cases() {
+        let prev = (100.0, 5.0, 101.0, 4.0);
+        // Bid size grows at the same price
+        assert_eq!(ofi_contribution(prev, (100.0, 7.0, 101.0, 4.0)), 2.0);
+        // Bid improves: the whole new size counts
+        assert_eq!(ofi_contribution(prev, (100.5, 1.0, 101.0, 4.0)), 1.0);
+        // Bid drops: the old size is gone
+        assert_eq!(ofi_contribution(prev, (99.5, 3.0, 101.0, 4.0)), -5.0);
+        // Ask lifted away: the previous ask size counts as buying pressure
+        assert_eq!(ofi_contribution(prev, (100.0, 5.0, 101.5, 2.0)), 4.0);
+        // Ask improves
+        assert_eq!(ofi_contribution(prev, (100.0, 5.0, 100.5, 3.0)), -3.0);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut ofi = OrderFlowImbalance::new(1_000);
+        assert_eq!(ofi.update(&bbo((100.0, 5.0), (101.0, 4.0), 0)), None);
+        assert_eq!(ofi.update(&bbo((100.0, 8.0), (101.0, 4.0), 100)), Some(3.0));
+        assert_eq!(
+            ofi.update(&bbo((100.0, 8.0), (101.0, 6.0), 600)),
+            Some(-2.0)
+        );
+        assert_eq!(ofi.value(), 1.0);
+
+        // The change at 100 leaves the window
+        ofi.update(&bbo((100.0, 9.0), (101.0, 6.0), 1_100));
+        assert_eq!((ofi.value(), ofi.event_count()), (-1.0, 2));
+    }
+
+    #[test]
+    fn test_one_sided_book_resets_reference() {
+        let mut ofi = OrderFlowImbalance::new(1_000);
+        ofi.update(&bbo((100.0, 5.0), (101.0, 4.0), 0));
+        let empty_ask = BboUpdate {
+            best_bid: Some((100.0, 5.0)),
+            best_ask: None,
+            timestamp: Timestamp::from_millis(1),
+        };
+        assert_eq!(ofi.update(&empty_ask), None);
+        assert_eq!(ofi.update(&bbo((100.0, 9.0), (101.0, 4.0), 2)), None);
+        assert_eq!(ofi.event_count(), 0);
+
+        ofi.reset();
+        assert_eq!(ofi.value(), 0.0);
+        assert!(OrderFlowImbalance::try_new(0).is_err());
+    }
+}
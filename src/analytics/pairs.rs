@@ -0,0 +1,390 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::beta::RollingBeta;
+use crate::error::{ensure, Result};
+use crate::time::Timestamp;
+
+/// How the hedge ratio between the two legs is estimated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HedgeMethod {
+    /// Ordinary least squares of leg A on leg B over the last `window` prices
+    RollingOls,
+    /// Kalman filter on `(beta, alpha)` treated as a random walk
+    ///
+    /// `delta` controls how fast the ratio may drift (typically 1e-4..1e-5) and
+    /// `observation_variance` is the noise of the measurement equation.
+    Kalman {
+        delta: f64,
+        observation_variance: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PairsConfig {
+    pub method: HedgeMethod,
+    /// Prices for the OLS fit and residuals used for the z-score
+    pub window: usize,
+    /// |z| above which a position is opened
+    pub entry_z: f64,
+    /// |z| below which an open position is closed
+    pub exit_z: f64,
+}
+
+impl Default for PairsConfig {
+    fn default() -> Self {
+        Self {
+            method: HedgeMethod::RollingOls,
+            window: 100,
+            entry_z: 2.0,
+            exit_z: 0.5,
+        }
+    }
+}
+
+/// Threshold crossing of the spread z-score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PairSignal {
+    /// Spread is cheap: buy A, sell `hedge_ratio` of B
+    EnterLong,
+    /// Spread is rich: sell A, buy `hedge_ratio` of B
+    EnterShort,
+    /// Spread has reverted; flatten
+    Exit,
+}
+
+/// Spread state after an update, with the signal it triggered if any
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PairsUpdate {
+    pub hedge_ratio: f64,
+    pub intercept: f64,
+    /// `a - intercept - hedge_ratio * b`
+    pub residual: f64,
+    pub zscore: Option<f64>,
+    pub signal: Option<PairSignal>,
+    pub timestamp: Timestamp,
+}
+
+/// Kalman filter state for `a = beta * b + alpha + noise`
+struct KalmanHedge {
+    delta: f64,
+    observation_variance: f64,
+    state: [f64; 2],
+    covariance: [[f64; 2]; 2],
+    initialized: bool,
+}
+
+impl KalmanHedge {
+    fn new(delta: f64, observation_variance: f64) -> Self {
+        Self {
+            delta,
+            observation_variance,
+            state: [0.0; 2],
+            covariance: [[0.0; 2]; 2],
+            initialized: false,
+        }
+    }
+
+    fn update(&mut self, a: f64, b: f64) -> (f64, f64) {
+        if !self.initialized {
+            self.state = [if b != 0.0 { a / b } else { 0.0 }, 0.0];
+            self.covariance = [[1.0, 0.0], [0.0, 1.0]];
+            self.initialized = true;
+        }
+
+        // Predict: random walk with process noise delta / (1 - delta)
+        let q = self.delta / (1.0 - self.delta);
+        let mut p = self.covariance;
+        p[0][0] += q;
+        p[1][1] += q;
+
+        // Observation vector h = [b, 1]
+        let h = [b, 1.0];
+        let predicted = h[0] * self.state[0] + h[1] * self.state[1];
+        let ph = [
+            p[0][0] * h[0] + p[0][1] * h[1],
+            p[1][0] * h[0] + p[1][1] * h[1],
+        ];
+        let innovation_variance = h[0] * ph[0] + h[1] * ph[1] + self.observation_variance;
+        let gain = [ph[0] / innovation_variance, ph[1] / innovation_variance];
+        let error = a - predicted;
+
+        self.state[0] += gain[0] * error;
+        self.state[1] += gain[1] * error;
+        for (i, row) in self.covariance.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = p[i][j] - gain[i] * ph[j];
+            }
+        }
+
+        (self.state[0], self.state[1])
+    }
+
+    fn reset(&mut self) {
+        self.initialized = false;
+    }
+}
+
+enum Hedge {
+    Ols(RollingBeta),
+    Kalman(KalmanHedge),
+}
+
+/// Statistical arbitrage engine for a pair of co-moving symbols
+///
+/// Estimates the hedge ratio, tracks the z-score of the residual spread and emits
+/// entry/exit signals when it crosses the configured thresholds.
+pub struct PairsEngine {
+    config: PairsConfig,
+    hedge: Hedge,
+    residuals: VecDeque<f64>,
+    position: Option<PairSignal>,
+    last: Option<PairsUpdate>,
+}
+
+impl PairsEngine {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: PairsConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: PairsConfig) -> Result<Self> {
+        ensure(
+            config.exit_z < config.entry_z,
+            "exit threshold must be below entry threshold",
+        )?;
+        ensure(config.exit_z >= 0.0, "exit threshold must not be negative")?;
+        ensure(config.window >= 2, "window must be at least 2")?;
+
+        let hedge = match config.method {
+            HedgeMethod::RollingOls => Hedge::Ols(RollingBeta::try_new(config.window)?),
+            HedgeMethod::Kalman {
+                delta,
+                observation_variance,
+            } => {
+                ensure(delta > 0.0 && delta < 1.0, "Kalman delta must be in (0, 1)")?;
+                ensure(
+                    observation_variance > 0.0,
+                    "observation variance must be positive",
+                )?;
+                Hedge::Kalman(KalmanHedge::new(delta, observation_variance))
+            }
+        };
+
+        Ok(Self {
+            config,
+            hedge,
+            residuals: VecDeque::with_capacity(config.window),
+            position: None,
+            last: None,
+        })
+    }
+
+    /// Feed synchronized prices of both legs; `None` while the hedge ratio is warming up
+    pub fn update(&mut self, a: f64, b: f64, timestamp: Timestamp) -> Option<PairsUpdate> {
+        let (hedge_ratio, intercept) = match &mut self.hedge {
+            Hedge::Ols(beta) => {
+                beta.update(a, b)?;
+                (beta.beta()?, beta.alpha()?)
+            }
+            Hedge::Kalman(kalman) => kalman.update(a, b),
+        };
+
+        let residual = a - intercept - hedge_ratio * b;
+        self.residuals.push_back(residual);
+        if self.residuals.len() > self.config.window {
+            self.residuals.pop_front();
+        }
+
+        let zscore = self.zscore(residual);
+        let signal = zscore.and_then(|z| self.transition(z));
+
+        let update = PairsUpdate {
+            hedge_ratio,
+            intercept,
+            residual,
+            zscore,
+            signal,
+            timestamp,
+        };
+        self.last = Some(update);
+
+        Some(update)
+    }
+
+    fn zscore(&self, residual: f64) -> Option<f64> {
+        if self.residuals.len() < self.config.window {
+            return None;
+        }
+
+        let n = self.residuals.len() as f64;
+        let mean = self.residuals.iter().sum::<f64>() / n;
+        let std_dev = (self
+            .residuals
+            .iter()
+            .map(|r| (r - mean).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        if std_dev <= f64::EPSILON {
+            return None;
+        }
+
+        Some((residual - mean) / std_dev)
+    }
+
+    fn transition(&mut self, z: f64) -> Option<PairSignal> {
+        let signal = match self.position {
+            None if z >= self.config.entry_z => PairSignal::EnterShort,
+            None if z <= -self.config.entry_z => PairSignal::EnterLong,
+            Some(_) if z.abs() <= self.config.exit_z => PairSignal::Exit,
+            _ => return None,
+        };
+
+        self.position = match signal {
+            PairSignal::Exit => None,
+            entry => Some(entry),
+        };
+        Some(signal)
+    }
+
+    /// Entry signal of the open position, if any
+    pub fn position(&self) -> Option<PairSignal> {
+        self.position
+    }
+
+    pub fn last(&self) -> Option<PairsUpdate> {
+        self.last
+    }
+
+    pub fn reset(&mut self) {
+        match &mut self.hedge {
+            Hedge::Ols(beta) => beta.reset(),
+            Hedge::Kalman(kalman) => kalman.reset(),
+        }
+        self.residuals.clear();
+        self.position = None;
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn wiggle(i: usize) -> f64 {
+        // Deterministic, non-repeating noise for the spread
+        ((i * 7919) % 101) as f64 / 100.0 - 0.5
+    }
+
+    #[test]
+    fn test_ols_recovers_hedge_ratio() {
+        let mut engine = PairsEngine::new(PairsConfig {
+            window: 50,
+            ..PairsConfig::default()
+        });
+
+        let mut last = None;
+        for i in 0..120 {
+            let b = 100.0 + i as f64;
+            last = engine.update(2.0 * b + 5.0 + 0.01 * wiggle(i), b, at(i as i64));
+        }
+
+        let update = last.unwrap();
+        assert!((update.hedge_ratio - 2.0).abs() < 1e-3);
+        assert!((update.intercept - 5.0).abs() < 0.5);
+        assert!(update.zscore.is_some());
+    }
+
+    #[test]
+    fn test_entry_and_exit_signals() {
+        let mut engine = PairsEngine::new(PairsConfig {
+            window: 30,
+            entry_z: 4.0,
+            exit_z: 1.0,
+            ..PairsConfig::default()
+        });
+
+        for i in 0..70 {
+            let b = 100.0 + (i % 5) as f64;
+            engine.update(b + 0.1 * wiggle(i), b, at(i as i64));
+        }
+        assert_eq!(engine.position(), None);
+
+        // A jumps well above its fair value against B
+        let update = engine.update(105.0, 100.0, at(70)).unwrap();
+        assert_eq!(update.signal, Some(PairSignal::EnterShort));
+        assert_eq!(engine.position(), Some(PairSignal::EnterShort));
+
+        let mut exit = None;
+        for i in 71..110 {
+            let b = 100.0 + (i % 5) as f64;
+            if let Some(PairSignal::Exit) = engine.update(b, b, at(i as i64)).and_then(|u| u.signal)
+            {
+                exit = Some(i);
+                break;
+            }
+        }
+        assert!(exit.is_some());
+        assert_eq!(engine.position(), None);
+    }
+
+    #[test]
+    fn test_kalman_tracks_ratio_change() {
+        let mut engine = PairsEngine::new(PairsConfig {
+            method: HedgeMethod::Kalman {
+                delta: 1e-3,
+                observation_variance: 1e-3,
+            },
+            window: 20,
+            ..PairsConfig::default()
+        });
+
+        for i in 0..200 {
+            let b = 50.0 + 10.0 * (i as f64 / 10.0).sin();
+            let ratio = if i < 100 { 1.5 } else { 2.5 };
+            engine.update(ratio * b, b, at(i as i64));
+        }
+
+        assert!((engine.last().unwrap().hedge_ratio - 2.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_warm_up_and_reset() {
+        let mut engine = PairsEngine::new(PairsConfig {
+            window: 5,
+            ..PairsConfig::default()
+        });
+
+        for i in 0..4 {
+            assert!(engine.update(i as f64, i as f64, at(i)).is_none());
+        }
+
+        engine.reset();
+        assert!(engine.last().is_none());
+        assert!(engine.update(1.0, 1.0, at(10)).is_none());
+
+        let config = PairsConfig {
+            exit_z: 2.0,
+            ..PairsConfig::default()
+        };
+        assert!(PairsEngine::try_new(config).is_err());
+        let config = PairsConfig {
+            window: 1,
+            ..PairsConfig::default()
+        };
+        assert!(PairsEngine::try_new(config).is_err());
+        let config = PairsConfig {
+            method: HedgeMethod::Kalman {
+                delta: 1.0,
+                observation_variance: 1e-3,
+            },
+            ..PairsConfig::default()
+        };
+        assert!(PairsEngine::try_new(config).is_err());
+    }
+}
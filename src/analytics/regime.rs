@@ -0,0 +1,265 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Volatility regime label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VolatilityRegime {
+    Low,
+    Normal,
+    High,
+}
+
+/// Emitted when the detected regime changes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegimeChange {
+    pub from: VolatilityRegime,
+    pub to: VolatilityRegime,
+    /// Short-horizon volatility that triggered the change
+    pub volatility: f64,
+}
+
+/// Callback invoked on regime changes
+pub type RegimeCallback = Box<dyn FnMut(&RegimeChange) + Send>;
+
+/// Volatility regime detector based on quantile buckets of rolling volatility
+///
+/// Short-horizon realized volatility is computed over `vol_window` returns and ranked
+/// against its own history over `history` observations. Readings below `low_quantile`
+/// are `Low`, above `high_quantile` are `High`. A regime must persist for `min_dwell`
+/// consecutive readings before a change is emitted, which suppresses flip-flopping
+/// around a bucket boundary.
+pub struct RegimeDetector {
+    vol_window: usize,
+    history: usize,
+    low_quantile: f64,
+    high_quantile: f64,
+    min_dwell: usize,
+    returns: VecDeque<f64>,
+    vol_history: VecDeque<f64>,
+    prev_price: Option<f64>,
+    current: Option<VolatilityRegime>,
+    candidate: Option<(VolatilityRegime, usize)>,
+    subscribers: Vec<RegimeCallback>,
+}
+
+impl RegimeDetector {
+    pub fn new(vol_window: usize, history: usize) -> Self {
+        Self {
+            vol_window,
+            history,
+            low_quantile: 0.25,
+            high_quantile: 0.75,
+            min_dwell: 1,
+            returns: VecDeque::with_capacity(vol_window),
+            vol_history: VecDeque::with_capacity(history),
+            prev_price: None,
+            current: None,
+            candidate: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Override the quantile boundaries between Low/Normal and Normal/High
+    pub fn with_quantiles(mut self, low: f64, high: f64) -> Self {
+        self.low_quantile = low;
+        self.high_quantile = high;
+        self
+    }
+
+    /// Require a new regime to be observed this many times in a row before switching
+    pub fn with_min_dwell(mut self, readings: usize) -> Self {
+        self.min_dwell = readings.max(1);
+        self
+    }
+
+    /// Register a callback invoked on every regime change
+    pub fn subscribe<F>(&mut self, callback: F)
+    where
+        F: FnMut(&RegimeChange) + Send + 'static,
+    {
+        self.subscribers.push(Box::new(callback));
+    }
+
+    /// Feed a price; returns a change event when the regime switches
+    pub fn update(&mut self, price: f64) -> Option<RegimeChange> {
+        let prev = self.prev_price.replace(price)?;
+        if prev <= 0.0 || price <= 0.0 {
+            return None;
+        }
+
+        self.returns.push_back((price / prev).ln());
+        if self.returns.len() > self.vol_window {
+            self.returns.pop_front();
+        }
+        if self.returns.len() < self.vol_window {
+            return None;
+        }
+
+        let vol = std_dev(&self.returns);
+        self.vol_history.push_back(vol);
+        if self.vol_history.len() > self.history {
+            self.vol_history.pop_front();
+        }
+        if self.vol_history.len() < self.history {
+            return None;
+        }
+
+        let observed = self.classify(vol);
+        self.transition(observed, vol)
+    }
+
+    fn classify(&self, vol: f64) -> VolatilityRegime {
+        let below = self.vol_history.iter().filter(|&&v| v < vol).count();
+        let rank = below as f64 / self.vol_history.len() as f64;
+
+        if rank < self.low_quantile {
+            VolatilityRegime::Low
+        } else if rank >= self.high_quantile {
+            VolatilityRegime::High
+        } else {
+            VolatilityRegime::Normal
+        }
+    }
+
+    fn transition(&mut self, observed: VolatilityRegime, vol: f64) -> Option<RegimeChange> {
+        let current = match self.current {
+            None => {
+                self.current = Some(observed);
+                return None;
+            }
+            Some(current) if current == observed => {
+                self.candidate = None;
+                return None;
+            }
+            Some(current) => current,
+        };
+
+        let seen = match self.candidate {
+            Some((regime, count)) if regime == observed => count + 1,
+            _ => 1,
+        };
+
+        if seen < self.min_dwell {
+            self.candidate = Some((observed, seen));
+            return None;
+        }
+
+        self.candidate = None;
+        self.current = Some(observed);
+
+        let change = RegimeChange {
+            from: current,
+            to: observed,
+            volatility: vol,
+        };
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber(&change);
+        }
+
+        Some(change)
+    }
+
+    /// Current regime, `None` while warming up
+    pub fn regime(&self) -> Option<VolatilityRegime> {
+        self.current
+    }
+
+    /// Latest short-horizon volatility reading
+    pub fn volatility(&self) -> Option<f64> {
+        self.vol_history.back().copied()
+    }
+
+    pub fn reset(&mut self) {
+        self.returns.clear();
+        self.vol_history.clear();
+        self.prev_price = None;
+        self.current = None;
+        self.candidate = None;
+    }
+}
+
+fn std_dev(values: &VecDeque<f64>) -> f64 {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn feed(
+        detector: &mut RegimeDetector,
+        price: &mut f64,
+        step: f64,
+        n: usize,
+    ) -> Vec<RegimeChange> {
+        let mut changes = Vec::new();
+        for i in 0..n {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            *price *= 1.0 + sign * step;
+            changes.extend(detector.update(*price));
+        }
+        changes
+    }
+
+    #[test]
+    fn test_detects_volatility_spike() {
+        let mut detector = RegimeDetector::new(10, 50);
+        let mut price = 100.0;
+
+        // Calm market with slowly increasing swings establishes the history
+        for k in 0..60 {
+            feed(&mut detector, &mut price, 0.001 + k as f64 * 0.00001, 1);
+        }
+        assert!(detector.regime().is_some());
+
+        let changes = feed(&mut detector, &mut price, 0.02, 20);
+        assert!(!changes.is_empty());
+        assert_eq!(changes.last().unwrap().to, VolatilityRegime::High);
+        assert_eq!(detector.regime(), Some(VolatilityRegime::High));
+    }
+
+    #[test]
+    fn test_subscribers_receive_changes() {
+        let mut detector = RegimeDetector::new(5, 20);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        detector.subscribe(move |change| sink.lock().unwrap().push(change.to));
+
+        let mut price = 100.0;
+        let mut emitted = feed(&mut detector, &mut price, 0.001, 40);
+        emitted.extend(feed(&mut detector, &mut price, 0.05, 10));
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), emitted.len());
+        assert!(seen.contains(&VolatilityRegime::High));
+    }
+
+    #[test]
+    fn test_min_dwell_suppresses_single_reading() {
+        let mut detector = RegimeDetector::new(2, 10).with_min_dwell(3);
+        let mut price = 100.0;
+
+        for k in 0..20 {
+            feed(&mut detector, &mut price, 0.001 + k as f64 * 0.00001, 1);
+        }
+        let before = detector.regime();
+
+        // One large move only produces a couple of high readings before calming down
+        let mut changes = feed(&mut detector, &mut price, 0.05, 1);
+        changes.extend(feed(&mut detector, &mut price, 0.0011, 1));
+        assert!(changes.is_empty());
+        assert_eq!(detector.regime(), before);
+    }
+
+    #[test]
+    fn test_warm_up_returns_no_regime() {
+        let mut detector = RegimeDetector::new(10, 50);
+        let mut price = 100.0;
+        feed(&mut detector, &mut price, 0.01, 30);
+        assert_eq!(detector.regime(), None);
+        assert!(detector.volatility().is_some());
+    }
+}
@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure, Result};
+use crate::orderbook::{BookSide, Quote};
+use crate::trades::{Side, Trade};
+
+/// Parameters for [`ReplenishmentTracker`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplenishmentConfig {
+    /// Share of the consumed size that must reappear at the touch (0.5 gives a half-life)
+    pub recovery_fraction: f64,
+    /// Depletions not refilled within this many milliseconds are counted as censored
+    pub max_wait: i64,
+    /// Smoothing factor of the refill time average
+    pub alpha: f64,
+}
+
+impl Default for ReplenishmentConfig {
+    fn default() -> Self {
+        Self {
+            recovery_fraction: 0.5,
+            max_wait: 10_000,
+            alpha: 0.1,
+        }
+    }
+}
+
+/// Refill statistics of one side of the book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplenishmentStats {
+    /// Exponentially weighted refill time in milliseconds
+    pub half_life: Option<f64>,
+    pub last_half_life: Option<i64>,
+    pub depletions: u64,
+    pub recovered: u64,
+    /// Depletions that did not refill within `max_wait`
+    pub censored: u64,
+}
+
+impl ReplenishmentStats {
+    /// Share of depletions that refilled in time
+    pub fn recovery_rate(&self) -> Option<f64> {
+        let finished = self.recovered + self.censored;
+        (finished > 0).then(|| self.recovered as f64 / finished as f64)
+    }
+}
+
+/// Touch liquidity taken by trades that has not refilled yet
+#[derive(Debug, Clone, Copy)]
+struct Depletion {
+    price: f64,
+    /// Size at the touch before the first trade
+    before: f64,
+    /// Lowest size left after the trades
+    after: f64,
+    start: i64,
+}
+
+#[derive(Debug, Default)]
+struct SideState {
+    depletion: Option<Depletion>,
+    stats: ReplenishmentStats,
+}
+
+/// Measures how fast depth at the touch refills after trades consume it
+///
+/// Trades hitting the best bid or lifting the best ask start a depletion sized from the
+/// prevailing quote; subsequent quotes showing enough size back at that price (or a
+/// better one) end it. Execution algorithms use the refill half-life to pace child
+/// orders. Quotes and trades must be fed in timestamp order.
+pub struct ReplenishmentTracker {
+    config: ReplenishmentConfig,
+    last_quote: Option<Quote>,
+    bid: SideState,
+    ask: SideState,
+}
+
+impl ReplenishmentTracker {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: ReplenishmentConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: ReplenishmentConfig) -> Result<Self> {
+        ensure(
+            config.recovery_fraction > 0.0 && config.recovery_fraction <= 1.0,
+            "recovery fraction must be in (0, 1]",
+        )?;
+        ensure(config.max_wait > 0, "max wait must be positive")?;
+        ensure(
+            config.alpha > 0.0 && config.alpha <= 1.0,
+            "alpha must be in (0, 1]",
+        )?;
+
+        Ok(Self {
+            config,
+            last_quote: None,
+            bid: SideState::default(),
+            ask: SideState::default(),
+        })
+    }
+
+    fn side_mut(&mut self, side: BookSide) -> &mut SideState {
+        match side {
+            BookSide::Bid => &mut self.bid,
+            BookSide::Ask => &mut self.ask,
+        }
+    }
+
+    /// Record a trade; only trades at or through the current touch count as depletions
+    pub fn on_trade(&mut self, trade: &Trade) {
+        let Some(quote) = self.last_quote else {
+            return;
+        };
+        let (side, touch, size) = match trade.side {
+            Side::Buy if trade.price >= quote.ask_price => {
+                (BookSide::Ask, quote.ask_price, quote.ask_size)
+            }
+            Side::Sell if trade.price <= quote.bid_price => {
+                (BookSide::Bid, quote.bid_price, quote.bid_size)
+            }
+            _ => return,
+        };
+
+        let state = self.side_mut(side);
+        match &mut state.depletion {
+            // Further trades against the same touch deepen the pending depletion
+            Some(pending) if pending.price == touch => {
+                pending.after = (pending.after - trade.quantity).max(0.0);
+            }
+            slot => {
+                *slot = Some(Depletion {
+                    price: touch,
+                    before: size,
+                    after: (size - trade.quantity).max(0.0),
+                    start: trade.timestamp.as_millis(),
+                });
+                state.stats.depletions += 1;
+            }
+        }
+    }
+
+    pub fn on_quote(&mut self, quote: &Quote) {
+        let fraction = self.config.recovery_fraction;
+        let max_wait = self.config.max_wait;
+        let alpha = self.config.alpha;
+
+        for side in [BookSide::Bid, BookSide::Ask] {
+            // Size offered at the depleted price or better
+            let size_at = |price: f64| match side {
+                BookSide::Bid if quote.bid_price >= price => quote.bid_size,
+                BookSide::Ask if quote.ask_price <= price => quote.ask_size,
+                _ => 0.0,
+            };
+
+            let state = self.side_mut(side);
+            let Some(depletion) = state.depletion else {
+                continue;
+            };
+            let elapsed = quote.timestamp.as_millis() - depletion.start;
+            let target = depletion.after + fraction * (depletion.before - depletion.after);
+
+            if elapsed > max_wait {
+                state.stats.censored += 1;
+                state.depletion = None;
+            } else if size_at(depletion.price) >= target && elapsed >= 0 {
+                let stats = &mut state.stats;
+                stats.recovered += 1;
+                stats.last_half_life = Some(elapsed);
+                stats.half_life = Some(match stats.half_life {
+                    Some(avg) => avg + alpha * (elapsed as f64 - avg),
+                    None => elapsed as f64,
+                });
+                state.depletion = None;
+            }
+        }
+
+        self.last_quote = Some(*quote);
+    }
+
+    pub fn stats(&self, side: BookSide) -> ReplenishmentStats {
+        match side {
+            BookSide::Bid => self.bid.stats,
+            BookSide::Ask => self.ask.stats,
+        }
+    }
+
+    /// Whether liquidity taken from `side` is still waiting to refill
+    pub fn is_depleted(&self, side: BookSide) -> bool {
+        match side {
+            BookSide::Bid => self.bid.depletion.is_some(),
+            BookSide::Ask => self.ask.depletion.is_some(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.last_quote = None;
+        self.bid = SideState::default();
+        self.ask = SideState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn quote(bid_size: f64, ask_size: f64, timestamp: i64) -> Quote {
+        Quote {
+            bid_price: 100.0,
+            bid_size,
+            ask_price: 101.0,
+            ask_size,
+            timestamp: at(timestamp),
+        }
+    }
+
+    #[test]
+    fn test_half_life_of_ask_refill() {
+        let mut tracker = ReplenishmentTracker::new(ReplenishmentConfig::default());
+        tracker.on_quote(&quote(5.0, 10.0, 0));
+        tracker.on_trade(&Trade::new(101.0, 6.0, Side::Buy, at(100)));
+        tracker.on_trade(&Trade::new(101.0, 2.0, Side::Buy, at(110)));
+        assert!(tracker.is_depleted(BookSide::Ask));
+
+        // 2 left of 10: the half-way target is 6
+        tracker.on_quote(&quote(5.0, 5.0, 300));
+        assert!(tracker.is_depleted(BookSide::Ask));
+        tracker.on_quote(&quote(5.0, 6.0, 500));
+
+        let stats = tracker.stats(BookSide::Ask);
+        assert_eq!(stats.last_half_life, Some(400));
+        assert_eq!(stats.half_life, Some(400.0));
+        assert_eq!(stats.recovery_rate(), Some(1.0));
+        assert_eq!(tracker.stats(BookSide::Bid).depletions, 0);
+    }
+
+    #[test]
+    fn test_level_gone_then_refilled_at_better_price() {
+        let mut tracker = ReplenishmentTracker::new(ReplenishmentConfig::default());
+        tracker.on_quote(&quote(4.0, 1.0, 0));
+        tracker.on_trade(&Trade::new(100.0, 4.0, Side::Sell, at(10)));
+
+        // Touch dropped to a worse price: size there does not count
+        let mut worse = quote(9.0, 1.0, 20);
+        worse.bid_price = 99.5;
+        tracker.on_quote(&worse);
+        assert!(tracker.is_depleted(BookSide::Bid));
+
+        let mut better = quote(2.0, 1.0, 60);
+        better.bid_price = 100.5;
+        tracker.on_quote(&better);
+        assert_eq!(tracker.stats(BookSide::Bid).last_half_life, Some(50));
+    }
+
+    #[test]
+    fn test_censored_and_ewma() {
+        let config = ReplenishmentConfig {
+            max_wait: 1_000,
+            alpha: 0.5,
+            ..ReplenishmentConfig::default()
+        };
+        let mut tracker = ReplenishmentTracker::new(config);
+        tracker.on_quote(&quote(1.0, 4.0, 0));
+
+        for (start, refill) in [(0, 100), (1_000, 300)] {
+            tracker.on_trade(&Trade::new(101.0, 4.0, Side::Buy, at(start)));
+            tracker.on_quote(&quote(1.0, 4.0, start + refill));
+        }
+        tracker.on_trade(&Trade::new(101.0, 4.0, Side::Buy, at(5_000)));
+        tracker.on_quote(&quote(1.0, 0.0, 6_001));
+
+        let stats = tracker.stats(BookSide::Ask);
+        assert_eq!(stats.depletions, 3);
+        assert_eq!(stats.censored, 1);
+        assert_eq!(stats.half_life, Some(200.0));
+        assert!((stats.recovery_rate().unwrap() - 2.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ignores_passive_prints_and_validates() {
+        let mut tracker = ReplenishmentTracker::new(ReplenishmentConfig::default());
+        tracker.on_trade(&Trade::new(101.0, 1.0, Side::Buy, at(0)));
+        tracker.on_quote(&quote(1.0, 1.0, 1));
+        tracker.on_trade(&Trade::new(100.5, 1.0, Side::Buy, at(2)));
+        assert!(!tracker.is_depleted(BookSide::Ask));
+
+        assert!(ReplenishmentTracker::try_new(ReplenishmentConfig {
+            recovery_fraction: 0.0,
+            ..ReplenishmentConfig::default()
+        })
+        .is_err());
+    }
+}
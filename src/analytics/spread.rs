@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::time::Timestamp;
+
+/// Milliseconds in a 365-day year
+const YEAR_MS: f64 = 365.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// State of the spread after an update
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpreadSnapshot {
+    /// `leg_a - leg_b`
+    pub spread: f64,
+    /// Spread relative to leg B, e.g. futures premium over spot
+    pub basis: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// `None` while the window is filling or if the spread has been constant
+    pub zscore: Option<f64>,
+    /// Basis scaled to one year using time to expiry, for dated instruments
+    pub annualized_basis: Option<f64>,
+    pub timestamp: Timestamp,
+}
+
+/// Live spread between two related instruments (perpetual vs spot, calendar spreads, ...)
+pub struct SpreadTracker {
+    window: usize,
+    expiry: Option<Timestamp>,
+    leg_a: Option<f64>,
+    leg_b: Option<f64>,
+    values: VecDeque<f64>,
+    last: Option<SpreadSnapshot>,
+}
+
+impl SpreadTracker {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            expiry: None,
+            leg_a: None,
+            leg_b: None,
+            values: VecDeque::with_capacity(window),
+            last: None,
+        }
+    }
+
+    /// Expiry of leg A used to annualize the basis
+    pub fn with_expiry(mut self, expiry: Timestamp) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// New price for leg A; emits a snapshot once both legs have a price
+    pub fn update_a(&mut self, price: f64, timestamp: Timestamp) -> Option<SpreadSnapshot> {
+        self.leg_a = Some(price);
+        self.recompute(timestamp)
+    }
+
+    /// New price for leg B; emits a snapshot once both legs have a price
+    pub fn update_b(&mut self, price: f64, timestamp: Timestamp) -> Option<SpreadSnapshot> {
+        self.leg_b = Some(price);
+        self.recompute(timestamp)
+    }
+
+    /// Update both legs at once, e.g. from a synchronized sample
+    pub fn update(&mut self, a: f64, b: f64, timestamp: Timestamp) -> Option<SpreadSnapshot> {
+        self.leg_a = Some(a);
+        self.leg_b = Some(b);
+        self.recompute(timestamp)
+    }
+
+    fn recompute(&mut self, timestamp: Timestamp) -> Option<SpreadSnapshot> {
+        let (a, b) = (self.leg_a?, self.leg_b?);
+        if b == 0.0 {
+            return None;
+        }
+
+        let spread = a - b;
+        self.values.push_back(spread);
+        if self.values.len() > self.window {
+            self.values.pop_front();
+        }
+
+        let n = self.values.len() as f64;
+        let mean = self.values.iter().sum::<f64>() / n;
+        let std_dev = (self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let zscore = if self.values.len() == self.window && std_dev > 0.0 {
+            Some((spread - mean) / std_dev)
+        } else {
+            None
+        };
+
+        let basis = spread / b;
+        let annualized_basis = self
+            .expiry
+            .filter(|&expiry| expiry > timestamp)
+            .map(|expiry| basis * YEAR_MS / (expiry.as_millis() - timestamp.as_millis()) as f64);
+
+        let snapshot = SpreadSnapshot {
+            spread,
+            basis,
+            mean,
+            std_dev,
+            zscore,
+            annualized_basis,
+            timestamp,
+        };
+        self.last = Some(snapshot);
+
+        Some(snapshot)
+    }
+
+    pub fn last(&self) -> Option<SpreadSnapshot> {
+        self.last
+    }
+
+    pub fn reset(&mut self) {
+        self.leg_a = None;
+        self.leg_b = None;
+        self.values.clear();
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_requires_both_legs() {
+        let mut tracker = SpreadTracker::new(3);
+        assert!(tracker.update_a(101.0, at(0)).is_none());
+
+        let snap = tracker.update_b(100.0, at(1)).unwrap();
+        assert_eq!(snap.spread, 1.0);
+        assert!((snap.basis - 0.01).abs() < 1e-12);
+        assert_eq!(snap.zscore, None);
+        assert_eq!(snap.annualized_basis, None);
+    }
+
+    #[test]
+    fn test_zscore() {
+        let mut tracker = SpreadTracker::new(4);
+        tracker.update(101.0, 100.0, at(0));
+        tracker.update(102.0, 100.0, at(1));
+        tracker.update(101.0, 100.0, at(2));
+
+        let snap = tracker.update(104.0, 100.0, at(3)).unwrap();
+        // spreads 1, 2, 1, 4: mean 2, population std sqrt(1.5)
+        assert_eq!(snap.mean, 2.0);
+        assert!((snap.zscore.unwrap() - 2.0 / 1.5_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_annualized_basis() {
+        let quarter = (YEAR_MS / 4.0) as i64;
+        let mut tracker = SpreadTracker::new(10).with_expiry(at(quarter));
+
+        let snap = tracker.update(102.0, 100.0, at(0)).unwrap();
+        assert!((snap.annualized_basis.unwrap() - 0.08).abs() < 1e-9);
+
+        // Expired contract: no annualized figure
+        let snap = tracker.update(102.0, 100.0, at(quarter + 1)).unwrap();
+        assert_eq!(snap.annualized_basis, None);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut tracker = SpreadTracker::new(2);
+        tracker.update(1.0, 1.0, at(0));
+        tracker.reset();
+        assert!(tracker.last().is_none());
+        assert!(tracker.update_b(1.0, at(1)).is_none());
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+use crate::orderbook::{OrderBook, Quote};
+
+/// Time-weighted averages over the covered span
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TwapValue {
+    pub mid: f64,
+    pub spread: f64,
+    /// Milliseconds of quoted time the averages are taken over
+    pub duration: i64,
+}
+
+/// Interval during which one mid/spread pair was in force
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: i64,
+    end: i64,
+    mid: f64,
+    spread: f64,
+}
+
+/// Time-weighted average of mid price and spread
+///
+/// Each observed value holds until the next one arrives, so averages weight values by
+/// how long they were quoted rather than by how many updates carried them: a burst of
+/// updates in a busy second does not outweigh a quiet minute.
+pub struct TwapTracker {
+    window: Option<i64>,
+    /// Closed segments still inside the window (rolling mode only)
+    segments: VecDeque<Segment>,
+    mid_area: KahanSum,
+    spread_area: KahanSum,
+    duration: i64,
+    /// Value in force since its timestamp
+    current: Option<(i64, f64, f64)>,
+    clock: SharedClock,
+}
+
+impl TwapTracker {
+    /// Averages since creation or the last [`reset`](Self::reset)
+    pub fn session() -> Self {
+        Self {
+            window: None,
+            segments: VecDeque::new(),
+            mid_area: KahanSum::new(),
+            spread_area: KahanSum::new(),
+            duration: 0,
+            current: None,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Averages over the trailing `window` milliseconds
+    ///
+    /// Panics on invalid parameters; see [`try_rolling`](Self::try_rolling)
+    pub fn rolling(window: i64) -> Self {
+        Self::try_rolling(window).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_rolling(window: i64) -> Result<Self> {
+        ensure(window > 0, "window must be positive")?;
+        Ok(Self {
+            window: Some(window),
+            ..Self::session()
+        })
+    }
+
+    /// Clock read by [`twap_now`](Self::twap_now) (default: system time)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// New mid/spread observed at `timestamp`; stale (out-of-order) updates are ignored
+    pub fn update(&mut self, mid: f64, spread: f64, timestamp: i64) {
+        if let Some((start, prev_mid, prev_spread)) = self.current {
+            if timestamp < start {
+                return;
+            }
+            self.close(Segment {
+                start,
+                end: timestamp,
+                mid: prev_mid,
+                spread: prev_spread,
+            });
+        }
+        self.current = Some((timestamp, mid, spread));
+        self.evict(timestamp);
+    }
+
+    pub fn on_quote(&mut self, quote: &Quote) {
+        self.update(quote.mid(), quote.spread(), quote.timestamp.as_millis());
+    }
+
+    /// Sample the book's top of book at its last update time; one-sided books are skipped
+    pub fn on_book(&mut self, book: &OrderBook) {
+        if let Some(quote) = book.quote() {
+            self.on_quote(&quote);
+        }
+    }
+
+    fn close(&mut self, segment: Segment) {
+        let length = segment.end - segment.start;
+        if length == 0 {
+            return;
+        }
+        self.mid_area += segment.mid * length as f64;
+        self.spread_area += segment.spread * length as f64;
+        self.duration += length;
+        if self.window.is_some() {
+            self.segments.push_back(segment);
+        }
+    }
+
+    fn evict(&mut self, now: i64) {
+        let Some(window) = self.window else {
+            return;
+        };
+        let cutoff = now - window;
+        while let Some(segment) = self.segments.front() {
+            if segment.end > cutoff {
+                break;
+            }
+            let length = (segment.end - segment.start) as f64;
+            self.mid_area -= segment.mid * length;
+            self.spread_area -= segment.spread * length;
+            self.duration -= segment.end - segment.start;
+            self.segments.pop_front();
+        }
+    }
+
+    /// Averages as of `now`, counting the value still in force up to `now`
+    ///
+    /// `None` before the first update or if no time has elapsed yet.
+    pub fn twap(&mut self, now: i64) -> Option<TwapValue> {
+        let (start, mid, spread) = self.current?;
+        let now = now.max(start);
+        self.evict(now);
+
+        let mut mid_area = self.mid_area.value();
+        let mut spread_area = self.spread_area.value();
+        let mut duration = self.duration;
+
+        // Open segment, then clip the part of the oldest one that fell out of the window
+        let open = match self.window {
+            Some(window) => now - start.max(now - window),
+            None => now - start,
+        };
+        mid_area += mid * open as f64;
+        spread_area += spread * open as f64;
+        duration += open;
+
+        if let (Some(window), Some(first)) = (self.window, self.segments.front()) {
+            let excluded = (now - window - first.start).max(0);
+            mid_area -= first.mid * excluded as f64;
+            spread_area -= first.spread * excluded as f64;
+            duration -= excluded;
+        }
+
+        if duration <= 0 {
+            return None;
+        }
+        Some(TwapValue {
+            mid: mid_area / duration as f64,
+            spread: spread_area / duration as f64,
+            duration,
+        })
+    }
+
+    /// [`twap`](Self::twap) at the current time of the configured clock
+    pub fn twap_now(&mut self) -> Option<TwapValue> {
+        let now = self.clock.now_millis();
+        self.twap(now)
+    }
+
+    pub fn reset(&mut self) {
+        self.segments.clear();
+        self.mid_area.reset();
+        self.spread_area.reset();
+        self.duration = 0;
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::clock::SimulationClock;
+    use crate::time::Timestamp;
+
+    #[test]
+    fn test_weights_by_time_not_updates() {
+        let mut twap = TwapTracker::session();
+        // One quiet value for 9 seconds, then a burst of 100 updates in the last second
+        twap.update(100.0, 1.0, 0);
+        for i in 0..100 {
+            twap.update(110.0, 3.0, 9_000 + i * 10);
+        }
+
+        let value = twap.twap(10_000).unwrap();
+        assert_eq!(value.duration, 10_000);
+        assert!((value.mid - 101.0).abs() < 1e-9);
+        assert!((value.spread - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rolling_window_clips_oldest_segment() {
+        let mut twap = TwapTracker::rolling(1_000);
+        twap.update(100.0, 1.0, 0);
+        twap.update(200.0, 1.0, 1_000);
+        twap.update(300.0, 1.0, 1_500);
+
+        // Window [1_000, 2_000]: 500ms at 200 and 500ms at 300
+        let value = twap.twap(2_000).unwrap();
+        assert_eq!(value.duration, 1_000);
+        assert!((value.mid - 250.0).abs() < 1e-9);
+
+        // Window [1_250, 2_250]: 250ms at 200 and 750ms at 300
+        let value = twap.twap(2_250).unwrap();
+        assert!((value.mid - 275.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quotes_and_stale_updates() {
+        let mut twap = TwapTracker::session();
+        assert!(twap.twap(0).is_none());
+
+        twap.on_quote(&Quote {
+            bid_price: 99.0,
+            bid_size: 1.0,
+            ask_price: 101.0,
+            ask_size: 1.0,
+            timestamp: Timestamp::from_millis(100),
+        });
+        assert!(twap.twap(100).is_none());
+
+        twap.update(500.0, 0.0, 50);
+        let value = twap.twap(200).unwrap();
+        assert_eq!(value.mid, 100.0);
+        assert_eq!(value.spread, 2.0);
+    }
+
+    #[test]
+    fn test_reset_and_try_rolling() {
+        let mut twap = TwapTracker::session();
+        twap.update(1.0, 0.1, 0);
+        twap.reset();
+        assert!(twap.twap(1_000).is_none());
+
+        assert!(TwapTracker::try_rolling(0).is_err());
+    }
+
+    #[test]
+    fn test_twap_now_with_simulation_clock() {
+        let clock = SimulationClock::default();
+        let mut twap = TwapTracker::session().with_clock(Arc::new(clock.clone()));
+
+        for (ts, mid) in [(0, 10.0), (300, 20.0)] {
+            clock.observe_millis(ts);
+            twap.update(mid, 0.0, ts);
+        }
+        clock.observe_millis(400);
+        assert!((twap.twap_now().unwrap().mid - 12.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::numeric::{widen, Real};
+use crate::time::Timestamp;
+
+/// OHLCV bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    /// Start of the bar interval
+    pub timestamp: Timestamp,
+}
+
+impl Candle {
+    pub fn new(
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+        volume: f64,
+        timestamp: Timestamp,
+    ) -> Self {
+        Self {
+            open,
+            high,
+            low,
+            close,
+            volume,
+            timestamp,
+        }
+    }
+
+    /// High minus low
+    pub fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    /// (high + low + close) / 3
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+
+    pub fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    /// Range extended to the previous close when the bar gapped away from it
+    pub fn true_range(&self, prev_close: Option<f64>) -> f64 {
+        match prev_close {
+            Some(prev) => self.high.max(prev) - self.low.min(prev),
+            None => self.range(),
+        }
+    }
+}
+
+/// Candle stored with [`Real`] precision, for keeping long histories of many symbols
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CompactCandle {
+    pub open: Real,
+    pub high: Real,
+    pub low: Real,
+    pub close: Real,
+    pub volume: Real,
+    pub timestamp: Timestamp,
+}
+
+impl From<Candle> for CompactCandle {
+    fn from(candle: Candle) -> Self {
+        Self {
+            open: candle.open as Real,
+            high: candle.high as Real,
+            low: candle.low as Real,
+            close: candle.close as Real,
+            volume: candle.volume as Real,
+            timestamp: candle.timestamp,
+        }
+    }
+}
+
+impl From<CompactCandle> for Candle {
+    fn from(candle: CompactCandle) -> Self {
+        Candle::new(
+            widen(candle.open),
+            widen(candle.high),
+            widen(candle.low),
+            widen(candle.close),
+            widen(candle.volume),
+            candle.timestamp,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candle_helpers() {
+        let candle = Candle::new(100.0, 110.0, 95.0, 105.0, 1_000.0, Timestamp::EPOCH);
+        assert_eq!(candle.range(), 15.0);
+        assert_eq!(candle.typical_price(), 310.0 / 3.0);
+        assert!(candle.is_bullish());
+        assert_eq!(candle.true_range(None), 15.0);
+        assert_eq!(candle.true_range(Some(120.0)), 25.0);
+        assert_eq!(candle.true_range(Some(100.0)), 15.0);
+    }
+
+    #[test]
+    fn test_compact_candle_round_trip() {
+        let candle = Candle::new(
+            100.5,
+            110.25,
+            95.0,
+            105.75,
+            1_000.0,
+            Timestamp::from_millis(7),
+        );
+        let compact = CompactCandle::from(candle);
+        assert_eq!(Candle::from(compact), candle);
+        assert_eq!(
+            std::mem::size_of::<CompactCandle>() < std::mem::size_of::<Candle>(),
+            cfg!(feature = "f32-storage")
+        );
+    }
+}
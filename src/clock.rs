@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::time::Timestamp;
+
+/// Source of the current time for components that are not driven by event timestamps
+///
+/// Inject [`SystemClock`] in production, [`MockClock`] in unit tests and
+/// [`SimulationClock`] in backtests so time is fully controlled by the caller.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Timestamp;
+
+    fn now_millis(&self) -> i64 {
+        self.now().as_millis()
+    }
+}
+
+/// Clock shared between components
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time of the host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::now()
+    }
+}
+
+/// Manually set clock for tests; clones share the same time
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    nanos: Arc<AtomicI64>,
+}
+
+impl MockClock {
+    pub fn new(start: Timestamp) -> Self {
+        Self {
+            nanos: Arc::new(AtomicI64::new(start.as_nanos())),
+        }
+    }
+
+    pub fn set(&self, time: Timestamp) {
+        self.nanos.store(time.as_nanos(), Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.nanos.fetch_add(by.as_nanos() as i64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+/// Clock driven by replayed event timestamps
+///
+/// Follows the latest event time observed and never moves backwards, so out-of-order
+/// events in a recording cannot rewind the components reading it. Clones share state.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationClock {
+    nanos: Arc<AtomicI64>,
+}
+
+impl SimulationClock {
+    pub fn new(start: Timestamp) -> Self {
+        Self {
+            nanos: Arc::new(AtomicI64::new(start.as_nanos())),
+        }
+    }
+
+    /// Advance to `time` if it is later than the current simulated time
+    pub fn observe(&self, time: Timestamp) -> Timestamp {
+        let previous = self.nanos.fetch_max(time.as_nanos(), Ordering::SeqCst);
+        Timestamp::from_nanos(previous.max(time.as_nanos()))
+    }
+
+    pub fn observe_millis(&self, millis: i64) -> Timestamp {
+        self.observe(Timestamp::from_millis(millis))
+    }
+}
+
+impl Clock for SimulationClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_shared_between_clones() {
+        let clock = MockClock::new(Timestamp::from_millis(1_000));
+        let shared: SharedClock = Arc::new(clock.clone());
+
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(shared.now_millis(), 1_250);
+
+        clock.set(Timestamp::from_millis(10));
+        assert_eq!(shared.now(), Timestamp::from_millis(10));
+    }
+
+    #[test]
+    fn test_simulation_clock_never_rewinds() {
+        let clock = SimulationClock::default();
+        assert_eq!(clock.observe_millis(500), Timestamp::from_millis(500));
+        assert_eq!(clock.observe_millis(200), Timestamp::from_millis(500));
+        assert_eq!(clock.observe_millis(700).as_millis(), 700);
+        assert_eq!(clock.now_millis(), 700);
+    }
+
+    #[test]
+    fn test_system_clock_is_recent() {
+        let before = Timestamp::now();
+        let now = SystemClock::shared().now();
+        assert!(now >= before);
+        assert!(now.saturating_duration_since(before) < Duration::from_secs(5));
+    }
+}
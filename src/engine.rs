@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crossbeam::channel::{self, Receiver, Sender};
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::indicators::EMA;
+use crate::orderbook::{BookSnapshot, LevelUpdate, OrderBook};
+use crate::time::Timestamp;
+use crate::trades::Trade;
+
+/// Input routed to the worker owning `symbol`
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    Trade {
+        symbol: String,
+        trade: Trade,
+    },
+    /// Batch of absolute level changes, applied atomically
+    Book {
+        symbol: String,
+        updates: Vec<LevelUpdate>,
+        timestamp: Timestamp,
+    },
+}
+
+impl EngineEvent {
+    pub fn symbol(&self) -> &str {
+        match self {
+            EngineEvent::Trade { symbol, .. } | EngineEvent::Book { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// FNV-1a followed by a SplitMix64 finalizer, stable across runs and platforms
+fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Consistent-hash ring mapping symbols to shards
+///
+/// Each shard owns `virtual_nodes` points on the ring, so adding or removing a shard only
+/// moves about `1 / shards` of the symbols.
+#[derive(Debug, Clone)]
+pub struct HashRing {
+    points: Vec<(u64, usize)>,
+}
+
+impl HashRing {
+    pub fn new(shards: usize, virtual_nodes: usize) -> Self {
+        let mut points: Vec<(u64, usize)> = (0..shards)
+            .flat_map(|shard| {
+                (0..virtual_nodes).map(move |node| {
+                    let key = format!("shard-{shard}-{node}");
+                    (stable_hash(key.as_bytes()), shard)
+                })
+            })
+            .collect();
+        points.sort_unstable();
+        Self { points }
+    }
+
+    /// Shard owning `symbol`; panics on an empty ring
+    pub fn shard_for(&self, symbol: &str) -> usize {
+        let hash = stable_hash(symbol.as_bytes());
+        let index = self.points.partition_point(|&(point, _)| point < hash);
+        self.points[index % self.points.len()].1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineConfig {
+    pub workers: usize,
+    /// Ring points per worker; more points spread symbols more evenly
+    pub virtual_nodes: usize,
+    /// Pending events per worker before `submit` blocks
+    pub queue_capacity: usize,
+    /// Period of the per-symbol EMA of the mid price
+    pub ema_period: usize,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, |n| n.get()),
+            virtual_nodes: 64,
+            queue_capacity: 4096,
+            ema_period: 20,
+        }
+    }
+}
+
+/// State of one symbol as seen by its worker
+#[derive(Debug, Clone)]
+pub struct SymbolView {
+    pub book: BookSnapshot,
+    pub mid_ema: Option<f64>,
+    pub last_trade: Option<Trade>,
+    pub traded_volume: f64,
+}
+
+/// Counters of one worker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    pub trades: u64,
+    pub book_updates: u64,
+    /// Events whose book batch failed validation
+    pub rejected: u64,
+    pub symbols: u64,
+}
+
+/// Statistics of every worker, in shard order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngineStats {
+    pub workers: Vec<WorkerStats>,
+}
+
+impl EngineStats {
+    pub fn total(&self) -> WorkerStats {
+        self.workers
+            .iter()
+            .fold(WorkerStats::default(), |acc, w| WorkerStats {
+                trades: acc.trades + w.trades,
+                book_updates: acc.book_updates + w.book_updates,
+                rejected: acc.rejected + w.rejected,
+                symbols: acc.symbols + w.symbols,
+            })
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    trades: AtomicU64,
+    book_updates: AtomicU64,
+    rejected: AtomicU64,
+    symbols: AtomicU64,
+}
+
+impl Counters {
+    fn load(&self) -> WorkerStats {
+        WorkerStats {
+            trades: self.trades.load(Ordering::Relaxed),
+            book_updates: self.book_updates.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            symbols: self.symbols.load(Ordering::Relaxed),
+        }
+    }
+}
+
+enum Command {
+    Event(EngineEvent),
+    View {
+        symbol: String,
+        reply: Sender<Option<SymbolView>>,
+    },
+}
+
+struct SymbolState {
+    book: OrderBook,
+    mid_ema: EMA,
+    last_mid_ema: Option<f64>,
+    last_trade: Option<Trade>,
+    traded_volume: f64,
+}
+
+struct Worker {
+    ema_period: usize,
+    symbols: HashMap<String, SymbolState>,
+    counters: Arc<Counters>,
+}
+
+impl Worker {
+    fn state(&mut self, symbol: String) -> Result<&mut SymbolState> {
+        if !self.symbols.contains_key(&symbol) {
+            let state = SymbolState {
+                book: OrderBook::try_new(symbol.clone())?,
+                mid_ema: EMA::new(self.ema_period),
+                last_mid_ema: None,
+                last_trade: None,
+                traded_volume: 0.0,
+            };
+            self.symbols.insert(symbol.clone(), state);
+            self.counters.symbols.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(self.symbols.get_mut(&symbol).expect("inserted above"))
+    }
+
+    fn handle(&mut self, event: EngineEvent) -> Result<()> {
+        match event {
+            EngineEvent::Trade { symbol, trade } => {
+                let state = self.state(symbol)?;
+                state.last_trade = Some(trade);
+                state.traded_volume += trade.quantity;
+                self.counters.trades.fetch_add(1, Ordering::Relaxed);
+            }
+            EngineEvent::Book {
+                symbol,
+                updates,
+                timestamp,
+            } => {
+                let state = self.state(symbol)?;
+                let change = state.book.apply_updates(&updates, timestamp)?;
+                if change.top_changed {
+                    if let Some(mid) = state.book.mid_price() {
+                        state.last_mid_ema = state.mid_ema.update(mid);
+                    }
+                }
+                self.counters.book_updates.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    fn view(&self, symbol: &str) -> Option<SymbolView> {
+        self.symbols.get(symbol).map(|state| SymbolView {
+            book: state.book.snapshot(),
+            mid_ema: state.last_mid_ema,
+            last_trade: state.last_trade,
+            traded_volume: state.traded_volume,
+        })
+    }
+
+    fn run(mut self, inbox: Receiver<Command>) {
+        for command in inbox {
+            match command {
+                Command::Event(event) => {
+                    if self.handle(event).is_err() {
+                        self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                Command::View { symbol, reply } => {
+                    let _ = reply.send(self.view(&symbol));
+                }
+            }
+        }
+    }
+}
+
+/// Multi-threaded engine sharding symbols across workers
+///
+/// Every worker thread exclusively owns the books and indicators of its symbols, so no
+/// locks are taken on the hot path; events for one symbol are processed in submission
+/// order. Dropping the engine drains the queues and joins the workers.
+pub struct ProcessingEngine {
+    ring: HashRing,
+    inboxes: Vec<Sender<Command>>,
+    counters: Vec<Arc<Counters>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl ProcessingEngine {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(config: EngineConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(config: EngineConfig) -> Result<Self> {
+        ensure(config.workers > 0, "engine needs at least one worker")?;
+        ensure(config.virtual_nodes > 0, "virtual nodes must be positive")?;
+        ensure(config.queue_capacity > 0, "queue capacity must be positive")?;
+        ensure(config.ema_period > 0, "EMA period must be positive")?;
+
+        let mut inboxes = Vec::with_capacity(config.workers);
+        let mut counters = Vec::with_capacity(config.workers);
+        let mut handles = Vec::with_capacity(config.workers);
+        for shard in 0..config.workers {
+            let (tx, rx) = channel::bounded(config.queue_capacity);
+            let shared = Arc::new(Counters::default());
+            let worker = Worker {
+                ema_period: config.ema_period,
+                symbols: HashMap::new(),
+                counters: Arc::clone(&shared),
+            };
+            let handle = thread::Builder::new()
+                .name(format!("engine-worker-{shard}"))
+                .spawn(move || worker.run(rx))?;
+
+            inboxes.push(tx);
+            counters.push(shared);
+            handles.push(handle);
+        }
+
+        Ok(Self {
+            ring: HashRing::new(config.workers, config.virtual_nodes),
+            inboxes,
+            counters,
+            handles,
+        })
+    }
+
+    pub fn workers(&self) -> usize {
+        self.inboxes.len()
+    }
+
+    /// Worker responsible for `symbol`
+    pub fn shard_of(&self, symbol: &str) -> usize {
+        self.ring.shard_for(symbol)
+    }
+
+    /// Route an event to its worker, blocking while that worker's queue is full
+    pub fn submit(&self, event: EngineEvent) -> Result<()> {
+        let shard = self.shard_of(event.symbol());
+        self.inboxes[shard]
+            .send(Command::Event(event))
+            .map_err(|_| {
+                MarketDataError::FeedDisconnected(format!("engine worker {shard} stopped"))
+            })
+    }
+
+    /// Current state of `symbol`, after every event submitted before this call
+    pub fn view(&self, symbol: &str) -> Option<SymbolView> {
+        let (reply, response) = channel::bounded(1);
+        self.inboxes[self.shard_of(symbol)]
+            .send(Command::View {
+                symbol: symbol.to_string(),
+                reply,
+            })
+            .ok()?;
+        response.recv().ok().flatten()
+    }
+
+    pub fn stats(&self) -> EngineStats {
+        EngineStats {
+            workers: self.counters.iter().map(|c| c.load()).collect(),
+        }
+    }
+
+    /// Process every queued event, stop the workers and return the final statistics
+    pub fn shutdown(mut self) -> EngineStats {
+        self.join();
+        self.stats()
+    }
+
+    fn join(&mut self) {
+        self.inboxes.clear();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ProcessingEngine {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trades::Side;
+
+    fn config(workers: usize) -> EngineConfig {
+        EngineConfig {
+            workers,
+            ema_period: 2,
+            ..EngineConfig::default()
+        }
+    }
+
+    fn book_event(symbol: &str, bid: f64, ask: f64, ms: i64) -> EngineEvent {
+        EngineEvent::Book {
+            symbol: symbol.to_string(),
+            updates: vec![LevelUpdate::bid(bid, 1.0), LevelUpdate::ask(ask, 1.0)],
+            timestamp: Timestamp::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_ring_moves_few_symbols_when_growing() {
+        let symbols: Vec<String> = (0..2_000).map(|i| format!("SYM{i}")).collect();
+        let four = HashRing::new(4, 64);
+        let five = HashRing::new(5, 64);
+
+        let moved = symbols
+            .iter()
+            .filter(|s| four.shard_for(s) != five.shard_for(s))
+            .count();
+        // Ideal is 1/5 of the symbols
+        assert!(moved < symbols.len() / 3, "moved {moved}");
+
+        let mut load = [0usize; 4];
+        for s in &symbols {
+            load[four.shard_for(s)] += 1;
+        }
+        assert!(load.iter().all(|&n| n > 300), "{load:?}");
+    }
+
+    #[test]
+    fn test_routes_and_processes_per_symbol() {
+        let engine = ProcessingEngine::new(config(3));
+        for (i, symbol) in ["BTCUSD", "ETHUSD", "SOLUSD"].iter().enumerate() {
+            let base = 100.0 * (i + 1) as f64;
+            engine
+                .submit(book_event(symbol, base, base + 2.0, 1))
+                .unwrap();
+            engine
+                .submit(book_event(symbol, base + 1.0, base + 3.0, 2))
+                .unwrap();
+            engine
+                .submit(EngineEvent::Trade {
+                    symbol: symbol.to_string(),
+                    trade: Trade::new(base + 2.0, 0.5, Side::Buy, Timestamp::from_millis(3)),
+                })
+                .unwrap();
+        }
+
+        let view = engine.view("ETHUSD").unwrap();
+        assert_eq!(view.book.best_bid(), Some((201.0, 1.0)));
+        assert_eq!(view.book.last_update(), Timestamp::from_millis(2));
+        // Mids 201 then 201.5 (the 202 ask is still resting) with alpha 2/3
+        assert!((view.mid_ema.unwrap() - (201.0 + 1.0 / 3.0)).abs() < 1e-9);
+        assert_eq!(view.traded_volume, 0.5);
+        assert!(engine.view("XRPUSD").is_none());
+
+        let total = engine.shutdown().total();
+        assert_eq!(total.symbols, 3);
+        assert_eq!(total.trades, 3);
+        assert_eq!(total.book_updates, 6);
+    }
+
+    #[test]
+    fn test_invalid_batches_are_counted_not_fatal() {
+        let engine = ProcessingEngine::new(config(2));
+        engine.submit(book_event("BTCUSD", -1.0, 101.0, 1)).unwrap();
+        engine
+            .submit(book_event("bad symbol", 100.0, 101.0, 1))
+            .unwrap();
+        engine
+            .submit(book_event("BTCUSD", 100.0, 101.0, 2))
+            .unwrap();
+
+        assert_eq!(
+            engine.view("BTCUSD").unwrap().book.best_ask(),
+            Some((101.0, 1.0))
+        );
+        let stats = engine.shutdown();
+        assert_eq!(stats.workers.len(), 2);
+        assert_eq!(stats.total().rejected, 2);
+        assert_eq!(stats.total().book_updates, 1);
+    }
+
+    #[test]
+    fn test_try_new_validates_config() {
+        assert!(ProcessingEngine::try_new(config(0)).is_err());
+        assert!(ProcessingEngine::try_new(EngineConfig {
+            queue_capacity: 0,
+            ..config(1)
+        })
+        .is_err());
+    }
+}
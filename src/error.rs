@@ -0,0 +1,107 @@
+use thiserror::Error;
+
+use crate::filter::RejectReason;
+
+/// Errors returned at the library boundary
+#[derive(Debug, Error)]
+pub enum MarketDataError {
+    #[error("invalid price {0}: must be finite and positive")]
+    InvalidPrice(f64),
+
+    #[error("invalid quantity {0}: must be finite and non-negative")]
+    InvalidQuantity(f64),
+
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error("expected {expected} values, got {actual}")]
+    DimensionMismatch { expected: usize, actual: usize },
+
+    #[error("sequence gap: expected {expected}, received {received}")]
+    SequenceGap { expected: u64, received: u64 },
+
+    #[error("checksum mismatch: expected {expected:#x}, computed {actual:#x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
+    #[error("crossed book: best bid {bid} above best ask {ask}")]
+    CrossedBook { bid: f64, ask: f64 },
+
+    #[error("locked book: best bid equals best ask at {0}")]
+    LockedBook(f64),
+
+    #[error("unknown order id {0}")]
+    UnknownOrder(u64),
+
+    #[error("duplicate order id {0}")]
+    DuplicateOrder(u64),
+
+    #[error("malformed message: {0}")]
+    Malformed(String),
+
+    #[error("feed disconnected: {0}")]
+    FeedDisconnected(String),
+
+    #[error("tick rejected: {0}")]
+    Rejected(#[from] RejectReason),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+impl MarketDataError {
+    pub fn invalid_parameter(message: impl Into<String>) -> Self {
+        MarketDataError::InvalidParameter(message.into())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MarketDataError>;
+
+/// `InvalidParameter` with `message` unless `condition` holds
+pub(crate) fn ensure(condition: bool, message: &str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(MarketDataError::invalid_parameter(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = MarketDataError::SequenceGap {
+            expected: 10,
+            received: 12,
+        };
+        assert_eq!(err.to_string(), "sequence gap: expected 10, received 12");
+
+        let err = MarketDataError::ChecksumMismatch {
+            expected: 0xff,
+            actual: 0x10,
+        };
+        assert_eq!(
+            err.to_string(),
+            "checksum mismatch: expected 0xff, computed 0x10"
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let err: MarketDataError = RejectReason::CrossedQuote.into();
+        assert!(matches!(
+            err,
+            MarketDataError::Rejected(RejectReason::CrossedQuote)
+        ));
+
+        let err: MarketDataError = serde_json::from_str::<f64>("nope").unwrap_err().into();
+        assert!(matches!(err, MarketDataError::Serialization(_)));
+    }
+}
@@ -0,0 +1,255 @@
+//! Venue-independent market data events
+//!
+//! Feed adapters normalize their wire formats into [`MarketEvent`] through
+//! [`ToMarketEvent`], so consumers handle one type whatever the exchange.
+
+use serde::{Deserialize, Serialize};
+
+use crate::candles::Candle;
+use crate::engine::EngineEvent;
+use crate::error::{MarketDataError, Result};
+use crate::orderbook::{BookChange, LevelUpdate, OrderBook, PriceLevel, Quote};
+use crate::time::Timestamp;
+use crate::trades::Trade;
+
+/// Payload of a [`MarketEvent`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MarketData {
+    /// Full book, bids and asks best-first
+    BookSnapshot {
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+    },
+    /// Absolute level changes, applied atomically
+    BookDelta {
+        updates: Vec<LevelUpdate>,
+        /// First sequence number covered, for venues whose deltas span a range of
+        /// ids (the event's `sequence` being the last); `None` for single-step feeds
+        first_sequence: Option<u64>,
+    },
+    Trade(Trade),
+    /// Best bid and offer
+    Ticker(Quote),
+    Candle(Candle),
+    /// Liveness signal without market data
+    Heartbeat,
+}
+
+/// Normalized event with its routing and timing metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketEvent {
+    pub exchange: String,
+    pub symbol: String,
+    /// Time the venue stamped the event, or `received` when it sends none
+    pub exchange_time: Timestamp,
+    /// Local time the event was read off the wire
+    pub received: Timestamp,
+    /// Venue sequence number, for feeds that provide one
+    pub sequence: Option<u64>,
+    pub data: MarketData,
+}
+
+impl MarketEvent {
+    pub fn new(exchange: &str, symbol: &str, exchange_time: Timestamp, data: MarketData) -> Self {
+        Self {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            exchange_time,
+            received: exchange_time,
+            sequence: None,
+            data,
+        }
+    }
+
+    pub fn with_received(mut self, received: Timestamp) -> Self {
+        self.received = received;
+        self
+    }
+
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// Time from the venue stamping the event to its arrival, zero on clock skew
+    pub fn latency(&self) -> std::time::Duration {
+        self.received.saturating_duration_since(self.exchange_time)
+    }
+
+    /// Apply a book snapshot or delta to `book`; other payloads are ignored
+    ///
+    /// Sequenced deltas get the gap detection of [`OrderBook::apply_delta`], extended
+    /// to deltas spanning a range of sequence numbers: stale ones are ignored and a
+    /// delta starting past the next expected number fails with
+    /// [`MarketDataError::SequenceGap`]. Unsequenced deltas are applied directly. A
+    /// snapshot without a sequence number leaves the book unsequenced.
+    pub fn apply_to(&self, book: &mut OrderBook) -> Result<Option<BookChange>> {
+        match &self.data {
+            MarketData::BookSnapshot { bids, asks } => {
+                book.apply_snapshot(bids, asks, self.sequence.unwrap_or(0), self.exchange_time)?;
+                book.sequence = self.sequence;
+                Ok(Some(BookChange {
+                    top_changed: true,
+                    inserted: bids.len() + asks.len(),
+                    bbo: Some(book.bbo()),
+                    ..BookChange::default()
+                }))
+            }
+            MarketData::BookDelta {
+                updates,
+                first_sequence,
+            } => match (self.sequence, book.sequence) {
+                (Some(last), Some(current)) => {
+                    if last <= current {
+                        return Ok(Some(BookChange::default()));
+                    }
+                    let first = first_sequence.unwrap_or(last);
+                    if first > current + 1 {
+                        return Err(MarketDataError::SequenceGap {
+                            expected: current + 1,
+                            received: first,
+                        });
+                    }
+                    let change = book.apply_updates(updates, self.exchange_time)?;
+                    book.sequence = Some(last);
+                    Ok(Some(change))
+                }
+                _ => book.apply_updates(updates, self.exchange_time).map(Some),
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Conversion of a venue message into the normalized model
+pub trait ToMarketEvent {
+    /// `None` for messages carrying no market data, e.g. subscription acks
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent>;
+}
+
+impl TryFrom<MarketEvent> for EngineEvent {
+    type Error = MarketDataError;
+
+    /// Trades and book deltas; the engine has no input for other payloads
+    fn try_from(event: MarketEvent) -> Result<Self> {
+        match event.data {
+            MarketData::Trade(trade) => Ok(EngineEvent::Trade {
+                symbol: event.symbol,
+                trade,
+            }),
+            MarketData::BookDelta { updates, .. } => Ok(EngineEvent::Book {
+                symbol: event.symbol,
+                updates,
+                timestamp: event.exchange_time,
+            }),
+            _ => Err(MarketDataError::invalid_parameter(
+                "only trades and book deltas map to engine events",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trades::Side;
+
+    fn level(price: f64, quantity: f64) -> PriceLevel {
+        PriceLevel { price, quantity }
+    }
+
+    #[test]
+    fn test_apply_snapshot_then_sequenced_deltas() {
+        let mut book = OrderBook::new("BTCUSD".to_string());
+        let t = Timestamp::from_millis(1_000);
+
+        let snapshot = MarketEvent::new(
+            "test",
+            "BTCUSD",
+            t,
+            MarketData::BookSnapshot {
+                bids: vec![level(100.0, 1.0)],
+                asks: vec![level(101.0, 1.0)],
+            },
+        )
+        .with_sequence(10);
+        snapshot.apply_to(&mut book).unwrap();
+        assert_eq!(book.sequence, Some(10));
+        assert_eq!(book.last_update, t);
+
+        let delta = |sequence| {
+            MarketEvent::new(
+                "test",
+                "BTCUSD",
+                t,
+                MarketData::BookDelta {
+                    updates: vec![LevelUpdate::bid(100.5, 2.0)],
+                    first_sequence: None,
+                },
+            )
+            .with_sequence(sequence)
+        };
+        let change = delta(11).apply_to(&mut book).unwrap().unwrap();
+        assert!(change.top_changed);
+        assert!(matches!(
+            delta(13).apply_to(&mut book),
+            Err(MarketDataError::SequenceGap { .. })
+        ));
+        assert_eq!(
+            delta(11).apply_to(&mut book).unwrap(),
+            Some(BookChange::default())
+        );
+
+        // Range deltas overlapping the current sequence are applied
+        let mut range = delta(20);
+        range.data = MarketData::BookDelta {
+            updates: vec![LevelUpdate::ask(100.8, 1.0)],
+            first_sequence: Some(12),
+        };
+        range.apply_to(&mut book).unwrap();
+        assert_eq!(book.sequence, Some(20));
+        assert_eq!(book.best_ask(), Some((100.8, 1.0)));
+
+        let heartbeat = MarketEvent::new("test", "BTCUSD", t, MarketData::Heartbeat);
+        assert_eq!(heartbeat.apply_to(&mut book).unwrap(), None);
+    }
+
+    #[test]
+    fn test_engine_conversion_and_latency() {
+        let trade = Trade::new(100.0, 1.0, Side::Buy, Timestamp::from_millis(5));
+        let event = MarketEvent::new("test", "ETHUSD", trade.timestamp, MarketData::Trade(trade))
+            .with_received(Timestamp::from_millis(8));
+        assert_eq!(event.latency(), std::time::Duration::from_millis(3));
+
+        let engine = EngineEvent::try_from(event).unwrap();
+        assert_eq!(engine.symbol(), "ETHUSD");
+
+        let heartbeat = MarketEvent::new(
+            "test",
+            "ETHUSD",
+            Timestamp::from_millis(1),
+            MarketData::Heartbeat,
+        );
+        assert!(EngineEvent::try_from(heartbeat).is_err());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let quote = Quote {
+            bid_price: 1.0,
+            bid_size: 2.0,
+            ask_price: 1.5,
+            ask_size: 3.0,
+            timestamp: Timestamp::from_millis(9),
+        };
+        let event = MarketEvent::new(
+            "test",
+            "X",
+            Timestamp::from_millis(9),
+            MarketData::Ticker(quote),
+        )
+        .with_sequence(4);
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<MarketEvent>(&json).unwrap(), event);
+    }
+}
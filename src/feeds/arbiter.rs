@@ -0,0 +1,553 @@
+//! Redundant-source arbitration
+//!
+//! [`FeedArbiter`] merges the same instruments received from several sources (e.g. two
+//! connections to one venue, or a WebSocket and a multicast line) into one stream.
+//! The first copy of every event wins and later copies are dropped, so the merged
+//! stream always runs at the pace of the fastest source and keeps flowing when one
+//! of them stalls. Events are matched by venue sequence number when they carry one,
+//! and by exchange timestamp and payload otherwise; identical unsequenced events from
+//! one source (e.g. two equal trades in the same millisecond) are told apart by how
+//! many of them each source has sent. A sequence skipped by one source
+//! is still delivered when another source has it, after the later ones, so gaps on one
+//! line are filled from the other.
+//!
+//! The arbiter also measures how far each source trails the winner and reports
+//! stalls, recoveries and failovers of the preferred (fastest healthy) source.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::time::Timestamp;
+
+/// Dedup window and health thresholds
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbiterConfig {
+    /// A source silent for this long is considered stalled
+    pub stall_timeout: Duration,
+    /// Delivered events remembered per symbol for matching late copies; sequenced
+    /// events this many sequences or more behind the highest delivered one are dropped
+    pub dedup_window: usize,
+    /// Weight of the newest sample in each source's smoothed lag, in `(0, 1]`
+    pub lag_smoothing: f64,
+}
+
+impl Default for ArbiterConfig {
+    fn default() -> Self {
+        Self {
+            stall_timeout: Duration::from_secs(5),
+            dedup_window: 1024,
+            lag_smoothing: 0.05,
+        }
+    }
+}
+
+/// Health and timing of one source
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceStats {
+    pub name: String,
+    /// Events this source delivered first
+    pub delivered: u64,
+    /// Late copies of events already delivered by another source
+    pub duplicates: u64,
+    /// Unsequenced events delivered with an exchange time older than one already
+    /// delivered for the symbol
+    pub out_of_order: u64,
+    /// Events dropped for being too old for the dedup window to tell from a copy
+    pub stale: u64,
+    /// Smoothed delay behind the first copy of each event; zero for the leader
+    pub lag: Duration,
+    /// Arrival time of the latest event, or the registration time before any
+    pub last_seen: Timestamp,
+    pub stalled: bool,
+}
+
+/// Health change reported by [`FeedArbiter::poll`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArbiterEvent {
+    /// The source sent nothing for [`ArbiterConfig::stall_timeout`]
+    Stalled { source: String },
+    /// A stalled source delivered data again
+    Recovered { source: String },
+    /// The preferred source stalled; `to` is the next fastest healthy one, if any
+    Failover { from: String, to: Option<String> },
+}
+
+/// Sequenced event already delivered, kept to measure the lag of late copies
+#[derive(Debug, Clone)]
+struct Seen {
+    sequence: u64,
+    received: Timestamp,
+}
+
+/// Unsequenced events sharing one exchange time and payload
+#[derive(Debug, Clone)]
+struct Copies {
+    exchange_time: Timestamp,
+    data: MarketData,
+    /// Arrival of each delivered occurrence
+    received: Vec<Timestamp>,
+    /// Occurrences offered by each source, by source index
+    offered: Vec<usize>,
+}
+
+/// Why an offered event was not delivered
+enum Dropped {
+    /// Copy of a delivered event, with the first copy's arrival if still remembered
+    Duplicate(Option<Timestamp>),
+    Stale,
+}
+
+#[derive(Debug, Default)]
+struct SymbolState {
+    /// Highest sequence delivered
+    sequence: Option<u64>,
+    /// Sequences delivered within `dedup_window` of the highest one
+    delivered: BTreeSet<u64>,
+    /// Latest exchange time among delivered unsequenced events
+    exchange_time: Option<Timestamp>,
+    recent: VecDeque<Seen>,
+    /// Unsequenced events remembered for matching copies, oldest first
+    unsequenced: VecDeque<Copies>,
+    /// Latest exchange time among unsequenced events evicted from `unsequenced`
+    evicted: Option<Timestamp>,
+}
+
+/// De-duplicating merger of redundant sources with stall detection
+#[derive(Debug)]
+pub struct FeedArbiter {
+    config: ArbiterConfig,
+    sources: Vec<SourceStats>,
+    symbols: HashMap<String, SymbolState>,
+    preferred: Option<usize>,
+    pending: Vec<ArbiterEvent>,
+}
+
+impl FeedArbiter {
+    pub fn new(config: ArbiterConfig) -> Result<Self> {
+        ensure(
+            !config.stall_timeout.is_zero(),
+            "stall timeout must be positive",
+        )?;
+        ensure(config.dedup_window > 0, "dedup window must not be empty")?;
+        ensure(
+            config.lag_smoothing > 0.0 && config.lag_smoothing <= 1.0,
+            "lag smoothing must be in (0, 1]",
+        )?;
+        Ok(Self {
+            config,
+            sources: Vec::new(),
+            symbols: HashMap::new(),
+            preferred: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Register a source; its stall timer starts at `now`
+    pub fn add_source(&mut self, name: &str, now: Timestamp) -> Result<()> {
+        ensure(!name.is_empty(), "source name must not be empty")?;
+        ensure(self.index(name).is_none(), "source already registered")?;
+        self.sources.push(SourceStats {
+            name: name.to_string(),
+            delivered: 0,
+            duplicates: 0,
+            out_of_order: 0,
+            stale: 0,
+            lag: Duration::ZERO,
+            last_seen: now,
+            stalled: false,
+        });
+        self.preferred.get_or_insert(self.sources.len() - 1);
+        Ok(())
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.sources.iter().position(|s| s.name == name)
+    }
+
+    pub fn sources(&self) -> &[SourceStats] {
+        &self.sources
+    }
+
+    pub fn source(&self, name: &str) -> Option<&SourceStats> {
+        self.index(name).map(|i| &self.sources[i])
+    }
+
+    /// Fastest healthy source, the one to read books from or to trust for
+    /// anything that cannot be merged
+    pub fn preferred(&self) -> Option<&str> {
+        self.preferred.map(|i| self.sources[i].name.as_str())
+    }
+
+    /// Offer an event received from `source`
+    ///
+    /// Returns the event when this is its first copy and `None` for duplicates and
+    /// for events too old for the dedup window: sequences that far behind the
+    /// highest delivered one, or unsequenced events no newer than the ones already
+    /// forgotten. Late unsequenced events that are not copies are delivered and
+    /// counted in [`SourceStats::out_of_order`]. Arrival times are taken from
+    /// [`MarketEvent::received`], so all sources must share one clock.
+    pub fn on_event(&mut self, source: &str, event: MarketEvent) -> Result<Option<MarketEvent>> {
+        let index = self.index(source).ok_or_else(|| {
+            MarketDataError::invalid_parameter(format!("unknown source {source:?}"))
+        })?;
+        let received = event.received;
+        {
+            let stats = &mut self.sources[index];
+            stats.last_seen = stats.last_seen.max(received);
+            if stats.stalled {
+                stats.stalled = false;
+                self.pending.push(ArbiterEvent::Recovered {
+                    source: stats.name.clone(),
+                });
+            }
+        }
+
+        let window = self.config.dedup_window;
+        let sources = self.sources.len();
+        let state = self.symbols.entry(event.symbol.clone()).or_default();
+        let outcome = match event.sequence {
+            Some(sequence) => {
+                let last = state.sequence.unwrap_or(0);
+                // Beyond the window nothing tells a gap fill from a stale copy
+                if state.sequence.is_some() && sequence <= last.saturating_sub(window as u64) {
+                    Err(Dropped::Stale)
+                } else if state.delivered.contains(&sequence) {
+                    let first = state
+                        .recent
+                        .iter()
+                        .find(|seen| seen.sequence == sequence)
+                        .map(|seen| seen.received);
+                    Err(Dropped::Duplicate(first))
+                } else {
+                    let highest = state.sequence.map_or(sequence, |last| last.max(sequence));
+                    state.sequence = Some(highest);
+                    state.delivered.insert(sequence);
+                    let oldest = highest.saturating_sub(window as u64);
+                    state.delivered = state.delivered.split_off(&oldest);
+                    state.recent.push_back(Seen { sequence, received });
+                    if state.recent.len() > window {
+                        state.recent.pop_front();
+                    }
+                    Ok(false)
+                }
+            }
+            None => {
+                let known = state.unsequenced.iter_mut().find(|copies| {
+                    copies.exchange_time == event.exchange_time && copies.data == event.data
+                });
+                let outcome = match known {
+                    // The n-th copy from a source matches the n-th delivered occurrence
+                    Some(copies) => {
+                        copies.offered.resize(sources, 0);
+                        let occurrence = copies.offered[index];
+                        copies.offered[index] += 1;
+                        match copies.received.get(occurrence) {
+                            Some(&first) => Err(Dropped::Duplicate(Some(first))),
+                            None => {
+                                copies.received.push(received);
+                                Ok(())
+                            }
+                        }
+                    }
+                    None if state.evicted.is_some_and(|t| event.exchange_time <= t) => {
+                        Err(Dropped::Stale)
+                    }
+                    None => {
+                        let mut offered = vec![0; sources];
+                        offered[index] = 1;
+                        state.unsequenced.push_back(Copies {
+                            exchange_time: event.exchange_time,
+                            data: event.data.clone(),
+                            received: vec![received],
+                            offered,
+                        });
+                        if state.unsequenced.len() > window {
+                            let evicted = state.unsequenced.pop_front().map(|c| c.exchange_time);
+                            state.evicted = state.evicted.max(evicted);
+                        }
+                        Ok(())
+                    }
+                };
+                outcome.map(|()| {
+                    let latest = state.exchange_time.unwrap_or(event.exchange_time);
+                    state.exchange_time = Some(latest.max(event.exchange_time));
+                    event.exchange_time < latest
+                })
+            }
+        };
+
+        let stats = &mut self.sources[index];
+        match outcome {
+            Ok(out_of_order) => {
+                stats.delivered += 1;
+                stats.out_of_order += u64::from(out_of_order);
+                self.record_lag(index, Duration::ZERO);
+                Ok(Some(event))
+            }
+            Err(Dropped::Duplicate(first_received)) => {
+                stats.duplicates += 1;
+                if let Some(first_received) = first_received {
+                    self.record_lag(index, received.saturating_duration_since(first_received));
+                }
+                Ok(None)
+            }
+            Err(Dropped::Stale) => {
+                stats.stale += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    fn record_lag(&mut self, index: usize, sample: Duration) {
+        let alpha = self.config.lag_smoothing;
+        let stats = &mut self.sources[index];
+        let lag = alpha * sample.as_secs_f64() + (1.0 - alpha) * stats.lag.as_secs_f64();
+        stats.lag = Duration::from_secs_f64(lag);
+        self.preferred = self.fastest_healthy();
+    }
+
+    fn fastest_healthy(&self) -> Option<usize> {
+        self.sources
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !s.stalled)
+            // First registered wins ties, keeping the preference stable
+            .min_by_key(|(i, s)| (s.lag, *i))
+            .map(|(i, _)| i)
+    }
+
+    /// Detect sources silent since `now - stall_timeout` and report health changes
+    /// since the last poll
+    pub fn poll(&mut self, now: Timestamp) -> Vec<ArbiterEvent> {
+        let mut events = std::mem::take(&mut self.pending);
+        let timeout = self.config.stall_timeout;
+        for stats in &mut self.sources {
+            if !stats.stalled && now.saturating_duration_since(stats.last_seen) >= timeout {
+                stats.stalled = true;
+                events.push(ArbiterEvent::Stalled {
+                    source: stats.name.clone(),
+                });
+            }
+        }
+
+        let previous = self.preferred;
+        if previous.is_some_and(|i| !self.sources[i].stalled) {
+            return events;
+        }
+        self.preferred = self.fastest_healthy();
+        if let Some(from) = previous {
+            if self.preferred != previous {
+                events.push(ArbiterEvent::Failover {
+                    from: self.sources[from].name.clone(),
+                    to: self.preferred.map(|i| self.sources[i].name.clone()),
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trades::{Side, Trade};
+
+    const START: Timestamp = Timestamp::from_millis(1_700_000_000_000);
+
+    fn arbiter() -> FeedArbiter {
+        let mut arbiter = FeedArbiter::new(ArbiterConfig {
+            stall_timeout: Duration::from_secs(1),
+            lag_smoothing: 1.0,
+            ..ArbiterConfig::default()
+        })
+        .unwrap();
+        arbiter.add_source("a", START).unwrap();
+        arbiter.add_source("b", START).unwrap();
+        arbiter
+    }
+
+    fn sequenced(sequence: u64, received_ms: u64) -> MarketEvent {
+        MarketEvent::new("venue", "BTC-USD", START, MarketData::Heartbeat)
+            .with_sequence(sequence)
+            .with_received(START + Duration::from_millis(received_ms))
+    }
+
+    fn trade(price: f64, exchange_ms: u64, received_ms: u64) -> MarketEvent {
+        let exchange_time = START + Duration::from_millis(exchange_ms);
+        let trade = Trade::new(price, 1.0, Side::Buy, exchange_time);
+        MarketEvent::new("venue", "BTC-USD", exchange_time, MarketData::Trade(trade))
+            .with_received(START + Duration::from_millis(received_ms))
+    }
+
+    #[test]
+    fn test_dedup_by_sequence_prefers_fastest() {
+        let mut arbiter = arbiter();
+        assert!(arbiter.on_event("a", sequenced(1, 0)).unwrap().is_some());
+        assert!(arbiter.on_event("b", sequenced(1, 3)).unwrap().is_none());
+        assert!(arbiter.on_event("b", sequenced(2, 4)).unwrap().is_some());
+        assert!(arbiter.on_event("a", sequenced(2, 10)).unwrap().is_none());
+        // b led the latest event by 6ms, a trailed b on the first by 3ms
+        assert_eq!(arbiter.preferred(), Some("b"));
+        assert_eq!(arbiter.source("a").unwrap().lag, Duration::from_millis(6));
+        assert_eq!(arbiter.source("b").unwrap().lag, Duration::ZERO);
+        assert_eq!(arbiter.source("a").unwrap().delivered, 1);
+        assert_eq!(arbiter.source("a").unwrap().duplicates, 1);
+
+        assert!(arbiter.on_event("c", sequenced(3, 11)).is_err());
+    }
+
+    #[test]
+    fn test_gap_on_one_line_filled_by_the_other() {
+        let mut arbiter = arbiter();
+        for sequence in 1..=4 {
+            assert!(arbiter
+                .on_event("a", sequenced(sequence, 0))
+                .unwrap()
+                .is_some());
+        }
+        // a skips 5; b is behind but has it
+        assert!(arbiter.on_event("a", sequenced(6, 1)).unwrap().is_some());
+        assert!(arbiter.on_event("b", sequenced(4, 2)).unwrap().is_none());
+        let fill = arbiter.on_event("b", sequenced(5, 3)).unwrap();
+        assert_eq!(fill.and_then(|event| event.sequence), Some(5));
+        assert!(arbiter.on_event("b", sequenced(6, 4)).unwrap().is_none());
+        assert!(arbiter.on_event("a", sequenced(5, 5)).unwrap().is_none());
+        assert_eq!(arbiter.source("b").unwrap().delivered, 1);
+
+        // Too far behind the window to tell from a stale copy
+        let mut arbiter = FeedArbiter::new(ArbiterConfig {
+            dedup_window: 2,
+            ..ArbiterConfig::default()
+        })
+        .unwrap();
+        arbiter.add_source("a", START).unwrap();
+        arbiter.add_source("b", START).unwrap();
+        assert!(arbiter.on_event("a", sequenced(10, 0)).unwrap().is_some());
+        assert!(arbiter.on_event("b", sequenced(9, 1)).unwrap().is_some());
+        assert!(arbiter.on_event("b", sequenced(8, 1)).unwrap().is_none());
+        assert_eq!(arbiter.source("b").unwrap().stale, 1);
+    }
+
+    #[test]
+    fn test_dedup_by_timestamp_and_payload() {
+        let mut arbiter = arbiter();
+        assert!(arbiter.on_event("a", trade(100.0, 5, 6)).unwrap().is_some());
+        // Same time, different trade
+        assert!(arbiter.on_event("a", trade(101.0, 5, 6)).unwrap().is_some());
+        assert!(arbiter.on_event("b", trade(100.0, 5, 8)).unwrap().is_none());
+        assert!(arbiter.on_event("b", trade(101.0, 5, 8)).unwrap().is_none());
+        assert!(arbiter.on_event("b", trade(102.0, 7, 9)).unwrap().is_some());
+        // Older than what was delivered but never seen: late, not a copy
+        assert!(arbiter.on_event("a", trade(99.0, 6, 10)).unwrap().is_some());
+        assert!(arbiter.on_event("b", trade(99.0, 6, 11)).unwrap().is_none());
+        assert_eq!(arbiter.source("a").unwrap().out_of_order, 1);
+        assert_eq!(arbiter.source("a").unwrap().duplicates, 0);
+        assert_eq!(arbiter.source("b").unwrap().duplicates, 3);
+    }
+
+    #[test]
+    fn test_identical_events_from_one_source_are_not_copies() {
+        let mut arbiter = arbiter();
+        // Two equal trades in the same millisecond on a
+        assert!(arbiter.on_event("a", trade(100.0, 5, 6)).unwrap().is_some());
+        assert!(arbiter.on_event("a", trade(100.0, 5, 6)).unwrap().is_some());
+        assert!(arbiter.on_event("b", trade(100.0, 5, 8)).unwrap().is_none());
+        assert!(arbiter.on_event("b", trade(100.0, 5, 8)).unwrap().is_none());
+        // b has a third one a has not sent (yet)
+        assert!(arbiter.on_event("b", trade(100.0, 5, 9)).unwrap().is_some());
+        assert!(arbiter
+            .on_event("a", trade(100.0, 5, 12))
+            .unwrap()
+            .is_none());
+        assert_eq!(arbiter.source("a").unwrap().delivered, 2);
+        assert_eq!(arbiter.source("a").unwrap().duplicates, 1);
+        assert_eq!(arbiter.source("b").unwrap().delivered, 1);
+        assert_eq!(arbiter.source("b").unwrap().duplicates, 2);
+    }
+
+    #[test]
+    fn test_events_older_than_the_window_are_stale() {
+        let mut arbiter = FeedArbiter::new(ArbiterConfig {
+            dedup_window: 2,
+            ..ArbiterConfig::default()
+        })
+        .unwrap();
+        arbiter.add_source("a", START).unwrap();
+        arbiter.add_source("b", START).unwrap();
+        for ms in 1..=3 {
+            assert!(arbiter
+                .on_event("a", trade(100.0, ms, ms))
+                .unwrap()
+                .is_some());
+        }
+        // The copy at 1ms was forgotten; the one at 2ms is still matched
+        assert!(arbiter.on_event("b", trade(100.0, 1, 4)).unwrap().is_none());
+        assert!(arbiter.on_event("b", trade(100.0, 2, 4)).unwrap().is_none());
+        let b = arbiter.source("b").unwrap();
+        assert_eq!((b.stale, b.duplicates, b.out_of_order), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_failover_on_stall_and_recovery() {
+        let mut arbiter = arbiter();
+        assert_eq!(arbiter.preferred(), Some("a"));
+        arbiter.on_event("a", sequenced(1, 0)).unwrap();
+        arbiter.on_event("b", sequenced(1, 1)).unwrap();
+        arbiter.on_event("b", sequenced(2, 900)).unwrap();
+        assert!(arbiter.poll(START + Duration::from_millis(950)).is_empty());
+
+        // a went silent after its first event; b keeps the stream going
+        assert_eq!(
+            arbiter.poll(START + Duration::from_millis(1000)),
+            vec![
+                ArbiterEvent::Stalled {
+                    source: "a".to_string()
+                },
+                ArbiterEvent::Failover {
+                    from: "a".to_string(),
+                    to: Some("b".to_string())
+                },
+            ]
+        );
+        assert!(arbiter.on_event("b", sequenced(3, 1000)).unwrap().is_some());
+        assert!(arbiter.source("a").unwrap().stalled);
+
+        assert!(arbiter.on_event("a", sequenced(3, 1200)).unwrap().is_none());
+        assert_eq!(
+            arbiter.poll(START + Duration::from_millis(1200)),
+            vec![ArbiterEvent::Recovered {
+                source: "a".to_string()
+            }]
+        );
+        assert_eq!(arbiter.preferred(), Some("b"));
+
+        assert_eq!(
+            arbiter.poll(START + Duration::from_millis(5000)),
+            vec![
+                ArbiterEvent::Stalled {
+                    source: "a".to_string()
+                },
+                ArbiterEvent::Stalled {
+                    source: "b".to_string()
+                },
+                ArbiterEvent::Failover {
+                    from: "b".to_string(),
+                    to: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = ArbiterConfig {
+            lag_smoothing: 0.0,
+            ..ArbiterConfig::default()
+        };
+        assert!(FeedArbiter::new(config).is_err());
+        let mut arbiter = arbiter();
+        assert!(arbiter.add_source("a", START).is_err());
+    }
+}
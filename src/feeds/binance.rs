@@ -0,0 +1,731 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::connector::{Connector, FeedEvent, Venue};
+use super::{disconnected, parse_decimal, parse_levels};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent, ToMarketEvent};
+use crate::orderbook::{BookChange, BookSide, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Venue name used in [`MarketEvent::exchange`]
+pub const EXCHANGE: &str = "binance";
+
+/// Depth events kept while waiting for a REST snapshot
+const MAX_BUFFERED: usize = 10_000;
+
+/// Diff depth event covering update ids `first_update_id..=final_update_id`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthUpdate {
+    pub symbol: String,
+    pub event_time: Timestamp,
+    pub first_update_id: u64,
+    pub final_update_id: u64,
+    pub updates: Vec<LevelUpdate>,
+}
+
+/// REST order book snapshot (`GET /api/v3/depth`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthSnapshot {
+    pub last_update_id: u64,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+impl DepthSnapshot {
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Raw {
+            last_update_id: u64,
+            bids: Vec<[String; 2]>,
+            asks: Vec<[String; 2]>,
+        }
+
+        let raw: Raw = serde_json::from_slice(bytes)?;
+        let levels = |side, levels: &[[String; 2]]| -> Result<Vec<PriceLevel>> {
+            Ok(parse_levels(side, levels)?
+                .into_iter()
+                .map(|u| PriceLevel {
+                    price: u.price,
+                    quantity: u.quantity,
+                })
+                .collect())
+        };
+        Ok(Self {
+            last_update_id: raw.last_update_id,
+            bids: levels(BookSide::Bid, &raw.bids)?,
+            asks: levels(BookSide::Ask, &raw.asks)?,
+        })
+    }
+}
+
+/// Decoded WebSocket payload
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinanceMessage {
+    Depth(DepthUpdate),
+    Trade {
+        symbol: String,
+        id: u64,
+        trade: Trade,
+    },
+    /// Successful reply to a request, e.g. `SUBSCRIBE`
+    Response,
+    /// Failed request, e.g. an invalid stream name
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "e")]
+enum RawEvent {
+    #[serde(rename = "depthUpdate")]
+    Depth {
+        #[serde(rename = "E")]
+        event_time: i64,
+        #[serde(rename = "s")]
+        symbol: String,
+        #[serde(rename = "U")]
+        first_update_id: u64,
+        #[serde(rename = "u")]
+        final_update_id: u64,
+        #[serde(rename = "b")]
+        bids: Vec<[String; 2]>,
+        #[serde(rename = "a")]
+        asks: Vec<[String; 2]>,
+    },
+    #[serde(rename = "trade")]
+    Trade {
+        #[serde(rename = "s")]
+        symbol: String,
+        #[serde(rename = "t")]
+        id: u64,
+        #[serde(rename = "p")]
+        price: String,
+        #[serde(rename = "q")]
+        quantity: String,
+        #[serde(rename = "T")]
+        trade_time: i64,
+        #[serde(rename = "m")]
+        buyer_is_maker: bool,
+    },
+}
+
+/// Parse a raw or combined-stream (`{"stream": .., "data": ..}`) message, or a reply
+pub fn parse_message(text: &str) -> Result<BinanceMessage> {
+    let mut value: Value = serde_json::from_str(text)?;
+    // Replies carry the request id: `{"result":null,"id":1}` or `{"code":2,"msg":..,"id":1}`
+    if value.get("id").is_some() {
+        let error = value.get("error").unwrap_or(&value);
+        return Ok(match error.get("msg").and_then(Value::as_str) {
+            Some(message) => BinanceMessage::Error {
+                message: message.to_string(),
+            },
+            None => BinanceMessage::Response,
+        });
+    }
+    if let Some(data) = value.get_mut("data") {
+        value = data.take();
+    }
+
+    match serde_json::from_value(value)? {
+        RawEvent::Depth {
+            event_time,
+            symbol,
+            first_update_id,
+            final_update_id,
+            bids,
+            asks,
+        } => {
+            let mut updates = parse_levels(BookSide::Bid, &bids)?;
+            updates.extend(parse_levels(BookSide::Ask, &asks)?);
+            Ok(BinanceMessage::Depth(DepthUpdate {
+                symbol,
+                event_time: Timestamp::from_millis(event_time),
+                first_update_id,
+                final_update_id,
+                updates,
+            }))
+        }
+        RawEvent::Trade {
+            symbol,
+            id,
+            price,
+            quantity,
+            trade_time,
+            buyer_is_maker,
+        } => {
+            // A maker buyer means the seller crossed the spread
+            let side = if buyer_is_maker {
+                Side::Sell
+            } else {
+                Side::Buy
+            };
+            Ok(BinanceMessage::Trade {
+                symbol,
+                id,
+                trade: Trade::new(
+                    parse_decimal(&price)?,
+                    parse_decimal(&quantity)?,
+                    side,
+                    Timestamp::from_millis(trade_time),
+                ),
+            })
+        }
+    }
+}
+
+impl ToMarketEvent for BinanceMessage {
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent> {
+        let event = match self {
+            BinanceMessage::Depth(update) => MarketEvent::new(
+                EXCHANGE,
+                &update.symbol,
+                update.event_time,
+                MarketData::BookDelta {
+                    updates: update.updates.clone(),
+                    first_sequence: Some(update.first_update_id),
+                },
+            )
+            .with_sequence(update.final_update_id),
+            BinanceMessage::Trade { symbol, trade, .. } => {
+                MarketEvent::new(EXCHANGE, symbol, trade.timestamp, MarketData::Trade(*trade))
+            }
+            BinanceMessage::Response | BinanceMessage::Error { .. } => return None,
+        };
+        Some(event.with_received(received))
+    }
+}
+
+/// Local book kept in sync with Binance's snapshot + diff depth procedure
+///
+/// Depth events are buffered until [`on_snapshot`](Self::on_snapshot) supplies the
+/// REST snapshot. Buffered events ending at or before the snapshot are dropped, the
+/// first applied event must straddle `last_update_id + 1`, and every later event must
+/// start right after the previous one ended. On a gap the book becomes unsynced and
+/// needs a fresh snapshot.
+#[derive(Debug, Clone)]
+pub struct BookSync {
+    book: OrderBook,
+    buffer: VecDeque<DepthUpdate>,
+    synced: bool,
+}
+
+impl BookSync {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            book: OrderBook::new(symbol.to_string()),
+            buffer: VecDeque::new(),
+            synced: false,
+        }
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Apply a live event, or buffer it while unsynced (returning `None`)
+    pub fn on_update(&mut self, update: DepthUpdate) -> Result<Option<BookChange>> {
+        if !self.synced {
+            if self.buffer.len() == MAX_BUFFERED {
+                self.buffer.pop_front();
+            }
+            self.buffer.push_back(update);
+            return Ok(None);
+        }
+
+        let last = self.book.sequence.unwrap_or(0);
+        if update.final_update_id <= last {
+            return Ok(None);
+        }
+        if update.first_update_id > last + 1 {
+            let received = update.first_update_id;
+            self.synced = false;
+            self.buffer.push_back(update);
+            return Err(MarketDataError::SequenceGap {
+                expected: last + 1,
+                received,
+            });
+        }
+
+        self.apply(&update).map(Some)
+    }
+
+    /// Load the REST snapshot and replay buffered events on top of it
+    ///
+    /// A snapshot older than every buffered event fails with a sequence gap; fetch
+    /// another one once more events have been buffered.
+    pub fn on_snapshot(&mut self, snapshot: &DepthSnapshot) -> Result<()> {
+        // REST snapshots carry no time: stamp with the last buffered event they include
+        let timestamp = self
+            .buffer
+            .iter()
+            .rev()
+            .find(|u| u.final_update_id <= snapshot.last_update_id)
+            .map_or(self.book.last_update, |u| u.event_time);
+        self.book.apply_snapshot(
+            &snapshot.bids,
+            &snapshot.asks,
+            snapshot.last_update_id,
+            timestamp,
+        )?;
+        self.synced = true;
+
+        let mut buffered = std::mem::take(&mut self.buffer);
+        buffered.retain(|u| u.final_update_id > snapshot.last_update_id);
+        if let Some(first) = buffered.front() {
+            if first.first_update_id > snapshot.last_update_id + 1 {
+                self.synced = false;
+                let received = first.first_update_id;
+                self.buffer = buffered;
+                return Err(MarketDataError::SequenceGap {
+                    expected: snapshot.last_update_id + 1,
+                    received,
+                });
+            }
+        }
+
+        for update in buffered {
+            self.on_update(update)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, update: &DepthUpdate) -> Result<BookChange> {
+        let change = self
+            .book
+            .apply_updates(&update.updates, update.event_time)?;
+        self.book.sequence = Some(update.final_update_id);
+        Ok(change)
+    }
+}
+
+/// Endpoints and stream options
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinanceConfig {
+    pub ws_url: String,
+    pub rest_url: String,
+    /// Levels requested in the REST snapshot
+    pub depth_limit: u32,
+    /// Diff depth push interval, 100 or 1000 ms
+    pub update_speed_ms: u32,
+}
+
+impl Default for BinanceConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: "wss://stream.binance.com:9443".to_string(),
+            rest_url: "https://api.binance.com".to_string(),
+            depth_limit: 1000,
+            update_speed_ms: 100,
+        }
+    }
+}
+
+impl BinanceConfig {
+    /// Depth and trade stream names of every symbol
+    pub fn streams(&self, symbols: &[String]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| s.to_lowercase())
+            .flat_map(|s| {
+                [
+                    format!("{s}@depth@{}ms", self.update_speed_ms),
+                    format!("{s}@trade"),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Binance protocol and the books of the subscribed symbols, synced from REST
+/// snapshots
+#[derive(Debug, Clone)]
+pub struct BinanceVenue {
+    config: BinanceConfig,
+    /// Combined stream endpoint, which wraps events with their stream name
+    url: String,
+    books: HashMap<String, BookSync>,
+    /// Symbols whose books wait for a REST snapshot
+    unsynced: Vec<String>,
+}
+
+impl Default for BinanceVenue {
+    fn default() -> Self {
+        Self::new(BinanceConfig::default())
+    }
+}
+
+impl BinanceVenue {
+    pub fn new(config: BinanceConfig) -> Self {
+        Self {
+            url: format!("{}/stream", config.ws_url),
+            config,
+            books: HashMap::new(),
+            unsynced: Vec::new(),
+        }
+    }
+
+    fn request(&self, method: &str, symbols: &[String]) -> String {
+        // Replies are only checked for errors, so every request shares one id
+        serde_json::json!({ "method": method, "params": self.config.streams(symbols), "id": 1 })
+            .to_string()
+    }
+
+    fn on_depth(&mut self, update: DepthUpdate) -> Result<Vec<FeedEvent>> {
+        let symbol = update.symbol.clone();
+        let sync = self
+            .books
+            .entry(symbol.clone())
+            .or_insert_with(|| BookSync::new(&symbol));
+        if !sync.is_synced() {
+            sync.on_update(update)?;
+            // Also retries snapshots that predated the buffered events
+            if !self.unsynced.contains(&symbol) {
+                self.unsynced.push(symbol);
+            }
+            return Ok(Vec::new());
+        }
+
+        match sync.on_update(update) {
+            Ok(change) => Ok(change
+                .map(|change| FeedEvent::Book { symbol, change })
+                .into_iter()
+                .collect()),
+            Err(MarketDataError::SequenceGap { .. }) => {
+                self.books.remove(&symbol);
+                Ok(vec![FeedEvent::Resubscribed { symbol }])
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Venue for BinanceVenue {
+    fn name(&self) -> &str {
+        EXCHANGE
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("SUBSCRIBE", symbols)]
+    }
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("UNSUBSCRIBE", symbols)]
+    }
+
+    fn on_text(&mut self, text: &str, _received: Timestamp) -> Result<Vec<FeedEvent>> {
+        match parse_message(text)? {
+            BinanceMessage::Depth(update) => self.on_depth(update),
+            BinanceMessage::Trade { symbol, trade, .. } => {
+                Ok(vec![FeedEvent::Trade { symbol, trade }])
+            }
+            BinanceMessage::Response => Ok(Vec::new()),
+            BinanceMessage::Error { message } => Err(disconnected(message)),
+        }
+    }
+
+    fn snapshot_requests(&mut self) -> Vec<(String, String)> {
+        self.unsynced
+            .drain(..)
+            .map(|symbol| {
+                let url = format!(
+                    "{}/api/v3/depth?symbol={symbol}&limit={}",
+                    self.config.rest_url, self.config.depth_limit
+                );
+                (symbol, url)
+            })
+            .collect()
+    }
+
+    fn on_snapshot(
+        &mut self,
+        symbol: &str,
+        body: &[u8],
+        _received: Timestamp,
+    ) -> Result<Vec<FeedEvent>> {
+        let snapshot = DepthSnapshot::from_json(body)?;
+        let Some(sync) = self.books.get_mut(symbol) else {
+            return Ok(Vec::new());
+        };
+        match sync.on_snapshot(&snapshot) {
+            // Snapshot predates the buffered events: retry after the next event
+            Err(MarketDataError::SequenceGap { .. }) => Ok(Vec::new()),
+            Err(e) => Err(e),
+            Ok(()) => Ok(vec![FeedEvent::Synced {
+                symbol: symbol.to_string(),
+            }]),
+        }
+    }
+
+    /// Synchronized book of `symbol`, if it has received its snapshot
+    fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books
+            .get(symbol)
+            .filter(|sync| sync.is_synced())
+            .map(BookSync::book)
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+        self.unsynced.clear();
+    }
+}
+
+/// WebSocket client for Binance books and trades
+///
+/// Symbols use Binance's names, e.g.
+/// `BinanceFeed::connect(BinanceVenue::default(), &["BTCUSDT"])`.
+pub type BinanceFeed = Connector<BinanceVenue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(first: u64, last: u64, bids: &[(f64, f64)]) -> DepthUpdate {
+        DepthUpdate {
+            symbol: "BTCUSDT".to_string(),
+            event_time: Timestamp::from_millis(last as i64),
+            first_update_id: first,
+            final_update_id: last,
+            updates: bids.iter().map(|&(p, q)| LevelUpdate::bid(p, q)).collect(),
+        }
+    }
+
+    fn snapshot(last_update_id: u64) -> DepthSnapshot {
+        DepthSnapshot {
+            last_update_id,
+            bids: vec![PriceLevel {
+                price: 100.0,
+                quantity: 1.0,
+            }],
+            asks: vec![PriceLevel {
+                price: 101.0,
+                quantity: 1.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let text = r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1700000000000,
+            "s":"BTCUSDT","U":157,"u":160,"b":[["0.0024","10"]],"a":[["0.0026","0"]]}}"#;
+        let BinanceMessage::Depth(update) = parse_message(text).unwrap() else {
+            panic!("expected depth");
+        };
+        assert_eq!((update.first_update_id, update.final_update_id), (157, 160));
+        assert_eq!(
+            update.updates,
+            vec![
+                LevelUpdate::bid(0.0024, 10.0),
+                LevelUpdate::ask(0.0026, 0.0)
+            ]
+        );
+
+        let text = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":12345,"p":"0.001","q":"100",
+            "T":1672515782136,"m":true,"M":true}"#;
+        let BinanceMessage::Trade { id, trade, .. } = parse_message(text).unwrap() else {
+            panic!("expected trade");
+        };
+        assert_eq!(id, 12345);
+        assert_eq!(
+            trade,
+            Trade::new(
+                0.001,
+                100.0,
+                Side::Sell,
+                Timestamp::from_millis(1672515782136)
+            )
+        );
+
+        assert!(matches!(
+            parse_message(r#"{"e":"trade","s":"X","t":1,"p":"abc","q":"1","T":1,"m":false}"#),
+            Err(MarketDataError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_market_event_conversion() {
+        let message = BinanceMessage::Depth(depth(96, 102, &[(100.0, 2.0)]));
+        let event = message
+            .to_market_event(Timestamp::from_millis(110))
+            .unwrap();
+        assert_eq!(event.exchange, EXCHANGE);
+        assert_eq!(event.sequence, Some(102));
+        assert_eq!(event.latency(), std::time::Duration::from_millis(8));
+
+        // A book synced at 100 accepts the range delta through the normalized path
+        let mut book = OrderBook::new("BTCUSDT".to_string());
+        let snapshot = snapshot(100);
+        book.apply_snapshot(&snapshot.bids, &snapshot.asks, 100, Timestamp::EPOCH)
+            .unwrap();
+        event.apply_to(&mut book).unwrap();
+        assert_eq!(book.sequence, Some(102));
+        assert_eq!(book.best_bid(), Some((100.0, 2.0)));
+    }
+
+    #[test]
+    fn test_snapshot_json() {
+        let json = br#"{"lastUpdateId":1027024,"bids":[["4.00000000","431.00000000"]],"asks":[["4.00000200","12.00000000"]]}"#;
+        let snapshot = DepthSnapshot::from_json(json).unwrap();
+        assert_eq!(snapshot.last_update_id, 1027024);
+        assert_eq!(snapshot.bids[0].quantity, 431.0);
+        assert_eq!(snapshot.asks[0].price, 4.000002);
+    }
+
+    #[test]
+    fn test_sync_drops_stale_and_replays_buffer() {
+        let mut sync = BookSync::new("BTCUSDT");
+        assert_eq!(sync.on_update(depth(90, 95, &[(99.0, 5.0)])).unwrap(), None);
+        assert_eq!(
+            sync.on_update(depth(96, 102, &[(100.0, 2.0)])).unwrap(),
+            None
+        );
+        assert_eq!(
+            sync.on_update(depth(103, 104, &[(100.5, 1.0)])).unwrap(),
+            None
+        );
+
+        sync.on_snapshot(&snapshot(100)).unwrap();
+        assert!(sync.is_synced());
+        assert_eq!(sync.book().sequence, Some(104));
+        assert_eq!(sync.book().best_bid(), Some((100.5, 1.0)));
+        // The event ending at 95 predates the snapshot and was dropped
+        assert_eq!(sync.book().top_bids(10).len(), 2);
+
+        let change = sync
+            .on_update(depth(105, 105, &[(100.5, 0.0)]))
+            .unwrap()
+            .unwrap();
+        assert!(change.top_changed);
+        assert_eq!(sync.on_update(depth(100, 105, &[])).unwrap(), None);
+    }
+
+    #[test]
+    fn test_sync_gaps_require_new_snapshot() {
+        let mut sync = BookSync::new("BTCUSDT");
+        sync.on_update(depth(120, 125, &[])).unwrap();
+        assert!(matches!(
+            sync.on_snapshot(&snapshot(100)),
+            Err(MarketDataError::SequenceGap {
+                expected: 101,
+                received: 120
+            })
+        ));
+        assert!(!sync.is_synced());
+
+        sync.on_snapshot(&snapshot(122)).unwrap();
+        assert_eq!(sync.book().sequence, Some(125));
+        assert!(matches!(
+            sync.on_update(depth(127, 130, &[])),
+            Err(MarketDataError::SequenceGap {
+                expected: 126,
+                received: 127
+            })
+        ));
+        assert!(!sync.is_synced());
+    }
+
+    #[test]
+    fn test_subscription_requests() {
+        let venue = BinanceVenue::default();
+        assert_eq!(venue.url(), "wss://stream.binance.com:9443/stream");
+        let symbols = ["BTCUSDT".to_string(), "ethusdt".to_string()];
+        let request: Value = serde_json::from_str(&venue.subscribe_requests(&symbols)[0]).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "method": "SUBSCRIBE",
+                "params": ["btcusdt@depth@100ms", "btcusdt@trade", "ethusdt@depth@100ms", "ethusdt@trade"],
+                "id": 1
+            })
+        );
+
+        assert_eq!(
+            parse_message(r#"{"result":null,"id":1}"#).unwrap(),
+            BinanceMessage::Response
+        );
+        assert_eq!(
+            parse_message(r#"{"code":2,"msg":"Invalid request: unknown stream","id":1}"#).unwrap(),
+            BinanceMessage::Error {
+                message: "Invalid request: unknown stream".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_venue_syncs_from_rest_snapshots() {
+        let text = |first: u64, last: u64| {
+            format!(
+                r#"{{"stream":"btcusdt@depth@100ms","data":{{"e":"depthUpdate","E":1700000000000,
+                "s":"BTCUSDT","U":{first},"u":{last},"b":[["100.5","1"]],"a":[]}}}}"#
+            )
+        };
+        let mut venue = BinanceVenue::default();
+        assert!(venue
+            .on_text(&text(96, 102), Timestamp::EPOCH)
+            .unwrap()
+            .is_empty());
+        assert!(venue
+            .on_text(&text(103, 104), Timestamp::EPOCH)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            venue.snapshot_requests(),
+            vec![(
+                "BTCUSDT".to_string(),
+                "https://api.binance.com/api/v3/depth?symbol=BTCUSDT&limit=1000".to_string()
+            )]
+        );
+        assert!(venue.snapshot_requests().is_empty());
+        assert!(venue.book("BTCUSDT").is_none());
+
+        // Too old for the buffered events: asked again after the next one
+        let body = |last_update_id: u64| {
+            format!(
+                r#"{{"lastUpdateId":{last_update_id},"bids":[["100","1"]],"asks":[["101","1"]]}}"#
+            )
+        };
+        assert!(venue
+            .on_snapshot("BTCUSDT", body(90).as_bytes(), Timestamp::EPOCH)
+            .unwrap()
+            .is_empty());
+        assert!(venue
+            .on_text(&text(105, 105), Timestamp::EPOCH)
+            .unwrap()
+            .is_empty());
+        assert_eq!(venue.snapshot_requests().len(), 1);
+
+        assert_eq!(
+            venue
+                .on_snapshot("BTCUSDT", body(100).as_bytes(), Timestamp::EPOCH)
+                .unwrap(),
+            vec![FeedEvent::Synced {
+                symbol: "BTCUSDT".to_string()
+            }]
+        );
+        assert_eq!(venue.book("BTCUSDT").unwrap().sequence, Some(105));
+
+        let events = venue.on_text(&text(106, 106), Timestamp::EPOCH).unwrap();
+        assert!(matches!(events[..], [FeedEvent::Book { .. }]));
+        assert_eq!(
+            venue.on_text(&text(108, 110), Timestamp::EPOCH).unwrap(),
+            vec![FeedEvent::Resubscribed {
+                symbol: "BTCUSDT".to_string()
+            }]
+        );
+        assert!(venue.book("BTCUSDT").is_none());
+    }
+}
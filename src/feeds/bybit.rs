@@ -0,0 +1,468 @@
+//! Bybit v5 public WebSocket feed
+//!
+//! Books come from the `orderbook.{depth}` topic: a snapshot followed by deltas whose
+//! update id `u` increases by one, so a skipped id means the book must be rebuilt. Bybit
+//! also restarts a stream by sending a delta with `u == 1`, which is treated as a
+//! snapshot.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::connector::{Connector, FeedEvent, Venue};
+use super::{disconnected, parse_decimal, parse_levels};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent, ToMarketEvent};
+use crate::orderbook::{BookDelta, BookSide, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Venue name used in [`MarketEvent::exchange`]
+pub const EXCHANGE: &str = "bybit";
+
+/// Decoded WebSocket payload
+#[derive(Debug, Clone, PartialEq)]
+pub enum BybitMessage {
+    Snapshot {
+        symbol: String,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+        update_id: u64,
+        timestamp: Timestamp,
+    },
+    Delta {
+        symbol: String,
+        updates: Vec<LevelUpdate>,
+        update_id: u64,
+        timestamp: Timestamp,
+    },
+    Trades {
+        symbol: String,
+        trades: Vec<Trade>,
+    },
+    /// Successful reply to a request, e.g. `subscribe` or `ping`
+    Response {
+        op: String,
+    },
+    /// Failed request, e.g. a subscription to an unknown symbol
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct RawResponse {
+    op: String,
+    #[serde(default)]
+    success: Option<bool>,
+    #[serde(default)]
+    ret_msg: String,
+}
+
+#[derive(Deserialize)]
+struct RawTopic {
+    topic: String,
+    #[serde(rename = "type")]
+    kind: String,
+    ts: i64,
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct RawBook {
+    s: String,
+    #[serde(default)]
+    b: Vec<[String; 2]>,
+    #[serde(default)]
+    a: Vec<[String; 2]>,
+    u: u64,
+}
+
+#[derive(Deserialize)]
+struct RawTrade {
+    #[serde(rename = "T")]
+    time: i64,
+    s: String,
+    #[serde(rename = "S")]
+    side: String,
+    v: String,
+    p: String,
+}
+
+fn price_levels(updates: Vec<LevelUpdate>) -> Vec<PriceLevel> {
+    updates
+        .into_iter()
+        .map(|u| PriceLevel {
+            price: u.price,
+            quantity: u.quantity,
+        })
+        .collect()
+}
+
+fn parse_book(kind: &str, timestamp: Timestamp, data: Value) -> Result<BybitMessage> {
+    let book = RawBook::deserialize(data)?;
+    let bids = parse_levels(BookSide::Bid, &book.b)?;
+    let asks = parse_levels(BookSide::Ask, &book.a)?;
+    match kind {
+        "snapshot" => {}
+        "delta" if book.u == 1 => {}
+        "delta" => {
+            return Ok(BybitMessage::Delta {
+                symbol: book.s,
+                updates: bids.into_iter().chain(asks).collect(),
+                update_id: book.u,
+                timestamp,
+            })
+        }
+        other => {
+            return Err(MarketDataError::Malformed(format!(
+                "invalid book message type {other:?}"
+            )))
+        }
+    }
+    Ok(BybitMessage::Snapshot {
+        symbol: book.s,
+        bids: price_levels(bids),
+        asks: price_levels(asks),
+        update_id: book.u,
+        timestamp,
+    })
+}
+
+fn parse_trades(data: Value) -> Result<BybitMessage> {
+    let raw: Vec<RawTrade> = Vec::deserialize(data)?;
+    let symbol = raw.first().map(|t| t.s.clone()).unwrap_or_default();
+    let trades = raw
+        .iter()
+        .map(|trade| {
+            let side = match trade.side.as_str() {
+                "Buy" => Side::Buy,
+                "Sell" => Side::Sell,
+                other => {
+                    return Err(MarketDataError::Malformed(format!(
+                        "invalid side {other:?}"
+                    )))
+                }
+            };
+            Ok(Trade::new(
+                parse_decimal(&trade.p)?,
+                parse_decimal(&trade.v)?,
+                side,
+                Timestamp::from_millis(trade.time),
+            ))
+        })
+        .collect::<Result<_>>()?;
+    Ok(BybitMessage::Trades { symbol, trades })
+}
+
+/// Parse a message of the v5 public WebSocket API
+pub fn parse_message(text: &str) -> Result<BybitMessage> {
+    let value: Value = serde_json::from_str(text)?;
+    if value.get("op").is_some() {
+        let response = RawResponse::deserialize(value)?;
+        return Ok(match response.success {
+            Some(false) => BybitMessage::Error {
+                message: format!("{}: {}", response.op, response.ret_msg),
+            },
+            _ => BybitMessage::Response { op: response.op },
+        });
+    }
+
+    let message = RawTopic::deserialize(value)?;
+    let timestamp = Timestamp::from_millis(message.ts);
+    match message.topic.split('.').next() {
+        Some("orderbook") => parse_book(&message.kind, timestamp, message.data),
+        Some("publicTrade") => parse_trades(message.data),
+        _ => Err(MarketDataError::Malformed(format!(
+            "unsupported topic {:?}",
+            message.topic
+        ))),
+    }
+}
+
+impl ToMarketEvent for BybitMessage {
+    /// The first trade of a batch for trade messages
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent> {
+        let event = match self {
+            BybitMessage::Snapshot {
+                symbol,
+                bids,
+                asks,
+                update_id,
+                timestamp,
+            } => MarketEvent::new(
+                EXCHANGE,
+                symbol,
+                *timestamp,
+                MarketData::BookSnapshot {
+                    bids: bids.clone(),
+                    asks: asks.clone(),
+                },
+            )
+            .with_sequence(*update_id),
+            BybitMessage::Delta {
+                symbol,
+                updates,
+                update_id,
+                timestamp,
+            } => MarketEvent::new(
+                EXCHANGE,
+                symbol,
+                *timestamp,
+                MarketData::BookDelta {
+                    updates: updates.clone(),
+                    first_sequence: None,
+                },
+            )
+            .with_sequence(*update_id),
+            BybitMessage::Trades { symbol, trades } => {
+                let trade = trades.first()?;
+                MarketEvent::new(EXCHANGE, symbol, trade.timestamp, MarketData::Trade(*trade))
+            }
+            BybitMessage::Response { .. } | BybitMessage::Error { .. } => return None,
+        };
+        Some(event.with_received(received))
+    }
+}
+
+/// Bybit protocol for [`Connector`], keeping one sequenced book per symbol
+#[derive(Debug, Clone)]
+pub struct BybitVenue {
+    ws_url: String,
+    depth: usize,
+    books: HashMap<String, OrderBook>,
+}
+
+impl Default for BybitVenue {
+    fn default() -> Self {
+        Self::new("wss://stream.bybit.com/v5/public/spot", 50)
+    }
+}
+
+impl BybitVenue {
+    /// `depth` is one of the depths Bybit offers for the market, e.g. 1, 50 or 200 for spot
+    pub fn new(ws_url: &str, depth: usize) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            depth,
+            books: HashMap::new(),
+        }
+    }
+
+    fn request(&self, op: &str, symbols: &[String]) -> String {
+        let args: Vec<String> = symbols
+            .iter()
+            .flat_map(|s| {
+                [
+                    format!("orderbook.{}.{s}", self.depth),
+                    format!("publicTrade.{s}"),
+                ]
+            })
+            .collect();
+        serde_json::json!({ "op": op, "args": args }).to_string()
+    }
+}
+
+impl Venue for BybitVenue {
+    fn name(&self) -> &str {
+        EXCHANGE
+    }
+
+    fn url(&self) -> &str {
+        &self.ws_url
+    }
+
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("subscribe", symbols)]
+    }
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("unsubscribe", symbols)]
+    }
+
+    /// Bybit drops connections without a ping for 20 seconds
+    fn ping_request(&self) -> Option<String> {
+        Some(r#"{"op":"ping"}"#.to_string())
+    }
+
+    fn on_text(&mut self, text: &str, _received: Timestamp) -> Result<Vec<FeedEvent>> {
+        let events = match parse_message(text)? {
+            BybitMessage::Snapshot {
+                symbol,
+                bids,
+                asks,
+                update_id,
+                timestamp,
+            } => {
+                let mut book = OrderBook::try_new(symbol.clone())?;
+                book.apply_snapshot(&bids, &asks, update_id, timestamp)?;
+                self.books.insert(symbol.clone(), book);
+                vec![FeedEvent::Synced { symbol }]
+            }
+            BybitMessage::Delta {
+                symbol,
+                updates,
+                update_id,
+                timestamp,
+            } => {
+                let Some(book) = self.books.get_mut(&symbol) else {
+                    return Ok(Vec::new());
+                };
+                let delta = BookDelta {
+                    sequence: update_id,
+                    updates,
+                    timestamp,
+                };
+                match book.apply_delta(&delta) {
+                    Ok(change) => vec![FeedEvent::Book { symbol, change }],
+                    Err(MarketDataError::SequenceGap { .. }) => {
+                        self.books.remove(&symbol);
+                        vec![FeedEvent::Resubscribed { symbol }]
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            BybitMessage::Trades { symbol, trades } => trades
+                .into_iter()
+                .map(|trade| FeedEvent::Trade {
+                    symbol: symbol.clone(),
+                    trade,
+                })
+                .collect(),
+            BybitMessage::Response { .. } => Vec::new(),
+            BybitMessage::Error { message } => return Err(disconnected(message)),
+        };
+        Ok(events)
+    }
+
+    fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol)
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+    }
+}
+
+/// WebSocket client for Bybit books and trades
+///
+/// Symbols use Bybit's names, e.g. `BybitFeed::connect(BybitVenue::default(), &["BTCUSDT"])`.
+pub type BybitFeed = Connector<BybitVenue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,
+        "data":{"s":"BTCUSDT","b":[["16493.50","0.006"],["16493.00","0.100"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724}}"#;
+
+    fn delta(update_id: u64) -> String {
+        format!(
+            r#"{{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304485000,
+            "data":{{"s":"BTCUSDT","b":[["16493.50","0"]],"a":[["16600.00","0.5"]],"u":{update_id},"seq":7961638725}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_parse_book_and_trades() {
+        let BybitMessage::Snapshot {
+            bids,
+            asks,
+            update_id,
+            timestamp,
+            ..
+        } = parse_message(SNAPSHOT).unwrap()
+        else {
+            panic!("expected snapshot");
+        };
+        assert_eq!(bids.len(), 2);
+        assert_eq!(asks[0].price, 16611.0);
+        assert_eq!(update_id, 18521288);
+        assert_eq!(timestamp, Timestamp::from_millis(1672304484978));
+
+        // A restarted stream
+        assert!(matches!(
+            parse_message(&delta(1)).unwrap(),
+            BybitMessage::Snapshot { .. }
+        ));
+
+        let trades = r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,
+            "data":[{"T":1672304486865,"s":"BTCUSDT","S":"Sell","v":"0.001","p":"16578.50","L":"PlusTick","i":"2290000000","BT":false}]}"#;
+        let message = parse_message(trades).unwrap();
+        assert_eq!(
+            message,
+            BybitMessage::Trades {
+                symbol: "BTCUSDT".to_string(),
+                trades: vec![Trade::new(
+                    16578.5,
+                    0.001,
+                    Side::Sell,
+                    Timestamp::from_millis(1672304486865)
+                )],
+            }
+        );
+        let event = message.to_market_event(Timestamp::EPOCH).unwrap();
+        assert_eq!(event.exchange, EXCHANGE);
+    }
+
+    #[test]
+    fn test_responses() {
+        assert_eq!(
+            parse_message(r#"{"success":true,"ret_msg":"pong","conn_id":"abc","op":"ping"}"#)
+                .unwrap(),
+            BybitMessage::Response {
+                op: "ping".to_string()
+            }
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"success":false,"ret_msg":"Invalid symbol","conn_id":"abc","op":"subscribe"}"#
+            )
+            .unwrap(),
+            BybitMessage::Error {
+                message: "subscribe: Invalid symbol".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_venue_applies_deltas_and_resyncs_on_gap() {
+        let mut venue = BybitVenue::default();
+        assert!(venue
+            .on_text(&delta(18521289), Timestamp::EPOCH)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            venue.on_text(SNAPSHOT, Timestamp::EPOCH).unwrap(),
+            vec![FeedEvent::Synced {
+                symbol: "BTCUSDT".to_string()
+            }]
+        );
+        let events = venue.on_text(&delta(18521289), Timestamp::EPOCH).unwrap();
+        assert!(matches!(events[..], [FeedEvent::Book { .. }]));
+        let book = venue.book("BTCUSDT").unwrap();
+        assert_eq!(book.best_bid(), Some((16493.0, 0.1)));
+        assert_eq!(book.best_ask(), Some((16600.0, 0.5)));
+
+        assert_eq!(
+            venue.on_text(&delta(18521291), Timestamp::EPOCH).unwrap(),
+            vec![FeedEvent::Resubscribed {
+                symbol: "BTCUSDT".to_string()
+            }]
+        );
+        assert!(venue.book("BTCUSDT").is_none());
+    }
+
+    #[test]
+    fn test_subscription_requests() {
+        let venue = BybitVenue::default();
+        let request: Value =
+            serde_json::from_str(&venue.subscribe_requests(&["BTCUSDT".to_string()])[0]).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({"op": "subscribe", "args": ["orderbook.50.BTCUSDT", "publicTrade.BTCUSDT"]})
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::connector::{Connector, FeedEvent, Venue};
+use super::{disconnected, parse_decimal, parse_levels};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent, ToMarketEvent};
+use crate::orderbook::{BookSide, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Venue name used in [`MarketEvent::exchange`]
+pub const EXCHANGE: &str = "coinbase";
+
+/// Decoded WebSocket payload
+#[derive(Debug, Clone)]
+pub enum CoinbaseMessage {
+    /// Full book sent after subscribing to a level2 channel
+    Snapshot {
+        product_id: String,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+    },
+    L2Update {
+        product_id: String,
+        timestamp: Timestamp,
+        updates: Vec<LevelUpdate>,
+    },
+    /// Trade from the matches channel; `last_match` replays count as well
+    Match {
+        product_id: String,
+        trade_id: u64,
+        sequence: u64,
+        trade: Trade,
+    },
+    Heartbeat {
+        product_id: String,
+        sequence: u64,
+    },
+    Subscriptions,
+    /// Error reported by the server, e.g. for an unknown product
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RawMessage {
+    Snapshot {
+        product_id: String,
+        bids: Vec<[String; 2]>,
+        asks: Vec<[String; 2]>,
+    },
+    L2update {
+        product_id: String,
+        time: DateTime<Utc>,
+        changes: Vec<[String; 3]>,
+    },
+    #[serde(alias = "last_match")]
+    Match {
+        product_id: String,
+        trade_id: u64,
+        sequence: u64,
+        time: DateTime<Utc>,
+        price: String,
+        size: String,
+        side: String,
+    },
+    Heartbeat {
+        product_id: String,
+        sequence: u64,
+    },
+    Subscriptions,
+    Error {
+        message: String,
+        #[serde(default)]
+        reason: String,
+    },
+}
+
+fn side(value: &str) -> Result<Side> {
+    match value {
+        "buy" => Ok(Side::Buy),
+        "sell" => Ok(Side::Sell),
+        _ => Err(MarketDataError::Malformed(format!(
+            "invalid side {value:?}"
+        ))),
+    }
+}
+
+fn price_levels(side: BookSide, levels: &[[String; 2]]) -> Result<Vec<PriceLevel>> {
+    Ok(parse_levels(side, levels)?
+        .into_iter()
+        .map(|u| PriceLevel {
+            price: u.price,
+            quantity: u.quantity,
+        })
+        .collect())
+}
+
+pub fn parse_message(text: &str) -> Result<CoinbaseMessage> {
+    Ok(match serde_json::from_str(text)? {
+        RawMessage::Snapshot {
+            product_id,
+            bids,
+            asks,
+        } => CoinbaseMessage::Snapshot {
+            product_id,
+            bids: price_levels(BookSide::Bid, &bids)?,
+            asks: price_levels(BookSide::Ask, &asks)?,
+        },
+        RawMessage::L2update {
+            product_id,
+            time,
+            changes,
+        } => {
+            let updates = changes
+                .iter()
+                .map(|[change_side, price, size]| {
+                    let side = match side(change_side)? {
+                        Side::Buy => BookSide::Bid,
+                        Side::Sell => BookSide::Ask,
+                    };
+                    Ok(LevelUpdate {
+                        side,
+                        price: parse_decimal(price)?,
+                        quantity: parse_decimal(size)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            CoinbaseMessage::L2Update {
+                product_id,
+                timestamp: Timestamp::from(time),
+                updates,
+            }
+        }
+        RawMessage::Match {
+            product_id,
+            trade_id,
+            sequence,
+            time,
+            price,
+            size,
+            side: maker_side,
+        } => {
+            // `side` is the maker's; the taker traded the other way
+            let aggressor = match side(&maker_side)? {
+                Side::Buy => Side::Sell,
+                Side::Sell => Side::Buy,
+            };
+            CoinbaseMessage::Match {
+                product_id,
+                trade_id,
+                sequence,
+                trade: Trade::new(
+                    parse_decimal(&price)?,
+                    parse_decimal(&size)?,
+                    aggressor,
+                    Timestamp::from(time),
+                ),
+            }
+        }
+        RawMessage::Heartbeat {
+            product_id,
+            sequence,
+        } => CoinbaseMessage::Heartbeat {
+            product_id,
+            sequence,
+        },
+        RawMessage::Subscriptions => CoinbaseMessage::Subscriptions,
+        RawMessage::Error { message, reason } => CoinbaseMessage::Error {
+            message: if reason.is_empty() {
+                message
+            } else {
+                format!("{message}: {reason}")
+            },
+        },
+    })
+}
+
+impl ToMarketEvent for CoinbaseMessage {
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent> {
+        let event = match self {
+            // Snapshots carry no exchange timestamp
+            CoinbaseMessage::Snapshot {
+                product_id,
+                bids,
+                asks,
+            } => MarketEvent::new(
+                EXCHANGE,
+                product_id,
+                received,
+                MarketData::BookSnapshot {
+                    bids: bids.clone(),
+                    asks: asks.clone(),
+                },
+            ),
+            CoinbaseMessage::L2Update {
+                product_id,
+                timestamp,
+                updates,
+            } => MarketEvent::new(
+                EXCHANGE,
+                product_id,
+                *timestamp,
+                MarketData::BookDelta {
+                    updates: updates.clone(),
+                    first_sequence: None,
+                },
+            ),
+            CoinbaseMessage::Match {
+                product_id,
+                sequence,
+                trade,
+                ..
+            } => MarketEvent::new(
+                EXCHANGE,
+                product_id,
+                trade.timestamp,
+                MarketData::Trade(*trade),
+            )
+            .with_sequence(*sequence),
+            CoinbaseMessage::Heartbeat {
+                product_id,
+                sequence,
+            } => MarketEvent::new(EXCHANGE, product_id, received, MarketData::Heartbeat)
+                .with_sequence(*sequence),
+            CoinbaseMessage::Subscriptions | CoinbaseMessage::Error { .. } => return None,
+        };
+        Some(event.with_received(received))
+    }
+}
+
+/// Coinbase Exchange protocol and the books of the subscribed products
+#[derive(Debug, Clone)]
+pub struct CoinbaseVenue {
+    ws_url: String,
+    book_channel: String,
+    books: HashMap<String, OrderBook>,
+}
+
+impl Default for CoinbaseVenue {
+    fn default() -> Self {
+        Self::new("wss://ws-feed.exchange.coinbase.com", "level2_batch")
+    }
+}
+
+impl CoinbaseVenue {
+    /// `book_channel` is a level2 channel; `level2_batch` needs no authentication
+    pub fn new(ws_url: &str, book_channel: &str) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            book_channel: book_channel.to_string(),
+            books: HashMap::new(),
+        }
+    }
+
+    /// Request for the book, matches and heartbeat channels
+    fn request(&self, kind: &str, products: &[String]) -> String {
+        serde_json::json!({
+            "type": kind,
+            "product_ids": products,
+            "channels": [self.book_channel.as_str(), "matches", "heartbeat"],
+        })
+        .to_string()
+    }
+}
+
+impl Venue for CoinbaseVenue {
+    fn name(&self) -> &str {
+        EXCHANGE
+    }
+
+    fn url(&self) -> &str {
+        &self.ws_url
+    }
+
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("subscribe", symbols)]
+    }
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![self.request("unsubscribe", symbols)]
+    }
+
+    /// Snapshots carry no exchange time and are stamped with `received`
+    fn on_text(&mut self, text: &str, received: Timestamp) -> Result<Vec<FeedEvent>> {
+        let events = match parse_message(text)? {
+            CoinbaseMessage::Snapshot {
+                product_id,
+                bids,
+                asks,
+            } => {
+                let mut book = OrderBook::try_new(product_id.clone())?;
+                book.apply_snapshot(&bids, &asks, 0, received)?;
+                // level2 carries no sequence numbers
+                book.sequence = None;
+                self.books.insert(product_id.clone(), book);
+                vec![FeedEvent::Synced { symbol: product_id }]
+            }
+            CoinbaseMessage::L2Update {
+                product_id,
+                timestamp,
+                updates,
+            } => {
+                let Some(book) = self.books.get_mut(&product_id) else {
+                    return Ok(Vec::new());
+                };
+                let change = book.apply_updates(&updates, timestamp)?;
+                vec![FeedEvent::Book {
+                    symbol: product_id,
+                    change,
+                }]
+            }
+            CoinbaseMessage::Match {
+                product_id, trade, ..
+            } => vec![FeedEvent::Trade {
+                symbol: product_id,
+                trade,
+            }],
+            CoinbaseMessage::Error { message } => return Err(disconnected(message)),
+            CoinbaseMessage::Heartbeat { .. } | CoinbaseMessage::Subscriptions => Vec::new(),
+        };
+        Ok(events)
+    }
+
+    fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol)
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+    }
+}
+
+/// WebSocket client for Coinbase books and trades
+///
+/// Products use Coinbase's ids, e.g.
+/// `CoinbaseFeed::connect(CoinbaseVenue::default(), &["BTC-USD"])`.
+pub type CoinbaseFeed = Connector<CoinbaseVenue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_book_messages() {
+        let text = r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["10101.10","0.45054140"]],"asks":[["10102.55","0.57753524"]]}"#;
+        let CoinbaseMessage::Snapshot { bids, asks, .. } = parse_message(text).unwrap() else {
+            panic!("expected snapshot");
+        };
+        assert_eq!((bids[0].price, bids[0].quantity), (10101.10, 0.45054140));
+        assert_eq!(asks[0].price, 10102.55);
+
+        let text = r#"{"type":"l2update","product_id":"BTC-USD","time":"2019-08-14T20:42:27.265Z",
+            "changes":[["buy","10101.80000000","0.162567"],["sell","10102.00","0"]]}"#;
+        let CoinbaseMessage::L2Update {
+            timestamp, updates, ..
+        } = parse_message(text).unwrap()
+        else {
+            panic!("expected l2update");
+        };
+        assert_eq!(timestamp.as_millis(), 1_565_815_347_265);
+        assert_eq!(
+            updates,
+            vec![
+                LevelUpdate::bid(10101.8, 0.162567),
+                LevelUpdate::ask(10102.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_match_uses_taker_side() {
+        let text = r#"{"type":"last_match","trade_id":10,"sequence":50,"maker_order_id":"a","taker_order_id":"b",
+            "time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell"}"#;
+        let CoinbaseMessage::Match {
+            trade_id,
+            sequence,
+            trade,
+            ..
+        } = parse_message(text).unwrap()
+        else {
+            panic!("expected match");
+        };
+        assert_eq!((trade_id, sequence), (10, 50));
+        assert_eq!(trade.side, Side::Buy);
+        assert_eq!(trade.price, 400.23);
+        assert_eq!(trade.timestamp.as_millis(), 1_415_348_367_028);
+
+        assert!(matches!(
+            parse_message(r#"{"type":"error","message":"Failed to subscribe","reason":"BTC-XYZ is not a valid product"}"#),
+            Ok(CoinbaseMessage::Error { message }) if message.ends_with("not a valid product")
+        ));
+        assert!(parse_message(r#"{"type":"l2update","product_id":"X","time":"2019-08-14T20:42:27Z","changes":[["hold","1","1"]]}"#).is_err());
+    }
+
+    #[test]
+    fn test_market_event_conversion() {
+        let received = Timestamp::from_millis(1_565_815_347_300);
+        let text = r#"{"type":"l2update","product_id":"BTC-USD","time":"2019-08-14T20:42:27.265Z","changes":[["buy","1","2"]]}"#;
+        let event = parse_message(text)
+            .unwrap()
+            .to_market_event(received)
+            .unwrap();
+        assert_eq!(
+            (event.exchange.as_str(), event.symbol.as_str()),
+            (EXCHANGE, "BTC-USD")
+        );
+        assert_eq!(event.latency(), Duration::from_millis(35));
+        assert!(matches!(
+            event.data,
+            MarketData::BookDelta {
+                first_sequence: None,
+                ..
+            }
+        ));
+
+        let subscriptions = parse_message(r#"{"type":"subscriptions","channels":[]}"#).unwrap();
+        assert_eq!(subscriptions.to_market_event(received), None);
+    }
+
+    #[test]
+    fn test_subscription_requests() {
+        let venue = CoinbaseVenue::default();
+        let products = ["BTC-USD".to_string()];
+        let value: serde_json::Value =
+            serde_json::from_str(&venue.subscribe_requests(&products)[0]).unwrap();
+        assert_eq!(value["type"], "subscribe");
+        assert_eq!(value["product_ids"][0], "BTC-USD");
+        assert_eq!(value["channels"][0], "level2_batch");
+        let value: serde_json::Value =
+            serde_json::from_str(&venue.unsubscribe_requests(&products)[0]).unwrap();
+        assert_eq!(value["type"], "unsubscribe");
+    }
+
+    #[test]
+    fn test_venue_stamps_snapshots_with_arrival() {
+        let mut venue = CoinbaseVenue::default();
+        let received = Timestamp::from_millis(1_565_815_347_000);
+        let snapshot = r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["100","1"]],"asks":[["101","1"]]}"#;
+        assert_eq!(
+            venue.on_text(snapshot, received).unwrap(),
+            vec![FeedEvent::Synced {
+                symbol: "BTC-USD".to_string()
+            }]
+        );
+        let book = venue.book("BTC-USD").unwrap();
+        assert_eq!((book.last_update, book.sequence), (received, None));
+
+        let update = r#"{"type":"l2update","product_id":"BTC-USD","time":"2019-08-14T20:42:27.265Z","changes":[["buy","100.5","2"]]}"#;
+        let events = venue.on_text(update, received).unwrap();
+        assert!(matches!(events[..], [FeedEvent::Book { .. }]));
+        assert_eq!(
+            venue.book("BTC-USD").unwrap().best_bid(),
+            Some((100.5, 2.0))
+        );
+    }
+}
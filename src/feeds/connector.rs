@@ -0,0 +1,634 @@
+//! Venue-independent WebSocket connection handling
+//!
+//! A [`Venue`] only describes the protocol: where to connect, how to (un)subscribe
+//! and keep the connection alive, and how to turn a text frame into [`FeedEvent`]s
+//! while maintaining its books. [`Connector`] does the rest: it tracks the subscribed
+//! symbols, answers WebSocket pings, sends the venue's application-level pings,
+//! treats a connection that stays silent past a read deadline as dead, reconnects
+//! with exponential backoff (resubscribing everything), resubscribes a single symbol
+//! when the venue reports its book diverged and fetches the REST snapshots of venues
+//! whose books do not start from one on the stream.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use super::disconnected;
+use crate::clock::{SharedClock, SystemClock};
+use crate::error::{ensure, Result};
+use crate::orderbook::{BookChange, OrderBook};
+use crate::time::Timestamp;
+use crate::trades::Trade;
+
+/// Normalized output of a venue, yielded by [`Connector::next_event`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedEvent {
+    /// The symbol's book changed; read it with [`Connector::book`]
+    Book {
+        symbol: String,
+        change: BookChange,
+    },
+    /// The symbol's book was (re)built from a snapshot
+    Synced {
+        symbol: String,
+    },
+    Trade {
+        symbol: String,
+        trade: Trade,
+    },
+    /// The symbol's book diverged (gap or checksum failure) and was dropped; the
+    /// connector resubscribes it to get a new snapshot
+    Resubscribed {
+        symbol: String,
+    },
+    /// The connection dropped and was re-established with every subscription
+    Reconnected,
+}
+
+/// Wire protocol of one venue
+pub trait Venue: Send {
+    /// Venue name, as in [`MarketEvent::exchange`](crate::events::MarketEvent::exchange)
+    fn name(&self) -> &str;
+
+    fn url(&self) -> &str;
+
+    /// Requests subscribing `symbols` to every channel the venue provides
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String>;
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String>;
+
+    /// Application-level keep-alive sent every ping interval, if the venue needs one
+    fn ping_request(&self) -> Option<String> {
+        None
+    }
+
+    /// Decode a text frame, updating the venue's books
+    ///
+    /// Pongs, acknowledgements and other frames without market data yield no events.
+    /// Errors reported by the server should be returned as errors.
+    fn on_text(&mut self, text: &str, received: Timestamp) -> Result<Vec<FeedEvent>>;
+
+    /// REST snapshots to fetch, as `(symbol, url)` pairs, for venues whose books start
+    /// from one instead of a snapshot on the stream; polled after every text frame
+    fn snapshot_requests(&mut self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Load the body of a snapshot asked for by [`snapshot_requests`](Self::snapshot_requests)
+    fn on_snapshot(
+        &mut self,
+        _symbol: &str,
+        _body: &[u8],
+        _received: Timestamp,
+    ) -> Result<Vec<FeedEvent>> {
+        Ok(Vec::new())
+    }
+
+    fn book(&self, symbol: &str) -> Option<&OrderBook>;
+
+    /// Forget all books after the connection dropped
+    fn reset(&mut self);
+}
+
+/// Keep-alive and reconnect policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectorConfig {
+    /// Interval between application-level pings
+    pub ping_interval: Duration,
+    /// A connection receiving no frame at all (data, pong or heartbeat) for this long
+    /// is dropped and reconnected; keep it above the ping interval
+    pub read_timeout: Duration,
+    /// Reconnect attempts after a dropped connection before giving up
+    pub max_reconnects: u32,
+    /// Delay before the first reconnect attempt, doubled after each failure
+    pub reconnect_delay: Duration,
+    /// Upper bound of the reconnect delay
+    pub max_reconnect_delay: Duration,
+}
+
+impl Default for ConnectorConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(20),
+            read_timeout: Duration::from_secs(30),
+            max_reconnects: 5,
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// WebSocket client driving a [`Venue`]
+pub struct Connector<V> {
+    venue: V,
+    config: ConnectorConfig,
+    symbols: Vec<String>,
+    socket: Socket,
+    ping: Interval,
+    /// Arrival of the latest frame, for the read deadline
+    last_frame: Instant,
+    pending: VecDeque<FeedEvent>,
+    http: reqwest::Client,
+    clock: SharedClock,
+}
+
+impl<V: Venue> Connector<V> {
+    pub async fn connect(venue: V, symbols: &[&str]) -> Result<Self> {
+        Self::connect_with(venue, ConnectorConfig::default(), symbols).await
+    }
+
+    pub async fn connect_with(venue: V, config: ConnectorConfig, symbols: &[&str]) -> Result<Self> {
+        ensure(!symbols.is_empty(), "at least one symbol is required")?;
+        ensure(
+            !config.ping_interval.is_zero(),
+            "ping interval must be positive",
+        )?;
+        ensure(
+            !config.read_timeout.is_zero(),
+            "read timeout must be positive",
+        )?;
+        let symbols: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+        let socket = Self::open(&venue, &symbols).await?;
+
+        Ok(Self {
+            ping: Self::ping_timer(config.ping_interval),
+            last_frame: Instant::now(),
+            venue,
+            config,
+            symbols,
+            socket,
+            pending: VecDeque::new(),
+            http: reqwest::Client::new(),
+            clock: SystemClock::shared(),
+        })
+    }
+
+    /// Clock stamping received frames (default: system time)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn ping_timer(period: Duration) -> Interval {
+        let mut ping = tokio::time::interval_at(Instant::now() + period, period);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ping
+    }
+
+    async fn open(venue: &V, symbols: &[String]) -> Result<Socket> {
+        let (mut socket, _) = connect_async(venue.url()).await.map_err(disconnected)?;
+        for request in venue.subscribe_requests(symbols) {
+            socket
+                .send(Message::Text(request))
+                .await
+                .map_err(disconnected)?;
+        }
+        Ok(socket)
+    }
+
+    async fn send(&mut self, requests: Vec<String>) -> Result<()> {
+        for request in requests {
+            self.socket
+                .send(Message::Text(request))
+                .await
+                .map_err(disconnected)?;
+        }
+        Ok(())
+    }
+
+    pub fn venue(&self) -> &V {
+        &self.venue
+    }
+
+    /// Currently subscribed symbols
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    /// Book of `symbol`, once its snapshot has arrived
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.venue.book(symbol)
+    }
+
+    /// Add symbols, kept across reconnects; already subscribed ones are skipped
+    pub async fn subscribe(&mut self, symbols: &[&str]) -> Result<()> {
+        let added: Vec<String> = symbols
+            .iter()
+            .filter(|s| !self.symbols.iter().any(|known| known == *s))
+            .map(|s| s.to_string())
+            .collect();
+        if added.is_empty() {
+            return Ok(());
+        }
+        self.send(self.venue.subscribe_requests(&added)).await?;
+        self.symbols.extend(added);
+        Ok(())
+    }
+
+    /// Remove symbols; their books stop updating
+    pub async fn unsubscribe(&mut self, symbols: &[&str]) -> Result<()> {
+        let removed: Vec<String> = symbols
+            .iter()
+            .filter(|s| self.symbols.iter().any(|known| known == *s))
+            .map(|s| s.to_string())
+            .collect();
+        if removed.is_empty() {
+            return Ok(());
+        }
+        self.send(self.venue.unsubscribe_requests(&removed)).await?;
+        self.symbols.retain(|known| !removed.contains(known));
+        Ok(())
+    }
+
+    /// Wait for the next event, keeping the connection alive and reconnecting
+    /// transparently
+    ///
+    /// A closed or failed connection, or one silent for `read_timeout`, is
+    /// re-established and yields [`FeedEvent::Reconnected`]. Fails with
+    /// [`MarketDataError::FeedDisconnected`](crate::error::MarketDataError::FeedDisconnected)
+    /// once `max_reconnects` consecutive attempts have failed, or when the venue
+    /// reports an error or a snapshot cannot be fetched.
+    pub async fn next_event(&mut self) -> Result<FeedEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                if let FeedEvent::Resubscribed { symbol } = &event {
+                    let symbols = [symbol.clone()];
+                    let mut requests = self.venue.unsubscribe_requests(&symbols);
+                    requests.extend(self.venue.subscribe_requests(&symbols));
+                    self.send(requests).await?;
+                }
+                return Ok(event);
+            }
+
+            let deadline = self.last_frame + self.config.read_timeout;
+            let frame = tokio::select! {
+                frame = self.socket.next() => frame,
+                // Half-open connections never fail a read: give up on silence instead
+                _ = tokio::time::sleep_until(deadline) => None,
+                _ = self.ping.tick() => {
+                    if let Some(ping) = self.venue.ping_request() {
+                        // A failed ping surfaces as a read error on the next poll
+                        let _ = self.socket.send(Message::Text(ping)).await;
+                    }
+                    continue;
+                }
+            };
+            if matches!(frame, Some(Ok(_))) {
+                self.last_frame = Instant::now();
+            }
+            let text = match frame {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Ping(payload))) => {
+                    let _ = self.socket.send(Message::Pong(payload)).await;
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                    self.reconnect().await?;
+                    return Ok(FeedEvent::Reconnected);
+                }
+                Some(Ok(_)) => continue,
+            };
+
+            let events = self.venue.on_text(&text, self.clock.now())?;
+            self.pending.extend(events);
+            self.fetch_snapshots().await?;
+        }
+    }
+
+    async fn fetch_snapshots(&mut self) -> Result<()> {
+        for (symbol, url) in self.venue.snapshot_requests() {
+            let response = self.http.get(url).send().await.map_err(disconnected)?;
+            let body = response
+                .error_for_status()
+                .map_err(disconnected)?
+                .bytes()
+                .await
+                .map_err(disconnected)?;
+            let events = self.venue.on_snapshot(&symbol, &body, self.clock.now())?;
+            self.pending.extend(events);
+        }
+        Ok(())
+    }
+
+    async fn reconnect(&mut self) -> Result<()> {
+        self.venue.reset();
+        self.pending.clear();
+        let mut delay = self.config.reconnect_delay;
+        let mut last_error = disconnected("connection closed");
+
+        for _ in 0..self.config.max_reconnects {
+            tokio::time::sleep(delay).await;
+            match Self::open(&self.venue, &self.symbols).await {
+                Ok(socket) => {
+                    self.socket = socket;
+                    self.ping = Self::ping_timer(self.config.ping_interval);
+                    self.last_frame = Instant::now();
+                    return Ok(());
+                }
+                Err(e) => last_error = e,
+            }
+            delay = (delay * 2).min(self.config.max_reconnect_delay);
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MarketDataError;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// Venue echoing every text frame as a resubscription of that symbol, except
+    /// `need <symbol>`, which asks for a snapshot from `url/<symbol>`
+    struct Echo {
+        url: String,
+        wanted: Vec<String>,
+    }
+
+    fn echo(url: String) -> Echo {
+        Echo {
+            url,
+            wanted: Vec::new(),
+        }
+    }
+
+    impl Venue for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn url(&self) -> &str {
+            &self.url
+        }
+
+        fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+            vec![format!("sub {}", symbols.join(","))]
+        }
+
+        fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+            vec![format!("unsub {}", symbols.join(","))]
+        }
+
+        fn ping_request(&self) -> Option<String> {
+            Some("ping".to_string())
+        }
+
+        fn on_text(&mut self, text: &str, _received: Timestamp) -> Result<Vec<FeedEvent>> {
+            if text == "pong" {
+                return Ok(Vec::new());
+            }
+            if let Some(symbol) = text.strip_prefix("need ") {
+                self.wanted.push(symbol.to_string());
+                return Ok(Vec::new());
+            }
+            Ok(vec![FeedEvent::Resubscribed {
+                symbol: text.to_string(),
+            }])
+        }
+
+        fn snapshot_requests(&mut self) -> Vec<(String, String)> {
+            let base = self.url.replace("ws://", "http://");
+            self.wanted
+                .drain(..)
+                .map(|symbol| (symbol.clone(), format!("{base}/{symbol}")))
+                .collect()
+        }
+
+        fn on_snapshot(
+            &mut self,
+            symbol: &str,
+            body: &[u8],
+            _received: Timestamp,
+        ) -> Result<Vec<FeedEvent>> {
+            assert_eq!(body, format!("{symbol} book").as_bytes());
+            Ok(vec![FeedEvent::Synced {
+                symbol: symbol.to_string(),
+            }])
+        }
+
+        fn book(&self, _symbol: &str) -> Option<&OrderBook> {
+            None
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    async fn read_text(socket: &mut WebSocketStream<TcpStream>) -> String {
+        loop {
+            match socket.next().await.unwrap().unwrap() {
+                Message::Text(text) => return text,
+                _ => continue,
+            }
+        }
+    }
+
+    /// Next request other than a keep-alive
+    async fn read_request(socket: &mut WebSocketStream<TcpStream>) -> String {
+        loop {
+            let text = read_text(socket).await;
+            if text != "ping" {
+                return text;
+            }
+        }
+    }
+
+    async fn listen() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+        accept_async(listener.accept().await.unwrap().0)
+            .await
+            .unwrap()
+    }
+
+    fn fast_config() -> ConnectorConfig {
+        ConnectorConfig {
+            ping_interval: Duration::from_millis(50),
+            reconnect_delay: Duration::from_millis(10),
+            ..ConnectorConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_pings_and_reconnect() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            let mut socket = accept(&listener).await;
+            requests.push(read_request(&mut socket).await);
+            requests.push(read_text(&mut socket).await);
+            socket.send(Message::Text("pong".into())).await.unwrap();
+            socket.send(Message::Text("BTC".into())).await.unwrap();
+            requests.push(read_request(&mut socket).await);
+            requests.push(read_request(&mut socket).await);
+            requests.push(read_request(&mut socket).await);
+            drop(socket);
+
+            let mut socket = accept(&listener).await;
+            requests.push(read_request(&mut socket).await);
+            requests
+        });
+
+        let mut connector = Connector::connect_with(echo(url), fast_config(), &["BTC"])
+            .await
+            .unwrap();
+        assert_eq!(
+            connector.next_event().await.unwrap(),
+            FeedEvent::Resubscribed {
+                symbol: "BTC".to_string()
+            }
+        );
+        connector.subscribe(&["ETH", "BTC"]).await.unwrap();
+        assert_eq!(connector.symbols(), ["BTC", "ETH"]);
+        assert_eq!(
+            connector.next_event().await.unwrap(),
+            FeedEvent::Reconnected
+        );
+
+        let requests = server.await.unwrap();
+        assert_eq!(
+            requests,
+            vec![
+                "sub BTC",
+                "ping",
+                "unsub BTC",
+                "sub BTC",
+                "sub ETH",
+                "sub BTC,ETH"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_is_kept_across_reconnects() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            let mut socket = accept(&listener).await;
+            requests.push(read_request(&mut socket).await);
+            requests.push(read_request(&mut socket).await);
+            drop(socket);
+
+            let mut socket = accept(&listener).await;
+            requests.push(read_request(&mut socket).await);
+            requests
+        });
+
+        let mut connector = Connector::connect_with(echo(url), fast_config(), &["BTC", "ETH"])
+            .await
+            .unwrap();
+        // Unknown symbols are skipped, and nothing is sent when none is left
+        connector.unsubscribe(&["BTC", "XRP"]).await.unwrap();
+        connector.unsubscribe(&["XRP"]).await.unwrap();
+        assert_eq!(connector.symbols(), ["ETH"]);
+        assert_eq!(
+            connector.next_event().await.unwrap(),
+            FeedEvent::Reconnected
+        );
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests, vec!["sub BTC,ETH", "unsub BTC", "sub ETH"]);
+    }
+
+    #[tokio::test]
+    async fn test_silent_connection_is_reconnected() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            // Reads pings but never answers, like a half-open connection
+            let mut first = accept(&listener).await;
+            let mut second = accept(&listener).await;
+            assert_eq!(read_request(&mut second).await, "sub BTC");
+            assert_eq!(read_request(&mut first).await, "sub BTC");
+            assert_eq!(read_text(&mut first).await, "ping");
+        });
+
+        let config = ConnectorConfig {
+            ping_interval: Duration::from_millis(20),
+            read_timeout: Duration::from_millis(100),
+            ..fast_config()
+        };
+        let mut connector = Connector::connect_with(echo(url), config, &["BTC"])
+            .await
+            .unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), connector.next_event()).await;
+        assert_eq!(event.unwrap().unwrap(), FeedEvent::Reconnected);
+        server.await.unwrap();
+
+        let config = ConnectorConfig {
+            read_timeout: Duration::ZERO,
+            ..ConnectorConfig::default()
+        };
+        let url = "ws://127.0.0.1:1".to_string();
+        assert!(matches!(
+            Connector::connect_with(echo(url), config, &["BTC"]).await,
+            Err(MarketDataError::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_are_fetched_for_the_venue() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut socket = accept(&listener).await;
+            read_request(&mut socket).await;
+            socket.send(Message::Text("need BTC".into())).await.unwrap();
+
+            // Plain HTTP on the same port
+            let (mut http, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 1024];
+            let read = http.read(&mut request).await.unwrap();
+            assert!(request[..read].starts_with(b"GET /BTC "));
+            http.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 8\r\n\r\nBTC book")
+                .await
+                .unwrap();
+            socket
+        });
+
+        let mut connector = Connector::connect_with(echo(url), fast_config(), &["BTC"])
+            .await
+            .unwrap();
+        assert_eq!(
+            connector.next_event().await.unwrap(),
+            FeedEvent::Synced {
+                symbol: "BTC".to_string()
+            }
+        );
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_reconnects() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut socket = accept(&listener).await;
+            read_request(&mut socket).await;
+            // Closing the listener refuses every reconnect attempt
+        });
+
+        let config = ConnectorConfig {
+            max_reconnects: 3,
+            ..fast_config()
+        };
+        let mut connector = Connector::connect_with(echo(url), config, &["BTC"])
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert!(matches!(
+            connector.next_event().await,
+            Err(MarketDataError::FeedDisconnected(_))
+        ));
+    }
+}
@@ -0,0 +1,768 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::connector::{Connector, FeedEvent, Venue};
+use super::{disconnected, parse_decimal};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent, ToMarketEvent};
+use crate::orderbook::{BookChange, BookSide, ChecksumFormat, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Venue name used in [`MarketEvent::exchange`]
+pub const EXCHANGE: &str = "kraken";
+
+/// Levels per side covered by Kraken's book checksum
+pub const CHECKSUM_DEPTH: usize = 10;
+
+/// Decoded WebSocket payload
+#[derive(Debug, Clone, PartialEq)]
+pub enum KrakenMessage {
+    /// Full book sent after subscribing, bids and asks best-first
+    Snapshot {
+        pair: String,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+        timestamp: Timestamp,
+        /// Checksum formatting implied by the decimals of the levels, `None` when the
+        /// book is empty
+        format: Option<ChecksumFormat>,
+    },
+    BookUpdate {
+        pair: String,
+        updates: Vec<LevelUpdate>,
+        timestamp: Timestamp,
+        format: Option<ChecksumFormat>,
+        /// CRC32 of the top [`CHECKSUM_DEPTH`] levels after the update
+        checksum: Option<u32>,
+    },
+    Trades {
+        pair: String,
+        trades: Vec<Trade>,
+    },
+    Heartbeat,
+    /// Subscription confirmed or removed
+    SubscriptionStatus {
+        pair: Option<String>,
+        status: String,
+    },
+    /// Error reported by the server, e.g. for an unknown pair
+    Error {
+        message: String,
+    },
+    /// Status events without market data, e.g. `systemStatus` or `pong`
+    Status,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+enum RawEvent {
+    Heartbeat,
+    #[serde(rename_all = "camelCase")]
+    SubscriptionStatus {
+        status: String,
+        #[serde(default)]
+        pair: Option<String>,
+        #[serde(default)]
+        error_message: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Error {
+        #[serde(default)]
+        error_message: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Book payload; one message may carry separate `a` and `b` objects
+#[derive(Deserialize, Default)]
+struct RawBook {
+    #[serde(default, rename = "as")]
+    snapshot_asks: Vec<Vec<String>>,
+    #[serde(default, rename = "bs")]
+    snapshot_bids: Vec<Vec<String>>,
+    #[serde(default)]
+    a: Vec<Vec<String>>,
+    #[serde(default)]
+    b: Vec<Vec<String>>,
+    #[serde(default)]
+    c: Option<String>,
+}
+
+/// `"seconds.micros"` timestamp, parsed without going through `f64`
+fn parse_time(value: &str) -> Result<Timestamp> {
+    let invalid = || MarketDataError::Malformed(format!("invalid timestamp {value:?}"));
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let seconds: i64 = seconds.parse().map_err(|_| invalid())?;
+    let nanos: i64 = format!("{fraction:0<9}").parse().map_err(|_| invalid())?;
+    Ok(Timestamp::from_nanos(seconds * 1_000_000_000 + nanos))
+}
+
+fn decimals(value: &str) -> usize {
+    value
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// Levels of one side with the latest level timestamp and the decimals used
+struct Levels {
+    updates: Vec<LevelUpdate>,
+    timestamp: Option<Timestamp>,
+    format: Option<ChecksumFormat>,
+}
+
+fn parse_levels(side: BookSide, raw: &[Vec<String>]) -> Result<Levels> {
+    let mut levels = Levels {
+        updates: Vec::with_capacity(raw.len()),
+        timestamp: None,
+        format: None,
+    };
+    for level in raw {
+        // `[price, volume, timestamp]`, with a fourth `"r"` on republished updates
+        let [price, quantity, time, ..] = level.as_slice() else {
+            return Err(MarketDataError::Malformed(format!(
+                "invalid level {level:?}"
+            )));
+        };
+        levels.updates.push(LevelUpdate {
+            side,
+            price: parse_decimal(price)?,
+            quantity: parse_decimal(quantity)?,
+        });
+        let time = parse_time(time)?;
+        levels.timestamp = Some(levels.timestamp.map_or(time, |t| t.max(time)));
+        levels.format.get_or_insert(ChecksumFormat::Kraken {
+            price_decimals: decimals(price),
+            quantity_decimals: decimals(quantity),
+        });
+    }
+    Ok(levels)
+}
+
+fn price_levels(levels: &Levels) -> Vec<PriceLevel> {
+    levels
+        .updates
+        .iter()
+        .map(|u| PriceLevel {
+            price: u.price,
+            quantity: u.quantity,
+        })
+        .collect()
+}
+
+fn parse_book(pair: String, payloads: &[Value], received: Timestamp) -> Result<KrakenMessage> {
+    let mut book = RawBook::default();
+    for payload in payloads {
+        let part = RawBook::deserialize(payload)?;
+        book.snapshot_asks.extend(part.snapshot_asks);
+        book.snapshot_bids.extend(part.snapshot_bids);
+        book.a.extend(part.a);
+        book.b.extend(part.b);
+        book.c = part.c.or(book.c);
+    }
+
+    if !book.snapshot_asks.is_empty() || !book.snapshot_bids.is_empty() {
+        let asks = parse_levels(BookSide::Ask, &book.snapshot_asks)?;
+        let bids = parse_levels(BookSide::Bid, &book.snapshot_bids)?;
+        return Ok(KrakenMessage::Snapshot {
+            pair,
+            timestamp: asks.timestamp.max(bids.timestamp).unwrap_or(received),
+            format: asks.format.or(bids.format),
+            bids: price_levels(&bids),
+            asks: price_levels(&asks),
+        });
+    }
+
+    let asks = parse_levels(BookSide::Ask, &book.a)?;
+    let bids = parse_levels(BookSide::Bid, &book.b)?;
+    let checksum = book
+        .c
+        .map(|c| {
+            c.parse()
+                .map_err(|_| MarketDataError::Malformed(format!("invalid checksum {c:?}")))
+        })
+        .transpose()?;
+    Ok(KrakenMessage::BookUpdate {
+        pair,
+        timestamp: asks.timestamp.max(bids.timestamp).unwrap_or(received),
+        format: asks.format.or(bids.format),
+        updates: asks.updates.into_iter().chain(bids.updates).collect(),
+        checksum,
+    })
+}
+
+fn parse_trades(pair: String, payload: &Value) -> Result<KrakenMessage> {
+    let raw: Vec<Vec<String>> = Vec::deserialize(payload)?;
+    let trades = raw
+        .iter()
+        .map(|trade| {
+            // `[price, volume, time, side, orderType, misc]`
+            let [price, quantity, time, side, ..] = trade.as_slice() else {
+                return Err(MarketDataError::Malformed(format!(
+                    "invalid trade {trade:?}"
+                )));
+            };
+            let side = match side.as_str() {
+                "b" => Side::Buy,
+                "s" => Side::Sell,
+                other => {
+                    return Err(MarketDataError::Malformed(format!(
+                        "invalid side {other:?}"
+                    )))
+                }
+            };
+            Ok(Trade::new(
+                parse_decimal(price)?,
+                parse_decimal(quantity)?,
+                side,
+                parse_time(time)?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+    Ok(KrakenMessage::Trades { pair, trades })
+}
+
+/// Parse a message of the v1 public WebSocket API
+///
+/// `received` stamps book messages whose levels carry no timestamp.
+pub fn parse_message(text: &str, received: Timestamp) -> Result<KrakenMessage> {
+    let value: Value = serde_json::from_str(text)?;
+    let Value::Array(items) = value else {
+        return Ok(match RawEvent::deserialize(value)? {
+            RawEvent::Heartbeat => KrakenMessage::Heartbeat,
+            RawEvent::SubscriptionStatus {
+                status,
+                error_message: Some(message),
+                pair,
+            } if status == "error" => KrakenMessage::Error {
+                message: match pair {
+                    Some(pair) => format!("{pair}: {message}"),
+                    None => message,
+                },
+            },
+            RawEvent::SubscriptionStatus { status, pair, .. } => {
+                KrakenMessage::SubscriptionStatus { pair, status }
+            }
+            RawEvent::Error { error_message } => KrakenMessage::Error {
+                message: error_message,
+            },
+            RawEvent::Other => KrakenMessage::Status,
+        });
+    };
+
+    // `[channelID, payload..., channelName, pair]`
+    let (Some(Value::String(pair)), Some(Value::String(channel))) =
+        (items.last(), items.get(items.len().wrapping_sub(2)))
+    else {
+        return Err(MarketDataError::Malformed(format!(
+            "invalid channel message {text:?}"
+        )));
+    };
+    let payloads = &items[1..items.len() - 2];
+    match channel.as_str() {
+        name if name.starts_with("book") => parse_book(pair.clone(), payloads, received),
+        "trade" => match payloads {
+            [payload] => parse_trades(pair.clone(), payload),
+            _ => Err(MarketDataError::Malformed(
+                "trade message without payload".to_string(),
+            )),
+        },
+        other => Err(MarketDataError::Malformed(format!(
+            "unsupported channel {other:?}"
+        ))),
+    }
+}
+
+/// Events of a message; a trade batch yields one event per trade
+pub fn market_events(message: &KrakenMessage, received: Timestamp) -> Vec<MarketEvent> {
+    match message {
+        KrakenMessage::Trades { pair, trades } => trades
+            .iter()
+            .map(|trade| {
+                MarketEvent::new(EXCHANGE, pair, trade.timestamp, MarketData::Trade(*trade))
+                    .with_received(received)
+            })
+            .collect(),
+        other => other.to_market_event(received).into_iter().collect(),
+    }
+}
+
+impl ToMarketEvent for KrakenMessage {
+    /// The first trade of a batch; use [`market_events`] to get all of them
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent> {
+        let event = match self {
+            KrakenMessage::Snapshot {
+                pair,
+                bids,
+                asks,
+                timestamp,
+                ..
+            } => MarketEvent::new(
+                EXCHANGE,
+                pair,
+                *timestamp,
+                MarketData::BookSnapshot {
+                    bids: bids.clone(),
+                    asks: asks.clone(),
+                },
+            ),
+            KrakenMessage::BookUpdate {
+                pair,
+                updates,
+                timestamp,
+                ..
+            } => MarketEvent::new(
+                EXCHANGE,
+                pair,
+                *timestamp,
+                MarketData::BookDelta {
+                    updates: updates.clone(),
+                    first_sequence: None,
+                },
+            ),
+            KrakenMessage::Trades { pair, trades } => {
+                let trade = trades.first()?;
+                MarketEvent::new(EXCHANGE, pair, trade.timestamp, MarketData::Trade(*trade))
+            }
+            KrakenMessage::Heartbeat
+            | KrakenMessage::SubscriptionStatus { .. }
+            | KrakenMessage::Error { .. }
+            | KrakenMessage::Status => return None,
+        };
+        Some(event.with_received(received))
+    }
+}
+
+/// Book of one pair, kept to the subscribed depth and verified against the checksum
+///
+/// Kraken does not send deletions for levels pushed out of the subscribed depth by
+/// better ones, so levels past `depth` are dropped after every update. A checksum
+/// mismatch means the book diverged and must be rebuilt from a new snapshot.
+#[derive(Debug, Clone)]
+pub struct KrakenBook {
+    book: OrderBook,
+    depth: usize,
+    format: Option<ChecksumFormat>,
+}
+
+impl KrakenBook {
+    pub fn from_snapshot(
+        pair: &str,
+        bids: &[PriceLevel],
+        asks: &[PriceLevel],
+        timestamp: Timestamp,
+        format: Option<ChecksumFormat>,
+        depth: usize,
+    ) -> Result<Self> {
+        let mut book = OrderBook::try_new(pair.to_string())?;
+        book.apply_snapshot(bids, asks, 0, timestamp)?;
+        // The v1 book channel carries no sequence numbers
+        book.sequence = None;
+        let mut book = Self {
+            book,
+            depth,
+            format,
+        };
+        book.truncate(timestamp)?;
+        Ok(book)
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// CRC32 of the top [`CHECKSUM_DEPTH`] levels in Kraken's format
+    pub fn checksum(&self) -> u32 {
+        // Without any level seen yet the book is empty and decimals do not matter
+        let format = self.format.unwrap_or(ChecksumFormat::Kraken {
+            price_decimals: 0,
+            quantity_decimals: 0,
+        });
+        self.book.checksum(CHECKSUM_DEPTH, format)
+    }
+
+    /// Apply an update, then verify `checksum` when the message carried one
+    ///
+    /// Fails with [`MarketDataError::ChecksumMismatch`] when the book diverged; the
+    /// update stays applied.
+    pub fn apply(
+        &mut self,
+        updates: &[LevelUpdate],
+        timestamp: Timestamp,
+        format: Option<ChecksumFormat>,
+        checksum: Option<u32>,
+    ) -> Result<BookChange> {
+        self.format = self.format.or(format);
+        let change = self.book.apply_updates(updates, timestamp)?;
+        self.truncate(timestamp)?;
+        if let Some(expected) = checksum {
+            let actual = self.checksum();
+            if actual != expected {
+                return Err(MarketDataError::ChecksumMismatch { expected, actual });
+            }
+        }
+        Ok(change)
+    }
+
+    fn truncate(&mut self, timestamp: Timestamp) -> Result<()> {
+        let removed: Vec<LevelUpdate> = self
+            .book
+            .top_bids(usize::MAX)
+            .iter()
+            .skip(self.depth)
+            .map(|level| LevelUpdate::bid(level.price, 0.0))
+            .chain(
+                self.book
+                    .top_asks(usize::MAX)
+                    .iter()
+                    .skip(self.depth)
+                    .map(|level| LevelUpdate::ask(level.price, 0.0)),
+            )
+            .collect();
+        if !removed.is_empty() {
+            self.book.apply_updates(&removed, timestamp)?;
+        }
+        Ok(())
+    }
+}
+
+/// Kraken v1 protocol and the checksum-verified books of the subscribed pairs
+#[derive(Debug, Clone)]
+pub struct KrakenVenue {
+    ws_url: String,
+    depth: usize,
+    books: HashMap<String, KrakenBook>,
+}
+
+impl Default for KrakenVenue {
+    fn default() -> Self {
+        Self::new("wss://ws.kraken.com", 10)
+    }
+}
+
+impl KrakenVenue {
+    /// `depth` is the levels per side: 10, 25, 100, 500 or 1000
+    pub fn new(ws_url: &str, depth: usize) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            depth,
+            books: HashMap::new(),
+        }
+    }
+
+    /// Requests for the book and trade channels
+    fn requests(&self, event: &str, pairs: &[String]) -> Vec<String> {
+        [
+            serde_json::json!({ "name": "book", "depth": self.depth }),
+            serde_json::json!({ "name": "trade" }),
+        ]
+        .into_iter()
+        .map(|subscription| {
+            serde_json::json!({ "event": event, "pair": pairs, "subscription": subscription })
+                .to_string()
+        })
+        .collect()
+    }
+}
+
+impl Venue for KrakenVenue {
+    fn name(&self) -> &str {
+        EXCHANGE
+    }
+
+    fn url(&self) -> &str {
+        &self.ws_url
+    }
+
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        self.requests("subscribe", symbols)
+    }
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        self.requests("unsubscribe", symbols)
+    }
+
+    /// Answered with a `pong` event; Kraken also sends heartbeats on quiet channels
+    fn ping_request(&self) -> Option<String> {
+        Some(r#"{"event":"ping"}"#.to_string())
+    }
+
+    fn on_text(&mut self, text: &str, received: Timestamp) -> Result<Vec<FeedEvent>> {
+        let events = match parse_message(text, received)? {
+            KrakenMessage::Snapshot {
+                pair,
+                bids,
+                asks,
+                timestamp,
+                format,
+            } => {
+                let book =
+                    KrakenBook::from_snapshot(&pair, &bids, &asks, timestamp, format, self.depth)?;
+                self.books.insert(pair.clone(), book);
+                vec![FeedEvent::Synced { symbol: pair }]
+            }
+            KrakenMessage::BookUpdate {
+                pair,
+                updates,
+                timestamp,
+                format,
+                checksum,
+            } => {
+                let Some(book) = self.books.get_mut(&pair) else {
+                    return Ok(Vec::new());
+                };
+                match book.apply(&updates, timestamp, format, checksum) {
+                    Ok(change) => vec![FeedEvent::Book {
+                        symbol: pair,
+                        change,
+                    }],
+                    Err(MarketDataError::ChecksumMismatch { .. }) => {
+                        self.books.remove(&pair);
+                        vec![FeedEvent::Resubscribed { symbol: pair }]
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            KrakenMessage::Trades { pair, trades } => trades
+                .into_iter()
+                .map(|trade| FeedEvent::Trade {
+                    symbol: pair.clone(),
+                    trade,
+                })
+                .collect(),
+            KrakenMessage::Error { message } => return Err(disconnected(message)),
+            KrakenMessage::Heartbeat
+            | KrakenMessage::SubscriptionStatus { .. }
+            | KrakenMessage::Status => Vec::new(),
+        };
+        Ok(events)
+    }
+
+    fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol).map(KrakenBook::book)
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+    }
+}
+
+/// WebSocket client for Kraken books and trades
+///
+/// Pairs use Kraken's WebSocket names, e.g.
+/// `KrakenFeed::connect(KrakenVenue::default(), &["XBT/USD"])`.
+pub type KrakenFeed = Connector<KrakenVenue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::checksum::crc32;
+
+    const RECEIVED: Timestamp = Timestamp::from_millis(1_534_614_300_000);
+
+    const SNAPSHOT: &str = r#"[0,{"as":[["5541.30000","2.50700000","1534614057.321597"],["5541.80000","0.33000000","1534614098.345543"]],
+        "bs":[["5541.20000","1.52900000","1534614248.765567"],["5539.90000","0.30000000","1534614241.769870"]]},"book-10","XBT/USD"]"#;
+
+    fn snapshot_book(depth: usize) -> KrakenBook {
+        let KrakenMessage::Snapshot {
+            pair,
+            bids,
+            asks,
+            timestamp,
+            format,
+        } = parse_message(SNAPSHOT, RECEIVED).unwrap()
+        else {
+            panic!("expected snapshot");
+        };
+        KrakenBook::from_snapshot(&pair, &bids, &asks, timestamp, format, depth).unwrap()
+    }
+
+    #[test]
+    fn test_parse_snapshot_and_updates() {
+        let KrakenMessage::Snapshot {
+            pair,
+            bids,
+            asks,
+            timestamp,
+            format,
+        } = parse_message(SNAPSHOT, RECEIVED).unwrap()
+        else {
+            panic!("expected snapshot");
+        };
+        assert_eq!(pair, "XBT/USD");
+        assert_eq!(
+            bids[0],
+            PriceLevel {
+                price: 5541.2,
+                quantity: 1.529
+            }
+        );
+        assert_eq!(asks[1].price, 5541.8);
+        assert_eq!(timestamp, Timestamp::from_nanos(1_534_614_248_765_567_000));
+        assert_eq!(
+            format,
+            Some(ChecksumFormat::Kraken {
+                price_decimals: 5,
+                quantity_decimals: 8
+            })
+        );
+
+        let text = r#"[1234,{"a":[["5541.30000","0.00000000","1534614335.345903"]]},
+            {"b":[["5541.10000","0.40100000","1534614335.345903","r"]],"c":"974942666"},"book-10","XBT/USD"]"#;
+        let KrakenMessage::BookUpdate {
+            updates, checksum, ..
+        } = parse_message(text, RECEIVED).unwrap()
+        else {
+            panic!("expected update");
+        };
+        assert_eq!(
+            updates,
+            vec![
+                LevelUpdate::ask(5541.3, 0.0),
+                LevelUpdate::bid(5541.1, 0.401)
+            ]
+        );
+        assert_eq!(checksum, Some(974_942_666));
+    }
+
+    #[test]
+    fn test_parse_trades_and_events() {
+        let text = r#"[0,[["5541.20000","0.15850568","1534614057.321597","s","l",""],
+            ["6060.00000","0.02455000","1534614057.324998","b","l",""]],"trade","XBT/USD"]"#;
+        let message = parse_message(text, RECEIVED).unwrap();
+        let events = market_events(&message, RECEIVED);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].data,
+            MarketData::Trade(Trade::new(
+                5541.2,
+                0.15850568,
+                Side::Sell,
+                Timestamp::from_micros(1_534_614_057_321_597)
+            ))
+        );
+        assert_eq!(events[1].exchange, EXCHANGE);
+
+        assert_eq!(
+            parse_message(r#"{"event":"heartbeat"}"#, RECEIVED).unwrap(),
+            KrakenMessage::Heartbeat
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"event":"systemStatus","status":"online","version":"1.0.0"}"#,
+                RECEIVED
+            )
+            .unwrap(),
+            KrakenMessage::Status
+        );
+        assert!(matches!(
+            parse_message(r#"{"event":"subscriptionStatus","status":"error","errorMessage":"Currency pair not supported","pair":"XBT/BTC"}"#, RECEIVED),
+            Ok(KrakenMessage::Error { message }) if message.starts_with("XBT/BTC")
+        ));
+        assert!(parse_message(
+            r#"[0,[["1","1","1","x","l",""]],"trade","XBT/USD"]"#,
+            RECEIVED
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_checksum_verification() {
+        let mut book = snapshot_book(10);
+        assert_eq!(
+            book.checksum(),
+            crc32(b"5541300002507000005541800003300000055412000015290000055399000030000000")
+        );
+
+        let update = [LevelUpdate::bid(5541.2, 2.0)];
+        let mut expected = book.clone();
+        expected
+            .book
+            .update_bid(5541.2, 2.0, Timestamp::EPOCH)
+            .unwrap();
+        let change = book
+            .apply(&update, RECEIVED, None, Some(expected.checksum()))
+            .unwrap();
+        assert!(change.top_changed);
+
+        assert!(matches!(
+            book.apply(
+                &[LevelUpdate::ask(5541.3, 1.0)],
+                RECEIVED,
+                None,
+                Some(expected.checksum())
+            ),
+            Err(MarketDataError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_levels_beyond_depth_are_dropped() {
+        // Truncating the snapshot keeps its time
+        let book = snapshot_book(1);
+        assert_eq!(book.book().top_asks(10).len(), 1);
+        assert_eq!(
+            book.book().last_update,
+            Timestamp::from_nanos(1_534_614_248_765_567_000)
+        );
+
+        let mut book = snapshot_book(2);
+        book.apply(&[LevelUpdate::bid(5541.25, 1.0)], RECEIVED, None, None)
+            .unwrap();
+        let bids = book.book().top_bids(10);
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids[1].price, 5541.2);
+
+        let requests = KrakenVenue::default().subscribe_requests(&["XBT/USD".to_string()]);
+        let value: serde_json::Value = serde_json::from_str(&requests[0]).unwrap();
+        assert_eq!(value["subscription"]["name"], "book");
+        assert_eq!(value["subscription"]["depth"], 10);
+        assert_eq!(value["pair"][0], "XBT/USD");
+        let value: serde_json::Value = serde_json::from_str(&requests[1]).unwrap();
+        assert_eq!(value["subscription"]["name"], "trade");
+    }
+
+    #[test]
+    fn test_venue_resubscribes_on_checksum_mismatch() {
+        let mut venue = KrakenVenue::default();
+        let update = |checksum: u32| {
+            format!(
+                r#"[1234,{{"b":[["5541.20000","2.00000000","1534614335.345903"]],"c":"{checksum}"}},"book-10","XBT/USD"]"#
+            )
+        };
+        assert!(venue.on_text(&update(0), RECEIVED).unwrap().is_empty());
+
+        assert_eq!(
+            venue.on_text(SNAPSHOT, RECEIVED).unwrap(),
+            vec![FeedEvent::Synced {
+                symbol: "XBT/USD".to_string()
+            }]
+        );
+        let mut expected = snapshot_book(10);
+        expected
+            .apply(&[LevelUpdate::bid(5541.2, 2.0)], RECEIVED, None, None)
+            .unwrap();
+        let events = venue
+            .on_text(&update(expected.checksum()), RECEIVED)
+            .unwrap();
+        assert!(matches!(events[..], [FeedEvent::Book { .. }]));
+        assert_eq!(
+            venue.book("XBT/USD").unwrap().best_bid(),
+            Some((5541.2, 2.0))
+        );
+
+        assert_eq!(
+            venue.on_text(&update(0), RECEIVED).unwrap(),
+            vec![FeedEvent::Resubscribed {
+                symbol: "XBT/USD".to_string()
+            }]
+        );
+        assert!(venue.book("XBT/USD").is_none());
+    }
+}
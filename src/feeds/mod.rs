@@ -0,0 +1,52 @@
+//! Live exchange connectors, enabled with the `feeds` feature
+//!
+//! Each venue module pairs a pure, synchronous parser and book synchronizer (usable
+//! with recorded data) with a [`Venue`] describing its protocol; [`Connector`] drives
+//! any of them over WebSocket, and each module names its client, e.g. [`KrakenFeed`].
+
+use std::fmt::Display;
+
+use crate::error::{MarketDataError, Result};
+use crate::orderbook::{BookSide, LevelUpdate};
+
+pub mod arbiter;
+pub mod binance;
+pub mod bybit;
+pub mod coinbase;
+pub mod connector;
+pub mod kraken;
+pub mod okx;
+
+pub use arbiter::{ArbiterConfig, ArbiterEvent, FeedArbiter, SourceStats};
+pub use binance::{BinanceConfig, BinanceFeed, BinanceVenue};
+pub use bybit::{BybitFeed, BybitVenue};
+pub use coinbase::{CoinbaseFeed, CoinbaseVenue};
+pub use connector::{Connector, ConnectorConfig, FeedEvent, Venue};
+pub use kraken::{KrakenFeed, KrakenVenue};
+pub use okx::{OkxFeed, OkxVenue};
+
+/// Connection-level failure of a feed
+pub(crate) fn disconnected(error: impl Display) -> MarketDataError {
+    MarketDataError::FeedDisconnected(error.to_string())
+}
+
+/// Decimal string as sent by exchanges that avoid JSON floats
+pub(crate) fn parse_decimal(value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|_| MarketDataError::Malformed(format!("invalid decimal {value:?}")))
+}
+
+/// `[price, quantity]` string pairs as absolute level updates
+pub(crate) fn parse_levels(side: BookSide, levels: &[[String; 2]]) -> Result<Vec<LevelUpdate>> {
+    levels
+        .iter()
+        .map(|[price, quantity]| {
+            Ok(LevelUpdate {
+                side,
+                price: parse_decimal(price)?,
+                quantity: parse_decimal(quantity)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,598 @@
+//! OKX v5 public WebSocket feed
+//!
+//! The `books` channel sends a snapshot and then updates chained by sequence ids: each
+//! update's `prevSeqId` is the `seqId` of the previous message (ids are not contiguous).
+//! Every message also carries a CRC32 of the top 25 levels, so both a broken chain and
+//! a checksum mismatch mean the book must be rebuilt.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::connector::{Connector, FeedEvent, Venue};
+use super::{disconnected, parse_decimal};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent, ToMarketEvent};
+use crate::orderbook::{BookChange, BookSide, ChecksumFormat, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Venue name used in [`MarketEvent::exchange`]
+pub const EXCHANGE: &str = "okx";
+
+/// Levels per side covered by OKX's book checksum
+pub const CHECKSUM_DEPTH: usize = 25;
+
+/// Decoded WebSocket payload
+#[derive(Debug, Clone, PartialEq)]
+pub enum OkxMessage {
+    Snapshot {
+        inst_id: String,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+        seq_id: i64,
+        checksum: i32,
+        timestamp: Timestamp,
+    },
+    Update {
+        inst_id: String,
+        updates: Vec<LevelUpdate>,
+        /// `seqId` of the message this one follows
+        prev_seq_id: i64,
+        seq_id: i64,
+        checksum: i32,
+        timestamp: Timestamp,
+    },
+    Trades {
+        inst_id: String,
+        trades: Vec<Trade>,
+    },
+    /// Reply to the `ping` keep-alive
+    Pong,
+    /// Subscription confirmed or removed
+    Subscription {
+        event: String,
+    },
+    /// Error reported by the server, e.g. for an unknown instrument
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    event: String,
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    msg: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArg {
+    channel: String,
+    inst_id: String,
+}
+
+#[derive(Deserialize)]
+struct RawPush {
+    arg: RawArg,
+    #[serde(default)]
+    action: Option<String>,
+    data: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBook {
+    #[serde(default)]
+    asks: Vec<Vec<String>>,
+    #[serde(default)]
+    bids: Vec<Vec<String>>,
+    ts: String,
+    checksum: i32,
+    #[serde(default = "no_sequence")]
+    prev_seq_id: i64,
+    #[serde(default = "no_sequence")]
+    seq_id: i64,
+}
+
+fn no_sequence() -> i64 {
+    -1
+}
+
+#[derive(Deserialize)]
+struct RawTrade {
+    px: String,
+    sz: String,
+    side: String,
+    ts: String,
+}
+
+fn parse_millis(value: &str) -> Result<Timestamp> {
+    value
+        .parse()
+        .map(Timestamp::from_millis)
+        .map_err(|_| MarketDataError::Malformed(format!("invalid timestamp {value:?}")))
+}
+
+/// `[price, quantity, deprecated, order count]` levels
+fn parse_levels(side: BookSide, raw: &[Vec<String>]) -> Result<Vec<LevelUpdate>> {
+    raw.iter()
+        .map(|level| {
+            let [price, quantity, ..] = level.as_slice() else {
+                return Err(MarketDataError::Malformed(format!(
+                    "invalid level {level:?}"
+                )));
+            };
+            Ok(LevelUpdate {
+                side,
+                price: parse_decimal(price)?,
+                quantity: parse_decimal(quantity)?,
+            })
+        })
+        .collect()
+}
+
+fn price_levels(updates: Vec<LevelUpdate>) -> Vec<PriceLevel> {
+    updates
+        .into_iter()
+        .map(|u| PriceLevel {
+            price: u.price,
+            quantity: u.quantity,
+        })
+        .collect()
+}
+
+fn parse_book(inst_id: String, action: Option<&str>, data: &[Value]) -> Result<OkxMessage> {
+    let [payload] = data else {
+        return Err(MarketDataError::Malformed(format!(
+            "expected one book per message, got {}",
+            data.len()
+        )));
+    };
+    let book = RawBook::deserialize(payload)?;
+    let bids = parse_levels(BookSide::Bid, &book.bids)?;
+    let asks = parse_levels(BookSide::Ask, &book.asks)?;
+    let timestamp = parse_millis(&book.ts)?;
+    match action {
+        Some("snapshot") => Ok(OkxMessage::Snapshot {
+            inst_id,
+            bids: price_levels(bids),
+            asks: price_levels(asks),
+            seq_id: book.seq_id,
+            checksum: book.checksum,
+            timestamp,
+        }),
+        Some("update") => Ok(OkxMessage::Update {
+            inst_id,
+            updates: bids.into_iter().chain(asks).collect(),
+            prev_seq_id: book.prev_seq_id,
+            seq_id: book.seq_id,
+            checksum: book.checksum,
+            timestamp,
+        }),
+        other => Err(MarketDataError::Malformed(format!(
+            "invalid book action {other:?}"
+        ))),
+    }
+}
+
+fn parse_trades(inst_id: String, data: Vec<Value>) -> Result<OkxMessage> {
+    let trades = data
+        .into_iter()
+        .map(|payload| {
+            let trade = RawTrade::deserialize(payload)?;
+            let side = match trade.side.as_str() {
+                "buy" => Side::Buy,
+                "sell" => Side::Sell,
+                other => {
+                    return Err(MarketDataError::Malformed(format!(
+                        "invalid side {other:?}"
+                    )))
+                }
+            };
+            Ok(Trade::new(
+                parse_decimal(&trade.px)?,
+                parse_decimal(&trade.sz)?,
+                side,
+                parse_millis(&trade.ts)?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+    Ok(OkxMessage::Trades { inst_id, trades })
+}
+
+/// Parse a message of the v5 public WebSocket API
+pub fn parse_message(text: &str) -> Result<OkxMessage> {
+    if text == "pong" {
+        return Ok(OkxMessage::Pong);
+    }
+    let value: Value = serde_json::from_str(text)?;
+    if value.get("event").is_some() {
+        let event = RawEvent::deserialize(value)?;
+        return Ok(match event.event.as_str() {
+            "error" => OkxMessage::Error {
+                message: format!("{} {}", event.code, event.msg),
+            },
+            _ => OkxMessage::Subscription { event: event.event },
+        });
+    }
+
+    let push = RawPush::deserialize(value)?;
+    match push.arg.channel.as_str() {
+        "books" => parse_book(push.arg.inst_id, push.action.as_deref(), &push.data),
+        "trades" => parse_trades(push.arg.inst_id, push.data),
+        other => Err(MarketDataError::Malformed(format!(
+            "unsupported channel {other:?}"
+        ))),
+    }
+}
+
+impl ToMarketEvent for OkxMessage {
+    /// The first trade of a batch for trade messages
+    ///
+    /// Book events carry no sequence: OKX sequence ids are not contiguous, so they
+    /// cannot be checked by [`OrderBookManager`](crate::orderbook::OrderBookManager).
+    fn to_market_event(&self, received: Timestamp) -> Option<MarketEvent> {
+        let event = match self {
+            OkxMessage::Snapshot {
+                inst_id,
+                bids,
+                asks,
+                timestamp,
+                ..
+            } => MarketEvent::new(
+                EXCHANGE,
+                inst_id,
+                *timestamp,
+                MarketData::BookSnapshot {
+                    bids: bids.clone(),
+                    asks: asks.clone(),
+                },
+            ),
+            OkxMessage::Update {
+                inst_id,
+                updates,
+                timestamp,
+                ..
+            } => MarketEvent::new(
+                EXCHANGE,
+                inst_id,
+                *timestamp,
+                MarketData::BookDelta {
+                    updates: updates.clone(),
+                    first_sequence: None,
+                },
+            ),
+            OkxMessage::Trades { inst_id, trades } => {
+                let trade = trades.first()?;
+                MarketEvent::new(
+                    EXCHANGE,
+                    inst_id,
+                    trade.timestamp,
+                    MarketData::Trade(*trade),
+                )
+            }
+            OkxMessage::Pong | OkxMessage::Subscription { .. } | OkxMessage::Error { .. } => {
+                return None
+            }
+        };
+        Some(event.with_received(received))
+    }
+}
+
+/// Book of one instrument, verified against the sequence chain and checksum
+#[derive(Debug, Clone)]
+pub struct OkxBook {
+    book: OrderBook,
+    seq_id: i64,
+}
+
+impl OkxBook {
+    pub fn from_snapshot(
+        inst_id: &str,
+        bids: &[PriceLevel],
+        asks: &[PriceLevel],
+        seq_id: i64,
+        checksum: i32,
+        timestamp: Timestamp,
+    ) -> Result<Self> {
+        let mut book = OrderBook::try_new(inst_id.to_string())?;
+        book.apply_snapshot(bids, asks, seq_id.max(0) as u64, timestamp)?;
+        let book = Self { book, seq_id };
+        book.verify(checksum)?;
+        Ok(book)
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Apply an update following `prev_seq_id`, then verify `checksum`
+    ///
+    /// Fails with [`MarketDataError::SequenceGap`] without touching the book when an
+    /// update was missed, and with [`MarketDataError::ChecksumMismatch`] when the book
+    /// diverged; the update stays applied.
+    pub fn apply(
+        &mut self,
+        updates: &[LevelUpdate],
+        prev_seq_id: i64,
+        seq_id: i64,
+        checksum: i32,
+        timestamp: Timestamp,
+    ) -> Result<BookChange> {
+        if prev_seq_id != self.seq_id {
+            return Err(MarketDataError::SequenceGap {
+                expected: self.seq_id.max(0) as u64,
+                received: prev_seq_id.max(0) as u64,
+            });
+        }
+        let change = self.book.apply_updates(updates, timestamp)?;
+        self.seq_id = seq_id;
+        self.book.sequence = Some(seq_id.max(0) as u64);
+        self.verify(checksum)?;
+        Ok(change)
+    }
+
+    fn verify(&self, checksum: i32) -> Result<()> {
+        let actual = self.book.checksum(CHECKSUM_DEPTH, ChecksumFormat::Okx);
+        if actual as i32 != checksum {
+            return Err(MarketDataError::ChecksumMismatch {
+                expected: checksum as u32,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// OKX protocol for [`Connector`], keeping one verified book per instrument
+#[derive(Debug, Clone)]
+pub struct OkxVenue {
+    ws_url: String,
+    books: HashMap<String, OkxBook>,
+}
+
+impl Default for OkxVenue {
+    fn default() -> Self {
+        Self::new("wss://ws.okx.com:8443/ws/v5/public")
+    }
+}
+
+impl OkxVenue {
+    pub fn new(ws_url: &str) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            books: HashMap::new(),
+        }
+    }
+
+    fn request(op: &str, inst_ids: &[String]) -> String {
+        let args: Vec<Value> = inst_ids
+            .iter()
+            .flat_map(|id| {
+                ["books", "trades"]
+                    .map(|channel| serde_json::json!({ "channel": channel, "instId": id }))
+            })
+            .collect();
+        serde_json::json!({ "op": op, "args": args }).to_string()
+    }
+}
+
+impl Venue for OkxVenue {
+    fn name(&self) -> &str {
+        EXCHANGE
+    }
+
+    fn url(&self) -> &str {
+        &self.ws_url
+    }
+
+    fn subscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![Self::request("subscribe", symbols)]
+    }
+
+    fn unsubscribe_requests(&self, symbols: &[String]) -> Vec<String> {
+        vec![Self::request("unsubscribe", symbols)]
+    }
+
+    /// OKX drops connections idle for 30 seconds
+    fn ping_request(&self) -> Option<String> {
+        Some("ping".to_string())
+    }
+
+    fn on_text(&mut self, text: &str, _received: Timestamp) -> Result<Vec<FeedEvent>> {
+        let events = match parse_message(text)? {
+            OkxMessage::Snapshot {
+                inst_id,
+                bids,
+                asks,
+                seq_id,
+                checksum,
+                timestamp,
+            } => {
+                match OkxBook::from_snapshot(&inst_id, &bids, &asks, seq_id, checksum, timestamp) {
+                    Ok(book) => {
+                        self.books.insert(inst_id.clone(), book);
+                        vec![FeedEvent::Synced { symbol: inst_id }]
+                    }
+                    Err(MarketDataError::ChecksumMismatch { .. }) => {
+                        self.books.remove(&inst_id);
+                        vec![FeedEvent::Resubscribed { symbol: inst_id }]
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            OkxMessage::Update {
+                inst_id,
+                updates,
+                prev_seq_id,
+                seq_id,
+                checksum,
+                timestamp,
+            } => {
+                let Some(book) = self.books.get_mut(&inst_id) else {
+                    return Ok(Vec::new());
+                };
+                match book.apply(&updates, prev_seq_id, seq_id, checksum, timestamp) {
+                    Ok(change) => vec![FeedEvent::Book {
+                        symbol: inst_id,
+                        change,
+                    }],
+                    Err(
+                        MarketDataError::SequenceGap { .. }
+                        | MarketDataError::ChecksumMismatch { .. },
+                    ) => {
+                        self.books.remove(&inst_id);
+                        vec![FeedEvent::Resubscribed { symbol: inst_id }]
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            OkxMessage::Trades { inst_id, trades } => trades
+                .into_iter()
+                .map(|trade| FeedEvent::Trade {
+                    symbol: inst_id.clone(),
+                    trade,
+                })
+                .collect(),
+            OkxMessage::Pong | OkxMessage::Subscription { .. } => Vec::new(),
+            OkxMessage::Error { message } => return Err(disconnected(message)),
+        };
+        Ok(events)
+    }
+
+    fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol).map(OkxBook::book)
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+    }
+}
+
+/// WebSocket client for OKX books and trades
+///
+/// Instruments use OKX's ids, e.g. `OkxFeed::connect(OkxVenue::default(), &["BTC-USDT"])`.
+pub type OkxFeed = Connector<OkxVenue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::checksum::crc32;
+
+    fn checksum(canonical: &str) -> i32 {
+        crc32(canonical.as_bytes()) as i32
+    }
+
+    fn snapshot() -> String {
+        format!(
+            r#"{{"arg":{{"channel":"books","instId":"BTC-USDT"}},"action":"snapshot",
+            "data":[{{"asks":[["8476.98","415","0","13"],["8477","7","0","2"]],"bids":[["8476.97","256","0","12"]],
+            "ts":"1597026383085","checksum":{},"prevSeqId":-1,"seqId":123456}}]}}"#,
+            checksum("8476.97:256:8476.98:415:8477:7")
+        )
+    }
+
+    fn update(prev_seq_id: i64, seq_id: i64, checksum: i32) -> String {
+        format!(
+            r#"{{"arg":{{"channel":"books","instId":"BTC-USDT"}},"action":"update",
+            "data":[{{"asks":[["8476.98","0","0","0"]],"bids":[["8476.5","10","0","1"]],
+            "ts":"1597026383185","checksum":{checksum},"prevSeqId":{prev_seq_id},"seqId":{seq_id}}}]}}"#
+        )
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let OkxMessage::Snapshot {
+            asks,
+            seq_id,
+            timestamp,
+            ..
+        } = parse_message(&snapshot()).unwrap()
+        else {
+            panic!("expected snapshot");
+        };
+        assert_eq!(asks.len(), 2);
+        assert_eq!(seq_id, 123456);
+        assert_eq!(timestamp, Timestamp::from_millis(1597026383085));
+
+        let trades = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},
+            "data":[{"instId":"BTC-USDT","tradeId":"130639474","px":"42219.9","sz":"0.12060306","side":"buy","ts":"1630048897897"}]}"#;
+        assert_eq!(
+            parse_message(trades).unwrap(),
+            OkxMessage::Trades {
+                inst_id: "BTC-USDT".to_string(),
+                trades: vec![Trade::new(
+                    42219.9,
+                    0.12060306,
+                    Side::Buy,
+                    Timestamp::from_millis(1630048897897)
+                )],
+            }
+        );
+
+        assert_eq!(parse_message("pong").unwrap(), OkxMessage::Pong);
+        assert_eq!(
+            parse_message(r#"{"event":"error","code":"60012","msg":"Invalid request"}"#).unwrap(),
+            OkxMessage::Error {
+                message: "60012 Invalid request".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_venue_verifies_sequence_and_checksum() {
+        let mut venue = OkxVenue::default();
+        venue.on_text(&snapshot(), Timestamp::EPOCH).unwrap();
+
+        let good = checksum("8476.97:256:8477:7:8476.5:10");
+        let events = venue
+            .on_text(&update(123456, 123460, good), Timestamp::EPOCH)
+            .unwrap();
+        assert!(matches!(events[..], [FeedEvent::Book { .. }]));
+        assert_eq!(
+            venue.book("BTC-USDT").unwrap().best_ask(),
+            Some((8477.0, 7.0))
+        );
+
+        // Chained to a message that never arrived
+        assert_eq!(
+            venue
+                .on_text(&update(123470, 123480, good), Timestamp::EPOCH)
+                .unwrap(),
+            vec![FeedEvent::Resubscribed {
+                symbol: "BTC-USDT".to_string()
+            }]
+        );
+        assert!(venue.book("BTC-USDT").is_none());
+
+        venue.on_text(&snapshot(), Timestamp::EPOCH).unwrap();
+        assert_eq!(
+            venue
+                .on_text(&update(123456, 123460, good ^ 1), Timestamp::EPOCH)
+                .unwrap(),
+            vec![FeedEvent::Resubscribed {
+                symbol: "BTC-USDT".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_subscription_requests() {
+        let request: Value = serde_json::from_str(
+            &OkxVenue::default().unsubscribe_requests(&["ETH-USDT".to_string()])[0],
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({"op": "unsubscribe", "args": [
+                {"channel": "books", "instId": "ETH-USDT"},
+                {"channel": "trades", "instId": "ETH-USDT"},
+            ]})
+        );
+    }
+}
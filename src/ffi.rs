@@ -0,0 +1,655 @@
+//! C ABI for embedding in C and C++ systems, enabled with the `ffi` feature
+//!
+//! Books and indicators are opaque handles created by `md_*_new` and released with
+//! the matching `md_*_free`. Fallible calls return an [`MdStatus`]; the message of the
+//! last failure on the calling thread is available from [`md_last_error`]. Handles
+//! are not thread-safe: synchronize access to one handle across threads. The header
+//! `include/market_data.h` is generated by cbindgen when building with the feature
+//! and refreshed with `MD_BLESS_HEADER=1 cargo test --features ffi`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+use std::slice;
+
+use crate::error::MarketDataError;
+use crate::indicators::{Indicator, EMA, RSI, SMA, WMA};
+use crate::orderbook::{BookSide, LevelUpdate, OrderBook, PriceLevel};
+use crate::time::Timestamp;
+
+/// Result of a fallible call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidPrice = 2,
+    InvalidQuantity = 3,
+    InvalidParameter = 4,
+    SequenceGap = 5,
+    CrossedBook = 6,
+    LockedBook = 7,
+    Other = 99,
+}
+
+impl From<&MarketDataError> for MdStatus {
+    fn from(error: &MarketDataError) -> Self {
+        match error {
+            MarketDataError::InvalidPrice(_) => MdStatus::InvalidPrice,
+            MarketDataError::InvalidQuantity(_) => MdStatus::InvalidQuantity,
+            MarketDataError::InvalidParameter(_) => MdStatus::InvalidParameter,
+            MarketDataError::SequenceGap { .. } => MdStatus::SequenceGap,
+            MarketDataError::CrossedBook { .. } => MdStatus::CrossedBook,
+            MarketDataError::LockedBook(_) => MdStatus::LockedBook,
+            _ => MdStatus::Other,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn fail(status: MdStatus, message: String) -> MdStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+fn status(result: crate::error::Result<()>) -> MdStatus {
+    match result {
+        Ok(()) => MdStatus::Ok,
+        Err(e) => fail(MdStatus::from(&e), e.to_string()),
+    }
+}
+
+fn null_pointer() -> MdStatus {
+    fail(MdStatus::NullPointer, "null pointer argument".to_string())
+}
+
+/// Copy the last error message of this thread into `buffer` as a NUL-terminated
+/// string, truncated to `capacity - 1` bytes
+///
+/// Returns the full message length in bytes, excluding the terminator.
+///
+/// # Safety
+///
+/// `buffer` must be null or valid for writes of `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn md_last_error(buffer: *mut c_char, capacity: usize) -> usize {
+    LAST_ERROR.with(|last| {
+        let message = last.borrow();
+        if !buffer.is_null() && capacity > 0 {
+            let len = message.len().min(capacity - 1);
+            ptr::copy_nonoverlapping(message.as_ptr(), buffer.cast::<u8>(), len);
+            *buffer.add(len) = 0;
+        }
+        message.len()
+    })
+}
+
+/// Price level as passed to and returned by the book functions
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MdLevel {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Values of [`MdLevelUpdate::side`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdSide {
+    Bid = 0,
+    Ask = 1,
+}
+
+impl MdSide {
+    /// Side for a value received from C, which may be out of range
+    fn from_raw(side: c_int) -> Option<BookSide> {
+        match side {
+            s if s == MdSide::Bid as c_int => Some(BookSide::Bid),
+            s if s == MdSide::Ask as c_int => Some(BookSide::Ask),
+            _ => None,
+        }
+    }
+}
+
+/// Absolute level change; zero quantity removes the level
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MdLevelUpdate {
+    /// An [`MdSide`] value; kept as a plain int so any value C writes is defined
+    pub side: c_int,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Best bid and offer; sizes are zero and prices NaN for an empty side
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MdBbo {
+    pub bid_price: f64,
+    pub bid_size: f64,
+    pub ask_price: f64,
+    pub ask_size: f64,
+}
+
+/// `len` elements at `data`, allowing a null pointer for an empty slice
+unsafe fn slice_or_empty<'a, T>(data: *const T, len: usize) -> Option<&'a [T]> {
+    match (data.is_null(), len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
+
+fn price_levels(levels: &[MdLevel]) -> Vec<PriceLevel> {
+    levels
+        .iter()
+        .map(|level| PriceLevel {
+            price: level.price,
+            quantity: level.quantity,
+        })
+        .collect()
+}
+
+/// New empty book, or null if `symbol` is null, not UTF-8 or not a valid symbol
+///
+/// # Safety
+///
+/// `symbol` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_new(symbol: *const c_char) -> *mut OrderBook {
+    if symbol.is_null() {
+        null_pointer();
+        return ptr::null_mut();
+    }
+    let symbol = match CStr::from_ptr(symbol).to_str() {
+        Ok(symbol) => symbol.to_string(),
+        Err(e) => {
+            fail(
+                MdStatus::InvalidParameter,
+                format!("symbol is not UTF-8: {e}"),
+            );
+            return ptr::null_mut();
+        }
+    };
+    match OrderBook::try_new(symbol) {
+        Ok(book) => Box::into_raw(Box::new(book)),
+        Err(e) => {
+            status(Err(e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Release a book; null is ignored
+///
+/// # Safety
+///
+/// `book` must be null or a handle from [`md_orderbook_new`] not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_free(book: *mut OrderBook) {
+    if !book.is_null() {
+        drop(Box::from_raw(book));
+    }
+}
+
+/// Set one bid level at `timestamp_ns` nanoseconds since the epoch; zero quantity
+/// removes it
+///
+/// # Safety
+///
+/// `book` must be null or a live handle from [`md_orderbook_new`].
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_update_bid(
+    book: *mut OrderBook,
+    price: f64,
+    quantity: f64,
+    timestamp_ns: i64,
+) -> MdStatus {
+    match book.as_mut() {
+        Some(book) => status(book.update_bid(price, quantity, Timestamp::from_nanos(timestamp_ns))),
+        None => null_pointer(),
+    }
+}
+
+/// Set one ask level at `timestamp_ns` nanoseconds since the epoch; zero quantity
+/// removes it
+///
+/// # Safety
+///
+/// `book` must be null or a live handle from [`md_orderbook_new`].
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_update_ask(
+    book: *mut OrderBook,
+    price: f64,
+    quantity: f64,
+    timestamp_ns: i64,
+) -> MdStatus {
+    match book.as_mut() {
+        Some(book) => status(book.update_ask(price, quantity, Timestamp::from_nanos(timestamp_ns))),
+        None => null_pointer(),
+    }
+}
+
+/// Apply `len` level changes atomically, stamped `timestamp_ns` nanoseconds since
+/// the epoch
+///
+/// # Safety
+///
+/// `book` must be null or a live handle; `updates` must point to `len` elements
+/// (or may be null when `len` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_apply_updates(
+    book: *mut OrderBook,
+    updates: *const MdLevelUpdate,
+    len: usize,
+    timestamp_ns: i64,
+) -> MdStatus {
+    let (Some(book), Some(updates)) = (book.as_mut(), slice_or_empty(updates, len)) else {
+        return null_pointer();
+    };
+    let mut converted = Vec::with_capacity(updates.len());
+    for update in updates {
+        let Some(side) = MdSide::from_raw(update.side) else {
+            return fail(
+                MdStatus::InvalidParameter,
+                format!("invalid side {}", update.side),
+            );
+        };
+        converted.push(LevelUpdate {
+            side,
+            price: update.price,
+            quantity: update.quantity,
+        });
+    }
+    status(
+        book.apply_updates(&converted, Timestamp::from_nanos(timestamp_ns))
+            .map(|_| ()),
+    )
+}
+
+/// Replace the whole book with a snapshot taken at `timestamp_ns` nanoseconds since
+/// the epoch; levels are best first
+///
+/// # Safety
+///
+/// `book` must be null or a live handle; `bids` and `asks` must point to
+/// `bid_count` and `ask_count` elements (or may be null when their count is zero).
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_apply_snapshot(
+    book: *mut OrderBook,
+    bids: *const MdLevel,
+    bid_count: usize,
+    asks: *const MdLevel,
+    ask_count: usize,
+    sequence: u64,
+    timestamp_ns: i64,
+) -> MdStatus {
+    let (Some(book), Some(bids), Some(asks)) = (
+        book.as_mut(),
+        slice_or_empty(bids, bid_count),
+        slice_or_empty(asks, ask_count),
+    ) else {
+        return null_pointer();
+    };
+    status(book.apply_snapshot(
+        &price_levels(bids),
+        &price_levels(asks),
+        sequence,
+        Timestamp::from_nanos(timestamp_ns),
+    ))
+}
+
+/// Write the best bid and offer to `out`
+///
+/// # Safety
+///
+/// `book` must be null or a live handle; `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_bbo(book: *const OrderBook, out: *mut MdBbo) -> MdStatus {
+    let (Some(book), false) = (book.as_ref(), out.is_null()) else {
+        return null_pointer();
+    };
+    let (bid_price, bid_size) = book.best_bid().unwrap_or((f64::NAN, 0.0));
+    let (ask_price, ask_size) = book.best_ask().unwrap_or((f64::NAN, 0.0));
+    out.write(MdBbo {
+        bid_price,
+        bid_size,
+        ask_price,
+        ask_size,
+    });
+    MdStatus::Ok
+}
+
+/// Mid price, NaN when a side is empty or `book` is null
+///
+/// # Safety
+///
+/// `book` must be null or a live handle from [`md_orderbook_new`].
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_mid_price(book: *const OrderBook) -> f64 {
+    book.as_ref()
+        .and_then(OrderBook::mid_price)
+        .unwrap_or(f64::NAN)
+}
+
+/// Copy up to `capacity` best bids, highest first, returning how many were written
+///
+/// # Safety
+///
+/// `book` must be null or a live handle; `out` must be valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_top_bids(
+    book: *const OrderBook,
+    out: *mut MdLevel,
+    capacity: usize,
+) -> usize {
+    copy_levels(book, out, capacity, OrderBook::top_bids)
+}
+
+/// Copy up to `capacity` best asks, lowest first, returning how many were written
+///
+/// # Safety
+///
+/// `book` must be null or a live handle; `out` must be valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn md_orderbook_top_asks(
+    book: *const OrderBook,
+    out: *mut MdLevel,
+    capacity: usize,
+) -> usize {
+    copy_levels(book, out, capacity, OrderBook::top_asks)
+}
+
+unsafe fn copy_levels(
+    book: *const OrderBook,
+    out: *mut MdLevel,
+    capacity: usize,
+    top: fn(&OrderBook, usize) -> Vec<PriceLevel>,
+) -> usize {
+    let (Some(book), false) = (book.as_ref(), out.is_null()) else {
+        null_pointer();
+        return 0;
+    };
+    let levels = top(book, capacity);
+    for (i, level) in levels.iter().enumerate() {
+        out.add(i).write(MdLevel {
+            price: level.price,
+            quantity: level.quantity,
+        });
+    }
+    levels.len()
+}
+
+/// Streaming indicator over a series of doubles
+pub struct MdIndicator(Box<dyn Indicator<f64, f64> + Send>);
+
+fn new_indicator<I>(indicator: crate::error::Result<I>) -> *mut MdIndicator
+where
+    I: Indicator<f64, f64> + Send + 'static,
+{
+    match indicator {
+        Ok(indicator) => Box::into_raw(Box::new(MdIndicator(Box::new(indicator)))),
+        Err(e) => {
+            status(Err(e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Simple moving average, or null if `period` is zero
+#[no_mangle]
+pub extern "C" fn md_sma_new(period: usize) -> *mut MdIndicator {
+    new_indicator(SMA::try_new(period))
+}
+
+/// Exponential moving average, or null if `period` is zero
+#[no_mangle]
+pub extern "C" fn md_ema_new(period: usize) -> *mut MdIndicator {
+    new_indicator(EMA::try_new(period))
+}
+
+/// Weighted moving average, or null if `period` is zero
+#[no_mangle]
+pub extern "C" fn md_wma_new(period: usize) -> *mut MdIndicator {
+    new_indicator(WMA::try_new(period))
+}
+
+/// RSI with Wilder smoothing if `wilder`, else simple; null if `period` is zero
+#[no_mangle]
+pub extern "C" fn md_rsi_new(period: usize, wilder: bool) -> *mut MdIndicator {
+    if wilder {
+        new_indicator(RSI::try_wilder(period))
+    } else {
+        new_indicator(RSI::try_new(period))
+    }
+}
+
+/// Feed one value, returning the indicator value or NaN while warming up
+///
+/// # Safety
+///
+/// `indicator` must be null or a live handle from an `md_*_new` indicator constructor.
+#[no_mangle]
+pub unsafe extern "C" fn md_indicator_update(indicator: *mut MdIndicator, value: f64) -> f64 {
+    indicator
+        .as_mut()
+        .and_then(|indicator| indicator.0.update(value))
+        .unwrap_or(f64::NAN)
+}
+
+/// Feed `len` values, writing one output per input to `out` (NaN while warming up)
+///
+/// # Safety
+///
+/// `indicator` must be a live handle; `values` and `out` must point to `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn md_indicator_update_batch(
+    indicator: *mut MdIndicator,
+    values: *const f64,
+    len: usize,
+    out: *mut f64,
+) -> MdStatus {
+    let (Some(indicator), Some(values), false) = (
+        indicator.as_mut(),
+        slice_or_empty(values, len),
+        out.is_null() && len > 0,
+    ) else {
+        return null_pointer();
+    };
+    for (i, &value) in values.iter().enumerate() {
+        out.add(i)
+            .write(indicator.0.update(value).unwrap_or(f64::NAN));
+    }
+    MdStatus::Ok
+}
+
+/// Clear the indicator as if freshly created
+///
+/// # Safety
+///
+/// `indicator` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_indicator_reset(indicator: *mut MdIndicator) {
+    if let Some(indicator) = indicator.as_mut() {
+        indicator.0.reset();
+    }
+}
+
+/// Release an indicator; null is ignored
+///
+/// # Safety
+///
+/// `indicator` must be null or a handle not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn md_indicator_free(indicator: *mut MdIndicator) {
+    if !indicator.is_null() {
+        drop(Box::from_raw(indicator));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        let mut buffer = [0 as c_char; 64];
+        let len = unsafe { md_last_error(buffer.as_mut_ptr(), buffer.len()) };
+        let message = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        assert_eq!(message.to_bytes().len(), len.min(63));
+        message.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_book_lifecycle() {
+        unsafe {
+            let book = md_orderbook_new(c"BTCUSD".as_ptr());
+            assert!(!book.is_null());
+
+            let bids = [MdLevel {
+                price: 100.0,
+                quantity: 1.0,
+            }];
+            let asks = [MdLevel {
+                price: 101.0,
+                quantity: 2.0,
+            }];
+            assert_eq!(
+                md_orderbook_apply_snapshot(book, bids.as_ptr(), 1, asks.as_ptr(), 1, 5, 0),
+                MdStatus::Ok
+            );
+            let updates = [
+                MdLevelUpdate {
+                    side: MdSide::Bid as c_int,
+                    price: 100.5,
+                    quantity: 3.0,
+                },
+                MdLevelUpdate {
+                    side: MdSide::Ask as c_int,
+                    price: 101.0,
+                    quantity: 0.0,
+                },
+                MdLevelUpdate {
+                    side: MdSide::Ask as c_int,
+                    price: 102.0,
+                    quantity: 1.0,
+                },
+            ];
+            assert_eq!(
+                md_orderbook_apply_updates(book, updates.as_ptr(), updates.len(), 1_000),
+                MdStatus::Ok
+            );
+
+            let mut bbo = MdBbo {
+                bid_price: 0.0,
+                bid_size: 0.0,
+                ask_price: 0.0,
+                ask_size: 0.0,
+            };
+            assert_eq!(md_orderbook_bbo(book, &mut bbo), MdStatus::Ok);
+            assert_eq!((bbo.bid_price, bbo.bid_size), (100.5, 3.0));
+            assert_eq!((bbo.ask_price, bbo.ask_size), (102.0, 1.0));
+            assert_eq!(md_orderbook_mid_price(book), 101.25);
+
+            let mut levels = [MdLevel {
+                price: 0.0,
+                quantity: 0.0,
+            }; 4];
+            assert_eq!(md_orderbook_top_bids(book, levels.as_mut_ptr(), 4), 2);
+            assert_eq!(
+                levels[1],
+                MdLevel {
+                    price: 100.0,
+                    quantity: 1.0
+                }
+            );
+            assert_eq!(md_orderbook_top_asks(book, levels.as_mut_ptr(), 4), 1);
+
+            md_orderbook_free(book);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            assert!(md_orderbook_new(ptr::null()).is_null());
+            assert!(md_orderbook_new(c"BTC USD".as_ptr()).is_null());
+            assert!(last_error().contains("invalid characters"));
+
+            let book = md_orderbook_new(c"BTCUSD".as_ptr());
+            assert_eq!(
+                md_orderbook_update_bid(book, -1.0, 1.0, 0),
+                MdStatus::InvalidPrice
+            );
+            assert!(last_error().starts_with("invalid price"));
+            assert_eq!(
+                md_orderbook_update_ask(ptr::null_mut(), 1.0, 1.0, 0),
+                MdStatus::NullPointer
+            );
+            assert!(md_orderbook_mid_price(book).is_nan());
+
+            let updates = [
+                MdLevelUpdate {
+                    side: MdSide::Bid as c_int,
+                    price: 100.0,
+                    quantity: 1.0,
+                },
+                MdLevelUpdate {
+                    side: 7,
+                    price: 101.0,
+                    quantity: 1.0,
+                },
+            ];
+            assert_eq!(
+                md_orderbook_apply_updates(book, updates.as_ptr(), updates.len(), 0),
+                MdStatus::InvalidParameter
+            );
+            assert_eq!(last_error(), "invalid side 7");
+            // Rejected as a whole: the valid update before it was not applied
+            assert!(md_orderbook_mid_price(book).is_nan());
+            md_orderbook_free(book);
+            md_orderbook_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_committed_header_is_current() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/market_data.h"));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/market_data.h");
+        if std::env::var_os("MD_BLESS_HEADER").is_some() {
+            std::fs::write(path, generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(path).unwrap();
+        assert!(
+            committed == generated,
+            "include/market_data.h is stale, rerun with MD_BLESS_HEADER=1"
+        );
+    }
+
+    #[test]
+    fn test_indicators() {
+        unsafe {
+            let sma = md_sma_new(2);
+            assert!(md_indicator_update(sma, 1.0).is_nan());
+            assert_eq!(md_indicator_update(sma, 3.0), 2.0);
+
+            md_indicator_reset(sma);
+            let values = [1.0, 2.0, 3.0];
+            let mut out = [0.0; 3];
+            assert_eq!(
+                md_indicator_update_batch(sma, values.as_ptr(), 3, out.as_mut_ptr()),
+                MdStatus::Ok
+            );
+            assert!(out[0].is_nan());
+            assert_eq!(&out[1..], &[1.5, 2.5]);
+            md_indicator_free(sma);
+
+            assert!(md_ema_new(0).is_null());
+            let rsi = md_rsi_new(14, true);
+            assert!(!rsi.is_null());
+            md_indicator_free(rsi);
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::orderbook::Quote;
+use crate::time::Timestamp;
+use crate::trades::Trade;
+
+/// Why a tick was rejected by the [`TickFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Error)]
+pub enum RejectReason {
+    #[error("price or quantity is NaN or infinite")]
+    NonFinite,
+    #[error("price is zero or negative")]
+    NonPositivePrice,
+    #[error("quantity is zero or negative")]
+    NonPositiveQuantity,
+    #[error("bid is at or above ask")]
+    CrossedQuote,
+    #[error("price deviates too far from the reference price")]
+    PriceDeviation,
+    #[error("exact repeat of the previous tick")]
+    Duplicate,
+    #[error("timestamp is older than the last accepted tick")]
+    OutOfOrder,
+}
+
+/// Trade or quote held in quarantine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Tick {
+    Trade(Trade),
+    Quote(Quote),
+}
+
+/// Rejected tick together with the reason
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedTick {
+    pub tick: Tick,
+    pub reason: RejectReason,
+}
+
+/// Tick filter configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FilterConfig {
+    /// Maximum relative distance from the reference price (last trade or mid), e.g. 0.05 = 5%
+    pub max_deviation: f64,
+    /// After this many consecutive deviation rejects the new level is accepted as a genuine gap
+    pub max_consecutive_deviations: usize,
+    /// Reject quotes with bid >= ask
+    pub reject_crossed: bool,
+    /// Reject ticks whose timestamp is older than the last accepted one
+    pub reject_out_of_order: bool,
+    /// Number of rejected ticks kept for inspection
+    pub quarantine_capacity: usize,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            max_deviation: 0.05,
+            max_consecutive_deviations: 5,
+            reject_crossed: true,
+            reject_out_of_order: true,
+            quarantine_capacity: 1000,
+        }
+    }
+}
+
+/// Counters describing filter activity
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterStats {
+    pub accepted_trades: u64,
+    pub accepted_quotes: u64,
+    pub rejected: HashMap<RejectReason, u64>,
+}
+
+impl FilterStats {
+    pub fn total_rejected(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+
+    pub fn rejected_for(&self, reason: RejectReason) -> u64 {
+        self.rejected.get(&reason).copied().unwrap_or(0)
+    }
+
+    /// Share of ticks that were rejected
+    pub fn reject_rate(&self) -> f64 {
+        let rejected = self.total_rejected();
+        let total = self.accepted_trades + self.accepted_quotes + rejected;
+        if total == 0 {
+            0.0
+        } else {
+            rejected as f64 / total as f64
+        }
+    }
+}
+
+/// Scrubbing stage that quarantines suspect trades and quotes for one symbol
+/// before they reach books and indicators
+pub struct TickFilter {
+    config: FilterConfig,
+    last_trade: Option<Trade>,
+    last_quote: Option<Quote>,
+    last_timestamp: Timestamp,
+    trade_deviations: usize,
+    quote_deviations: usize,
+    quarantine: VecDeque<QuarantinedTick>,
+    stats: FilterStats,
+}
+
+impl TickFilter {
+    pub fn new(config: FilterConfig) -> Self {
+        Self {
+            config,
+            last_trade: None,
+            last_quote: None,
+            last_timestamp: Timestamp::MIN,
+            trade_deviations: 0,
+            quote_deviations: 0,
+            quarantine: VecDeque::new(),
+            stats: FilterStats::default(),
+        }
+    }
+
+    /// Check a trade, returning `Ok` if it may be passed downstream
+    pub fn check_trade(&mut self, trade: &Trade) -> Result<(), RejectReason> {
+        match self.validate_trade(trade) {
+            Ok(()) => {
+                self.trade_deviations = 0;
+                self.last_trade = Some(*trade);
+                self.last_timestamp = self.last_timestamp.max(trade.timestamp);
+                self.stats.accepted_trades += 1;
+                Ok(())
+            }
+            Err(reason) => {
+                if reason == RejectReason::PriceDeviation {
+                    self.trade_deviations += 1;
+                }
+                self.reject(Tick::Trade(*trade), reason);
+                Err(reason)
+            }
+        }
+    }
+
+    /// Check a quote, returning `Ok` if it may be passed downstream
+    pub fn check_quote(&mut self, quote: &Quote) -> Result<(), RejectReason> {
+        match self.validate_quote(quote) {
+            Ok(()) => {
+                self.quote_deviations = 0;
+                self.last_quote = Some(*quote);
+                self.last_timestamp = self.last_timestamp.max(quote.timestamp);
+                self.stats.accepted_quotes += 1;
+                Ok(())
+            }
+            Err(reason) => {
+                if reason == RejectReason::PriceDeviation {
+                    self.quote_deviations += 1;
+                }
+                self.reject(Tick::Quote(*quote), reason);
+                Err(reason)
+            }
+        }
+    }
+
+    fn validate_trade(&self, trade: &Trade) -> Result<(), RejectReason> {
+        if !trade.price.is_finite() || !trade.quantity.is_finite() {
+            return Err(RejectReason::NonFinite);
+        }
+        if trade.price <= 0.0 {
+            return Err(RejectReason::NonPositivePrice);
+        }
+        if trade.quantity <= 0.0 {
+            return Err(RejectReason::NonPositiveQuantity);
+        }
+        if self.last_trade.as_ref() == Some(trade) {
+            return Err(RejectReason::Duplicate);
+        }
+        self.check_order(trade.timestamp)?;
+
+        let reference = self
+            .last_trade
+            .map(|t| t.price)
+            .or_else(|| self.last_quote.map(|q| q.mid()));
+        self.check_deviation(trade.price, reference, self.trade_deviations)
+    }
+
+    fn validate_quote(&self, quote: &Quote) -> Result<(), RejectReason> {
+        let fields = [
+            quote.bid_price,
+            quote.bid_size,
+            quote.ask_price,
+            quote.ask_size,
+        ];
+        if fields.iter().any(|v| !v.is_finite()) {
+            return Err(RejectReason::NonFinite);
+        }
+        if quote.bid_price <= 0.0 || quote.ask_price <= 0.0 {
+            return Err(RejectReason::NonPositivePrice);
+        }
+        if quote.bid_size < 0.0 || quote.ask_size < 0.0 {
+            return Err(RejectReason::NonPositiveQuantity);
+        }
+        if self.config.reject_crossed && quote.bid_price >= quote.ask_price {
+            return Err(RejectReason::CrossedQuote);
+        }
+        if self.last_quote.as_ref() == Some(quote) {
+            return Err(RejectReason::Duplicate);
+        }
+        self.check_order(quote.timestamp)?;
+
+        let reference = self.last_quote.map(|q| q.mid());
+        self.check_deviation(quote.mid(), reference, self.quote_deviations)
+    }
+
+    fn check_order(&self, timestamp: Timestamp) -> Result<(), RejectReason> {
+        if self.config.reject_out_of_order && timestamp < self.last_timestamp {
+            return Err(RejectReason::OutOfOrder);
+        }
+        Ok(())
+    }
+
+    fn check_deviation(
+        &self,
+        price: f64,
+        reference: Option<f64>,
+        consecutive: usize,
+    ) -> Result<(), RejectReason> {
+        // A persistent new level is a real gap, not a bad tick
+        if consecutive >= self.config.max_consecutive_deviations {
+            return Ok(());
+        }
+
+        match reference {
+            Some(reference) if (price / reference - 1.0).abs() > self.config.max_deviation => {
+                Err(RejectReason::PriceDeviation)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn reject(&mut self, tick: Tick, reason: RejectReason) {
+        *self.stats.rejected.entry(reason).or_insert(0) += 1;
+
+        if self.config.quarantine_capacity == 0 {
+            return;
+        }
+        if self.quarantine.len() == self.config.quarantine_capacity {
+            self.quarantine.pop_front();
+        }
+        self.quarantine.push_back(QuarantinedTick { tick, reason });
+    }
+
+    /// Rejected ticks, oldest first
+    pub fn quarantined(&self) -> impl Iterator<Item = &QuarantinedTick> {
+        self.quarantine.iter()
+    }
+
+    /// Remove and return all quarantined ticks
+    pub fn drain_quarantine(&mut self) -> Vec<QuarantinedTick> {
+        self.quarantine.drain(..).collect()
+    }
+
+    pub fn stats(&self) -> &FilterStats {
+        &self.stats
+    }
+
+    pub fn reset(&mut self) {
+        self.last_trade = None;
+        self.last_quote = None;
+        self.last_timestamp = Timestamp::MIN;
+        self.trade_deviations = 0;
+        self.quote_deviations = 0;
+        self.quarantine.clear();
+        self.stats = FilterStats::default();
+    }
+}
+
+impl Default for TickFilter {
+    fn default() -> Self {
+        Self::new(FilterConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trades::Side;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn quote(bid: f64, ask: f64, timestamp: i64) -> Quote {
+        Quote {
+            bid_price: bid,
+            bid_size: 1.0,
+            ask_price: ask,
+            ask_size: 1.0,
+            timestamp: at(timestamp),
+        }
+    }
+
+    #[test]
+    fn test_sanity_checks() {
+        let mut filter = TickFilter::default();
+
+        assert_eq!(
+            filter.check_trade(&Trade::new(f64::NAN, 1.0, Side::Buy, at(1))),
+            Err(RejectReason::NonFinite)
+        );
+        assert_eq!(
+            filter.check_trade(&Trade::new(0.0, 1.0, Side::Buy, at(1))),
+            Err(RejectReason::NonPositivePrice)
+        );
+        assert_eq!(
+            filter.check_trade(&Trade::new(100.0, -1.0, Side::Buy, at(1))),
+            Err(RejectReason::NonPositiveQuantity)
+        );
+        assert_eq!(
+            filter.check_quote(&quote(101.0, 100.0, 1)),
+            Err(RejectReason::CrossedQuote)
+        );
+        assert!(filter
+            .check_trade(&Trade::new(100.0, 1.0, Side::Buy, at(1)))
+            .is_ok());
+
+        assert_eq!(filter.stats().accepted_trades, 1);
+        assert_eq!(filter.stats().total_rejected(), 4);
+        assert_eq!(filter.quarantined().count(), 4);
+    }
+
+    #[test]
+    fn test_deviation_from_last_price() {
+        let mut filter = TickFilter::default();
+        filter.check_quote(&quote(99.9, 100.1, 1)).unwrap();
+
+        // First trade is checked against the mid
+        assert_eq!(
+            filter.check_trade(&Trade::new(120.0, 1.0, Side::Buy, at(2))),
+            Err(RejectReason::PriceDeviation)
+        );
+        assert!(filter
+            .check_trade(&Trade::new(100.5, 1.0, Side::Buy, at(3)))
+            .is_ok());
+        assert_eq!(
+            filter.check_trade(&Trade::new(1.0, 1.0, Side::Sell, at(4))),
+            Err(RejectReason::PriceDeviation)
+        );
+        assert_eq!(filter.stats().rejected_for(RejectReason::PriceDeviation), 2);
+    }
+
+    #[test]
+    fn test_persistent_gap_is_accepted() {
+        let config = FilterConfig {
+            max_consecutive_deviations: 3,
+            ..FilterConfig::default()
+        };
+        let mut filter = TickFilter::new(config);
+        filter
+            .check_trade(&Trade::new(100.0, 1.0, Side::Buy, at(1)))
+            .unwrap();
+
+        for ts in 2..5 {
+            assert!(filter
+                .check_trade(&Trade::new(130.0, 1.0, Side::Buy, at(ts)))
+                .is_err());
+        }
+        assert!(filter
+            .check_trade(&Trade::new(130.0, 1.0, Side::Buy, at(5)))
+            .is_ok());
+        assert!(filter
+            .check_trade(&Trade::new(130.5, 1.0, Side::Buy, at(6)))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_duplicates_and_out_of_order() {
+        let mut filter = TickFilter::default();
+        let trade = Trade::new(100.0, 1.0, Side::Buy, at(10));
+
+        filter.check_trade(&trade).unwrap();
+        assert_eq!(filter.check_trade(&trade), Err(RejectReason::Duplicate));
+        assert_eq!(
+            filter.check_quote(&quote(99.0, 101.0, 5)),
+            Err(RejectReason::OutOfOrder)
+        );
+
+        let drained = filter.drain_quarantine();
+        assert_eq!(drained.len(), 2);
+        assert_eq!(drained[0].reason, RejectReason::Duplicate);
+        assert_eq!(filter.quarantined().count(), 0);
+    }
+
+    #[test]
+    fn test_quarantine_is_bounded() {
+        let config = FilterConfig {
+            quarantine_capacity: 2,
+            ..FilterConfig::default()
+        };
+        let mut filter = TickFilter::new(config);
+
+        for ts in 0..5 {
+            let _ = filter.check_trade(&Trade::new(-1.0, 1.0, Side::Buy, at(ts)));
+        }
+
+        assert_eq!(filter.quarantined().count(), 2);
+        assert_eq!(
+            filter.stats().rejected_for(RejectReason::NonPositivePrice),
+            5
+        );
+        assert!((filter.stats().reject_rate() - 1.0).abs() < 1e-12);
+    }
+}
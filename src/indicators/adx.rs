@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::candles::Candle;
+use crate::error::Result;
+
+/// Average Directional Index with the directional indicators, returning
+/// `(ADX, +DI, -DI)`
+///
+/// Directional movement and true range are Wilder-smoothed over `period` candles
+/// (seeded with their sum, then `s = s - s / period + x`), giving
+/// `±DI = 100 * ±DM / TR`. ADX is the Wilder average of
+/// `DX = 100 * |+DI - -DI| / (+DI + -DI)`, so the first value arrives after
+/// `2 * period` candles. The DIs alone are available after `period + 1`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ADX {
+    period: usize,
+    prev: Option<Candle>,
+    moves: usize,
+    tr: f64,
+    plus_dm: f64,
+    minus_dm: f64,
+    di: Option<(f64, f64)>,
+    dx_sum: f64,
+    dx_count: usize,
+    current: Option<(f64, f64, f64)>,
+}
+
+impl ADX {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            prev: None,
+            moves: 0,
+            tr: 0.0,
+            plus_dm: 0.0,
+            minus_dm: 0.0,
+            di: None,
+            dx_sum: 0.0,
+            dx_count: 0,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<(f64, f64, f64)> {
+        let prev = self.prev.replace(*candle)?;
+
+        let up = candle.high - prev.high;
+        let down = prev.low - candle.low;
+        let plus_dm = if up > down && up > 0.0 { up } else { 0.0 };
+        let minus_dm = if down > up && down > 0.0 { down } else { 0.0 };
+        let tr = candle.true_range(Some(prev.close));
+
+        let n = self.period as f64;
+        if self.moves < self.period {
+            self.tr += tr;
+            self.plus_dm += plus_dm;
+            self.minus_dm += minus_dm;
+            self.moves += 1;
+            if self.moves < self.period {
+                return None;
+            }
+        } else {
+            self.tr += tr - self.tr / n;
+            self.plus_dm += plus_dm - self.plus_dm / n;
+            self.minus_dm += minus_dm - self.minus_dm / n;
+        }
+
+        let (plus_di, minus_di) = if self.tr > 0.0 {
+            (
+                100.0 * self.plus_dm / self.tr,
+                100.0 * self.minus_dm / self.tr,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        self.di = Some((plus_di, minus_di));
+
+        let di_sum = plus_di + minus_di;
+        let dx = if di_sum > 0.0 {
+            100.0 * (plus_di - minus_di).abs() / di_sum
+        } else {
+            0.0
+        };
+
+        let adx = match self.current {
+            Some((adx, _, _)) => (adx * (n - 1.0) + dx) / n,
+            None => {
+                self.dx_sum += dx;
+                self.dx_count += 1;
+                if self.dx_count < self.period {
+                    return None;
+                }
+                self.dx_sum / n
+            }
+        };
+
+        self.current = Some((adx, plus_di, minus_di));
+        self.current
+    }
+
+    /// Latest `(+DI, -DI)`, available before ADX has warmed up
+    pub fn directional_indicators(&self) -> Option<(f64, f64)> {
+        self.di
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, (f64, f64, f64)> for ADX {
+    fn update(&mut self, input: Candle) -> Option<(f64, f64, f64)> {
+        ADX::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        ADX::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_wilder_smoothing_by_hand() {
+        let mut adx = ADX::new(2);
+        assert_eq!(adx.update(&candle(10.0, 8.0, 9.0)), None);
+        // TR 2, +DM 1
+        assert_eq!(adx.update(&candle(11.0, 9.0, 10.5)), None);
+        // TR 1.5, +DM 0.5; sums TR 3.5, +DM 1.5, so DX = 100
+        assert_eq!(adx.update(&candle(11.5, 10.0, 11.0)), None);
+        let (plus_di, minus_di) = adx.directional_indicators().unwrap();
+        assert!((plus_di - 1.5 / 3.5 * 100.0).abs() < 1e-9);
+        assert_eq!(minus_di, 0.0);
+
+        // TR 2, -DM 1: smoothed TR 3.75, +DM 0.75, -DM 1
+        let (value, plus_di, minus_di) = adx.update(&candle(11.0, 9.0, 9.5)).unwrap();
+        assert!((plus_di - 0.75 / 3.75 * 100.0).abs() < 1e-9);
+        assert!((minus_di - 1.0 / 3.75 * 100.0).abs() < 1e-9);
+        let dx = 100.0 * 0.25 / 1.75;
+        assert!((value - (100.0 + dx) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_steady_uptrend_is_strong_trend() {
+        let mut adx = ADX::new(14);
+        let mut value = None;
+        for i in 0..60 {
+            let base = 100.0 + i as f64;
+            value = adx.update(&candle(base + 1.0, base - 1.0, base + 0.5));
+            assert_eq!(value.is_some(), i >= 27);
+        }
+
+        let (value, plus_di, minus_di) = value.unwrap();
+        assert!(plus_di > minus_di);
+        assert!(value > 90.0);
+    }
+
+    #[test]
+    fn test_flat_market_and_reset() {
+        let mut adx = ADX::new(3);
+        for _ in 0..10 {
+            adx.update(&candle(101.0, 99.0, 100.0));
+        }
+        assert_eq!(Indicator::current(&adx), Some((0.0, 0.0, 0.0)));
+
+        adx.reset();
+        assert!(!Indicator::is_ready(&adx));
+        assert_eq!(adx.directional_indicators(), None);
+        assert!(ADX::try_new(0).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::candles::Candle;
+use crate::error::Result;
+
+/// Average True Range with Wilder smoothing
+///
+/// Seeded with the simple mean of the first `period` true ranges, then
+/// `atr = (atr * (period - 1) + tr) / period`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ATR {
+    period: usize,
+    prev_close: Option<f64>,
+    seed_sum: f64,
+    seen: usize,
+    current: Option<f64>,
+}
+
+impl ATR {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            prev_close: None,
+            seed_sum: 0.0,
+            seen: 0,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        let tr = candle.true_range(self.prev_close);
+        self.prev_close = Some(candle.close);
+
+        match self.current {
+            Some(atr) => {
+                let n = self.period as f64;
+                self.current = Some((atr * (n - 1.0) + tr) / n);
+            }
+            None => {
+                self.seed_sum += tr;
+                self.seen += 1;
+                if self.seen == self.period {
+                    self.current = Some(self.seed_sum / self.period as f64);
+                }
+            }
+        }
+
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.prev_close = None;
+        self.seed_sum = 0.0;
+        self.seen = 0;
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, f64> for ATR {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        ATR::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        ATR::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Feeds the close of each candle to a price indicator
+///
+/// Lets close-based indicators such as [`SMA`](super::SMA) sit next to range-based ones
+/// in a candle pipeline: `Box<dyn Indicator<Candle, f64>>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnClose<T>(pub T);
+
+impl<O, T: Indicator<f64, O>> Indicator<Candle, O> for OnClose<T> {
+    fn update(&mut self, input: Candle) -> Option<O> {
+        self.0.update(input.close)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.0.is_ready()
+    }
+
+    fn current(&self) -> Option<O> {
+        self.0.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::SMA;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_wilder_smoothing() {
+        let mut atr = ATR::new(3);
+        assert_eq!(atr.update(&candle(10.0, 8.0, 9.0)), None);
+        // Gap up: true range reaches back to the previous close
+        assert_eq!(atr.update(&candle(13.0, 12.0, 12.5)), None);
+        assert_eq!(atr.update(&candle(13.0, 10.0, 12.0)), Some(3.0));
+
+        // (3 * 2 + 1) / 3
+        let value = atr.update(&candle(12.5, 11.5, 12.0)).unwrap();
+        assert!((value - 7.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_candle_pipeline_mixes_range_and_close_indicators() {
+        let mut indicators: Vec<Box<dyn Indicator<Candle, f64>>> =
+            vec![Box::new(ATR::new(2)), Box::new(OnClose(SMA::new(2)))];
+
+        for c in [candle(11.0, 9.0, 10.0), candle(13.0, 11.0, 12.0)] {
+            for indicator in indicators.iter_mut() {
+                indicator.update(c);
+            }
+        }
+        assert_eq!(indicators[0].current(), Some(2.5));
+        assert_eq!(indicators[1].current(), Some(11.0));
+
+        indicators[0].reset();
+        assert!(!indicators[0].is_ready());
+        assert!(ATR::try_new(0).is_err());
+    }
+}
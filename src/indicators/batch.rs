@@ -0,0 +1,403 @@
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+use crate::numeric::KahanSum;
+use crate::time::Timestamp;
+use super::{check_period, rsi_from, Indicator, RsiSmoothing};
+
+/// Simple moving average of every full window, matching [`SMA`](super::SMA)
+pub fn sma_series(values: &[f64], period: usize) -> Result<Vec<Option<f64>>> {
+    check_period("period", period)?;
+    let mut out = Vec::with_capacity(values.len());
+    let mut sum = KahanSum::new();
+
+    for (i, &value) in values.iter().enumerate() {
+        sum += value;
+        if i >= period {
+            sum -= values[i - period];
+        }
+        out.push((i + 1 >= period).then(|| sum.value() / period as f64));
+    }
+    Ok(out)
+}
+
+/// Exponential moving average seeded with the first value, matching [`EMA`](super::EMA)
+pub fn ema_series(values: &[f64], period: usize) -> Result<Vec<Option<f64>>> {
+    check_period("period", period)?;
+    let multiplier = 2.0 / (period as f64 + 1.0);
+    let mut current: Option<f64> = None;
+
+    Ok(values
+        .iter()
+        .map(|&value| {
+            let ema = current.map_or(value, |prev| (value - prev) * multiplier + prev);
+            current = Some(ema);
+            current
+        })
+        .collect())
+}
+
+/// RSI over close prices, matching [`RSI`](super::RSI) with the given smoothing
+pub fn rsi_series(
+    values: &[f64],
+    period: usize,
+    smoothing: RsiSmoothing,
+) -> Result<Vec<Option<f64>>> {
+    check_period("period", period)?;
+    let mut out = Vec::with_capacity(values.len());
+    out.extend(values.first().map(|_| None));
+
+    let gain = |i: usize| (values[i] - values[i - 1]).max(0.0);
+    let loss = |i: usize| (values[i - 1] - values[i]).max(0.0);
+    let n = period as f64;
+    let (mut gains, mut losses) = (KahanSum::new(), KahanSum::new());
+    let mut averages: Option<(f64, f64)> = None;
+
+    for i in 1..values.len() {
+        if let Some((avg_gain, avg_loss)) = averages {
+            let avg_gain = (avg_gain * (n - 1.0) + gain(i)) / n;
+            let avg_loss = (avg_loss * (n - 1.0) + loss(i)) / n;
+            averages = Some((avg_gain, avg_loss));
+            out.push(Some(rsi_from(avg_gain, avg_loss)));
+            continue;
+        }
+
+        gains += gain(i);
+        losses += loss(i);
+        if i > period {
+            gains -= gain(i - period);
+            losses -= loss(i - period);
+        }
+        if i < period {
+            out.push(None);
+            continue;
+        }
+
+        let (avg_gain, avg_loss) = (gains.value() / n, losses.value() / n);
+        if smoothing == RsiSmoothing::Wilder {
+            averages = Some((avg_gain, avg_loss));
+        }
+        out.push(Some(rsi_from(avg_gain, avg_loss)));
+    }
+    Ok(out)
+}
+
+/// `(upper, middle, lower)` bands of every full window, matching
+/// [`BollingerBands`](super::BollingerBands)
+pub fn bollinger_series(
+    values: &[f64],
+    period: usize,
+    std_dev: f64,
+) -> Result<Vec<Option<(f64, f64, f64)>>> {
+    check_period("period", period)?;
+    let means = sma_series(values, period)?;
+
+    Ok(means
+        .iter()
+        .enumerate()
+        .map(|(i, mean)| {
+            let middle = (*mean)?;
+            let window = &values[i + 1 - period..=i];
+            let variance = window.iter().map(|v| (v - middle).powi(2)).sum::<f64>() / period as f64;
+            let band = std_dev * variance.sqrt();
+            Some((middle + band, middle, middle - band))
+        })
+        .collect())
+}
+
+/// `(macd, signal, histogram)`, matching [`MACD`](super::MACD)
+pub fn macd_series(
+    values: &[f64],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Result<Vec<Option<(f64, f64, f64)>>> {
+    let fast = ema_series(values, fast_period)?;
+    let slow = ema_series(values, slow_period)?;
+    let line: Vec<f64> = fast
+        .iter()
+        .zip(&slow)
+        .filter_map(|(f, s)| Some((*f)? - (*s)?))
+        .collect();
+    let signal = ema_series(&line, signal_period)?;
+
+    Ok(line
+        .iter()
+        .zip(signal)
+        .map(|(&macd, signal)| signal.map(|s| (macd, s, macd - s)))
+        .collect())
+}
+
+/// Split a three-output series into aligned columns, e.g. Bollinger upper/middle/lower
+pub fn unzip3(series: &[Option<(f64, f64, f64)>]) -> [Vec<Option<f64>>; 3] {
+    let mut columns = [
+        Vec::with_capacity(series.len()),
+        Vec::with_capacity(series.len()),
+        Vec::with_capacity(series.len()),
+    ];
+    for value in series {
+        columns[0].push(value.map(|v| v.0));
+        columns[1].push(value.map(|v| v.1));
+        columns[2].push(value.map(|v| v.2));
+    }
+    columns
+}
+
+/// Indicator output that can be spread over one or more table columns
+pub trait IndicatorOutput {
+    /// Number of columns, e.g. 3 for Bollinger upper/middle/lower
+    const WIDTH: usize;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]);
+}
+
+impl IndicatorOutput for f64 {
+    const WIDTH: usize = 1;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self));
+    }
+}
+
+impl IndicatorOutput for (f64, f64) {
+    const WIDTH: usize = 2;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self.0));
+        columns[1].push(Some(self.1));
+    }
+}
+
+impl IndicatorOutput for (f64, f64, f64) {
+    const WIDTH: usize = 3;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self.0));
+        columns[1].push(Some(self.1));
+        columns[2].push(Some(self.2));
+    }
+}
+
+/// Column-oriented indicator output aligned with the input candles
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndicatorTable {
+    /// Candle timestamps, one per row
+    pub timestamps: Vec<Timestamp>,
+    /// Named columns, `None` where the indicator was still warming up
+    pub columns: Vec<(String, Vec<Option<f64>>)>,
+}
+
+impl IndicatorTable {
+    pub fn column(&self, name: &str) -> Option<&[Option<f64>]> {
+        self.columns
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Column names in insertion order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+type Feed = Box<dyn FnMut(Candle, &mut [Vec<Option<f64>>])>;
+
+/// Named indicators run together over a candle series
+///
+/// Close-based indicators join through [`OnClose`](super::OnClose):
+/// `set.add(&["sma_20"], OnClose(SMA::new(20)))`.
+#[derive(Default)]
+pub struct IndicatorSet {
+    names: Vec<String>,
+    feeds: Vec<(usize, Feed)>,
+}
+
+impl IndicatorSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an indicator with one column name per output value
+    pub fn add<O, T>(&mut self, names: &[&str], mut indicator: T) -> Result<&mut Self>
+    where
+        O: IndicatorOutput,
+        T: Indicator<Candle, O> + 'static,
+    {
+        if names.len() != O::WIDTH {
+            return Err(MarketDataError::DimensionMismatch {
+                expected: O::WIDTH,
+                actual: names.len(),
+            });
+        }
+        if let Some(name) = names.iter().find(|n| self.names.iter().any(|m| m == *n)) {
+            return Err(MarketDataError::invalid_parameter(format!(
+                "duplicate column {name}"
+            )));
+        }
+
+        self.names.extend(names.iter().map(|n| n.to_string()));
+        self.feeds.push((
+            O::WIDTH,
+            Box::new(move |candle, columns| match indicator.update(candle) {
+                Some(output) => output.write(columns),
+                None => columns.iter_mut().for_each(|c| c.push(None)),
+            }),
+        ));
+        Ok(self)
+    }
+
+    /// Feed every candle to every indicator, continuing from any earlier run
+    pub fn run(&mut self, candles: &[Candle]) -> IndicatorTable {
+        let mut columns: Vec<Vec<Option<f64>>> = self
+            .names
+            .iter()
+            .map(|_| Vec::with_capacity(candles.len()))
+            .collect();
+
+        for candle in candles {
+            let mut offset = 0;
+            for (width, feed) in &mut self.feeds {
+                feed(*candle, &mut columns[offset..offset + *width]);
+                offset += *width;
+            }
+        }
+
+        IndicatorTable {
+            timestamps: candles.iter().map(|c| c.timestamp).collect(),
+            columns: self.names.iter().cloned().zip(columns).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::{OnClose, ATR, BollingerBands, SMA};
+    use crate::testing::gbm_path;
+
+    fn candles() -> Vec<Candle> {
+        (0..10)
+            .map(|i| {
+                let close = 100.0 + i as f64;
+                Candle::new(
+                    close,
+                    close + 1.0,
+                    close - 1.0,
+                    close,
+                    10.0,
+                    Timestamp::from_millis(i * 60_000),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aligned_columns() {
+        let mut set = IndicatorSet::new();
+        set.add(&["sma_3"], OnClose(SMA::new(3))).unwrap();
+        set.add(
+            &["bb_upper", "bb_middle", "bb_lower"],
+            OnClose(BollingerBands::new(5, 2.0)),
+        )
+        .unwrap();
+        set.add(&["atr"], ATR::new(2)).unwrap();
+
+        let table = set.run(&candles());
+        assert_eq!(table.rows(), 10);
+        assert_eq!(table.names().count(), 5);
+        assert_eq!(table.timestamps[9].as_millis(), 9 * 60_000);
+
+        let sma = table.column("sma_3").unwrap();
+        assert_eq!(&sma[..3], &[None, None, Some(101.0)]);
+        assert_eq!(table.column("bb_middle").unwrap()[4], Some(102.0));
+        assert_eq!(table.column("bb_upper").unwrap()[3], None);
+        assert!(table
+            .column("atr")
+            .unwrap()
+            .iter()
+            .all(|v| v.is_none_or(|v| v == 2.0)));
+    }
+
+    fn prices() -> Vec<f64> {
+        gbm_path(17, 100.0, 0.0, 0.01, 499)
+    }
+
+    // Streaming buffers round inputs to f32 under `f32-storage`
+    const TOLERANCE: f64 = if cfg!(feature = "f32-storage") { 1e-3 } else { 1e-9 };
+
+    fn assert_close(batch: &[Option<f64>], streaming: &[Option<f64>]) {
+        assert_eq!(batch.len(), streaming.len());
+        for (b, s) in batch.iter().zip(streaming) {
+            match (b, s) {
+                (Some(b), Some(s)) => assert!((b - s).abs() < TOLERANCE, "{b} != {s}"),
+                _ => assert_eq!(b, s),
+            }
+        }
+    }
+
+    #[test]
+    fn test_series_match_streaming() {
+        use crate::indicators::{EMA, MACD, RSI};
+
+        let prices = prices();
+        assert_close(
+            &sma_series(&prices, 20).unwrap(),
+            &SMA::new(20).compute_series(&prices),
+        );
+        assert_close(
+            &ema_series(&prices, 12).unwrap(),
+            &EMA::new(12).compute_series(&prices),
+        );
+        assert_close(
+            &rsi_series(&prices, 14, RsiSmoothing::Simple).unwrap(),
+            &RSI::new(14).compute_series(&prices),
+        );
+        assert_close(
+            &rsi_series(&prices, 14, RsiSmoothing::Wilder).unwrap(),
+            &RSI::wilder(14).compute_series(&prices),
+        );
+
+        let bands = bollinger_series(&prices, 20, 2.0).unwrap();
+        let streamed = BollingerBands::new(20, 2.0).compute_series(&prices);
+        for (batch, streaming) in unzip3(&bands).iter().zip(unzip3(&streamed).iter()) {
+            assert_close(batch, streaming);
+        }
+
+        let macd = macd_series(&prices, 12, 26, 9).unwrap();
+        let streamed = MACD::new(12, 26, 9).compute_series(&prices);
+        for (batch, streaming) in unzip3(&macd).iter().zip(unzip3(&streamed).iter()) {
+            assert_close(batch, streaming);
+        }
+    }
+
+    #[test]
+    fn test_series_edge_cases() {
+        assert!(sma_series(&[1.0], 0).is_err());
+        assert_eq!(rsi_series(&[], 14, RsiSmoothing::Simple).unwrap(), vec![]);
+        assert_eq!(sma_series(&[1.0, 2.0], 3).unwrap(), vec![None, None]);
+
+        // compute_series keeps state for streaming afterwards
+        let mut sma = SMA::new(2);
+        sma.compute_series(&[1.0, 2.0]);
+        assert_eq!(sma.update(4.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_name_validation() {
+        let mut set = IndicatorSet::new();
+        assert!(matches!(
+            set.add(&["a", "b"], OnClose(SMA::new(3))),
+            Err(MarketDataError::DimensionMismatch {
+                expected: 1,
+                actual: 2
+            })
+        ));
+        set.add(&["a"], OnClose(SMA::new(3))).unwrap();
+        assert!(set.add(&["a"], OnClose(SMA::new(5))).is_err());
+        assert_eq!(set.run(&[]).rows(), 0);
+    }
+}
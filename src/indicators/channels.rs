@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator, ATR, EMA};
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+use crate::numeric::{widen, Real};
+
+/// Keltner Channels returning `(upper, middle, lower)`
+///
+/// The middle line is an EMA of the close and the bands sit `multiplier` ATRs away
+/// from it. Output starts once the ATR has warmed up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeltnerChannels {
+    ema: EMA,
+    atr: ATR,
+    multiplier: f64,
+    current: Option<(f64, f64, f64)>,
+}
+
+impl KeltnerChannels {
+    /// Validating constructor
+    pub fn try_new(ema_period: usize, atr_period: usize, multiplier: f64) -> Result<Self> {
+        check_period("ema_period", ema_period)?;
+        check_period("atr_period", atr_period)?;
+        if !(multiplier.is_finite() && multiplier > 0.0) {
+            return Err(MarketDataError::invalid_parameter(
+                "multiplier must be finite and positive",
+            ));
+        }
+        Ok(Self {
+            ema: EMA::new(ema_period),
+            atr: ATR::new(atr_period),
+            multiplier,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(ema_period: usize, atr_period: usize, multiplier: f64) -> Self {
+        Self::try_new(ema_period, atr_period, multiplier).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<(f64, f64, f64)> {
+        let middle = self.ema.update(candle.close)?;
+        let atr = self.atr.update(candle)?;
+        let offset = self.multiplier * atr;
+        self.current = Some((middle + offset, middle, middle - offset));
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.ema.reset();
+        self.atr.reset();
+        self.current = None;
+    }
+}
+
+impl Indicator<Candle, (f64, f64, f64)> for KeltnerChannels {
+    fn update(&mut self, input: Candle) -> Option<(f64, f64, f64)> {
+        KeltnerChannels::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        KeltnerChannels::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        self.current
+    }
+}
+
+/// Donchian Channels returning `(upper, middle, lower)`
+///
+/// Upper and lower are the highest high and lowest low of the last `period` candles,
+/// including the current one; the middle is their midpoint. A breakout strategy
+/// compares a close against the previous bar's channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DonchianChannels {
+    period: usize,
+    highs: VecDeque<Real>,
+    lows: VecDeque<Real>,
+    current: Option<(f64, f64, f64)>,
+}
+
+impl DonchianChannels {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            highs: VecDeque::with_capacity(period),
+            lows: VecDeque::with_capacity(period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<(f64, f64, f64)> {
+        self.highs.push_back(candle.high as Real);
+        self.lows.push_back(candle.low as Real);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+        if self.highs.len() < self.period {
+            return None;
+        }
+
+        let upper = self
+            .highs
+            .iter()
+            .map(|&h| widen(h))
+            .fold(f64::MIN, f64::max);
+        let lower = self.lows.iter().map(|&l| widen(l)).fold(f64::MAX, f64::min);
+        self.current = Some((upper, (upper + lower) / 2.0, lower));
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.highs.clear();
+        self.lows.clear();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, (f64, f64, f64)> for DonchianChannels {
+    fn update(&mut self, input: Candle) -> Option<(f64, f64, f64)> {
+        DonchianChannels::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        DonchianChannels::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_keltner_bands_are_atr_multiples_around_ema() {
+        let mut keltner = KeltnerChannels::new(3, 2, 2.0);
+        assert_eq!(keltner.update(&candle(11.0, 9.0, 10.0)), None);
+
+        // EMA(3): 10 + (12 - 10) * 0.5 = 11; ATR(2): (2 + 3) / 2 = 2.5
+        let (upper, middle, lower) = keltner.update(&candle(13.0, 11.0, 12.0)).unwrap();
+        assert_eq!(middle, 11.0);
+        assert_eq!(upper, 16.0);
+        assert_eq!(lower, 6.0);
+
+        keltner.reset();
+        assert!(!Indicator::is_ready(&keltner));
+        assert!(KeltnerChannels::try_new(20, 10, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_donchian_tracks_rolling_extremes() {
+        let mut donchian = DonchianChannels::new(3);
+        assert_eq!(donchian.update(&candle(10.0, 8.0, 9.0)), None);
+        assert_eq!(donchian.update(&candle(12.0, 9.0, 11.0)), None);
+        assert_eq!(
+            donchian.update(&candle(11.0, 7.0, 8.0)),
+            Some((12.0, 9.5, 7.0))
+        );
+        // The 12 high and 7 low are still inside the window
+        assert_eq!(
+            donchian.update(&candle(9.0, 8.5, 9.0)),
+            Some((12.0, 9.5, 7.0))
+        );
+        // The 12 high rolls out
+        assert_eq!(
+            donchian.update(&candle(9.5, 8.0, 9.0)),
+            Some((11.0, 9.0, 7.0))
+        );
+        assert!(DonchianChannels::try_new(0).is_err());
+    }
+}
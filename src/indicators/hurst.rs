@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MarketDataError, Result};
+use super::Indicator;
+use crate::numeric::{widen, Real};
+
+/// Smallest sub-series length used by the rescaled range analysis
+const MIN_CHUNK: usize = 8;
+
+/// Market regime implied by a Hurst exponent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HurstRegime {
+    MeanReverting,
+    RandomWalk,
+    Trending,
+}
+
+impl HurstRegime {
+    /// Classify an exponent, treating `0.5 ± band` as a random walk
+    pub fn from_exponent(hurst: f64, band: f64) -> Self {
+        if hurst > 0.5 + band {
+            HurstRegime::Trending
+        } else if hurst < 0.5 - band {
+            HurstRegime::MeanReverting
+        } else {
+            HurstRegime::RandomWalk
+        }
+    }
+}
+
+/// Rolling Hurst exponent estimated by rescaled range (R/S) analysis of log returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HurstExponent {
+    window: usize,
+    returns: VecDeque<Real>,
+    prev_price: Option<f64>,
+    current: Option<f64>,
+}
+
+impl HurstExponent {
+    /// Validating constructor; rejects windows too short for the R/S regression
+    pub fn try_new(window: usize) -> Result<Self> {
+        if window < 4 * MIN_CHUNK {
+            return Err(MarketDataError::invalid_parameter(format!(
+                "window must be at least {}",
+                4 * MIN_CHUNK
+            )));
+        }
+        Ok(Self {
+            window,
+            returns: VecDeque::with_capacity(window),
+            prev_price: None,
+            current: None,
+        })
+    }
+
+    /// `window` is the number of log returns analysed; it needs at least `4 * 8` samples
+    /// so that two chunk sizes are available for the regression
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(window: usize) -> Self {
+        Self::try_new(window).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        if let Some(prev) = self.prev_price {
+            if prev > 0.0 && price > 0.0 {
+                self.returns.push_back((price / prev).ln() as Real);
+
+                if self.returns.len() > self.window {
+                    self.returns.pop_front();
+                }
+            }
+        }
+        self.prev_price = Some(price);
+
+        if self.returns.len() == self.window {
+            let returns: Vec<f64> = self.returns.iter().map(|&r| widen(r)).collect();
+            self.current = rescaled_range_hurst(&returns);
+        }
+
+        self.current
+    }
+
+    /// Last computed exponent
+    pub fn value(&self) -> Option<f64> {
+        self.current
+    }
+
+    /// Regime classification of the last exponent
+    pub fn regime(&self, band: f64) -> Option<HurstRegime> {
+        self.current.map(|h| HurstRegime::from_exponent(h, band))
+    }
+
+    pub fn reset(&mut self) {
+        self.returns.clear();
+        self.prev_price = None;
+        self.current = None;
+    }
+}
+
+impl Indicator<f64, f64> for HurstExponent {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        HurstExponent::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        HurstExponent::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Hurst exponent of a return series via the slope of log(R/S) against log(n)
+pub fn rescaled_range_hurst(returns: &[f64]) -> Option<f64> {
+    let mut points = Vec::new();
+    let mut size = MIN_CHUNK;
+
+    while size <= returns.len() / 2 {
+        let rs: Vec<f64> = returns
+            .chunks_exact(size)
+            .filter_map(rescaled_range)
+            .collect();
+
+        if !rs.is_empty() {
+            let mean_rs = rs.iter().sum::<f64>() / rs.len() as f64;
+            points.push(((size as f64).ln(), mean_rs.ln()));
+        }
+        size *= 2;
+    }
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let var: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+
+    Some(cov / var)
+}
+
+fn rescaled_range(chunk: &[f64]) -> Option<f64> {
+    let n = chunk.len() as f64;
+    let mean = chunk.iter().sum::<f64>() / n;
+
+    let mut cumulative = 0.0;
+    let mut max = f64::MIN;
+    let mut min = f64::MAX;
+    for v in chunk {
+        cumulative += v - mean;
+        max = max.max(cumulative);
+        min = min.min(cumulative);
+    }
+
+    let std = (chunk.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std == 0.0 {
+        return None;
+    }
+
+    Some((max - min) / std)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(seed: &mut u64) -> f64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+
+    #[test]
+    fn test_mean_reverting_series() {
+        let mut hurst = HurstExponent::new(256);
+        let mut seed = 7;
+
+        // Prices oscillate around a level with negatively autocorrelated returns
+        let mut value = None;
+        for i in 0..300 {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            value = hurst.update(100.0 + sign * (1.0 + noise(&mut seed) * 0.2));
+        }
+
+        assert!(value.unwrap() < 0.3);
+        assert_eq!(hurst.regime(0.1), Some(HurstRegime::MeanReverting));
+    }
+
+    #[test]
+    fn test_trending_series() {
+        let mut hurst = HurstExponent::new(256);
+        let mut seed = 11;
+        let mut price = 100.0;
+        let mut drift = 0.0;
+
+        for _ in 0..300 {
+            // Persistent (positively autocorrelated) returns
+            drift = 0.9 * drift + noise(&mut seed) * 0.01;
+            price *= f64::exp(drift);
+            hurst.update(price);
+        }
+
+        assert!(hurst.value().unwrap() > 0.6);
+        assert_eq!(hurst.regime(0.1), Some(HurstRegime::Trending));
+    }
+
+    #[test]
+    fn test_warm_up_and_reset() {
+        let mut hurst = HurstExponent::new(32);
+
+        for i in 0..32 {
+            assert_eq!(hurst.update(100.0 + i as f64), None);
+        }
+        assert!(hurst.update(140.0).is_some());
+
+        hurst.reset();
+        assert_eq!(hurst.value(), None);
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(HurstExponent::try_new(16).is_err());
+        assert!(HurstExponent::try_new(32).is_ok());
+    }
+
+    #[test]
+    fn test_too_short_series() {
+        assert_eq!(rescaled_range_hurst(&[0.01; 20]), None);
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator, IndicatorOutput};
+use crate::candles::Candle;
+use crate::error::Result;
+use crate::numeric::{widen, Real};
+
+/// One bar of Ichimoku lines
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IchimokuOutput {
+    /// Conversion line: midpoint of the `tenkan` period range
+    pub tenkan: f64,
+    /// Base line: midpoint of the `kijun` period range
+    pub kijun: f64,
+    /// Senkou span A computed on this bar, plotted `displacement` bars ahead
+    pub leading_span_a: f64,
+    /// Senkou span B computed on this bar, plotted `displacement` bars ahead
+    pub leading_span_b: f64,
+    /// Cloud `(span A, span B)` over this bar, i.e. the leading spans computed
+    /// `displacement` bars ago; `None` until that much history exists
+    pub cloud: Option<(f64, f64)>,
+    /// This bar's close, plotted `displacement` bars back
+    pub chikou: f64,
+}
+
+impl IchimokuOutput {
+    /// Top and bottom of the cloud over this bar
+    pub fn cloud_bounds(&self) -> Option<(f64, f64)> {
+        self.cloud.map(|(a, b)| (a.max(b), a.min(b)))
+    }
+}
+
+/// Columns: tenkan, kijun, span A, span B, with the spans aligned to the bar they
+/// cover (the chikou line looks into the future of any bar and has no column)
+impl IndicatorOutput for IchimokuOutput {
+    const WIDTH: usize = 4;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self.tenkan));
+        columns[1].push(Some(self.kijun));
+        columns[2].push(self.cloud.map(|(a, _)| a));
+        columns[3].push(self.cloud.map(|(_, b)| b));
+    }
+}
+
+/// Ichimoku Kinko Hyo from candles
+///
+/// Tenkan, kijun and senkou B are midpoints of the high/low range over their periods;
+/// senkou A is the average of tenkan and kijun. Both senkou spans are shifted forward
+/// by `displacement` bars, which a streaming indicator handles by buffering them: each
+/// output carries the spans just computed and the cloud that applies to the current
+/// bar. Output starts once the longest window is full.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ichimoku {
+    tenkan_period: usize,
+    kijun_period: usize,
+    senkou_b_period: usize,
+    displacement: usize,
+    highs: VecDeque<Real>,
+    lows: VecDeque<Real>,
+    /// Leading spans of the last `displacement` bars, oldest first
+    pending: VecDeque<(f64, f64)>,
+    current: Option<IchimokuOutput>,
+}
+
+impl Ichimoku {
+    /// Validating constructor
+    pub fn try_new(
+        tenkan_period: usize,
+        kijun_period: usize,
+        senkou_b_period: usize,
+        displacement: usize,
+    ) -> Result<Self> {
+        check_period("tenkan_period", tenkan_period)?;
+        check_period("kijun_period", kijun_period)?;
+        check_period("senkou_b_period", senkou_b_period)?;
+        check_period("displacement", displacement)?;
+        let window = tenkan_period.max(kijun_period).max(senkou_b_period);
+        Ok(Self {
+            tenkan_period,
+            kijun_period,
+            senkou_b_period,
+            displacement,
+            highs: VecDeque::with_capacity(window),
+            lows: VecDeque::with_capacity(window),
+            pending: VecDeque::with_capacity(displacement),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(
+        tenkan_period: usize,
+        kijun_period: usize,
+        senkou_b_period: usize,
+        displacement: usize,
+    ) -> Self {
+        Self::try_new(tenkan_period, kijun_period, senkou_b_period, displacement)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// The classic 9/26/52 settings displaced by 26
+    pub fn standard() -> Self {
+        Self::new(9, 26, 52, 26)
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<IchimokuOutput> {
+        let window = self
+            .tenkan_period
+            .max(self.kijun_period)
+            .max(self.senkou_b_period);
+        self.highs.push_back(candle.high as Real);
+        self.lows.push_back(candle.low as Real);
+        if self.highs.len() > window {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+
+        let tenkan = self.midpoint(self.tenkan_period)?;
+        let kijun = self.midpoint(self.kijun_period)?;
+        let span_b = self.midpoint(self.senkou_b_period)?;
+        let span_a = (tenkan + kijun) / 2.0;
+
+        let cloud = if self.pending.len() == self.displacement {
+            self.pending.pop_front()
+        } else {
+            None
+        };
+        self.pending.push_back((span_a, span_b));
+
+        self.current = Some(IchimokuOutput {
+            tenkan,
+            kijun,
+            leading_span_a: span_a,
+            leading_span_b: span_b,
+            cloud,
+            chikou: candle.close,
+        });
+        self.current
+    }
+
+    /// Midpoint of the highest high and lowest low of the last `period` candles
+    fn midpoint(&self, period: usize) -> Option<f64> {
+        let len = self.highs.len();
+        if len < period {
+            return None;
+        }
+        let highest = self
+            .highs
+            .range(len - period..)
+            .map(|&h| widen(h))
+            .fold(f64::MIN, f64::max);
+        let lowest = self
+            .lows
+            .range(len - period..)
+            .map(|&l| widen(l))
+            .fold(f64::MAX, f64::min);
+        Some((highest + lowest) / 2.0)
+    }
+
+    pub fn reset(&mut self) {
+        self.highs.clear();
+        self.lows.clear();
+        self.pending.clear();
+        self.current = None;
+    }
+
+    pub fn displacement(&self) -> usize {
+        self.displacement
+    }
+}
+
+impl Default for Ichimoku {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Indicator<Candle, IchimokuOutput> for Ichimoku {
+    fn update(&mut self, input: Candle) -> Option<IchimokuOutput> {
+        Ichimoku::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        Ichimoku::reset(self)
+    }
+
+    fn current(&self) -> Option<IchimokuOutput> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::IndicatorSet;
+    use crate::time::Timestamp;
+
+    fn candle(i: usize) -> Candle {
+        let mid = 100.0 + i as f64;
+        Candle::new(
+            mid,
+            mid + 1.0,
+            mid - 1.0,
+            mid + 0.5,
+            1.0,
+            Timestamp::from_millis(i as i64),
+        )
+    }
+
+    #[test]
+    fn test_lines_on_a_rising_series() {
+        let mut ichimoku = Ichimoku::new(2, 3, 4, 2);
+        for i in 0..3 {
+            assert_eq!(ichimoku.update(&candle(i)), None);
+        }
+
+        let out = ichimoku.update(&candle(3)).unwrap();
+        // Highs 101..104, lows 99..102
+        assert_eq!(out.tenkan, (104.0 + 101.0) / 2.0);
+        assert_eq!(out.kijun, (104.0 + 100.0) / 2.0);
+        assert_eq!(out.leading_span_b, (104.0 + 99.0) / 2.0);
+        assert_eq!(out.leading_span_a, (out.tenkan + out.kijun) / 2.0);
+        assert_eq!(out.chikou, 103.5);
+        assert_eq!(out.cloud, None);
+    }
+
+    #[test]
+    fn test_spans_are_displaced_forward() {
+        let mut ichimoku = Ichimoku::new(2, 3, 4, 2);
+        let outputs: Vec<IchimokuOutput> =
+            (0..8).filter_map(|i| ichimoku.update(&candle(i))).collect();
+
+        assert_eq!(outputs[1].cloud, None);
+        for t in 2..outputs.len() {
+            let earlier = outputs[t - 2];
+            assert_eq!(
+                outputs[t].cloud,
+                Some((earlier.leading_span_a, earlier.leading_span_b))
+            );
+        }
+        let (top, bottom) = outputs[4].cloud_bounds().unwrap();
+        assert!(top >= bottom);
+    }
+
+    #[test]
+    fn test_indicator_set_columns_and_reset() {
+        let candles: Vec<Candle> = (0..10).map(candle).collect();
+        let mut set = IndicatorSet::new();
+        set.add(
+            &["tenkan", "kijun", "span_a", "span_b"],
+            Ichimoku::new(2, 3, 4, 2),
+        )
+        .unwrap();
+        let table = set.run(&candles);
+        assert_eq!(table.column("kijun").unwrap()[3], Some(102.0));
+        assert_eq!(table.column("span_a").unwrap()[4], None);
+        assert!(table.column("span_b").unwrap()[5].is_some());
+
+        let mut ichimoku = Ichimoku::standard();
+        ichimoku.update(&candle(0));
+        ichimoku.reset();
+        assert!(!Indicator::is_ready(&ichimoku));
+        assert!(Ichimoku::try_new(9, 26, 52, 0).is_err());
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+use crate::time::Timestamp;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Pivot point formula
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PivotMethod {
+    /// Floor-trader pivots: `P = (H + L + C) / 3`, levels from `2P - L`, `2P - H`, ...
+    Classic,
+    /// Fibonacci pivots: `P ± 0.382, 0.618, 1.0` times the range
+    Fibonacci,
+    /// Camarilla pivots: `C ± 1.1 / 12, 6, 4, 2` times the range
+    Camarilla,
+}
+
+/// Support and resistance levels derived from one session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PivotLevels {
+    pub pivot: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    /// Only set by [`PivotMethod::Camarilla`]
+    pub r4: Option<f64>,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+    /// Only set by [`PivotMethod::Camarilla`]
+    pub s4: Option<f64>,
+}
+
+impl PivotMethod {
+    /// Levels for the next session from this session's high, low and close
+    pub fn levels(&self, high: f64, low: f64, close: f64) -> PivotLevels {
+        let pivot = (high + low + close) / 3.0;
+        let range = high - low;
+        match self {
+            PivotMethod::Classic => PivotLevels {
+                pivot,
+                r1: 2.0 * pivot - low,
+                r2: pivot + range,
+                r3: high + 2.0 * (pivot - low),
+                r4: None,
+                s1: 2.0 * pivot - high,
+                s2: pivot - range,
+                s3: low - 2.0 * (high - pivot),
+                s4: None,
+            },
+            PivotMethod::Fibonacci => PivotLevels {
+                pivot,
+                r1: pivot + 0.382 * range,
+                r2: pivot + 0.618 * range,
+                r3: pivot + range,
+                r4: None,
+                s1: pivot - 0.382 * range,
+                s2: pivot - 0.618 * range,
+                s3: pivot - range,
+                s4: None,
+            },
+            PivotMethod::Camarilla => {
+                let step = |divisor: f64| 1.1 * range / divisor;
+                PivotLevels {
+                    pivot,
+                    r1: close + step(12.0),
+                    r2: close + step(6.0),
+                    r3: close + step(4.0),
+                    r4: Some(close + step(2.0)),
+                    s1: close - step(12.0),
+                    s2: close - step(6.0),
+                    s3: close - step(4.0),
+                    s4: Some(close - step(2.0)),
+                }
+            }
+        }
+    }
+
+    pub fn from_candle(&self, candle: &Candle) -> PivotLevels {
+        self.levels(candle.high, candle.low, candle.close)
+    }
+}
+
+/// Pivot levels of the previous session, from a stream of intraday candles
+///
+/// Candles are grouped into sessions of `session_ms` (shifted by a UTC offset, as for
+/// [`VWAP::daily`](super::VWAP::daily)). When a candle opens a new session, the
+/// completed one's high, low and last close produce the levels that apply until the
+/// next boundary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PivotPoints {
+    method: PivotMethod,
+    session_ms: i64,
+    utc_offset_ms: i64,
+    session: Option<i64>,
+    /// High, low and last close of the session in progress
+    high: f64,
+    low: f64,
+    close: f64,
+    current: Option<PivotLevels>,
+}
+
+impl PivotPoints {
+    /// Validating constructor
+    pub fn try_new(method: PivotMethod, session_ms: i64, utc_offset_ms: i64) -> Result<Self> {
+        if session_ms <= 0 {
+            return Err(MarketDataError::invalid_parameter(
+                "session_ms must be positive",
+            ));
+        }
+        Ok(Self {
+            method,
+            session_ms,
+            utc_offset_ms,
+            session: None,
+            high: f64::MIN,
+            low: f64::MAX,
+            close: 0.0,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(method: PivotMethod, session_ms: i64, utc_offset_ms: i64) -> Self {
+        Self::try_new(method, session_ms, utc_offset_ms).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Daily sessions starting at midnight shifted by `utc_offset_ms`
+    pub fn daily(method: PivotMethod, utc_offset_ms: i64) -> Self {
+        Self::new(method, DAY_MS, utc_offset_ms)
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<PivotLevels> {
+        let session =
+            (candle.timestamp.as_millis() + self.utc_offset_ms).div_euclid(self.session_ms);
+        match self.session {
+            Some(current) if session > current => {
+                self.current = Some(self.method.levels(self.high, self.low, self.close));
+                self.start_session(session, candle);
+            }
+            // Late candles of an already closed session are ignored
+            Some(current) if session < current => {}
+            Some(_) => {
+                self.high = self.high.max(candle.high);
+                self.low = self.low.min(candle.low);
+                self.close = candle.close;
+            }
+            None => self.start_session(session, candle),
+        }
+        self.current
+    }
+
+    fn start_session(&mut self, session: i64, candle: &Candle) {
+        self.session = Some(session);
+        self.high = candle.high;
+        self.low = candle.low;
+        self.close = candle.close;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.method, self.session_ms, self.utc_offset_ms);
+    }
+}
+
+impl Indicator<Candle, PivotLevels> for PivotPoints {
+    fn update(&mut self, input: Candle) -> Option<PivotLevels> {
+        PivotPoints::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        PivotPoints::reset(self)
+    }
+
+    fn current(&self) -> Option<PivotLevels> {
+        self.current
+    }
+}
+
+/// Whether a swing point is a local high or low
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// Confirmed local extreme
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SwingPoint {
+    pub kind: SwingKind,
+    pub price: f64,
+    /// Timestamp of the candle that made the extreme
+    pub timestamp: Timestamp,
+}
+
+/// Rolling swing-high/swing-low detector returning `(resistance, support)`
+///
+/// A candle is a swing high when its high is strictly above the highs of the
+/// `strength` candles on each side (a swing low mirrors this), so points are confirmed
+/// `strength` candles late. The latest confirmed swing high and low serve as
+/// resistance and support; the last `max_levels` of each are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwingDetector {
+    strength: usize,
+    max_levels: usize,
+    window: VecDeque<Candle>,
+    highs: VecDeque<SwingPoint>,
+    lows: VecDeque<SwingPoint>,
+}
+
+impl SwingDetector {
+    /// Validating constructor
+    pub fn try_new(strength: usize, max_levels: usize) -> Result<Self> {
+        check_period("strength", strength)?;
+        check_period("max_levels", max_levels)?;
+        Ok(Self {
+            strength,
+            max_levels,
+            window: VecDeque::with_capacity(2 * strength + 1),
+            highs: VecDeque::with_capacity(max_levels),
+            lows: VecDeque::with_capacity(max_levels),
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(strength: usize, max_levels: usize) -> Self {
+        Self::try_new(strength, max_levels).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Feed a candle; returns `(resistance, support)` once both kinds of swing exist
+    pub fn update(&mut self, candle: &Candle) -> Option<(f64, f64)> {
+        self.window.push_back(*candle);
+        if self.window.len() > 2 * self.strength + 1 {
+            self.window.pop_front();
+        }
+        if self.window.len() == 2 * self.strength + 1 {
+            let center = self.window[self.strength];
+            let others = || {
+                self.window
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != self.strength)
+                    .map(|(_, c)| c)
+            };
+            if others().all(|c| center.high > c.high) {
+                push_level(&mut self.highs, self.max_levels, SwingKind::High, &center);
+            }
+            if others().all(|c| center.low < c.low) {
+                push_level(&mut self.lows, self.max_levels, SwingKind::Low, &center);
+            }
+        }
+        self.current()
+    }
+
+    fn current(&self) -> Option<(f64, f64)> {
+        Some((self.highs.back()?.price, self.lows.back()?.price))
+    }
+
+    /// Confirmed swing highs, oldest first
+    pub fn swing_highs(&self) -> impl Iterator<Item = &SwingPoint> {
+        self.highs.iter()
+    }
+
+    /// Confirmed swing lows, oldest first
+    pub fn swing_lows(&self) -> impl Iterator<Item = &SwingPoint> {
+        self.lows.iter()
+    }
+
+    /// Nearest swing high above `price`
+    pub fn resistance_above(&self, price: f64) -> Option<f64> {
+        self.highs
+            .iter()
+            .map(|p| p.price)
+            .filter(|&p| p > price)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Nearest swing low below `price`
+    pub fn support_below(&self, price: f64) -> Option<f64> {
+        self.lows
+            .iter()
+            .map(|p| p.price)
+            .filter(|&p| p < price)
+            .max_by(f64::total_cmp)
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.highs.clear();
+        self.lows.clear();
+    }
+}
+
+fn push_level(levels: &mut VecDeque<SwingPoint>, max: usize, kind: SwingKind, candle: &Candle) {
+    let price = match kind {
+        SwingKind::High => candle.high,
+        SwingKind::Low => candle.low,
+    };
+    levels.push_back(SwingPoint {
+        kind,
+        price,
+        timestamp: candle.timestamp,
+    });
+    if levels.len() > max {
+        levels.pop_front();
+    }
+}
+
+impl Indicator<Candle, (f64, f64)> for SwingDetector {
+    fn update(&mut self, input: Candle) -> Option<(f64, f64)> {
+        SwingDetector::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        SwingDetector::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64)> {
+        SwingDetector::current(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60 * 1000;
+
+    fn candle(high: f64, low: f64, close: f64, timestamp: i64) -> Candle {
+        Candle::new(
+            close,
+            high,
+            low,
+            close,
+            1.0,
+            Timestamp::from_millis(timestamp),
+        )
+    }
+
+    #[test]
+    fn test_pivot_formulas() {
+        let classic = PivotMethod::Classic.levels(110.0, 90.0, 103.0);
+        assert_eq!(classic.pivot, 101.0);
+        assert_eq!((classic.r1, classic.s1), (112.0, 92.0));
+        assert_eq!((classic.r2, classic.s2), (121.0, 81.0));
+        assert_eq!((classic.r3, classic.s3), (132.0, 72.0));
+        assert_eq!(classic.r4, None);
+
+        let fib = PivotMethod::Fibonacci.levels(110.0, 90.0, 103.0);
+        assert!((fib.r1 - (101.0 + 0.382 * 20.0)).abs() < 1e-9);
+        assert_eq!(fib.s3, 81.0);
+
+        let camarilla = PivotMethod::Camarilla.levels(110.0, 90.0, 103.0);
+        assert!((camarilla.r4.unwrap() - 114.0).abs() < 1e-9);
+        assert!((camarilla.s1 - (103.0 - 22.0 / 12.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_session_pivots_use_the_previous_day() {
+        let mut pivots = PivotPoints::daily(PivotMethod::Classic, 0);
+        assert_eq!(pivots.update(&candle(105.0, 95.0, 100.0, 0)), None);
+        assert_eq!(pivots.update(&candle(110.0, 98.0, 104.0, HOUR)), None);
+        assert_eq!(pivots.update(&candle(106.0, 90.0, 103.0, 2 * HOUR)), None);
+
+        // First candle of day two publishes day one's levels: H 110, L 90, C 103
+        let levels = pivots.update(&candle(104.0, 100.0, 101.0, DAY_MS)).unwrap();
+        assert_eq!(levels, PivotMethod::Classic.levels(110.0, 90.0, 103.0));
+        // They hold for the rest of the session
+        assert_eq!(
+            pivots.update(&candle(150.0, 50.0, 120.0, DAY_MS + HOUR)),
+            Some(levels)
+        );
+
+        pivots.reset();
+        assert!(!Indicator::is_ready(&pivots));
+        assert!(PivotPoints::try_new(PivotMethod::Classic, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_swing_points_confirm_late() {
+        let mut swings = SwingDetector::new(1, 3);
+        let bars = [
+            (10.0, 8.0),
+            (12.0, 9.0),  // swing high
+            (11.0, 7.0),  // swing low
+            (13.0, 10.0), // swing high
+            (12.5, 9.5),
+        ];
+        let outputs: Vec<_> = bars
+            .iter()
+            .enumerate()
+            .map(|(i, &(h, l))| swings.update(&candle(h, l, l, i as i64)))
+            .collect();
+
+        assert_eq!(outputs[..3], [None, None, None]);
+        // Bar 3 confirms the low at bar 2, then bar 4 the high at bar 3
+        assert_eq!(outputs[3], Some((12.0, 7.0)));
+        assert_eq!(outputs[4], Some((13.0, 7.0)));
+
+        let highs: Vec<i64> = swings
+            .swing_highs()
+            .map(|p| p.timestamp.as_millis())
+            .collect();
+        assert_eq!(highs, [1, 3]);
+        assert_eq!(swings.resistance_above(12.2), Some(13.0));
+        assert_eq!(swings.support_below(7.5), Some(7.0));
+        assert_eq!(swings.support_below(6.0), None);
+    }
+}
@@ -1,28 +1,140 @@
 use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MarketDataError, Result};
+use crate::numeric::{widen, KahanSum, Real};
+
+mod adx;
+mod atr;
+mod batch;
+mod channels;
+mod hurst;
+mod ichimoku;
+mod levels;
+mod momentum;
+mod paired;
+mod sar;
+mod stochastic;
+mod supertrend;
+mod volatility;
+mod volume;
+mod vwap;
+mod zscore;
+
+pub use adx::ADX;
+pub use atr::{OnClose, ATR};
+pub use batch::{
+    bollinger_series, ema_series, macd_series, rsi_series, sma_series, unzip3, IndicatorOutput,
+    IndicatorSet, IndicatorTable,
+};
+pub use channels::{DonchianChannels, KeltnerChannels};
+pub use hurst::{rescaled_range_hurst, HurstExponent, HurstRegime};
+pub use ichimoku::{Ichimoku, IchimokuOutput};
+pub use levels::{PivotLevels, PivotMethod, PivotPoints, SwingDetector, SwingKind, SwingPoint};
+pub use momentum::{Momentum, WilliamsR, ROC};
+pub use paired::PairedReturns;
+pub use sar::{ParabolicSAR, SarOutput, SarTrend};
+pub use stochastic::Stochastic;
+pub use supertrend::{SuperTrend, SuperTrendOutput};
+pub use volatility::{RealizedVolatility, RollingStdDev, RollingVariance};
+pub use volume::{AccumulationDistribution, CMF, MFI, OBV};
+pub use vwap::VWAP;
+pub use zscore::ZScore;
+
+fn check_period(name: &str, period: usize) -> Result<()> {
+    if period == 0 {
+        return Err(MarketDataError::invalid_parameter(format!("{name} must be at least 1")));
+    }
+    Ok(())
+}
+
+/// Common streaming interface of all indicators
+///
+/// `Input` is what one update consumes (a close price, a candle, ...) and `Output` what
+/// it produces, so indicators of the same shape can be driven generically or stored
+/// together as `Vec<Box<dyn Indicator<f64, f64>>>`.
+pub trait Indicator<Input, Output> {
+    /// Feed one input; `None` while warming up
+    fn update(&mut self, input: Input) -> Option<Output>;
+
+    /// Clear all state as if freshly constructed
+    fn reset(&mut self);
+
+    /// Enough inputs have been seen to produce a value
+    fn is_ready(&self) -> bool {
+        self.current().is_some()
+    }
+
+    /// Latest value, without consuming input
+    fn current(&self) -> Option<Output>;
+
+    /// Feed a whole series, returning one output per input
+    ///
+    /// State carries over, so streaming can continue after a historical warm-up. For
+    /// stateless backtests the `*_series` functions are faster.
+    fn compute_series(&mut self, inputs: &[Input]) -> Vec<Option<Output>>
+    where
+        Input: Clone,
+    {
+        inputs.iter().map(|input| self.update(input.clone())).collect()
+    }
+}
+
+impl<I, O, T: Indicator<I, O> + ?Sized> Indicator<I, O> for Box<T> {
+    fn update(&mut self, input: I) -> Option<O> {
+        (**self).update(input)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn is_ready(&self) -> bool {
+        (**self).is_ready()
+    }
+
+    fn current(&self) -> Option<O> {
+        (**self).current()
+    }
+}
 
 /// Simple Moving Average calculator
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SMA {
     period: usize,
-    values: VecDeque<f64>,
+    values: VecDeque<Real>,
+    sum: KahanSum,
 }
 
 impl SMA {
-    pub fn new(period: usize) -> Self {
-        Self {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
             period,
             values: VecDeque::with_capacity(period),
-        }
+            sum: KahanSum::new(),
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn update(&mut self, value: f64) -> Option<f64> {
+        let value = value as Real;
         self.values.push_back(value);
+        self.sum += widen(value);
         
         if self.values.len() > self.period {
-            self.values.pop_front();
+            if let Some(old) = self.values.pop_front() {
+                self.sum -= widen(old);
+            }
         }
         
         if self.values.len() == self.period {
-            Some(self.values.iter().sum::<f64>() / self.period as f64)
+            Some(self.sum.value() / self.period as f64)
         } else {
             None
         }
@@ -30,10 +142,30 @@ impl SMA {
 
     pub fn reset(&mut self) {
         self.values.clear();
+        self.sum.reset();
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<f64, f64> for SMA {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        SMA::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        SMA::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        (self.values.len() == self.period).then(|| self.sum.value() / self.period as f64)
     }
 }
 
 /// Exponential Moving Average calculator
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EMA {
     period: usize,
     multiplier: f64,
@@ -41,13 +173,20 @@ pub struct EMA {
 }
 
 impl EMA {
-    pub fn new(period: usize) -> Self {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
         let multiplier = 2.0 / (period as f64 + 1.0);
-        Self {
+        Ok(Self {
             period,
             multiplier,
             current: None,
-        }
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn update(&mut self, value: f64) -> Option<f64> {
@@ -67,90 +206,346 @@ impl EMA {
     pub fn reset(&mut self) {
         self.current = None;
     }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<f64, f64> for EMA {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        EMA::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        EMA::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Linearly Weighted Moving Average: the newest value has weight `period`, the oldest 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WMA {
+    period: usize,
+    values: VecDeque<Real>,
+    /// Plain sum of the window, used to shift every weight down by one per update
+    sum: KahanSum,
+    weighted_sum: KahanSum,
+}
+
+impl WMA {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            values: VecDeque::with_capacity(period),
+            sum: KahanSum::new(),
+            weighted_sum: KahanSum::new(),
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let stored = value as Real;
+        let value = widen(stored);
+
+        if self.values.len() == self.period {
+            self.weighted_sum += self.period as f64 * value - self.sum.value();
+            if let Some(old) = self.values.pop_front() {
+                self.sum -= widen(old);
+            }
+        } else {
+            self.weighted_sum += (self.values.len() + 1) as f64 * value;
+        }
+        self.values.push_back(stored);
+        self.sum += value;
+
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        let n = self.period as f64;
+        let weights = n * (n + 1.0) / 2.0;
+        (self.values.len() == self.period).then(|| self.weighted_sum.value() / weights)
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.sum.reset();
+        self.weighted_sum.reset();
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<f64, f64> for WMA {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        WMA::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        WMA::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        WMA::current(self)
+    }
+}
+
+/// Hull Moving Average: `WMA(2 * WMA(n / 2) - WMA(n))` over `floor(sqrt(n))` values
+///
+/// Doubling the faster average and subtracting the slower one cancels most of the lag,
+/// and the final short WMA smooths the result. On a straight line it tracks the price
+/// exactly once warmed up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HMA {
+    period: usize,
+    half: WMA,
+    full: WMA,
+    smoother: WMA,
+    current: Option<f64>,
+}
+
+impl HMA {
+    /// Validating constructor; periods below 2 have no half-length average
+    pub fn try_new(period: usize) -> Result<Self> {
+        if period < 2 {
+            return Err(MarketDataError::invalid_parameter("period must be at least 2"));
+        }
+        let sqrt = ((period as f64).sqrt() as usize).max(1);
+        Ok(Self {
+            period,
+            half: WMA::new((period / 2).max(1)),
+            full: WMA::new(period),
+            smoother: WMA::new(sqrt),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let half = self.half.update(value);
+        let full = self.full.update(value)?;
+        self.current = self.smoother.update(2.0 * half? - full);
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.half.reset();
+        self.full.reset();
+        self.smoother.reset();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<f64, f64> for HMA {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        HMA::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        HMA::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// How [`RSI`] averages gains and losses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RsiSmoothing {
+    /// Simple mean over the last `period` changes
+    Simple,
+    /// Wilder's recursive average, as used by TradingView and TA-Lib
+    Wilder,
+}
+
+fn rsi_from(avg_gain: f64, avg_loss: f64) -> f64 {
+    if avg_loss == 0.0 {
+        return 100.0;
+    }
+    let rs = avg_gain / avg_loss;
+    100.0 - (100.0 / (1.0 + rs))
 }
 
 /// RSI (Relative Strength Index) calculator
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RSI {
     period: usize,
-    gains: VecDeque<f64>,
-    losses: VecDeque<f64>,
+    smoothing: RsiSmoothing,
+    gains: VecDeque<Real>,
+    losses: VecDeque<Real>,
+    /// Wilder averages once seeded
+    averages: Option<(f64, f64)>,
     prev_close: Option<f64>,
+    current: Option<f64>,
 }
 
 impl RSI {
-    pub fn new(period: usize) -> Self {
-        Self {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
             period,
+            smoothing: RsiSmoothing::Simple,
             gains: VecDeque::with_capacity(period),
             losses: VecDeque::with_capacity(period),
+            averages: None,
             prev_close: None,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Validating constructor for [`wilder`](Self::wilder)
+    pub fn try_wilder(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self::wilder(period))
+    }
+
+    /// Wilder-smoothed RSI: seeded with the simple mean of the first `period` changes,
+    /// then `avg = (avg * (period - 1) + x) / period`
+    pub fn wilder(period: usize) -> Self {
+        Self {
+            smoothing: RsiSmoothing::Wilder,
+            ..Self::new(period)
         }
     }
 
+    pub fn smoothing(&self) -> RsiSmoothing {
+        self.smoothing
+    }
+
     pub fn update(&mut self, close: f64) -> Option<f64> {
-        if let Some(prev) = self.prev_close {
-            let change = close - prev;
-            
-            if change > 0.0 {
-                self.gains.push_back(change);
-                self.losses.push_back(0.0);
-            } else {
-                self.gains.push_back(0.0);
-                self.losses.push_back(change.abs());
-            }
-            
-            if self.gains.len() > self.period {
-                self.gains.pop_front();
-                self.losses.pop_front();
-            }
-            
-            if self.gains.len() == self.period {
-                let avg_gain = self.gains.iter().sum::<f64>() / self.period as f64;
-                let avg_loss = self.losses.iter().sum::<f64>() / self.period as f64;
-                
-                if avg_loss == 0.0 {
-                    return Some(100.0);
-                }
-                
-                let rs = avg_gain / avg_loss;
-                let rsi = 100.0 - (100.0 / (1.0 + rs));
-                
-                self.prev_close = Some(close);
-                return Some(rsi);
-            }
+        let rsi = self.push(close);
+        self.prev_close = Some(close);
+        if rsi.is_some() {
+            self.current = rsi;
+        }
+        rsi
+    }
+
+    fn push(&mut self, close: f64) -> Option<f64> {
+        let prev = self.prev_close?;
+        let change = close - prev;
+
+        if let Some((avg_gain, avg_loss)) = self.averages {
+            let n = self.period as f64;
+            let avg_gain = (avg_gain * (n - 1.0) + change.max(0.0)) / n;
+            let avg_loss = (avg_loss * (n - 1.0) + (-change).max(0.0)) / n;
+            self.averages = Some((avg_gain, avg_loss));
+            return Some(rsi_from(avg_gain, avg_loss));
         }
         
-        self.prev_close = Some(close);
-        None
+        if change > 0.0 {
+            self.gains.push_back(change as Real);
+            self.losses.push_back(0.0);
+        } else {
+            self.gains.push_back(0.0);
+            self.losses.push_back(change.abs() as Real);
+        }
+        
+        if self.gains.len() > self.period {
+            self.gains.pop_front();
+            self.losses.pop_front();
+        }
+        
+        if self.gains.len() < self.period {
+            return None;
+        }
+
+        let avg_gain = self.gains.iter().map(|&g| widen(g)).sum::<f64>() / self.period as f64;
+        let avg_loss = self.losses.iter().map(|&l| widen(l)).sum::<f64>() / self.period as f64;
+
+        if self.smoothing == RsiSmoothing::Wilder {
+            // Seeded: from now on the recursive averages replace the window
+            self.averages = Some((avg_gain, avg_loss));
+            self.gains.clear();
+            self.losses.clear();
+        }
+
+        Some(rsi_from(avg_gain, avg_loss))
     }
 
     pub fn reset(&mut self) {
         self.gains.clear();
         self.losses.clear();
+        self.averages = None;
         self.prev_close = None;
+        self.current = None;
+    }
+}
+
+impl Indicator<f64, f64> for RSI {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        RSI::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        RSI::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
     }
 }
 
 /// Bollinger Bands calculator
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BollingerBands {
     sma: SMA,
     period: usize,
     std_dev: f64,
-    values: VecDeque<f64>,
+    values: VecDeque<Real>,
+    current: Option<(f64, f64, f64)>,
 }
 
 impl BollingerBands {
-    pub fn new(period: usize, std_dev: f64) -> Self {
-        Self {
+    /// Validating constructor
+    pub fn try_new(period: usize, std_dev: f64) -> Result<Self> {
+        check_period("period", period)?;
+        if !(std_dev.is_finite() && std_dev > 0.0) {
+            return Err(MarketDataError::invalid_parameter("std_dev must be finite and positive"));
+        }
+        Ok(Self {
             sma: SMA::new(period),
             period,
             std_dev,
             values: VecDeque::with_capacity(period),
-        }
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize, std_dev: f64) -> Self {
+        Self::try_new(period, std_dev).unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn update(&mut self, value: f64) -> Option<(f64, f64, f64)> {
-        self.values.push_back(value);
+        self.values.push_back(value as Real);
         
         if self.values.len() > self.period {
             self.values.pop_front();
@@ -160,14 +555,15 @@ impl BollingerBands {
             if self.values.len() == self.period {
                 let variance = self.values
                     .iter()
-                    .map(|v| (v - middle).powi(2))
+                    .map(|&v| (widen(v) - middle).powi(2))
                     .sum::<f64>() / self.period as f64;
                 
                 let std = variance.sqrt();
                 let upper = middle + (self.std_dev * std);
                 let lower = middle - (self.std_dev * std);
                 
-                return Some((upper, middle, lower));
+                self.current = Some((upper, middle, lower));
+                return self.current;
             }
         }
         
@@ -177,23 +573,52 @@ impl BollingerBands {
     pub fn reset(&mut self) {
         self.sma.reset();
         self.values.clear();
+        self.current = None;
+    }
+}
+
+impl Indicator<f64, (f64, f64, f64)> for BollingerBands {
+    fn update(&mut self, input: f64) -> Option<(f64, f64, f64)> {
+        BollingerBands::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        BollingerBands::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        self.current
     }
 }
 
 /// MACD (Moving Average Convergence Divergence) calculator
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MACD {
     fast_ema: EMA,
     slow_ema: EMA,
     signal_ema: EMA,
+    current: Option<(f64, f64, f64)>,
 }
 
 impl MACD {
-    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
-        Self {
+    /// Validating constructor
+    pub fn try_new(fast_period: usize, slow_period: usize, signal_period: usize) -> Result<Self> {
+        check_period("fast_period", fast_period)?;
+        check_period("signal_period", signal_period)?;
+        if slow_period <= fast_period {
+            return Err(MarketDataError::invalid_parameter("slow_period must be longer than fast_period"));
+        }
+        Ok(Self {
             fast_ema: EMA::new(fast_period),
             slow_ema: EMA::new(slow_period),
             signal_ema: EMA::new(signal_period),
-        }
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self::try_new(fast_period, slow_period, signal_period).unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn update(&mut self, close: f64) -> Option<(f64, f64, f64)> {
@@ -202,7 +627,8 @@ impl MACD {
             
             if let Some(signal_line) = self.signal_ema.update(macd_line) {
                 let histogram = macd_line - signal_line;
-                return Some((macd_line, signal_line, histogram));
+                self.current = Some((macd_line, signal_line, histogram));
+                return self.current;
             }
         }
         
@@ -213,6 +639,21 @@ impl MACD {
         self.fast_ema.reset();
         self.slow_ema.reset();
         self.signal_ema.reset();
+        self.current = None;
+    }
+}
+
+impl Indicator<f64, (f64, f64, f64)> for MACD {
+    fn update(&mut self, input: f64) -> Option<(f64, f64, f64)> {
+        MACD::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        MACD::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        self.current
     }
 }
 
@@ -220,6 +661,27 @@ impl MACD {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_try_new_validates_parameters() {
+        assert!(SMA::try_new(0).is_err());
+        assert!(EMA::try_new(0).is_err());
+        assert!(RSI::try_new(14).is_ok());
+        assert!(BollingerBands::try_new(20, -1.0).is_err());
+        assert!(BollingerBands::try_new(20, f64::NAN).is_err());
+        assert!(MACD::try_new(26, 12, 9).is_err());
+        assert!(matches!(
+            MACD::try_new(12, 26, 0),
+            Err(MarketDataError::InvalidParameter(_))
+        ));
+        assert!(MACD::try_new(12, 26, 9).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "period must be at least 1")]
+    fn test_new_panics_on_invalid_parameters() {
+        SMA::new(0);
+    }
+
     #[test]
     fn test_sma() {
         let mut sma = SMA::new(3);
@@ -243,6 +705,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_rsi() {
         let mut rsi = RSI::new(14);
         
@@ -259,12 +722,13 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn test_bollinger_bands() {
         let mut bb = BollingerBands::new(20, 2.0);
         
         // Feed some data
         for i in 1..=25 {
-            let result = bb.update(50.0 + (i as f64 % 10) as f64);
+            let result = bb.update(50.0 + (i as f64 % 10.0) as f64);
             
             if i >= 20 {
                 assert!(result.is_some());
@@ -275,6 +739,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rsi_all_gains_keeps_tracking_closes() {
+        let mut rsi = RSI::new(2);
+        for close in [1.0, 2.0, 3.0, 4.0] {
+            rsi.update(close);
+        }
+        assert_eq!(rsi.current(), Some(100.0));
+
+        // Change is measured from 4.0, the previous close
+        let value = rsi.update(3.0).unwrap();
+        assert!((value - 50.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wilder_rsi_matches_reference() {
+        // Wilder's 14-period example as published by StockCharts
+        let closes = [
+            44.3389, 44.0902, 44.1497, 43.6124, 44.3278, 44.8264, 45.0955, 45.4245, 45.8433,
+            46.0826, 45.8931, 46.0328, 45.6140, 46.2820, 46.2820, 46.0028, 46.0328, 46.4116,
+            46.2222, 45.6439, 46.2122, 46.2521, 45.7137, 46.4515, 45.7835, 45.3548, 44.0288,
+            44.1783, 44.2181, 44.5672, 43.4205, 42.6628, 43.1314,
+        ];
+        let expected = [
+            70.53, 66.32, 66.55, 69.41, 66.36, 57.97, 62.93, 63.26, 56.06, 62.38, 54.71, 50.42,
+            39.99, 41.46, 41.87, 45.46, 37.30, 33.08, 37.77,
+        ];
+
+        let mut rsi = RSI::wilder(14);
+        let values: Vec<f64> = closes.iter().filter_map(|&c| rsi.update(c)).collect();
+        assert_eq!(values.len(), expected.len());
+        for (value, reference) in values.iter().zip(expected) {
+            assert!((value - reference).abs() < 0.01, "{value} vs {reference}");
+        }
+        assert_eq!(rsi.smoothing(), RsiSmoothing::Wilder);
+    }
+
+    #[test]
+    fn test_wilder_rsi_reset_reseeds() {
+        let mut rsi = RSI::try_wilder(2).unwrap();
+        for close in [1.0, 2.0, 3.0, 2.0] {
+            rsi.update(close);
+        }
+        rsi.reset();
+        assert_eq!(rsi.update(1.0), None);
+        assert_eq!(rsi.update(2.0), None);
+        // Fresh seed from two up-moves
+        assert_eq!(rsi.update(3.0), Some(100.0));
+        assert!(RSI::try_wilder(0).is_err());
+    }
+
+    #[test]
+    fn test_heterogeneous_indicators_behind_trait() {
+        let mut indicators: Vec<Box<dyn Indicator<f64, f64>>> = vec![
+            Box::new(SMA::new(3)),
+            Box::new(EMA::new(3)),
+            Box::new(RSI::new(3)),
+        ];
+
+        for price in [10.0, 11.0, 12.0] {
+            for indicator in indicators.iter_mut() {
+                indicator.update(price);
+            }
+        }
+        let ready: Vec<bool> = indicators.iter().map(|i| i.is_ready()).collect();
+        assert_eq!(ready, vec![true, true, false]);
+        assert_eq!(indicators[0].current(), Some(11.0));
+
+        for indicator in indicators.iter_mut() {
+            indicator.reset();
+            assert!(!indicator.is_ready());
+        }
+    }
+
+    #[test]
+    fn test_generic_driver_over_multi_output_indicators() {
+        fn run<O, I: Indicator<f64, O>>(indicator: &mut I, prices: &[f64]) -> Option<O> {
+            prices.iter().filter_map(|&p| indicator.update(p)).last()
+        }
+
+        let prices: Vec<f64> = (0..60).map(|i| 100.0 + (i % 7) as f64).collect();
+        let mut bb = BollingerBands::new(20, 2.0);
+        let mut macd = MACD::new(12, 26, 9);
+
+        assert_eq!(run(&mut bb, &prices), Indicator::current(&bb));
+        assert_eq!(run(&mut macd, &prices), Indicator::current(&macd));
+        assert!(Indicator::is_ready(&macd));
+    }
+
     #[test]
     fn test_macd() {
         let mut macd = MACD::new(12, 26, 9);
@@ -287,4 +839,67 @@ mod tests {
         let result = macd.update(100.0);
         assert!(result.is_some());
     }
+
+    #[test]
+    fn test_state_survives_serde_round_trip() {
+        let prices: Vec<f64> = (0..40).map(|i| 100.0 + (i % 9) as f64 * 0.7).collect();
+        let mut rsi = RSI::new(14);
+        let mut macd = MACD::new(12, 26, 9);
+        for &p in &prices {
+            rsi.update(p);
+            macd.update(p);
+        }
+
+        // Persist mid-stream, then resume as a restarted process would
+        let mut restored_rsi: RSI = serde_json::from_str(&serde_json::to_string(&rsi).unwrap()).unwrap();
+        let mut restored_macd: MACD =
+            serde_json::from_str(&serde_json::to_string(&macd).unwrap()).unwrap();
+
+        for p in [104.0, 101.5, 107.25] {
+            assert_eq!(restored_rsi.update(p), rsi.update(p));
+            assert_eq!(restored_macd.update(p), macd.update(p));
+        }
+    }
+
+    #[test]
+    fn test_wma_reference_values() {
+        let mut wma = WMA::new(3);
+        assert_eq!(wma.update(1.0), None);
+        assert_eq!(wma.update(2.0), None);
+        // (1 + 2 * 2 + 3 * 3) / 6
+        assert_eq!(wma.update(3.0), Some(14.0 / 6.0));
+        // (2 + 2 * 3 + 3 * 10) / 6
+        assert_eq!(wma.update(10.0), Some(38.0 / 6.0));
+
+        let prices: Vec<f64> = (0..50).map(|i| 100.0 + ((i * 7) % 11) as f64).collect();
+        let mut wma = WMA::new(5);
+        for (t, &p) in prices.iter().enumerate() {
+            let value = wma.update(p);
+            if t >= 4 {
+                let naive: f64 =
+                    (0..5).map(|k| (k + 1) as f64 * prices[t - 4 + k]).sum::<f64>() / 15.0;
+                assert!((value.unwrap() - naive).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hma_tracks_a_straight_line() {
+        let mut hma = HMA::new(4);
+        let mut outputs = Vec::new();
+        for t in 0..10 {
+            outputs.push(hma.update(10.0 + 2.0 * t as f64));
+        }
+
+        // WMA(4) needs 4 values, then WMA(2) of the difference one more
+        assert!(outputs[..4].iter().all(Option::is_none));
+        let tolerance = if cfg!(feature = "f32-storage") { 1e-4 } else { 1e-9 };
+        for (t, value) in outputs.iter().enumerate().skip(4) {
+            assert!((value.unwrap() - (10.0 + 2.0 * t as f64)).abs() < tolerance);
+        }
+
+        hma.reset();
+        assert!(!Indicator::is_ready(&hma));
+        assert!(HMA::try_new(1).is_err());
+    }
 }
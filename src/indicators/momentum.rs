@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::candles::Candle;
+use crate::error::Result;
+use crate::numeric::{widen, Real};
+
+/// Window holding the last `period + 1` values, so the value `period` updates ago is
+/// at the front
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Lookback {
+    period: usize,
+    values: VecDeque<Real>,
+}
+
+impl Lookback {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            values: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    /// Push `value` and return the one `period` updates before it
+    fn push(&mut self, value: f64) -> Option<f64> {
+        self.values.push_back(value as Real);
+        if self.values.len() > self.period + 1 {
+            self.values.pop_front();
+        }
+        (self.values.len() == self.period + 1).then(|| widen(self.values[0]))
+    }
+}
+
+/// Momentum: change of the value over `period` updates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Momentum {
+    lookback: Lookback,
+    current: Option<f64>,
+}
+
+impl Momentum {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            lookback: Lookback::new(period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let past = self.lookback.push(value)?;
+        self.current = Some(value - past);
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.lookback.values.clear();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.lookback.period
+    }
+}
+
+impl Indicator<f64, f64> for Momentum {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        Momentum::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        Momentum::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Rate of Change: percentage change of the value over `period` updates
+///
+/// Undefined (no output) while the value `period` updates ago is zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ROC {
+    lookback: Lookback,
+    current: Option<f64>,
+}
+
+impl ROC {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            lookback: Lookback::new(period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let past = self.lookback.push(value)?;
+        if past == 0.0 {
+            return None;
+        }
+        self.current = Some((value - past) / past * 100.0);
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.lookback.values.clear();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.lookback.period
+    }
+}
+
+impl Indicator<f64, f64> for ROC {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        ROC::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        ROC::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Williams %R: where the close sits below the highest high of the last `period`
+/// candles, from 0 (at the high) to -100 (at the low); -50 for a flat range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WilliamsR {
+    period: usize,
+    highs: VecDeque<Real>,
+    lows: VecDeque<Real>,
+    current: Option<f64>,
+}
+
+impl WilliamsR {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            highs: VecDeque::with_capacity(period),
+            lows: VecDeque::with_capacity(period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        self.highs.push_back(candle.high as Real);
+        self.lows.push_back(candle.low as Real);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+        if self.highs.len() < self.period {
+            return None;
+        }
+
+        let highest = self
+            .highs
+            .iter()
+            .map(|&h| widen(h))
+            .fold(f64::MIN, f64::max);
+        let lowest = self.lows.iter().map(|&l| widen(l)).fold(f64::MAX, f64::min);
+        self.current = Some(if highest > lowest {
+            (highest - candle.close) / (highest - lowest) * -100.0
+        } else {
+            -50.0
+        });
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.highs.clear();
+        self.lows.clear();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, f64> for WilliamsR {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        WilliamsR::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        WilliamsR::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_momentum_and_roc() {
+        let mut momentum = Momentum::new(2);
+        let mut roc = ROC::new(2);
+        let prices = [100.0, 102.0, 110.0, 99.0];
+        let momentum_out: Vec<_> = prices.iter().map(|&p| momentum.update(p)).collect();
+        let roc_out: Vec<_> = prices.iter().map(|&p| roc.update(p)).collect();
+
+        assert_eq!(momentum_out, [None, None, Some(10.0), Some(-3.0)]);
+        assert_eq!(roc_out[..2], [None, None]);
+        assert!((roc_out[2].unwrap() - 10.0).abs() < 1e-9);
+        assert!((roc_out[3].unwrap() + 3.0 / 102.0 * 100.0).abs() < 1e-9);
+
+        roc.reset();
+        assert!(!Indicator::is_ready(&roc));
+        assert!(Momentum::try_new(0).is_err());
+    }
+
+    #[test]
+    fn test_williams_r_range() {
+        let mut williams = WilliamsR::new(3);
+        assert_eq!(williams.update(&candle(10.0, 8.0, 9.0)), None);
+        assert_eq!(williams.update(&candle(12.0, 9.0, 11.0)), None);
+        // Range 7..12, close at the low
+        assert_eq!(williams.update(&candle(11.0, 7.0, 7.0)), Some(-100.0));
+        // Close at the high
+        assert_eq!(williams.update(&candle(12.0, 8.0, 12.0)), Some(0.0));
+        // Range 7..12, close 9.5 halfway
+        assert_eq!(williams.update(&candle(10.0, 9.0, 9.5)), Some(-50.0));
+
+        let mut flat = WilliamsR::new(2);
+        flat.update(&candle(5.0, 5.0, 5.0));
+        assert_eq!(flat.update(&candle(5.0, 5.0, 5.0)), Some(-50.0));
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use super::Indicator;
+use crate::returns::ReturnKind;
+
+/// Turns synchronized `(a, b)` price pairs into return pairs for a two-input indicator
+///
+/// Cross-asset indicators such as [`RollingCorrelation`](crate::analytics::RollingCorrelation)
+/// and [`RollingBeta`](crate::analytics::RollingBeta) work on returns; this adapter lets
+/// them consume prices of two symbols sampled at the same times. The first pair only
+/// sets the reference, and pairs with a non-positive or non-finite price are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedReturns<T> {
+    inner: T,
+    kind: ReturnKind,
+    prev: Option<(f64, f64)>,
+}
+
+impl<T> PairedReturns<T> {
+    pub fn new(inner: T, kind: ReturnKind) -> Self {
+        Self {
+            inner,
+            kind,
+            prev: None,
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<O, T: Indicator<(f64, f64), O>> Indicator<(f64, f64), O> for PairedReturns<T> {
+    fn update(&mut self, (a, b): (f64, f64)) -> Option<O> {
+        let valid = |p: f64| p.is_finite() && p > 0.0;
+        if !(valid(a) && valid(b)) {
+            return None;
+        }
+        let (prev_a, prev_b) = self.prev.replace((a, b))?;
+        let returns = (self.kind.between(prev_a, a)?, self.kind.between(prev_b, b)?);
+        self.inner.update(returns)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.prev = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+
+    fn current(&self) -> Option<O> {
+        self.inner.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::{RollingBeta, RollingCorrelation};
+
+    #[test]
+    fn test_correlation_and_beta_from_prices() {
+        let a = [100.0, 101.0, 99.5, 102.0, 103.0, 101.5];
+        // b moves twice as much as a in log terms
+        let pairs: Vec<(f64, f64)> = a.iter().map(|&p| (p, p * p / 100.0)).collect();
+
+        let mut correlation = PairedReturns::new(RollingCorrelation::pearson(4), ReturnKind::Log);
+        let mut beta = PairedReturns::new(RollingBeta::new(4), ReturnKind::Log);
+        let correlations = correlation.compute_series(&pairs);
+        // Regress b on a: the asset is the second leg
+        let swapped: Vec<(f64, f64)> = pairs.iter().map(|&(x, y)| (y, x)).collect();
+        let betas = beta.compute_series(&swapped);
+
+        assert!(correlations[..4].iter().all(Option::is_none));
+        assert!((correlations[5].unwrap() - 1.0).abs() < 1e-9);
+        assert!((betas[5].unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bad_prices_are_skipped() {
+        let mut correlation =
+            PairedReturns::new(RollingCorrelation::pearson(2), ReturnKind::Simple);
+        assert_eq!(correlation.update((100.0, 50.0)), None);
+        assert_eq!(correlation.update((0.0, 51.0)), None);
+        assert_eq!(correlation.update((101.0, f64::NAN)), None);
+        correlation.update((102.0, 52.0));
+        assert!((correlation.update((101.0, 51.0)).unwrap() - 1.0).abs() < 1e-9);
+
+        correlation.reset();
+        assert!(!correlation.is_ready());
+        assert_eq!(
+            correlation.inner().method(),
+            crate::analytics::CorrelationMethod::Pearson
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Indicator, IndicatorOutput};
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+
+/// Direction of the Parabolic SAR trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SarTrend {
+    /// SAR trails below price
+    Up,
+    /// SAR trails above price
+    Down,
+}
+
+/// One Parabolic SAR reading
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SarOutput {
+    /// Stop level for this bar
+    pub sar: f64,
+    pub trend: SarTrend,
+    /// The trend flipped on this bar
+    pub reversed: bool,
+}
+
+/// Columns: SAR, then the trend as `1.0` (up) or `-1.0` (down)
+impl IndicatorOutput for SarOutput {
+    const WIDTH: usize = 2;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self.sar));
+        columns[1].push(Some(match self.trend {
+            SarTrend::Up => 1.0,
+            SarTrend::Down => -1.0,
+        }));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SarState {
+    trend: SarTrend,
+    sar: f64,
+    /// Extreme point: highest high of an uptrend, lowest low of a downtrend
+    extreme: f64,
+    acceleration: f64,
+}
+
+/// Wilder's Parabolic Stop and Reverse
+///
+/// The SAR moves towards the trend's extreme point by an acceleration factor that
+/// starts at `step`, grows by `step` on every new extreme and is capped at `max_step`.
+/// It never moves inside the previous two bars' range; when price penetrates it, the
+/// trend reverses and the SAR jumps to the old extreme. The initial direction comes
+/// from the directional movement between the first two candles, which also produce
+/// the first output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParabolicSAR {
+    step: f64,
+    max_step: f64,
+    /// (high, low) of the previous two candles, most recent first
+    prev: Option<(f64, f64)>,
+    prev2: Option<(f64, f64)>,
+    state: Option<SarState>,
+    current: Option<SarOutput>,
+}
+
+impl ParabolicSAR {
+    /// Validating constructor; requires `0 < step <= max_step`
+    pub fn try_new(step: f64, max_step: f64) -> Result<Self> {
+        if !(step.is_finite() && step > 0.0 && max_step.is_finite() && step <= max_step) {
+            return Err(MarketDataError::invalid_parameter(
+                "step must be positive and at most max_step",
+            ));
+        }
+        Ok(Self {
+            step,
+            max_step,
+            prev: None,
+            prev2: None,
+            state: None,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(step: f64, max_step: f64) -> Self {
+        Self::try_new(step, max_step).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<SarOutput> {
+        let Some((prev_high, prev_low)) = self.prev else {
+            self.prev = Some((candle.high, candle.low));
+            return None;
+        };
+
+        let (state, reversed) = match self.state {
+            None => {
+                let up = candle.high - prev_high >= prev_low - candle.low;
+                let state = if up {
+                    SarState {
+                        trend: SarTrend::Up,
+                        sar: prev_low,
+                        extreme: candle.high.max(prev_high),
+                        acceleration: self.step,
+                    }
+                } else {
+                    SarState {
+                        trend: SarTrend::Down,
+                        sar: prev_high,
+                        extreme: candle.low.min(prev_low),
+                        acceleration: self.step,
+                    }
+                };
+                (state, false)
+            }
+            Some(state) => self.advance(state, candle, (prev_high, prev_low)),
+        };
+
+        self.state = Some(state);
+        self.prev2 = self.prev;
+        self.prev = Some((candle.high, candle.low));
+        self.current = Some(SarOutput {
+            sar: state.sar,
+            trend: state.trend,
+            reversed,
+        });
+        self.current
+    }
+
+    /// Next state and whether it reversed
+    fn advance(&self, state: SarState, candle: &Candle, prev: (f64, f64)) -> (SarState, bool) {
+        let SarState {
+            trend,
+            sar,
+            extreme,
+            acceleration,
+        } = state;
+        let prev2 = self.prev2.unwrap_or(prev);
+        let mut sar = sar + acceleration * (extreme - sar);
+
+        match trend {
+            SarTrend::Up => {
+                sar = sar.min(prev.1).min(prev2.1);
+                if candle.low <= sar {
+                    return (self.reversal(SarTrend::Down, extreme, candle.low), true);
+                }
+            }
+            SarTrend::Down => {
+                sar = sar.max(prev.0).max(prev2.0);
+                if candle.high >= sar {
+                    return (self.reversal(SarTrend::Up, extreme, candle.high), true);
+                }
+            }
+        }
+
+        let new_extreme = match trend {
+            SarTrend::Up => candle.high > extreme,
+            SarTrend::Down => candle.low < extreme,
+        };
+        let (extreme, acceleration) = if new_extreme {
+            let extreme = match trend {
+                SarTrend::Up => candle.high,
+                SarTrend::Down => candle.low,
+            };
+            (extreme, (acceleration + self.step).min(self.max_step))
+        } else {
+            (extreme, acceleration)
+        };
+
+        (
+            SarState {
+                trend,
+                sar,
+                extreme,
+                acceleration,
+            },
+            false,
+        )
+    }
+
+    fn reversal(&self, trend: SarTrend, old_extreme: f64, new_extreme: f64) -> SarState {
+        SarState {
+            trend,
+            sar: old_extreme,
+            extreme: new_extreme,
+            acceleration: self.step,
+        }
+    }
+
+    /// Current acceleration factor
+    pub fn acceleration(&self) -> Option<f64> {
+        self.state.map(|state| state.acceleration)
+    }
+
+    pub fn reset(&mut self) {
+        self.prev = None;
+        self.prev2 = None;
+        self.state = None;
+        self.current = None;
+    }
+}
+
+impl Default for ParabolicSAR {
+    /// Wilder's 0.02 step capped at 0.2
+    fn default() -> Self {
+        Self::new(0.02, 0.2)
+    }
+}
+
+impl Indicator<Candle, SarOutput> for ParabolicSAR {
+    fn update(&mut self, input: Candle) -> Option<SarOutput> {
+        ParabolicSAR::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        ParabolicSAR::reset(self)
+    }
+
+    fn current(&self) -> Option<SarOutput> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn candle(high: f64, low: f64) -> Candle {
+        Candle::new(low, high, low, high, 1.0, Timestamp::EPOCH)
+    }
+
+    #[test]
+    fn test_trailing_stop_and_reversal_by_hand() {
+        let mut sar = ParabolicSAR::default();
+        assert_eq!(sar.update(&candle(10.0, 9.0)), None);
+
+        let first = sar.update(&candle(11.0, 10.0)).unwrap();
+        assert_eq!((first.sar, first.trend), (9.0, SarTrend::Up));
+
+        // 9 + 0.02 * (11 - 9) = 9.04, capped by the 9 low two bars back
+        assert_eq!(sar.update(&candle(12.0, 11.0)).unwrap().sar, 9.0);
+        // 9 + 0.04 * (12 - 9)
+        let out = sar.update(&candle(13.0, 12.0)).unwrap();
+        assert!((out.sar - 9.12).abs() < 1e-12);
+        assert!((sar.acceleration().unwrap() - 0.06).abs() < 1e-12);
+
+        // The drop through ~9.35 flips the trend; SAR jumps to the 13 extreme
+        let flip = sar.update(&candle(12.0, 8.0)).unwrap();
+        assert_eq!(
+            flip,
+            SarOutput {
+                sar: 13.0,
+                trend: SarTrend::Down,
+                reversed: true
+            }
+        );
+        assert_eq!(sar.acceleration(), Some(0.02));
+    }
+
+    #[test]
+    fn test_acceleration_is_capped_in_a_long_trend() {
+        let mut sar = ParabolicSAR::new(0.05, 0.15);
+        for i in 0..30 {
+            let low = 100.0 + i as f64;
+            if let Some(out) = sar.update(&candle(low + 1.0, low)) {
+                assert_eq!(out.trend, SarTrend::Up);
+                assert!(out.sar <= low);
+                assert!(!out.reversed);
+            }
+        }
+        assert_eq!(sar.acceleration(), Some(0.15));
+    }
+
+    #[test]
+    fn test_downtrend_start_and_validation() {
+        let mut sar = ParabolicSAR::default();
+        sar.update(&candle(10.0, 9.0));
+        let out = sar.update(&candle(9.5, 8.0)).unwrap();
+        assert_eq!((out.sar, out.trend), (10.0, SarTrend::Down));
+
+        sar.reset();
+        assert!(!Indicator::is_ready(&sar));
+        assert!(ParabolicSAR::try_new(0.3, 0.2).is_err());
+        assert!(ParabolicSAR::try_new(0.0, 0.2).is_err());
+    }
+}
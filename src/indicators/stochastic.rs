@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator, SMA};
+use crate::candles::Candle;
+use crate::error::Result;
+use crate::numeric::{widen, Real};
+
+/// Stochastic oscillator returning `(%K, %D)`
+///
+/// Raw %K is where the close sits within the highest high and lowest low of the last
+/// `k_period` candles (50 for a flat range). %K is raw %K smoothed by an SMA of
+/// `k_smoothing` (1 for the fast stochastic, 3 for the usual slow one) and %D is an
+/// SMA of %K over `d_period`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stochastic {
+    k_period: usize,
+    highs: VecDeque<Real>,
+    lows: VecDeque<Real>,
+    k_smoother: SMA,
+    d_smoother: SMA,
+    current: Option<(f64, f64)>,
+}
+
+impl Stochastic {
+    /// Validating constructor
+    pub fn try_new(k_period: usize, k_smoothing: usize, d_period: usize) -> Result<Self> {
+        check_period("k_period", k_period)?;
+        check_period("k_smoothing", k_smoothing)?;
+        check_period("d_period", d_period)?;
+        Ok(Self {
+            k_period,
+            highs: VecDeque::with_capacity(k_period),
+            lows: VecDeque::with_capacity(k_period),
+            k_smoother: SMA::new(k_smoothing),
+            d_smoother: SMA::new(d_period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(k_period: usize, k_smoothing: usize, d_period: usize) -> Self {
+        Self::try_new(k_period, k_smoothing, d_period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<(f64, f64)> {
+        self.highs.push_back(candle.high as Real);
+        self.lows.push_back(candle.low as Real);
+        if self.highs.len() > self.k_period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+        if self.highs.len() < self.k_period {
+            return None;
+        }
+
+        let highest = self
+            .highs
+            .iter()
+            .map(|&h| widen(h))
+            .fold(f64::MIN, f64::max);
+        let lowest = self.lows.iter().map(|&l| widen(l)).fold(f64::MAX, f64::min);
+        let raw_k = if highest > lowest {
+            (candle.close - lowest) / (highest - lowest) * 100.0
+        } else {
+            50.0
+        };
+
+        let k = self.k_smoother.update(raw_k)?;
+        let d = self.d_smoother.update(k)?;
+        self.current = Some((k, d));
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.highs.clear();
+        self.lows.clear();
+        self.k_smoother.reset();
+        self.d_smoother.reset();
+        self.current = None;
+    }
+}
+
+impl Indicator<Candle, (f64, f64)> for Stochastic {
+    fn update(&mut self, input: Candle) -> Option<(f64, f64)> {
+        Stochastic::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        Stochastic::reset(self)
+    }
+
+    fn current(&self) -> Option<(f64, f64)> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_fast_stochastic() {
+        let mut stoch = Stochastic::new(3, 1, 2);
+        assert_eq!(stoch.update(&candle(10.0, 8.0, 9.0)), None);
+        assert_eq!(stoch.update(&candle(12.0, 9.0, 11.0)), None);
+
+        // Range 8..12: close 11 -> 75, no %D yet
+        assert_eq!(stoch.update(&candle(11.0, 10.0, 11.0)), None);
+        // Range 9..12: close 9 -> 0; %D = (75 + 0) / 2
+        assert_eq!(stoch.update(&candle(10.0, 9.5, 9.0)), Some((0.0, 37.5)));
+    }
+
+    #[test]
+    fn test_slow_k_smoothing() {
+        let mut stoch = Stochastic::new(2, 2, 1);
+        stoch.update(&candle(10.0, 0.0, 5.0));
+        // Range 0..10: raw 100, not enough for the %K smoothing
+        assert_eq!(stoch.update(&candle(10.0, 5.0, 10.0)), None);
+        // Range 5..10: raw 0; slow %K = 50
+        assert_eq!(stoch.update(&candle(8.0, 6.0, 5.0)), Some((50.0, 50.0)));
+    }
+
+    #[test]
+    fn test_flat_range_and_reset() {
+        let mut stoch = Stochastic::new(2, 1, 1);
+        stoch.update(&candle(5.0, 5.0, 5.0));
+        assert_eq!(stoch.update(&candle(5.0, 5.0, 5.0)), Some((50.0, 50.0)));
+        assert!(Indicator::is_ready(&stoch));
+
+        stoch.reset();
+        assert!(!Indicator::is_ready(&stoch));
+        assert!(Stochastic::try_new(14, 0, 3).is_err());
+    }
+}
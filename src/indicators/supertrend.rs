@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator, IndicatorOutput, SarTrend, ATR};
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+
+/// One SuperTrend reading
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SuperTrendOutput {
+    /// Active band: the lower band in an uptrend, the upper band in a downtrend
+    pub value: f64,
+    pub trend: SarTrend,
+    /// The trend flipped on this bar
+    pub reversed: bool,
+}
+
+/// Columns: band value, then the trend as `1.0` (up) or `-1.0` (down)
+impl IndicatorOutput for SuperTrendOutput {
+    const WIDTH: usize = 2;
+
+    fn write(self, columns: &mut [Vec<Option<f64>>]) {
+        columns[0].push(Some(self.value));
+        columns[1].push(Some(match self.trend {
+            SarTrend::Up => 1.0,
+            SarTrend::Down => -1.0,
+        }));
+    }
+}
+
+/// SuperTrend: ATR bands around the bar midpoint that only ratchet with the trend
+///
+/// The basic bands are `(high + low) / 2 ± multiplier * ATR`. The lower band may only
+/// rise and the upper band only fall while the previous close stays on their side; a
+/// close through the active band flips the trend. The first bar after the ATR warm-up
+/// starts an uptrend unless it closes below the lower band.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuperTrend {
+    atr: ATR,
+    multiplier: f64,
+    /// Final (upper, lower) bands and close of the previous bar
+    prev: Option<(f64, f64, f64)>,
+    current: Option<SuperTrendOutput>,
+}
+
+impl SuperTrend {
+    /// Validating constructor
+    pub fn try_new(atr_period: usize, multiplier: f64) -> Result<Self> {
+        check_period("atr_period", atr_period)?;
+        if !(multiplier.is_finite() && multiplier > 0.0) {
+            return Err(MarketDataError::invalid_parameter(
+                "multiplier must be finite and positive",
+            ));
+        }
+        Ok(Self {
+            atr: ATR::new(atr_period),
+            multiplier,
+            prev: None,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(atr_period: usize, multiplier: f64) -> Self {
+        Self::try_new(atr_period, multiplier).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<SuperTrendOutput> {
+        let atr = self.atr.update(candle)?;
+        let mid = (candle.high + candle.low) / 2.0;
+        let mut upper = mid + self.multiplier * atr;
+        let mut lower = mid - self.multiplier * atr;
+
+        let (trend, reversed) = match (self.prev, self.current) {
+            (Some((prev_upper, prev_lower, prev_close)), Some(prev)) => {
+                if upper > prev_upper && prev_close <= prev_upper {
+                    upper = prev_upper;
+                }
+                if lower < prev_lower && prev_close >= prev_lower {
+                    lower = prev_lower;
+                }
+                let trend = match prev.trend {
+                    SarTrend::Up if candle.close < lower => SarTrend::Down,
+                    SarTrend::Down if candle.close > upper => SarTrend::Up,
+                    trend => trend,
+                };
+                (trend, trend != prev.trend)
+            }
+            _ if candle.close < lower => (SarTrend::Down, false),
+            _ => (SarTrend::Up, false),
+        };
+
+        self.prev = Some((upper, lower, candle.close));
+        self.current = Some(SuperTrendOutput {
+            value: match trend {
+                SarTrend::Up => lower,
+                SarTrend::Down => upper,
+            },
+            trend,
+            reversed,
+        });
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.atr.reset();
+        self.prev = None;
+        self.current = None;
+    }
+}
+
+impl Indicator<Candle, SuperTrendOutput> for SuperTrend {
+    fn update(&mut self, input: Candle) -> Option<SuperTrendOutput> {
+        SuperTrend::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        SuperTrend::reset(self)
+    }
+
+    fn current(&self) -> Option<SuperTrendOutput> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::candle;
+
+    #[test]
+    fn test_lower_band_ratchets_up_then_flips() {
+        let mut supertrend = SuperTrend::new(1, 1.0);
+
+        // ATR(1) is the true range: mid 10 ± 2
+        let first = supertrend.update(&candle(11.0, 9.0, 10.5)).unwrap();
+        assert_eq!((first.value, first.trend), (8.0, SarTrend::Up));
+
+        // Mid 11 ± 2: the lower band rises to 9
+        let rising = supertrend.update(&candle(12.0, 10.0, 11.5)).unwrap();
+        assert_eq!(rising.value, 9.0);
+
+        // Mid 10.5, TR 3 gives a basic lower band of 7.5; the band holds at 9
+        let pullback = supertrend.update(&candle(12.0, 9.0, 10.0)).unwrap();
+        assert_eq!((pullback.value, pullback.trend), (9.0, SarTrend::Up));
+
+        // A close under 9 flips to the upper band: mid 8.5 + TR 3
+        let flip = supertrend.update(&candle(10.0, 7.0, 7.5)).unwrap();
+        assert_eq!(
+            flip,
+            SuperTrendOutput {
+                value: 11.5,
+                trend: SarTrend::Down,
+                reversed: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_warm_up_reset_and_validation() {
+        let mut supertrend = SuperTrend::new(3, 3.0);
+        assert_eq!(supertrend.update(&candle(11.0, 9.0, 10.0)), None);
+        assert_eq!(supertrend.update(&candle(11.0, 9.0, 10.0)), None);
+        assert!(supertrend.update(&candle(11.0, 9.0, 10.0)).is_some());
+
+        supertrend.reset();
+        assert!(!Indicator::is_ready(&supertrend));
+        assert!(SuperTrend::try_new(10, -1.0).is_err());
+        assert!(SuperTrend::try_new(0, 3.0).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::error::{MarketDataError, Result};
+use crate::numeric::{widen, Real};
+
+/// Variance of the last `period` values
+///
+/// Mean and sum of squared deviations are updated in O(1) per value with Welford's
+/// recurrence, extended to drop the value leaving the window. Population variance
+/// (dividing by `period`, as [`BollingerBands`](super::BollingerBands) does) unless
+/// [`with_sample`](Self::with_sample) selects the unbiased `period - 1` divisor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingVariance {
+    period: usize,
+    sample: bool,
+    values: VecDeque<Real>,
+    mean: f64,
+    /// Sum of squared deviations from `mean`
+    m2: f64,
+}
+
+impl RollingVariance {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            sample: false,
+            values: VecDeque::with_capacity(period),
+            mean: 0.0,
+            m2: 0.0,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Divide by `period - 1` instead of `period`; a period of 1 then never produces
+    /// a value
+    pub fn with_sample(mut self, sample: bool) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let stored = value as Real;
+        let value = widen(stored);
+
+        if self.values.len() == self.period {
+            if let Some(old) = self.values.pop_front() {
+                let old = widen(old);
+                // Replace `old` by `value` without changing the count
+                let n = self.period as f64;
+                let old_mean = self.mean;
+                self.mean += (value - old) / n;
+                self.m2 += (value - old) * (value - self.mean + old - old_mean);
+            }
+        } else {
+            let n = (self.values.len() + 1) as f64;
+            let delta = value - self.mean;
+            self.mean += delta / n;
+            self.m2 += delta * (value - self.mean);
+        }
+        self.values.push_back(stored);
+
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        if self.values.len() < self.period {
+            return None;
+        }
+        let divisor = if self.sample {
+            self.period.checked_sub(1).filter(|&d| d > 0)?
+        } else {
+            self.period
+        };
+        // Rounding can leave a constant window marginally negative
+        Some(self.m2.max(0.0) / divisor as f64)
+    }
+
+    /// Mean of the current window
+    pub fn mean(&self) -> Option<f64> {
+        (self.values.len() == self.period).then_some(self.mean)
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<f64, f64> for RollingVariance {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        RollingVariance::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        RollingVariance::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        RollingVariance::current(self)
+    }
+}
+
+/// Standard deviation of the last `period` values, the square root of
+/// [`RollingVariance`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingStdDev {
+    variance: RollingVariance,
+}
+
+impl RollingStdDev {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            variance: RollingVariance::new(period),
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Use the sample (`period - 1`) variance
+    pub fn with_sample(mut self, sample: bool) -> Self {
+        self.variance = self.variance.with_sample(sample);
+        self
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.variance.update(value).map(f64::sqrt)
+    }
+
+    /// Mean of the current window
+    pub fn mean(&self) -> Option<f64> {
+        self.variance.mean()
+    }
+
+    pub fn reset(&mut self) {
+        self.variance.reset();
+    }
+
+    pub fn period(&self) -> usize {
+        self.variance.period()
+    }
+}
+
+impl Indicator<f64, f64> for RollingStdDev {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        RollingStdDev::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        RollingStdDev::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.variance.current().map(f64::sqrt)
+    }
+}
+
+/// Annualized volatility of log returns over a rolling window of prices
+///
+/// The sample standard deviation of the last `window` log returns, scaled by
+/// `sqrt(periods_per_year)`: 252 for daily bars, `252 * 390` for US equity minutes,
+/// `365 * 24` for hourly crypto bars. Non-positive prices are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealizedVolatility {
+    returns: RollingStdDev,
+    scale: f64,
+    prev_price: Option<f64>,
+    current: Option<f64>,
+}
+
+impl RealizedVolatility {
+    /// Validating constructor; the window needs two returns for a sample deviation
+    pub fn try_new(window: usize, periods_per_year: f64) -> Result<Self> {
+        if window < 2 {
+            return Err(MarketDataError::invalid_parameter(
+                "window must be at least 2",
+            ));
+        }
+        if !(periods_per_year.is_finite() && periods_per_year > 0.0) {
+            return Err(MarketDataError::invalid_parameter(
+                "periods_per_year must be finite and positive",
+            ));
+        }
+        Ok(Self {
+            returns: RollingStdDev::new(window).with_sample(true),
+            scale: periods_per_year.sqrt(),
+            prev_price: None,
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(window: usize, periods_per_year: f64) -> Self {
+        Self::try_new(window, periods_per_year).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        if !(price.is_finite() && price > 0.0) {
+            return self.current;
+        }
+        let prev = self.prev_price.replace(price)?;
+        let std = self.returns.update((price / prev).ln())?;
+        self.current = Some(std * self.scale);
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.returns.reset();
+        self.prev_price = None;
+        self.current = None;
+    }
+}
+
+impl Indicator<f64, f64> for RealizedVolatility {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        RealizedVolatility::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        RealizedVolatility::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_variance(values: &[f64], sample: bool) -> f64 {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let ss: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+        ss / if sample { n - 1.0 } else { n }
+    }
+
+    #[test]
+    fn test_rolling_variance_matches_two_pass() {
+        let values: Vec<f64> = (0..200)
+            .map(|i| 50_000.0 + ((i * 37) % 23) as f64 * 1.5)
+            .collect();
+        let mut population = RollingVariance::new(10);
+        let mut sample = RollingVariance::new(10).with_sample(true);
+
+        for (t, &v) in values.iter().enumerate() {
+            let (p, s) = (population.update(v), sample.update(v));
+            if t < 9 {
+                assert_eq!((p, s), (None, None));
+                continue;
+            }
+            let window = &values[t - 9..=t];
+            assert!((p.unwrap() - naive_variance(window, false)).abs() < 1e-6);
+            assert!((s.unwrap() - naive_variance(window, true)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_std_dev_of_constant_window_is_zero() {
+        let mut std = RollingStdDev::new(3);
+        for v in [4.0, 2.0, 9.0, 7.0, 7.0, 7.0] {
+            std.update(v);
+        }
+        assert_eq!(Indicator::current(&std), Some(0.0));
+        assert_eq!(std.mean(), Some(7.0));
+
+        let mut single = RollingStdDev::new(1).with_sample(true);
+        assert_eq!(single.update(1.0), None);
+    }
+
+    #[test]
+    fn test_realized_volatility_annualizes_log_returns() {
+        let mut vol = RealizedVolatility::new(2, 252.0);
+        assert_eq!(vol.update(100.0), None);
+        assert_eq!(vol.update(101.0), None);
+        let r1 = (101.0f64 / 100.0).ln();
+        let r2 = (99.0f64 / 101.0).ln();
+        let expected = naive_variance(&[r1, r2], true).sqrt() * 252f64.sqrt();
+        assert!((vol.update(99.0).unwrap() - expected).abs() < 1e-6);
+        // Bad ticks are ignored
+        assert!((vol.update(-1.0).unwrap() - expected).abs() < 1e-6);
+
+        vol.reset();
+        assert!(!Indicator::is_ready(&vol));
+        assert!(RealizedVolatility::try_new(1, 252.0).is_err());
+        assert!(RealizedVolatility::try_new(20, 0.0).is_err());
+    }
+}
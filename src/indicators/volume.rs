@@ -0,0 +1,409 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator};
+use crate::candles::Candle;
+use crate::error::Result;
+use crate::numeric::{widen, KahanSum, Real};
+
+/// Where the close sits in the bar's range, from -1 (at the low) to 1 (at the high);
+/// 0 for a bar without range
+fn money_flow_multiplier(high: f64, low: f64, close: f64) -> f64 {
+    let range = high - low;
+    if range > 0.0 {
+        ((close - low) - (high - close)) / range
+    } else {
+        0.0
+    }
+}
+
+/// On-Balance Volume: running total adding volume on up closes and subtracting it on
+/// down closes
+///
+/// The first close only sets the reference, so the line starts at 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OBV {
+    prev_close: Option<f64>,
+    total: KahanSum,
+    current: Option<f64>,
+}
+
+impl OBV {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, close: f64, volume: f64) -> Option<f64> {
+        if let Some(prev) = self.prev_close.replace(close) {
+            if close > prev {
+                self.total += volume;
+            } else if close < prev {
+                self.total -= volume;
+            }
+        }
+        self.current = Some(self.total.value());
+        self.current
+    }
+
+    pub fn update_candle(&mut self, candle: &Candle) -> Option<f64> {
+        self.update(candle.close, candle.volume)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Indicator<Candle, f64> for OBV {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        self.update_candle(&input)
+    }
+
+    fn reset(&mut self) {
+        OBV::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// `(close, volume)` pairs
+impl Indicator<(f64, f64), f64> for OBV {
+    fn update(&mut self, (close, volume): (f64, f64)) -> Option<f64> {
+        OBV::update(self, close, volume)
+    }
+
+    fn reset(&mut self) {
+        OBV::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Accumulation/Distribution line: running total of volume weighted by where the close
+/// sits in the bar's range
+///
+/// The money flow multiplier `((close - low) - (high - close)) / (high - low)` goes from
+/// -1 (close at the low) to 1 (close at the high); bars without a range contribute
+/// nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccumulationDistribution {
+    total: KahanSum,
+    current: Option<f64>,
+}
+
+impl AccumulationDistribution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64, volume: f64) -> Option<f64> {
+        self.total += money_flow_multiplier(high, low, close) * volume;
+        self.current = Some(self.total.value());
+        self.current
+    }
+
+    pub fn update_candle(&mut self, candle: &Candle) -> Option<f64> {
+        self.update(candle.high, candle.low, candle.close, candle.volume)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Indicator<Candle, f64> for AccumulationDistribution {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        self.update_candle(&input)
+    }
+
+    fn reset(&mut self) {
+        AccumulationDistribution::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Money Flow Index: RSI of volume-weighted typical price, from 0 to 100
+///
+/// Each candle's raw money flow `typical price * volume` counts as positive when the
+/// typical price rose from the previous candle and negative when it fell. The index is
+/// `100 - 100 / (1 + positive / negative)` over the last `period` flows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MFI {
+    period: usize,
+    prev_typical: Option<f64>,
+    /// (positive, negative) flow of each candle in the window
+    flows: VecDeque<(Real, Real)>,
+    positive: KahanSum,
+    negative: KahanSum,
+    current: Option<f64>,
+}
+
+impl MFI {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            prev_typical: None,
+            flows: VecDeque::with_capacity(period),
+            positive: KahanSum::new(),
+            negative: KahanSum::new(),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        let typical = candle.typical_price();
+        let prev = self.prev_typical.replace(typical)?;
+
+        let flow = typical * candle.volume;
+        let (positive, negative) = if typical > prev {
+            (flow as Real, 0.0)
+        } else if typical < prev {
+            (0.0, flow as Real)
+        } else {
+            (0.0, 0.0)
+        };
+        self.flows.push_back((positive, negative));
+        self.positive += widen(positive);
+        self.negative += widen(negative);
+
+        if self.flows.len() > self.period {
+            if let Some((old_positive, old_negative)) = self.flows.pop_front() {
+                self.positive -= widen(old_positive);
+                self.negative -= widen(old_negative);
+            }
+        }
+        if self.flows.len() < self.period {
+            return None;
+        }
+
+        let (positive, negative) = (self.positive.value(), self.negative.value());
+        self.current = Some(if negative <= 0.0 {
+            100.0
+        } else {
+            100.0 - 100.0 / (1.0 + positive / negative)
+        });
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.prev_typical = None;
+        self.flows.clear();
+        self.positive.reset();
+        self.negative.reset();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, f64> for MFI {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        MFI::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        MFI::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+/// Chaikin Money Flow: volume-weighted average of the money flow multiplier over the
+/// last `period` candles, from -1 to 1
+///
+/// The windowed counterpart of [`AccumulationDistribution`]; 0 while the window has
+/// no volume.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CMF {
+    period: usize,
+    /// (money flow volume, volume) of each candle in the window
+    window: VecDeque<(Real, Real)>,
+    flow_volume: KahanSum,
+    volume: KahanSum,
+    current: Option<f64>,
+}
+
+impl CMF {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            flow_volume: KahanSum::new(),
+            volume: KahanSum::new(),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        let multiplier = money_flow_multiplier(candle.high, candle.low, candle.close);
+        let entry = ((multiplier * candle.volume) as Real, candle.volume as Real);
+        self.window.push_back(entry);
+        self.flow_volume += widen(entry.0);
+        self.volume += widen(entry.1);
+
+        if self.window.len() > self.period {
+            if let Some((old_flow, old_volume)) = self.window.pop_front() {
+                self.flow_volume -= widen(old_flow);
+                self.volume -= widen(old_volume);
+            }
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let volume = self.volume.value();
+        self.current = Some(if volume > 0.0 {
+            self.flow_volume.value() / volume
+        } else {
+            0.0
+        });
+        self.current
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.flow_volume.reset();
+        self.volume.reset();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator<Candle, f64> for CMF {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        CMF::update(self, &input)
+    }
+
+    fn reset(&mut self) {
+        CMF::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    #[test]
+    fn test_obv_follows_close_direction() {
+        let mut obv = OBV::new();
+        assert_eq!(obv.update(10.0, 100.0), Some(0.0));
+        assert_eq!(obv.update(11.0, 50.0), Some(50.0));
+        assert_eq!(obv.update(11.0, 70.0), Some(50.0));
+        assert_eq!(obv.update(9.0, 30.0), Some(20.0));
+
+        obv.reset();
+        assert!(!Indicator::<Candle, f64>::is_ready(&obv));
+    }
+
+    #[test]
+    fn test_obv_candle_and_pair_inputs_agree() {
+        let candles = [
+            Candle::new(10.0, 11.0, 9.0, 10.0, 5.0, Timestamp::EPOCH),
+            Candle::new(10.0, 12.0, 9.5, 11.5, 8.0, Timestamp::from_millis(1)),
+            Candle::new(11.5, 12.0, 10.0, 10.5, 3.0, Timestamp::from_millis(2)),
+        ];
+        let pairs: Vec<(f64, f64)> = candles.iter().map(|c| (c.close, c.volume)).collect();
+
+        let from_candles = OBV::new().compute_series(&candles);
+        let from_pairs = OBV::new().compute_series(&pairs);
+        assert_eq!(from_candles, from_pairs);
+        assert_eq!(from_candles[2], Some(5.0));
+    }
+
+    #[test]
+    fn test_accumulation_distribution() {
+        let mut ad = AccumulationDistribution::new();
+        // Close at the high: full volume accumulates
+        assert_eq!(ad.update(12.0, 10.0, 12.0, 100.0), Some(100.0));
+        // Close at the midpoint: no flow
+        assert_eq!(ad.update(12.0, 10.0, 11.0, 100.0), Some(100.0));
+        // Close a quarter up the range: multiplier -0.5
+        assert_eq!(ad.update(14.0, 10.0, 11.0, 40.0), Some(80.0));
+        // Flat bar contributes nothing
+        assert_eq!(
+            ad.update_candle(&Candle::new(
+                11.0,
+                11.0,
+                11.0,
+                11.0,
+                500.0,
+                Timestamp::from_millis(3)
+            )),
+            Some(80.0)
+        );
+    }
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64) -> Candle {
+        Candle::new(close, high, low, close, volume, Timestamp::EPOCH)
+    }
+
+    #[test]
+    fn test_mfi_splits_flow_by_typical_price_direction() {
+        let mut mfi = MFI::new(2);
+        // Typical prices 10, 12, 11, 13
+        assert_eq!(mfi.update(&bar(10.0, 10.0, 10.0, 1.0)), None);
+        assert_eq!(mfi.update(&bar(12.0, 12.0, 12.0, 10.0)), None);
+        // Flows +120, -110
+        let value = mfi.update(&bar(11.0, 11.0, 11.0, 10.0)).unwrap();
+        assert!((value - (100.0 - 100.0 / (1.0 + 120.0 / 110.0))).abs() < 1e-9);
+        // Flows -110, +130
+        let value = mfi.update(&bar(13.0, 13.0, 13.0, 10.0)).unwrap();
+        assert!((value - (100.0 - 100.0 / (1.0 + 130.0 / 110.0))).abs() < 1e-9);
+
+        let mut rising = MFI::new(2);
+        for i in 0..4 {
+            rising.update(&bar(10.0 + i as f64, 9.0 + i as f64, 10.0 + i as f64, 5.0));
+        }
+        assert_eq!(Indicator::current(&rising), Some(100.0));
+        assert!(MFI::try_new(0).is_err());
+    }
+
+    #[test]
+    fn test_cmf_window() {
+        let mut cmf = CMF::new(2);
+        // Close at the high: multiplier 1
+        assert_eq!(cmf.update(&bar(12.0, 10.0, 12.0, 100.0)), None);
+        // Close a quarter up the range: multiplier -0.5
+        assert_eq!(cmf.update(&bar(14.0, 10.0, 11.0, 100.0)), Some(0.25));
+        // First bar leaves the window; close at the low
+        assert_eq!(cmf.update(&bar(14.0, 10.0, 10.0, 300.0)), Some(-350.0 / 400.0));
+
+        cmf.reset();
+        assert!(!Indicator::is_ready(&cmf));
+        cmf.update(&bar(10.0, 9.0, 9.5, 0.0));
+        assert_eq!(cmf.update(&bar(10.0, 9.0, 9.5, 0.0)), Some(0.0));
+    }
+}
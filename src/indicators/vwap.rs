@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+use super::Indicator;
+use crate::candles::Candle;
+use crate::numeric::KahanSum;
+use crate::trades::Trade;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Volume-weighted average price over a session
+///
+/// By default the session only ends on [`reset_session`](Self::reset_session);
+/// [`daily`](Self::daily) resets automatically when an update falls on a new calendar
+/// day (shifted by a UTC offset, e.g. for exchange-local sessions). Candles contribute
+/// their typical price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VWAP {
+    day_offset: Option<i64>,
+    session: Option<i64>,
+    price_volume: KahanSum,
+    volume: KahanSum,
+    current: Option<f64>,
+}
+
+impl VWAP {
+    /// VWAP accumulating until [`reset_session`](Self::reset_session)
+    pub fn new() -> Self {
+        Self {
+            day_offset: None,
+            session: None,
+            price_volume: KahanSum::new(),
+            volume: KahanSum::new(),
+            current: None,
+        }
+    }
+
+    /// VWAP resetting at midnight of the day shifted by `utc_offset_ms`
+    pub fn daily(utc_offset_ms: i64) -> Self {
+        Self {
+            day_offset: Some(utc_offset_ms),
+            ..Self::new()
+        }
+    }
+
+    /// Add `volume` traded at `price`; non-positive volumes are ignored
+    pub fn update(&mut self, price: f64, volume: f64, timestamp: i64) -> Option<f64> {
+        if let Some(offset) = self.day_offset {
+            let day = (timestamp + offset).div_euclid(DAY_MS);
+            if self.session.is_some_and(|session| day > session) {
+                self.reset_session();
+            }
+            self.session = Some(self.session.map_or(day, |session| session.max(day)));
+        }
+
+        if volume > 0.0 && price.is_finite() {
+            self.price_volume += price * volume;
+            self.volume += volume;
+            self.current = Some(self.price_volume.value() / self.volume.value());
+        }
+        self.current
+    }
+
+    pub fn update_trade(&mut self, trade: &Trade) -> Option<f64> {
+        self.update(trade.price, trade.quantity, trade.timestamp.as_millis())
+    }
+
+    pub fn update_candle(&mut self, candle: &Candle) -> Option<f64> {
+        self.update(
+            candle.typical_price(),
+            candle.volume,
+            candle.timestamp.as_millis(),
+        )
+    }
+
+    /// Volume accumulated in the current session
+    pub fn volume(&self) -> f64 {
+        self.volume.value()
+    }
+
+    /// Start a new session
+    pub fn reset_session(&mut self) {
+        self.price_volume.reset();
+        self.volume.reset();
+        self.current = None;
+    }
+
+    pub fn reset(&mut self) {
+        self.reset_session();
+        self.session = None;
+    }
+}
+
+impl Default for VWAP {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Indicator<Trade, f64> for VWAP {
+    fn update(&mut self, input: Trade) -> Option<f64> {
+        self.update_trade(&input)
+    }
+
+    fn reset(&mut self) {
+        VWAP::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+impl Indicator<Candle, f64> for VWAP {
+    fn update(&mut self, input: Candle) -> Option<f64> {
+        self.update_candle(&input)
+    }
+
+    fn reset(&mut self) {
+        VWAP::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+    use crate::trades::Side;
+
+    #[test]
+    fn test_vwap_over_trades() {
+        let mut vwap = VWAP::new();
+        assert_eq!(
+            vwap.update_trade(&Trade::new(100.0, 1.0, Side::Buy, Timestamp::EPOCH)),
+            Some(100.0)
+        );
+        assert_eq!(
+            vwap.update_trade(&Trade::new(
+                103.0,
+                2.0,
+                Side::Sell,
+                Timestamp::from_millis(1)
+            )),
+            Some(102.0)
+        );
+        // Zero volume does not move the average
+        assert_eq!(vwap.update(500.0, 0.0, 2), Some(102.0));
+        assert_eq!(vwap.volume(), 3.0);
+
+        vwap.reset_session();
+        assert_eq!(Indicator::<Trade, f64>::current(&vwap), None);
+    }
+
+    #[test]
+    fn test_candles_use_typical_price() {
+        let mut vwap = VWAP::new();
+        vwap.update_candle(&Candle::new(10.0, 12.0, 9.0, 12.0, 2.0, Timestamp::EPOCH));
+        let value = vwap.update_candle(&Candle::new(
+            12.0,
+            15.0,
+            12.0,
+            15.0,
+            1.0,
+            Timestamp::from_millis(60_000),
+        ));
+        // Typical prices 11 and 14
+        assert_eq!(value, Some(12.0));
+    }
+
+    #[test]
+    fn test_daily_session_reset_with_offset() {
+        let hour = 60 * 60 * 1000;
+        // Sessions roll at 22:00 UTC
+        let mut vwap = VWAP::daily(2 * hour);
+        vwap.update(100.0, 1.0, 20 * hour);
+        assert_eq!(vwap.update(110.0, 1.0, 21 * hour), Some(105.0));
+        assert_eq!(vwap.update(200.0, 1.0, 22 * hour), Some(200.0));
+
+        // Late print from the previous session is still counted, without a reset
+        assert_eq!(vwap.update(100.0, 1.0, 21 * hour), Some(150.0));
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_period, Indicator, RollingStdDev};
+use crate::error::Result;
+
+/// Number of rolling standard deviations the latest value sits from the rolling mean
+///
+/// Mean and (population) deviation cover the last `period` values including the
+/// latest, matching the spread z-scores in [`analytics`](crate::analytics). A flat
+/// window has no z-score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZScore {
+    std_dev: RollingStdDev,
+    current: Option<f64>,
+}
+
+impl ZScore {
+    /// Validating constructor
+    pub fn try_new(period: usize) -> Result<Self> {
+        check_period("period", period)?;
+        Ok(Self {
+            std_dev: RollingStdDev::new(period),
+            current: None,
+        })
+    }
+
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(period: usize) -> Self {
+        Self::try_new(period).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let std_dev = self.std_dev.update(value)?;
+        let mean = self.std_dev.mean()?;
+        self.current =
+            (std_dev > f64::EPSILON * mean.abs().max(1.0)).then(|| (value - mean) / std_dev);
+        self.current
+    }
+
+    /// Rolling mean of the current window
+    pub fn mean(&self) -> Option<f64> {
+        self.std_dev.mean()
+    }
+
+    pub fn reset(&mut self) {
+        self.std_dev.reset();
+        self.current = None;
+    }
+
+    pub fn period(&self) -> usize {
+        self.std_dev.period()
+    }
+}
+
+impl Indicator<f64, f64> for ZScore {
+    fn update(&mut self, input: f64) -> Option<f64> {
+        ZScore::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        ZScore::reset(self)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zscore_of_window() {
+        let mut zscore = ZScore::new(4);
+        for v in [2.0, 4.0, 4.0] {
+            assert_eq!(zscore.update(v), None);
+        }
+        // Window 2, 4, 4, 6: mean 4, population std sqrt(2)
+        let z = zscore.update(6.0).unwrap();
+        assert!((z - 2.0 / 2f64.sqrt()).abs() < 1e-9);
+        assert_eq!(zscore.mean(), Some(4.0));
+
+        // Window 4, 4, 6, 2: the same values, latest now below the mean
+        let z = zscore.update(2.0).unwrap();
+        assert!((z + 2.0 / 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flat_window_and_reset() {
+        let mut zscore = ZScore::new(3);
+        for _ in 0..5 {
+            assert_eq!(zscore.update(100.0), None);
+        }
+
+        zscore.update(103.0);
+        assert!(Indicator::is_ready(&zscore));
+        zscore.reset();
+        assert!(!Indicator::is_ready(&zscore));
+        assert!(ZScore::try_new(0).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+use crate::time::Timestamp;
+
+/// Encoding of the timestamp column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampFormat {
+    UnixMillis,
+    UnixSeconds,
+    /// RFC 3339 / ISO 8601 with offset, e.g. `2024-01-02T09:30:00Z`
+    Rfc3339,
+    /// `chrono` format string interpreted as UTC, e.g. `%Y-%m-%d %H:%M:%S`; formats
+    /// without a time of day (`%Y-%m-%d`) map to midnight
+    Custom(String),
+}
+
+impl TimestampFormat {
+    pub fn parse(&self, value: &str) -> Result<Timestamp> {
+        let invalid = || MarketDataError::Malformed(format!("invalid timestamp {value:?}"));
+        match self {
+            TimestampFormat::UnixMillis => value
+                .parse()
+                .map(Timestamp::from_millis)
+                .map_err(|_| invalid()),
+            TimestampFormat::UnixSeconds => {
+                let seconds: f64 = value.parse().map_err(|_| invalid())?;
+                Ok(Timestamp::from_millis((seconds * 1_000.0).round() as i64))
+            }
+            TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .map(|t| Timestamp::from(t.with_timezone(&Utc)))
+                .map_err(|_| invalid()),
+            TimestampFormat::Custom(format) => NaiveDateTime::parse_from_str(value, format)
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(value, format)
+                        .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight exists"))
+                })
+                .map(|t| Timestamp::from(t.and_utc()))
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+/// Header names of the OHLCV columns, matched case-insensitively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OhlcvColumns {
+    pub timestamp: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    /// `None` for files without volume, which then read as zero
+    pub volume: Option<String>,
+}
+
+impl Default for OhlcvColumns {
+    fn default() -> Self {
+        Self {
+            timestamp: "timestamp".to_string(),
+            open: "open".to_string(),
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            volume: Some("volume".to_string()),
+        }
+    }
+}
+
+/// Reader of OHLCV CSV files into candles sorted by time
+#[derive(Debug, Clone)]
+pub struct OhlcvReader {
+    columns: OhlcvColumns,
+    timestamp_format: TimestampFormat,
+    delimiter: u8,
+}
+
+impl Default for OhlcvReader {
+    fn default() -> Self {
+        Self {
+            columns: OhlcvColumns::default(),
+            timestamp_format: TimestampFormat::UnixMillis,
+            delimiter: b',',
+        }
+    }
+}
+
+impl OhlcvReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_columns(mut self, columns: OhlcvColumns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn read_file(&self, path: impl AsRef<Path>) -> Result<Vec<Candle>> {
+        self.read(File::open(path)?)
+    }
+
+    /// Parse every row; errors name the offending line
+    pub fn read(&self, reader: impl Read) -> Result<Vec<Candle>> {
+        let mut csv = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+
+        let headers = csv
+            .headers()
+            .map_err(|e| MarketDataError::Malformed(e.to_string()))?
+            .clone();
+        let index = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name))
+                .ok_or_else(|| MarketDataError::Malformed(format!("missing column {name:?}")))
+        };
+        let timestamp = index(&self.columns.timestamp)?;
+        let prices = [
+            index(&self.columns.open)?,
+            index(&self.columns.high)?,
+            index(&self.columns.low)?,
+            index(&self.columns.close)?,
+        ];
+        let volume = self.columns.volume.as_deref().map(index).transpose()?;
+
+        let mut candles = Vec::new();
+        for (row, record) in csv.records().enumerate() {
+            let line = row + 2;
+            let context =
+                |e: MarketDataError| MarketDataError::Malformed(format!("line {line}: {e}"));
+            let record =
+                record.map_err(|e| MarketDataError::Malformed(format!("line {line}: {e}")))?;
+
+            let field = |i: usize| record.get(i).unwrap_or_default();
+            let number = |i: usize| -> Result<f64> {
+                field(i).parse().map_err(|_| {
+                    MarketDataError::Malformed(format!("invalid number {:?}", field(i)))
+                })
+            };
+
+            let [open, high, low, close] = prices.map(number);
+            candles.push(Candle::new(
+                open.map_err(context)?,
+                high.map_err(context)?,
+                low.map_err(context)?,
+                close.map_err(context)?,
+                volume.map_or(Ok(0.0), number).map_err(context)?,
+                self.timestamp_format
+                    .parse(field(timestamp))
+                    .map_err(context)?,
+            ));
+        }
+
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::{IndicatorSet, OnClose, SMA};
+
+    #[test]
+    fn test_default_layout() {
+        let data = "timestamp,open,high,low,close,volume
+120000,101,103,100,102,5
+60000,100,102,99,101,10
+";
+        let candles = OhlcvReader::new().read(data.as_bytes()).unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(
+            candles[0],
+            Candle::new(
+                100.0,
+                102.0,
+                99.0,
+                101.0,
+                10.0,
+                Timestamp::from_millis(60_000)
+            )
+        );
+
+        let mut set = IndicatorSet::new();
+        set.add(&["sma_2"], OnClose(SMA::new(2))).unwrap();
+        assert_eq!(
+            set.run(&candles).column("sma_2").unwrap(),
+            &[None, Some(101.5)]
+        );
+    }
+
+    #[test]
+    fn test_custom_columns_and_formats() {
+        let data = "Date;Open;High;Low;Adj Close\n2024-01-02;1;2;0.5;1.5\n";
+        let reader = OhlcvReader::new()
+            .with_delimiter(b';')
+            .with_timestamp_format(TimestampFormat::Custom("%Y-%m-%d".to_string()))
+            .with_columns(OhlcvColumns {
+                timestamp: "date".to_string(),
+                close: "adj close".to_string(),
+                volume: None,
+                ..OhlcvColumns::default()
+            });
+        let candles = reader.read(data.as_bytes()).unwrap();
+        assert_eq!(candles[0].timestamp.as_millis(), 1_704_153_600_000);
+        assert_eq!((candles[0].close, candles[0].volume), (1.5, 0.0));
+
+        assert_eq!(
+            TimestampFormat::UnixSeconds.parse("1.5").unwrap(),
+            Timestamp::from_millis(1_500)
+        );
+        assert_eq!(
+            TimestampFormat::Rfc3339
+                .parse("2024-01-02T00:00:00+01:00")
+                .unwrap(),
+            Timestamp::from_millis(1_704_150_000_000)
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let missing = "time,open,high,low,close\n";
+        assert!(OhlcvReader::new()
+            .read(missing.as_bytes())
+            .unwrap_err()
+            .to_string()
+            .contains("missing column \"timestamp\""));
+
+        let bad = "timestamp,open,high,low,close,volume\n1,1,1,1,1,1\n2,1,x,1,1,1\n";
+        let err = OhlcvReader::new().read(bad.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Readers for historical data files
+
+pub mod csv;
+
+pub use self::csv::{OhlcvColumns, OhlcvReader, TimestampFormat};
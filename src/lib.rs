@@ -1,5 +1,54 @@
+pub mod error;
 pub mod orderbook;
 pub mod indicators;
+pub mod analytics;
+pub mod trades;
+pub mod filter;
+pub mod aggregation;
+pub mod candles;
+pub mod clock;
+pub mod engine;
+pub mod events;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod io;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "feeds")]
+pub mod feeds;
+pub mod numeric;
+pub mod pipeline;
+pub mod pool;
+pub mod protocols;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod replay;
+pub mod returns;
+pub mod server;
+pub mod storage;
+pub mod symbols;
+pub mod time;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod testing;
 
-pub use orderbook::{OrderBook, PriceLevel};
-pub use indicators::{SMA, EMA, RSI, BollingerBands, MACD};
+pub use error::MarketDataError;
+pub use orderbook::{
+    BboUpdate, BookChange, BookDelta, BookJournal, BookSide, BookSnapshot, ChecksumFormat, L3OrderBook, LevelUpdate, OrderBook, OrderBookManager, PriceLevel,
+    LiquidityConfig, LiquiditySnapshot, Quote, Sweep, TickOrderBook,
+};
+pub use indicators::{
+    Indicator, AccumulationDistribution, ADX, ATR, OBV, SMA, EMA, WMA, HMA, RSI, RsiSmoothing, BollingerBands, MACD,
+    KeltnerChannels, DonchianChannels, HurstExponent, Ichimoku, CMF, MFI, Momentum, ParabolicSAR, ROC, Stochastic,
+    SuperTrend, VWAP, WilliamsR, RealizedVolatility, RollingStdDev, RollingVariance, PairedReturns, ZScore,
+    PivotLevels, PivotMethod, PivotPoints, SwingDetector, SwingKind, SwingPoint,
+};
+pub use trades::{Side, Trade, TradeTape};
+pub use candles::{Candle, CompactCandle};
+pub use numeric::KahanSum;
+pub use returns::{ReturnKind, RollingReturns};
+pub use clock::{Clock, MockClock, SharedClock, SimulationClock, SystemClock};
+pub use time::{Monotonic, Timestamp};
+pub use filter::{FilterConfig, RejectReason, TickFilter};
+pub use symbols::{SymbolInfo, SymbolRegistry};
+pub use events::{MarketData, MarketEvent, ToMarketEvent};
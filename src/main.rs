@@ -1,8 +1,7 @@
-use rust_market_data_processor::{OrderBook, SMA, EMA, RSI, MACD};
+use rust_market_data_processor::{OrderBook, Timestamp, SMA, EMA, RSI, MACD};
 use tracing::{info, Level};
-use tracing_subscriber;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_max_level(Level::INFO)
@@ -11,28 +10,30 @@ fn main() {
     info!("Starting Rust Market Data Processor");
 
     // Demonstrate OrderBook
-    demo_orderbook();
+    demo_orderbook()?;
 
     // Demonstrate Technical Indicators
     demo_indicators();
 
     info!("Demo completed successfully");
+    Ok(())
 }
 
-fn demo_orderbook() {
+fn demo_orderbook() -> anyhow::Result<()> {
     info!("=== OrderBook Demo ===");
     
     let mut ob = OrderBook::new("BTCUSD".to_string());
+    let now = Timestamp::now();
     
     // Add some bids
-    ob.update_bid(50000.0, 1.5);
-    ob.update_bid(49999.0, 2.0);
-    ob.update_bid(49998.0, 1.0);
+    ob.update_bid(50000.0, 1.5, now)?;
+    ob.update_bid(49999.0, 2.0, now)?;
+    ob.update_bid(49998.0, 1.0, now)?;
     
     // Add some asks
-    ob.update_ask(50001.0, 1.0);
-    ob.update_ask(50002.0, 1.5);
-    ob.update_ask(50003.0, 2.0);
+    ob.update_ask(50001.0, 1.0, now)?;
+    ob.update_ask(50002.0, 1.5, now)?;
+    ob.update_ask(50003.0, 2.0, now)?;
     
     info!("Symbol: {}", ob.symbol);
     
@@ -68,6 +69,8 @@ fn demo_orderbook() {
     for level in ob.top_asks(3) {
         info!("  ${:.2} @ {:.4}", level.price, level.quantity);
     }
+
+    Ok(())
 }
 
 fn demo_indicators() {
@@ -0,0 +1,276 @@
+//! Processing health metrics, enabled with the `metrics` feature
+//!
+//! Values are recorded through the [`metrics`](::metrics) facade into whatever
+//! recorder the application installs; with the `prometheus` feature
+//! [`install_prometheus`] installs one serving a scrape endpoint. Rates are the
+//! counters' per-second increase, e.g. `rate(market_data_events_total[1m])`.
+//!
+//! Book metrics are recorded by [`OrderBookManager`](crate::orderbook::OrderBookManager)
+//! itself; events are counted by [`record_event`] (or [`EventMetrics`] in a stream
+//! pipeline) and indicators timed by wrapping them in [`TimedIndicator`].
+
+use ::metrics::{counter, describe_counter, describe_histogram, histogram, Unit};
+
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::indicators::Indicator;
+use crate::orderbook::BookChange;
+use crate::time::Monotonic;
+
+/// Events seen, labelled by `exchange` and `kind`
+pub const EVENTS: &str = "market_data_events_total";
+/// Delay from venue timestamp to local receipt, labelled by `exchange`
+pub const FEED_LATENCY: &str = "market_data_feed_latency_seconds";
+/// Book updates applied, labelled by `symbol`
+pub const BOOK_UPDATES: &str = "market_data_book_updates_total";
+/// Book updates rejected by validation, labelled by `symbol`
+pub const BOOK_REJECTED: &str = "market_data_book_rejected_total";
+/// Sequence gaps detected on book deltas, labelled by `symbol`
+pub const SEQUENCE_GAPS: &str = "market_data_sequence_gaps_total";
+/// Time spent per indicator update or batch computation, labelled by `indicator`
+pub const INDICATOR_DURATION: &str = "market_data_indicator_duration_seconds";
+
+/// Register units and help texts with the installed recorder
+pub fn describe() {
+    describe_counter!(EVENTS, Unit::Count, "Market data events processed");
+    describe_histogram!(
+        FEED_LATENCY,
+        Unit::Seconds,
+        "Delay from venue timestamp to local receipt"
+    );
+    describe_counter!(BOOK_UPDATES, Unit::Count, "Order book updates applied");
+    describe_counter!(
+        BOOK_REJECTED,
+        Unit::Count,
+        "Order book updates rejected by validation"
+    );
+    describe_counter!(
+        SEQUENCE_GAPS,
+        Unit::Count,
+        "Sequence gaps detected on book deltas"
+    );
+    describe_histogram!(
+        INDICATOR_DURATION,
+        Unit::Seconds,
+        "Time spent computing indicators"
+    );
+}
+
+/// Label value of an event payload
+fn kind(data: &MarketData) -> &'static str {
+    match data {
+        MarketData::BookSnapshot { .. } => "book_snapshot",
+        MarketData::BookDelta { .. } => "book_delta",
+        MarketData::Trade(_) => "trade",
+        MarketData::Ticker(_) => "ticker",
+        MarketData::Candle(_) => "candle",
+        MarketData::Heartbeat => "heartbeat",
+    }
+}
+
+/// Count an event and record its feed latency
+pub fn record_event(event: &MarketEvent) {
+    counter!(EVENTS, "exchange" => event.exchange.clone(), "kind" => kind(&event.data))
+        .increment(1);
+    histogram!(FEED_LATENCY, "exchange" => event.exchange.clone()).record(event.latency());
+}
+
+/// Count the outcome of applying an update to `symbol`'s book
+pub(crate) fn record_book_update(symbol: &str, result: &Result<BookChange>) {
+    let name = match result {
+        Ok(_) => BOOK_UPDATES,
+        Err(MarketDataError::SequenceGap { .. }) => SEQUENCE_GAPS,
+        Err(_) => BOOK_REJECTED,
+    };
+    counter!(name, "symbol" => symbol.to_string()).increment(1);
+}
+
+/// Run a computation, e.g. a `*_series` batch function, recording its duration
+pub fn time_indicator<T>(name: &'static str, compute: impl FnOnce() -> T) -> T {
+    let start = Monotonic::now();
+    let output = compute();
+    histogram!(INDICATOR_DURATION, "indicator" => name).record(start.elapsed());
+    output
+}
+
+/// Indicator wrapper recording the duration of every update
+#[derive(Debug, Clone)]
+pub struct TimedIndicator<I> {
+    inner: I,
+    name: &'static str,
+}
+
+impl<I> TimedIndicator<I> {
+    /// `name` labels the timings, e.g. `"rsi_14"`
+    pub fn new(inner: I, name: &'static str) -> Self {
+        Self { inner, name }
+    }
+
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I, In, Out> Indicator<In, Out> for TimedIndicator<I>
+where
+    I: Indicator<In, Out>,
+{
+    fn update(&mut self, input: In) -> Option<Out> {
+        time_indicator(self.name, || self.inner.update(input))
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn current(&self) -> Option<Out> {
+        self.inner.current()
+    }
+}
+
+/// Stream pipeline processor recording [`record_event`] for every event
+#[cfg(feature = "streaming")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EventMetrics;
+
+#[cfg(feature = "streaming")]
+impl crate::pipeline::stream::Processor for EventMetrics {
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        record_event(event);
+        Ok(())
+    }
+}
+
+/// Install a Prometheus recorder serving the metrics at `http://{addr}/metrics`
+///
+/// Must be called at most once per process, from within a tokio runtime or not.
+#[cfg(feature = "prometheus")]
+pub fn install_prometheus(addr: std::net::SocketAddr) -> Result<()> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .map_err(|e| MarketDataError::invalid_parameter(format!("prometheus exporter: {e}")))?;
+    describe();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{BookDelta, LevelUpdate, OrderBookManager};
+    use crate::time::Timestamp;
+    use crate::SMA;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    /// Counter values and histogram sample counts by metric name and labels
+    fn capture(run: impl FnOnce()) -> Vec<(String, Vec<String>, u64)> {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        ::metrics::with_local_recorder(&recorder, run);
+
+        let mut values: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let (_, key) = key.into_parts();
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect();
+                let value = match value {
+                    DebugValue::Counter(count) => count,
+                    DebugValue::Histogram(samples) => samples.len() as u64,
+                    DebugValue::Gauge(_) => 0,
+                };
+                (key.name().to_string(), labels, value)
+            })
+            .collect();
+        values.sort();
+        values
+    }
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn test_book_updates_and_gaps() {
+        let values = capture(|| {
+            let mut books = OrderBookManager::new();
+            let t = Timestamp::from_millis(1);
+            books
+                .apply_updates("BTC", &[LevelUpdate::bid(100.0, 1.0)], t)
+                .unwrap();
+            books
+                .apply_snapshot("BTC", &[], &[], 10, Timestamp::EPOCH)
+                .unwrap();
+            let delta = |sequence| BookDelta {
+                sequence,
+                updates: vec![LevelUpdate::bid(100.0, 1.0)],
+                timestamp: t,
+            };
+            books.apply_delta("BTC", &delta(11)).unwrap();
+            assert!(books.apply_delta("BTC", &delta(13)).is_err());
+            assert!(books
+                .apply_updates("BTC", &[LevelUpdate::bid(-1.0, 1.0)], t)
+                .is_err());
+        });
+
+        let symbol = labels(&["symbol=BTC"]);
+        assert_eq!(
+            values,
+            vec![
+                (BOOK_REJECTED.to_string(), symbol.clone(), 1),
+                (BOOK_UPDATES.to_string(), symbol.clone(), 3),
+                (SEQUENCE_GAPS.to_string(), symbol, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_and_indicator_timings() {
+        let values = capture(|| {
+            let event = MarketEvent::new(
+                "binance",
+                "BTC",
+                Timestamp::from_millis(1_000),
+                MarketData::Heartbeat,
+            )
+            .with_received(Timestamp::from_millis(1_250));
+            record_event(&event);
+            record_event(&event);
+
+            let mut sma = TimedIndicator::new(SMA::new(2), "sma_2");
+            assert_eq!(sma.compute_series(&[1.0, 2.0, 3.0])[2], Some(2.5));
+            assert_eq!(sma.current(), Some(2.5));
+            time_indicator("sma_batch", || ());
+        });
+
+        assert_eq!(
+            values,
+            vec![
+                (
+                    EVENTS.to_string(),
+                    labels(&["exchange=binance", "kind=heartbeat"]),
+                    2
+                ),
+                (FEED_LATENCY.to_string(), labels(&["exchange=binance"]), 2),
+                (
+                    INDICATOR_DURATION.to_string(),
+                    labels(&["indicator=sma_2"]),
+                    3
+                ),
+                (
+                    INDICATOR_DURATION.to_string(),
+                    labels(&["indicator=sma_batch"]),
+                    1
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::iter::Sum;
+use std::ops::{AddAssign, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+/// Element type of buffered indicator history and [`CompactCandle`](crate::candles::CompactCandle)
+///
+/// `f64` by default; the `f32-storage` feature halves the memory of long windows for
+/// users tracking many symbols. Computations and public APIs stay in `f64`.
+#[cfg(not(feature = "f32-storage"))]
+pub type Real = f64;
+
+/// Element type of buffered indicator history and [`CompactCandle`](crate::candles::CompactCandle)
+///
+/// `f32` because the `f32-storage` feature is enabled. Computations and public APIs
+/// stay in `f64`.
+#[cfg(feature = "f32-storage")]
+pub type Real = f32;
+
+/// Widen a stored [`Real`] back to `f64` for computation
+#[inline]
+#[allow(clippy::useless_conversion)]
+pub fn widen(value: Real) -> f64 {
+    f64::from(value)
+}
+
+/// Compensated (Kahan-Babuška/Neumaier) running sum
+///
+/// Tracks the low-order bits lost by each addition so that long-running totals and
+/// add/subtract rolling windows do not drift the way naive `f64` accumulation does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct KahanSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl KahanSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: f64) {
+        let t = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - t) + value;
+        } else {
+            self.compensation += (value - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    pub fn sub(&mut self, value: f64) {
+        self.add(-value);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+
+    pub fn reset(&mut self) {
+        self.sum = 0.0;
+        self.compensation = 0.0;
+    }
+}
+
+impl From<f64> for KahanSum {
+    fn from(value: f64) -> Self {
+        Self {
+            sum: value,
+            compensation: 0.0,
+        }
+    }
+}
+
+impl AddAssign<f64> for KahanSum {
+    fn add_assign(&mut self, value: f64) {
+        self.add(value);
+    }
+}
+
+impl SubAssign<f64> for KahanSum {
+    fn sub_assign(&mut self, value: f64) {
+        self.sub(value);
+    }
+}
+
+impl Sum<f64> for KahanSum {
+    fn sum<I: Iterator<Item = f64>>(iter: I) -> Self {
+        let mut total = KahanSum::new();
+        for value in iter {
+            total.add(value);
+        }
+        total
+    }
+}
+
+impl<'a> Sum<&'a f64> for KahanSum {
+    fn sum<I: Iterator<Item = &'a f64>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduced_error_over_many_updates() {
+        const N: usize = 1_000_000;
+        let mut naive = 0.0_f64;
+        let mut compensated = KahanSum::new();
+
+        for _ in 0..N {
+            naive += 0.1;
+            compensated += 0.1;
+        }
+
+        let exact = N as f64 * 0.1;
+        let naive_error = (naive - exact).abs();
+        let compensated_error = (compensated.value() - exact).abs();
+        assert!(naive_error > 1e-7);
+        assert!(compensated_error < 1e-9);
+    }
+
+    #[test]
+    fn test_rolling_add_sub_does_not_drift() {
+        // A rolling window of large and tiny values; the naive sum loses the tiny ones
+        let mut naive = 0.0_f64;
+        let mut compensated = KahanSum::new();
+        for i in 0..1_000_000 {
+            let big = if i % 2 == 0 { 1e16 } else { -1e16 };
+            for value in [big, 1.0] {
+                naive += value;
+                compensated += value;
+            }
+        }
+
+        assert_eq!(compensated.value(), 1_000_000.0);
+        assert_ne!(naive, 1_000_000.0);
+
+        compensated -= 1_000_000.0;
+        assert_eq!(compensated.value(), 0.0);
+    }
+
+    #[test]
+    fn test_sum_and_reset() {
+        let values = [1e100, 1.0, -1e100];
+        let mut total: KahanSum = values.iter().sum();
+        assert_eq!(total.value(), 1.0);
+        assert_eq!(values.iter().sum::<f64>(), 0.0);
+
+        total.reset();
+        assert_eq!(total.value(), 0.0);
+        assert_eq!(KahanSum::from(2.5).value(), 2.5);
+    }
+}
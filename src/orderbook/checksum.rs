@@ -0,0 +1,165 @@
+use super::{OrderBook, PriceLevel};
+use crate::error::{MarketDataError, Result};
+
+/// Canonical level formatting used to checksum the top of a book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumFormat {
+    /// Kraken: asks (best first) then bids (best first), each price and quantity printed
+    /// with the pair's fixed decimals, the `.` removed and leading zeros stripped
+    Kraken {
+        price_decimals: usize,
+        quantity_decimals: usize,
+    },
+    /// OKX: `bid_px:bid_sz:ask_px:ask_sz` interleaved level by level, numbers in their
+    /// shortest decimal form; the exchange publishes the CRC as a signed `i32`
+    Okx,
+}
+
+impl ChecksumFormat {
+    /// The string the exchange feeds to CRC32 for these levels
+    pub fn canonical_string(&self, bids: &[PriceLevel], asks: &[PriceLevel]) -> String {
+        match *self {
+            ChecksumFormat::Kraken {
+                price_decimals,
+                quantity_decimals,
+            } => {
+                let mut out = String::new();
+                for level in asks.iter().chain(bids) {
+                    push_kraken_number(&mut out, level.price, price_decimals);
+                    push_kraken_number(&mut out, level.quantity, quantity_decimals);
+                }
+                out
+            }
+            ChecksumFormat::Okx => {
+                let mut parts = Vec::with_capacity(2 * bids.len().max(asks.len()));
+                for i in 0..bids.len().max(asks.len()) {
+                    if let Some(bid) = bids.get(i) {
+                        parts.push(format!("{}:{}", bid.price, bid.quantity));
+                    }
+                    if let Some(ask) = asks.get(i) {
+                        parts.push(format!("{}:{}", ask.price, ask.quantity));
+                    }
+                }
+                parts.join(":")
+            }
+        }
+    }
+}
+
+fn push_kraken_number(out: &mut String, value: f64, decimals: usize) {
+    let formatted = format!("{value:.decimals$}").replace('.', "");
+    let trimmed = formatted.trim_start_matches('0');
+    out.push_str(if trimmed.is_empty() { "0" } else { trimmed });
+}
+
+impl OrderBook {
+    /// CRC32 of the top `depth` levels per side in the exchange's canonical format
+    ///
+    /// Kraken uses a depth of 10, OKX of 25. Compare with the OKX value via `as i32`.
+    pub fn checksum(&self, depth: usize, format: ChecksumFormat) -> u32 {
+        let canonical = format.canonical_string(&self.top_bids(depth), &self.top_asks(depth));
+        crc32(canonical.as_bytes())
+    }
+
+    /// Check the local book against the checksum the exchange sent with a delta
+    pub fn verify_checksum(
+        &self,
+        depth: usize,
+        format: ChecksumFormat,
+        expected: u32,
+    ) -> Result<()> {
+        let actual = self.checksum(depth, format);
+        if actual != expected {
+            return Err(MarketDataError::ChecksumMismatch { expected, actual });
+        }
+        Ok(())
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Standard (IEEE 802.3) CRC32, as used by both Kraken and OKX
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn level(price: f64, quantity: f64) -> PriceLevel {
+        PriceLevel { price, quantity }
+    }
+
+    #[test]
+    fn test_crc32_reference_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_okx_canonical_string_interleaves_sides() {
+        let bids = [level(3366.1, 7.0), level(3366.0, 6.0)];
+        let asks = [level(3366.8, 9.0), level(3368.0, 8.0), level(3372.0, 8.0)];
+        assert_eq!(
+            ChecksumFormat::Okx.canonical_string(&bids, &asks),
+            "3366.1:7:3366.8:9:3366:6:3368:8:3372:8"
+        );
+    }
+
+    #[test]
+    fn test_kraken_canonical_string_strips_point_and_leading_zeros() {
+        let format = ChecksumFormat::Kraken {
+            price_decimals: 5,
+            quantity_decimals: 8,
+        };
+        let bids = [level(0.05005, 0.00000500)];
+        let asks = [level(0.05010, 1.5)];
+        assert_eq!(
+            format.canonical_string(&bids, &asks),
+            "50101500000005005500"
+        );
+    }
+
+    #[test]
+    fn test_book_checksum_and_verification() {
+        let mut book = OrderBook::new("ETH-USDT".to_string());
+        book.update_bid(3366.1, 7.0, Timestamp::EPOCH).unwrap();
+        book.update_bid(3366.0, 6.0, Timestamp::EPOCH).unwrap();
+        book.update_ask(3366.8, 9.0, Timestamp::EPOCH).unwrap();
+        book.update_ask(3368.0, 8.0, Timestamp::EPOCH).unwrap();
+
+        let checksum = book.checksum(25, ChecksumFormat::Okx);
+        assert_eq!(checksum, crc32(b"3366.1:7:3366.8:9:3366:6:3368:8"));
+        assert!(book
+            .verify_checksum(25, ChecksumFormat::Okx, checksum)
+            .is_ok());
+
+        book.update_ask(3368.0, 0.0, Timestamp::EPOCH).unwrap();
+        assert!(matches!(
+            book.verify_checksum(25, ChecksumFormat::Okx, checksum),
+            Err(MarketDataError::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,339 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::{LevelUpdate, OrderBook, PriceLevel};
+use crate::error::{MarketDataError, Result};
+use crate::time::Timestamp;
+
+/// One change applied to a journaled [`OrderBook`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// Full book state: journaling start or an exchange snapshot
+    Snapshot {
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+        sequence: Option<u64>,
+        timestamp: Timestamp,
+    },
+    /// Level changes applied together; `sequence` is set for deltas
+    Updates {
+        updates: Vec<LevelUpdate>,
+        sequence: Option<u64>,
+        timestamp: Timestamp,
+    },
+}
+
+impl JournalEntry {
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            JournalEntry::Snapshot { timestamp, .. } | JournalEntry::Updates { timestamp, .. } => {
+                *timestamp
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Storage {
+    Memory(Vec<JournalEntry>),
+    /// JSON lines
+    File {
+        path: PathBuf,
+        writer: Mutex<BufWriter<File>>,
+    },
+}
+
+/// Append-only log of the changes applied to an order book
+///
+/// Install it with [`OrderBook::start_journal`]; the book then records every applied
+/// snapshot, batch, delta and single-level update, which
+/// [`OrderBook::reconstruct_at`] replays to rebuild past states. Clones of the
+/// book start without a journal.
+#[derive(Debug)]
+pub struct BookJournal {
+    storage: Storage,
+}
+
+impl BookJournal {
+    pub fn in_memory() -> Self {
+        Self {
+            storage: Storage::Memory(Vec::new()),
+        }
+    }
+
+    /// Journal written to `path` as JSON lines, replacing any existing file
+    pub fn to_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path)?;
+        Ok(Self {
+            storage: Storage::File {
+                path,
+                writer: Mutex::new(BufWriter::new(file)),
+            },
+        })
+    }
+
+    /// Entries of a journal file written by [`to_file`](Self::to_file)
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<JournalEntry>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+
+    pub(super) fn append(&mut self, entry: JournalEntry) -> Result<()> {
+        match &mut self.storage {
+            Storage::Memory(entries) => entries.push(entry),
+            Storage::File { writer, .. } => {
+                let writer = writer
+                    .get_mut()
+                    .map_err(|_| MarketDataError::invalid_parameter("journal writer poisoned"))?;
+                serde_json::to_writer(&mut *writer, &entry)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// All entries so far, oldest first; flushes and reads back a file journal
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        match &self.storage {
+            Storage::Memory(entries) => Ok(entries.clone()),
+            Storage::File { path, .. } => {
+                self.flush()?;
+                Self::read_file(path)
+            }
+        }
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        if let Storage::File { writer, .. } = &self.storage {
+            writer
+                .lock()
+                .map_err(|_| MarketDataError::invalid_parameter("journal writer poisoned"))?
+                .flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl OrderBook {
+    /// Start recording every applied change to `journal`, beginning with the current
+    /// state as a snapshot
+    pub fn start_journal(&mut self, mut journal: BookJournal) -> Result<()> {
+        journal.append(JournalEntry::Snapshot {
+            bids: self.top_bids(usize::MAX),
+            asks: self.top_asks(usize::MAX),
+            sequence: self.sequence,
+            timestamp: self.last_update,
+        })?;
+        self.journal = Some(journal);
+        Ok(())
+    }
+
+    pub fn journal(&self) -> Option<&BookJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Stop journaling, returning the journal
+    pub fn take_journal(&mut self) -> Option<BookJournal> {
+        self.journal.take()
+    }
+
+    /// Append to the journal, if journaling; `entry` is only built when needed
+    pub(super) fn record(&mut self, entry: impl FnOnce() -> JournalEntry) -> Result<()> {
+        match &mut self.journal {
+            Some(journal) => journal.append(entry()),
+            None => Ok(()),
+        }
+    }
+
+    /// Book as it was at `timestamp`, rebuilt from the journal
+    ///
+    /// Entries stamped after `timestamp` are left out. Fails if journaling is off or
+    /// started after `timestamp`.
+    pub fn reconstruct_at(&self, timestamp: Timestamp) -> Result<OrderBook> {
+        let journal = self
+            .journal
+            .as_ref()
+            .ok_or_else(|| MarketDataError::invalid_parameter("journaling is not enabled"))?;
+        let mut book = Self::from_journal(self.symbol.clone(), &journal.entries()?, timestamp)?;
+        book.strict = self.strict;
+        Ok(book)
+    }
+
+    /// Replay journal entries up to and including `timestamp` into a new book
+    pub fn from_journal(
+        symbol: String,
+        entries: &[JournalEntry],
+        timestamp: Timestamp,
+    ) -> Result<OrderBook> {
+        match entries.first() {
+            Some(first) if first.timestamp() <= timestamp => {}
+            _ => {
+                return Err(MarketDataError::invalid_parameter(format!(
+                    "journal has no entries at or before {timestamp}"
+                )))
+            }
+        }
+
+        let mut book = OrderBook::new(symbol);
+        for entry in entries.iter().take_while(|e| e.timestamp() <= timestamp) {
+            match entry {
+                JournalEntry::Snapshot {
+                    bids,
+                    asks,
+                    sequence,
+                    timestamp,
+                } => {
+                    book.apply_snapshot(bids, asks, sequence.unwrap_or_default(), *timestamp)?;
+                    book.sequence = *sequence;
+                }
+                JournalEntry::Updates {
+                    updates,
+                    sequence,
+                    timestamp,
+                } => {
+                    book.apply_updates(updates, *timestamp)?;
+                    if sequence.is_some() {
+                        book.sequence = *sequence;
+                    }
+                }
+            }
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::BookDelta;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn journaled_book(journal: BookJournal) -> OrderBook {
+        let mut book = OrderBook::new("BTCUSD".to_string());
+        book.start_journal(journal).unwrap();
+        book.apply_snapshot(
+            &[PriceLevel {
+                price: 100.0,
+                quantity: 1.0,
+            }],
+            &[PriceLevel {
+                price: 101.0,
+                quantity: 1.0,
+            }],
+            10,
+            at(0),
+        )
+        .unwrap();
+        book.apply_delta(&BookDelta {
+            sequence: 11,
+            updates: vec![LevelUpdate::bid(100.5, 2.0)],
+            timestamp: at(1_000),
+        })
+        .unwrap();
+        book.apply_updates(
+            &[LevelUpdate::ask(101.0, 0.0), LevelUpdate::ask(101.5, 3.0)],
+            at(2_000),
+        )
+        .unwrap();
+        book
+    }
+
+    #[test]
+    fn test_reconstruct_past_states() {
+        let book = journaled_book(BookJournal::in_memory());
+
+        let before_delta = book.reconstruct_at(at(999)).unwrap();
+        assert_eq!(before_delta.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(before_delta.sequence, Some(10));
+
+        let after_delta = book.reconstruct_at(at(1_500)).unwrap();
+        assert_eq!(after_delta.best_bid(), Some((100.5, 2.0)));
+        assert_eq!(after_delta.best_ask(), Some((101.0, 1.0)));
+        assert_eq!(after_delta.sequence, Some(11));
+        assert_eq!(after_delta.last_update, at(1_000));
+
+        let latest = book.reconstruct_at(at(5_000)).unwrap();
+        assert_eq!(latest.top_asks(10), book.top_asks(10));
+        assert_eq!(latest.top_bids(10), book.top_bids(10));
+    }
+
+    #[test]
+    fn test_single_level_updates_and_errors() {
+        let mut book = OrderBook::new("BTCUSD".to_string());
+        assert!(book.reconstruct_at(at(0)).is_err());
+
+        book.set_last_update(at(100));
+        book.start_journal(BookJournal::in_memory()).unwrap();
+        book.update_bid(99.0, 1.0, at(200)).unwrap();
+        assert_eq!(book.last_update, at(200));
+        // Rejected updates are not journaled
+        assert!(book.update_bid(-1.0, 1.0, at(300)).is_err());
+        assert_eq!(book.journal().unwrap().entries().unwrap().len(), 2);
+
+        assert!(book.reconstruct_at(at(50)).is_err());
+        assert_eq!(book.reconstruct_at(at(100)).unwrap().best_bid(), None);
+        assert_eq!(
+            book.reconstruct_at(at(200)).unwrap().best_bid(),
+            Some((99.0, 1.0))
+        );
+        assert!(book.take_journal().is_some());
+        assert!(book.reconstruct_at(at(100)).is_err());
+    }
+
+    #[test]
+    fn test_resync_snapshot_is_stamped_with_its_own_time() {
+        let mut book = journaled_book(BookJournal::in_memory());
+        let resync = [PriceLevel {
+            price: 90.0,
+            quantity: 5.0,
+        }];
+        book.apply_snapshot(&resync, &[], 20, at(3_000)).unwrap();
+        assert_eq!(book.last_update, at(3_000));
+
+        let before = book.reconstruct_at(at(2_500)).unwrap();
+        assert_eq!(before.best_bid(), Some((100.5, 2.0)));
+        assert_eq!(before.sequence, Some(11));
+        let after = book.reconstruct_at(at(3_000)).unwrap();
+        assert_eq!(after.best_bid(), Some((90.0, 5.0)));
+        assert_eq!(after.sequence, Some(20));
+    }
+
+    #[test]
+    fn test_clones_are_not_journaled() {
+        let book = journaled_book(BookJournal::in_memory());
+        let mut clone = book.clone();
+        assert!(clone.journal().is_none());
+        clone.update_bid(100.8, 1.0, at(4_000)).unwrap();
+        assert_eq!(book.journal().unwrap().entries().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_file_journal_round_trip() {
+        let path = std::env::temp_dir().join(format!("book-journal-{}.jsonl", std::process::id()));
+        let book = journaled_book(BookJournal::to_file(&path).unwrap());
+        let from_memory = journaled_book(BookJournal::in_memory());
+
+        let entries = book.journal().unwrap().entries().unwrap();
+        assert_eq!(entries, from_memory.journal().unwrap().entries().unwrap());
+        assert_eq!(BookJournal::read_file(&path).unwrap().len(), 4);
+
+        let rebuilt = OrderBook::from_journal("BTCUSD".to_string(), &entries, at(1_000)).unwrap();
+        assert_eq!(rebuilt.best_bid(), Some((100.5, 2.0)));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use super::{validate, BookSide, OrderBook, OrderedFloat};
+use crate::error::{MarketDataError, Result};
+use crate::time::Timestamp;
+
+/// Resting order in an [`L3OrderBook`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct L3Order {
+    pub id: u64,
+    pub side: BookSide,
+    pub price: f64,
+    pub quantity: f64,
+    /// Time the order gained its current queue priority
+    pub timestamp: Timestamp,
+}
+
+/// Result of [`L3OrderBook::execute`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Execution {
+    pub order: L3Order,
+    pub executed: f64,
+    /// Quantity left resting; zero means the order was filled and removed
+    pub remaining: f64,
+}
+
+/// Liquidity ahead of an order at its price level
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QueuePosition {
+    /// Zero-based rank in the level's FIFO queue
+    pub orders_ahead: usize,
+    pub quantity_ahead: f64,
+    /// Total quantity resting at the level, including the order itself
+    pub level_quantity: f64,
+}
+
+/// FIFO queue of order ids at one price
+#[derive(Debug, Clone, Default)]
+struct Level {
+    orders: VecDeque<u64>,
+    quantity: f64,
+}
+
+/// Order-by-order (level 3) book for full-depth feeds
+///
+/// Tracks every resting order by id with price-time priority, as needed for ITCH or
+/// Coinbase full-channel data and queue-position analytics. Reducing an order's size
+/// keeps its priority; raising it or changing its price sends it to the back of the
+/// queue, as on most matching engines.
+#[derive(Debug, Clone)]
+pub struct L3OrderBook {
+    pub symbol: String,
+    orders: HashMap<u64, L3Order>,
+    bids: BTreeMap<OrderedFloat, Level>,
+    asks: BTreeMap<OrderedFloat, Level>,
+    pub last_update: Timestamp,
+}
+
+impl L3OrderBook {
+    pub fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            orders: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_update: Timestamp::EPOCH,
+        }
+    }
+
+    fn levels(&mut self, side: BookSide) -> &mut BTreeMap<OrderedFloat, Level> {
+        match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        }
+    }
+
+    fn enqueue(&mut self, order: L3Order) {
+        let level = self
+            .levels(order.side)
+            .entry(OrderedFloat(order.price))
+            .or_default();
+        level.orders.push_back(order.id);
+        level.quantity += order.quantity;
+        self.orders.insert(order.id, order);
+    }
+
+    fn dequeue(&mut self, id: u64) -> Result<L3Order> {
+        let order = self
+            .orders
+            .remove(&id)
+            .ok_or(MarketDataError::UnknownOrder(id))?;
+        let levels = self.levels(order.side);
+        let key = OrderedFloat(order.price);
+        if let Some(level) = levels.get_mut(&key) {
+            level.orders.retain(|&other| other != id);
+            level.quantity -= order.quantity;
+            if level.orders.is_empty() {
+                levels.remove(&key);
+            }
+        }
+        Ok(order)
+    }
+
+    /// New order at the back of its price level
+    pub fn add(
+        &mut self,
+        id: u64,
+        side: BookSide,
+        price: f64,
+        quantity: f64,
+        timestamp: Timestamp,
+    ) -> Result<()> {
+        validate(price, quantity)?;
+        if quantity == 0.0 {
+            return Err(MarketDataError::InvalidQuantity(quantity));
+        }
+        if self.orders.contains_key(&id) {
+            return Err(MarketDataError::DuplicateOrder(id));
+        }
+
+        self.enqueue(L3Order {
+            id,
+            side,
+            price,
+            quantity,
+            timestamp,
+        });
+        self.last_update = timestamp;
+        Ok(())
+    }
+
+    /// Change an order's price and/or size; zero quantity deletes it
+    pub fn modify(
+        &mut self,
+        id: u64,
+        price: f64,
+        quantity: f64,
+        timestamp: Timestamp,
+    ) -> Result<()> {
+        validate(price, quantity)?;
+        let current = *self
+            .orders
+            .get(&id)
+            .ok_or(MarketDataError::UnknownOrder(id))?;
+
+        if quantity == 0.0 {
+            self.dequeue(id)?;
+        } else if price == current.price && quantity <= current.quantity {
+            // Size reduction in place keeps priority
+            let level = self
+                .levels(current.side)
+                .get_mut(&OrderedFloat(price))
+                .expect("order level exists");
+            level.quantity -= current.quantity - quantity;
+            self.orders.insert(
+                id,
+                L3Order {
+                    quantity,
+                    ..current
+                },
+            );
+        } else {
+            self.dequeue(id)?;
+            self.enqueue(L3Order {
+                price,
+                quantity,
+                timestamp,
+                ..current
+            });
+        }
+        self.last_update = timestamp;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: u64, timestamp: Timestamp) -> Result<L3Order> {
+        let order = self.dequeue(id)?;
+        self.last_update = timestamp;
+        Ok(order)
+    }
+
+    /// Fill up to `quantity` of a resting order, removing it once fully filled
+    pub fn execute(&mut self, id: u64, quantity: f64, timestamp: Timestamp) -> Result<Execution> {
+        if !(quantity.is_finite() && quantity > 0.0) {
+            return Err(MarketDataError::InvalidQuantity(quantity));
+        }
+        let order = *self
+            .orders
+            .get(&id)
+            .ok_or(MarketDataError::UnknownOrder(id))?;
+        let executed = quantity.min(order.quantity);
+        let remaining = order.quantity - executed;
+
+        if remaining <= 0.0 {
+            self.dequeue(id)?;
+        } else {
+            let level = self
+                .levels(order.side)
+                .get_mut(&OrderedFloat(order.price))
+                .expect("order level exists");
+            level.quantity -= executed;
+            self.orders.insert(
+                id,
+                L3Order {
+                    quantity: remaining,
+                    ..order
+                },
+            );
+        }
+        self.last_update = timestamp;
+
+        Ok(Execution {
+            order,
+            executed,
+            remaining,
+        })
+    }
+
+    pub fn order(&self, id: u64) -> Option<&L3Order> {
+        self.orders.get(&id)
+    }
+
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Best bid price and total quantity resting there
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(k, level)| (k.0, level.quantity))
+    }
+
+    /// Best ask price and total quantity resting there
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks
+            .iter()
+            .next()
+            .map(|(k, level)| (k.0, level.quantity))
+    }
+
+    /// Orders resting at `price`, in priority order
+    pub fn level_orders(&self, side: BookSide, price: f64) -> Vec<L3Order> {
+        let levels = match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        levels
+            .get(&OrderedFloat(price))
+            .map(|level| level.orders.iter().map(|id| self.orders[id]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn queue_position(&self, id: u64) -> Option<QueuePosition> {
+        let order = self.orders.get(&id)?;
+        let levels = match order.side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        let level = levels.get(&OrderedFloat(order.price))?;
+        let rank = level.orders.iter().position(|&other| other == id)?;
+
+        Some(QueuePosition {
+            orders_ahead: rank,
+            quantity_ahead: level
+                .orders
+                .iter()
+                .take(rank)
+                .map(|other| self.orders[other].quantity)
+                .sum(),
+            level_quantity: level.quantity,
+        })
+    }
+
+    /// Aggregate into a price-level book
+    pub fn to_l2(&self) -> OrderBook {
+        let mut book = OrderBook::new(self.symbol.clone());
+        book.bids = self
+            .bids
+            .iter()
+            .map(|(k, level)| (*k, level.quantity))
+            .collect();
+        book.asks = self
+            .asks
+            .iter()
+            .map(|(k, level)| (*k, level.quantity))
+            .collect();
+        book.last_update = self.last_update;
+        book
+    }
+
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(ms: i64) -> Timestamp {
+        Timestamp::from_millis(ms)
+    }
+
+    fn book() -> L3OrderBook {
+        let mut book = L3OrderBook::new("AAPL".to_string());
+        book.add(1, BookSide::Bid, 100.0, 5.0, ts(1)).unwrap();
+        book.add(2, BookSide::Bid, 100.0, 3.0, ts(2)).unwrap();
+        book.add(3, BookSide::Bid, 100.0, 2.0, ts(3)).unwrap();
+        book.add(4, BookSide::Ask, 101.0, 4.0, ts(4)).unwrap();
+        book
+    }
+
+    #[test]
+    fn test_add_aggregates_levels() {
+        let book = book();
+        assert_eq!(book.best_bid(), Some((100.0, 10.0)));
+        assert_eq!(book.best_ask(), Some((101.0, 4.0)));
+        assert_eq!(book.order_count(), 4);
+
+        let ids: Vec<u64> = book
+            .level_orders(BookSide::Bid, 100.0)
+            .iter()
+            .map(|o| o.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let l2 = book.to_l2();
+        assert_eq!(l2.best_bid(), Some((100.0, 10.0)));
+        assert_eq!(l2.last_update, ts(4));
+    }
+
+    #[test]
+    fn test_queue_priority_on_modify() {
+        let mut book = book();
+        assert_eq!(
+            book.queue_position(3),
+            Some(QueuePosition {
+                orders_ahead: 2,
+                quantity_ahead: 8.0,
+                level_quantity: 10.0
+            })
+        );
+
+        // Reduction keeps priority, increase loses it
+        book.modify(1, 100.0, 4.0, ts(5)).unwrap();
+        assert_eq!(book.queue_position(1).unwrap().orders_ahead, 0);
+        book.modify(2, 100.0, 6.0, ts(6)).unwrap();
+        assert_eq!(book.queue_position(2).unwrap().orders_ahead, 2);
+        assert_eq!(book.best_bid(), Some((100.0, 12.0)));
+
+        // Price change moves the order to the new level
+        book.modify(3, 100.5, 2.0, ts(7)).unwrap();
+        assert_eq!(book.best_bid(), Some((100.5, 2.0)));
+        assert_eq!(book.order(3).unwrap().timestamp, ts(7));
+    }
+
+    #[test]
+    fn test_execute_and_delete() {
+        let mut book = book();
+        let fill = book.execute(4, 1.5, ts(5)).unwrap();
+        assert_eq!((fill.executed, fill.remaining), (1.5, 2.5));
+        assert_eq!(book.best_ask(), Some((101.0, 2.5)));
+
+        let fill = book.execute(4, 10.0, ts(6)).unwrap();
+        assert_eq!((fill.executed, fill.remaining), (2.5, 0.0));
+        assert_eq!(book.best_ask(), None);
+
+        let deleted = book.delete(1, ts(7)).unwrap();
+        assert_eq!(deleted.quantity, 5.0);
+        assert_eq!(book.queue_position(2).unwrap().orders_ahead, 0);
+    }
+
+    #[test]
+    fn test_errors_leave_book_untouched() {
+        let mut book = book();
+        assert!(matches!(
+            book.add(1, BookSide::Ask, 102.0, 1.0, ts(9)),
+            Err(MarketDataError::DuplicateOrder(1))
+        ));
+        assert!(matches!(
+            book.delete(99, ts(9)),
+            Err(MarketDataError::UnknownOrder(99))
+        ));
+        assert!(book.execute(2, -1.0, ts(9)).is_err());
+        assert!(book.add(7, BookSide::Bid, f64::NAN, 1.0, ts(9)).is_err());
+        assert_eq!(book.order_count(), 4);
+        assert_eq!(book.last_update, ts(4));
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+use super::{OrderBook, OrderedFloat, Sweep};
+
+/// Parameters of [`OrderBook::liquidity`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityConfig {
+    /// Half-width of the band around the mid whose resting quantity counts as depth,
+    /// in basis points
+    pub band_bps: f64,
+    /// Order size whose fill cost discounts the score
+    pub fill_quantity: f64,
+}
+
+impl Default for LiquidityConfig {
+    fn default() -> Self {
+        Self {
+            band_bps: 10.0,
+            fill_quantity: 1.0,
+        }
+    }
+}
+
+/// Liquidity measures of one book state
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LiquiditySnapshot {
+    pub mid: f64,
+    pub spread_bps: f64,
+    /// Bid quantity within the band below the mid
+    pub bid_depth: f64,
+    /// Ask quantity within the band above the mid
+    pub ask_depth: f64,
+    /// Cost versus the mid of selling `fill_quantity` into the bids, in basis points;
+    /// `None` if the side is too thin
+    pub sell_cost_bps: Option<f64>,
+    /// Cost versus the mid of buying `fill_quantity` from the asks
+    pub buy_cost_bps: Option<f64>,
+    /// Band depth divided by `1 + average fill cost in bps`; 0 when either side
+    /// cannot fill the order
+    pub score: f64,
+}
+
+impl OrderBook {
+    /// Bid and ask quantity resting within `bps` basis points of the mid
+    pub fn depth_within_bps(&self, bps: f64) -> Option<(f64, f64)> {
+        let mid = self.mid_price()?;
+        let band = mid * bps / 10_000.0;
+        let bids = self
+            .bids
+            .range(OrderedFloat(mid - band)..)
+            .map(|(_, q)| q)
+            .sum();
+        let asks = self
+            .asks
+            .range(..=OrderedFloat(mid + band))
+            .map(|(_, q)| q)
+            .sum();
+        Some((bids, asks))
+    }
+
+    /// Average prices of selling and of buying `quantity` at market, `None` for a side
+    /// without enough visible quantity
+    pub fn price_to_fill(&self, quantity: f64) -> (Option<f64>, Option<f64>) {
+        let complete = |sweep: Option<Sweep>| {
+            sweep.filter(|s| s.is_complete()).map(|s| s.average_price)
+        };
+        (
+            complete(self.simulate_market_sell(quantity)),
+            complete(self.simulate_market_buy(quantity)),
+        )
+    }
+
+    /// Spread, band depth, fill costs and a combined score; `None` unless both sides
+    /// are quoted
+    pub fn liquidity(&self, config: &LiquidityConfig) -> Option<LiquiditySnapshot> {
+        let mid = self.mid_price()?;
+        let (bid_depth, ask_depth) = self.depth_within_bps(config.band_bps)?;
+        let cost = |price: Option<f64>| price.map(|p| (p - mid).abs() / mid * 10_000.0);
+        let (sell, buy) = self.price_to_fill(config.fill_quantity);
+        let (sell_cost_bps, buy_cost_bps) = (cost(sell), cost(buy));
+
+        let score = match (sell_cost_bps, buy_cost_bps) {
+            (Some(sell), Some(buy)) => (bid_depth + ask_depth) / (1.0 + (sell + buy) / 2.0),
+            _ => 0.0,
+        };
+        Some(LiquiditySnapshot {
+            mid,
+            spread_bps: self.spread()? / mid * 10_000.0,
+            bid_depth,
+            ask_depth,
+            sell_cost_bps,
+            buy_cost_bps,
+            score,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn book() -> OrderBook {
+        let mut book = OrderBook::new("BTCUSD".to_string());
+        for (i, quantity) in [1.0, 2.0, 4.0].into_iter().enumerate() {
+            book.update_bid(99.95 - 0.1 * i as f64, quantity, Timestamp::EPOCH)
+                .unwrap();
+            book.update_ask(100.05 + 0.1 * i as f64, quantity, Timestamp::EPOCH)
+                .unwrap();
+        }
+        book
+    }
+
+    #[test]
+    fn test_depth_within_band() {
+        let book = book();
+        // 10 bps of 100 is 0.1: only the touch on each side
+        assert_eq!(book.depth_within_bps(10.0), Some((1.0, 1.0)));
+        assert_eq!(book.depth_within_bps(20.0), Some((3.0, 3.0)));
+        assert_eq!(
+            OrderBook::new("ETHUSD".to_string()).depth_within_bps(10.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_price_to_fill() {
+        let book = book();
+        let (sell, buy) = book.price_to_fill(3.0);
+        assert!((buy.unwrap() - (100.05 + 2.0 * 100.15) / 3.0).abs() < 1e-9);
+        assert!((sell.unwrap() - (99.95 + 2.0 * 99.85) / 3.0).abs() < 1e-9);
+        assert_eq!(book.price_to_fill(8.0), (None, None));
+    }
+
+    #[test]
+    fn test_liquidity_score() {
+        let book = book();
+        let snapshot = book.liquidity(&LiquidityConfig::default()).unwrap();
+        assert!((snapshot.spread_bps - 10.0).abs() < 1e-9);
+        // Filling one unit costs the half spread, 5 bps, on either side
+        assert!((snapshot.buy_cost_bps.unwrap() - 5.0).abs() < 1e-9);
+        assert!((snapshot.score - 2.0 / 6.0).abs() < 1e-9);
+
+        let thin = book.liquidity(&LiquidityConfig {
+            band_bps: 10.0,
+            fill_quantity: 100.0,
+        });
+        assert_eq!(thin.unwrap().score, 0.0);
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::{BboUpdate, BookChange, BookDelta, BookSnapshot, LevelUpdate, OrderBook, PriceLevel};
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::time::Timestamp;
+
+/// Update counters of one managed book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookStats {
+    /// Batches, deltas and snapshots applied
+    pub updates: u64,
+    /// Updates that failed validation and left the book unchanged
+    pub rejected: u64,
+    /// Sequence gaps detected on deltas
+    pub gaps: u64,
+    /// Applied updates that changed the best bid or ask
+    pub top_changes: u64,
+}
+
+/// Callback invoked with the symbol and new top of book on every BBO change
+pub type BboCallback = Box<dyn FnMut(&str, &BboUpdate) + Send>;
+
+/// Order books of many symbols, created on first update
+///
+/// Routes updates by symbol and keeps per-symbol counters, replacing the
+/// `HashMap<String, OrderBook>` plumbing every multi-symbol consumer needs.
+#[derive(Default)]
+pub struct OrderBookManager {
+    books: HashMap<String, (OrderBook, BookStats)>,
+    strict: bool,
+    bbo_subscribers: Vec<BboCallback>,
+}
+
+impl std::fmt::Debug for OrderBookManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrderBookManager")
+            .field("books", &self.books)
+            .field("strict", &self.strict)
+            .field("bbo_subscribers", &self.bbo_subscribers.len())
+            .finish()
+    }
+}
+
+impl OrderBookManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create books in strict mode, see [`OrderBook::with_strict`]
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Register a callback invoked whenever any book's best bid or ask changes
+    ///
+    /// Strategies that only react to the top of book can subscribe here instead of
+    /// diffing books after every update.
+    pub fn subscribe_bbo<F>(&mut self, callback: F)
+    where
+        F: FnMut(&str, &BboUpdate) + Send + 'static,
+    {
+        self.bbo_subscribers.push(Box::new(callback));
+    }
+
+    /// Book of `symbol`, created empty if unknown
+    pub fn book_mut(&mut self, symbol: &str) -> Result<&mut OrderBook> {
+        self.entry(symbol).map(|(book, _)| book)
+    }
+
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol).map(|(book, _)| book)
+    }
+
+    pub fn stats(&self, symbol: &str) -> Option<BookStats> {
+        self.books.get(symbol).map(|&(_, stats)| stats)
+    }
+
+    /// Add or replace a book, returning the one previously held for its symbol
+    pub fn insert(&mut self, book: OrderBook) -> Option<OrderBook> {
+        self.books
+            .insert(book.symbol.clone(), (book, BookStats::default()))
+            .map(|(book, _)| book)
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> Option<OrderBook> {
+        self.books.remove(symbol).map(|(book, _)| book)
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.books.contains_key(symbol)
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.books.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &OrderBook)> {
+        self.books
+            .iter()
+            .map(|(symbol, (book, _))| (symbol.as_str(), book))
+    }
+
+    /// Apply a batch to `symbol`'s book, see [`OrderBook::apply_updates`]
+    pub fn apply_updates(
+        &mut self,
+        symbol: &str,
+        updates: &[LevelUpdate],
+        timestamp: Timestamp,
+    ) -> Result<BookChange> {
+        self.record(symbol, |book| book.apply_updates(updates, timestamp))
+    }
+
+    /// Apply a sequenced delta to `symbol`'s book, see [`OrderBook::apply_delta`]
+    pub fn apply_delta(&mut self, symbol: &str, delta: &BookDelta) -> Result<BookChange> {
+        self.record(symbol, |book| book.apply_delta(delta))
+    }
+
+    pub fn apply_snapshot(
+        &mut self,
+        symbol: &str,
+        bids: &[PriceLevel],
+        asks: &[PriceLevel],
+        sequence: u64,
+        timestamp: Timestamp,
+    ) -> Result<()> {
+        self.record(symbol, |book| {
+            book.apply_snapshot(bids, asks, sequence, timestamp)?;
+            Ok(BookChange {
+                top_changed: true,
+                bbo: Some(book.bbo()),
+                ..BookChange::default()
+            })
+        })
+        .map(|_| ())
+    }
+
+    /// Route a normalized event to its symbol's book; non-book events are ignored
+    /// without creating a book
+    pub fn apply_event(&mut self, event: &MarketEvent) -> Result<Option<BookChange>> {
+        if !matches!(
+            event.data,
+            MarketData::BookSnapshot { .. } | MarketData::BookDelta { .. }
+        ) {
+            return Ok(None);
+        }
+        self.record(&event.symbol, |book| {
+            Ok(event.apply_to(book)?.unwrap_or_default())
+        })
+        .map(Some)
+    }
+
+    /// Snapshots of the best `depth` levels of every book, sorted by symbol
+    pub fn snapshots(&self, depth: usize) -> Vec<BookSnapshot> {
+        let mut snapshots: Vec<BookSnapshot> = self
+            .books
+            .values()
+            .map(|(book, _)| book.snapshot_depth(depth))
+            .collect();
+        snapshots.sort_by(|a, b| a.symbol().cmp(b.symbol()));
+        snapshots
+    }
+
+    fn entry(&mut self, symbol: &str) -> Result<&mut (OrderBook, BookStats)> {
+        if !self.books.contains_key(symbol) {
+            let book = OrderBook::try_new(symbol.to_string())?.with_strict(self.strict);
+            self.books
+                .insert(symbol.to_string(), (book, BookStats::default()));
+        }
+        Ok(self.books.get_mut(symbol).expect("inserted above"))
+    }
+
+    fn record(
+        &mut self,
+        symbol: &str,
+        apply: impl FnOnce(&mut OrderBook) -> Result<BookChange>,
+    ) -> Result<BookChange> {
+        let (book, stats) = self.entry(symbol)?;
+        let result = apply(book);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_book_update(symbol, &result);
+        match result {
+            Ok(change) => {
+                stats.updates += 1;
+                stats.top_changes += u64::from(change.top_changed);
+                if let Some(bbo) = &change.bbo {
+                    for callback in &mut self.bbo_subscribers {
+                        callback(symbol, bbo);
+                    }
+                }
+                Ok(change)
+            }
+            Err(e) => {
+                if matches!(e, MarketDataError::SequenceGap { .. }) {
+                    stats.gaps += 1;
+                } else {
+                    stats.rejected += 1;
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_updates_by_symbol() {
+        let mut manager = OrderBookManager::new();
+        let t = Timestamp::from_millis(1);
+        manager
+            .apply_updates("BTC", &[LevelUpdate::bid(100.0, 1.0)], t)
+            .unwrap();
+        manager
+            .apply_updates("ETH", &[LevelUpdate::ask(10.0, 2.0)], t)
+            .unwrap();
+        manager
+            .apply_updates("BTC", &[LevelUpdate::ask(101.0, 1.0)], t)
+            .unwrap();
+
+        assert_eq!(manager.len(), 2);
+        assert_eq!(manager.book("BTC").unwrap().mid_price(), Some(100.5));
+        assert_eq!(manager.book("ETH").unwrap().best_ask(), Some((10.0, 2.0)));
+        assert_eq!(manager.stats("BTC").unwrap().updates, 2);
+        assert!(manager.book("SOL").is_none());
+
+        let snapshots = manager.snapshots(5);
+        let symbols: Vec<&str> = snapshots.iter().map(|s| s.symbol()).collect();
+        assert_eq!(symbols, vec!["BTC", "ETH"]);
+    }
+
+    #[test]
+    fn test_stats_count_rejections_and_gaps() {
+        let mut manager = OrderBookManager::new().with_strict(true);
+        let t = Timestamp::from_millis(1);
+        let level = |price| PriceLevel {
+            price,
+            quantity: 1.0,
+        };
+        manager
+            .apply_snapshot("BTC", &[level(100.0)], &[level(101.0)], 5, Timestamp::EPOCH)
+            .unwrap();
+
+        assert!(manager
+            .apply_updates("BTC", &[LevelUpdate::bid(102.0, 1.0)], t)
+            .is_err());
+        let gap = BookDelta {
+            sequence: 9,
+            updates: vec![],
+            timestamp: t,
+        };
+        assert!(manager.apply_delta("BTC", &gap).is_err());
+
+        let stats = manager.stats("BTC").unwrap();
+        assert_eq!((stats.updates, stats.rejected, stats.gaps), (1, 1, 1));
+        assert_eq!(manager.book("BTC").unwrap().best_bid(), Some((100.0, 1.0)));
+    }
+
+    #[test]
+    fn test_apply_event() {
+        let mut manager = OrderBookManager::new();
+        let t = Timestamp::from_millis(1);
+        let heartbeat = MarketEvent::new("test", "BTC", t, MarketData::Heartbeat);
+        assert_eq!(manager.apply_event(&heartbeat).unwrap(), None);
+        assert!(manager.is_empty());
+
+        let delta = MarketEvent::new(
+            "test",
+            "BTC",
+            t,
+            MarketData::BookDelta {
+                updates: vec![LevelUpdate::bid(100.0, 1.0)],
+                first_sequence: None,
+            },
+        );
+        let change = manager.apply_event(&delta).unwrap().unwrap();
+        assert_eq!(change.inserted, 1);
+        assert!(manager.remove("BTC").is_some());
+        assert!(!manager.contains("BTC"));
+    }
+
+    #[test]
+    fn test_bbo_subscribers_see_top_of_book_changes_only() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let mut manager = OrderBookManager::new();
+        manager.subscribe_bbo(move |symbol, bbo| {
+            sink.lock().unwrap().push((symbol.to_string(), *bbo));
+        });
+
+        let t = Timestamp::from_millis(7);
+        let change = manager
+            .apply_updates(
+                "BTC",
+                &[LevelUpdate::bid(100.0, 1.0), LevelUpdate::ask(101.0, 2.0)],
+                t,
+            )
+            .unwrap();
+        // A level behind the touch leaves the BBO alone
+        let deep = manager
+            .apply_updates("BTC", &[LevelUpdate::bid(99.0, 5.0)], t)
+            .unwrap();
+        manager
+            .apply_updates("BTC", &[LevelUpdate::bid(100.0, 3.0)], t)
+            .unwrap();
+
+        assert_eq!(change.bbo.unwrap().best_ask, Some((101.0, 2.0)));
+        assert_eq!(deep.bbo, None);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1].0, "BTC");
+        assert_eq!(seen[1].1.best_bid, Some((100.0, 3.0)));
+        assert_eq!(seen[1].1.timestamp, t);
+    }
+}
@@ -1,64 +1,555 @@
 use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
+
+use crate::clock::Clock;
+use crate::error::{ensure, MarketDataError, Result};
+use crate::time::Timestamp;
+
+pub mod checksum;
+pub mod journal;
+pub mod l3;
+pub mod liquidity;
+pub mod manager;
+pub mod scheduler;
+pub mod snapshot;
+pub mod ticks;
+
+pub use checksum::ChecksumFormat;
+pub use journal::{BookJournal, JournalEntry};
+pub use l3::{Execution, L3Order, L3OrderBook, QueuePosition};
+pub use liquidity::{LiquidityConfig, LiquiditySnapshot};
+pub use manager::{BboCallback, BookStats, OrderBookManager};
+pub use scheduler::{SnapshotReason, SnapshotScheduler, SnapshotSink};
+pub use snapshot::BookSnapshot;
+pub use ticks::TickOrderBook;
+
+/// Side of the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// Absolute level change as sent by exchanges; zero quantity deletes the level
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelUpdate {
+    pub side: BookSide,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+impl LevelUpdate {
+    pub fn bid(price: f64, quantity: f64) -> Self {
+        Self {
+            side: BookSide::Bid,
+            price,
+            quantity,
+        }
+    }
+
+    pub fn ask(price: f64, quantity: f64) -> Self {
+        Self {
+            side: BookSide::Ask,
+            price,
+            quantity,
+        }
+    }
+}
+
+/// Summary of a batch applied with [`OrderBook::apply_updates`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookChange {
+    pub inserted: usize,
+    pub modified: usize,
+    pub removed: usize,
+    /// Best bid or ask price/size differs from before the batch
+    pub top_changed: bool,
+    /// New top of book, set whenever `top_changed` is
+    #[serde(default)]
+    pub bbo: Option<BboUpdate>,
+}
+
+impl BookChange {
+    pub fn is_empty(&self) -> bool {
+        self.inserted + self.modified + self.removed == 0
+    }
+}
+
+/// Best bid and ask (price, size) after an update that changed either of them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BboUpdate {
+    pub best_bid: Option<(f64, f64)>,
+    pub best_ask: Option<(f64, f64)>,
+    pub timestamp: Timestamp,
+}
+
+/// Incremental book update carrying the exchange sequence number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookDelta {
+    pub sequence: u64,
+    pub updates: Vec<LevelUpdate>,
+    pub timestamp: Timestamp,
+}
 
 /// Price level in the order book
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PriceLevel {
     pub price: f64,
     pub quantity: f64,
 }
 
+/// Outcome of walking one side of the book with a market order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sweep {
+    /// Quantity that the visible liquidity could fill
+    pub filled: f64,
+    /// Requested quantity left over once the side was exhausted
+    pub unfilled: f64,
+    /// Volume-weighted fill price
+    pub average_price: f64,
+    /// Price of the last level touched
+    pub worst_price: f64,
+    /// Levels touched, including a partially consumed last one
+    pub levels_consumed: usize,
+    pub notional: f64,
+}
+
+impl Sweep {
+    pub fn is_complete(&self) -> bool {
+        self.unfilled == 0.0
+    }
+
+    /// Cost of the fill relative to `reference` (e.g. the mid), in basis points;
+    /// positive means worse than the reference for either direction
+    pub fn slippage_bps(&self, reference: f64) -> f64 {
+        (self.average_price - reference).abs() / reference * 10_000.0
+    }
+}
+
+/// Top-of-book quote
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub bid_price: f64,
+    pub bid_size: f64,
+    pub ask_price: f64,
+    pub ask_size: f64,
+    pub timestamp: Timestamp,
+}
+
+impl Quote {
+    pub fn mid(&self) -> f64 {
+        (self.bid_price + self.ask_price) / 2.0
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.ask_price - self.bid_price
+    }
+}
+
 /// Order book for a trading symbol
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OrderBook {
     pub symbol: String,
     pub bids: BTreeMap<OrderedFloat, f64>,
     pub asks: BTreeMap<OrderedFloat, f64>,
-    pub last_update: i64,
+    /// Exchange time of the last applied update
+    pub last_update: Timestamp,
+    /// Sequence number of the last applied snapshot or delta
+    #[serde(default)]
+    pub sequence: Option<u64>,
+    /// Reject updates that would leave the book crossed or locked
+    #[serde(default)]
+    pub strict: bool,
+    /// Log of applied changes, see [`start_journal`](Self::start_journal)
+    #[serde(skip)]
+    journal: Option<BookJournal>,
+}
+
+/// Clones are not journaled: a journal records the changes of one book only
+impl Clone for OrderBook {
+    fn clone(&self) -> Self {
+        Self {
+            symbol: self.symbol.clone(),
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+            last_update: self.last_update,
+            sequence: self.sequence,
+            strict: self.strict,
+            journal: None,
+        }
+    }
 }
 
 /// Wrapper for f64 to make it orderable in BTreeMap
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct OrderedFloat(pub f64);
 
+impl PartialEq for OrderedFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
 impl Eq for OrderedFloat {}
 
+impl PartialOrd for OrderedFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for OrderedFloat {
+    /// IEEE 754 total order, so that even NaN keys cannot break the BTreeMap invariants
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Largest serialized book accepted by [`OrderBook::from_json`]
+pub const MAX_JSON_BYTES: usize = 16 * 1024 * 1024;
+
+fn validate(price: f64, quantity: f64) -> Result<OrderedFloat> {
+    if !(price.is_finite() && price > 0.0) {
+        return Err(MarketDataError::InvalidPrice(price));
+    }
+    if !(quantity.is_finite() && quantity >= 0.0) {
+        return Err(MarketDataError::InvalidQuantity(quantity));
+    }
+    Ok(OrderedFloat(price))
+}
+
+/// Walk `levels` best-first until `quantity` is filled or liquidity runs out
+fn sweep(levels: impl Iterator<Item = (f64, f64)>, quantity: f64) -> Option<Sweep> {
+    if !(quantity.is_finite() && quantity > 0.0) {
+        return None;
+    }
+
+    let mut remaining = quantity;
+    let mut notional = 0.0;
+    let mut worst_price = None;
+    let mut levels_consumed = 0;
+
+    for (price, available) in levels {
+        if remaining <= 0.0 {
+            break;
+        }
+        let fill = available.min(remaining);
+        notional += fill * price;
+        remaining -= fill;
+        worst_price = Some(price);
+        levels_consumed += 1;
+    }
+
+    let worst_price = worst_price?;
+    let unfilled = remaining.max(0.0);
+    let filled = quantity - unfilled;
+    Some(Sweep {
+        filled,
+        unfilled,
+        average_price: notional / filled,
+        worst_price,
+        levels_consumed,
+        notional,
+    })
+}
+
+/// Error for a bid at or above an ask
+fn check_touch(bid: f64, ask: f64) -> Result<()> {
+    if bid > ask {
+        Err(MarketDataError::CrossedBook { bid, ask })
+    } else if bid == ask {
+        Err(MarketDataError::LockedBook(bid))
+    } else {
+        Ok(())
     }
 }
 
 impl OrderBook {
+    /// Create a new order book, validating the symbol
+    ///
+    /// Symbols must be non-empty ASCII made of alphanumerics and `/ - _ . :` separators.
+    pub fn try_new(symbol: String) -> Result<Self> {
+        ensure(!symbol.is_empty(), "symbol must not be empty")?;
+        ensure(
+            symbol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "/-_.:".contains(c)),
+            "symbol contains invalid characters",
+        )?;
+        Ok(Self::new(symbol))
+    }
+
     /// Create a new order book
     pub fn new(symbol: String) -> Self {
         Self {
             symbol,
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
-            last_update: 0,
+            last_update: Timestamp::EPOCH,
+            sequence: None,
+            strict: false,
+            journal: None,
+        }
+    }
+
+    /// Enable strict mode: updates that would cross or lock the book fail with the
+    /// error [`validate`](Self::validate) reports, leaving the book unchanged
+    ///
+    /// Single-level updates are checked one at a time, so feeds that move both sides
+    /// should use [`apply_updates`](Self::apply_updates) to validate the whole batch.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Crossed (best bid above best ask) or locked (equal) books are errors
+    pub fn validate(&self) -> Result<()> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid, _)), Some((ask, _))) => check_touch(bid, ask),
+            _ => Ok(()),
         }
     }
 
-    /// Update bid level
-    pub fn update_bid(&mut self, price: f64, quantity: f64) {
-        let key = OrderedFloat(price);
+    pub fn is_crossed(&self) -> bool {
+        matches!(self.validate(), Err(MarketDataError::CrossedBook { .. }))
+    }
+
+    pub fn is_locked(&self) -> bool {
+        matches!(self.validate(), Err(MarketDataError::LockedBook(_)))
+    }
+
+    /// Deserialize a book from untrusted JSON
+    ///
+    /// Unlike plain `serde_json::from_slice`, this bounds the input size and rejects
+    /// levels that [`update_bid`](Self::update_bid)/[`update_ask`](Self::update_ask)
+    /// would refuse, so the result upholds the same invariants as a book built
+    /// incrementally.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MAX_JSON_BYTES {
+            return Err(MarketDataError::invalid_parameter(format!(
+                "serialized book exceeds {MAX_JSON_BYTES} bytes"
+            )));
+        }
+
+        let book: OrderBook = serde_json::from_slice(bytes)?;
+        for (price, quantity) in book.bids.iter().chain(book.asks.iter()) {
+            validate(price.0, *quantity)?;
+            if *quantity == 0.0 {
+                return Err(MarketDataError::InvalidQuantity(*quantity));
+            }
+        }
+        Ok(book)
+    }
+
+    /// Update bid level at `timestamp`; a zero quantity removes the level
+    pub fn update_bid(&mut self, price: f64, quantity: f64, timestamp: Timestamp) -> Result<()> {
+        let key = validate(price, quantity)?;
+        if quantity > 0.0 && self.strict {
+            if let Some((ask, _)) = self.best_ask() {
+                check_touch(price, ask)?;
+            }
+        }
+        self.record(|| JournalEntry::Updates {
+            updates: vec![LevelUpdate::bid(price, quantity)],
+            sequence: None,
+            timestamp,
+        })?;
         if quantity == 0.0 {
             self.bids.remove(&key);
         } else {
             self.bids.insert(key, quantity);
         }
+        self.last_update = timestamp;
+        Ok(())
     }
 
-    /// Update ask level
-    pub fn update_ask(&mut self, price: f64, quantity: f64) {
-        let key = OrderedFloat(price);
+    /// Update ask level at `timestamp`; a zero quantity removes the level
+    pub fn update_ask(&mut self, price: f64, quantity: f64, timestamp: Timestamp) -> Result<()> {
+        let key = validate(price, quantity)?;
+        if quantity > 0.0 && self.strict {
+            if let Some((bid, _)) = self.best_bid() {
+                check_touch(bid, price)?;
+            }
+        }
+        self.record(|| JournalEntry::Updates {
+            updates: vec![LevelUpdate::ask(price, quantity)],
+            sequence: None,
+            timestamp,
+        })?;
         if quantity == 0.0 {
             self.asks.remove(&key);
         } else {
             self.asks.insert(key, quantity);
         }
+        self.last_update = timestamp;
+        Ok(())
+    }
+
+    /// Apply one exchange message worth of level changes atomically
+    ///
+    /// Every update is validated before anything is applied, so an invalid entry
+    /// leaves the book untouched. In strict mode a batch leaving the book crossed or
+    /// locked is rolled back. On success `last_update` is set once to `timestamp` and
+    /// a single [`BookChange`] summarizes the whole batch.
+    pub fn apply_updates(&mut self, updates: &[LevelUpdate], timestamp: Timestamp) -> Result<BookChange> {
+        self.apply_level_updates(updates, timestamp, || JournalEntry::Updates {
+            updates: updates.to_vec(),
+            sequence: None,
+            timestamp,
+        })
+    }
+
+    /// Apply `updates`, then journal `entry`; the batch is rolled back if the book
+    /// ends up crossed in strict mode or the journal append fails
+    fn apply_level_updates(
+        &mut self,
+        updates: &[LevelUpdate],
+        timestamp: Timestamp,
+        entry: impl FnOnce() -> JournalEntry,
+    ) -> Result<BookChange> {
+        for update in updates {
+            validate(update.price, update.quantity)?;
+        }
+
+        let top_before = (self.best_bid(), self.best_ask());
+        let track_undo = self.strict || self.journal.is_some();
+        let mut change = BookChange::default();
+        let mut undo = Vec::new();
+
+        for update in updates {
+            let side = match update.side {
+                BookSide::Bid => &mut self.bids,
+                BookSide::Ask => &mut self.asks,
+            };
+            let key = OrderedFloat(update.price);
+
+            let previous = if update.quantity == 0.0 {
+                let previous = side.remove(&key);
+                if previous.is_some() {
+                    change.removed += 1;
+                }
+                previous
+            } else {
+                let previous = side.insert(key, update.quantity);
+                match previous {
+                    None => change.inserted += 1,
+                    Some(previous) if previous != update.quantity => change.modified += 1,
+                    Some(_) => {}
+                }
+                previous
+            };
+            if track_undo {
+                undo.push((update.side, key, previous));
+            }
+        }
+
+        let checked = if self.strict { self.validate() } else { Ok(()) };
+        if let Err(e) = checked.and_then(|()| self.record(entry)) {
+            for (side, key, previous) in undo.into_iter().rev() {
+                let side = match side {
+                    BookSide::Bid => &mut self.bids,
+                    BookSide::Ask => &mut self.asks,
+                };
+                match previous {
+                    Some(quantity) => side.insert(key, quantity),
+                    None => side.remove(&key),
+                };
+            }
+            return Err(e);
+        }
+
+        change.top_changed = (self.best_bid(), self.best_ask()) != top_before;
+        self.last_update = timestamp;
+        if change.top_changed {
+            change.bbo = Some(self.bbo());
+        }
+        Ok(change)
+    }
+
+    /// Replace the whole book with an exchange snapshot taken at `sequence` and
+    /// `timestamp`
+    ///
+    /// Levels are validated first, so an invalid snapshot leaves the book untouched.
+    /// Zero-quantity levels are skipped.
+    pub fn apply_snapshot(
+        &mut self,
+        bids: &[PriceLevel],
+        asks: &[PriceLevel],
+        sequence: u64,
+        timestamp: Timestamp,
+    ) -> Result<()> {
+        let side = |levels: &[PriceLevel]| -> Result<BTreeMap<OrderedFloat, f64>> {
+            let mut map = BTreeMap::new();
+            for level in levels {
+                let key = validate(level.price, level.quantity)?;
+                if level.quantity > 0.0 {
+                    map.insert(key, level.quantity);
+                }
+            }
+            Ok(map)
+        };
+
+        let (bid_map, ask_map) = (side(bids)?, side(asks)?);
+        self.record(|| JournalEntry::Snapshot {
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+            sequence: Some(sequence),
+            timestamp,
+        })?;
+        self.bids = bid_map;
+        self.asks = ask_map;
+        self.sequence = Some(sequence);
+        self.last_update = timestamp;
+        Ok(())
+    }
+
+    /// Apply the delta following the current sequence number
+    ///
+    /// Deltas at or below the current sequence are stale (e.g. buffered while the
+    /// snapshot was fetched) and ignored with an empty change. A jump past the next
+    /// sequence returns [`MarketDataError::SequenceGap`] without touching the book:
+    /// the caller must resynchronize from a fresh snapshot.
+    pub fn apply_delta(&mut self, delta: &BookDelta) -> Result<BookChange> {
+        let Some(current) = self.sequence else {
+            return Err(MarketDataError::invalid_parameter("delta received before any snapshot"));
+        };
+        if delta.sequence <= current {
+            return Ok(BookChange::default());
+        }
+        if delta.sequence != current + 1 {
+            return Err(MarketDataError::SequenceGap {
+                expected: current + 1,
+                received: delta.sequence,
+            });
+        }
+
+        let change =
+            self.apply_level_updates(&delta.updates, delta.timestamp, || JournalEntry::Updates {
+                updates: delta.updates.clone(),
+                sequence: Some(delta.sequence),
+                timestamp: delta.timestamp,
+            })?;
+        self.sequence = Some(delta.sequence);
+        Ok(change)
+    }
+
+    /// Record the exchange time of the latest update
+    pub fn set_last_update(&mut self, timestamp: Timestamp) {
+        self.last_update = timestamp;
+    }
+
+    /// Stamp the book with `clock`'s current time, for feeds without exchange timestamps
+    pub fn touch(&mut self, clock: &dyn Clock) {
+        self.last_update = clock.now();
+    }
+
+    /// Current top of book, stamped with `last_update`
+    pub fn bbo(&self) -> BboUpdate {
+        BboUpdate {
+            best_bid: self.best_bid(),
+            best_ask: self.best_ask(),
+            timestamp: self.last_update,
+        }
     }
 
     /// Get best bid (highest buy price)
@@ -71,6 +562,20 @@ impl OrderBook {
         self.asks.iter().next().map(|(k, v)| (k.0, *v))
     }
 
+    /// Get best bid and ask as a quote stamped with the last update time
+    pub fn quote(&self) -> Option<Quote> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid_price, bid_size)), Some((ask_price, ask_size))) => Some(Quote {
+                bid_price,
+                bid_size,
+                ask_price,
+                ask_size,
+                timestamp: self.last_update,
+            }),
+            _ => None,
+        }
+    }
+
     /// Get mid price
     pub fn mid_price(&self) -> Option<f64> {
         match (self.best_bid(), self.best_ask()) {
@@ -120,6 +625,24 @@ impl OrderBook {
             .collect()
     }
 
+    /// Write the top N bid levels into `out`, reusing its allocation
+    pub fn top_bids_into(&self, n: usize, out: &mut Vec<PriceLevel>) {
+        out.clear();
+        out.extend(self.bids.iter().rev().take(n).map(|(k, v)| PriceLevel {
+            price: k.0,
+            quantity: *v,
+        }));
+    }
+
+    /// Write the top N ask levels into `out`, reusing its allocation
+    pub fn top_asks_into(&self, n: usize, out: &mut Vec<PriceLevel>) {
+        out.clear();
+        out.extend(self.asks.iter().take(n).map(|(k, v)| PriceLevel {
+            price: k.0,
+            quantity: *v,
+        }));
+    }
+
     /// Calculate total volume at bid side
     pub fn total_bid_volume(&self) -> f64 {
         self.bids.values().sum()
@@ -130,6 +653,43 @@ impl OrderBook {
         self.asks.values().sum()
     }
 
+    /// Volume imbalance over the best `n` levels of each side, in [-1, 1]
+    pub fn volume_imbalance_top(&self, n: usize) -> f64 {
+        let bid_vol: f64 = self.bids.values().rev().take(n).sum();
+        let ask_vol: f64 = self.asks.values().take(n).sum();
+        let total = bid_vol + ask_vol;
+
+        if total > 0.0 {
+            (bid_vol - ask_vol) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Microprice: best bid and ask weighted by the size on the opposite side
+    ///
+    /// A heavy bid pulls the price towards the ask, anticipating the next move.
+    pub fn weighted_mid_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid, bid_size)), Some((ask, ask_size))) if bid_size + ask_size > 0.0 => {
+                Some((bid * ask_size + ask * bid_size) / (bid_size + ask_size))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fill a market buy of `quantity` against the asks without modifying the book
+    ///
+    /// Returns `None` for a non-positive quantity or an empty ask side.
+    pub fn simulate_market_buy(&self, quantity: f64) -> Option<Sweep> {
+        sweep(self.asks.iter().map(|(k, v)| (k.0, *v)), quantity)
+    }
+
+    /// Fill a market sell of `quantity` against the bids without modifying the book
+    pub fn simulate_market_sell(&self, quantity: f64) -> Option<Sweep> {
+        sweep(self.bids.iter().rev().map(|(k, v)| (k.0, *v)), quantity)
+    }
+
     /// Calculate volume imbalance
     pub fn volume_imbalance(&self) -> f64 {
         let bid_vol = self.total_bid_volume();
@@ -147,6 +707,7 @@ impl OrderBook {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_orderbook_creation() {
@@ -159,8 +720,8 @@ mod tests {
     #[test]
     fn test_update_bid() {
         let mut ob = OrderBook::new("BTCUSD".to_string());
-        ob.update_bid(50000.0, 1.5);
-        ob.update_bid(49999.0, 2.0);
+        ob.update_bid(50000.0, 1.5, Timestamp::EPOCH).unwrap();
+        ob.update_bid(49999.0, 2.0, Timestamp::EPOCH).unwrap();
         
         assert_eq!(ob.bids.len(), 2);
         assert_eq!(ob.best_bid(), Some((50000.0, 1.5)));
@@ -169,8 +730,8 @@ mod tests {
     #[test]
     fn test_update_ask() {
         let mut ob = OrderBook::new("BTCUSD".to_string());
-        ob.update_ask(50001.0, 1.0);
-        ob.update_ask(50002.0, 1.5);
+        ob.update_ask(50001.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(50002.0, 1.5, Timestamp::EPOCH).unwrap();
         
         assert_eq!(ob.asks.len(), 2);
         assert_eq!(ob.best_ask(), Some((50001.0, 1.0)));
@@ -179,8 +740,8 @@ mod tests {
     #[test]
     fn test_mid_price() {
         let mut ob = OrderBook::new("BTCUSD".to_string());
-        ob.update_bid(50000.0, 1.0);
-        ob.update_ask(50002.0, 1.0);
+        ob.update_bid(50000.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(50002.0, 1.0, Timestamp::EPOCH).unwrap();
         
         assert_eq!(ob.mid_price(), Some(50001.0));
     }
@@ -188,8 +749,8 @@ mod tests {
     #[test]
     fn test_spread() {
         let mut ob = OrderBook::new("BTCUSD".to_string());
-        ob.update_bid(50000.0, 1.0);
-        ob.update_ask(50002.0, 1.0);
+        ob.update_bid(50000.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(50002.0, 1.0, Timestamp::EPOCH).unwrap();
         
         assert_eq!(ob.spread(), Some(2.0));
     }
@@ -197,10 +758,365 @@ mod tests {
     #[test]
     fn test_volume_imbalance() {
         let mut ob = OrderBook::new("BTCUSD".to_string());
-        ob.update_bid(50000.0, 3.0);
-        ob.update_ask(50001.0, 1.0);
+        ob.update_bid(50000.0, 3.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(50001.0, 1.0, Timestamp::EPOCH).unwrap();
         
         let imbalance = ob.volume_imbalance();
         assert!(imbalance > 0.0); // More bids than asks
     }
+
+    #[test]
+    fn test_top_levels_into_reuses_buffer() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        for i in 0..5 {
+            ob.update_bid(100.0 - i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_ask(101.0 + i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
+        }
+
+        let mut levels = Vec::with_capacity(3);
+        ob.top_bids_into(3, &mut levels);
+        assert_eq!(levels.iter().map(|l| l.price).collect::<Vec<_>>(), vec![100.0, 99.0, 98.0]);
+
+        ob.top_asks_into(2, &mut levels);
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].price, 101.0);
+        assert_eq!(levels.capacity(), 3);
+    }
+
+    #[test]
+    fn test_apply_updates_batch() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(102.0, 1.0, Timestamp::EPOCH).unwrap();
+
+        let change = ob
+            .apply_updates(
+                &[
+                    LevelUpdate::bid(100.0, 2.0),
+                    LevelUpdate::bid(99.0, 1.0),
+                    LevelUpdate::ask(102.0, 0.0),
+                    LevelUpdate::ask(103.0, 1.0),
+                    LevelUpdate::ask(110.0, 0.0),
+                ],
+                Timestamp::from_millis(5),
+            )
+            .unwrap();
+
+        assert_eq!(change.inserted, 2);
+        assert_eq!(change.modified, 1);
+        assert_eq!(change.removed, 1);
+        assert!(change.top_changed);
+        assert_eq!(ob.best_ask(), Some((103.0, 1.0)));
+        assert_eq!(ob.last_update, Timestamp::from_millis(5));
+
+        // Deep level change only
+        let change = ob.apply_updates(&[LevelUpdate::bid(90.0, 1.0)], Timestamp::from_millis(6)).unwrap();
+        assert!(!change.top_changed);
+    }
+
+    #[test]
+    fn test_apply_updates_is_atomic() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+
+        let result = ob.apply_updates(
+            &[LevelUpdate::bid(100.0, 0.0), LevelUpdate::ask(f64::NAN, 1.0)],
+            Timestamp::from_millis(1),
+        );
+        assert!(matches!(result, Err(MarketDataError::InvalidPrice(_))));
+        assert_eq!(ob.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(ob.last_update, Timestamp::EPOCH);
+        assert!(ob.apply_updates(&[], Timestamp::from_millis(2)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_quote_uses_last_update() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(101.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.set_last_update(Timestamp::from_millis(1_234));
+
+        assert_eq!(ob.quote().unwrap().timestamp, Timestamp::from_millis(1_234));
+    }
+
+    #[test]
+    fn test_rejects_invalid_levels() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+
+        assert!(matches!(
+            ob.update_bid(f64::NAN, 1.0, Timestamp::EPOCH),
+            Err(MarketDataError::InvalidPrice(_))
+        ));
+        assert!(matches!(
+            ob.update_ask(f64::INFINITY, 1.0, Timestamp::EPOCH),
+            Err(MarketDataError::InvalidPrice(_))
+        ));
+        assert!(matches!(
+            ob.update_bid(-1.0, 1.0, Timestamp::EPOCH),
+            Err(MarketDataError::InvalidPrice(_))
+        ));
+        assert!(matches!(
+            ob.update_ask(100.0, -1.0, Timestamp::EPOCH),
+            Err(MarketDataError::InvalidQuantity(_))
+        ));
+        assert!(matches!(
+            ob.update_ask(100.0, f64::NAN, Timestamp::EPOCH),
+            Err(MarketDataError::InvalidQuantity(_))
+        ));
+
+        assert!(ob.bids.is_empty());
+        assert!(ob.asks.is_empty());
+    }
+
+    #[test]
+    fn test_try_new_validates_symbol() {
+        assert!(OrderBook::try_new("BTC/USD".to_string()).is_ok());
+        assert!(OrderBook::try_new("ES-2024.Z".to_string()).is_ok());
+        assert!(OrderBook::try_new(String::new()).is_err());
+        assert!(OrderBook::try_new("BTC USD".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_from_json_validates_levels() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(101.0, 2.0, Timestamp::EPOCH).unwrap();
+
+        let json = serde_json::to_vec(&ob).unwrap();
+        let parsed = OrderBook::from_json(&json).unwrap();
+        assert_eq!(parsed.best_ask(), Some((101.0, 2.0)));
+
+        let bad = br#"{"symbol":"X","bids":{"-1.0":1.0},"asks":{},"last_update":0}"#;
+        assert!(matches!(OrderBook::from_json(bad), Err(MarketDataError::InvalidPrice(_))));
+        assert!(matches!(OrderBook::from_json(b"{"), Err(MarketDataError::Serialization(_))));
+    }
+
+    fn levels(levels: &[(f64, f64)]) -> Vec<PriceLevel> {
+        levels
+            .iter()
+            .map(|&(price, quantity)| PriceLevel { price, quantity })
+            .collect()
+    }
+
+    fn delta(sequence: u64, updates: Vec<LevelUpdate>) -> BookDelta {
+        BookDelta {
+            sequence,
+            updates,
+            timestamp: Timestamp::from_millis(sequence as i64),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_then_deltas_in_sequence() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(1.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.apply_snapshot(
+            &levels(&[(100.0, 1.0), (99.0, 2.0)]),
+            &levels(&[(101.0, 1.0), (102.0, 0.0)]),
+            10,
+            Timestamp::EPOCH,
+        )
+        .unwrap();
+        assert_eq!(ob.bids.len(), 2);
+        assert_eq!(ob.asks.len(), 1);
+        assert_eq!(ob.sequence, Some(10));
+
+        // Buffered delta from before the snapshot is ignored
+        assert!(ob.apply_delta(&delta(9, vec![LevelUpdate::bid(100.0, 0.0)])).unwrap().is_empty());
+        assert_eq!(ob.best_bid(), Some((100.0, 1.0)));
+
+        let change = ob.apply_delta(&delta(11, vec![LevelUpdate::ask(100.5, 3.0)])).unwrap();
+        assert!(change.top_changed);
+        assert_eq!(ob.sequence, Some(11));
+        assert_eq!(ob.last_update, Timestamp::from_millis(11));
+    }
+
+    #[test]
+    fn test_delta_gap_is_an_error() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        assert!(ob.apply_delta(&delta(1, vec![])).is_err());
+
+        ob.apply_snapshot(&levels(&[(100.0, 1.0)]), &[], 5, Timestamp::EPOCH)
+            .unwrap();
+        assert!(matches!(
+            ob.apply_delta(&delta(7, vec![LevelUpdate::bid(100.0, 0.0)])),
+            Err(MarketDataError::SequenceGap { expected: 6, received: 7 })
+        ));
+        assert_eq!(ob.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(ob.sequence, Some(5));
+
+        // Invalid snapshot leaves the previous state
+        assert!(ob
+            .apply_snapshot(&levels(&[(-1.0, 1.0)]), &[], 8, Timestamp::EPOCH)
+            .is_err());
+        assert_eq!(ob.sequence, Some(5));
+    }
+
+    #[test]
+    fn test_validate_detects_crossed_and_locked() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(101.0, 1.0, Timestamp::EPOCH).unwrap();
+        assert!(ob.validate().is_ok());
+
+        ob.update_bid(101.0, 1.0, Timestamp::EPOCH).unwrap();
+        assert!(matches!(ob.validate(), Err(MarketDataError::LockedBook(p)) if p == 101.0));
+        assert!(ob.is_locked());
+
+        ob.update_bid(102.0, 1.0, Timestamp::EPOCH).unwrap();
+        assert!(ob.is_crossed());
+        assert!(ob.mid_price().is_some());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_crossing_updates() {
+        let mut ob = OrderBook::new("BTCUSD".to_string()).with_strict(true);
+        ob.update_bid(100.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(101.0, 1.0, Timestamp::EPOCH).unwrap();
+
+        assert!(matches!(
+            ob.update_ask(99.0, 1.0, Timestamp::EPOCH),
+            Err(MarketDataError::CrossedBook { .. })
+        ));
+        assert!(ob.update_bid(101.0, 1.0, Timestamp::EPOCH).is_err());
+        assert_eq!(ob.best_ask(), Some((101.0, 1.0)));
+
+        // The batch moves both sides consistently, so it passes as a whole
+        ob.apply_updates(
+            &[LevelUpdate::ask(101.0, 0.0), LevelUpdate::ask(103.0, 1.0), LevelUpdate::bid(102.0, 1.0)],
+            Timestamp::from_millis(1),
+        )
+        .unwrap();
+
+        // A crossing batch is rolled back completely
+        let before = ob.top_bids(10).len();
+        assert!(ob
+            .apply_updates(
+                &[LevelUpdate::bid(102.0, 0.0), LevelUpdate::bid(104.0, 2.0)],
+                Timestamp::from_millis(2),
+            )
+            .is_err());
+        assert_eq!(ob.best_bid(), Some((102.0, 1.0)));
+        assert_eq!(ob.top_bids(10).len(), before);
+        assert_eq!(ob.last_update, Timestamp::from_millis(1));
+    }
+
+    #[test]
+    fn test_simulate_market_orders() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        ob.update_ask(101.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(102.0, 2.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(104.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_bid(100.0, 0.5, Timestamp::EPOCH).unwrap();
+
+        let buy = ob.simulate_market_buy(2.0).unwrap();
+        assert!(buy.is_complete());
+        assert_eq!(buy.average_price, 101.5);
+        assert_eq!(buy.worst_price, 102.0);
+        assert_eq!(buy.levels_consumed, 2);
+        assert_eq!(buy.notional, 203.0);
+        assert!((buy.slippage_bps(101.0) - 49.504950495).abs() < 1e-6);
+
+        let sell = ob.simulate_market_sell(2.0).unwrap();
+        assert_eq!((sell.filled, sell.unfilled), (0.5, 1.5));
+        assert!(!sell.is_complete());
+        assert_eq!(sell.average_price, 100.0);
+
+        // The book is left untouched
+        assert_eq!(ob.best_ask(), Some((101.0, 1.0)));
+        assert_eq!(ob.simulate_market_buy(0.0), None);
+        assert_eq!(OrderBook::new("X".to_string()).simulate_market_sell(1.0), None);
+    }
+
+    #[test]
+    fn test_depth_imbalance_and_microprice() {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        assert_eq!(ob.volume_imbalance_top(5), 0.0);
+        assert_eq!(ob.weighted_mid_price(), None);
+
+        ob.update_bid(100.0, 3.0, Timestamp::EPOCH).unwrap();
+        ob.update_bid(99.0, 10.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(102.0, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(103.0, 2.0, Timestamp::EPOCH).unwrap();
+
+        assert_eq!(ob.volume_imbalance_top(1), 0.5);
+        assert_eq!(ob.volume_imbalance_top(2), 0.625);
+        assert_eq!(ob.volume_imbalance_top(10), ob.volume_imbalance());
+
+        // Three times more size on the bid moves the microprice 3/4 of the way up
+        assert_eq!(ob.weighted_mid_price(), Some(101.5));
+        assert_eq!(ob.mid_price(), Some(101.0));
+    }
+
+    #[test]
+    fn test_ordered_float_total_order() {
+        let mut values = [
+            OrderedFloat(2.0),
+            OrderedFloat(f64::NAN),
+            OrderedFloat(-0.0),
+            OrderedFloat(0.0),
+        ];
+        values.sort();
+        assert_eq!(values[0].0.to_bits(), (-0.0_f64).to_bits());
+        assert_eq!(values[2].0, 2.0);
+        assert!(values[3].0.is_nan());
+        assert_eq!(OrderedFloat(f64::NAN), OrderedFloat(f64::NAN));
+    }
+
+    fn adversarial_f64() -> impl Strategy<Value = f64> {
+        prop_oneof![
+            Just(f64::NAN),
+            Just(f64::INFINITY),
+            Just(f64::NEG_INFINITY),
+            Just(0.0),
+            Just(-0.0),
+            Just(f64::MIN_POSITIVE),
+            Just(f64::MAX),
+            any::<f64>(),
+            -1e6..1e6_f64,
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_book_stays_consistent(
+            updates in prop::collection::vec((any::<bool>(), adversarial_f64(), adversarial_f64()), 0..200)
+        ) {
+            let mut ob = OrderBook::new("BTCUSD".to_string());
+
+            for (is_bid, price, quantity) in updates {
+                let result = if is_bid {
+                    ob.update_bid(price, quantity, Timestamp::EPOCH)
+                } else {
+                    ob.update_ask(price, quantity, Timestamp::EPOCH)
+                };
+                let valid = price.is_finite() && price > 0.0 && quantity.is_finite() && quantity >= 0.0;
+                prop_assert_eq!(result.is_ok(), valid);
+            }
+
+            // Only valid levels are stored and iteration order is strictly increasing
+            for side in [&ob.bids, &ob.asks] {
+                let prices: Vec<f64> = side.keys().map(|k| k.0).collect();
+                prop_assert!(prices.iter().all(|p| p.is_finite() && *p > 0.0));
+                prop_assert!(prices.windows(2).all(|w| w[0] < w[1]));
+                prop_assert!(side.values().all(|q| q.is_finite() && *q > 0.0));
+            }
+
+            if let (Some((bid, _)), Some((ask, _))) = (ob.best_bid(), ob.best_ask()) {
+                prop_assert_eq!(Some(bid), ob.bids.keys().map(|k| k.0).reduce(f64::max));
+                prop_assert_eq!(Some(ask), ob.asks.keys().map(|k| k.0).reduce(f64::min));
+            }
+        }
+
+        #[test]
+        fn prop_ordered_float_is_total(a in adversarial_f64(), b in adversarial_f64(), c in adversarial_f64()) {
+            let (a, b, c) = (OrderedFloat(a), OrderedFloat(b), OrderedFloat(c));
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+    }
 }
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use super::snapshot::BookSnapshot;
+use super::OrderBook;
+use crate::clock::{SharedClock, SystemClock};
+use crate::error::{ensure, Result};
+
+/// Why a snapshot was captured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SnapshotReason {
+    /// First snapshot after creation or reset
+    Initial,
+    /// Regular cadence elapsed
+    Interval,
+    /// Mid price moved more than the configured threshold since the last snapshot
+    MidMove,
+}
+
+/// Destination of captured snapshots, e.g. a recorder or a publishing channel
+pub trait SnapshotSink: Send {
+    fn record(&mut self, snapshot: &BookSnapshot, reason: SnapshotReason);
+}
+
+impl<F> SnapshotSink for F
+where
+    F: FnMut(&BookSnapshot, SnapshotReason) + Send,
+{
+    fn record(&mut self, snapshot: &BookSnapshot, reason: SnapshotReason) {
+        self(snapshot, reason)
+    }
+}
+
+/// Captures book snapshots on a fixed cadence and on large mid moves
+///
+/// Call [`poll`](Self::poll) after applying updates (or from a timer). Snapshots are
+/// truncated to `depth` levels, identical consecutive snapshots are skipped, and only
+/// the latest `history` snapshots are retained in memory.
+pub struct SnapshotScheduler {
+    interval: i64,
+    mid_move_bps: Option<f64>,
+    depth: usize,
+    history_capacity: usize,
+    next_due: Option<i64>,
+    last: Option<BookSnapshot>,
+    history: VecDeque<(SnapshotReason, BookSnapshot)>,
+    sinks: Vec<Box<dyn SnapshotSink>>,
+    skipped: u64,
+    clock: SharedClock,
+}
+
+impl SnapshotScheduler {
+    /// Snapshot every `interval` milliseconds
+    ///
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(interval: i64) -> Self {
+        Self::try_new(interval).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(interval: i64) -> Result<Self> {
+        ensure(interval > 0, "interval must be positive")?;
+        Ok(Self {
+            interval,
+            mid_move_bps: None,
+            depth: 20,
+            history_capacity: 64,
+            next_due: None,
+            last: None,
+            history: VecDeque::new(),
+            sinks: Vec::new(),
+            skipped: 0,
+            clock: SystemClock::shared(),
+        })
+    }
+
+    /// Also snapshot when the mid moves at least `bps` basis points from the last snapshot
+    pub fn with_mid_move_bps(mut self, bps: f64) -> Self {
+        self.mid_move_bps = Some(bps);
+        self
+    }
+
+    /// Levels kept per side (default 20)
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Snapshots retained by [`history`](Self::history) (default 64)
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity;
+        self
+    }
+
+    /// Clock read by [`poll_now`](Self::poll_now) (default: system time)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn add_sink<S: SnapshotSink + 'static>(&mut self, sink: S) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Capture a snapshot if one is due at `now` (milliseconds)
+    pub fn poll(&mut self, book: &OrderBook, now: i64) -> Option<SnapshotReason> {
+        let reason = self.due(book, now)?;
+        self.next_due = Some(now + self.interval);
+
+        let snapshot = book.snapshot_depth(self.depth);
+        if self
+            .last
+            .as_ref()
+            .is_some_and(|last| last.same_levels(&snapshot))
+        {
+            self.skipped += 1;
+            return None;
+        }
+
+        for sink in &mut self.sinks {
+            sink.record(&snapshot, reason);
+        }
+        if self.history_capacity > 0 {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back((reason, snapshot.clone()));
+        }
+        self.last = Some(snapshot);
+
+        Some(reason)
+    }
+
+    /// [`poll`](Self::poll) at the current time of the configured clock, for timer-driven use
+    pub fn poll_now(&mut self, book: &OrderBook) -> Option<SnapshotReason> {
+        let now = self.clock.now_millis();
+        self.poll(book, now)
+    }
+
+    fn due(&self, book: &OrderBook, now: i64) -> Option<SnapshotReason> {
+        let Some(next_due) = self.next_due else {
+            return Some(SnapshotReason::Initial);
+        };
+        if now >= next_due {
+            return Some(SnapshotReason::Interval);
+        }
+
+        let threshold = self.mid_move_bps?;
+        let previous = self.last.as_ref()?.mid_price()?;
+        let mid = book.mid_price()?;
+        ((mid - previous).abs() / previous * 10_000.0 >= threshold)
+            .then_some(SnapshotReason::MidMove)
+    }
+
+    /// Latest retained snapshots, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &(SnapshotReason, BookSnapshot)> {
+        self.history.iter()
+    }
+
+    pub fn last(&self) -> Option<&BookSnapshot> {
+        self.last.as_ref()
+    }
+
+    /// Due snapshots dropped because the book had not changed
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    pub fn reset(&mut self) {
+        self.next_due = None;
+        self.last = None;
+        self.history.clear();
+        self.skipped = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::clock::MockClock;
+    use crate::testing::top_of_book;
+    use crate::time::Timestamp;
+
+    #[test]
+    fn test_interval_cadence_and_skip_unchanged() {
+        let mut scheduler = SnapshotScheduler::new(1_000);
+        let mut ob = top_of_book("BTCUSD", 100.0, 101.0, 1.0);
+
+        assert_eq!(scheduler.poll(&ob, 0), Some(SnapshotReason::Initial));
+        assert_eq!(scheduler.poll(&ob, 500), None);
+        // Due, but nothing changed
+        assert_eq!(scheduler.poll(&ob, 1_000), None);
+        assert_eq!(scheduler.skipped(), 1);
+
+        ob.update_bid(100.5, 2.0, Timestamp::EPOCH).unwrap();
+        assert_eq!(scheduler.poll(&ob, 1_500), None);
+        assert_eq!(scheduler.poll(&ob, 2_000), Some(SnapshotReason::Interval));
+        assert_eq!(scheduler.last().unwrap().best_bid(), Some((100.5, 2.0)));
+    }
+
+    #[test]
+    fn test_mid_move_triggers_early_snapshot() {
+        let mut scheduler = SnapshotScheduler::new(60_000).with_mid_move_bps(10.0);
+        let mut ob = top_of_book("BTCUSD", 100.0, 100.2, 1.0);
+        scheduler.poll(&ob, 0);
+
+        // 5 bps: below threshold
+        ob.update_ask(100.3, 1.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(100.2, 0.0, Timestamp::EPOCH).unwrap();
+        assert_eq!(scheduler.poll(&ob, 10), None);
+
+        ob.update_bid(100.3, 1.0, Timestamp::EPOCH).unwrap();
+        assert_eq!(scheduler.poll(&ob, 20), Some(SnapshotReason::MidMove));
+    }
+
+    #[test]
+    fn test_sinks_and_bounded_history() {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&recorded);
+        let mut scheduler = SnapshotScheduler::new(10).with_depth(1).with_history(2);
+        scheduler.add_sink(move |snap: &BookSnapshot, reason| {
+            sink.lock().unwrap().push((reason, snap.bids().len()));
+        });
+
+        let mut ob = top_of_book("BTCUSD", 100.0, 101.0, 1.0);
+        for i in 0..4 {
+            ob.update_bid(99.0 - i as f64, 1.0, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_bid(100.0, 1.0 + i as f64, Timestamp::EPOCH)
+                .unwrap();
+            scheduler.poll(&ob, i * 10);
+        }
+
+        assert_eq!(recorded.lock().unwrap().len(), 4);
+        assert!(recorded
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|&(_, depth)| depth == 1));
+        let history: Vec<_> = scheduler
+            .history()
+            .map(|(r, s)| (*r, s.best_bid()))
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                (SnapshotReason::Interval, Some((100.0, 3.0))),
+                (SnapshotReason::Interval, Some((100.0, 4.0)))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_poll_now_reads_injected_clock() {
+        let clock = MockClock::new(Timestamp::from_millis(0));
+        let mut scheduler = SnapshotScheduler::new(1_000).with_clock(Arc::new(clock.clone()));
+        let mut ob = top_of_book("BTCUSD", 100.0, 101.0, 1.0);
+
+        assert_eq!(scheduler.poll_now(&ob), Some(SnapshotReason::Initial));
+        ob.update_bid(100.5, 1.0, Timestamp::EPOCH).unwrap();
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(scheduler.poll_now(&ob), None);
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(scheduler.poll_now(&ob), Some(SnapshotReason::Interval));
+    }
+
+    #[test]
+    fn test_reset_and_try_new() {
+        let mut scheduler = SnapshotScheduler::new(10);
+        let ob = top_of_book("BTCUSD", 1.0, 2.0, 1.0);
+        scheduler.poll(&ob, 0);
+        scheduler.reset();
+        assert_eq!(scheduler.poll(&ob, 1), Some(SnapshotReason::Initial));
+
+        assert!(SnapshotScheduler::try_new(0).is_err());
+    }
+}
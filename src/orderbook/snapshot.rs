@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use super::{OrderBook, PriceLevel, Quote};
+use crate::time::Timestamp;
+
+/// Immutable view of an [`OrderBook`] at one point in time
+///
+/// Levels are frozen behind `Arc`s, so cloning a snapshot is O(1) and it can be sent to
+/// analytics threads while the writer keeps mutating the live book.
+#[derive(Debug, Clone)]
+pub struct BookSnapshot {
+    symbol: Arc<str>,
+    /// Best (highest) bid first
+    bids: Arc<[PriceLevel]>,
+    /// Best (lowest) ask first
+    asks: Arc<[PriceLevel]>,
+    last_update: Timestamp,
+}
+
+impl BookSnapshot {
+    /// Snapshot from levels already ordered best first, e.g. read back from storage
+    pub fn new(symbol: &str, bids: Vec<PriceLevel>, asks: Vec<PriceLevel>, last_update: Timestamp) -> Self {
+        Self {
+            symbol: Arc::from(symbol),
+            bids: bids.into(),
+            asks: asks.into(),
+            last_update,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn last_update(&self) -> Timestamp {
+        self.last_update
+    }
+
+    /// Bid levels, best first
+    pub fn bids(&self) -> &[PriceLevel] {
+        &self.bids
+    }
+
+    /// Ask levels, best first
+    pub fn asks(&self) -> &[PriceLevel] {
+        &self.asks
+    }
+
+    pub fn top_bids(&self, n: usize) -> &[PriceLevel] {
+        &self.bids[..n.min(self.bids.len())]
+    }
+
+    pub fn top_asks(&self, n: usize) -> &[PriceLevel] {
+        &self.asks[..n.min(self.asks.len())]
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.first().map(|l| (l.price, l.quantity))
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.first().map(|l| (l.price, l.quantity))
+    }
+
+    pub fn quote(&self) -> Option<Quote> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid_price, bid_size)), Some((ask_price, ask_size))) => Some(Quote {
+                bid_price,
+                bid_size,
+                ask_price,
+                ask_size,
+                timestamp: self.last_update,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        self.quote().map(|q| q.mid())
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        self.quote().map(|q| q.spread())
+    }
+
+    pub fn total_bid_volume(&self) -> f64 {
+        self.bids.iter().map(|l| l.quantity).sum()
+    }
+
+    pub fn total_ask_volume(&self) -> f64 {
+        self.asks.iter().map(|l| l.quantity).sum()
+    }
+
+    /// Same levels on both sides, ignoring symbol and timestamp
+    pub fn same_levels(&self, other: &Self) -> bool {
+        let eq = |a: &[PriceLevel], b: &[PriceLevel]| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(x, y)| x.price == y.price && x.quantity == y.quantity)
+        };
+        self.ptr_eq(other) || (eq(&self.bids, &other.bids) && eq(&self.asks, &other.asks))
+    }
+
+    /// Both snapshots share the same frozen levels (one was cloned from the other)
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.bids, &other.bids) && Arc::ptr_eq(&self.asks, &other.asks)
+    }
+}
+
+impl OrderBook {
+    /// Freeze the current levels into a cheaply cloneable [`BookSnapshot`]
+    ///
+    /// Costs one copy of the book; every clone of the result afterwards is a reference
+    /// count bump, so one writer can publish a snapshot to many readers without locks.
+    pub fn snapshot(&self) -> BookSnapshot {
+        self.snapshot_depth(usize::MAX)
+    }
+
+    /// Snapshot of the best `depth` levels per side, bounding its memory
+    pub fn snapshot_depth(&self, depth: usize) -> BookSnapshot {
+        BookSnapshot {
+            symbol: Arc::from(self.symbol.as_str()),
+            bids: self.top_bids(depth).into(),
+            asks: self.top_asks(depth).into(),
+            last_update: self.last_update,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn book() -> OrderBook {
+        let mut ob = OrderBook::new("BTCUSD".to_string());
+        for i in 0..5 {
+            ob.update_bid(100.0 - i as f64, 1.0 + i as f64, Timestamp::EPOCH)
+                .unwrap();
+            ob.update_ask(101.0 + i as f64, 2.0, Timestamp::EPOCH)
+                .unwrap();
+        }
+        ob.set_last_update(Timestamp::from_millis(42));
+        ob
+    }
+
+    #[test]
+    fn test_snapshot_matches_book() {
+        let ob = book();
+        let snap = ob.snapshot();
+
+        assert_eq!(snap.symbol(), "BTCUSD");
+        assert_eq!(snap.best_bid(), ob.best_bid());
+        assert_eq!(snap.best_ask(), ob.best_ask());
+        assert_eq!(snap.quote(), ob.quote());
+        assert_eq!(snap.mid_price(), ob.mid_price());
+        assert_eq!(snap.total_bid_volume(), ob.total_bid_volume());
+        assert_eq!(
+            snap.top_asks(2).iter().map(|l| l.price).collect::<Vec<_>>(),
+            vec![101.0, 102.0]
+        );
+        assert_eq!(snap.top_bids(50).len(), 5);
+
+        let shallow = ob.snapshot_depth(2);
+        assert_eq!(shallow.bids().len(), 2);
+        assert!(!shallow.same_levels(&snap));
+        assert!(ob.snapshot().same_levels(&snap));
+    }
+
+    #[test]
+    fn test_snapshot_unaffected_by_later_writes() {
+        let mut ob = book();
+        let snap = ob.snapshot();
+
+        ob.update_bid(100.0, 0.0, Timestamp::EPOCH).unwrap();
+        ob.update_ask(100.5, 3.0, Timestamp::EPOCH).unwrap();
+        ob.set_last_update(Timestamp::from_millis(43));
+
+        assert_eq!(snap.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(snap.best_ask(), Some((101.0, 2.0)));
+        assert_eq!(snap.last_update(), Timestamp::from_millis(42));
+        assert_eq!(ob.snapshot().best_ask(), Some((100.5, 3.0)));
+    }
+
+    #[test]
+    fn test_clone_shares_levels_across_threads() {
+        let snap = book().snapshot();
+        let copy = snap.clone();
+        assert!(copy.ptr_eq(&snap));
+        assert!(!snap.ptr_eq(&book().snapshot()));
+
+        let volume = thread::spawn(move || copy.total_ask_volume())
+            .join()
+            .unwrap();
+        assert_eq!(volume, 10.0);
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use super::{BboUpdate, BookChange, BookSide, LevelUpdate, OrderBook, OrderedFloat, PriceLevel};
+use crate::error::{MarketDataError, Result};
+use crate::symbols::SymbolInfo;
+use crate::time::Timestamp;
+
+/// Order book keyed by integer tick counts instead of raw `f64` prices
+///
+/// Prices are converted through the instrument's [`SymbolInfo`], so `0.1 + 0.2` and
+/// `0.3` land on the same level and every price read back is the exact grid value,
+/// which makes formatting levels for exchange checksums reliable. Prices off the
+/// tick grid are rejected rather than silently rounded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickOrderBook {
+    info: SymbolInfo,
+    bids: BTreeMap<i64, f64>,
+    asks: BTreeMap<i64, f64>,
+    pub last_update: Timestamp,
+    pub sequence: Option<u64>,
+}
+
+impl TickOrderBook {
+    pub fn new(info: SymbolInfo) -> Self {
+        Self {
+            info,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_update: Timestamp::default(),
+            sequence: None,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.info.symbol
+    }
+
+    pub fn info(&self) -> &SymbolInfo {
+        &self.info
+    }
+
+    /// Tick count of a valid on-grid price
+    pub fn to_ticks(&self, price: f64) -> Result<i64> {
+        let valid = price.is_finite() && price > 0.0 && self.info.is_on_tick(price);
+        if !valid {
+            return Err(MarketDataError::InvalidPrice(price));
+        }
+        Ok(self.info.price_to_ticks(price))
+    }
+
+    pub fn to_price(&self, ticks: i64) -> f64 {
+        self.info.ticks_to_price(ticks)
+    }
+
+    pub fn update_bid(&mut self, price: f64, quantity: f64) -> Result<()> {
+        self.apply(LevelUpdate::bid(price, quantity)).map(|_| ())
+    }
+
+    pub fn update_ask(&mut self, price: f64, quantity: f64) -> Result<()> {
+        self.apply(LevelUpdate::ask(price, quantity)).map(|_| ())
+    }
+
+    /// Apply a batch atomically, as [`OrderBook::apply_updates`] does
+    pub fn apply_updates(
+        &mut self,
+        updates: &[LevelUpdate],
+        timestamp: Timestamp,
+    ) -> Result<BookChange> {
+        for update in updates {
+            self.check(update)?;
+        }
+
+        let top_before = (self.best_bid_ticks(), self.best_ask_ticks());
+        let mut change = BookChange::default();
+        for update in updates {
+            let previous = self.apply(*update)?;
+            let removed = update.quantity == 0.0;
+            match previous {
+                Some(_) if removed => change.removed += 1,
+                None if !removed => change.inserted += 1,
+                Some(previous) if previous != update.quantity => change.modified += 1,
+                _ => {}
+            }
+        }
+
+        change.top_changed = (self.best_bid_ticks(), self.best_ask_ticks()) != top_before;
+        self.last_update = timestamp;
+        if change.top_changed {
+            change.bbo = Some(BboUpdate {
+                best_bid: self.best_bid(),
+                best_ask: self.best_ask(),
+                timestamp,
+            });
+        }
+        Ok(change)
+    }
+
+    /// Tick count of a valid update
+    fn check(&self, update: &LevelUpdate) -> Result<i64> {
+        if !(update.quantity.is_finite() && update.quantity >= 0.0) {
+            return Err(MarketDataError::InvalidQuantity(update.quantity));
+        }
+        self.to_ticks(update.price)
+    }
+
+    /// Quantity previously resting at the level
+    fn apply(&mut self, update: LevelUpdate) -> Result<Option<f64>> {
+        let ticks = self.check(&update)?;
+        let side = match update.side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        };
+        let previous = if update.quantity == 0.0 {
+            side.remove(&ticks)
+        } else {
+            side.insert(ticks, update.quantity)
+        };
+        Ok(previous)
+    }
+
+    pub fn best_bid_ticks(&self) -> Option<(i64, f64)> {
+        self.bids.iter().next_back().map(|(&t, &q)| (t, q))
+    }
+
+    pub fn best_ask_ticks(&self) -> Option<(i64, f64)> {
+        self.asks.iter().next().map(|(&t, &q)| (t, q))
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.best_bid_ticks().map(|(t, q)| (self.to_price(t), q))
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.best_ask_ticks().map(|(t, q)| (self.to_price(t), q))
+    }
+
+    /// Spread as a whole number of ticks
+    pub fn spread_ticks(&self) -> Option<i64> {
+        match (self.best_bid_ticks(), self.best_ask_ticks()) {
+            (Some((bid, _)), Some((ask, _))) => Some(ask - bid),
+            _ => None,
+        }
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.best_bid_ticks(), self.best_ask_ticks()) {
+            (Some((bid, _)), Some((ask, _))) => {
+                Some((self.to_price(bid) + self.to_price(ask)) / 2.0)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn level_count(&self, side: BookSide) -> usize {
+        match side {
+            BookSide::Bid => self.bids.len(),
+            BookSide::Ask => self.asks.len(),
+        }
+    }
+
+    pub fn top_bids(&self, n: usize) -> Vec<PriceLevel> {
+        self.levels(self.bids.iter().rev(), n)
+    }
+
+    pub fn top_asks(&self, n: usize) -> Vec<PriceLevel> {
+        self.levels(self.asks.iter(), n)
+    }
+
+    fn levels<'a>(
+        &self,
+        iter: impl Iterator<Item = (&'a i64, &'a f64)>,
+        n: usize,
+    ) -> Vec<PriceLevel> {
+        iter.take(n)
+            .map(|(&t, &q)| PriceLevel {
+                price: self.to_price(t),
+                quantity: q,
+            })
+            .collect()
+    }
+
+    /// Copy into a float-keyed [`OrderBook`] for the analytics built on it
+    pub fn to_order_book(&self) -> OrderBook {
+        let mut book = OrderBook::new(self.info.symbol.clone());
+        let side = |levels: &BTreeMap<i64, f64>| {
+            levels
+                .iter()
+                .map(|(&t, &q)| (OrderedFloat(self.to_price(t)), q))
+                .collect()
+        };
+        book.bids = side(&self.bids);
+        book.asks = side(&self.asks);
+        book.last_update = self.last_update;
+        book.sequence = self.sequence;
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> TickOrderBook {
+        TickOrderBook::new(SymbolInfo::new("BTCUSD", "BTC", "USD", 0.1, 0.001))
+    }
+
+    #[test]
+    fn test_float_noise_maps_to_one_level() {
+        let mut ob = book();
+        ob.update_bid(0.1 + 0.2, 1.0).unwrap();
+        ob.update_bid(0.3, 2.0).unwrap();
+
+        assert_eq!(ob.level_count(BookSide::Bid), 1);
+        assert_eq!(ob.best_bid(), Some((0.3, 2.0)));
+        assert_eq!(ob.best_bid_ticks(), Some((3, 2.0)));
+    }
+
+    #[test]
+    fn test_rejects_off_grid_and_invalid_input() {
+        let mut ob = book();
+        assert!(matches!(
+            ob.update_ask(100.05, 1.0),
+            Err(MarketDataError::InvalidPrice(_))
+        ));
+        assert!(ob.update_ask(-0.1, 1.0).is_err());
+        assert!(matches!(
+            ob.update_ask(100.1, f64::NAN),
+            Err(MarketDataError::InvalidQuantity(_))
+        ));
+
+        // An invalid entry leaves the batch unapplied
+        let result = ob.apply_updates(
+            &[LevelUpdate::bid(100.0, 1.0), LevelUpdate::ask(100.05, 1.0)],
+            Timestamp::from_millis(1),
+        );
+        assert!(result.is_err());
+        assert_eq!(ob.level_count(BookSide::Bid), 0);
+    }
+
+    #[test]
+    fn test_batch_and_conversion() {
+        let mut ob = book();
+        let change = ob
+            .apply_updates(
+                &[
+                    LevelUpdate::bid(100.0, 1.0),
+                    LevelUpdate::bid(99.9, 2.0),
+                    LevelUpdate::ask(100.3, 1.5),
+                    LevelUpdate::bid(99.9, 0.0),
+                ],
+                Timestamp::from_millis(5),
+            )
+            .unwrap();
+
+        assert_eq!((change.inserted, change.removed), (3, 1));
+        assert!(change.top_changed);
+        assert_eq!(ob.spread_ticks(), Some(3));
+        assert_eq!(ob.top_asks(5)[0].price, 100.3);
+
+        let float_book = ob.to_order_book();
+        assert_eq!(float_book.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(float_book.mid_price(), ob.mid_price());
+        assert_eq!(float_book.last_update, Timestamp::from_millis(5));
+    }
+}
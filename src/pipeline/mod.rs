@@ -0,0 +1,406 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::{MarketDataError, Result};
+
+#[cfg(feature = "streaming")]
+pub mod stream;
+
+/// What a full or busy channel does with a new message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Queue the message; when full, evict the oldest queued message
+    DropOldest,
+    /// Replace a still-queued message with the same key in place, so the consumer only
+    /// sees the latest state; otherwise queue as [`DropOldest`](Self::DropOldest)
+    ConflateLatest,
+}
+
+/// Message routed through a [`channel`], declaring its overflow policy
+///
+/// Depth updates typically conflate by symbol, while trades drop oldest so that a slow
+/// consumer loses stale prints instead of stalling the feed thread.
+pub trait PipelineMessage: Send {
+    type Key: Eq + Hash + Clone + Send;
+
+    fn policy(&self) -> OverflowPolicy;
+
+    /// Identity used for conflation, e.g. the symbol of a book update
+    fn conflation_key(&self) -> Self::Key;
+}
+
+/// How [`Sender::send`] disposed of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    Queued,
+    /// Overwrote a pending message with the same key
+    Conflated,
+    /// Queued after evicting the oldest pending message
+    EvictedOldest,
+}
+
+/// Counters of a channel since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub sent: u64,
+    pub received: u64,
+    pub conflated: u64,
+    pub evicted: u64,
+    pub high_water_mark: usize,
+}
+
+struct Slot<T> {
+    seq: u64,
+    message: T,
+}
+
+struct State<T: PipelineMessage> {
+    queue: VecDeque<Slot<T>>,
+    /// Sequence number of the pending conflatable message for each key
+    pending: HashMap<T::Key, u64>,
+    next_seq: u64,
+    senders: usize,
+    receiver_alive: bool,
+    stats: ChannelStats,
+}
+
+impl<T: PipelineMessage> State<T> {
+    fn pop(&mut self) -> Option<T> {
+        let slot = self.queue.pop_front()?;
+        if slot.message.policy() == OverflowPolicy::ConflateLatest {
+            let key = slot.message.conflation_key();
+            if self.pending.get(&key) == Some(&slot.seq) {
+                self.pending.remove(&key);
+            }
+        }
+        Some(slot.message)
+    }
+}
+
+struct Shared<T: PipelineMessage> {
+    capacity: usize,
+    state: Mutex<State<T>>,
+    ready: Condvar,
+}
+
+impl<T: PipelineMessage> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // A panicking peer cannot leave the queue half-updated, so poisoning is benign
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Bounded, never-blocking channel applying each message's [`OverflowPolicy`]
+///
+/// Panics if `capacity` is zero.
+pub fn channel<T: PipelineMessage>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "channel capacity must be positive");
+
+    let shared = Arc::new(Shared {
+        capacity,
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(capacity),
+            pending: HashMap::new(),
+            next_seq: 0,
+            senders: 1,
+            receiver_alive: true,
+            stats: ChannelStats::default(),
+        }),
+        ready: Condvar::new(),
+    });
+
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Producer side; cheap to clone, and sending never blocks
+pub struct Sender<T: PipelineMessage> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: PipelineMessage> Sender<T> {
+    /// Fails with [`MarketDataError::FeedDisconnected`] once the receiver is dropped
+    pub fn send(&self, message: T) -> Result<Delivery> {
+        let mut state = self.shared.lock();
+        if !state.receiver_alive {
+            return Err(MarketDataError::FeedDisconnected(
+                "pipeline receiver dropped".to_string(),
+            ));
+        }
+        state.stats.sent += 1;
+
+        let conflate = message.policy() == OverflowPolicy::ConflateLatest;
+        let key = conflate.then(|| message.conflation_key());
+        if let Some(&seq) = key.as_ref().and_then(|k| state.pending.get(k)) {
+            let index = (seq - state.queue[0].seq) as usize;
+            state.queue[index].message = message;
+            state.stats.conflated += 1;
+            return Ok(Delivery::Conflated);
+        }
+
+        let mut delivery = Delivery::Queued;
+        if state.queue.len() >= self.shared.capacity {
+            state.pop();
+            state.stats.evicted += 1;
+            delivery = Delivery::EvictedOldest;
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        if let Some(key) = key {
+            state.pending.insert(key, seq);
+        }
+        state.queue.push_back(Slot { seq, message });
+        state.stats.high_water_mark = state.stats.high_water_mark.max(state.queue.len());
+        drop(state);
+
+        self.shared.ready.notify_one();
+        Ok(delivery)
+    }
+}
+
+impl<T: PipelineMessage> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: PipelineMessage> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.lock().senders -= 1;
+        self.shared.ready.notify_all();
+    }
+}
+
+/// Consumer side of a [`channel`]
+pub struct Receiver<T: PipelineMessage> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: PipelineMessage> Receiver<T> {
+    pub fn try_recv(&self) -> Option<T> {
+        let mut state = self.shared.lock();
+        let message = state.pop();
+        if message.is_some() {
+            state.stats.received += 1;
+        }
+        message
+    }
+
+    /// Block until a message arrives; `None` once the queue is empty and all senders are gone
+    pub fn recv(&self) -> Option<T> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(message) = state.pop() {
+                state.stats.received += 1;
+                return Some(message);
+            }
+            if state.senders == 0 {
+                return None;
+            }
+            state = self
+                .shared
+                .ready
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Like [`recv`](Self::recv) but gives up after `timeout`
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(message) = state.pop() {
+                state.stats.received += 1;
+                return Some(message);
+            }
+            let now = Instant::now();
+            if state.senders == 0 || now >= deadline {
+                return None;
+            }
+            state = self
+                .shared
+                .ready
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Messages currently queued
+    pub fn len(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.shared.lock().stats
+    }
+}
+
+impl<T: PipelineMessage> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receiver_alive = false;
+        state.queue.clear();
+        state.pending.clear();
+    }
+}
+
+impl<T: PipelineMessage> Iterator for Receiver<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv()
+    }
+}
+
+/// Run `f` on a dedicated thread over every message of `input`, forwarding its outputs
+/// into a new channel of `capacity`
+///
+/// The stage exits when `input` is closed or the downstream receiver is dropped.
+pub fn stage<T, U, F>(
+    input: Receiver<T>,
+    capacity: usize,
+    mut f: F,
+) -> (Receiver<U>, JoinHandle<()>)
+where
+    T: PipelineMessage + 'static,
+    U: PipelineMessage + 'static,
+    F: FnMut(T) -> Option<U> + Send + 'static,
+{
+    let (tx, rx) = channel(capacity);
+    let handle = thread::spawn(move || {
+        for message in input {
+            if let Some(output) = f(message) {
+                if tx.send(output).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    (rx, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Depth(&'static str, u32),
+        Trade(u32),
+    }
+
+    impl PipelineMessage for Msg {
+        type Key = &'static str;
+
+        fn policy(&self) -> OverflowPolicy {
+            match self {
+                Msg::Depth(..) => OverflowPolicy::ConflateLatest,
+                Msg::Trade(_) => OverflowPolicy::DropOldest,
+            }
+        }
+
+        fn conflation_key(&self) -> &'static str {
+            match self {
+                Msg::Depth(symbol, _) => symbol,
+                Msg::Trade(_) => "",
+            }
+        }
+    }
+
+    #[test]
+    fn test_conflates_depth_per_key_in_place() {
+        let (tx, rx) = channel(8);
+        assert_eq!(tx.send(Msg::Depth("BTC", 1)).unwrap(), Delivery::Queued);
+        tx.send(Msg::Trade(1)).unwrap();
+        tx.send(Msg::Depth("ETH", 1)).unwrap();
+        assert_eq!(tx.send(Msg::Depth("BTC", 2)).unwrap(), Delivery::Conflated);
+        tx.send(Msg::Depth("BTC", 3)).unwrap();
+
+        let received: Vec<Msg> = std::iter::from_fn(|| rx.try_recv()).collect();
+        assert_eq!(
+            received,
+            vec![Msg::Depth("BTC", 3), Msg::Trade(1), Msg::Depth("ETH", 1)]
+        );
+
+        // Once consumed, the next update for the key is queued again
+        assert_eq!(tx.send(Msg::Depth("BTC", 4)).unwrap(), Delivery::Queued);
+        assert_eq!(rx.stats().conflated, 2);
+    }
+
+    #[test]
+    fn test_drop_oldest_when_full() {
+        let (tx, rx) = channel(2);
+        tx.send(Msg::Depth("BTC", 1)).unwrap();
+        tx.send(Msg::Trade(1)).unwrap();
+        assert_eq!(tx.send(Msg::Trade(2)).unwrap(), Delivery::EvictedOldest);
+
+        // The evicted depth update no longer absorbs new ones
+        assert_eq!(
+            tx.send(Msg::Depth("BTC", 2)).unwrap(),
+            Delivery::EvictedOldest
+        );
+        assert_eq!(rx.try_recv(), Some(Msg::Trade(2)));
+        assert_eq!(rx.try_recv(), Some(Msg::Depth("BTC", 2)));
+
+        let stats = rx.stats();
+        assert_eq!(stats.evicted, 2);
+        assert_eq!(stats.high_water_mark, 2);
+    }
+
+    #[test]
+    fn test_disconnect_both_ways() {
+        let (tx, rx) = channel::<Msg>(4);
+        let tx2 = tx.clone();
+        tx.send(Msg::Trade(1)).unwrap();
+        drop(tx);
+        drop(tx2);
+        assert_eq!(rx.recv(), Some(Msg::Trade(1)));
+        assert_eq!(rx.recv(), None);
+
+        let (tx, rx) = channel::<Msg>(4);
+        drop(rx);
+        assert!(matches!(
+            tx.send(Msg::Trade(1)),
+            Err(MarketDataError::FeedDisconnected(_))
+        ));
+    }
+
+    #[test]
+    fn test_stage_runs_on_own_thread() {
+        let (tx, rx) = channel(16);
+        let (out, handle) = stage(rx, 16, |msg| match msg {
+            Msg::Trade(n) => Some(Msg::Trade(n * 10)),
+            Msg::Depth(..) => None,
+        });
+
+        for n in 1..=3 {
+            tx.send(Msg::Trade(n)).unwrap();
+            tx.send(Msg::Depth("BTC", n)).unwrap();
+        }
+        drop(tx);
+
+        let received: Vec<Msg> = out.collect();
+        handle.join().unwrap();
+        assert_eq!(
+            received,
+            vec![Msg::Trade(10), Msg::Trade(20), Msg::Trade(30)]
+        );
+    }
+}
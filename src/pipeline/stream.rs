@@ -0,0 +1,345 @@
+//! Async event pipeline on tokio channels, enabled with the `streaming` feature
+//!
+//! [`Source`]s produce [`MarketEvent`]s into a bounded channel; a dispatcher fans
+//! every event out to each [`Processor`] over its own bounded channel, so a slow
+//! processor applies backpressure all the way to the sources instead of losing data.
+//! Lossy monitoring taps are available through [`StreamPipeline::subscribe`].
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::events::MarketEvent;
+use crate::orderbook::OrderBookManager;
+
+/// Handle given to sources for emitting events
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    events: mpsc::Sender<MarketEvent>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl EventSender {
+    /// Emit an event, waiting while the pipeline is saturated
+    ///
+    /// Fails with [`MarketDataError::FeedDisconnected`] once shutdown was requested;
+    /// sources should return when it does.
+    pub async fn send(&self, event: MarketEvent) -> Result<()> {
+        if self.is_shutdown() {
+            return Err(MarketDataError::FeedDisconnected(
+                "pipeline shut down".to_string(),
+            ));
+        }
+        self.events
+            .send(event)
+            .await
+            .map_err(|_| MarketDataError::FeedDisconnected("pipeline stopped".to_string()))
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// Resolve once shutdown is requested, for sources waiting on other I/O
+    pub async fn shutdown_requested(&mut self) {
+        // An error means the handle was dropped, which also ends the pipeline
+        let _ = self.shutdown.wait_for(|&stop| stop).await;
+    }
+}
+
+/// Producer of market events, e.g. a feed adapter or a file replay
+#[async_trait]
+pub trait Source: Send + 'static {
+    /// Emit events until exhausted or until [`EventSender::send`] fails
+    async fn run(self: Box<Self>, out: EventSender) -> Result<()>;
+}
+
+/// Source replaying an in-memory sequence of events as fast as possible
+pub struct IterSource<I>(pub I);
+
+#[async_trait]
+impl<I> Source for IterSource<I>
+where
+    I: Iterator<Item = MarketEvent> + Send + 'static,
+{
+    async fn run(self: Box<Self>, out: EventSender) -> Result<()> {
+        for event in self.0 {
+            if out.send(event).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Consumer of every event, run on its own task
+pub trait Processor: Send + 'static {
+    fn process(&mut self, event: &MarketEvent) -> Result<()>;
+}
+
+impl<F> Processor for F
+where
+    F: FnMut(&MarketEvent) -> Result<()> + Send + 'static,
+{
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        self(event)
+    }
+}
+
+/// Shared processor, so the caller can inspect its state while the pipeline runs
+impl<P: Processor> Processor for Arc<Mutex<P>> {
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        self.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .process(event)
+    }
+}
+
+impl Processor for OrderBookManager {
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        self.apply_event(event).map(|_| ())
+    }
+}
+
+/// Outcome of a finished pipeline
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Events dispatched to the processors
+    pub events: u64,
+    /// Errors returned by each processor, in registration order
+    pub processor_errors: Vec<u64>,
+    /// Messages of sources that ended with an error
+    pub source_errors: Vec<String>,
+}
+
+/// Builder wiring sources to processors
+pub struct StreamPipeline {
+    capacity: usize,
+    sources: Vec<Box<dyn Source>>,
+    processors: Vec<Box<dyn Processor>>,
+    taps: broadcast::Sender<MarketEvent>,
+}
+
+impl StreamPipeline {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(capacity: usize) -> Self {
+        Self::try_new(capacity).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// `capacity` bounds every channel: the source queue, each processor queue and
+    /// the lag a tap tolerates before skipping events
+    pub fn try_new(capacity: usize) -> Result<Self> {
+        ensure(capacity > 0, "capacity must be positive")?;
+        Ok(Self {
+            capacity,
+            sources: Vec::new(),
+            processors: Vec::new(),
+            taps: broadcast::channel(capacity).0,
+        })
+    }
+
+    pub fn with_source(mut self, source: impl Source) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    pub fn with_processor(mut self, processor: impl Processor) -> Self {
+        self.processors.push(Box::new(processor));
+        self
+    }
+
+    /// Lossy copy of the event stream; a lagging tap skips events instead of slowing
+    /// down the pipeline
+    pub fn subscribe(&self) -> broadcast::Receiver<MarketEvent> {
+        self.taps.subscribe()
+    }
+
+    /// Start every task on the current tokio runtime
+    pub fn spawn(self) -> PipelineHandle {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (events_tx, mut events_rx) = mpsc::channel(self.capacity);
+
+        let sources: Vec<JoinHandle<Result<()>>> = self
+            .sources
+            .into_iter()
+            .map(|source| {
+                let out = EventSender {
+                    events: events_tx.clone(),
+                    shutdown: shutdown_rx.clone(),
+                };
+                tokio::spawn(source.run(out))
+            })
+            .collect();
+        drop(events_tx);
+
+        let (inputs, processors): (Vec<_>, Vec<_>) = self
+            .processors
+            .into_iter()
+            .map(|mut processor| {
+                let (tx, mut rx) = mpsc::channel::<Arc<MarketEvent>>(self.capacity);
+                let task = tokio::spawn(async move {
+                    let mut errors = 0;
+                    while let Some(event) = rx.recv().await {
+                        if processor.process(&event).is_err() {
+                            errors += 1;
+                        }
+                    }
+                    errors
+                });
+                (tx, task)
+            })
+            .unzip();
+
+        let taps = self.taps;
+        let task = tokio::spawn(async move {
+            let mut events = 0;
+            while let Some(event) = events_rx.recv().await {
+                // Without subscribers the tap send fails, which is fine
+                let _ = taps.send(event.clone());
+                let event = Arc::new(event);
+                for input in &inputs {
+                    // A processor task only ends early by panicking; keep the others fed
+                    let _ = input.send(Arc::clone(&event)).await;
+                }
+                events += 1;
+            }
+            drop(inputs);
+
+            let mut report = PipelineReport {
+                events,
+                ..PipelineReport::default()
+            };
+            for source in sources {
+                match source.await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => report.source_errors.push(e.to_string()),
+                    Err(e) => report.source_errors.push(e.to_string()),
+                }
+            }
+            for processor in processors {
+                report.processor_errors.push(processor.await.unwrap_or(0));
+            }
+            report
+        });
+
+        PipelineHandle {
+            shutdown: shutdown_tx,
+            task,
+        }
+    }
+}
+
+/// Running pipeline
+pub struct PipelineHandle {
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<PipelineReport>,
+}
+
+impl PipelineHandle {
+    /// Ask sources to stop; events already emitted still reach every processor
+    pub fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+
+    /// Wait until every source has finished and every processor has drained
+    pub async fn join(self) -> Result<PipelineReport> {
+        self.task
+            .await
+            .map_err(|e| MarketDataError::FeedDisconnected(format!("pipeline task failed: {e}")))
+    }
+
+    /// Graceful shutdown: [`shutdown`](Self::shutdown) then [`join`](Self::join)
+    pub async fn stop(self) -> Result<PipelineReport> {
+        self.shutdown();
+        self.join().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::MarketData;
+    use crate::orderbook::LevelUpdate;
+    use crate::time::Timestamp;
+
+    fn delta(symbol: &str, price: f64, millis: i64) -> MarketEvent {
+        MarketEvent::new(
+            "test",
+            symbol,
+            Timestamp::from_millis(millis),
+            MarketData::BookDelta {
+                updates: vec![LevelUpdate::bid(price, 1.0)],
+                first_sequence: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sources_feed_every_processor() {
+        let books = Arc::new(Mutex::new(OrderBookManager::new()));
+        let mut seen = Vec::new();
+        let (count_tx, mut count_rx) = mpsc::unbounded_channel();
+
+        let pipeline = StreamPipeline::new(2)
+            .with_source(IterSource((1..=50).map(|i| delta("BTC", i as f64, i))))
+            .with_source(IterSource((1..=50).map(|i| delta("ETH", i as f64, i))))
+            .with_processor(Arc::clone(&books))
+            .with_processor(move |event: &MarketEvent| {
+                count_tx.send(event.symbol.clone()).unwrap();
+                Ok(())
+            });
+        let report = pipeline.spawn().join().await.unwrap();
+
+        assert_eq!(report.events, 100);
+        assert_eq!(report.processor_errors, vec![0, 0]);
+        while let Ok(symbol) = count_rx.try_recv() {
+            seen.push(symbol);
+        }
+        assert_eq!(seen.len(), 100);
+
+        let books = books.lock().unwrap();
+        assert_eq!(books.book("BTC").unwrap().best_bid(), Some((50.0, 1.0)));
+        assert_eq!(books.stats("ETH").unwrap().updates, 50);
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_stops_endless_source() {
+        let processed = Arc::new(Mutex::new(0u64));
+        let counter = Arc::clone(&processed);
+
+        let pipeline = StreamPipeline::new(8)
+            .with_source(IterSource((0..).map(|i| delta("BTC", 1.0 + i as f64, i))))
+            .with_processor(move |_: &MarketEvent| {
+                *counter.lock().unwrap() += 1;
+                Ok(())
+            });
+        let mut tap = pipeline.subscribe();
+        let handle = pipeline.spawn();
+
+        // The tap may lag behind the endless source, but it does observe it
+        match tap.recv().await {
+            Ok(event) => assert_eq!(event.symbol, "BTC"),
+            Err(e) => assert!(matches!(e, broadcast::error::RecvError::Lagged(_))),
+        }
+        let report = handle.stop().await.unwrap();
+
+        assert!(report.events > 0);
+        assert_eq!(*processed.lock().unwrap(), report.events);
+        assert!(report.source_errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_processor_errors_are_counted() {
+        let pipeline = StreamPipeline::new(4)
+            .with_source(IterSource((1..=3).map(|i| delta("BTC", i as f64, i))))
+            .with_processor(|event: &MarketEvent| {
+                ensure(event.exchange_time.as_millis() != 2, "rejected")
+            });
+        let report = pipeline.spawn().join().await.unwrap();
+        assert_eq!(report.processor_errors, vec![1]);
+        assert!(StreamPipeline::try_new(0).is_err());
+    }
+}
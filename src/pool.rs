@@ -0,0 +1,207 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use crossbeam::queue::ArrayQueue;
+
+/// Objects that can be cleared for reuse without giving up their allocation
+pub trait Recycle {
+    fn recycle(&mut self);
+}
+
+impl<T> Recycle for Vec<T> {
+    fn recycle(&mut self) {
+        self.clear();
+    }
+}
+
+impl Recycle for String {
+    fn recycle(&mut self) {
+        self.clear();
+    }
+}
+
+/// Single-threaded free list of reusable objects
+///
+/// `acquire` hands out a recycled object when one is available and only falls back to
+/// the factory when the pool is empty; `release` keeps at most `capacity` objects.
+pub struct Pool<T> {
+    free: Vec<T>,
+    capacity: usize,
+    factory: fn() -> T,
+    hits: u64,
+    misses: u64,
+}
+
+impl<T: Recycle> Pool<T> {
+    pub fn new(capacity: usize, factory: fn() -> T) -> Self {
+        Self {
+            free: Vec::with_capacity(capacity),
+            capacity,
+            factory,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Pool pre-filled with `capacity` objects, so the hot path never allocates
+    pub fn prefilled(capacity: usize, factory: fn() -> T) -> Self {
+        let mut pool = Self::new(capacity, factory);
+        pool.free.extend((0..capacity).map(|_| factory()));
+        pool
+    }
+
+    pub fn acquire(&mut self) -> T {
+        match self.free.pop() {
+            Some(item) => {
+                self.hits += 1;
+                item
+            }
+            None => {
+                self.misses += 1;
+                (self.factory)()
+            }
+        }
+    }
+
+    pub fn release(&mut self, mut item: T) {
+        if self.free.len() < self.capacity {
+            item.recycle();
+            self.free.push(item);
+        }
+    }
+
+    /// Objects currently available
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Acquisitions served from the pool and from the factory
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+}
+
+/// Lock-free pool shared across threads; objects return to it when the guard drops
+pub struct SharedPool<T> {
+    free: Arc<ArrayQueue<T>>,
+    factory: fn() -> T,
+}
+
+impl<T> Clone for SharedPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            free: Arc::clone(&self.free),
+            factory: self.factory,
+        }
+    }
+}
+
+impl<T: Recycle> SharedPool<T> {
+    pub fn new(capacity: usize, factory: fn() -> T) -> Self {
+        Self {
+            free: Arc::new(ArrayQueue::new(capacity.max(1))),
+            factory,
+        }
+    }
+
+    pub fn acquire(&self) -> Pooled<T> {
+        let item = self.free.pop().unwrap_or_else(self.factory);
+        Pooled {
+            item: Some(item),
+            free: Arc::clone(&self.free),
+        }
+    }
+
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+}
+
+/// Object borrowed from a [`SharedPool`]
+pub struct Pooled<T: Recycle> {
+    item: Option<T>,
+    free: Arc<ArrayQueue<T>>,
+}
+
+impl<T: Recycle> Pooled<T> {
+    /// Keep the object instead of returning it to the pool
+    pub fn detach(mut self) -> T {
+        self.item.take().expect("present until dropped")
+    }
+}
+
+impl<T: Recycle> Deref for Pooled<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().expect("present until dropped")
+    }
+}
+
+impl<T: Recycle> DerefMut for Pooled<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().expect("present until dropped")
+    }
+}
+
+impl<T: Recycle> Drop for Pooled<T> {
+    fn drop(&mut self) {
+        if let Some(mut item) = self.item.take() {
+            item.recycle();
+            // A full pool simply drops the object
+            let _ = self.free.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_reuses_allocations() {
+        let mut pool: Pool<Vec<u64>> = Pool::new(2, || Vec::with_capacity(64));
+
+        let mut buffer = pool.acquire();
+        buffer.extend([1, 2, 3]);
+        let ptr = buffer.as_ptr();
+        pool.release(buffer);
+
+        let buffer = pool.acquire();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(pool.stats(), (1, 1));
+    }
+
+    #[test]
+    fn test_pool_capacity_is_bounded() {
+        let mut pool: Pool<String> = Pool::prefilled(1, String::new);
+        assert_eq!(pool.available(), 1);
+
+        let (a, b) = (pool.acquire(), pool.acquire());
+        pool.release(a);
+        pool.release(b);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_shared_pool_returns_on_drop() {
+        let pool: SharedPool<Vec<u8>> = SharedPool::new(4, Vec::new);
+
+        let handle = {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                let mut buf = pool.acquire();
+                buf.push(1);
+            })
+        };
+        handle.join().unwrap();
+        assert_eq!(pool.available(), 1);
+
+        let buf = pool.acquire();
+        assert!(buf.is_empty());
+        let kept = buf.detach();
+        assert_eq!(pool.available(), 0);
+        drop(kept);
+    }
+}
@@ -0,0 +1,624 @@
+//! FIX 4.4 / 5.0 market data messages
+//!
+//! Decodes MarketDataSnapshotFullRefresh (`35=W`) and MarketDataIncrementalRefresh
+//! (`35=X`) into [`MarketEvent`]s that [`OrderBookManager::apply_event`] applies
+//! directly. Book entries (`269=0` bid, `269=1` offer) become
+//! [`MarketData::BookSnapshot`] and [`MarketData::BookDelta`]; trade entries
+//! (`269=2`) of incremental refreshes become [`MarketData::Trade`]. Other entry
+//! types, e.g. opening or settlement prices, are skipped.
+//!
+//! Per-instrument `RptSeq` (83) is used as the event sequence, so gaps are detected
+//! by the book; the session-level `MsgSeqNum` is left to the FIX engine. Framing is
+//! checked against `BodyLength` (9) and `CheckSum` (10).
+//!
+//! [`OrderBookManager::apply_event`]: crate::orderbook::OrderBookManager::apply_event
+
+use std::collections::HashMap;
+use std::str;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::orderbook::{BookSide, LevelUpdate, PriceLevel};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Standard field delimiter
+pub const SOH: u8 = 0x01;
+
+pub const BEGIN_STRING: u32 = 8;
+pub const BODY_LENGTH: u32 = 9;
+pub const CHECKSUM: u32 = 10;
+pub const MSG_SEQ_NUM: u32 = 34;
+pub const MSG_TYPE: u32 = 35;
+pub const SENDING_TIME: u32 = 52;
+pub const SIDE: u32 = 54;
+pub const SYMBOL: u32 = 55;
+pub const TRANSACT_TIME: u32 = 60;
+pub const RPT_SEQ: u32 = 83;
+pub const NO_MD_ENTRIES: u32 = 268;
+pub const MD_ENTRY_TYPE: u32 = 269;
+pub const MD_ENTRY_PX: u32 = 270;
+pub const MD_ENTRY_SIZE: u32 = 271;
+pub const MD_ENTRY_DATE: u32 = 272;
+pub const MD_ENTRY_TIME: u32 = 273;
+pub const MD_UPDATE_ACTION: u32 = 279;
+pub const AGGRESSOR_SIDE: u32 = 2446;
+
+pub const SNAPSHOT_FULL_REFRESH: &str = "W";
+pub const INCREMENTAL_REFRESH: &str = "X";
+
+/// Largest accepted BodyLength, so a corrupt header cannot stall a stream forever
+pub const MAX_BODY_LENGTH: usize = 1 << 20;
+
+fn malformed(message: impl Into<String>) -> MarketDataError {
+    MarketDataError::Malformed(message.into())
+}
+
+/// Parsed message borrowing its field values from the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage<'a> {
+    fields: Vec<(u32, &'a str)>,
+}
+
+impl<'a> FixMessage<'a> {
+    /// Decode the message at the start of `buf`, returning it with its encoded length
+    ///
+    /// Returns `Ok(None)` when `buf` holds only part of a message. Fails on a bad
+    /// header, a body length above [`MAX_BODY_LENGTH`] or not ending at the `CheckSum`
+    /// field, or a wrong checksum.
+    pub fn decode(buf: &'a [u8], delimiter: u8) -> Result<Option<(FixMessage<'a>, usize)>> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        let mut body_start = 0;
+        let mut body_end = None;
+
+        while offset < buf.len() {
+            let Some(len) = buf[offset..].iter().position(|&b| b == delimiter) else {
+                return Ok(None);
+            };
+            let (tag, value) = parse_field(&buf[offset..offset + len])?;
+            let position = fields.len();
+            let expected = [BEGIN_STRING, BODY_LENGTH, MSG_TYPE];
+            if position < expected.len() && tag != expected[position] {
+                return Err(malformed(format!(
+                    "expected tag {} at field {position}, got {tag}",
+                    expected[position]
+                )));
+            }
+            if tag == BODY_LENGTH {
+                body_start = offset + len + 1;
+                let body_len: usize = value
+                    .parse()
+                    .ok()
+                    .filter(|&len| len <= MAX_BODY_LENGTH)
+                    .ok_or_else(|| malformed(format!("invalid body length {value:?}")))?;
+                body_end = Some(
+                    body_start
+                        .checked_add(body_len)
+                        .ok_or_else(|| malformed("body length overflows"))?,
+                );
+            }
+            if tag == CHECKSUM {
+                if Some(offset) != body_end {
+                    return Err(malformed(format!(
+                        "body length {} does not match body of {} bytes",
+                        body_end.unwrap_or(0) - body_start,
+                        offset - body_start
+                    )));
+                }
+                let expected = buf[..offset]
+                    .iter()
+                    .fold(0u8, |sum, &b| sum.wrapping_add(b));
+                let received: u8 = value
+                    .parse()
+                    .map_err(|_| malformed(format!("invalid checksum {value:?}")))?;
+                if expected != received {
+                    return Err(MarketDataError::ChecksumMismatch {
+                        expected: expected as u32,
+                        actual: received as u32,
+                    });
+                }
+                fields.push((tag, value));
+                return Ok(Some((FixMessage { fields }, offset + len + 1)));
+            }
+            if body_end.is_some_and(|end| offset >= end) {
+                return Err(malformed("body length ends before the checksum"));
+            }
+            fields.push((tag, value));
+            offset += len + 1;
+        }
+        Ok(None)
+    }
+
+    /// Decode a complete message delimited by [`SOH`], failing on trailing bytes
+    pub fn parse(buf: &'a [u8]) -> Result<FixMessage<'a>> {
+        match FixMessage::decode(buf, SOH)? {
+            Some((message, len)) if len == buf.len() => Ok(message),
+            Some(_) => Err(malformed("trailing bytes after checksum")),
+            None => Err(malformed("incomplete message")),
+        }
+    }
+
+    /// All fields in wire order, including the header and trailer
+    pub fn fields(&self) -> &[(u32, &'a str)] {
+        &self.fields
+    }
+
+    /// Value of the first occurrence of `tag`
+    pub fn get(&self, tag: u32) -> Option<&'a str> {
+        self.fields
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|&(_, value)| value)
+    }
+
+    pub fn begin_string(&self) -> &'a str {
+        self.fields[0].1
+    }
+
+    pub fn msg_type(&self) -> &'a str {
+        self.fields[2].1
+    }
+
+    /// Fields before the first repeating group
+    fn body(&self) -> &[(u32, &'a str)] {
+        let end = self
+            .fields
+            .iter()
+            .position(|&(tag, _)| tag == NO_MD_ENTRIES)
+            .unwrap_or(self.fields.len());
+        &self.fields[..end]
+    }
+
+    /// Entries of the `NoMDEntries` group, split on its first field
+    fn entries(&self) -> Result<Vec<&[(u32, &'a str)]>> {
+        let Some(start) = self
+            .fields
+            .iter()
+            .position(|&(tag, _)| tag == NO_MD_ENTRIES)
+        else {
+            return Ok(Vec::new());
+        };
+        let count: usize = parse_value(self.fields[start].1, "entry count")?;
+        let group = &self.fields[start + 1..self.fields.len() - 1];
+        if count > group.len() {
+            return Err(malformed(format!(
+                "{count} entries announced, {} fields sent",
+                group.len()
+            )));
+        }
+        let Some(&(delimiter, _)) = group.first() else {
+            return if count == 0 {
+                Ok(Vec::new())
+            } else {
+                Err(malformed(format!("{count} entries announced, none sent")))
+            };
+        };
+
+        let mut entries = Vec::new();
+        let mut begin = 0;
+        for (i, &(tag, _)) in group.iter().enumerate().skip(1) {
+            if tag == delimiter {
+                entries.push(&group[begin..i]);
+                begin = i;
+            }
+        }
+        entries.push(&group[begin..]);
+        if entries.len() != count {
+            return Err(malformed(format!(
+                "{count} entries announced, {} sent",
+                entries.len()
+            )));
+        }
+        Ok(entries)
+    }
+}
+
+fn parse_field(field: &[u8]) -> Result<(u32, &str)> {
+    let field = str::from_utf8(field).map_err(|e| malformed(format!("field not UTF-8: {e}")))?;
+    let (tag, value) = field
+        .split_once('=')
+        .ok_or_else(|| malformed(format!("field without '=': {field:?}")))?;
+    let tag = tag
+        .parse()
+        .map_err(|_| malformed(format!("invalid tag {tag:?}")))?;
+    Ok((tag, value))
+}
+
+fn parse_value<T: str::FromStr>(value: &str, name: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| malformed(format!("invalid {name} {value:?}")))
+}
+
+fn find<'a>(fields: &[(u32, &'a str)], tag: u32) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(t, _)| *t == tag)
+        .map(|&(_, value)| value)
+}
+
+/// UTCTimestamp `YYYYMMDD-HH:MM:SS[.sss…]`
+pub fn parse_utc_timestamp(value: &str) -> Result<Timestamp> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%d-%H:%M:%S%.f")
+        .map(|datetime| Timestamp::from(datetime.and_utc()))
+        .map_err(|_| malformed(format!("invalid UTC timestamp {value:?}")))
+}
+
+/// `MDEntryDate` and `MDEntryTime` of an entry, if both are present
+fn entry_time(entry: &[(u32, &str)]) -> Result<Option<Timestamp>> {
+    let (Some(date), Some(time)) = (find(entry, MD_ENTRY_DATE), find(entry, MD_ENTRY_TIME)) else {
+        return Ok(None);
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y%m%d")
+        .map_err(|_| malformed(format!("invalid entry date {date:?}")))?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+        .map_err(|_| malformed(format!("invalid entry time {time:?}")))?;
+    Ok(Some(Timestamp::from(date.and_time(time).and_utc())))
+}
+
+/// Book side of a bid or offer entry, `None` for other entry types
+fn book_side(entry: &[(u32, &str)]) -> Result<Option<BookSide>> {
+    match find(entry, MD_ENTRY_TYPE) {
+        Some("0") => Ok(Some(BookSide::Bid)),
+        Some("1") => Ok(Some(BookSide::Ask)),
+        Some(_) => Ok(None),
+        None => Err(malformed("entry without MDEntryType")),
+    }
+}
+
+fn entry_price(entry: &[(u32, &str)]) -> Result<f64> {
+    let price = find(entry, MD_ENTRY_PX).ok_or_else(|| malformed("entry without MDEntryPx"))?;
+    parse_value(price, "price")
+}
+
+fn entry_size(entry: &[(u32, &str)]) -> Result<f64> {
+    find(entry, MD_ENTRY_SIZE).map_or(Ok(0.0), |size| parse_value(size, "size"))
+}
+
+/// Decoder of market data messages from one venue
+#[derive(Debug, Clone)]
+pub struct FixDecoder {
+    exchange: String,
+    delimiter: u8,
+}
+
+impl FixDecoder {
+    /// `exchange` names the venue on the produced events
+    pub fn new(exchange: &str) -> Self {
+        Self {
+            exchange: exchange.to_string(),
+            delimiter: SOH,
+        }
+    }
+
+    /// Field delimiter other than [`SOH`], e.g. `b'|'` for logged messages
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Decode the message at the start of `buf`, returning its events and length
+    ///
+    /// Returns `Ok(None)` when `buf` holds only part of a message. Messages other
+    /// than `35=W` and `35=X`, e.g. session heartbeats, decode to no events.
+    pub fn decode(
+        &self,
+        buf: &[u8],
+        received: Timestamp,
+    ) -> Result<Option<(Vec<MarketEvent>, usize)>> {
+        let Some((message, len)) = FixMessage::decode(buf, self.delimiter)? else {
+            return Ok(None);
+        };
+        Ok(Some((self.events(&message, received)?, len)))
+    }
+
+    /// Events of a parsed message
+    pub fn events(
+        &self,
+        message: &FixMessage<'_>,
+        received: Timestamp,
+    ) -> Result<Vec<MarketEvent>> {
+        let body = message.body();
+        let time = match find(body, TRANSACT_TIME).or_else(|| find(body, SENDING_TIME)) {
+            Some(time) => parse_utc_timestamp(time)?,
+            None => received,
+        };
+        match message.msg_type() {
+            SNAPSHOT_FULL_REFRESH => self
+                .snapshot(message, time, received)
+                .map(|event| vec![event]),
+            INCREMENTAL_REFRESH => self.incremental(message, time, received),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn snapshot(
+        &self,
+        message: &FixMessage<'_>,
+        time: Timestamp,
+        received: Timestamp,
+    ) -> Result<MarketEvent> {
+        let body = message.body();
+        let symbol = find(body, SYMBOL).ok_or_else(|| malformed("snapshot without Symbol"))?;
+
+        // Order-level depth repeats prices; aggregate it into levels
+        let mut bids: Vec<PriceLevel> = Vec::new();
+        let mut asks: Vec<PriceLevel> = Vec::new();
+        let mut sequence = find(body, RPT_SEQ);
+        for entry in message.entries()? {
+            sequence = sequence.or_else(|| find(entry, RPT_SEQ));
+            let levels = match book_side(entry)? {
+                Some(BookSide::Bid) => &mut bids,
+                Some(BookSide::Ask) => &mut asks,
+                None => continue,
+            };
+            let price = entry_price(entry)?;
+            let quantity = entry_size(entry)?;
+            match levels.iter_mut().find(|level| level.price == price) {
+                Some(level) => level.quantity += quantity,
+                None => levels.push(PriceLevel { price, quantity }),
+            }
+        }
+        bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+
+        let event = MarketEvent::new(
+            &self.exchange,
+            symbol,
+            time,
+            MarketData::BookSnapshot { bids, asks },
+        )
+        .with_received(received);
+        Ok(match sequence {
+            Some(sequence) => event.with_sequence(parse_value(sequence, "RptSeq")?),
+            None => event,
+        })
+    }
+
+    /// One delta per symbol, at the position of its first book entry, and one event
+    /// per trade; entries without a symbol belong to the previous entry's instrument
+    fn incremental(
+        &self,
+        message: &FixMessage<'_>,
+        time: Timestamp,
+        received: Timestamp,
+    ) -> Result<Vec<MarketEvent>> {
+        let mut events: Vec<MarketEvent> = Vec::new();
+        let mut deltas: HashMap<&str, usize> = HashMap::new();
+        let mut symbol = find(message.body(), SYMBOL);
+
+        for entry in message.entries()? {
+            symbol = find(entry, SYMBOL).or(symbol);
+            let symbol = symbol.ok_or_else(|| malformed("entry without Symbol"))?;
+            let sequence = find(entry, RPT_SEQ)
+                .map(|sequence| parse_value::<u64>(sequence, "RptSeq"))
+                .transpose()?;
+
+            if find(entry, MD_ENTRY_TYPE) == Some("2") {
+                let time = entry_time(entry)?.unwrap_or(time);
+                let side = match find(entry, AGGRESSOR_SIDE).or_else(|| find(entry, SIDE)) {
+                    Some("2") => Side::Sell,
+                    _ => Side::Buy,
+                };
+                let trade = Trade::new(entry_price(entry)?, entry_size(entry)?, side, time);
+                let event =
+                    MarketEvent::new(&self.exchange, symbol, time, MarketData::Trade(trade))
+                        .with_received(received);
+                events.push(match sequence {
+                    Some(sequence) => event.with_sequence(sequence),
+                    None => event,
+                });
+                continue;
+            }
+
+            let Some(side) = book_side(entry)? else {
+                continue;
+            };
+            let price = entry_price(entry)?;
+            let quantity = match find(entry, MD_UPDATE_ACTION) {
+                Some("0") | Some("1") | None => entry_size(entry)?,
+                Some("2") => 0.0,
+                Some(action) => {
+                    return Err(malformed(format!("unknown MDUpdateAction {action:?}")))
+                }
+            };
+            let update = LevelUpdate {
+                side,
+                price,
+                quantity,
+            };
+
+            let index = *deltas.entry(symbol).or_insert_with(|| {
+                events.push(
+                    MarketEvent::new(
+                        &self.exchange,
+                        symbol,
+                        time,
+                        MarketData::BookDelta {
+                            updates: Vec::new(),
+                            first_sequence: None,
+                        },
+                    )
+                    .with_received(received),
+                );
+                events.len() - 1
+            });
+            let event = &mut events[index];
+            if let MarketData::BookDelta {
+                updates,
+                first_sequence,
+            } = &mut event.data
+            {
+                updates.push(update);
+                if let Some(sequence) = sequence {
+                    *first_sequence =
+                        Some(first_sequence.map_or(sequence, |first| first.min(sequence)));
+                    event.sequence =
+                        Some(event.sequence.map_or(sequence, |last| last.max(sequence)));
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::OrderBookManager;
+
+    /// Frame `body` (fields after MsgType, `|`-delimited) with header and trailer
+    fn message(msg_type: &str, body: &str) -> Vec<u8> {
+        framed(msg_type, body, b'|')
+    }
+
+    /// As [`message`], with `|` in `body` replaced by `delimiter`
+    fn framed(msg_type: &str, body: &str, delimiter: u8) -> Vec<u8> {
+        let body = format!("35={msg_type}|{body}");
+        let mut text = format!("8=FIX.4.4|9={}|{body}", body.len()).into_bytes();
+        for b in text.iter_mut().filter(|b| **b == b'|') {
+            *b = delimiter;
+        }
+        let checksum = text.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        text.extend_from_slice(format!("10={checksum:03}").as_bytes());
+        text.push(delimiter);
+        text
+    }
+
+    fn decoder() -> FixDecoder {
+        FixDecoder::new("fix").with_delimiter(b'|')
+    }
+
+    #[test]
+    fn test_decode_framing() {
+        let msg = message("0", "34=2|52=20240102-03:04:05.678|");
+        let (parsed, len) = FixMessage::decode(&msg, b'|').unwrap().unwrap();
+        assert_eq!(len, msg.len());
+        assert_eq!(parsed.begin_string(), "FIX.4.4");
+        assert_eq!(parsed.msg_type(), "0");
+        assert_eq!(parsed.get(MSG_SEQ_NUM), Some("2"));
+        assert!(FixMessage::decode(&msg[..msg.len() - 2], b'|')
+            .unwrap()
+            .is_none());
+
+        let with_soh = framed("0", "34=2|", SOH);
+        assert_eq!(
+            FixMessage::parse(&with_soh).unwrap().get(MSG_SEQ_NUM),
+            Some("2")
+        );
+        assert!(FixMessage::parse(&[with_soh.as_slice(), b"8"].concat()).is_err());
+
+        let mut corrupted = msg.clone();
+        corrupted[20] = b'9';
+        assert!(matches!(
+            FixMessage::decode(&corrupted, b'|'),
+            Err(MarketDataError::ChecksumMismatch { .. })
+        ));
+        let wrong_length = String::from_utf8(msg).unwrap().replacen("9=", "9=1", 1);
+        assert!(FixMessage::decode(wrong_length.as_bytes(), b'|').is_err());
+    }
+
+    #[test]
+    fn test_snapshot_drives_book() {
+        let msg = message(
+            "W",
+            "52=20240102-03:04:05.678|55=BTC-USD|83=10|268=4|269=0|270=100|271=1|\
+             269=0|270=100|271=2|269=1|270=101|271=4|269=2|270=100.5|271=1|",
+        );
+        let (events, _) = decoder()
+            .decode(&msg, Timestamp::from_millis(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.symbol, "BTC-USD");
+        assert_eq!(event.sequence, Some(10));
+        assert_eq!(
+            event.exchange_time,
+            parse_utc_timestamp("20240102-03:04:05.678").unwrap()
+        );
+
+        let mut books = OrderBookManager::new();
+        books.apply_event(event).unwrap();
+        let book = books.book("BTC-USD").unwrap();
+        assert_eq!(book.best_bid(), Some((100.0, 3.0)));
+        assert_eq!(book.best_ask(), Some((101.0, 4.0)));
+    }
+
+    #[test]
+    fn test_incremental_refresh() {
+        let snapshot = message(
+            "W",
+            "55=BTC-USD|83=10|268=2|269=0|270=100|271=1|269=1|270=101|271=1|",
+        );
+        let incremental = message(
+            "X",
+            "60=20240102-03:04:06|268=4|279=0|269=0|55=BTC-USD|83=11|270=100.5|271=2|\
+             279=2|269=1|83=12|270=101|279=0|269=2|55=ETH-USD|83=3|270=10|271=5|2446=2|\
+             279=0|269=1|55=BTC-USD|83=13|270=102|271=3|",
+        );
+        let decoder = decoder();
+        let received = Timestamp::from_millis(1);
+        let (snapshot, _) = decoder.decode(&snapshot, received).unwrap().unwrap();
+        let (events, _) = decoder.decode(&incremental, received).unwrap().unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].sequence, Some(13));
+        assert!(matches!(
+            &events[0].data,
+            MarketData::BookDelta { updates, first_sequence: Some(11) } if updates.len() == 3
+        ));
+        assert_eq!(events[1].symbol, "ETH-USD");
+        assert_eq!(
+            events[1].data,
+            MarketData::Trade(Trade::new(10.0, 5.0, Side::Sell, events[1].exchange_time))
+        );
+
+        let mut books = OrderBookManager::new();
+        books.apply_event(&snapshot[0]).unwrap();
+        for event in &events {
+            books.apply_event(event).unwrap();
+        }
+        let book = books.book("BTC-USD").unwrap();
+        assert_eq!(book.best_bid(), Some((100.5, 2.0)));
+        assert_eq!(book.best_ask(), Some((102.0, 3.0)));
+        assert_eq!(book.sequence, Some(13));
+        assert!(!books.contains("ETH-USD"));
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        let decoder = decoder();
+        let received = Timestamp::from_millis(1);
+        let wrong_count = message("W", "55=BTC|268=2|269=0|270=100|271=1|");
+        assert!(decoder.decode(&wrong_count, received).is_err());
+        let no_symbol = message("X", "268=1|279=0|269=0|270=100|271=1|");
+        assert!(decoder.decode(&no_symbol, received).is_err());
+        let heartbeat = message("0", "");
+        assert_eq!(
+            decoder.decode(&heartbeat, received).unwrap().unwrap().0,
+            vec![]
+        );
+        assert!(FixMessage::decode(b"9=5|8=FIX.4.4|", b'|').is_err());
+    }
+
+    #[test]
+    fn test_untrusted_lengths_are_rejected() {
+        for length in ["18446744073709551615", "99999999999999999999", "1048577"] {
+            let header = format!("8=FIX.4.4|9={length}|35=0|");
+            assert!(matches!(
+                FixMessage::decode(header.as_bytes(), b'|'),
+                Err(MarketDataError::Malformed(_))
+            ));
+        }
+
+        let huge_count = message("W", "55=BTC|268=100000000000000000|269=0|270=100|271=1|");
+        assert!(matches!(
+            decoder().decode(&huge_count, Timestamp::from_millis(1)),
+            Err(MarketDataError::Malformed(_))
+        ));
+    }
+}
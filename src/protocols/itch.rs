@@ -0,0 +1,591 @@
+//! Nasdaq TotalView-ITCH 5.0 order messages
+//!
+//! Messages are big-endian and share an 11 byte header: type `u8`, stock locate
+//! `u16`, tracking number `u16` and a 48 bit timestamp in nanoseconds since
+//! midnight. Decoded are the messages that change the order book:
+//!
+//! | type | message                        | size |
+//! |------|--------------------------------|------|
+//! | `R`  | stock directory                | 39   |
+//! | `A`  | add order                      | 36   |
+//! | `F`  | add order with attribution     | 40   |
+//! | `E`  | order executed                 | 31   |
+//! | `C`  | order executed with price      | 36   |
+//! | `X`  | order cancel                   | 23   |
+//! | `D`  | order delete                   | 19   |
+//! | `U`  | order replace                  | 35   |
+//!
+//! Other types decode to [`ItchMessage::Other`]. As in [`wire`](super::wire), views
+//! borrow the input and read fields on access. [`ItchBooks`] applies the messages to
+//! one [`L3OrderBook`] per instrument.
+
+use std::collections::HashMap;
+
+use crate::error::{MarketDataError, Result};
+use crate::orderbook::{BookSide, L3OrderBook};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+pub const STOCK_DIRECTORY_TAG: u8 = b'R';
+pub const ADD_ORDER_TAG: u8 = b'A';
+pub const ADD_ORDER_MPID_TAG: u8 = b'F';
+pub const ORDER_EXECUTED_TAG: u8 = b'E';
+pub const ORDER_EXECUTED_PRICE_TAG: u8 = b'C';
+pub const ORDER_CANCEL_TAG: u8 = b'X';
+pub const ORDER_DELETE_TAG: u8 = b'D';
+pub const ORDER_REPLACE_TAG: u8 = b'U';
+
+/// Length of the common message header
+pub const HEADER_LEN: usize = 11;
+
+/// Prices carry four implied decimals
+const PRICE_SCALE: f64 = 10_000.0;
+
+#[inline]
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([buf[offset], buf[offset + 1]])
+}
+
+#[inline]
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&buf[offset..offset + 4]);
+    u32::from_be_bytes(bytes)
+}
+
+#[inline]
+fn read_u64(buf: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[offset..offset + 8]);
+    u64::from_be_bytes(bytes)
+}
+
+#[inline]
+fn read_u48(buf: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes[2..].copy_from_slice(&buf[offset..offset + 6]);
+    u64::from_be_bytes(bytes)
+}
+
+#[inline]
+fn read_price(buf: &[u8], offset: usize) -> f64 {
+    read_u32(buf, offset) as f64 / PRICE_SCALE
+}
+
+/// Right-padded alpha field without its padding
+#[inline]
+fn read_alpha(buf: &[u8], offset: usize, len: usize) -> &str {
+    std::str::from_utf8(&buf[offset..offset + len])
+        .unwrap_or_default()
+        .trim_end()
+}
+
+macro_rules! header_fields {
+    () => {
+        pub fn stock_locate(&self) -> u16 {
+            read_u16(self.buf, 1)
+        }
+
+        pub fn tracking_number(&self) -> u16 {
+            read_u16(self.buf, 3)
+        }
+
+        /// Nanoseconds since midnight
+        pub fn timestamp(&self) -> u64 {
+            read_u48(self.buf, 5)
+        }
+
+        pub fn order_reference(&self) -> u64 {
+            read_u64(self.buf, 11)
+        }
+    };
+}
+
+/// Borrowed view of a stock directory message
+#[derive(Debug, Clone, Copy)]
+pub struct StockDirectory<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> StockDirectory<'a> {
+    pub fn stock_locate(&self) -> u16 {
+        read_u16(self.buf, 1)
+    }
+
+    pub fn stock(&self) -> &'a str {
+        read_alpha(self.buf, 11, 8)
+    }
+}
+
+/// Borrowed view of an add order message, with or without attribution
+#[derive(Debug, Clone, Copy)]
+pub struct AddOrder<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> AddOrder<'a> {
+    header_fields!();
+
+    pub fn side(&self) -> BookSide {
+        if self.buf[19] == b'B' {
+            BookSide::Bid
+        } else {
+            BookSide::Ask
+        }
+    }
+
+    pub fn shares(&self) -> u32 {
+        read_u32(self.buf, 20)
+    }
+
+    pub fn stock(&self) -> &'a str {
+        read_alpha(self.buf, 24, 8)
+    }
+
+    pub fn price(&self) -> f64 {
+        read_price(self.buf, 32)
+    }
+
+    /// Market participant id of an `F` message
+    pub fn attribution(&self) -> Option<&'a str> {
+        (self.buf[0] == ADD_ORDER_MPID_TAG).then(|| read_alpha(self.buf, 36, 4))
+    }
+}
+
+/// Borrowed view of an order executed message, with or without price
+#[derive(Debug, Clone, Copy)]
+pub struct OrderExecuted<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> OrderExecuted<'a> {
+    header_fields!();
+
+    pub fn executed_shares(&self) -> u32 {
+        read_u32(self.buf, 19)
+    }
+
+    pub fn match_number(&self) -> u64 {
+        read_u64(self.buf, 23)
+    }
+
+    /// Execution price of a `C` message, which differs from the order's price
+    pub fn execution_price(&self) -> Option<f64> {
+        (self.buf[0] == ORDER_EXECUTED_PRICE_TAG).then(|| read_price(self.buf, 32))
+    }
+
+    /// Whether the execution should be shown on time and sales; always true for `E`
+    pub fn printable(&self) -> bool {
+        self.buf[0] != ORDER_EXECUTED_PRICE_TAG || self.buf[31] == b'Y'
+    }
+}
+
+/// Borrowed view of an order cancel (partial) message
+#[derive(Debug, Clone, Copy)]
+pub struct OrderCancel<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> OrderCancel<'a> {
+    header_fields!();
+
+    pub fn cancelled_shares(&self) -> u32 {
+        read_u32(self.buf, 19)
+    }
+}
+
+/// Borrowed view of an order delete message
+#[derive(Debug, Clone, Copy)]
+pub struct OrderDelete<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> OrderDelete<'a> {
+    header_fields!();
+}
+
+/// Borrowed view of an order replace message
+///
+/// `order_reference` is the original order, replaced by `new_order_reference`.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderReplace<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> OrderReplace<'a> {
+    header_fields!();
+
+    pub fn new_order_reference(&self) -> u64 {
+        read_u64(self.buf, 19)
+    }
+
+    pub fn shares(&self) -> u32 {
+        read_u32(self.buf, 27)
+    }
+
+    pub fn price(&self) -> f64 {
+        read_price(self.buf, 31)
+    }
+}
+
+/// Borrowed view of one message
+#[derive(Debug, Clone, Copy)]
+pub enum ItchMessage<'a> {
+    StockDirectory(StockDirectory<'a>),
+    AddOrder(AddOrder<'a>),
+    OrderExecuted(OrderExecuted<'a>),
+    OrderCancel(OrderCancel<'a>),
+    OrderDelete(OrderDelete<'a>),
+    OrderReplace(OrderReplace<'a>),
+    /// Message type not affecting the book, e.g. system events or NOII
+    Other(u8),
+}
+
+impl<'a> ItchMessage<'a> {
+    /// Decode one message without its length prefix
+    pub fn decode(buf: &'a [u8]) -> Result<ItchMessage<'a>> {
+        let Some(&tag) = buf.first() else {
+            return Err(MarketDataError::Malformed("empty ITCH message".to_string()));
+        };
+
+        let len = match tag {
+            STOCK_DIRECTORY_TAG => 39,
+            ADD_ORDER_TAG => 36,
+            ADD_ORDER_MPID_TAG => 40,
+            ORDER_EXECUTED_TAG => 31,
+            ORDER_EXECUTED_PRICE_TAG => 36,
+            ORDER_CANCEL_TAG => 23,
+            ORDER_DELETE_TAG => 19,
+            ORDER_REPLACE_TAG => 35,
+            other => return Ok(ItchMessage::Other(other)),
+        };
+        if buf.len() < len {
+            return Err(MarketDataError::Malformed(format!(
+                "ITCH message {:?} of {} bytes, expected {len}",
+                tag as char,
+                buf.len()
+            )));
+        }
+
+        let buf = &buf[..len];
+        Ok(match tag {
+            STOCK_DIRECTORY_TAG => ItchMessage::StockDirectory(StockDirectory { buf }),
+            ADD_ORDER_TAG | ADD_ORDER_MPID_TAG => {
+                if !matches!(buf[19], b'B' | b'S') {
+                    return Err(MarketDataError::Malformed(format!(
+                        "invalid side {:#04x}",
+                        buf[19]
+                    )));
+                }
+                ItchMessage::AddOrder(AddOrder { buf })
+            }
+            ORDER_EXECUTED_TAG | ORDER_EXECUTED_PRICE_TAG => {
+                ItchMessage::OrderExecuted(OrderExecuted { buf })
+            }
+            ORDER_CANCEL_TAG => ItchMessage::OrderCancel(OrderCancel { buf }),
+            ORDER_DELETE_TAG => ItchMessage::OrderDelete(OrderDelete { buf }),
+            _ => ItchMessage::OrderReplace(OrderReplace { buf }),
+        })
+    }
+}
+
+/// Iterator over messages framed by a big-endian `u16` length, as in ITCH files and
+/// SoupBinTCP / MoldUDP64 payloads
+///
+/// Stops after the first error; a trailing partial message is reported as malformed.
+pub struct ItchReader<'a> {
+    buf: &'a [u8],
+    failed: bool,
+}
+
+impl<'a> ItchReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, failed: false }
+    }
+
+    /// Bytes not consumed yet
+    pub fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a> Iterator for ItchReader<'a> {
+    type Item = Result<ItchMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.buf.is_empty() {
+            return None;
+        }
+
+        let len = match self.buf {
+            [high, low, ..] => u16::from_be_bytes([*high, *low]) as usize,
+            _ => 0,
+        };
+        if self.buf.len() < 2 + len || len == 0 {
+            self.failed = true;
+            return Some(Err(MarketDataError::Malformed(format!(
+                "truncated ITCH frame: {} bytes left",
+                self.buf.len()
+            ))));
+        }
+        let message = &self.buf[2..2 + len];
+        self.buf = &self.buf[2 + len..];
+        let decoded = ItchMessage::decode(message);
+        self.failed = decoded.is_err();
+        Some(decoded)
+    }
+}
+
+/// Level 3 books of all instruments of an ITCH session, keyed by stock locate
+#[derive(Debug, Clone)]
+pub struct ItchBooks {
+    /// Midnight of the session day, to which message timestamps are relative
+    session: Timestamp,
+    books: HashMap<u16, L3OrderBook>,
+}
+
+impl ItchBooks {
+    /// `session` is midnight (exchange time, as UTC) of the day being replayed
+    pub fn new(session: Timestamp) -> Self {
+        Self {
+            session,
+            books: HashMap::new(),
+        }
+    }
+
+    fn time(&self, nanos: u64) -> Timestamp {
+        Timestamp::from_nanos(self.session.as_nanos() + nanos as i64)
+    }
+
+    fn book_mut(&mut self, locate: u16) -> Result<&mut L3OrderBook> {
+        self.books.get_mut(&locate).ok_or_else(|| {
+            MarketDataError::Malformed(format!("order message for unknown stock locate {locate}"))
+        })
+    }
+
+    /// Apply a message, returning the trade for printable executions
+    ///
+    /// Books are created by stock directory messages or the first order added to an
+    /// instrument. The trade's side is the aggressor's, opposite the resting order.
+    pub fn apply(&mut self, message: &ItchMessage<'_>) -> Result<Option<Trade>> {
+        match message {
+            ItchMessage::StockDirectory(directory) => {
+                self.books
+                    .entry(directory.stock_locate())
+                    .or_insert_with(|| L3OrderBook::new(directory.stock().to_string()));
+            }
+            ItchMessage::AddOrder(add) => {
+                let time = self.time(add.timestamp());
+                self.books
+                    .entry(add.stock_locate())
+                    .or_insert_with(|| L3OrderBook::new(add.stock().to_string()))
+                    .add(
+                        add.order_reference(),
+                        add.side(),
+                        add.price(),
+                        add.shares() as f64,
+                        time,
+                    )?;
+            }
+            ItchMessage::OrderExecuted(executed) => {
+                let time = self.time(executed.timestamp());
+                let execution = self.book_mut(executed.stock_locate())?.execute(
+                    executed.order_reference(),
+                    executed.executed_shares() as f64,
+                    time,
+                )?;
+                if !executed.printable() {
+                    return Ok(None);
+                }
+                let side = match execution.order.side {
+                    BookSide::Bid => Side::Sell,
+                    BookSide::Ask => Side::Buy,
+                };
+                return Ok(Some(Trade::new(
+                    executed.execution_price().unwrap_or(execution.order.price),
+                    execution.executed,
+                    side,
+                    time,
+                )));
+            }
+            ItchMessage::OrderCancel(cancel) => {
+                let time = self.time(cancel.timestamp());
+                let book = self.book_mut(cancel.stock_locate())?;
+                let id = cancel.order_reference();
+                let order = *book.order(id).ok_or(MarketDataError::UnknownOrder(id))?;
+                let remaining = (order.quantity - cancel.cancelled_shares() as f64).max(0.0);
+                book.modify(id, order.price, remaining, time)?;
+            }
+            ItchMessage::OrderDelete(delete) => {
+                let time = self.time(delete.timestamp());
+                self.book_mut(delete.stock_locate())?
+                    .delete(delete.order_reference(), time)?;
+            }
+            ItchMessage::OrderReplace(replace) => {
+                // The new reference takes the original's side and loses its priority
+                let time = self.time(replace.timestamp());
+                let book = self.book_mut(replace.stock_locate())?;
+                let original = book.delete(replace.order_reference(), time)?;
+                book.add(
+                    replace.new_order_reference(),
+                    original.side,
+                    replace.price(),
+                    replace.shares() as f64,
+                    time,
+                )?;
+            }
+            ItchMessage::Other(_) => {}
+        }
+        Ok(None)
+    }
+
+    /// Apply every message of a length-prefixed buffer, collecting the trades
+    pub fn apply_all(&mut self, buf: &[u8]) -> Result<Vec<Trade>> {
+        let mut trades = Vec::new();
+        for message in ItchReader::new(buf) {
+            trades.extend(self.apply(&message?)?);
+        }
+        Ok(trades)
+    }
+
+    pub fn book(&self, stock: &str) -> Option<&L3OrderBook> {
+        self.books.values().find(|book| book.symbol == stock)
+    }
+
+    pub fn book_by_locate(&self, locate: u16) -> Option<&L3OrderBook> {
+        self.books.get(&locate)
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const LOCATE: u16 = 7;
+
+    /// Length-prefixed message with the common header and `body`
+    fn message(tag: u8, nanos: u64, body: &[u8]) -> Vec<u8> {
+        let mut msg = vec![tag];
+        msg.extend_from_slice(&LOCATE.to_be_bytes());
+        msg.extend_from_slice(&0u16.to_be_bytes());
+        msg.extend_from_slice(&nanos.to_be_bytes()[2..]);
+        msg.extend_from_slice(body);
+        let mut framed = (msg.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(&msg);
+        framed
+    }
+
+    fn add(id: u64, side: u8, shares: u32, price: u32) -> Vec<u8> {
+        let mut body = id.to_be_bytes().to_vec();
+        body.push(side);
+        body.extend_from_slice(&shares.to_be_bytes());
+        body.extend_from_slice(b"AAPL    ");
+        body.extend_from_slice(&price.to_be_bytes());
+        message(ADD_ORDER_TAG, 1_000, &body)
+    }
+
+    fn with_shares(tag: u8, id: u64, shares: u32, rest: &[u8]) -> Vec<u8> {
+        let mut body = id.to_be_bytes().to_vec();
+        body.extend_from_slice(&shares.to_be_bytes());
+        body.extend_from_slice(rest);
+        message(tag, 2_000, &body)
+    }
+
+    #[test]
+    fn test_decode_views() {
+        let msg = add(42, b'B', 100, 1_502_500);
+        let ItchMessage::AddOrder(view) = ItchMessage::decode(&msg[2..]).unwrap() else {
+            panic!("expected add order");
+        };
+        assert_eq!(view.stock_locate(), LOCATE);
+        assert_eq!(view.timestamp(), 1_000);
+        assert_eq!(view.order_reference(), 42);
+        assert_eq!(view.side(), BookSide::Bid);
+        assert_eq!(view.shares(), 100);
+        assert_eq!(view.stock(), "AAPL");
+        assert_eq!(view.price(), 150.25);
+        assert_eq!(view.attribution(), None);
+
+        assert!(matches!(
+            ItchMessage::decode(b"S\0\0\0\0\0\0\0\0\0\0O"),
+            Ok(ItchMessage::Other(b'S'))
+        ));
+        assert!(ItchMessage::decode(&msg[2..30]).is_err());
+        let mut bad_side = msg.clone();
+        bad_side[2 + 19] = b'Z';
+        assert!(ItchMessage::decode(&bad_side[2..]).is_err());
+    }
+
+    #[test]
+    fn test_order_lifecycle_builds_l3_book() {
+        let mut feed = Vec::new();
+        feed.extend(add(1, b'B', 100, 1_000_000));
+        feed.extend(add(2, b'B', 50, 1_000_000));
+        feed.extend(add(3, b'S', 200, 1_010_000));
+        feed.extend(with_shares(ORDER_EXECUTED_TAG, 1, 30, &9u64.to_be_bytes()));
+        feed.extend(with_shares(ORDER_CANCEL_TAG, 2, 20, &[]));
+        let mut with_price = 10u64.to_be_bytes().to_vec();
+        with_price.push(b'Y');
+        with_price.extend_from_slice(&1_005_000u32.to_be_bytes());
+        feed.extend(with_shares(ORDER_EXECUTED_PRICE_TAG, 3, 50, &with_price));
+        let mut replace = 4u64.to_be_bytes().to_vec();
+        replace.extend_from_slice(&80u32.to_be_bytes());
+        replace.extend_from_slice(&1_001_000u32.to_be_bytes());
+        feed.extend(message(
+            ORDER_REPLACE_TAG,
+            3_000,
+            &[&1u64.to_be_bytes()[..], &replace].concat(),
+        ));
+        feed.extend(message(ORDER_DELETE_TAG, 4_000, &3u64.to_be_bytes()));
+
+        let session = Timestamp::from_millis(1_700_000_000_000);
+        let mut books = ItchBooks::new(session);
+        let trades = books.apply_all(&feed).unwrap();
+        let executed = session + Duration::from_nanos(2_000);
+        assert_eq!(
+            trades,
+            vec![
+                Trade::new(100.0, 30.0, Side::Sell, executed),
+                Trade::new(100.5, 50.0, Side::Buy, executed),
+            ]
+        );
+
+        let book = books.book("AAPL").unwrap();
+        assert_eq!(book.order_count(), 2);
+        assert!(book.order(1).is_none());
+        assert_eq!(book.order(2).unwrap().quantity, 30.0);
+        assert_eq!(book.best_bid(), Some((100.1, 80.0)));
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(
+            book.last_update,
+            Timestamp::from_nanos(session.as_nanos() + 4_000)
+        );
+        assert!(books.book_by_locate(LOCATE).is_some());
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        let msg = add(1, b'B', 100, 1_000_000);
+        let mut reader = ItchReader::new(&msg[..msg.len() - 1]);
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+
+        let mut books = ItchBooks::new(Timestamp::EPOCH);
+        let delete = message(ORDER_DELETE_TAG, 0, &1u64.to_be_bytes());
+        assert!(books.apply_all(&delete).is_err());
+        books.apply_all(&msg).unwrap();
+        assert!(matches!(
+            books.apply_all(&msg),
+            Err(MarketDataError::DuplicateOrder(1))
+        ));
+    }
+}
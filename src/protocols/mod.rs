@@ -0,0 +1,11 @@
+pub mod fix;
+pub mod itch;
+#[cfg(feature = "sbe")]
+pub mod sbe;
+pub mod wire;
+
+pub use fix::{FixDecoder, FixMessage};
+pub use itch::{ItchBooks, ItchMessage, ItchReader};
+#[cfg(feature = "sbe")]
+pub use sbe::{Mdp3Decoder, Mdp3Update, SbeSchema};
+pub use wire::{QuoteView, RawTick, TickReader, TickView, TradeView};
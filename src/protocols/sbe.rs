@@ -0,0 +1,1122 @@
+//! Simple Binary Encoding messages and CME MDP 3.0 market data, enabled with the
+//! `sbe` feature
+//!
+//! [`SbeSchema`] is built from the XML message templates the venue publishes (for
+//! CME, `templates_FixBinary.xml`) and decodes any of its messages into named fields
+//! and repeating groups. Fields added in a later schema version than the one on the
+//! wire decode to [`SbeValue::Null`], and block lengths are taken from the wire so
+//! newer messages remain readable with older templates.
+//!
+//! [`Mdp3Decoder`] adds the MDP 3.0 packet framing and turns incremental refresh book
+//! (`MDIncrementalRefreshBook`) and trade summary messages into [`Mdp3Update`]s.
+//! Only the outright book is kept: implied entries are skipped. `RptSeq` is reported
+//! per update so callers can detect gaps per instrument.
+
+use std::collections::HashMap;
+
+use crate::error::{MarketDataError, Result};
+use crate::orderbook::{BookSide, LevelUpdate, OrderBook};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+fn malformed(message: impl Into<String>) -> MarketDataError {
+    MarketDataError::Malformed(message.into())
+}
+
+/// Primitive wire type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Primitive {
+    Char,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float,
+    Double,
+}
+
+impl Primitive {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" => Primitive::Char,
+            "int8" => Primitive::Int8,
+            "int16" => Primitive::Int16,
+            "int32" => Primitive::Int32,
+            "int64" => Primitive::Int64,
+            "uint8" => Primitive::UInt8,
+            "uint16" => Primitive::UInt16,
+            "uint32" => Primitive::UInt32,
+            "uint64" => Primitive::UInt64,
+            "float" => Primitive::Float,
+            "double" => Primitive::Double,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Primitive::Char | Primitive::Int8 | Primitive::UInt8 => 1,
+            Primitive::Int16 | Primitive::UInt16 => 2,
+            Primitive::Int32 | Primitive::UInt32 | Primitive::Float => 4,
+            Primitive::Int64 | Primitive::UInt64 | Primitive::Double => 8,
+        }
+    }
+
+    /// Null value when the schema declares none
+    fn default_null(self) -> i128 {
+        match self {
+            Primitive::Char => 0,
+            Primitive::Int8 => i8::MIN as i128,
+            Primitive::Int16 => i16::MIN as i128,
+            Primitive::Int32 => i32::MIN as i128,
+            Primitive::Int64 => i64::MIN as i128,
+            Primitive::UInt8 => u8::MAX as i128,
+            Primitive::UInt16 => u16::MAX as i128,
+            Primitive::UInt32 => u32::MAX as i128,
+            Primitive::UInt64 => u64::MAX as i128,
+            Primitive::Float | Primitive::Double => 0,
+        }
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(
+            self,
+            Primitive::Int8 | Primitive::Int16 | Primitive::Int32 | Primitive::Int64
+        )
+    }
+}
+
+/// Resolved encoding of a field or composite member
+#[derive(Debug, Clone)]
+enum Encoding {
+    Primitive {
+        primitive: Primitive,
+        length: usize,
+        null: Option<i128>,
+        constant: Option<String>,
+    },
+    Composite(Vec<Member>),
+    Enum {
+        primitive: Primitive,
+        /// Wire value (a character for `char` enums) and name
+        values: Vec<(String, String)>,
+    },
+    Set(Primitive),
+}
+
+#[derive(Debug, Clone)]
+struct Member {
+    name: String,
+    offset: usize,
+    encoding: Encoding,
+}
+
+impl Encoding {
+    fn size(&self) -> usize {
+        match self {
+            Encoding::Primitive {
+                constant: Some(_), ..
+            } => 0,
+            Encoding::Primitive {
+                primitive, length, ..
+            } => primitive.size() * length,
+            Encoding::Composite(members) => members
+                .iter()
+                .map(|member| member.offset + member.encoding.size())
+                .max()
+                .unwrap_or(0),
+            Encoding::Enum { primitive, .. } | Encoding::Set(primitive) => primitive.size(),
+        }
+    }
+}
+
+/// Decoded field value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SbeValue<'s> {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    /// Character array without trailing NUL padding
+    Str(String),
+    /// Composite of a mantissa and exponent, e.g. CME `PRICE9`
+    Decimal(f64),
+    /// Name of the valid value of an enum
+    Enum(&'s str),
+    Composite(Vec<(&'s str, SbeValue<'s>)>),
+    /// Null value, unknown enum value or field newer than the message version
+    Null,
+}
+
+impl SbeValue<'_> {
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            SbeValue::Int(value) => Some(value),
+            SbeValue::UInt(value) => i64::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            SbeValue::Int(value) => u64::try_from(value).ok(),
+            SbeValue::UInt(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            SbeValue::Int(value) => Some(value as f64),
+            SbeValue::UInt(value) => Some(value as f64),
+            SbeValue::Float(value) | SbeValue::Decimal(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SbeValue::Str(value) => Some(value),
+            SbeValue::Enum(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    offset: usize,
+    encoding: Encoding,
+    since_version: u16,
+    /// Valid value name of a constant enum field (`valueRef`)
+    constant_enum: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    name: String,
+    dimension: Encoding,
+    since_version: u16,
+    fields: Vec<Field>,
+    groups: Vec<Group>,
+}
+
+#[derive(Debug, Clone)]
+struct Template {
+    name: String,
+    fields: Vec<Field>,
+    groups: Vec<Group>,
+}
+
+/// Fields and groups of a message or group entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbeRecord<'s> {
+    pub fields: Vec<(&'s str, SbeValue<'s>)>,
+    pub groups: Vec<(&'s str, Vec<SbeRecord<'s>>)>,
+}
+
+impl<'s> SbeRecord<'s> {
+    /// Value of field `name`, [`SbeValue::Null`] when absent
+    pub fn get(&self, name: &str) -> &SbeValue<'s> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map_or(&SbeValue::Null, |(_, value)| value)
+    }
+
+    /// Entries of group `name`, empty when absent
+    pub fn group(&self, name: &str) -> &[SbeRecord<'s>] {
+        self.groups
+            .iter()
+            .find(|(group, _)| *group == name)
+            .map_or(&[], |(_, entries)| entries)
+    }
+}
+
+/// Decoded message
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbeMessage<'s> {
+    pub template_id: u16,
+    /// Template name, e.g. `MDIncrementalRefreshBook46`
+    pub name: &'s str,
+    /// Schema version the message was encoded with
+    pub version: u16,
+    pub body: SbeRecord<'s>,
+}
+
+/// Message templates of one schema
+#[derive(Debug, Clone)]
+pub struct SbeSchema {
+    pub id: u16,
+    pub version: u16,
+    big_endian: bool,
+    header: Encoding,
+    templates: HashMap<u16, Template>,
+}
+
+/// Named type definitions of the `<types>` section
+struct Types<'a, 'input> {
+    nodes: HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+}
+
+fn attribute<T: std::str::FromStr>(node: roxmltree::Node<'_, '_>, name: &str) -> Result<Option<T>> {
+    node.attribute(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                malformed(format!(
+                    "invalid {name}={value:?} on <{}>",
+                    node.tag_name().name()
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn required<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str> {
+    node.attribute(name).ok_or_else(|| {
+        malformed(format!(
+            "<{}> without {name} attribute",
+            node.tag_name().name()
+        ))
+    })
+}
+
+impl Types<'_, '_> {
+    /// Encoding of a type definition node
+    fn definition(&self, node: roxmltree::Node<'_, '_>) -> Result<Encoding> {
+        match node.tag_name().name() {
+            "type" => {
+                let name = required(node, "primitiveType")?;
+                let primitive = Primitive::parse(name)
+                    .ok_or_else(|| malformed(format!("unknown primitive type {name:?}")))?;
+                let constant = (node.attribute("presence") == Some("constant"))
+                    .then(|| node.text().unwrap_or_default().trim().to_string());
+                let null = match node.attribute("nullValue") {
+                    Some(value) if primitive == Primitive::Char => {
+                        Some(value.bytes().next().unwrap_or(0) as i128)
+                    }
+                    Some(_) => attribute(node, "nullValue")?,
+                    None => None,
+                };
+                Ok(Encoding::Primitive {
+                    primitive,
+                    length: attribute(node, "length")?.unwrap_or(1),
+                    null,
+                    constant,
+                })
+            }
+            "composite" => {
+                let mut members = Vec::new();
+                let mut offset = 0;
+                for child in node.children().filter(|child| child.is_element()) {
+                    let encoding = match child.tag_name().name() {
+                        "ref" => self.resolve(required(child, "type")?)?,
+                        _ => self.definition(child)?,
+                    };
+                    offset = attribute(child, "offset")?.unwrap_or(offset);
+                    let size = encoding.size();
+                    members.push(Member {
+                        name: required(child, "name")?.to_string(),
+                        offset,
+                        encoding,
+                    });
+                    offset += size;
+                }
+                Ok(Encoding::Composite(members))
+            }
+            kind @ ("enum" | "set") => {
+                let encoding = self.resolve(required(node, "encodingType")?)?;
+                let Encoding::Primitive { primitive, .. } = encoding else {
+                    return Err(malformed(format!("{kind} encoded as a composite")));
+                };
+                if kind == "set" {
+                    return Ok(Encoding::Set(primitive));
+                }
+                let values = node
+                    .children()
+                    .filter(|child| child.has_tag_name("validValue"))
+                    .map(|value| {
+                        Ok((
+                            value.text().unwrap_or_default().trim().to_string(),
+                            required(value, "name")?.to_string(),
+                        ))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Encoding::Enum { primitive, values })
+            }
+            other => Err(malformed(format!("unknown type definition <{other}>"))),
+        }
+    }
+
+    /// Encoding of a named type or primitive
+    fn resolve(&self, name: &str) -> Result<Encoding> {
+        if let Some(node) = self.nodes.get(name) {
+            return self.definition(*node);
+        }
+        let primitive =
+            Primitive::parse(name).ok_or_else(|| malformed(format!("unknown type {name:?}")))?;
+        Ok(Encoding::Primitive {
+            primitive,
+            length: 1,
+            null: None,
+            constant: None,
+        })
+    }
+
+    /// Fields and groups among the children of a message or group node
+    fn members(&self, node: roxmltree::Node<'_, '_>) -> Result<(Vec<Field>, Vec<Group>)> {
+        let mut fields = Vec::new();
+        let mut groups = Vec::new();
+        let mut offset = 0;
+        for child in node.children().filter(|child| child.is_element()) {
+            match child.tag_name().name() {
+                "field" => {
+                    let encoding = self.resolve(required(child, "type")?)?;
+                    let constant_enum = (child.attribute("presence") == Some("constant"))
+                        .then(|| child.attribute("valueRef"))
+                        .flatten()
+                        .map(|value| value.rsplit('.').next().unwrap_or(value).to_string());
+                    let size = if constant_enum.is_some() {
+                        0
+                    } else {
+                        encoding.size()
+                    };
+                    offset = attribute(child, "offset")?.unwrap_or(offset);
+                    fields.push(Field {
+                        name: required(child, "name")?.to_string(),
+                        offset,
+                        encoding,
+                        since_version: attribute(child, "sinceVersion")?.unwrap_or(0),
+                        constant_enum,
+                    });
+                    offset += size;
+                }
+                "group" => {
+                    let (fields, groups_of_group) = self.members(child)?;
+                    groups.push(Group {
+                        name: required(child, "name")?.to_string(),
+                        dimension: self.resolve(
+                            child
+                                .attribute("dimensionType")
+                                .unwrap_or("groupSizeEncoding"),
+                        )?,
+                        since_version: attribute(child, "sinceVersion")?.unwrap_or(0),
+                        fields,
+                        groups: groups_of_group,
+                    });
+                }
+                // Variable-length data follows the groups and is not decoded
+                _ => {}
+            }
+        }
+        Ok((fields, groups))
+    }
+}
+
+/// Cursor over a message buffer honouring the schema byte order
+#[derive(Clone, Copy)]
+struct Reader<'b> {
+    buf: &'b [u8],
+    big_endian: bool,
+}
+
+impl<'b> Reader<'b> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        let bytes = self.buf.get(offset..offset + N).ok_or_else(|| {
+            malformed(format!(
+                "SBE field at {offset} past end of {} bytes",
+                self.buf.len()
+            ))
+        })?;
+        let mut array = [0u8; N];
+        array.copy_from_slice(bytes);
+        if !self.big_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    /// Integer value of `primitive` at `offset`, widened without loss
+    fn integer(&self, primitive: Primitive, offset: usize) -> Result<i128> {
+        // `bytes` returns big-endian order, so from_be_bytes applies throughout
+        Ok(match primitive {
+            Primitive::Char | Primitive::UInt8 => self.bytes::<1>(offset)?[0] as i128,
+            Primitive::Int8 => self.bytes::<1>(offset)?[0] as i8 as i128,
+            Primitive::Int16 => i16::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::UInt16 => u16::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::Int32 => i32::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::UInt32 => u32::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::Int64 => i64::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::UInt64 => u64::from_be_bytes(self.bytes(offset)?) as i128,
+            Primitive::Float | Primitive::Double => {
+                return Err(malformed("floating point value read as integer"))
+            }
+        })
+    }
+
+    fn value<'s>(&self, encoding: &'s Encoding, offset: usize) -> Result<SbeValue<'s>> {
+        match encoding {
+            Encoding::Primitive {
+                constant: Some(constant),
+                ..
+            } => Ok(match constant.parse::<i64>() {
+                Ok(value) => SbeValue::Int(value),
+                Err(_) => SbeValue::Str(constant.clone()),
+            }),
+            Encoding::Primitive {
+                primitive: Primitive::Char,
+                length,
+                null,
+                ..
+            } => {
+                let bytes = self
+                    .buf
+                    .get(offset..offset + length)
+                    .ok_or_else(|| malformed(format!("SBE field at {offset} past end")))?;
+                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                if end == 0 || (*length == 1 && null.is_some_and(|null| bytes[0] as i128 == null)) {
+                    return Ok(SbeValue::Null);
+                }
+                Ok(SbeValue::Str(
+                    String::from_utf8_lossy(&bytes[..end]).into_owned(),
+                ))
+            }
+            Encoding::Primitive {
+                primitive: Primitive::Float,
+                ..
+            } => Ok(match f32::from_be_bytes(self.bytes(offset)?) {
+                value if value.is_nan() => SbeValue::Null,
+                value => SbeValue::Float(value as f64),
+            }),
+            Encoding::Primitive {
+                primitive: Primitive::Double,
+                ..
+            } => Ok(match f64::from_be_bytes(self.bytes(offset)?) {
+                value if value.is_nan() => SbeValue::Null,
+                value => SbeValue::Float(value),
+            }),
+            Encoding::Primitive {
+                primitive, null, ..
+            } => {
+                let value = self.integer(*primitive, offset)?;
+                if value == null.unwrap_or_else(|| primitive.default_null()) {
+                    Ok(SbeValue::Null)
+                } else if primitive.is_signed() {
+                    Ok(SbeValue::Int(value as i64))
+                } else {
+                    Ok(SbeValue::UInt(value as u64))
+                }
+            }
+            Encoding::Enum { primitive, values } => {
+                let raw = self.integer(*primitive, offset)?;
+                let raw = match primitive {
+                    Primitive::Char => (raw as u8 as char).to_string(),
+                    _ => raw.to_string(),
+                };
+                Ok(values
+                    .iter()
+                    .find(|(value, _)| *value == raw)
+                    .map_or(SbeValue::Null, |(_, name)| SbeValue::Enum(name)))
+            }
+            Encoding::Set(primitive) => {
+                Ok(SbeValue::UInt(self.integer(*primitive, offset)? as u64))
+            }
+            Encoding::Composite(members) => {
+                let values = members
+                    .iter()
+                    .map(|member| {
+                        Ok((
+                            member.name.as_str(),
+                            self.value(&member.encoding, offset + member.offset)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let part = |name: &str| {
+                    values
+                        .iter()
+                        .find(|(member, _)| *member == name)
+                        .map(|(_, value)| value)
+                };
+                match (part("mantissa"), part("exponent")) {
+                    (Some(SbeValue::Null), Some(_)) => Ok(SbeValue::Null),
+                    (Some(mantissa), Some(exponent)) => {
+                        match (mantissa.as_f64(), exponent.as_i64()) {
+                            (Some(mantissa), Some(exponent)) => {
+                                Ok(SbeValue::Decimal(mantissa * 10f64.powi(exponent as i32)))
+                            }
+                            _ => Ok(SbeValue::Null),
+                        }
+                    }
+                    _ => Ok(SbeValue::Composite(values)),
+                }
+            }
+        }
+    }
+
+    fn unsigned(&self, encoding: &Encoding, name: &str) -> Result<usize> {
+        let value = match encoding {
+            Encoding::Composite(members) => members
+                .iter()
+                .find(|member| member.name == name)
+                .map(|member| self.value(&member.encoding, member.offset))
+                .transpose()?,
+            _ => None,
+        };
+        value
+            .and_then(|value| value.as_u64())
+            .map(|value| value as usize)
+            .ok_or_else(|| malformed(format!("SBE composite without {name}")))
+    }
+}
+
+impl SbeSchema {
+    /// Parse the `messageSchema` XML of a venue
+    pub fn parse(xml: &str) -> Result<Self> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|e| malformed(format!("invalid SBE schema: {e}")))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "messageSchema" {
+            return Err(malformed("SBE schema root is not <messageSchema>"));
+        }
+
+        let mut types = Types {
+            nodes: HashMap::new(),
+        };
+        for section in root.children().filter(|child| child.has_tag_name("types")) {
+            for node in section.children().filter(|child| child.is_element()) {
+                types.nodes.insert(required(node, "name")?, node);
+            }
+        }
+
+        let mut templates = HashMap::new();
+        for node in root
+            .children()
+            .filter(|child| child.tag_name().name() == "message")
+        {
+            let id = attribute(node, "id")?.ok_or_else(|| malformed("<message> without id"))?;
+            let (fields, groups) = types.members(node)?;
+            templates.insert(
+                id,
+                Template {
+                    name: required(node, "name")?.to_string(),
+                    fields,
+                    groups,
+                },
+            );
+        }
+
+        Ok(Self {
+            id: attribute(root, "id")?.unwrap_or(0),
+            version: attribute(root, "version")?.unwrap_or(0),
+            big_endian: root.attribute("byteOrder") == Some("bigEndian"),
+            header: types.resolve(root.attribute("headerType").unwrap_or("messageHeader"))?,
+            templates,
+        })
+    }
+
+    /// Name of template `id`, if the schema defines it
+    pub fn template_name(&self, id: u16) -> Option<&str> {
+        self.templates
+            .get(&id)
+            .map(|template| template.name.as_str())
+    }
+
+    /// Decode the message at the start of `buf`, returning it with the length of its
+    /// header, root block and groups
+    pub fn decode<'s>(&'s self, buf: &[u8]) -> Result<(SbeMessage<'s>, usize)> {
+        let reader = Reader {
+            buf,
+            big_endian: self.big_endian,
+        };
+        let block_length = reader.unsigned(&self.header, "blockLength")?;
+        let template_id = reader.unsigned(&self.header, "templateId")? as u16;
+        let version = reader.unsigned(&self.header, "version")? as u16;
+        let template = self
+            .templates
+            .get(&template_id)
+            .ok_or_else(|| malformed(format!("unknown SBE template {template_id}")))?;
+
+        let mut offset = self.header.size();
+        let body = self.record(
+            &template.fields,
+            &template.groups,
+            reader,
+            &mut offset,
+            block_length,
+            version,
+        )?;
+        Ok((
+            SbeMessage {
+                template_id,
+                name: &template.name,
+                version,
+                body,
+            },
+            offset,
+        ))
+    }
+
+    /// Decode a block at `offset` followed by its groups, advancing `offset` past them
+    fn record<'s>(
+        &'s self,
+        fields: &'s [Field],
+        groups: &'s [Group],
+        reader: Reader<'_>,
+        offset: &mut usize,
+        block_length: usize,
+        version: u16,
+    ) -> Result<SbeRecord<'s>> {
+        if reader.buf.len() < *offset + block_length {
+            return Err(malformed(format!(
+                "SBE block of {block_length} bytes at {offset} past end of {} bytes",
+                reader.buf.len()
+            )));
+        }
+        let block = Reader {
+            buf: &reader.buf[*offset..*offset + block_length],
+            ..reader
+        };
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let value = match &field.constant_enum {
+                    _ if field.since_version > version => SbeValue::Null,
+                    Some(name) => SbeValue::Enum(name),
+                    None => block.value(&field.encoding, field.offset)?,
+                };
+                Ok((field.name.as_str(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        *offset += block_length;
+
+        let mut decoded = Vec::with_capacity(groups.len());
+        for group in groups {
+            if group.since_version > version {
+                decoded.push((group.name.as_str(), Vec::new()));
+                continue;
+            }
+            let dimension = Reader {
+                buf: reader.buf.get(*offset..).unwrap_or_default(),
+                ..reader
+            };
+            let entry_length = dimension.unsigned(&group.dimension, "blockLength")?;
+            let count = dimension.unsigned(&group.dimension, "numInGroup")?;
+            *offset += group.dimension.size();
+            let entries = (0..count)
+                .map(|_| {
+                    self.record(
+                        &group.fields,
+                        &group.groups,
+                        reader,
+                        offset,
+                        entry_length,
+                        version,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            decoded.push((group.name.as_str(), entries));
+        }
+
+        Ok(SbeRecord {
+            fields,
+            groups: decoded,
+        })
+    }
+}
+
+/// MDP 3.0 packet: sequence number, sending time and the messages it carries
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mdp3Packet<'s> {
+    pub sequence: u32,
+    pub sending_time: Timestamp,
+    pub messages: Vec<SbeMessage<'s>>,
+}
+
+/// Change carried by an [`Mdp3Update`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mdp3UpdateKind {
+    /// New, change, delete or overlay of the level at a price
+    Level(LevelUpdate),
+    /// `DeleteThru`: every level on the side removed
+    ClearSide(BookSide),
+    /// Book reset: every level on both sides removed
+    Reset,
+    /// `DeleteFrom`: levels from the 1-based `level` outward removed
+    DeleteFrom {
+        side: BookSide,
+        level: usize,
+    },
+    Trade(Trade),
+}
+
+/// Book or trade update for one instrument
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mdp3Update {
+    pub security_id: i64,
+    /// Per-instrument sequence number, consecutive unless messages were lost
+    pub rpt_seq: u64,
+    pub transact_time: Timestamp,
+    pub kind: Mdp3UpdateKind,
+}
+
+impl Mdp3Update {
+    /// Apply a book change to the instrument's `book`; trades leave it unchanged
+    pub fn apply_to(&self, book: &mut OrderBook) -> Result<()> {
+        let updates = match self.kind {
+            Mdp3UpdateKind::Level(update) => vec![update],
+            Mdp3UpdateKind::ClearSide(side) => removals(book, side, 0),
+            Mdp3UpdateKind::Reset => {
+                let mut updates = removals(book, BookSide::Bid, 0);
+                updates.extend(removals(book, BookSide::Ask, 0));
+                updates
+            }
+            Mdp3UpdateKind::DeleteFrom { side, level } => {
+                removals(book, side, level.saturating_sub(1))
+            }
+            Mdp3UpdateKind::Trade(_) => return Ok(()),
+        };
+        book.apply_updates(&updates, self.transact_time).map(|_| ())
+    }
+}
+
+/// Zero-quantity updates for the levels of `side` from index `from` outward
+fn removals(book: &OrderBook, side: BookSide, from: usize) -> Vec<LevelUpdate> {
+    let levels = match side {
+        BookSide::Bid => book.top_bids(usize::MAX),
+        BookSide::Ask => book.top_asks(usize::MAX),
+    };
+    levels
+        .into_iter()
+        .skip(from)
+        .map(|level| LevelUpdate {
+            side,
+            price: level.price,
+            quantity: 0.0,
+        })
+        .collect()
+}
+
+/// Decoder of CME MDP 3.0 packets
+#[derive(Debug, Clone)]
+pub struct Mdp3Decoder {
+    schema: SbeSchema,
+}
+
+/// `MsgSeqNum` and `SendingTime` preceding the messages of a packet
+const PACKET_HEADER_LEN: usize = 12;
+
+impl Mdp3Decoder {
+    pub fn new(schema: SbeSchema) -> Self {
+        Self { schema }
+    }
+
+    /// Decoder for the `templates_FixBinary.xml` schema text
+    pub fn from_xml(xml: &str) -> Result<Self> {
+        SbeSchema::parse(xml).map(Self::new)
+    }
+
+    pub fn schema(&self) -> &SbeSchema {
+        &self.schema
+    }
+
+    /// Decode a packet: its header, then messages each prefixed by a little-endian
+    /// `u16` size that includes the prefix itself
+    pub fn decode_packet<'s>(&'s self, buf: &[u8]) -> Result<Mdp3Packet<'s>> {
+        if buf.len() < PACKET_HEADER_LEN {
+            return Err(malformed(format!("MDP3 packet of {} bytes", buf.len())));
+        }
+        let sequence = u32::from_le_bytes(buf[0..4].try_into().expect("4 bytes"));
+        let sending_time = u64::from_le_bytes(buf[4..12].try_into().expect("8 bytes"));
+
+        let mut messages = Vec::new();
+        let mut rest = &buf[PACKET_HEADER_LEN..];
+        while !rest.is_empty() {
+            let size = match rest {
+                [low, high, ..] => u16::from_le_bytes([*low, *high]) as usize,
+                _ => 0,
+            };
+            if size < 2 || size > rest.len() {
+                return Err(malformed(format!(
+                    "MDP3 message size {size} with {} bytes left",
+                    rest.len()
+                )));
+            }
+            let (message, _) = self.schema.decode(&rest[2..size])?;
+            messages.push(message);
+            rest = &rest[size..];
+        }
+
+        Ok(Mdp3Packet {
+            sequence,
+            sending_time: Timestamp::from_nanos(sending_time as i64),
+            messages,
+        })
+    }
+
+    /// Book and trade updates of a packet in wire order; other messages are skipped
+    pub fn updates(&self, packet: &Mdp3Packet<'_>) -> Result<Vec<Mdp3Update>> {
+        let mut updates = Vec::new();
+        for message in &packet.messages {
+            let book = message.name.starts_with("MDIncrementalRefreshBook");
+            let trades = message.name.starts_with("MDIncrementalRefreshTradeSummary");
+            if !book && !trades {
+                continue;
+            }
+            let transact_time = message
+                .body
+                .get("TransactTime")
+                .as_i64()
+                .map_or(packet.sending_time, Timestamp::from_nanos);
+
+            for entry in message.body.group("NoMDEntries") {
+                let kind = if book {
+                    match book_entry(entry)? {
+                        Some(kind) => kind,
+                        None => continue,
+                    }
+                } else {
+                    let side = match entry.get("AggressorSide").as_str() {
+                        Some("Sell") => Side::Sell,
+                        _ => Side::Buy,
+                    };
+                    Mdp3UpdateKind::Trade(Trade::new(
+                        required_f64(entry, "MDEntryPx")?,
+                        required_f64(entry, "MDEntrySize")?,
+                        side,
+                        transact_time,
+                    ))
+                };
+                updates.push(Mdp3Update {
+                    security_id: entry
+                        .get("SecurityID")
+                        .as_i64()
+                        .ok_or_else(|| malformed("MDP3 entry without SecurityID"))?,
+                    rpt_seq: entry.get("RptSeq").as_u64().unwrap_or(0),
+                    transact_time,
+                    kind,
+                });
+            }
+        }
+        Ok(updates)
+    }
+}
+
+fn required_f64(entry: &SbeRecord<'_>, name: &str) -> Result<f64> {
+    entry
+        .get(name)
+        .as_f64()
+        .ok_or_else(|| malformed(format!("MDP3 entry without {name}")))
+}
+
+/// Change of an outright book entry, `None` for implied entries
+fn book_entry(entry: &SbeRecord<'_>) -> Result<Option<Mdp3UpdateKind>> {
+    let side = match entry.get("MDEntryType").as_str() {
+        Some("Bid") => BookSide::Bid,
+        Some("Offer") => BookSide::Ask,
+        Some("BookReset") => return Ok(Some(Mdp3UpdateKind::Reset)),
+        _ => return Ok(None),
+    };
+    Ok(Some(match entry.get("MDUpdateAction").as_str() {
+        Some("New" | "Change" | "Overlay") => Mdp3UpdateKind::Level(LevelUpdate {
+            side,
+            price: required_f64(entry, "MDEntryPx")?,
+            quantity: required_f64(entry, "MDEntrySize")?,
+        }),
+        Some("Delete") => Mdp3UpdateKind::Level(LevelUpdate {
+            side,
+            price: required_f64(entry, "MDEntryPx")?,
+            quantity: 0.0,
+        }),
+        Some("DeleteThru") => Mdp3UpdateKind::ClearSide(side),
+        Some("DeleteFrom") => Mdp3UpdateKind::DeleteFrom {
+            side,
+            level: entry
+                .get("MDPriceLevel")
+                .as_u64()
+                .ok_or_else(|| malformed("DeleteFrom without MDPriceLevel"))?
+                as usize,
+        },
+        other => return Err(malformed(format!("unknown MDUpdateAction {other:?}"))),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Subset of the CME MDP 3.0 templates with their real layouts, shared with the
+    /// `sbe_decoder` fuzz target
+    const SCHEMA: &str = include_str!("testdata/mdp3_subset.xml");
+
+    const TIME: u64 = 1_700_000_000_123_456_789;
+    const SECURITY: i32 = 4242;
+
+    /// Book entry: price in ticks of 1e-9, action and entry type
+    fn book_entry(
+        price: Option<i64>,
+        size: i32,
+        rpt_seq: u32,
+        level: u8,
+        action: u8,
+        kind: u8,
+    ) -> Vec<u8> {
+        let mut entry = price.unwrap_or(i64::MAX).to_le_bytes().to_vec();
+        entry.extend_from_slice(&size.to_le_bytes());
+        entry.extend_from_slice(&SECURITY.to_le_bytes());
+        entry.extend_from_slice(&rpt_seq.to_le_bytes());
+        entry.extend_from_slice(&1i32.to_le_bytes());
+        entry.extend_from_slice(&[level, action, kind]);
+        entry.resize(32, 0);
+        entry
+    }
+
+    /// Size-prefixed message of `template` with `entries` and an empty order group
+    fn message(template: u16, entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for value in [11u16, template, 1, 9] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        body.extend_from_slice(&TIME.to_le_bytes());
+        body.extend_from_slice(&[0x80, 0, 0]);
+        body.extend_from_slice(&32u16.to_le_bytes());
+        body.push(entries.len() as u8);
+        for entry in entries {
+            body.extend_from_slice(entry);
+        }
+        body.extend_from_slice(&[24, 0, 0, 0, 0, 0, 0, 0]);
+        let mut framed = ((body.len() + 2) as u16).to_le_bytes().to_vec();
+        framed.extend_from_slice(&body);
+        framed
+    }
+
+    fn packet(messages: &[Vec<u8>]) -> Vec<u8> {
+        let mut packet = 7u32.to_le_bytes().to_vec();
+        packet.extend_from_slice(&TIME.to_le_bytes());
+        for message in messages {
+            packet.extend_from_slice(message);
+        }
+        packet
+    }
+
+    const PX: i64 = 1_000_000_000;
+
+    #[test]
+    fn test_schema_decodes_fields_and_groups() {
+        let schema = SbeSchema::parse(SCHEMA).unwrap();
+        assert_eq!((schema.id, schema.version), (1, 9));
+        assert_eq!(schema.template_name(46), Some("MDIncrementalRefreshBook46"));
+
+        let msg = message(46, &[book_entry(None, 5, 1, 1, 2, b'1')]);
+        let (decoded, len) = schema.decode(&msg[2..]).unwrap();
+        assert_eq!(len, msg.len() - 2);
+        assert_eq!(decoded.name, "MDIncrementalRefreshBook46");
+        assert_eq!(decoded.body.get("TransactTime"), &SbeValue::UInt(TIME));
+        assert_eq!(
+            decoded.body.get("MatchEventIndicator"),
+            &SbeValue::UInt(0x80)
+        );
+
+        let entry = &decoded.body.group("NoMDEntries")[0];
+        assert_eq!(entry.get("MDEntryPx"), &SbeValue::Null);
+        assert_eq!(entry.get("MDEntrySize"), &SbeValue::Int(5));
+        assert_eq!(entry.get("MDUpdateAction"), &SbeValue::Enum("Delete"));
+        assert_eq!(entry.get("MDEntryType"), &SbeValue::Enum("Offer"));
+        assert_eq!(entry.get("TradeableSize"), &SbeValue::Null);
+        assert!(decoded.body.group("NoOrderIDEntries").is_empty());
+
+        assert!(schema.decode(&msg[2..msg.len() - 10]).is_err());
+        assert!(SbeSchema::parse("<messageSchema><types><type name=\"x\" primitiveType=\"int128\"/></types></messageSchema>").is_err());
+    }
+
+    #[test]
+    fn test_incremental_refresh_drives_book() {
+        let decoder = Mdp3Decoder::from_xml(SCHEMA).unwrap();
+        let bytes = packet(&[
+            message(
+                46,
+                &[
+                    book_entry(Some(100 * PX), 5, 1, 1, 0, b'0'),
+                    book_entry(Some(99 * PX), 7, 2, 2, 0, b'0'),
+                    book_entry(Some(101 * PX), 3, 3, 1, 0, b'1'),
+                    book_entry(Some(100 * PX), 9, 4, 1, 0, b'E'),
+                ],
+            ),
+            message(48, &{
+                let mut trade = book_entry(Some(101 * PX), 2, 5, 0, 0, 0);
+                trade[24] = 1;
+                vec![trade]
+            }),
+            message(
+                46,
+                &[
+                    book_entry(Some(101 * PX), 1, 6, 1, 1, b'1'),
+                    book_entry(None, 0, 7, 2, 4, b'0'),
+                ],
+            ),
+        ]);
+        let decoded = decoder.decode_packet(&bytes).unwrap();
+        assert_eq!(decoded.sequence, 7);
+        assert_eq!(decoded.messages.len(), 3);
+
+        let updates = decoder.updates(&decoded).unwrap();
+        assert_eq!(updates.len(), 6);
+        assert_eq!(updates[0].security_id, SECURITY as i64);
+        assert_eq!(updates[0].transact_time, Timestamp::from_nanos(TIME as i64));
+        assert_eq!(
+            updates[3].kind,
+            Mdp3UpdateKind::Trade(Trade::new(
+                101.0,
+                2.0,
+                Side::Buy,
+                Timestamp::from_nanos(TIME as i64)
+            ))
+        );
+        assert_eq!(updates[3].rpt_seq, 5);
+        assert_eq!(
+            updates[5].kind,
+            Mdp3UpdateKind::DeleteFrom {
+                side: BookSide::Bid,
+                level: 2
+            }
+        );
+
+        let mut book = OrderBook::new("ESZ4".to_string());
+        for update in &updates {
+            update.apply_to(&mut book).unwrap();
+        }
+        assert_eq!(book.best_bid(), Some((100.0, 5.0)));
+        assert_eq!(book.top_bids(10).len(), 1);
+        assert_eq!(book.best_ask(), Some((101.0, 1.0)));
+
+        Mdp3Update {
+            kind: Mdp3UpdateKind::ClearSide(BookSide::Ask),
+            ..updates[0]
+        }
+        .apply_to(&mut book)
+        .unwrap();
+        assert_eq!(book.best_ask(), None);
+
+        let reset = packet(&[message(46, &[book_entry(None, 0, 8, 0, 0, b'J')])]);
+        let reset = decoder
+            .updates(&decoder.decode_packet(&reset).unwrap())
+            .unwrap();
+        assert_eq!(reset[0].kind, Mdp3UpdateKind::Reset);
+        reset[0].apply_to(&mut book).unwrap();
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn test_malformed_packets() {
+        let decoder = Mdp3Decoder::from_xml(SCHEMA).unwrap();
+        assert!(decoder.decode_packet(&[0; 4]).is_err());
+        let mut truncated = packet(&[message(46, &[book_entry(Some(PX), 1, 1, 1, 0, b'0')])]);
+        truncated.pop();
+        assert!(decoder.decode_packet(&truncated).is_err());
+        let unknown = packet(&[message(99, &[])]);
+        assert!(decoder.decode_packet(&unknown).is_err());
+    }
+}
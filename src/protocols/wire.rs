@@ -0,0 +1,370 @@
+//! Compact little-endian binary tick format
+//!
+//! Each record starts with a one byte message type followed by fixed-width fields:
+//!
+//! | type | layout                                                        | size |
+//! |------|---------------------------------------------------------------|------|
+//! | `T`  | side `u8`, timestamp `i64`, price `f64`, quantity `f64`       | 26   |
+//! | `Q`  | timestamp `i64`, bid, bid size, ask, ask size (all `f64`)     | 41   |
+//!
+//! Timestamps are nanoseconds since the Unix epoch.
+//!
+//! Decoding never allocates: [`TickView`] borrows the input and reads fields on
+//! access. Convert with [`TickView::to_tick`] only when an owned value is needed.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::error::{MarketDataError, Result};
+use crate::filter::Tick;
+use crate::orderbook::Quote;
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+pub const TRADE_TAG: u8 = b'T';
+pub const QUOTE_TAG: u8 = b'Q';
+pub const TRADE_LEN: usize = 26;
+pub const QUOTE_LEN: usize = 41;
+
+#[inline]
+fn read_f64(buf: &[u8], offset: usize) -> f64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[offset..offset + 8]);
+    f64::from_le_bytes(bytes)
+}
+
+#[inline]
+fn read_i64(buf: &[u8], offset: usize) -> i64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[offset..offset + 8]);
+    i64::from_le_bytes(bytes)
+}
+
+/// Borrowed view of an encoded trade
+#[derive(Debug, Clone, Copy)]
+pub struct TradeView<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> TradeView<'a> {
+    pub fn side(&self) -> Side {
+        if self.buf[1] == 0 {
+            Side::Buy
+        } else {
+            Side::Sell
+        }
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        Timestamp::from_nanos(read_i64(self.buf, 2))
+    }
+
+    pub fn price(&self) -> f64 {
+        read_f64(self.buf, 10)
+    }
+
+    pub fn quantity(&self) -> f64 {
+        read_f64(self.buf, 18)
+    }
+
+    pub fn to_trade(&self) -> Trade {
+        Trade::new(self.price(), self.quantity(), self.side(), self.timestamp())
+    }
+}
+
+/// Borrowed view of an encoded quote
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteView<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> QuoteView<'a> {
+    pub fn timestamp(&self) -> Timestamp {
+        Timestamp::from_nanos(read_i64(self.buf, 1))
+    }
+
+    pub fn bid_price(&self) -> f64 {
+        read_f64(self.buf, 9)
+    }
+
+    pub fn bid_size(&self) -> f64 {
+        read_f64(self.buf, 17)
+    }
+
+    pub fn ask_price(&self) -> f64 {
+        read_f64(self.buf, 25)
+    }
+
+    pub fn ask_size(&self) -> f64 {
+        read_f64(self.buf, 33)
+    }
+
+    pub fn to_quote(&self) -> Quote {
+        Quote {
+            bid_price: self.bid_price(),
+            bid_size: self.bid_size(),
+            ask_price: self.ask_price(),
+            ask_size: self.ask_size(),
+            timestamp: self.timestamp(),
+        }
+    }
+}
+
+/// Borrowed view of one encoded record
+#[derive(Debug, Clone, Copy)]
+pub enum TickView<'a> {
+    Trade(TradeView<'a>),
+    Quote(QuoteView<'a>),
+}
+
+impl<'a> TickView<'a> {
+    /// Decode the record at the start of `buf`, returning it with its encoded length
+    ///
+    /// Returns `Ok(None)` when `buf` holds only part of a record.
+    pub fn decode(buf: &'a [u8]) -> Result<Option<(TickView<'a>, usize)>> {
+        let Some(&tag) = buf.first() else {
+            return Ok(None);
+        };
+
+        let len = match tag {
+            TRADE_TAG => TRADE_LEN,
+            QUOTE_TAG => QUOTE_LEN,
+            other => {
+                return Err(MarketDataError::Malformed(format!(
+                    "unknown message type {other:#04x}"
+                )));
+            }
+        };
+        if buf.len() < len {
+            return Ok(None);
+        }
+
+        let record = &buf[..len];
+        let view = match tag {
+            TRADE_TAG => {
+                if record[1] > 1 {
+                    return Err(MarketDataError::Malformed(format!(
+                        "invalid side {}",
+                        record[1]
+                    )));
+                }
+                TickView::Trade(TradeView { buf: record })
+            }
+            _ => TickView::Quote(QuoteView { buf: record }),
+        };
+        Ok(Some((view, len)))
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            TickView::Trade(trade) => trade.timestamp(),
+            TickView::Quote(quote) => quote.timestamp(),
+        }
+    }
+
+    pub fn to_tick(&self) -> Tick {
+        match self {
+            TickView::Trade(trade) => Tick::Trade(trade.to_trade()),
+            TickView::Quote(quote) => Tick::Quote(quote.to_quote()),
+        }
+    }
+}
+
+/// Iterator over the records of a contiguous buffer
+///
+/// Stops after the first error; a trailing partial record is reported as malformed.
+pub struct TickReader<'a> {
+    buf: &'a [u8],
+    failed: bool,
+}
+
+impl<'a> TickReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, failed: false }
+    }
+
+    /// Bytes not consumed yet
+    pub fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a> Iterator for TickReader<'a> {
+    type Item = Result<TickView<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.buf.is_empty() {
+            return None;
+        }
+
+        match TickView::decode(self.buf) {
+            Ok(Some((view, len))) => {
+                self.buf = &self.buf[len..];
+                Some(Ok(view))
+            }
+            Ok(None) => {
+                self.failed = true;
+                Some(Err(MarketDataError::Malformed(format!(
+                    "truncated record ({} trailing bytes)",
+                    self.buf.len()
+                ))))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Owned, reference-counted record sliced out of a network or file buffer without copying
+#[derive(Debug, Clone)]
+pub struct RawTick {
+    bytes: Bytes,
+}
+
+impl RawTick {
+    /// Split the next complete record off the front of `buf`
+    ///
+    /// Returns `Ok(None)` (leaving `buf` untouched) until a full record is buffered,
+    /// which makes this suitable for framing a byte stream.
+    pub fn split_from(buf: &mut Bytes) -> Result<Option<RawTick>> {
+        let len = match TickView::decode(buf)? {
+            Some((_, len)) => len,
+            None => return Ok(None),
+        };
+        Ok(Some(RawTick {
+            bytes: buf.split_to(len),
+        }))
+    }
+
+    pub fn view(&self) -> TickView<'_> {
+        TickView::decode(&self.bytes)
+            .ok()
+            .flatten()
+            .map(|(view, _)| view)
+            .expect("validated when split")
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+pub fn encode_trade(trade: &Trade, out: &mut BytesMut) {
+    out.reserve(TRADE_LEN);
+    out.put_u8(TRADE_TAG);
+    out.put_u8(match trade.side {
+        Side::Buy => 0,
+        Side::Sell => 1,
+    });
+    out.put_i64_le(trade.timestamp.as_nanos());
+    out.put_f64_le(trade.price);
+    out.put_f64_le(trade.quantity);
+}
+
+pub fn encode_quote(quote: &Quote, out: &mut BytesMut) {
+    out.reserve(QUOTE_LEN);
+    out.put_u8(QUOTE_TAG);
+    out.put_i64_le(quote.timestamp.as_nanos());
+    out.put_f64_le(quote.bid_price);
+    out.put_f64_le(quote.bid_size);
+    out.put_f64_le(quote.ask_price);
+    out.put_f64_le(quote.ask_size);
+}
+
+/// Skip `count` complete records at the front of `buf`, e.g. to resume a replay
+pub fn skip_records(buf: &mut Bytes, count: usize) -> Result<usize> {
+    let mut skipped = 0;
+    while skipped < count {
+        match TickView::decode(buf)? {
+            Some((_, len)) => buf.advance(len),
+            None => break,
+        }
+        skipped += 1;
+    }
+    Ok(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (Trade, Quote) {
+        let trade = Trade::new(
+            50_000.5,
+            0.25,
+            Side::Sell,
+            Timestamp::from_millis(1_700_000_000_000),
+        );
+        let quote = Quote {
+            bid_price: 50_000.0,
+            bid_size: 1.5,
+            ask_price: 50_001.0,
+            ask_size: 2.0,
+            timestamp: Timestamp::from_millis(1_700_000_000_001),
+        };
+        (trade, quote)
+    }
+
+    #[test]
+    fn test_round_trip_through_views() {
+        let (trade, quote) = sample();
+        let mut buf = BytesMut::new();
+        encode_trade(&trade, &mut buf);
+        encode_quote(&quote, &mut buf);
+        assert_eq!(buf.len(), TRADE_LEN + QUOTE_LEN);
+
+        let ticks: Vec<TickView> = TickReader::new(&buf).collect::<Result<_>>().unwrap();
+        assert_eq!(ticks.len(), 2);
+        match ticks[0] {
+            TickView::Trade(view) => {
+                assert_eq!(view.price(), 50_000.5);
+                assert_eq!(view.side(), Side::Sell);
+                assert_eq!(view.to_trade(), trade);
+            }
+            _ => panic!("expected trade"),
+        }
+        assert_eq!(ticks[1].to_tick(), Tick::Quote(quote));
+    }
+
+    #[test]
+    fn test_stream_framing_with_bytes() {
+        let (trade, quote) = sample();
+        let mut encoded = BytesMut::new();
+        encode_trade(&trade, &mut encoded);
+        encode_quote(&quote, &mut encoded);
+        let encoded = encoded.freeze();
+
+        // Only part of the quote has arrived
+        let mut buf = encoded.slice(..TRADE_LEN + 10);
+        let first = RawTick::split_from(&mut buf).unwrap().unwrap();
+        assert_eq!(first.view().timestamp(), trade.timestamp);
+        assert!(RawTick::split_from(&mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 10);
+
+        // The split record shares the original allocation
+        assert_eq!(first.as_bytes().as_ptr(), encoded.as_ptr());
+
+        let mut rest = encoded.clone();
+        assert_eq!(skip_records(&mut rest, 5).unwrap(), 2);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(matches!(
+            TickView::decode(b"X123"),
+            Err(MarketDataError::Malformed(_))
+        ));
+
+        let mut buf = BytesMut::new();
+        encode_trade(&sample().0, &mut buf);
+        buf[1] = 7;
+        assert!(TickView::decode(&buf).is_err());
+
+        let mut reader = TickReader::new(&buf[..5]);
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+        assert!(TickView::decode(&[]).unwrap().is_none());
+    }
+}
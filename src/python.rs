@@ -0,0 +1,534 @@
+//! Python bindings, enabled with the `python` feature
+//!
+//! Built as the `market_data` extension module with maturin (see `pyproject.toml`).
+//! Wraps the order book, the streaming indicators, the candle aggregator and the
+//! batch `*_series` functions; prices are floats, timestamps integer milliseconds and
+//! book levels `(price, quantity)` tuples. Invalid arguments raise `ValueError`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::aggregation::CandleBuilder;
+use crate::candles;
+use crate::error::MarketDataError;
+use crate::indicators::{self, Indicator, RsiSmoothing};
+use crate::orderbook::{self, PriceLevel};
+use crate::time::Timestamp;
+
+impl From<MarketDataError> for PyErr {
+    fn from(error: MarketDataError) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+fn price_levels(levels: Vec<(f64, f64)>) -> Vec<PriceLevel> {
+    levels
+        .into_iter()
+        .map(|(price, quantity)| PriceLevel { price, quantity })
+        .collect()
+}
+
+fn level_tuples(levels: Vec<PriceLevel>) -> Vec<(f64, f64)> {
+    levels
+        .into_iter()
+        .map(|level| (level.price, level.quantity))
+        .collect()
+}
+
+/// Price-level order book of one symbol
+#[pyclass(name = "OrderBook")]
+pub struct PyOrderBook {
+    book: orderbook::OrderBook,
+}
+
+#[pymethods]
+impl PyOrderBook {
+    #[new]
+    #[pyo3(signature = (symbol, strict = false))]
+    fn new(symbol: String, strict: bool) -> PyResult<Self> {
+        Ok(Self {
+            book: orderbook::OrderBook::try_new(symbol)?.with_strict(strict),
+        })
+    }
+
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.book.symbol
+    }
+
+    #[getter]
+    fn sequence(&self) -> Option<u64> {
+        self.book.sequence
+    }
+
+    /// Set a bid level at `timestamp` (epoch milliseconds); quantity 0 removes it
+    fn update_bid(&mut self, price: f64, quantity: f64, timestamp: i64) -> PyResult<()> {
+        Ok(self
+            .book
+            .update_bid(price, quantity, Timestamp::from_millis(timestamp))?)
+    }
+
+    /// Set an ask level at `timestamp` (epoch milliseconds); quantity 0 removes it
+    fn update_ask(&mut self, price: f64, quantity: f64, timestamp: i64) -> PyResult<()> {
+        Ok(self
+            .book
+            .update_ask(price, quantity, Timestamp::from_millis(timestamp))?)
+    }
+
+    /// Replace the whole book with a snapshot taken at `timestamp` (epoch milliseconds)
+    #[pyo3(signature = (bids, asks, timestamp, sequence = 0))]
+    fn apply_snapshot(
+        &mut self,
+        bids: Vec<(f64, f64)>,
+        asks: Vec<(f64, f64)>,
+        timestamp: i64,
+        sequence: u64,
+    ) -> PyResult<()> {
+        Ok(self.book.apply_snapshot(
+            &price_levels(bids),
+            &price_levels(asks),
+            sequence,
+            Timestamp::from_millis(timestamp),
+        )?)
+    }
+
+    /// Apply `(side, price, quantity)` changes atomically, `side` being `"bid"` or `"ask"`
+    fn apply_updates(&mut self, updates: Vec<(String, f64, f64)>, timestamp: i64) -> PyResult<()> {
+        let updates = updates
+            .into_iter()
+            .map(|(side, price, quantity)| match side.as_str() {
+                "bid" => Ok(orderbook::LevelUpdate::bid(price, quantity)),
+                "ask" => Ok(orderbook::LevelUpdate::ask(price, quantity)),
+                _ => Err(PyValueError::new_err(format!("unknown side {side:?}"))),
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        self.book
+            .apply_updates(&updates, Timestamp::from_millis(timestamp))?;
+        Ok(())
+    }
+
+    fn best_bid(&self) -> Option<(f64, f64)> {
+        self.book.best_bid()
+    }
+
+    fn best_ask(&self) -> Option<(f64, f64)> {
+        self.book.best_ask()
+    }
+
+    fn mid_price(&self) -> Option<f64> {
+        self.book.mid_price()
+    }
+
+    fn spread(&self) -> Option<f64> {
+        self.book.spread()
+    }
+
+    fn weighted_mid_price(&self) -> Option<f64> {
+        self.book.weighted_mid_price()
+    }
+
+    fn volume_imbalance(&self) -> f64 {
+        self.book.volume_imbalance()
+    }
+
+    /// Best `n` bids, highest first
+    fn top_bids(&self, n: usize) -> Vec<(f64, f64)> {
+        level_tuples(self.book.top_bids(n))
+    }
+
+    /// Best `n` asks, lowest first
+    fn top_asks(&self, n: usize) -> Vec<(f64, f64)> {
+        level_tuples(self.book.top_asks(n))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OrderBook({:?}, best_bid={:?}, best_ask={:?})",
+            self.book.symbol,
+            self.book.best_bid(),
+            self.book.best_ask()
+        )
+    }
+}
+
+/// Wrap single-output indicators fed with floats
+macro_rules! py_indicator {
+    ($wrapper:ident, $name:literal, $inner:ty, ($($arg:ident: $ty:ty),*)) => {
+        #[pyclass(name = $name)]
+        pub struct $wrapper {
+            inner: $inner,
+        }
+
+        #[pymethods]
+        impl $wrapper {
+            #[new]
+            fn new($($arg: $ty),*) -> PyResult<Self> {
+                Ok(Self {
+                    inner: <$inner>::try_new($($arg),*)?,
+                })
+            }
+
+            /// Feed one value; `None` while warming up
+            fn update(&mut self, value: f64) -> Option<f64> {
+                self.inner.update(value)
+            }
+
+            /// Feed a list, returning one output per input
+            fn compute(&mut self, values: Vec<f64>) -> Vec<Option<f64>> {
+                self.inner.compute_series(&values)
+            }
+
+            fn current(&self) -> Option<f64> {
+                Indicator::current(&self.inner)
+            }
+
+            fn reset(&mut self) {
+                Indicator::reset(&mut self.inner);
+            }
+        }
+    };
+}
+
+py_indicator!(PySma, "SMA", indicators::SMA, (period: usize));
+py_indicator!(PyEma, "EMA", indicators::EMA, (period: usize));
+py_indicator!(PyWma, "WMA", indicators::WMA, (period: usize));
+
+/// RSI with simple smoothing, or Wilder's as used by TradingView and TA-Lib
+#[pyclass(name = "RSI")]
+pub struct PyRsi {
+    inner: indicators::RSI,
+}
+
+#[pymethods]
+impl PyRsi {
+    #[new]
+    #[pyo3(signature = (period = 14, wilder = false))]
+    fn new(period: usize, wilder: bool) -> PyResult<Self> {
+        let inner = if wilder {
+            indicators::RSI::try_wilder(period)?
+        } else {
+            indicators::RSI::try_new(period)?
+        };
+        Ok(Self { inner })
+    }
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.inner.update(value)
+    }
+
+    fn compute(&mut self, values: Vec<f64>) -> Vec<Option<f64>> {
+        self.inner.compute_series(&values)
+    }
+
+    fn current(&self) -> Option<f64> {
+        Indicator::current(&self.inner)
+    }
+
+    fn reset(&mut self) {
+        Indicator::reset(&mut self.inner);
+    }
+}
+
+/// Bollinger Bands producing `(upper, middle, lower)`
+#[pyclass(name = "BollingerBands")]
+pub struct PyBollingerBands {
+    inner: indicators::BollingerBands,
+}
+
+#[pymethods]
+impl PyBollingerBands {
+    #[new]
+    #[pyo3(signature = (period = 20, std_dev = 2.0))]
+    fn new(period: usize, std_dev: f64) -> PyResult<Self> {
+        Ok(Self {
+            inner: indicators::BollingerBands::try_new(period, std_dev)?,
+        })
+    }
+
+    fn update(&mut self, value: f64) -> Option<(f64, f64, f64)> {
+        self.inner.update(value)
+    }
+
+    fn compute(&mut self, values: Vec<f64>) -> Vec<Option<(f64, f64, f64)>> {
+        self.inner.compute_series(&values)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        Indicator::current(&self.inner)
+    }
+
+    fn reset(&mut self) {
+        Indicator::reset(&mut self.inner);
+    }
+}
+
+/// MACD producing `(macd, signal, histogram)`
+#[pyclass(name = "MACD")]
+pub struct PyMacd {
+    inner: indicators::MACD,
+}
+
+#[pymethods]
+impl PyMacd {
+    #[new]
+    #[pyo3(signature = (fast_period = 12, slow_period = 26, signal_period = 9))]
+    fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> PyResult<Self> {
+        Ok(Self {
+            inner: indicators::MACD::try_new(fast_period, slow_period, signal_period)?,
+        })
+    }
+
+    fn update(&mut self, value: f64) -> Option<(f64, f64, f64)> {
+        self.inner.update(value)
+    }
+
+    fn compute(&mut self, values: Vec<f64>) -> Vec<Option<(f64, f64, f64)>> {
+        self.inner.compute_series(&values)
+    }
+
+    fn current(&self) -> Option<(f64, f64, f64)> {
+        Indicator::current(&self.inner)
+    }
+
+    fn reset(&mut self) {
+        Indicator::reset(&mut self.inner);
+    }
+}
+
+/// OHLCV bar; `timestamp` is the interval start in milliseconds
+#[pyclass(name = "Candle", get_all, frozen, eq)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyCandle {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: f64,
+    timestamp: i64,
+}
+
+impl From<candles::Candle> for PyCandle {
+    fn from(candle: candles::Candle) -> Self {
+        Self {
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume: candle.volume,
+            timestamp: candle.timestamp.as_millis(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyCandle {
+    fn __repr__(&self) -> String {
+        format!(
+            "Candle(timestamp={}, open={}, high={}, low={}, close={}, volume={})",
+            self.timestamp, self.open, self.high, self.low, self.close, self.volume
+        )
+    }
+}
+
+fn py_candles(candles: Vec<candles::Candle>) -> Vec<PyCandle> {
+    candles.into_iter().map(PyCandle::from).collect()
+}
+
+/// Time-bucketed candles built from trades
+#[pyclass(name = "CandleAggregator")]
+pub struct PyCandleAggregator {
+    builder: CandleBuilder,
+}
+
+#[pymethods]
+impl PyCandleAggregator {
+    #[new]
+    fn new(interval_ms: i64) -> PyResult<Self> {
+        Ok(Self {
+            builder: CandleBuilder::try_new(interval_ms)?,
+        })
+    }
+
+    /// Add a trade, returning the candles it completed
+    fn add(&mut self, price: f64, volume: f64, timestamp: i64) -> Vec<PyCandle> {
+        py_candles(
+            self.builder
+                .add(price, volume, Timestamp::from_millis(timestamp)),
+        )
+    }
+
+    /// Add many `(price, volume, timestamp)` trades, returning the completed candles
+    fn add_many(&mut self, trades: Vec<(f64, f64, i64)>) -> Vec<PyCandle> {
+        let mut completed = Vec::new();
+        for (price, volume, timestamp) in trades {
+            completed.extend(
+                self.builder
+                    .add(price, volume, Timestamp::from_millis(timestamp)),
+            );
+        }
+        py_candles(completed)
+    }
+
+    /// The candle still being built
+    fn current(&self) -> Option<PyCandle> {
+        self.builder.current().map(PyCandle::from)
+    }
+
+    /// Close and return the candle being built
+    fn flush(&mut self) -> Option<PyCandle> {
+        self.builder.flush().map(PyCandle::from)
+    }
+}
+
+/// Simple moving average of a whole series
+#[pyfunction]
+fn sma(values: Vec<f64>, period: usize) -> PyResult<Vec<Option<f64>>> {
+    Ok(indicators::sma_series(&values, period)?)
+}
+
+/// Exponential moving average of a whole series
+#[pyfunction]
+fn ema(values: Vec<f64>, period: usize) -> PyResult<Vec<Option<f64>>> {
+    Ok(indicators::ema_series(&values, period)?)
+}
+
+/// RSI of a whole series, with the smoothing of [`PyRsi`]
+#[pyfunction]
+#[pyo3(signature = (values, period = 14, wilder = false))]
+fn rsi(values: Vec<f64>, period: usize, wilder: bool) -> PyResult<Vec<Option<f64>>> {
+    let smoothing = if wilder {
+        RsiSmoothing::Wilder
+    } else {
+        RsiSmoothing::Simple
+    };
+    Ok(indicators::rsi_series(&values, period, smoothing)?)
+}
+
+/// `(upper, middle, lower)` columns of Bollinger Bands over a whole series
+#[pyfunction]
+#[pyo3(signature = (values, period = 20, std_dev = 2.0))]
+fn bollinger(values: Vec<f64>, period: usize, std_dev: f64) -> PyResult<[Vec<Option<f64>>; 3]> {
+    Ok(indicators::unzip3(&indicators::bollinger_series(
+        &values, period, std_dev,
+    )?))
+}
+
+/// `(macd, signal, histogram)` columns over a whole series
+#[pyfunction]
+#[pyo3(signature = (values, fast_period = 12, slow_period = 26, signal_period = 9))]
+fn macd(
+    values: Vec<f64>,
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> PyResult<[Vec<Option<f64>>; 3]> {
+    Ok(indicators::unzip3(&indicators::macd_series(
+        &values,
+        fast_period,
+        slow_period,
+        signal_period,
+    )?))
+}
+
+#[pymodule]
+#[pyo3(name = "market_data")]
+fn market_data_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyOrderBook>()?;
+    m.add_class::<PySma>()?;
+    m.add_class::<PyEma>()?;
+    m.add_class::<PyWma>()?;
+    m.add_class::<PyRsi>()?;
+    m.add_class::<PyBollingerBands>()?;
+    m.add_class::<PyMacd>()?;
+    m.add_class::<PyCandle>()?;
+    m.add_class::<PyCandleAggregator>()?;
+    m.add_function(wrap_pyfunction!(sma, m)?)?;
+    m.add_function(wrap_pyfunction!(ema, m)?)?;
+    m.add_function(wrap_pyfunction!(rsi, m)?)?;
+    m.add_function(wrap_pyfunction!(bollinger, m)?)?;
+    m.add_function(wrap_pyfunction!(macd, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+    use std::ffi::CString;
+
+    /// Run Python `code` with the module imported as `md`
+    fn run(code: &str) -> PyResult<()> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "market_data")?;
+            market_data_module(&module)?;
+            let globals = PyDict::new(py);
+            globals.set_item("md", module)?;
+            let code = CString::new(code).expect("no NUL bytes");
+            py.run(&code, Some(&globals), None)
+        })
+    }
+
+    #[test]
+    fn test_order_book_from_python() {
+        run(r#"
+book = md.OrderBook("BTCUSD")
+book.apply_snapshot([(100.0, 1.0), (99.0, 2.0)], [(101.0, 1.5)], timestamp=0, sequence=7)
+book.apply_updates([("bid", 100.5, 0.5), ("ask", 101.0, 0.0), ("ask", 102.0, 3.0)], 1_000)
+assert book.best_bid() == (100.5, 0.5)
+assert book.best_ask() == (102.0, 3.0)
+assert book.top_bids(2) == [(100.5, 0.5), (100.0, 1.0)]
+assert book.mid_price() == 101.25
+assert book.sequence == 7
+try:
+    book.update_bid(-1.0, 1.0, 0)
+    raise AssertionError("negative price accepted")
+except ValueError:
+    pass
+"#)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_indicators_and_batch_functions() {
+        run(r#"
+values = [float(v) for v in range(1, 31)]
+sma = md.SMA(3)
+assert sma.compute(values[:4]) == [None, None, 2.0, 3.0]
+assert sma.current() == 3.0
+assert md.sma(values, 3)[:4] == [None, None, 2.0, 3.0]
+
+prices = [100.0 + (i % 7) - (i % 3) for i in range(40)]
+assert md.RSI(14).compute(prices) == md.rsi(prices, 14)
+assert md.RSI(14, wilder=True).compute(prices) == md.rsi(prices, 14, wilder=True)
+
+upper, middle, lower = md.bollinger(values, 5)
+assert middle[4] == 3.0 and upper[4] > middle[4] > lower[4]
+assert md.BollingerBands(5).compute(values)[4][1] == 3.0
+
+macd, signal, histogram = md.macd(values, 3, 6, 2)
+assert len(macd) == len(signal) == len(histogram)
+
+try:
+    md.EMA(0)
+    raise AssertionError("zero period accepted")
+except ValueError:
+    pass
+"#)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_candle_aggregator() {
+        run(r#"
+agg = md.CandleAggregator(60_000)
+assert agg.add_many([(100.0, 1.0, 0), (105.0, 2.0, 10_000), (99.0, 1.0, 59_000)]) == []
+closed = agg.add(101.0, 1.0, 60_000)
+assert len(closed) == 1
+bar = closed[0]
+assert (bar.open, bar.high, bar.low, bar.close, bar.volume) == (100.0, 105.0, 99.0, 99.0, 4.0)
+assert agg.flush().timestamp == 60_000
+"#)
+        .unwrap();
+    }
+}
@@ -0,0 +1,397 @@
+//! Replay of recorded sessions in exchange-time order
+//!
+//! Recordings are JSON lines of serialized [`MarketEvent`]s, or CSV files with the
+//! columns `timestamp,symbol,kind,side,price,quantity`: `kind` is `trade` (side `buy`
+//! or `sell`) or `book` (side `bid` or `ask`, zero quantity deleting the level), and
+//! timestamps are milliseconds since the Unix epoch. Book rows sharing a symbol and
+//! timestamp form one atomic delta.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::clock::SimulationClock;
+use crate::error::{ensure, MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::orderbook::{BookSide, LevelUpdate};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Exchange name given to events loaded from CSV
+pub const CSV_EXCHANGE: &str = "replay";
+
+/// Pacing of [`Replay::run`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// No waiting between events
+    AsFastAsPossible,
+    /// Recorded gaps between events are reproduced in wall-clock time
+    Realtime,
+    /// Recorded gaps are divided by the factor, e.g. 10.0 for ten times faster
+    Accelerated(f64),
+}
+
+impl ReplaySpeed {
+    /// Wall-clock delay for a recorded gap, `None` when not pacing
+    fn scale(&self, gap: Duration) -> Option<Duration> {
+        match *self {
+            ReplaySpeed::AsFastAsPossible => None,
+            ReplaySpeed::Realtime => Some(gap),
+            ReplaySpeed::Accelerated(factor) => Some(gap.div_f64(factor)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    timestamp: i64,
+    symbol: String,
+    kind: String,
+    side: String,
+    price: f64,
+    quantity: f64,
+}
+
+fn malformed(line: usize, error: impl std::fmt::Display) -> MarketDataError {
+    MarketDataError::Malformed(format!("line {line}: {error}"))
+}
+
+/// Recorded events sorted by exchange time
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    events: Vec<MarketEvent>,
+    clock: Option<Arc<SimulationClock>>,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance `clock` to each event's exchange time as it is replayed, so clock-driven
+    /// components observe recorded rather than wall-clock time
+    pub fn with_clock(mut self, clock: Arc<SimulationClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Load a `.csv` file, or JSON lines for any other extension
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+        {
+            self.load_csv(file)
+        } else {
+            self.load_jsonl(file)
+        }
+    }
+
+    /// Add one serialized [`MarketEvent`] per non-empty line, returning the count
+    pub fn load_jsonl(&mut self, reader: impl BufRead) -> Result<usize> {
+        let mut events = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(&line).map_err(|e| malformed(index + 1, e))?);
+        }
+        Ok(self.extend(events))
+    }
+
+    /// Add the rows of a CSV recording with a header line, returning the event count
+    pub fn load_csv(&mut self, reader: impl Read) -> Result<usize> {
+        let mut events: Vec<MarketEvent> = Vec::new();
+        let mut csv = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        for (index, row) in csv.deserialize::<CsvRow>().enumerate() {
+            // Line numbers count the header
+            let line = index + 2;
+            let row = row.map_err(|e| malformed(line, e))?;
+            let time = Timestamp::from_millis(row.timestamp);
+
+            let data = match (row.kind.as_str(), row.side.as_str()) {
+                ("trade", side) => {
+                    let side = match side {
+                        "buy" => Side::Buy,
+                        "sell" => Side::Sell,
+                        _ => return Err(malformed(line, format!("invalid trade side {side:?}"))),
+                    };
+                    let timestamp = Timestamp::from_millis(row.timestamp);
+                    MarketData::Trade(Trade::new(row.price, row.quantity, side, timestamp))
+                }
+                ("book", side) => {
+                    let side = match side {
+                        "bid" => BookSide::Bid,
+                        "ask" => BookSide::Ask,
+                        _ => return Err(malformed(line, format!("invalid book side {side:?}"))),
+                    };
+                    let update = LevelUpdate {
+                        side,
+                        price: row.price,
+                        quantity: row.quantity,
+                    };
+                    if let Some(MarketEvent {
+                        data: MarketData::BookDelta { updates, .. },
+                        ..
+                    }) = events
+                        .last_mut()
+                        .filter(|e| e.exchange_time == time && e.symbol == row.symbol)
+                    {
+                        updates.push(update);
+                        continue;
+                    }
+                    MarketData::BookDelta {
+                        updates: vec![update],
+                        first_sequence: None,
+                    }
+                }
+                (kind, _) => return Err(malformed(line, format!("invalid kind {kind:?}"))),
+            };
+            events.push(MarketEvent::new(CSV_EXCHANGE, &row.symbol, time, data));
+        }
+        Ok(self.extend(events))
+    }
+
+    /// Merge events into the timeline; events with equal times keep their load order
+    pub fn extend(&mut self, events: impl IntoIterator<Item = MarketEvent>) -> usize {
+        let before = self.events.len();
+        self.events.extend(events);
+        self.events.sort_by_key(|e| e.exchange_time);
+        self.events.len() - before
+    }
+
+    pub fn events(&self) -> &[MarketEvent] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Recorded time span from the first to the last event
+    pub fn duration(&self) -> Duration {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) => last
+                .exchange_time
+                .saturating_duration_since(first.exchange_time),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Feed every event to `handler` in order, blocking to honour `speed`
+    ///
+    /// Pacing is measured from the start of the replay, so time spent in the handler
+    /// does not accumulate as drift. Stops at the first handler error.
+    pub fn run(
+        &self,
+        speed: ReplaySpeed,
+        mut handler: impl FnMut(&MarketEvent) -> Result<()>,
+    ) -> Result<usize> {
+        if let ReplaySpeed::Accelerated(factor) = speed {
+            ensure(
+                factor.is_finite() && factor > 0.0,
+                "acceleration must be positive",
+            )?;
+        }
+        let Some(first) = self.events.first().map(|e| e.exchange_time) else {
+            return Ok(0);
+        };
+
+        let started = Instant::now();
+        for event in &self.events {
+            if let Some(due) = speed.scale(event.exchange_time.saturating_duration_since(first)) {
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+            if let Some(clock) = &self.clock {
+                clock.observe(event.exchange_time);
+            }
+            handler(event)?;
+        }
+        Ok(self.events.len())
+    }
+}
+
+/// Paced replay feeding the async pipeline, see [`Replay::into_source`]
+#[cfg(feature = "streaming")]
+pub struct ReplaySource {
+    replay: Replay,
+    speed: ReplaySpeed,
+}
+
+#[cfg(feature = "streaming")]
+impl Replay {
+    /// [`Source`](crate::pipeline::stream::Source) emitting the events paced like
+    /// [`run`](Self::run)
+    pub fn into_source(self, speed: ReplaySpeed) -> ReplaySource {
+        ReplaySource {
+            replay: self,
+            speed,
+        }
+    }
+}
+
+#[cfg(feature = "streaming")]
+#[async_trait::async_trait]
+impl crate::pipeline::stream::Source for ReplaySource {
+    async fn run(self: Box<Self>, out: crate::pipeline::stream::EventSender) -> Result<()> {
+        if let ReplaySpeed::Accelerated(factor) = self.speed {
+            ensure(
+                factor.is_finite() && factor > 0.0,
+                "acceleration must be positive",
+            )?;
+        }
+        let Some(first) = self.replay.events.first().map(|e| e.exchange_time) else {
+            return Ok(());
+        };
+
+        let started = tokio::time::Instant::now();
+        for event in self.replay.events {
+            if let Some(due) = self
+                .speed
+                .scale(event.exchange_time.saturating_duration_since(first))
+            {
+                tokio::time::sleep_until(started + due).await;
+            }
+            if let Some(clock) = &self.replay.clock {
+                clock.observe(event.exchange_time);
+            }
+            if out.send(event).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Replay {
+    type Item = MarketEvent;
+    type IntoIter = std::vec::IntoIter<MarketEvent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::orderbook::OrderBookManager;
+
+    const CSV: &str = "timestamp,symbol,kind,side,price,quantity
+2000,BTC,trade,buy,101.0,0.5
+1000,BTC,book,bid,100.0,2.0
+1000,BTC,book,ask,101.0,1.0
+1500,ETH,book,bid,10.0,3.0
+";
+
+    #[test]
+    fn test_csv_rows_sorted_and_batched() {
+        let mut replay = Replay::new();
+        assert_eq!(replay.load_csv(CSV.as_bytes()).unwrap(), 3);
+
+        let times: Vec<i64> = replay
+            .events()
+            .iter()
+            .map(|e| e.exchange_time.as_millis())
+            .collect();
+        assert_eq!(times, vec![1000, 1500, 2000]);
+        assert!(
+            matches!(&replay.events()[0].data, MarketData::BookDelta { updates, .. } if updates.len() == 2)
+        );
+        assert_eq!(replay.duration(), Duration::from_secs(1));
+
+        let mut books = OrderBookManager::new();
+        let mut trades = 0;
+        let clock = Arc::new(SimulationClock::new(Timestamp::from_millis(0)));
+        let replay = replay.with_clock(Arc::clone(&clock));
+        replay
+            .run(ReplaySpeed::AsFastAsPossible, |event| {
+                if let MarketData::Trade(_) = event.data {
+                    trades += 1;
+                }
+                books.apply_event(event).map(|_| ())
+            })
+            .unwrap();
+
+        assert_eq!(trades, 1);
+        assert_eq!(books.book("BTC").unwrap().mid_price(), Some(100.5));
+        assert_eq!(clock.now(), Timestamp::from_millis(2000));
+    }
+
+    #[test]
+    fn test_jsonl_round_trip_and_errors() {
+        let mut source = Replay::new();
+        source.load_csv(CSV.as_bytes()).unwrap();
+        let jsonl: String = source
+            .events()
+            .iter()
+            .map(|e| serde_json::to_string(e).unwrap() + "\n\n")
+            .collect();
+
+        let mut replay = Replay::new();
+        assert_eq!(replay.load_jsonl(jsonl.as_bytes()).unwrap(), 3);
+        assert_eq!(replay.events(), source.events());
+
+        let err = Replay::new().load_jsonl("{}\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+        let bad = "timestamp,symbol,kind,side,price,quantity\n1,X,quote,bid,1,1\n";
+        let err = Replay::new().load_csv(bad.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[tokio::test]
+    async fn test_replay_source() {
+        use crate::pipeline::stream::StreamPipeline;
+
+        let mut replay = Replay::new();
+        replay.load_csv(CSV.as_bytes()).unwrap();
+        let books = Arc::new(std::sync::Mutex::new(OrderBookManager::new()));
+
+        let report = StreamPipeline::new(4)
+            .with_source(replay.into_source(ReplaySpeed::Accelerated(100.0)))
+            .with_processor(Arc::clone(&books))
+            .spawn()
+            .join()
+            .await
+            .unwrap();
+        assert_eq!(report.events, 3);
+        assert_eq!(books.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_paced_replay() {
+        let mut replay = Replay::new();
+        replay.load_csv(CSV.as_bytes()).unwrap();
+
+        // One recorded second at 20x speed takes about 50 ms
+        let started = Instant::now();
+        assert_eq!(
+            replay
+                .run(ReplaySpeed::Accelerated(20.0), |_| Ok(()))
+                .unwrap(),
+            3
+        );
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(replay
+            .run(ReplaySpeed::Accelerated(0.0), |_| Ok(()))
+            .is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+use crate::candles::Candle;
+use crate::numeric::KahanSum;
+
+/// Return convention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReturnKind {
+    /// `p1 / p0 - 1`
+    Simple,
+    /// `ln(p1 / p0)`
+    Log,
+}
+
+impl ReturnKind {
+    /// Return between two prices, `None` unless both are positive and finite
+    pub fn between(&self, from: f64, to: f64) -> Option<f64> {
+        if !(from > 0.0 && to > 0.0 && from.is_finite() && to.is_finite()) {
+            return None;
+        }
+
+        Some(match self {
+            ReturnKind::Simple => to / from - 1.0,
+            ReturnKind::Log => (to / from).ln(),
+        })
+    }
+
+    /// Express a return of this kind in another convention
+    pub fn convert(&self, value: f64, to: ReturnKind) -> f64 {
+        match (self, to) {
+            (ReturnKind::Simple, ReturnKind::Log) => value.ln_1p(),
+            (ReturnKind::Log, ReturnKind::Simple) => value.exp_m1(),
+            _ => value,
+        }
+    }
+}
+
+/// Returns between consecutive prices; pairs with a non-positive price are skipped
+pub fn returns(prices: &[f64], kind: ReturnKind) -> Vec<f64> {
+    prices
+        .windows(2)
+        .filter_map(|w| kind.between(w[0], w[1]))
+        .collect()
+}
+
+/// Close-to-close returns of a candle series
+pub fn candle_returns(candles: &[Candle], kind: ReturnKind) -> Vec<f64> {
+    candles
+        .windows(2)
+        .filter_map(|w| kind.between(w[0].close, w[1].close))
+        .collect()
+}
+
+/// Running total return: compounded for simple returns, summed for log returns
+pub fn cumulative(returns: &[f64], kind: ReturnKind) -> Vec<f64> {
+    let mut total = 0.0;
+    returns
+        .iter()
+        .map(|r| {
+            total = match kind {
+                ReturnKind::Simple => (1.0 + total) * (1.0 + r) - 1.0,
+                ReturnKind::Log => total + r,
+            };
+            total
+        })
+        .collect()
+}
+
+/// Sample `(timestamp, price)` ticks onto a regular grid of `interval` milliseconds
+///
+/// Each output point is the last price at or before the end of its interval, stamped
+/// with the interval start. Intervals without ticks carry the previous price forward
+/// so that gaps produce zero returns instead of disappearing.
+pub fn resample(ticks: &[(i64, f64)], interval: i64) -> Vec<(i64, f64)> {
+    assert!(interval > 0, "interval must be positive");
+
+    let Some(&(first, _)) = ticks.first() else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<(i64, f64)> = Vec::new();
+    let mut bucket = first.div_euclid(interval) * interval;
+    let mut last = None;
+
+    for &(timestamp, price) in ticks {
+        while timestamp >= bucket + interval {
+            if let Some(price) = last {
+                result.push((bucket, price));
+            }
+            bucket += interval;
+        }
+        last = Some(price);
+    }
+    if let Some(price) = last {
+        result.push((bucket, price));
+    }
+
+    result
+}
+
+/// Returns of ticks resampled onto a regular grid
+pub fn resampled_returns(ticks: &[(i64, f64)], interval: i64, kind: ReturnKind) -> Vec<(i64, f64)> {
+    let sampled = resample(ticks, interval);
+    sampled
+        .windows(2)
+        .filter_map(|w| kind.between(w[0].1, w[1].1).map(|r| (w[1].0, r)))
+        .collect()
+}
+
+/// Streaming returns with rolling sum, mean and volatility over the last `window` returns
+pub struct RollingReturns {
+    kind: ReturnKind,
+    window: usize,
+    last_price: Option<f64>,
+    values: VecDeque<f64>,
+    sum: KahanSum,
+    sum_sq: KahanSum,
+}
+
+impl RollingReturns {
+    pub fn new(kind: ReturnKind, window: usize) -> Self {
+        Self {
+            kind,
+            window,
+            last_price: None,
+            values: VecDeque::with_capacity(window),
+            sum: KahanSum::new(),
+            sum_sq: KahanSum::new(),
+        }
+    }
+
+    /// Push a price and get the return from the previous one
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let value = self
+            .last_price
+            .and_then(|prev| self.kind.between(prev, price));
+        if price > 0.0 && price.is_finite() {
+            self.last_price = Some(price);
+        }
+
+        let value = value?;
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        if self.values.len() > self.window {
+            if let Some(old) = self.values.pop_front() {
+                self.sum -= old;
+                self.sum_sq -= old * old;
+            }
+        }
+
+        Some(value)
+    }
+
+    pub fn update_candle(&mut self, candle: &Candle) -> Option<f64> {
+        self.update(candle.close)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.values.len() == self.window
+    }
+
+    /// Total return over the window (compounded for simple returns)
+    pub fn cumulative(&self) -> Option<f64> {
+        if !self.is_ready() {
+            return None;
+        }
+
+        Some(match self.kind {
+            ReturnKind::Simple => self.values.iter().fold(1.0, |acc, r| acc * (1.0 + r)) - 1.0,
+            ReturnKind::Log => self.sum.value(),
+        })
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        self.is_ready().then(|| self.sum.value() / self.window as f64)
+    }
+
+    /// Sample standard deviation of the returns in the window
+    pub fn volatility(&self) -> Option<f64> {
+        if !self.is_ready() || self.window < 2 {
+            return None;
+        }
+
+        let n = self.window as f64;
+        let sum = self.sum.value();
+        let variance = (self.sum_sq.value() - sum * sum / n) / (n - 1.0);
+        Some(variance.max(0.0).sqrt())
+    }
+
+    pub fn reset(&mut self) {
+        self.last_price = None;
+        self.values.clear();
+        self.sum.reset();
+        self.sum_sq.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    #[test]
+    fn test_simple_and_log_returns() {
+        let prices = [100.0, 110.0, 99.0];
+        let simple = returns(&prices, ReturnKind::Simple);
+        assert!((simple[0] - 0.1).abs() < 1e-12);
+        assert!((simple[1] + 0.1).abs() < 1e-12);
+
+        let log = returns(&prices, ReturnKind::Log);
+        assert!((log[0] - ReturnKind::Simple.convert(simple[0], ReturnKind::Log)).abs() < 1e-12);
+
+        // Compounding simple returns and summing log returns agree
+        let total_simple = *cumulative(&simple, ReturnKind::Simple).last().unwrap();
+        let total_log = *cumulative(&log, ReturnKind::Log).last().unwrap();
+        assert!((total_simple + 0.01).abs() < 1e-12);
+        assert!(
+            (ReturnKind::Log.convert(total_log, ReturnKind::Simple) - total_simple).abs() < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_candle_returns_skip_bad_prices() {
+        let candles = [
+            Candle::new(1.0, 1.0, 1.0, 100.0, 1.0, Timestamp::EPOCH),
+            Candle::new(1.0, 1.0, 1.0, 0.0, 1.0, Timestamp::from_millis(1)),
+            Candle::new(1.0, 1.0, 1.0, 105.0, 1.0, Timestamp::from_millis(2)),
+        ];
+        assert!(candle_returns(&candles, ReturnKind::Simple).is_empty());
+        assert_eq!(returns(&[100.0, 105.0], ReturnKind::Simple).len(), 1);
+    }
+
+    #[test]
+    fn test_resample_fills_gaps() {
+        let ticks = [(100, 10.0), (900, 11.0), (2_500, 12.0), (2_600, 13.0)];
+        let sampled = resample(&ticks, 1_000);
+        assert_eq!(sampled, vec![(0, 11.0), (1_000, 11.0), (2_000, 13.0)]);
+
+        let rets = resampled_returns(&ticks, 1_000, ReturnKind::Simple);
+        assert_eq!(rets[0], (1_000, 0.0));
+        assert!((rets[1].1 - (13.0 / 11.0 - 1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_returns() {
+        let mut rolling = RollingReturns::new(ReturnKind::Log, 2);
+        assert_eq!(rolling.update(100.0), None);
+        rolling.update(110.0);
+        assert!(rolling.cumulative().is_none());
+
+        rolling.update(121.0);
+        assert!((rolling.cumulative().unwrap() - (1.21_f64).ln()).abs() < 1e-12);
+        assert!(rolling.volatility().unwrap() < 1e-9);
+
+        rolling.update_candle(&Candle::new(
+            121.0,
+            121.0,
+            121.0,
+            121.0,
+            1.0,
+            Timestamp::from_millis(3),
+        ));
+        assert!((rolling.mean().unwrap() - (1.1_f64).ln() / 2.0).abs() < 1e-12);
+
+        rolling.reset();
+        assert!(rolling.mean().is_none());
+    }
+}
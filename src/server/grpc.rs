@@ -0,0 +1,585 @@
+//! gRPC service over tonic, enabled with the `grpc` feature
+//!
+//! Serves the `market_data.v1.MarketData` service of `proto/market_data.proto`: book
+//! snapshots, the latest published indicator values and a stream of live updates.
+//! Data enters through a [`GrpcPublisher`], which keeps the books and indicator values
+//! the queries are answered from.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::orderbook::{self, OrderBookManager};
+use crate::pipeline::stream::Processor;
+use crate::time::Timestamp;
+use crate::trades::Side;
+
+/// Types and client/server stubs generated from `proto/market_data.proto`
+pub mod proto {
+    tonic::include_proto!("market_data.v1");
+}
+
+use proto::market_data_server::{MarketData as MarketDataRpc, MarketDataServer};
+use proto::market_update::Payload;
+
+/// Wildcard symbol subscribing to every symbol
+pub const ALL_SYMBOLS: &str = "*";
+
+/// Server options
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcServerConfig {
+    pub bind: SocketAddr,
+    /// Updates a subscriber may fall behind before it is resynchronized with books
+    pub capacity: usize,
+    /// Levels per side when a request does not ask for a depth
+    pub snapshot_depth: usize,
+}
+
+impl Default for GrpcServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 50051)),
+            capacity: 1024,
+            snapshot_depth: 50,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    books: OrderBookManager,
+    /// Latest value of every indicator, by symbol then name
+    indicators: HashMap<String, BTreeMap<String, proto::IndicatorValue>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    updates: broadcast::Sender<Arc<proto::MarketUpdate>>,
+    state: Mutex<State>,
+    snapshot_depth: usize,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn book(&self, symbol: &str, depth: usize) -> Option<proto::OrderBook> {
+        let state = self.state();
+        let book = state.books.book(symbol)?;
+        Some(proto::OrderBook {
+            symbol: symbol.to_string(),
+            sequence: book.sequence,
+            timestamp: book.last_update.as_nanos(),
+            bids: levels(&book.top_bids(depth)),
+            asks: levels(&book.top_asks(depth)),
+        })
+    }
+
+    /// Current books of the subscribed symbols, sorted by symbol
+    fn book_updates(&self, subscription: &HashSet<String>) -> Vec<proto::MarketUpdate> {
+        let symbols: Vec<String> = {
+            let state = self.state();
+            let mut symbols: Vec<String> = state
+                .books
+                .symbols()
+                .filter(|symbol| matches(subscription, symbol))
+                .map(str::to_string)
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        symbols
+            .iter()
+            .filter_map(|symbol| self.book(symbol, self.snapshot_depth))
+            .map(|book| proto::MarketUpdate {
+                symbol: book.symbol.clone(),
+                exchange_time: book.timestamp,
+                received: book.timestamp,
+                sequence: book.sequence,
+                payload: Some(Payload::Book(book)),
+                ..proto::MarketUpdate::default()
+            })
+            .collect()
+    }
+}
+
+fn matches(subscription: &HashSet<String>, symbol: &str) -> bool {
+    subscription.contains(ALL_SYMBOLS) || subscription.contains(symbol)
+}
+
+fn levels(levels: &[orderbook::PriceLevel]) -> Vec<proto::PriceLevel> {
+    levels
+        .iter()
+        .map(|level| proto::PriceLevel {
+            price: level.price,
+            quantity: level.quantity,
+        })
+        .collect()
+}
+
+fn side(side: Side) -> i32 {
+    match side {
+        Side::Buy => proto::Side::Buy as i32,
+        Side::Sell => proto::Side::Sell as i32,
+    }
+}
+
+fn book_side(side: orderbook::BookSide) -> i32 {
+    match side {
+        orderbook::BookSide::Bid => proto::Side::Buy as i32,
+        orderbook::BookSide::Ask => proto::Side::Sell as i32,
+    }
+}
+
+impl From<&MarketEvent> for proto::MarketUpdate {
+    fn from(event: &MarketEvent) -> Self {
+        let payload = match &event.data {
+            MarketData::BookSnapshot { bids, asks } => Payload::Snapshot(proto::OrderBook {
+                symbol: event.symbol.clone(),
+                sequence: event.sequence,
+                timestamp: event.exchange_time.as_nanos(),
+                bids: levels(bids),
+                asks: levels(asks),
+            }),
+            MarketData::BookDelta {
+                updates,
+                first_sequence,
+            } => Payload::Delta(proto::BookDelta {
+                updates: updates
+                    .iter()
+                    .map(|update| proto::LevelUpdate {
+                        side: book_side(update.side),
+                        price: update.price,
+                        quantity: update.quantity,
+                    })
+                    .collect(),
+                first_sequence: *first_sequence,
+            }),
+            MarketData::Trade(trade) => Payload::Trade(proto::Trade {
+                price: trade.price,
+                quantity: trade.quantity,
+                side: side(trade.side),
+                timestamp_ms: trade.timestamp.as_millis(),
+            }),
+            MarketData::Ticker(quote) => Payload::Ticker(proto::Ticker {
+                bid_price: quote.bid_price,
+                bid_size: quote.bid_size,
+                ask_price: quote.ask_price,
+                ask_size: quote.ask_size,
+                timestamp_ms: quote.timestamp.as_millis(),
+            }),
+            MarketData::Candle(candle) => Payload::Candle(proto::Candle {
+                open: candle.open,
+                high: candle.high,
+                low: candle.low,
+                close: candle.close,
+                volume: candle.volume,
+                timestamp_ms: candle.timestamp.as_millis(),
+            }),
+            MarketData::Heartbeat => Payload::Heartbeat(proto::Heartbeat {}),
+        };
+        proto::MarketUpdate {
+            exchange: event.exchange.clone(),
+            symbol: event.symbol.clone(),
+            exchange_time: event.exchange_time.as_nanos(),
+            received: event.received.as_nanos(),
+            sequence: event.sequence,
+            payload: Some(payload),
+        }
+    }
+}
+
+/// Handle feeding the service; clones share the server
+#[derive(Debug, Clone)]
+pub struct GrpcPublisher {
+    shared: Arc<Shared>,
+}
+
+impl GrpcPublisher {
+    /// Stream an event, applying book events to the books served by `GetOrderBook`
+    ///
+    /// A book event the books reject (e.g. a sequence gap) is not streamed.
+    pub fn publish_event(&self, event: &MarketEvent) -> Result<()> {
+        let update = Arc::new(proto::MarketUpdate::from(event));
+        // Held while streaming so subscribers see events in the order they were applied
+        let mut state = self.shared.state();
+        state.books.apply_event(event)?;
+        // Without subscribers the send fails, which is fine
+        let _ = self.shared.updates.send(update);
+        Ok(())
+    }
+
+    /// Record and stream an indicator value with one field per output, e.g.
+    /// `[("macd", m), ("signal", s), ("histogram", h)]`
+    pub fn publish_indicator(
+        &self,
+        symbol: &str,
+        name: &str,
+        timestamp: Timestamp,
+        fields: &[(&str, f64)],
+    ) -> Result<()> {
+        ensure(!fields.is_empty(), "indicator needs at least one field")?;
+        let value = proto::IndicatorValue {
+            name: name.to_string(),
+            timestamp: timestamp.as_nanos(),
+            fields: fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), *value))
+                .collect(),
+        };
+        let update = Arc::new(proto::MarketUpdate {
+            symbol: symbol.to_string(),
+            exchange_time: value.timestamp,
+            received: value.timestamp,
+            payload: Some(Payload::Indicator(value.clone())),
+            ..proto::MarketUpdate::default()
+        });
+
+        let mut state = self.shared.state();
+        state
+            .indicators
+            .entry(symbol.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+        let _ = self.shared.updates.send(update);
+        Ok(())
+    }
+
+    /// [`publish_indicator`](Self::publish_indicator) for a scalar indicator, stored
+    /// as field `"value"`
+    pub fn publish_value(
+        &self,
+        symbol: &str,
+        name: &str,
+        timestamp: Timestamp,
+        value: f64,
+    ) -> Result<()> {
+        self.publish_indicator(symbol, name, timestamp, &[("value", value)])
+    }
+}
+
+/// Streams every event flowing through a [`StreamPipeline`](crate::pipeline::stream::StreamPipeline)
+impl Processor for GrpcPublisher {
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        self.publish_event(event)
+    }
+}
+
+/// Implementation of the generated `MarketData` service trait
+#[derive(Debug, Clone)]
+pub struct MarketDataService {
+    shared: Arc<Shared>,
+}
+
+type UpdateStream =
+    Pin<Box<dyn Stream<Item = std::result::Result<proto::MarketUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl MarketDataRpc for MarketDataService {
+    async fn get_order_book(
+        &self,
+        request: Request<proto::GetOrderBookRequest>,
+    ) -> std::result::Result<Response<proto::OrderBook>, Status> {
+        let request = request.into_inner();
+        let depth = match request.depth {
+            0 => self.shared.snapshot_depth,
+            depth => depth as usize,
+        };
+        self.shared
+            .book(&request.symbol, depth)
+            .map(Response::new)
+            .ok_or_else(|| Status::not_found(format!("no book for {}", request.symbol)))
+    }
+
+    async fn get_indicators(
+        &self,
+        request: Request<proto::GetIndicatorsRequest>,
+    ) -> std::result::Result<Response<proto::IndicatorValues>, Status> {
+        let request = request.into_inner();
+        let state = self.shared.state();
+        let values = state
+            .indicators
+            .get(&request.symbol)
+            .ok_or_else(|| Status::not_found(format!("no indicators for {}", request.symbol)))?
+            .values()
+            .filter(|value| request.names.is_empty() || request.names.contains(&value.name))
+            .cloned()
+            .collect();
+        Ok(Response::new(proto::IndicatorValues {
+            symbol: request.symbol,
+            values,
+        }))
+    }
+
+    type SubscribeMarketDataStream = UpdateStream;
+
+    async fn subscribe_market_data(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> std::result::Result<Response<UpdateStream>, Status> {
+        let subscription: HashSet<String> = request.into_inner().symbols.into_iter().collect();
+        if subscription.is_empty() {
+            return Err(Status::invalid_argument("no symbols requested"));
+        }
+
+        let shared = Arc::clone(&self.shared);
+        let (tx, rx) = mpsc::channel(shared.snapshot_depth.max(16));
+        // Subscribed before reading the books, so no update falls between the two
+        let mut updates = shared.updates.subscribe();
+        let mut shutdown = shared.shutdown.clone();
+        tokio::spawn(async move {
+            let mut pending = shared.book_updates(&subscription);
+            loop {
+                for update in pending.drain(..) {
+                    if tx.send(Ok(update)).await.is_err() {
+                        return;
+                    }
+                }
+                tokio::select! {
+                    _ = stopped(&mut shutdown) => return,
+                    update = updates.recv() => match update {
+                        Ok(update) => {
+                            if matches(&subscription, &update.symbol) {
+                                pending.push(update.as_ref().clone());
+                            }
+                        }
+                        // Skipped deltas cannot be recovered, so resend the books instead
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            pending = shared.book_updates(&subscription);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    },
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+/// Running server; stop it with [`stop`](Self::stop)
+#[derive(Debug)]
+pub struct GrpcServer {
+    local_addr: SocketAddr,
+    publisher: GrpcPublisher,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<std::result::Result<(), tonic::transport::Error>>,
+}
+
+impl GrpcServer {
+    /// Bind and start serving on the current tokio runtime
+    pub async fn start(config: GrpcServerConfig) -> Result<Self> {
+        ensure(config.capacity > 0, "capacity must be positive")?;
+        ensure(config.snapshot_depth > 0, "snapshot depth must be positive")?;
+
+        let listener = TcpListener::bind(config.bind).await?;
+        let local_addr = listener.local_addr()?;
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        let shared = Arc::new(Shared {
+            updates: broadcast::channel(config.capacity).0,
+            state: Mutex::new(State::default()),
+            snapshot_depth: config.snapshot_depth,
+            shutdown: shutdown_rx.clone(),
+        });
+
+        let service = MarketDataService {
+            shared: Arc::clone(&shared),
+        };
+        let task = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(MarketDataServer::new(service))
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async move {
+                    stopped(&mut shutdown_rx).await
+                }),
+        );
+
+        Ok(Self {
+            local_addr,
+            publisher: GrpcPublisher { shared },
+            shutdown: shutdown_tx,
+            task,
+        })
+    }
+
+    /// Bound address, e.g. to find the port chosen for port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn publisher(&self) -> GrpcPublisher {
+        self.publisher.clone()
+    }
+
+    /// Stop accepting calls, end open subscriptions and wait for the server to finish
+    pub async fn stop(self) -> Result<()> {
+        self.shutdown.send_replace(true);
+        self.task
+            .await
+            .map_err(|e| MarketDataError::FeedDisconnected(format!("server task failed: {e}")))?
+            .map_err(|e| MarketDataError::FeedDisconnected(e.to_string()))
+    }
+}
+
+/// Resolve once the server is stopping
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    // An error means the server handle was dropped, which also stops it
+    let _ = shutdown.wait_for(|&stop| stop).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::LevelUpdate;
+    use crate::trades::Trade;
+    use proto::market_data_client::MarketDataClient;
+    use tonic::transport::Channel;
+
+    async fn start() -> (GrpcServer, MarketDataClient<Channel>) {
+        let server = GrpcServer::start(GrpcServerConfig {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            ..GrpcServerConfig::default()
+        })
+        .await
+        .unwrap();
+        let client = MarketDataClient::connect(format!("http://{}", server.local_addr()))
+            .await
+            .unwrap();
+        (server, client)
+    }
+
+    fn delta(symbol: &str, price: f64, millis: i64) -> MarketEvent {
+        MarketEvent::new(
+            "test",
+            symbol,
+            Timestamp::from_millis(millis),
+            MarketData::BookDelta {
+                updates: vec![
+                    LevelUpdate::bid(price, 1.0),
+                    LevelUpdate::ask(price + 10.0, 2.0),
+                ],
+                first_sequence: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_order_book_and_indicator_queries() {
+        let (server, mut client) = start().await;
+        let publisher = server.publisher();
+        publisher.publish_event(&delta("BTC", 100.0, 1)).unwrap();
+        publisher.publish_event(&delta("BTC", 99.0, 2)).unwrap();
+        publisher
+            .publish_value("BTC", "rsi_14", Timestamp::from_millis(2), 55.0)
+            .unwrap();
+        publisher
+            .publish_indicator(
+                "BTC",
+                "macd",
+                Timestamp::from_millis(2),
+                &[("macd", 1.0), ("signal", 0.5), ("histogram", 0.5)],
+            )
+            .unwrap();
+
+        let book = client
+            .get_order_book(proto::GetOrderBookRequest {
+                symbol: "BTC".to_string(),
+                depth: 1,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            book.bids,
+            vec![proto::PriceLevel {
+                price: 100.0,
+                quantity: 1.0
+            }]
+        );
+        assert_eq!(book.asks[0].price, 109.0);
+        assert_eq!(book.timestamp, Timestamp::from_millis(2).as_nanos());
+
+        let missing = client
+            .get_order_book(proto::GetOrderBookRequest {
+                symbol: "ETH".to_string(),
+                depth: 0,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+
+        let indicators = client
+            .get_indicators(proto::GetIndicatorsRequest {
+                symbol: "BTC".to_string(),
+                names: vec!["rsi_14".to_string()],
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(indicators.values.len(), 1);
+        assert_eq!(indicators.values[0].fields["value"], 55.0);
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscription_streams_book_then_updates() {
+        let (server, mut client) = start().await;
+        let publisher = server.publisher();
+        publisher.publish_event(&delta("BTC", 100.0, 1)).unwrap();
+
+        let mut stream = client
+            .subscribe_market_data(proto::SubscribeRequest {
+                symbols: vec!["BTC".to_string()],
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        match stream.message().await.unwrap().unwrap().payload {
+            Some(Payload::Book(book)) => assert_eq!(book.bids[0].price, 100.0),
+            other => panic!("expected a book, got {other:?}"),
+        }
+
+        publisher.publish_event(&delta("ETH", 10.0, 2)).unwrap();
+        let trade = MarketEvent::new(
+            "test",
+            "BTC",
+            Timestamp::from_millis(3),
+            MarketData::Trade(Trade::new(
+                100.5,
+                0.2,
+                Side::Sell,
+                Timestamp::from_millis(3),
+            )),
+        );
+        publisher.publish_event(&trade).unwrap();
+
+        let update = stream.message().await.unwrap().unwrap();
+        assert_eq!(update, proto::MarketUpdate::from(&trade));
+        assert_eq!(
+            update.payload,
+            Some(Payload::Trade(proto::Trade {
+                price: 100.5,
+                quantity: 0.2,
+                side: proto::Side::Sell as i32,
+                timestamp_ms: 3,
+            }))
+        );
+
+        // Stopping ends open subscriptions
+        server.stop().await.unwrap();
+        assert!(matches!(stream.message().await, Ok(None) | Err(_)));
+    }
+}
@@ -0,0 +1,10 @@
+//! Network services publishing processed market data to downstream consumers
+//!
+//! [`ws`] (feature `ws-server`) broadcasts events and indicator values as JSON over
+//! WebSocket; [`grpc`] (feature `grpc`) serves book snapshots, indicator queries and
+//! update streams to gRPC clients.
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "ws-server")]
+pub mod ws;
@@ -0,0 +1,511 @@
+//! WebSocket server publishing normalized market data as JSON, enabled with the
+//! `ws-server` feature
+//!
+//! A [`WsPublisher`] serializes every published event and indicator value once and
+//! broadcasts it to the connected clients. Clients pick symbols with
+//! `{"op": "subscribe", "symbols": ["BTCUSD"]}` (`"*"` for all) and are sent the
+//! current book of each newly subscribed symbol before its deltas. Deltas carry
+//! absolute levels, so one already reflected in that book is harmless to reapply.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::events::MarketEvent;
+use crate::orderbook::{OrderBookManager, PriceLevel};
+use crate::pipeline::stream::Processor;
+use crate::time::Timestamp;
+
+/// Wildcard symbol subscribing to every symbol
+pub const ALL_SYMBOLS: &str = "*";
+
+/// Message sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsMessage {
+    /// Normalized feed event: book snapshot or delta, trade, ticker or candle
+    Event(MarketEvent),
+    /// Output of an indicator, e.g. `"rsi_14"` with a number or a JSON object
+    Indicator {
+        symbol: String,
+        name: String,
+        timestamp: Timestamp,
+        value: serde_json::Value,
+    },
+    /// Current book, sent on subscription and after the client fell behind
+    Book {
+        symbol: String,
+        sequence: Option<u64>,
+        timestamp: Timestamp,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+    },
+    /// Rejected client request
+    Error { message: String },
+}
+
+/// Request sent by clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ClientRequest {
+    Subscribe { symbols: Vec<String> },
+    Unsubscribe { symbols: Vec<String> },
+}
+
+/// Server options
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsServerConfig {
+    pub bind: SocketAddr,
+    /// Messages a client may fall behind before it is resynchronized with books
+    pub capacity: usize,
+    /// Levels per side in the books sent to clients
+    pub snapshot_depth: usize,
+}
+
+impl Default for WsServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 9001)),
+            capacity: 1024,
+            snapshot_depth: 50,
+        }
+    }
+}
+
+/// Serialized message, tagged with its symbol for filtering
+#[derive(Debug)]
+struct Outgoing {
+    symbol: String,
+    json: String,
+}
+
+#[derive(Debug)]
+struct Shared {
+    messages: broadcast::Sender<Arc<Outgoing>>,
+    books: Mutex<OrderBookManager>,
+    snapshot_depth: usize,
+}
+
+impl Shared {
+    fn books(&self) -> MutexGuard<'_, OrderBookManager> {
+        self.books
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Current books of the subscribed symbols as serialized messages
+    fn book_messages(&self, subscription: &Subscription) -> Result<Vec<String>> {
+        let books = self.books();
+        books
+            .iter()
+            .filter(|(symbol, _)| subscription.matches(symbol))
+            .map(|(symbol, book)| {
+                let message = WsMessage::Book {
+                    symbol: symbol.to_string(),
+                    sequence: book.sequence,
+                    timestamp: book.last_update,
+                    bids: book.top_bids(self.snapshot_depth),
+                    asks: book.top_asks(self.snapshot_depth),
+                };
+                Ok(serde_json::to_string(&message)?)
+            })
+            .collect()
+    }
+}
+
+/// Handle for publishing to every connected client; clones share the server
+#[derive(Debug, Clone)]
+pub struct WsPublisher {
+    shared: Arc<Shared>,
+}
+
+impl WsPublisher {
+    /// Broadcast an event, applying book events to the books sent to new subscribers
+    ///
+    /// A book event the books reject (e.g. a sequence gap) is not broadcast.
+    pub fn publish_event(&self, event: &MarketEvent) -> Result<()> {
+        let json = serde_json::to_string(&WsMessage::Event(event.clone()))?;
+        // Held while broadcasting so clients see events in the order they were applied
+        let mut books = self.shared.books();
+        books.apply_event(event)?;
+        self.send(&event.symbol, json);
+        Ok(())
+    }
+
+    /// Broadcast an indicator value, e.g. an `f64` or a serializable output struct
+    pub fn publish_indicator(
+        &self,
+        symbol: &str,
+        name: &str,
+        timestamp: Timestamp,
+        value: &impl Serialize,
+    ) -> Result<()> {
+        let message = WsMessage::Indicator {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            timestamp,
+            value: serde_json::to_value(value)?,
+        };
+        let json = serde_json::to_string(&message)?;
+        self.send(symbol, json);
+        Ok(())
+    }
+
+    /// Connected clients
+    pub fn client_count(&self) -> usize {
+        self.shared.messages.receiver_count()
+    }
+
+    fn send(&self, symbol: &str, json: String) {
+        // Without connected clients the send fails, which is fine
+        let _ = self.shared.messages.send(Arc::new(Outgoing {
+            symbol: symbol.to_string(),
+            json,
+        }));
+    }
+}
+
+/// Publishes every event flowing through a [`StreamPipeline`](crate::pipeline::stream::StreamPipeline)
+impl Processor for WsPublisher {
+    fn process(&mut self, event: &MarketEvent) -> Result<()> {
+        self.publish_event(event)
+    }
+}
+
+/// Symbols a client receives
+#[derive(Debug, Default)]
+struct Subscription {
+    symbols: HashSet<String>,
+}
+
+impl Subscription {
+    fn matches(&self, symbol: &str) -> bool {
+        self.symbols.contains(ALL_SYMBOLS) || self.symbols.contains(symbol)
+    }
+}
+
+/// Running server; stop it with [`stop`](Self::stop)
+#[derive(Debug)]
+pub struct WsServer {
+    local_addr: SocketAddr,
+    publisher: WsPublisher,
+    connections: Arc<AtomicUsize>,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl WsServer {
+    /// Bind and start accepting clients on the current tokio runtime
+    pub async fn start(config: WsServerConfig) -> Result<Self> {
+        ensure(config.capacity > 0, "capacity must be positive")?;
+        ensure(config.snapshot_depth > 0, "snapshot depth must be positive")?;
+
+        let listener = TcpListener::bind(config.bind).await?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            messages: broadcast::channel(config.capacity).0,
+            books: Mutex::new(OrderBookManager::new()),
+            snapshot_depth: config.snapshot_depth,
+        });
+        let connections = Arc::new(AtomicUsize::new(0));
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+
+        let task = {
+            let shared = Arc::clone(&shared);
+            let connections = Arc::clone(&connections);
+            tokio::spawn(async move {
+                let mut clients = JoinSet::new();
+                loop {
+                    tokio::select! {
+                        _ = stopped(&mut shutdown_rx) => break,
+                        accepted = listener.accept() => {
+                            // A failed accept only affects that client
+                            if let Ok((stream, _)) = accepted {
+                                let shared = Arc::clone(&shared);
+                                let connections = Arc::clone(&connections);
+                                let shutdown = shutdown_rx.clone();
+                                clients.spawn(async move {
+                                    connections.fetch_add(1, Ordering::Relaxed);
+                                    // Errors only end this client's connection
+                                    let _ = serve(stream, &shared, shutdown).await;
+                                    connections.fetch_sub(1, Ordering::Relaxed);
+                                });
+                            }
+                        }
+                        Some(_) = clients.join_next(), if !clients.is_empty() => {}
+                    }
+                }
+                while clients.join_next().await.is_some() {}
+            })
+        };
+
+        Ok(Self {
+            local_addr,
+            publisher: WsPublisher { shared },
+            connections,
+            shutdown: shutdown_tx,
+            task,
+        })
+    }
+
+    /// Bound address, e.g. to find the port chosen for port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn publisher(&self) -> WsPublisher {
+        self.publisher.clone()
+    }
+
+    /// Clients past the WebSocket handshake or still in it
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Stop accepting, close every client connection and wait for them to end
+    pub async fn stop(self) -> Result<()> {
+        self.shutdown.send_replace(true);
+        self.task
+            .await
+            .map_err(|e| closed(format!("server task failed: {e}")))
+    }
+}
+
+/// Resolve once the server is stopping
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    // An error means the server handle was dropped, which also stops it
+    let _ = shutdown.wait_for(|&stop| stop).await;
+}
+
+fn closed(error: impl Display) -> MarketDataError {
+    MarketDataError::FeedDisconnected(error.to_string())
+}
+
+/// Run one client connection until it closes or the server stops
+async fn serve(
+    stream: TcpStream,
+    shared: &Shared,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    let socket = tokio_tungstenite::accept_async(stream)
+        .await
+        .map_err(closed)?;
+    let (mut sink, mut requests) = socket.split();
+    let mut messages = shared.messages.subscribe();
+    let mut subscription = Subscription::default();
+
+    loop {
+        tokio::select! {
+            _ = stopped(&mut shutdown) => {
+                let _ = sink.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            message = messages.recv() => match message {
+                Ok(message) => {
+                    if subscription.matches(&message.symbol) {
+                        sink.send(Message::Text(message.json.clone()))
+                            .await
+                            .map_err(closed)?;
+                    }
+                }
+                // Skipped deltas cannot be recovered, so resend the books instead
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    for json in shared.book_messages(&subscription)? {
+                        sink.send(Message::Text(json)).await.map_err(closed)?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            request = requests.next() => match request {
+                Some(Ok(Message::Text(text))) => {
+                    for json in handle_request(&text, shared, &mut subscription)? {
+                        sink.send(Message::Text(json)).await.map_err(closed)?;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                // Pings are answered by the protocol layer
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(closed(e)),
+            },
+        }
+    }
+}
+
+/// Apply a client request, returning the messages to send back
+fn handle_request(
+    text: &str,
+    shared: &Shared,
+    subscription: &mut Subscription,
+) -> Result<Vec<String>> {
+    match serde_json::from_str::<ClientRequest>(text) {
+        Ok(ClientRequest::Subscribe { symbols }) => {
+            let added = Subscription {
+                symbols: symbols
+                    .into_iter()
+                    .filter(|symbol| !subscription.matches(symbol))
+                    .collect(),
+            };
+            let books = shared.book_messages(&added)?;
+            subscription.symbols.extend(added.symbols);
+            Ok(books)
+        }
+        Ok(ClientRequest::Unsubscribe { symbols }) => {
+            for symbol in &symbols {
+                subscription.symbols.remove(symbol);
+            }
+            Ok(Vec::new())
+        }
+        Err(e) => {
+            let message = WsMessage::Error {
+                message: format!("invalid request: {e}"),
+            };
+            Ok(vec![serde_json::to_string(&message)?])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::MarketData;
+    use crate::orderbook::LevelUpdate;
+    use crate::trades::{Side, Trade};
+    use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn start() -> WsServer {
+        WsServer::start(WsServerConfig {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            ..WsServerConfig::default()
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn connect(server: &WsServer, symbols: &[&str]) -> Client {
+        let url = format!("ws://{}", server.local_addr());
+        let (mut client, _) = connect_async(url).await.unwrap();
+        let request = ClientRequest::Subscribe {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+        };
+        client
+            .send(Message::Text(serde_json::to_string(&request).unwrap()))
+            .await
+            .unwrap();
+        client
+    }
+
+    async fn next(client: &mut Client) -> WsMessage {
+        loop {
+            if let Message::Text(text) = client.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    fn delta(symbol: &str, price: f64, millis: i64) -> MarketEvent {
+        MarketEvent::new(
+            "test",
+            symbol,
+            Timestamp::from_millis(millis),
+            MarketData::BookDelta {
+                updates: vec![LevelUpdate::bid(price, 1.0)],
+                first_sequence: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_get_book_then_their_symbols() {
+        let server = start().await;
+        let publisher = server.publisher();
+        publisher.publish_event(&delta("BTC", 100.0, 1)).unwrap();
+
+        let mut btc = connect(&server, &["BTC"]).await;
+        match next(&mut btc).await {
+            WsMessage::Book { symbol, bids, .. } => {
+                assert_eq!(symbol, "BTC");
+                assert_eq!(bids[0].price, 100.0);
+            }
+            other => panic!("expected a book, got {other:?}"),
+        }
+        let mut all = connect(&server, &[ALL_SYMBOLS]).await;
+        assert!(matches!(next(&mut all).await, WsMessage::Book { .. }));
+
+        // Receiving the books shows both subscriptions are in effect
+        publisher.publish_event(&delta("ETH", 10.0, 2)).unwrap();
+        let trade = MarketEvent::new(
+            "test",
+            "BTC",
+            Timestamp::from_millis(3),
+            MarketData::Trade(Trade::new(100.5, 0.2, Side::Buy, Timestamp::from_millis(3))),
+        );
+        publisher.publish_event(&trade).unwrap();
+        publisher
+            .publish_indicator("BTC", "rsi_14", Timestamp::from_millis(3), &61.5)
+            .unwrap();
+
+        assert_eq!(next(&mut btc).await, WsMessage::Event(trade.clone()));
+        match next(&mut btc).await {
+            WsMessage::Indicator { name, value, .. } => {
+                assert_eq!(name, "rsi_14");
+                assert_eq!(value, serde_json::json!(61.5));
+            }
+            other => panic!("expected an indicator, got {other:?}"),
+        }
+        assert_eq!(
+            next(&mut all).await,
+            WsMessage::Event(delta("ETH", 10.0, 2))
+        );
+        assert_eq!(next(&mut all).await, WsMessage::Event(trade));
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_and_shutdown() {
+        let server = start().await;
+        let mut client = connect(&server, &[]).await;
+        client
+            .send(Message::Text("{\"op\": \"teleport\"}".to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(next(&mut client).await, WsMessage::Error { .. }));
+        assert_eq!(server.connections(), 1);
+        assert_eq!(server.publisher().client_count(), 1);
+
+        server.stop().await.unwrap();
+        assert!(matches!(
+            client.next().await,
+            Some(Ok(Message::Close(_))) | None
+        ));
+    }
+
+    #[test]
+    fn test_message_format() {
+        let json = serde_json::to_value(WsMessage::Event(delta("BTC", 100.0, 1))).unwrap();
+        assert_eq!(json["type"], "event");
+        assert_eq!(json["symbol"], "BTC");
+        assert!(json["data"]["BookDelta"].is_object());
+
+        let request: ClientRequest =
+            serde_json::from_str(r#"{"op": "unsubscribe", "symbols": ["BTC"]}"#).unwrap();
+        assert_eq!(
+            request,
+            ClientRequest::Unsubscribe {
+                symbols: vec!["BTC".to_string()]
+            }
+        );
+    }
+}
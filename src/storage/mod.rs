@@ -0,0 +1,13 @@
+//! Recording market data to disk for later replay
+//!
+//! [`ticks`] (feature `mmap`, on by default) is a memory-mapped binary tick store for
+//! fast time-range queries in backtests; columnar Parquet files for compact capture
+//! and analytics tooling live under [`parquet`] (feature `parquet`).
+
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "mmap")]
+pub mod ticks;
+
+#[cfg(feature = "mmap")]
+pub use ticks::{TickFile, TickStore, TickWriter};
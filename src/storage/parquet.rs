@@ -0,0 +1,506 @@
+//! Parquet recording of market events, candles and book snapshots
+//!
+//! Each file holds one record type. The Arrow schema metadata carries the record kind
+//! and [`SCHEMA_VERSION`], which [`read_parquet`] checks before decoding, so files
+//! written by a newer layout are rejected instead of misread.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::basic::Compression;
+use ::parquet::file::properties::WriterProperties;
+use arrow::array::{
+    Array, ArrayRef, Float64Array, Float64Builder, Int64Array, ListArray, ListBuilder, StringArray,
+    UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::candles::Candle;
+use crate::error::{MarketDataError, Result};
+use crate::events::{MarketData, MarketEvent};
+use crate::orderbook::{BookSnapshot, PriceLevel};
+use crate::time::Timestamp;
+
+/// Layout version written to every file
+pub const SCHEMA_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "market_data.schema_version";
+const KIND_KEY: &str = "market_data.kind";
+
+fn storage_error(e: impl std::fmt::Display) -> MarketDataError {
+    MarketDataError::Storage(e.to_string())
+}
+
+/// Type that can be stored as rows of a Parquet file
+pub trait ColumnarRecord: Sized {
+    /// Name recorded in the file metadata, checked when reading
+    const KIND: &'static str;
+
+    fn fields() -> Vec<Field>;
+
+    fn to_columns(records: &[Self]) -> Vec<ArrayRef>;
+
+    fn from_batch(batch: &RecordBatch) -> Result<Vec<Self>>;
+}
+
+fn schema<T: ColumnarRecord>() -> SchemaRef {
+    let metadata = HashMap::from([
+        (VERSION_KEY.to_string(), SCHEMA_VERSION.to_string()),
+        (KIND_KEY.to_string(), T::KIND.to_string()),
+    ]);
+    Arc::new(Schema::new_with_metadata(T::fields(), metadata))
+}
+
+fn column<'a, A: Array + 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a A> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<A>())
+        .ok_or_else(|| MarketDataError::Malformed(format!("missing or mistyped column {name}")))
+}
+
+fn float_list(field: &str) -> Field {
+    Field::new(
+        field,
+        DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+        false,
+    )
+}
+
+impl ColumnarRecord for Candle {
+    const KIND: &'static str = "candle";
+
+    fn fields() -> Vec<Field> {
+        let mut fields = vec![Field::new("timestamp", DataType::Int64, false)];
+        for name in ["open", "high", "low", "close", "volume"] {
+            fields.push(Field::new(name, DataType::Float64, false));
+        }
+        fields
+    }
+
+    fn to_columns(records: &[Self]) -> Vec<ArrayRef> {
+        let floats = |f: fn(&Candle) -> f64| -> ArrayRef {
+            Arc::new(records.iter().map(f).collect::<Float64Array>())
+        };
+        vec![
+            Arc::new(
+                records
+                    .iter()
+                    .map(|c| c.timestamp.as_millis())
+                    .collect::<Int64Array>(),
+            ),
+            floats(|c| c.open),
+            floats(|c| c.high),
+            floats(|c| c.low),
+            floats(|c| c.close),
+            floats(|c| c.volume),
+        ]
+    }
+
+    fn from_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
+        let timestamp = column::<Int64Array>(batch, "timestamp")?;
+        let open = column::<Float64Array>(batch, "open")?;
+        let high = column::<Float64Array>(batch, "high")?;
+        let low = column::<Float64Array>(batch, "low")?;
+        let close = column::<Float64Array>(batch, "close")?;
+        let volume = column::<Float64Array>(batch, "volume")?;
+        Ok((0..batch.num_rows())
+            .map(|i| {
+                Candle::new(
+                    open.value(i),
+                    high.value(i),
+                    low.value(i),
+                    close.value(i),
+                    volume.value(i),
+                    Timestamp::from_millis(timestamp.value(i)),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Short name of an event's payload, stored in the `kind` column for filtering
+fn data_kind(data: &MarketData) -> &'static str {
+    match data {
+        MarketData::BookSnapshot { .. } => "book_snapshot",
+        MarketData::BookDelta { .. } => "book_delta",
+        MarketData::Trade(_) => "trade",
+        MarketData::Ticker(_) => "ticker",
+        MarketData::Candle(_) => "candle",
+        MarketData::Heartbeat => "heartbeat",
+    }
+}
+
+/// Metadata in typed columns; the payload as JSON, since its shape varies by kind
+impl ColumnarRecord for MarketEvent {
+    const KIND: &'static str = "market_event";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("exchange", DataType::Utf8, false),
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("exchange_time_ns", DataType::Int64, false),
+            Field::new("received_ns", DataType::Int64, false),
+            Field::new("sequence", DataType::UInt64, true),
+            Field::new("kind", DataType::Utf8, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]
+    }
+
+    fn to_columns(records: &[Self]) -> Vec<ArrayRef> {
+        let payloads: Vec<String> = records
+            .iter()
+            .map(|e| serde_json::to_string(&e.data).expect("market data serializes to JSON"))
+            .collect();
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                records.iter().map(|e| &e.exchange),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                records.iter().map(|e| &e.symbol),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                records.iter().map(|e| e.exchange_time.as_nanos()),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                records.iter().map(|e| e.received.as_nanos()),
+            )),
+            Arc::new(records.iter().map(|e| e.sequence).collect::<UInt64Array>()),
+            Arc::new(StringArray::from_iter_values(
+                records.iter().map(|e| data_kind(&e.data)),
+            )),
+            Arc::new(StringArray::from_iter_values(payloads)),
+        ]
+    }
+
+    fn from_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
+        let exchange = column::<StringArray>(batch, "exchange")?;
+        let symbol = column::<StringArray>(batch, "symbol")?;
+        let exchange_time = column::<Int64Array>(batch, "exchange_time_ns")?;
+        let received = column::<Int64Array>(batch, "received_ns")?;
+        let sequence = column::<UInt64Array>(batch, "sequence")?;
+        let payload = column::<StringArray>(batch, "payload")?;
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(MarketEvent {
+                    exchange: exchange.value(i).to_string(),
+                    symbol: symbol.value(i).to_string(),
+                    exchange_time: Timestamp::from_nanos(exchange_time.value(i)),
+                    received: Timestamp::from_nanos(received.value(i)),
+                    sequence: sequence.is_valid(i).then(|| sequence.value(i)),
+                    data: serde_json::from_str(payload.value(i))?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// One row per snapshot, levels as parallel price and quantity lists best first
+impl ColumnarRecord for BookSnapshot {
+    const KIND: &'static str = "book_snapshot";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("timestamp_ns", DataType::Int64, false),
+            float_list("bid_prices"),
+            float_list("bid_quantities"),
+            float_list("ask_prices"),
+            float_list("ask_quantities"),
+        ]
+    }
+
+    fn to_columns(records: &[Self]) -> Vec<ArrayRef> {
+        let list = |levels: fn(&BookSnapshot) -> &[PriceLevel], value: fn(&PriceLevel) -> f64| {
+            let mut builder = ListBuilder::new(Float64Builder::new());
+            for snapshot in records {
+                for level in levels(snapshot) {
+                    builder.values().append_value(value(level));
+                }
+                builder.append(true);
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        };
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                records.iter().map(|s| s.symbol()),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                records.iter().map(|s| s.last_update().as_nanos()),
+            )),
+            list(BookSnapshot::bids, |l| l.price),
+            list(BookSnapshot::bids, |l| l.quantity),
+            list(BookSnapshot::asks, |l| l.price),
+            list(BookSnapshot::asks, |l| l.quantity),
+        ]
+    }
+
+    fn from_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
+        let symbol = column::<StringArray>(batch, "symbol")?;
+        let timestamp = column::<Int64Array>(batch, "timestamp_ns")?;
+        let lists = [
+            "bid_prices",
+            "bid_quantities",
+            "ask_prices",
+            "ask_quantities",
+        ]
+        .map(|name| column::<ListArray>(batch, name));
+        let [bid_prices, bid_quantities, ask_prices, ask_quantities] = lists;
+        let (bid_prices, bid_quantities) = (bid_prices?, bid_quantities?);
+        let (ask_prices, ask_quantities) = (ask_prices?, ask_quantities?);
+
+        let levels = |prices: &ListArray,
+                      quantities: &ListArray,
+                      i: usize|
+         -> Result<Vec<PriceLevel>> {
+            let (prices, quantities) = (prices.value(i), quantities.value(i));
+            let floats = |array: &ArrayRef| {
+                array
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .map(|a| a.values().to_vec())
+                    .ok_or_else(|| MarketDataError::Malformed("level list is not f64".to_string()))
+            };
+            let (prices, quantities) = (floats(&prices)?, floats(&quantities)?);
+            if prices.len() != quantities.len() {
+                return Err(MarketDataError::DimensionMismatch {
+                    expected: prices.len(),
+                    actual: quantities.len(),
+                });
+            }
+            Ok(prices
+                .into_iter()
+                .zip(quantities)
+                .map(|(price, quantity)| PriceLevel { price, quantity })
+                .collect())
+        };
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(BookSnapshot::new(
+                    symbol.value(i),
+                    levels(bid_prices, bid_quantities, i)?,
+                    levels(ask_prices, ask_quantities, i)?,
+                    Timestamp::from_nanos(timestamp.value(i)),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Buffered Parquet writer for one record type
+///
+/// Records are buffered and written as row groups of `batch_size`; call
+/// [`finish`](Self::finish) to write the rest and the file footer, without which
+/// the file is unreadable.
+pub struct ParquetWriter<T: ColumnarRecord> {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    buffer: Vec<T>,
+    batch_size: usize,
+}
+
+impl<T: ColumnarRecord> ParquetWriter<T> {
+    /// Create or truncate `path`, compressing with Snappy
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let schema = schema::<T>();
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))
+            .map_err(storage_error)?;
+        Ok(Self {
+            writer,
+            schema,
+            buffer: Vec::new(),
+            batch_size: 64 * 1024,
+        })
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn write(&mut self, record: T) -> Result<()> {
+        self.buffer.push(record);
+        if self.buffer.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn write_all(&mut self, records: impl IntoIterator<Item = T>) -> Result<()> {
+        for record in records {
+            self.write(record)?;
+        }
+        Ok(())
+    }
+
+    /// Write buffered records as a row group
+    pub fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = RecordBatch::try_new(self.schema.clone(), T::to_columns(&self.buffer))
+            .map_err(storage_error)?;
+        self.writer.write(&batch).map_err(storage_error)?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.close().map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+/// Read every record of a file written by [`ParquetWriter`]
+///
+/// Fails if the file holds another record kind or a newer schema version.
+pub fn read_parquet<T: ColumnarRecord>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(File::open(path)?).map_err(storage_error)?;
+    let metadata = builder.schema().metadata();
+
+    let version: u32 = metadata
+        .get(VERSION_KEY)
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| MarketDataError::Malformed("file has no schema version".to_string()))?;
+    if version > SCHEMA_VERSION {
+        return Err(MarketDataError::Malformed(format!(
+            "schema version {version} is newer than supported {SCHEMA_VERSION}"
+        )));
+    }
+    match metadata.get(KIND_KEY) {
+        Some(kind) if kind == T::KIND => {}
+        kind => {
+            return Err(MarketDataError::Malformed(format!(
+                "expected {} records, file holds {}",
+                T::KIND,
+                kind.map_or("unknown", String::as_str)
+            )))
+        }
+    }
+
+    let mut records = Vec::new();
+    for batch in builder.build().map_err(storage_error)? {
+        records.extend(T::from_batch(&batch.map_err(storage_error)?)?);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{LevelUpdate, Quote};
+    use crate::trades::{Side, Trade};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}-{}.parquet", std::process::id()))
+    }
+
+    #[test]
+    fn test_candles_round_trip_across_row_groups() {
+        let path = temp_path("candles");
+        let candles: Vec<Candle> = (0..5)
+            .map(|i| {
+                Candle::new(
+                    100.0,
+                    101.0 + i as f64,
+                    99.0,
+                    100.5,
+                    10.0,
+                    Timestamp::from_millis(i * 60_000),
+                )
+            })
+            .collect();
+        let mut writer = ParquetWriter::create(&path).unwrap().with_batch_size(2);
+        writer.write_all(candles.iter().copied()).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(read_parquet::<Candle>(&path).unwrap(), candles);
+        // Wrong record type
+        assert!(read_parquet::<MarketEvent>(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_market_events_round_trip() {
+        let path = temp_path("events");
+        let at = Timestamp::from_millis(1_700_000_000_000);
+        let events = vec![
+            MarketEvent::new(
+                "binance",
+                "BTCUSDT",
+                at,
+                MarketData::Trade(Trade::new(50_000.0, 0.1, Side::Buy, at)),
+            )
+            .with_sequence(7),
+            MarketEvent::new(
+                "binance",
+                "BTCUSDT",
+                at,
+                MarketData::BookDelta {
+                    updates: vec![LevelUpdate::bid(49_999.0, 2.0)],
+                    first_sequence: None,
+                },
+            ),
+            MarketEvent::new(
+                "coinbase",
+                "BTC-USD",
+                at,
+                MarketData::Ticker(Quote {
+                    bid_price: 49_998.0,
+                    bid_size: 1.0,
+                    ask_price: 50_001.0,
+                    ask_size: 2.0,
+                    timestamp: at,
+                }),
+            ),
+        ];
+        let mut writer = ParquetWriter::create(&path).unwrap();
+        writer.write_all(events.clone()).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(read_parquet::<MarketEvent>(&path).unwrap(), events);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_book_snapshots_round_trip() {
+        let path = temp_path("snapshots");
+        let level = |price, quantity| PriceLevel { price, quantity };
+        let snapshots = vec![
+            BookSnapshot::new(
+                "BTCUSD",
+                vec![level(100.0, 1.0), level(99.5, 2.0)],
+                vec![level(100.5, 3.0)],
+                Timestamp::from_millis(1),
+            ),
+            BookSnapshot::new(
+                "BTCUSD",
+                Vec::new(),
+                vec![level(101.0, 1.0)],
+                Timestamp::from_millis(2),
+            ),
+        ];
+        let mut writer = ParquetWriter::create(&path).unwrap();
+        writer.write_all(snapshots.clone()).unwrap();
+        writer.finish().unwrap();
+
+        let read = read_parquet::<BookSnapshot>(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        for (a, b) in read.iter().zip(&snapshots) {
+            assert!(a.same_levels(b));
+            assert_eq!((a.symbol(), a.last_update()), (b.symbol(), b.last_update()));
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,434 @@
+//! Memory-mapped store of trade ticks in fixed-size binary records
+//!
+//! Each symbol gets one append-only file: a 16-byte header (magic, format version,
+//! record size) followed by 32-byte little-endian records of timestamp (ms), price,
+//! quantity and side. Records are kept in timestamp order, so a time range is found
+//! by binary search over the mapped file and only the records inside it are decoded.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::error::{ensure, MarketDataError, Result};
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+const MAGIC: &[u8; 8] = b"MDTICKS\0";
+/// Format version written to new files
+pub const TICK_FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 32;
+
+fn header() -> [u8; HEADER_LEN] {
+    let mut header = [0; HEADER_LEN];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..12].copy_from_slice(&TICK_FORMAT_VERSION.to_le_bytes());
+    header[12..16].copy_from_slice(&(RECORD_LEN as u32).to_le_bytes());
+    header
+}
+
+fn check_header(bytes: &[u8]) -> Result<()> {
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        return Err(MarketDataError::Malformed("not a tick file".to_string()));
+    }
+    let version = u32::from_le_bytes(bytes[8..12].try_into().expect("4 bytes"));
+    let record_len = u32::from_le_bytes(bytes[12..16].try_into().expect("4 bytes"));
+    if version != TICK_FORMAT_VERSION || record_len as usize != RECORD_LEN {
+        return Err(MarketDataError::Malformed(format!(
+            "unsupported tick format version {version} with {record_len}-byte records"
+        )));
+    }
+    Ok(())
+}
+
+fn encode(trade: &Trade) -> [u8; RECORD_LEN] {
+    let mut record = [0; RECORD_LEN];
+    record[..8].copy_from_slice(&trade.timestamp.as_millis().to_le_bytes());
+    record[8..16].copy_from_slice(&trade.price.to_le_bytes());
+    record[16..24].copy_from_slice(&trade.quantity.to_le_bytes());
+    record[24] = match trade.side {
+        Side::Buy => 0,
+        Side::Sell => 1,
+    };
+    record
+}
+
+fn read_i64(bytes: &[u8]) -> i64 {
+    i64::from_le_bytes(bytes[..8].try_into().expect("8 bytes"))
+}
+
+fn read_f64(bytes: &[u8]) -> f64 {
+    f64::from_le_bytes(bytes[..8].try_into().expect("8 bytes"))
+}
+
+fn decode(record: &[u8]) -> Trade {
+    let side = if record[24] == 0 {
+        Side::Buy
+    } else {
+        Side::Sell
+    };
+    Trade::new(
+        read_f64(&record[8..]),
+        read_f64(&record[16..]),
+        side,
+        Timestamp::from_millis(read_i64(record)),
+    )
+}
+
+/// File name for a symbol; `/` and `:` are escaped so that every valid symbol maps to
+/// a distinct plain file name
+fn file_name(symbol: &str) -> Result<String> {
+    ensure(!symbol.is_empty(), "symbol must not be empty")?;
+    ensure(
+        symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/-_.:".contains(c)),
+        "symbol contains invalid characters",
+    )?;
+    Ok(format!(
+        "{}.ticks",
+        symbol.replace('/', "%2F").replace(':', "%3A")
+    ))
+}
+
+/// Appends ticks of one symbol to its file
+///
+/// Ticks must arrive in timestamp order (equal timestamps are fine); earlier ones are
+/// rejected so the file stays searchable. A torn last record left by an interrupted
+/// write is overwritten in place by the next append; files are never truncated, since
+/// a reader may have them mapped.
+#[derive(Debug)]
+pub struct TickWriter {
+    writer: BufWriter<File>,
+    last_timestamp: Option<i64>,
+    len: usize,
+}
+
+impl TickWriter {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let size = file.metadata()?.len() as usize;
+
+        let (len, last_timestamp) = if size == 0 {
+            file.write_all(&header())?;
+            (0, None)
+        } else {
+            let mut existing = [0; HEADER_LEN];
+            file.read_exact(&mut existing)?;
+            check_header(&existing)?;
+
+            let len = (size - HEADER_LEN) / RECORD_LEN;
+            let last_timestamp = if len > 0 {
+                let mut timestamp = [0; 8];
+                file.seek(SeekFrom::Start(
+                    (HEADER_LEN + (len - 1) * RECORD_LEN) as u64,
+                ))?;
+                file.read_exact(&mut timestamp)?;
+                Some(i64::from_le_bytes(timestamp))
+            } else {
+                None
+            };
+            (len, last_timestamp)
+        };
+        // Past the last whole record, so a torn tail gets overwritten
+        file.seek(SeekFrom::Start((HEADER_LEN + len * RECORD_LEN) as u64))?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            last_timestamp,
+            len,
+        })
+    }
+
+    pub fn append(&mut self, trade: &Trade) -> Result<()> {
+        let timestamp = trade.timestamp.as_millis();
+        if let Some(last) = self.last_timestamp.filter(|&last| timestamp < last) {
+            return Err(MarketDataError::invalid_parameter(format!(
+                "tick at {timestamp} is older than the last stored tick at {last}"
+            )));
+        }
+        self.writer.write_all(&encode(trade))?;
+        self.last_timestamp = Some(timestamp);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Ticks in the file, including buffered ones
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Write buffered ticks to the file, making them visible to newly opened readers
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Read-only memory map of one symbol's tick file
+///
+/// The map covers the file as it was when opened; reopen to see ticks appended since.
+/// A torn last record is skipped. Files must never shrink while mapped ([`TickWriter`]
+/// only appends): truncating a mapped file from elsewhere is undefined behavior.
+#[derive(Debug)]
+pub struct TickFile {
+    map: Mmap,
+    len: usize,
+}
+
+impl TickFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: tick files never shrink. Writers append, overwriting at most a torn
+        // partial record past the last whole one, which `len` below excludes, so the
+        // records read through this map are never modified or truncated while it lives
+        let map = unsafe { Mmap::map(&file)? };
+        check_header(&map)?;
+        let len = (map.len() - HEADER_LEN) / RECORD_LEN;
+        Ok(Self { map, len })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn record(&self, index: usize) -> &[u8] {
+        let start = HEADER_LEN + index * RECORD_LEN;
+        &self.map[start..start + RECORD_LEN]
+    }
+
+    fn timestamp(&self, index: usize) -> Timestamp {
+        Timestamp::from_millis(read_i64(self.record(index)))
+    }
+
+    pub fn get(&self, index: usize) -> Option<Trade> {
+        (index < self.len).then(|| decode(self.record(index)))
+    }
+
+    /// Timestamps of the first and last tick
+    pub fn time_span(&self) -> Option<(Timestamp, Timestamp)> {
+        (self.len > 0).then(|| (self.timestamp(0), self.timestamp(self.len - 1)))
+    }
+
+    /// Index of the first tick at or after `timestamp`
+    fn lower_bound(&self, timestamp: Timestamp) -> usize {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.timestamp(mid) < timestamp {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Ticks with `from <= timestamp < to`, decoded lazily in time order
+    pub fn range(
+        &self,
+        from: Timestamp,
+        to: Timestamp,
+    ) -> impl ExactSizeIterator<Item = Trade> + '_ {
+        let start = self.lower_bound(from);
+        let end = self.lower_bound(to).max(start);
+        (start..end).map(|i| decode(self.record(i)))
+    }
+
+    /// Number of ticks with `from <= timestamp < to`, without decoding them
+    pub fn count(&self, from: Timestamp, to: Timestamp) -> usize {
+        self.range(from, to).len()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Trade> + '_ {
+        (0..self.len).map(|i| decode(self.record(i)))
+    }
+}
+
+/// Directory of per-symbol tick files
+///
+/// Writers are opened on first use and kept open; call [`flush`](Self::flush) before
+/// querying ticks appended through the same store.
+#[derive(Debug)]
+pub struct TickStore {
+    dir: PathBuf,
+    writers: HashMap<String, TickWriter>,
+}
+
+impl TickStore {
+    /// Open the store in `dir`, creating the directory if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            writers: HashMap::new(),
+        })
+    }
+
+    fn path(&self, symbol: &str) -> Result<PathBuf> {
+        Ok(self.dir.join(file_name(symbol)?))
+    }
+
+    pub fn append(&mut self, symbol: &str, trade: &Trade) -> Result<()> {
+        if !self.writers.contains_key(symbol) {
+            let writer = TickWriter::open(self.path(symbol)?)?;
+            self.writers.insert(symbol.to_string(), writer);
+        }
+        self.writers
+            .get_mut(symbol)
+            .expect("inserted above")
+            .append(trade)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        for writer in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Map the symbol's file for queries; `None` if nothing was ever stored for it
+    pub fn reader(&self, symbol: &str) -> Result<Option<TickFile>> {
+        let path = self.path(symbol)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        TickFile::open(path).map(Some)
+    }
+
+    /// Ticks of `symbol` with `from <= timestamp < to`
+    pub fn query(&self, symbol: &str, from: Timestamp, to: Timestamp) -> Result<Vec<Trade>> {
+        Ok(self
+            .reader(symbol)?
+            .map(|file| file.range(from, to).collect())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn trade(price: f64, timestamp: i64) -> Trade {
+        let side = if timestamp % 2 == 0 {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        Trade::new(price, 0.5, side, at(timestamp))
+    }
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_range_queries() {
+        let dir = temp_dir("tick-range");
+        let mut store = TickStore::open(&dir).unwrap();
+        for t in [10, 20, 20, 30, 40, 50] {
+            store
+                .append("BTC/USD", &trade(100.0 + t as f64, t))
+                .unwrap();
+        }
+        store.append("ETH/USD", &trade(2_000.0, 15)).unwrap();
+        store.flush().unwrap();
+
+        let file = store.reader("BTC/USD").unwrap().unwrap();
+        assert_eq!(file.len(), 6);
+        assert_eq!(file.time_span(), Some((at(10), at(50))));
+        let ticks: Vec<i64> = file
+            .range(at(20), at(40))
+            .map(|t| t.timestamp.as_millis())
+            .collect();
+        assert_eq!(ticks, [20, 20, 30]);
+        assert_eq!(file.count(at(0), at(1_000)), 6);
+        assert_eq!(file.count(at(41), at(45)), 0);
+        assert_eq!(file.count(at(50), at(10)), 0);
+        assert_eq!(file.get(3), Some(trade(130.0, 30)));
+
+        assert_eq!(
+            store.query("ETH/USD", at(0), at(100)).unwrap(),
+            [trade(2_000.0, 15)]
+        );
+        assert!(store.query("SOL/USD", at(0), at(100)).unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_reopen_overwrites_torn_record() {
+        let dir = temp_dir("tick-reopen");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("BTCUSD.ticks");
+        {
+            let mut writer = TickWriter::open(&path).unwrap();
+            writer.append(&trade(100.0, 1)).unwrap();
+            writer.append(&trade(101.0, 2)).unwrap();
+            writer.flush().unwrap();
+        }
+        // Simulate a crash halfway through a record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0xff; 7]).unwrap();
+        let mapped = TickFile::open(&path).unwrap();
+        assert_eq!(mapped.len(), 2);
+
+        let mut writer = TickWriter::open(&path).unwrap();
+        assert_eq!(writer.len(), 2);
+        assert!(writer.append(&trade(99.0, 1)).is_err());
+        writer.append(&trade(102.0, 3)).unwrap();
+        writer.flush().unwrap();
+
+        let file = TickFile::open(&path).unwrap();
+        let prices: Vec<f64> = file.iter().map(|t| t.price).collect();
+        assert_eq!(prices, [100.0, 101.0, 102.0]);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            (HEADER_LEN + 3 * RECORD_LEN) as u64
+        );
+        assert_eq!(
+            mapped.iter().map(|t| t.price).collect::<Vec<_>>(),
+            [100.0, 101.0]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_foreign_files_and_symbols() {
+        let dir = temp_dir("tick-foreign");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("other.ticks");
+        std::fs::write(&path, b"definitely not ticks").unwrap();
+        assert!(TickFile::open(&path).is_err());
+        assert!(TickWriter::open(&path).is_err());
+
+        let mut store = TickStore::open(&dir).unwrap();
+        assert!(store.append("BTC USD", &trade(1.0, 1)).is_err());
+        // Path separators are escaped, so symbols cannot leave the directory
+        assert_eq!(file_name("../BTC").unwrap(), "..%2FBTC.ticks");
+        assert_eq!(file_name("BTC:PERP").unwrap(), "BTC%3APERP.ticks");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::FootprintBuilder;
+use crate::error::{ensure, MarketDataError, Result};
+use crate::orderbook::LevelUpdate;
+
+/// Decimal places needed to print `step` exactly (capped at 12)
+fn decimals(step: f64) -> u32 {
+    (0..12)
+        .find(|&p| {
+            let scaled = step * 10f64.powi(p as i32);
+            (scaled - scaled.round()).abs() < 1e-9 * scaled.max(1.0)
+        })
+        .unwrap_or(12)
+}
+
+/// `steps` multiples of `step`, stripped of binary noise beyond `precision` decimals
+fn snap(step: f64, steps: f64, precision: u32) -> f64 {
+    let scale = 10f64.powi(precision as i32);
+    (steps * step * scale).round() / scale
+}
+
+/// Static trading rules of one instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    /// Minimum price increment
+    pub tick_size: f64,
+    /// Minimum quantity increment
+    pub lot_size: f64,
+    /// Decimal places of prices, derived from `tick_size` unless overridden
+    pub price_precision: u32,
+    /// Decimal places of quantities, derived from `lot_size` unless overridden
+    pub quantity_precision: u32,
+    /// Quote currency value of one unit of quantity per unit of price (1 for spot)
+    pub contract_multiplier: f64,
+}
+
+impl SymbolInfo {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(symbol: &str, base: &str, quote: &str, tick_size: f64, lot_size: f64) -> Self {
+        Self::try_new(symbol, base, quote, tick_size, lot_size).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(
+        symbol: &str,
+        base: &str,
+        quote: &str,
+        tick_size: f64,
+        lot_size: f64,
+    ) -> Result<Self> {
+        let info = Self {
+            symbol: symbol.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            tick_size,
+            lot_size,
+            price_precision: decimals(tick_size),
+            quantity_precision: decimals(lot_size),
+            contract_multiplier: 1.0,
+        };
+        info.validate()?;
+        Ok(info)
+    }
+
+    pub fn with_contract_multiplier(mut self, multiplier: f64) -> Self {
+        self.contract_multiplier = multiplier;
+        self
+    }
+
+    /// Override the display precision derived from tick and lot size
+    pub fn with_precision(mut self, price: u32, quantity: u32) -> Self {
+        self.price_precision = price;
+        self.quantity_precision = quantity;
+        self
+    }
+
+    /// Check the invariants `try_new` enforces, e.g. after deserializing
+    pub fn validate(&self) -> Result<()> {
+        ensure(!self.symbol.is_empty(), "symbol must not be empty")?;
+        ensure(
+            self.tick_size.is_finite() && self.tick_size > 0.0,
+            "tick size must be positive",
+        )?;
+        ensure(
+            self.lot_size.is_finite() && self.lot_size > 0.0,
+            "lot size must be positive",
+        )?;
+        ensure(
+            self.contract_multiplier.is_finite() && self.contract_multiplier > 0.0,
+            "contract multiplier must be positive",
+        )
+    }
+
+    /// Nearest valid price
+    pub fn round_to_tick(&self, price: f64) -> f64 {
+        snap(
+            self.tick_size,
+            (price / self.tick_size).round(),
+            self.price_precision,
+        )
+    }
+
+    /// Highest valid price at or below `price`, e.g. for passive bids
+    pub fn floor_to_tick(&self, price: f64) -> f64 {
+        snap(
+            self.tick_size,
+            self.ticks_floor(price),
+            self.price_precision,
+        )
+    }
+
+    /// Lowest valid price at or above `price`, e.g. for passive asks
+    pub fn ceil_to_tick(&self, price: f64) -> f64 {
+        snap(self.tick_size, self.ticks_ceil(price), self.price_precision)
+    }
+
+    /// Largest tradable quantity not exceeding `quantity`
+    pub fn round_to_lot(&self, quantity: f64) -> f64 {
+        let lots = (quantity / self.lot_size + 1e-9).floor();
+        snap(self.lot_size, lots, self.quantity_precision)
+    }
+
+    pub fn is_on_tick(&self, price: f64) -> bool {
+        (self.round_to_tick(price) - price).abs() <= self.tick_size * 1e-6
+    }
+
+    /// Nearest whole number of ticks from zero to `price`
+    pub fn price_to_ticks(&self, price: f64) -> i64 {
+        (price / self.tick_size).round() as i64
+    }
+
+    /// Exact price of `ticks` multiples of the tick size
+    pub fn ticks_to_price(&self, ticks: i64) -> f64 {
+        snap(self.tick_size, ticks as f64, self.price_precision)
+    }
+
+    /// Signed number of ticks from `from` to `to`
+    pub fn ticks_between(&self, from: f64, to: f64) -> i64 {
+        ((to - from) / self.tick_size).round() as i64
+    }
+
+    /// Quote currency value of `quantity` at `price`
+    pub fn notional(&self, price: f64, quantity: f64) -> f64 {
+        price * quantity * self.contract_multiplier
+    }
+
+    pub fn format_price(&self, price: f64) -> String {
+        format!("{:.*}", self.price_precision as usize, price)
+    }
+
+    pub fn format_quantity(&self, quantity: f64) -> String {
+        format!("{:.*}", self.quantity_precision as usize, quantity)
+    }
+
+    /// Level update snapped to the instrument grid, so float noise from upstream
+    /// arithmetic cannot create duplicate book levels
+    pub fn normalize(&self, update: LevelUpdate) -> LevelUpdate {
+        LevelUpdate {
+            price: self.round_to_tick(update.price),
+            quantity: self.round_to_lot(update.quantity),
+            ..update
+        }
+    }
+
+    /// Footprint builder binning trades at this instrument's tick size
+    pub fn footprint_builder(&self, interval: i64) -> Result<FootprintBuilder> {
+        FootprintBuilder::try_new(self.tick_size, interval)
+    }
+
+    fn ticks_floor(&self, price: f64) -> f64 {
+        (price / self.tick_size + 1e-9).floor()
+    }
+
+    fn ticks_ceil(&self, price: f64) -> f64 {
+        (price / self.tick_size - 1e-9).ceil()
+    }
+}
+
+/// Metadata of every known instrument, keyed by symbol
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    symbols: HashMap<String, SymbolInfo>,
+}
+
+impl SymbolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a JSON array of [`SymbolInfo`], validating every entry
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let infos: Vec<SymbolInfo> = serde_json::from_slice(bytes)?;
+        let mut registry = Self::new();
+        for info in infos {
+            registry.insert(info)?;
+        }
+        Ok(registry)
+    }
+
+    /// Add or replace an instrument, returning the previous definition
+    pub fn insert(&mut self, info: SymbolInfo) -> Result<Option<SymbolInfo>> {
+        info.validate()?;
+        Ok(self.symbols.insert(info.symbol.clone(), info))
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(symbol)
+    }
+
+    /// Like [`get`](Self::get) but an error for unknown symbols
+    pub fn require(&self, symbol: &str) -> Result<&SymbolInfo> {
+        self.get(symbol)
+            .ok_or_else(|| MarketDataError::invalid_parameter(format!("unknown symbol {symbol}")))
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> Option<SymbolInfo> {
+        self.symbols.remove(symbol)
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.symbols.contains_key(symbol)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SymbolInfo> {
+        self.symbols.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn btc() -> SymbolInfo {
+        SymbolInfo::new("BTCUSD", "BTC", "USD", 0.1, 0.001)
+    }
+
+    #[test]
+    fn test_tick_rounding_is_exact() {
+        let info = btc();
+        assert_eq!(info.price_precision, 1);
+        assert_eq!(info.quantity_precision, 3);
+
+        assert_eq!(info.round_to_tick(0.1 + 0.2), 0.3);
+        assert_eq!(info.round_to_tick(50_000.06), 50_000.1);
+        assert_eq!(info.floor_to_tick(50_000.09), 50_000.0);
+        assert_eq!(info.ceil_to_tick(50_000.01), 50_000.1);
+        assert_eq!(info.ceil_to_tick(50_000.1), 50_000.1);
+        assert!(info.is_on_tick(50_000.3));
+        assert!(!info.is_on_tick(50_000.35));
+        assert_eq!(info.ticks_between(100.0, 101.5), 15);
+        assert_eq!(info.price_to_ticks(0.1 + 0.2), 3);
+        assert_eq!(info.ticks_to_price(3), 0.3);
+    }
+
+    #[test]
+    fn test_lots_and_formatting() {
+        let info = btc().with_contract_multiplier(0.5);
+        assert_eq!(info.round_to_lot(1.23456), 1.234);
+        assert_eq!(info.round_to_lot(0.3), 0.3);
+        assert_eq!(info.format_price(100.0), "100.0");
+        assert_eq!(info.format_quantity(0.5), "0.500");
+        assert_eq!(info.notional(100.0, 2.0), 100.0);
+
+        let update = info.normalize(LevelUpdate::bid(99.94, 1.0009));
+        assert_eq!((update.price, update.quantity), (99.9, 1.0));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(SymbolInfo::try_new("X", "A", "B", 0.0, 1.0).is_err());
+        assert!(SymbolInfo::try_new("X", "A", "B", 1.0, f64::NAN).is_err());
+        assert!(SymbolInfo::try_new("", "A", "B", 1.0, 1.0).is_err());
+
+        let mut registry = SymbolRegistry::new();
+        assert!(registry
+            .insert(btc().with_contract_multiplier(0.0))
+            .is_err());
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_registry_from_json() {
+        let json =
+            serde_json::to_vec(&[btc(), SymbolInfo::new("ES", "ES", "USD", 0.25, 1.0)]).unwrap();
+        let registry = SymbolRegistry::from_json(&json).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.require("ES").unwrap().tick_size, 0.25);
+        assert!(registry.require("ETHUSD").is_err());
+        assert_eq!(registry.get("BTCUSD"), Some(&btc()));
+    }
+}
@@ -0,0 +1,259 @@
+//! Deterministic data generators and fixtures for tests and benchmarks
+//!
+//! Everything here is seeded, so a failing test can be replayed exactly. The proptest
+//! [`strategies`] need the `testing` feature.
+
+use std::time::Duration;
+
+use crate::candles::Candle;
+use crate::orderbook::OrderBook;
+use crate::time::Timestamp;
+use crate::trades::{Side, Trade};
+
+/// Small, fast, seedable generator (SplitMix64); not suitable for cryptography
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[low, high)`
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Standard normal draw (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// Exponential draw with the given mean
+    pub fn exponential(&mut self, mean: f64) -> f64 {
+        -mean * (1.0 - self.next_f64()).ln()
+    }
+
+    pub fn side(&mut self) -> Side {
+        if self.next_u64() & 1 == 0 {
+            Side::Buy
+        } else {
+            Side::Sell
+        }
+    }
+}
+
+/// Geometric Brownian motion path of `steps + 1` prices starting at `start`
+///
+/// `drift` and `volatility` are per step, e.g. `0.0` and `0.001` for a tick-level path.
+pub fn gbm_path(seed: u64, start: f64, drift: f64, volatility: f64, steps: usize) -> Vec<f64> {
+    let mut rng = SeededRng::new(seed);
+    let mut price = start;
+    let mut path = Vec::with_capacity(steps + 1);
+    path.push(price);
+
+    for _ in 0..steps {
+        price *= ((drift - 0.5 * volatility * volatility) + volatility * rng.normal()).exp();
+        path.push(price);
+    }
+    path
+}
+
+/// Mean-reverting (Ornstein-Uhlenbeck) path around `mean`, useful for spread and pairs tests
+pub fn mean_reverting_path(
+    seed: u64,
+    mean: f64,
+    speed: f64,
+    volatility: f64,
+    steps: usize,
+) -> Vec<f64> {
+    let mut rng = SeededRng::new(seed);
+    let mut value = mean;
+    let mut path = Vec::with_capacity(steps + 1);
+    path.push(value);
+
+    for _ in 0..steps {
+        value += speed * (mean - value) + volatility * rng.normal();
+        path.push(value);
+    }
+    path
+}
+
+/// Trades along a GBM path with exponential inter-arrival times and random sides
+pub fn trade_stream(
+    seed: u64,
+    start_price: f64,
+    count: usize,
+    start_time: Timestamp,
+    mean_interval_ms: f64,
+) -> Vec<Trade> {
+    let mut rng = SeededRng::new(seed);
+    let path = gbm_path(seed.wrapping_add(1), start_price, 0.0, 0.0005, count);
+    let mut timestamp = start_time;
+
+    path.into_iter()
+        .skip(1)
+        .map(|price| {
+            timestamp =
+                timestamp + Duration::from_millis(rng.exponential(mean_interval_ms).round() as u64);
+            let quantity = (rng.exponential(1.0) * 100.0).round() / 100.0 + 0.01;
+            Trade::new(price, quantity, rng.side(), timestamp)
+        })
+        .collect()
+}
+
+/// Book with `levels` price levels per side around `mid`, one `tick` apart
+///
+/// Sizes grow away from the touch, as they typically do on real venues.
+pub fn random_book(seed: u64, symbol: &str, mid: f64, levels: usize, tick: f64) -> OrderBook {
+    let mut rng = SeededRng::new(seed);
+    let mut book = OrderBook::new(symbol.to_string());
+    let half_spread = tick * (1 + (rng.next_u64() % 3) as usize) as f64 / 2.0;
+
+    for i in 0..levels {
+        let depth = 1.0 + i as f64 * 0.5;
+        let bid = mid - half_spread - i as f64 * tick;
+        let ask = mid + half_spread + i as f64 * tick;
+        if bid > 0.0 {
+            book.update_bid(bid, rng.range(0.1, 1.0) * depth, Timestamp::EPOCH)
+                .expect("generated bid is valid");
+        }
+        book.update_ask(ask, rng.range(0.1, 1.0) * depth, Timestamp::EPOCH)
+            .expect("generated ask is valid");
+    }
+    book
+}
+
+/// Book with a single bid and ask level, both of `size`
+pub fn top_of_book(symbol: &str, bid: f64, ask: f64, size: f64) -> OrderBook {
+    let mut book = OrderBook::new(symbol.to_string());
+    book.update_bid(bid, size, Timestamp::EPOCH)
+        .expect("bid is valid");
+    book.update_ask(ask, size, Timestamp::EPOCH)
+        .expect("ask is valid");
+    book
+}
+
+/// Unit-volume bar at the epoch opening at `close`, for indicators that only read
+/// high, low and close
+pub fn candle(high: f64, low: f64, close: f64) -> Candle {
+    Candle::new(close, high, low, close, 1.0, Timestamp::EPOCH)
+}
+
+/// Proptest strategies producing realistic market data
+#[cfg(any(test, feature = "testing"))]
+pub mod strategies {
+    use proptest::prelude::*;
+
+    use crate::orderbook::OrderBook;
+    use crate::time::Timestamp;
+    use crate::trades::{Side, Trade};
+
+    /// Positive, finite prices across several orders of magnitude
+    pub fn price() -> impl Strategy<Value = f64> {
+        (-4.0..6.0_f64).prop_map(|exponent| 10f64.powf(exponent))
+    }
+
+    pub fn quantity() -> impl Strategy<Value = f64> {
+        (0.0001..1_000.0_f64).prop_map(|q| (q * 1e4).round() / 1e4)
+    }
+
+    pub fn side() -> impl Strategy<Value = Side> {
+        prop_oneof![Just(Side::Buy), Just(Side::Sell)]
+    }
+
+    /// Seeded GBM path of `len` prices
+    pub fn price_path(len: usize) -> impl Strategy<Value = Vec<f64>> {
+        (any::<u64>(), price(), 0.0001..0.02_f64).prop_map(move |(seed, start, vol)| {
+            super::gbm_path(seed, start, 0.0, vol, len.saturating_sub(1))
+        })
+    }
+
+    /// Trades with non-decreasing timestamps
+    pub fn trades(max_len: usize) -> impl Strategy<Value = Vec<Trade>> {
+        prop::collection::vec((price(), quantity(), side(), 0..1_000i64), 0..max_len).prop_map(
+            |raw| {
+                let mut timestamp = 0;
+                raw.into_iter()
+                    .map(|(price, quantity, side, gap)| {
+                        timestamp += gap;
+                        Trade::new(price, quantity, side, Timestamp::from_millis(timestamp))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Uncrossed books with up to `max_levels` levels per side
+    pub fn book(max_levels: usize) -> impl Strategy<Value = OrderBook> {
+        (any::<u64>(), 1.0..100_000.0_f64, 1..=max_levels.max(1)).prop_map(|(seed, mid, levels)| {
+            super::random_book(seed, "TEST", mid, levels, mid * 1e-4)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_generators_are_deterministic() {
+        assert_eq!(
+            gbm_path(7, 100.0, 0.0, 0.01, 50),
+            gbm_path(7, 100.0, 0.0, 0.01, 50)
+        );
+        assert_ne!(
+            gbm_path(7, 100.0, 0.0, 0.01, 50),
+            gbm_path(8, 100.0, 0.0, 0.01, 50)
+        );
+
+        let trades = trade_stream(3, 100.0, 100, Timestamp::EPOCH, 50.0);
+        assert_eq!(trades.len(), 100);
+        assert!(trades.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        assert!(trades.iter().all(|t| t.price > 0.0 && t.quantity > 0.0));
+    }
+
+    #[test]
+    fn test_random_book_is_uncrossed() {
+        let book = random_book(1, "BTCUSD", 50_000.0, 10, 0.5);
+        assert_eq!(book.bids.len(), 10);
+        assert_eq!(book.asks.len(), 10);
+        assert!(book.spread().unwrap() > 0.0);
+        assert!((book.mid_price().unwrap() - 50_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mean_reverting_path_stays_near_mean() {
+        let path = mean_reverting_path(5, 0.0, 0.2, 0.1, 5_000);
+        let mean = path.iter().sum::<f64>() / path.len() as f64;
+        assert!(mean.abs() < 0.05);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_strategies_produce_valid_data(book in strategies::book(20), trades in strategies::trades(50)) {
+            let (bid, _) = book.best_bid().unwrap();
+            let (ask, _) = book.best_ask().unwrap();
+            prop_assert!(bid < ask);
+            prop_assert!(trades.iter().all(|t| t.price.is_finite() && t.price > 0.0));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_MICRO: i64 = 1_000;
+
+/// Wall-clock time as nanoseconds since the Unix epoch
+///
+/// Use for exchange and receive timestamps that must be compared across processes
+/// and machines. Wall clocks can step backwards (NTP adjustments); use
+/// [`Monotonic`] to measure latencies inside one process.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub const EPOCH: Timestamp = Timestamp(0);
+    pub const MIN: Timestamp = Timestamp(i64::MIN);
+    pub const MAX: Timestamp = Timestamp(i64::MAX);
+
+    pub fn now() -> Self {
+        // `SystemTime` is unavailable in the browser
+        #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+        return Timestamp::from_millis(js_sys::Date::now() as i64);
+
+        #[allow(unreachable_code)]
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Timestamp(elapsed.as_nanos() as i64)
+    }
+
+    pub const fn from_nanos(nanos: i64) -> Self {
+        Timestamp(nanos)
+    }
+
+    /// Saturates outside the ~1677..2262 range representable in i64 nanoseconds
+    pub const fn from_micros(micros: i64) -> Self {
+        Timestamp(micros.saturating_mul(NANOS_PER_MICRO))
+    }
+
+    /// Saturates outside the ~1677..2262 range representable in i64 nanoseconds
+    pub const fn from_millis(millis: i64) -> Self {
+        Timestamp(millis.saturating_mul(NANOS_PER_MILLI))
+    }
+
+    pub const fn as_nanos(&self) -> i64 {
+        self.0
+    }
+
+    /// Microseconds since the epoch, rounded towards negative infinity
+    pub const fn as_micros(&self) -> i64 {
+        self.0.div_euclid(NANOS_PER_MICRO)
+    }
+
+    /// Milliseconds since the epoch, rounded towards negative infinity
+    pub const fn as_millis(&self) -> i64 {
+        self.0.div_euclid(NANOS_PER_MILLI)
+    }
+
+    /// Time elapsed since `earlier`, or zero if `earlier` is later
+    pub fn saturating_duration_since(&self, earlier: Timestamp) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0).max(0) as u64)
+    }
+
+    pub fn to_datetime(&self) -> DateTime<Utc> {
+        Utc.timestamp_nanos(self.0)
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    /// Saturates outside the ~1677..2262 range representable in i64 nanoseconds
+    fn from(datetime: DateTime<Utc>) -> Self {
+        match datetime.timestamp_nanos_opt() {
+            Some(nanos) => Timestamp(nanos),
+            None if datetime.timestamp() < 0 => Timestamp(i64::MIN),
+            None => Timestamp(i64::MAX),
+        }
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.to_datetime()
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => Timestamp(after.as_nanos() as i64),
+            Err(before) => Timestamp(-(before.duration().as_nanos() as i64)),
+        }
+    }
+}
+
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, rhs: Duration) -> Timestamp {
+        Timestamp(self.0.saturating_add(rhs.as_nanos() as i64))
+    }
+}
+
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, rhs: Duration) -> Timestamp {
+        Timestamp(self.0.saturating_sub(rhs.as_nanos() as i64))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.to_datetime()
+                .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+        )
+    }
+}
+
+/// Monotonic time as nanoseconds since a process-wide anchor
+///
+/// Never goes backwards, but is meaningless outside the current process. Use for
+/// latency measurement and internal scheduling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Monotonic(u64);
+
+fn anchor() -> Instant {
+    static ANCHOR: OnceLock<Instant> = OnceLock::new();
+    *ANCHOR.get_or_init(Instant::now)
+}
+
+impl Monotonic {
+    pub fn now() -> Self {
+        Monotonic(anchor().elapsed().as_nanos() as u64)
+    }
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Monotonic(nanos)
+    }
+
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    pub fn duration_since(&self, earlier: Monotonic) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Monotonic::now().duration_since(*self)
+    }
+}
+
+impl Add<Duration> for Monotonic {
+    type Output = Monotonic;
+
+    fn add(self, rhs: Duration) -> Monotonic {
+        Monotonic(self.0.saturating_add(rhs.as_nanos() as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        let ts = Timestamp::from_millis(1_700_000_000_123);
+        assert_eq!(ts.as_nanos(), 1_700_000_000_123_000_000);
+        assert_eq!(ts.as_micros(), 1_700_000_000_123_000);
+        assert_eq!(ts.as_millis(), 1_700_000_000_123);
+
+        // Floor division for pre-epoch values
+        assert_eq!(Timestamp::from_nanos(-1).as_millis(), -1);
+
+        // Out-of-range inputs saturate instead of overflowing
+        assert_eq!(Timestamp::from_millis(i64::MAX), Timestamp::MAX);
+        assert_eq!(Timestamp::from_micros(i64::MIN), Timestamp::MIN);
+    }
+
+    #[test]
+    fn test_chrono_round_trip() {
+        let datetime = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+        let ts = Timestamp::from(datetime) + Duration::from_nanos(42);
+
+        assert_eq!(ts.to_datetime().timestamp_subsec_nanos(), 42);
+        assert_eq!(ts.to_string(), "2024-03-01T12:30:00.000000042Z");
+        assert_eq!(
+            DateTime::<Utc>::from(ts - Duration::from_nanos(42)),
+            datetime
+        );
+    }
+
+    #[test]
+    fn test_ordering_and_durations() {
+        let a = Timestamp::from_millis(1_000);
+        let b = a + Duration::from_millis(250);
+        assert!(b > a);
+        assert_eq!(b.saturating_duration_since(a), Duration::from_millis(250));
+        assert_eq!(a.saturating_duration_since(b), Duration::ZERO);
+        assert_eq!(serde_json::to_string(&a).unwrap(), "1000000000");
+    }
+
+    #[test]
+    fn test_monotonic_never_goes_backwards() {
+        let start = Monotonic::now();
+        let later = Monotonic::now();
+        assert!(later >= start);
+        assert_eq!(
+            start.duration_since(later + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+        assert!(
+            Timestamp::now() > Timestamp::from(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::time::Timestamp;
+
+pub mod tape;
+
+pub use tape::{Retention, TapeStats, TradeTape};
+
+/// Aggressor side of a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// +1 for buys, -1 for sells
+    pub fn sign(&self) -> f64 {
+        match self {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        }
+    }
+}
+
+/// Executed trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub price: f64,
+    pub quantity: f64,
+    pub side: Side,
+    pub timestamp: Timestamp,
+}
+
+impl Trade {
+    pub fn new(price: f64, quantity: f64, side: Side, timestamp: Timestamp) -> Self {
+        Self {
+            price,
+            quantity,
+            side,
+            timestamp,
+        }
+    }
+
+    /// Traded value (price * quantity)
+    pub fn notional(&self) -> f64 {
+        self.price * self.quantity
+    }
+
+    /// Quantity signed by aggressor side
+    pub fn signed_quantity(&self) -> f64 {
+        self.side.sign() * self.quantity
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use super::{Side, Trade};
+use crate::error::{ensure, Result};
+use crate::numeric::KahanSum;
+
+/// How long a [`TradeTape`] keeps trades
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Retention {
+    /// The most recent `n` trades of each symbol
+    Count(usize),
+    /// Trades within this many milliseconds of the symbol's latest trade
+    Window(i64),
+}
+
+/// Aggregates over the trades currently retained for one symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TapeStats {
+    pub trades: usize,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub notional: f64,
+    /// Volume-weighted average price, `None` without volume
+    pub vwap: Option<f64>,
+}
+
+impl TapeStats {
+    pub fn volume(&self) -> f64 {
+        self.buy_volume + self.sell_volume
+    }
+
+    /// Buy minus sell volume over total volume, in [-1, 1]
+    pub fn imbalance(&self) -> f64 {
+        let volume = self.volume();
+        if volume > 0.0 {
+            (self.buy_volume - self.sell_volume) / volume
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct SymbolTape {
+    trades: VecDeque<Trade>,
+    buy_volume: KahanSum,
+    sell_volume: KahanSum,
+    notional: KahanSum,
+}
+
+impl SymbolTape {
+    fn push(&mut self, trade: Trade) {
+        self.volume(trade.side).add(trade.quantity);
+        self.notional.add(trade.notional());
+        self.trades.push_back(trade);
+    }
+
+    fn pop(&mut self) {
+        if let Some(trade) = self.trades.pop_front() {
+            self.volume(trade.side).sub(trade.quantity);
+            self.notional.sub(trade.notional());
+        }
+    }
+
+    fn volume(&mut self, side: Side) -> &mut KahanSum {
+        match side {
+            Side::Buy => &mut self.buy_volume,
+            Side::Sell => &mut self.sell_volume,
+        }
+    }
+
+    fn evict(&mut self, retention: Retention) {
+        match retention {
+            Retention::Count(n) => {
+                while self.trades.len() > n {
+                    self.pop();
+                }
+            }
+            Retention::Window(window) => {
+                let Some(latest) = self.trades.back().map(|t| t.timestamp.as_millis()) else {
+                    return;
+                };
+                while self
+                    .trades
+                    .front()
+                    .is_some_and(|t| t.timestamp.as_millis() <= latest - window)
+                {
+                    self.pop();
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> TapeStats {
+        let buy_volume = self.buy_volume.value();
+        let sell_volume = self.sell_volume.value();
+        let volume = buy_volume + sell_volume;
+        let notional = self.notional.value();
+        TapeStats {
+            trades: self.trades.len(),
+            buy_volume,
+            sell_volume,
+            notional,
+            vwap: (volume > 0.0).then(|| notional / volume),
+        }
+    }
+}
+
+/// Recent trades of many symbols with rolling statistics
+///
+/// Statistics are maintained incrementally with compensated sums, so queries are
+/// O(1) except [`largest_trade`](Self::largest_trade), which scans the retained
+/// trades. Time retention is driven by trade timestamps, not the wall clock.
+#[derive(Debug, Clone)]
+pub struct TradeTape {
+    retention: Retention,
+    symbols: HashMap<String, SymbolTape>,
+}
+
+impl TradeTape {
+    /// Panics on invalid parameters; see [`try_new`](Self::try_new)
+    pub fn new(retention: Retention) -> Self {
+        Self::try_new(retention).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new(retention: Retention) -> Result<Self> {
+        match retention {
+            Retention::Count(n) => ensure(n > 0, "retained trade count must be positive")?,
+            Retention::Window(ms) => ensure(ms > 0, "retention window must be positive")?,
+        }
+        Ok(Self {
+            retention,
+            symbols: HashMap::new(),
+        })
+    }
+
+    pub fn retention(&self) -> Retention {
+        self.retention
+    }
+
+    pub fn record(&mut self, symbol: &str, trade: Trade) {
+        if !self.symbols.contains_key(symbol) {
+            self.symbols
+                .insert(symbol.to_string(), SymbolTape::default());
+        }
+        let tape = self.symbols.get_mut(symbol).expect("inserted above");
+        tape.push(trade);
+        tape.evict(self.retention);
+    }
+
+    /// Retained trades of `symbol`, oldest first
+    pub fn trades(&self, symbol: &str) -> impl Iterator<Item = &Trade> {
+        self.symbols
+            .get(symbol)
+            .into_iter()
+            .flat_map(|t| t.trades.iter())
+    }
+
+    pub fn stats(&self, symbol: &str) -> Option<TapeStats> {
+        self.symbols.get(symbol).map(SymbolTape::stats)
+    }
+
+    pub fn trade_count(&self, symbol: &str) -> usize {
+        self.symbols.get(symbol).map_or(0, |t| t.trades.len())
+    }
+
+    pub fn vwap(&self, symbol: &str) -> Option<f64> {
+        self.stats(symbol)?.vwap
+    }
+
+    /// Buy and sell aggressor volume
+    pub fn volume_split(&self, symbol: &str) -> Option<(f64, f64)> {
+        self.stats(symbol).map(|s| (s.buy_volume, s.sell_volume))
+    }
+
+    /// Retained trade with the largest quantity; the earliest one on ties
+    pub fn largest_trade(&self, symbol: &str) -> Option<Trade> {
+        self.trades(symbol)
+            .copied()
+            .reduce(|best, t| if t.quantity > best.quantity { t } else { best })
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.symbols.keys().map(String::as_str)
+    }
+
+    pub fn clear(&mut self, symbol: &str) {
+        self.symbols.remove(symbol);
+    }
+
+    pub fn reset(&mut self) {
+        self.symbols.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timestamp;
+
+    fn at(millis: i64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_count_retention() {
+        let mut tape = TradeTape::new(Retention::Count(3));
+        for (i, qty) in [1.0, 5.0, 2.0, 3.0].into_iter().enumerate() {
+            let side = if i % 2 == 0 { Side::Buy } else { Side::Sell };
+            tape.record("BTC", Trade::new(100.0 + i as f64, qty, side, at(i as i64)));
+        }
+
+        assert_eq!(tape.trade_count("BTC"), 3);
+        assert_eq!(tape.trades("BTC").next().unwrap().quantity, 5.0);
+        assert_eq!(tape.volume_split("BTC"), Some((2.0, 8.0)));
+        assert_eq!(tape.largest_trade("BTC").unwrap().price, 101.0);
+
+        let vwap = (101.0 * 5.0 + 102.0 * 2.0 + 103.0 * 3.0) / 10.0;
+        assert!((tape.vwap("BTC").unwrap() - vwap).abs() < 1e-12);
+        assert!((tape.stats("BTC").unwrap().imbalance() + 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_window_retention() {
+        let mut tape = TradeTape::new(Retention::Window(1_000));
+        tape.record("ETH", Trade::new(10.0, 1.0, Side::Buy, at(0)));
+        tape.record("ETH", Trade::new(11.0, 1.0, Side::Buy, at(500)));
+        tape.record("ETH", Trade::new(12.0, 1.0, Side::Sell, at(1_200)));
+
+        // The trade at 0 fell out of the (200, 1200] window
+        assert_eq!(tape.trade_count("ETH"), 2);
+        assert_eq!(tape.vwap("ETH"), Some(11.5));
+
+        tape.record("ETH", Trade::new(12.0, 1.0, Side::Sell, at(5_000)));
+        assert_eq!(tape.trade_count("ETH"), 1);
+        assert_eq!(tape.volume_split("ETH"), Some((0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_symbols_are_independent() {
+        let mut tape = TradeTape::new(Retention::Count(10));
+        tape.record("A", Trade::new(1.0, 1.0, Side::Buy, at(0)));
+        tape.record("B", Trade::new(2.0, 1.0, Side::Buy, at(0)));
+
+        assert_eq!(tape.symbols().count(), 2);
+        tape.clear("A");
+        assert_eq!(tape.stats("A"), None);
+        assert_eq!(tape.trade_count("A"), 0);
+        assert_eq!(tape.vwap("B"), Some(2.0));
+        assert!(TradeTape::try_new(Retention::Window(0)).is_err());
+        assert!(TradeTape::try_new(Retention::Count(0)).is_err());
+    }
+}
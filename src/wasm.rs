@@ -0,0 +1,386 @@
+//! JavaScript bindings for browser dashboards, enabled with the `wasm` feature
+//!
+//! Build with `wasm-pack build --target web -- --no-default-features --features wasm`.
+//! Numbers cross the boundary as plain `number`s and `Float64Array`s: book levels
+//! and multi-output values are flattened (`[price, quantity, price, quantity, ...]`)
+//! and warm-up outputs of whole-series computations are `NaN`. Timestamps are
+//! milliseconds since the epoch.
+
+use wasm_bindgen::prelude::*;
+
+use crate::aggregation::CandleBuilder;
+use crate::candles::Candle;
+use crate::error::{ensure, Result};
+use crate::indicators::{self, Indicator};
+use crate::orderbook::{self, PriceLevel};
+use crate::time::Timestamp;
+
+/// `[p0, q0, p1, q1, ...]` as levels
+fn levels(flat: &[f64]) -> Result<Vec<PriceLevel>> {
+    ensure(flat.len().is_multiple_of(2), "levels must be price/quantity pairs")?;
+    Ok(flat
+        .chunks_exact(2)
+        .map(|pair| PriceLevel {
+            price: pair[0],
+            quantity: pair[1],
+        })
+        .collect())
+}
+
+fn flatten_levels(levels: &[PriceLevel]) -> Vec<f64> {
+    levels
+        .iter()
+        .flat_map(|level| [level.price, level.quantity])
+        .collect()
+}
+
+fn pair((a, b): (f64, f64)) -> Vec<f64> {
+    vec![a, b]
+}
+
+fn triple((a, b, c): (f64, f64, f64)) -> Vec<f64> {
+    vec![a, b, c]
+}
+
+/// Warm-up outputs as `NaN`
+fn nan_series(series: Vec<Option<f64>>) -> Vec<f64> {
+    series
+        .into_iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect()
+}
+
+/// Three-output series flattened to triples, warm-up outputs as three `NaN`s
+fn nan_triples(series: Vec<Option<(f64, f64, f64)>>) -> Vec<f64> {
+    series
+        .into_iter()
+        .flat_map(|value| triple(value.unwrap_or((f64::NAN, f64::NAN, f64::NAN))))
+        .collect()
+}
+
+/// `[timestamp, open, high, low, close, volume]` per candle
+fn flatten_candles(candles: impl IntoIterator<Item = Candle>) -> Vec<f64> {
+    candles
+        .into_iter()
+        .flat_map(|c| {
+            let timestamp = c.timestamp.as_millis() as f64;
+            [timestamp, c.open, c.high, c.low, c.close, c.volume]
+        })
+        .collect()
+}
+
+/// Price-level order book of one symbol
+#[wasm_bindgen(js_name = OrderBook)]
+pub struct WasmOrderBook {
+    book: orderbook::OrderBook,
+}
+
+#[wasm_bindgen(js_class = OrderBook)]
+impl WasmOrderBook {
+    #[wasm_bindgen(constructor)]
+    pub fn new(symbol: String) -> std::result::Result<WasmOrderBook, JsError> {
+        Ok(Self {
+            book: orderbook::OrderBook::try_new(symbol)?,
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn symbol(&self) -> String {
+        self.book.symbol.clone()
+    }
+
+    /// Set a bid level; quantity 0 removes it
+    #[wasm_bindgen(js_name = updateBid)]
+    pub fn update_bid(
+        &mut self,
+        price: f64,
+        quantity: f64,
+        timestamp: f64,
+    ) -> std::result::Result<(), JsError> {
+        Ok(self
+            .book
+            .update_bid(price, quantity, Timestamp::from_millis(timestamp as i64))?)
+    }
+
+    /// Set an ask level; quantity 0 removes it
+    #[wasm_bindgen(js_name = updateAsk)]
+    pub fn update_ask(
+        &mut self,
+        price: f64,
+        quantity: f64,
+        timestamp: f64,
+    ) -> std::result::Result<(), JsError> {
+        Ok(self
+            .book
+            .update_ask(price, quantity, Timestamp::from_millis(timestamp as i64))?)
+    }
+
+    /// Replace the whole book with flattened bid and ask levels, best first
+    #[wasm_bindgen(js_name = applySnapshot)]
+    pub fn apply_snapshot(
+        &mut self,
+        bids: &[f64],
+        asks: &[f64],
+        sequence: f64,
+        timestamp: f64,
+    ) -> std::result::Result<(), JsError> {
+        ensure(
+            sequence >= 0.0 && sequence.fract() == 0.0,
+            "sequence must be a non-negative integer",
+        )?;
+        Ok(self.book.apply_snapshot(
+            &levels(bids)?,
+            &levels(asks)?,
+            sequence as u64,
+            Timestamp::from_millis(timestamp as i64),
+        )?)
+    }
+
+    /// Apply flattened bid and ask changes atomically; quantity 0 removes a level
+    #[wasm_bindgen(js_name = applyUpdates)]
+    pub fn apply_updates(
+        &mut self,
+        bids: &[f64],
+        asks: &[f64],
+        timestamp: f64,
+    ) -> std::result::Result<(), JsError> {
+        let updates: Vec<orderbook::LevelUpdate> = levels(bids)?
+            .into_iter()
+            .map(|l| orderbook::LevelUpdate::bid(l.price, l.quantity))
+            .chain(
+                levels(asks)?
+                    .into_iter()
+                    .map(|l| orderbook::LevelUpdate::ask(l.price, l.quantity)),
+            )
+            .collect();
+        self.book
+            .apply_updates(&updates, Timestamp::from_millis(timestamp as i64))?;
+        Ok(())
+    }
+
+    /// `[price, quantity]` of the best bid
+    #[wasm_bindgen(js_name = bestBid)]
+    pub fn best_bid(&self) -> Option<Vec<f64>> {
+        self.book.best_bid().map(pair)
+    }
+
+    /// `[price, quantity]` of the best ask
+    #[wasm_bindgen(js_name = bestAsk)]
+    pub fn best_ask(&self) -> Option<Vec<f64>> {
+        self.book.best_ask().map(pair)
+    }
+
+    #[wasm_bindgen(js_name = midPrice)]
+    pub fn mid_price(&self) -> Option<f64> {
+        self.book.mid_price()
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        self.book.spread()
+    }
+
+    #[wasm_bindgen(js_name = volumeImbalance)]
+    pub fn volume_imbalance(&self) -> f64 {
+        self.book.volume_imbalance()
+    }
+
+    /// Best `n` bids flattened, highest first
+    #[wasm_bindgen(js_name = topBids)]
+    pub fn top_bids(&self, n: usize) -> Vec<f64> {
+        flatten_levels(&self.book.top_bids(n))
+    }
+
+    /// Best `n` asks flattened, lowest first
+    #[wasm_bindgen(js_name = topAsks)]
+    pub fn top_asks(&self, n: usize) -> Vec<f64> {
+        flatten_levels(&self.book.top_asks(n))
+    }
+}
+
+/// Wrap single-output indicators fed with numbers
+macro_rules! wasm_indicator {
+    ($wrapper:ident, $js:ident, $inner:ty) => {
+        #[wasm_bindgen(js_name = $js)]
+        pub struct $wrapper {
+            inner: $inner,
+        }
+
+        #[wasm_bindgen(js_class = $js)]
+        impl $wrapper {
+            #[wasm_bindgen(constructor)]
+            pub fn new(period: usize) -> std::result::Result<$wrapper, JsError> {
+                Ok(Self {
+                    inner: <$inner>::try_new(period)?,
+                })
+            }
+
+            /// Feed one value; `undefined` while warming up
+            pub fn update(&mut self, value: f64) -> Option<f64> {
+                self.inner.update(value)
+            }
+
+            /// Feed a whole series, one output per input
+            pub fn compute(&mut self, values: &[f64]) -> Vec<f64> {
+                nan_series(self.inner.compute_series(values))
+            }
+
+            pub fn current(&self) -> Option<f64> {
+                Indicator::current(&self.inner)
+            }
+
+            pub fn reset(&mut self) {
+                Indicator::reset(&mut self.inner);
+            }
+        }
+    };
+}
+
+wasm_indicator!(WasmSma, SMA, indicators::SMA);
+wasm_indicator!(WasmEma, EMA, indicators::EMA);
+wasm_indicator!(WasmWma, WMA, indicators::WMA);
+wasm_indicator!(WasmRsi, RSI, indicators::RSI);
+
+/// Bollinger Bands producing `[upper, middle, lower]`
+#[wasm_bindgen(js_name = BollingerBands)]
+pub struct WasmBollingerBands {
+    inner: indicators::BollingerBands,
+}
+
+#[wasm_bindgen(js_class = BollingerBands)]
+impl WasmBollingerBands {
+    #[wasm_bindgen(constructor)]
+    pub fn new(period: usize, std_dev: f64) -> std::result::Result<WasmBollingerBands, JsError> {
+        Ok(Self {
+            inner: indicators::BollingerBands::try_new(period, std_dev)?,
+        })
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<Vec<f64>> {
+        self.inner.update(value).map(triple)
+    }
+
+    /// Feed a whole series, three outputs per input
+    pub fn compute(&mut self, values: &[f64]) -> Vec<f64> {
+        nan_triples(self.inner.compute_series(values))
+    }
+
+    pub fn reset(&mut self) {
+        Indicator::reset(&mut self.inner);
+    }
+}
+
+/// MACD producing `[macd, signal, histogram]`
+#[wasm_bindgen(js_name = MACD)]
+pub struct WasmMacd {
+    inner: indicators::MACD,
+}
+
+#[wasm_bindgen(js_class = MACD)]
+impl WasmMacd {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        fast_period: usize,
+        slow_period: usize,
+        signal_period: usize,
+    ) -> std::result::Result<WasmMacd, JsError> {
+        Ok(Self {
+            inner: indicators::MACD::try_new(fast_period, slow_period, signal_period)?,
+        })
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<Vec<f64>> {
+        self.inner.update(value).map(triple)
+    }
+
+    /// Feed a whole series, three outputs per input
+    pub fn compute(&mut self, values: &[f64]) -> Vec<f64> {
+        nan_triples(self.inner.compute_series(values))
+    }
+
+    pub fn reset(&mut self) {
+        Indicator::reset(&mut self.inner);
+    }
+}
+
+/// Time-bucketed candles built from trades, as flattened
+/// `[timestamp, open, high, low, close, volume]` sextuples
+#[wasm_bindgen(js_name = CandleAggregator)]
+pub struct WasmCandleAggregator {
+    builder: CandleBuilder,
+}
+
+#[wasm_bindgen(js_class = CandleAggregator)]
+impl WasmCandleAggregator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(interval_ms: f64) -> std::result::Result<WasmCandleAggregator, JsError> {
+        Ok(Self {
+            builder: CandleBuilder::try_new(interval_ms as i64)?,
+        })
+    }
+
+    /// Add a trade, returning the candles it completed
+    pub fn add(&mut self, price: f64, volume: f64, timestamp: f64) -> Vec<f64> {
+        let timestamp = Timestamp::from_millis(timestamp as i64);
+        flatten_candles(self.builder.add(price, volume, timestamp))
+    }
+
+    /// The candle still being built
+    pub fn current(&self) -> Option<Vec<f64>> {
+        self.builder.current().map(|c| flatten_candles([c]))
+    }
+
+    /// Close and return the candle being built
+    pub fn flush(&mut self) -> Option<Vec<f64>> {
+        self.builder.flush().map(|c| flatten_candles([c]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_book_with_flat_levels() {
+        let mut book = WasmOrderBook::new("BTCUSD".to_string()).unwrap();
+        book.apply_snapshot(&[100.0, 1.0, 99.0, 2.0], &[101.0, 1.5], 7.0, 0.0)
+            .unwrap();
+        book.apply_updates(&[100.5, 0.5], &[101.0, 0.0, 102.0, 3.0], 1_000.0)
+            .unwrap();
+
+        assert_eq!(book.best_bid(), Some(vec![100.5, 0.5]));
+        assert_eq!(book.best_ask(), Some(vec![102.0, 3.0]));
+        assert_eq!(book.top_bids(2), vec![100.5, 0.5, 100.0, 1.0]);
+        assert_eq!(book.mid_price(), Some(101.25));
+        assert!(levels(&[100.0]).is_err());
+    }
+
+    #[test]
+    fn test_indicators_mark_warm_up_with_nan() {
+        let mut sma = WasmSma::new(3).unwrap();
+        let out = sma.compute(&[1.0, 2.0, 3.0, 4.0]);
+        assert!(out[0].is_nan() && out[1].is_nan());
+        assert_eq!(&out[2..], &[2.0, 3.0]);
+        assert_eq!(sma.update(5.0), Some(4.0));
+
+        let mut bands = WasmBollingerBands::new(2, 2.0).unwrap();
+        let out = bands.compute(&[1.0, 3.0]);
+        assert_eq!(out.len(), 6);
+        assert!(out[..3].iter().all(|v| v.is_nan()));
+        assert_eq!(&out[3..], &[4.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_candle_aggregator_flattens_candles() {
+        let mut candles = WasmCandleAggregator::new(60_000.0).unwrap();
+        assert!(candles.add(100.0, 1.0, 0.0).is_empty());
+        assert!(candles.add(105.0, 2.0, 30_000.0).is_empty());
+        assert_eq!(
+            candles.add(101.0, 1.0, 60_000.0),
+            vec![0.0, 100.0, 105.0, 100.0, 105.0, 3.0]
+        );
+        assert_eq!(
+            candles.flush(),
+            Some(vec![60_000.0, 101.0, 101.0, 101.0, 101.0, 1.0])
+        );
+    }
+}